@@ -0,0 +1 @@
+ALTER TABLE folders ADD COLUMN deleted_at INTEGER DEFAULT NULL;
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS wishlist_items (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    artist TEXT NOT NULL,
+    title TEXT NOT NULL,
+    release_mbid TEXT DEFAULT NULL,
+    -- Where the item came from, e.g. a chart URL
+    source TEXT DEFAULT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_wishlist_items_user_id ON wishlist_items(user_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_wishlist_items_user_release
+    ON wishlist_items(user_id, release_mbid) WHERE release_mbid IS NOT NULL;
@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS watched_artists (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    artist_mbid TEXT NOT NULL,
+    name TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE,
+    UNIQUE (user_id, artist_mbid)
+);
+
+-- Per-user decisions on calendar entries: 'ignore' hides a release for good,
+-- 'remind' hides it until remind_at
+CREATE TABLE IF NOT EXISTS release_actions (
+    user_id TEXT NOT NULL,
+    release_group_mbid TEXT NOT NULL,
+    action TEXT NOT NULL,
+    remind_at INTEGER DEFAULT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    PRIMARY KEY (user_id, release_group_mbid),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
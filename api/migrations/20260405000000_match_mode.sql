@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN match_mode TEXT DEFAULT NULL;
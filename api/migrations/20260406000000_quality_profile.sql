@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN quality_profile TEXT DEFAULT NULL;
@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN rename_rules TEXT DEFAULT NULL;
@@ -0,0 +1 @@
+ALTER TABLE folders ADD COLUMN import_mode TEXT NOT NULL DEFAULT 'default';
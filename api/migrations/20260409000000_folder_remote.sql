@@ -0,0 +1 @@
+ALTER TABLE folders ADD COLUMN remote TEXT;
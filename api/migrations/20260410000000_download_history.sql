@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS download_history (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    item TEXT NOT NULL,
+    artist TEXT,
+    album TEXT,
+    format TEXT,
+    peer TEXT NOT NULL,
+    size INTEGER NOT NULL DEFAULT 0,
+    outcome TEXT NOT NULL,
+    error TEXT,
+    finished_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_history_user_finished
+ON download_history(user_id, finished_at);
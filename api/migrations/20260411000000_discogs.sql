@@ -0,0 +1,3 @@
+ALTER TABLE user_settings ADD COLUMN discogs_token TEXT DEFAULT NULL;
+ALTER TABLE user_settings ADD COLUMN discogs_username TEXT DEFAULT NULL;
+ALTER TABLE user_settings ADD COLUMN discogs_sync_collection INTEGER NOT NULL DEFAULT 0;
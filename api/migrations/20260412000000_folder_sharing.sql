@@ -0,0 +1,2 @@
+-- Folders are private to their owner unless shared with the other users
+ALTER TABLE folders ADD COLUMN shared INTEGER NOT NULL DEFAULT 0;
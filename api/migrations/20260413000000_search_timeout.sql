@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN search_timeout_secs INTEGER DEFAULT NULL;
@@ -0,0 +1,2 @@
+ALTER TABLE download_history ADD COLUMN imported_path TEXT;
+ALTER TABLE download_history ADD COLUMN duplicate INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN home_dashboard INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS invites (
+    id TEXT PRIMARY KEY NOT NULL,
+    created_by TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    folder_name TEXT DEFAULT NULL,
+    folder_path TEXT DEFAULT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    expires_at INTEGER NOT NULL,
+    used_at INTEGER DEFAULT NULL,
+    used_by TEXT DEFAULT NULL,
+    FOREIGN KEY (created_by) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1 @@
+ALTER TABLE user_settings ADD COLUMN routing_rules TEXT DEFAULT NULL;
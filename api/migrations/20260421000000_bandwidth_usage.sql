@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS bandwidth_usage (
+    user_id TEXT NOT NULL,
+    day TEXT NOT NULL,
+    bytes INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, day)
+);
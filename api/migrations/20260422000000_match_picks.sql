@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS match_picks (
+    id TEXT PRIMARY KEY NOT NULL,
+    peer TEXT NOT NULL,
+    item TEXT NOT NULL,
+    rank INTEGER NOT NULL,
+    score REAL NOT NULL,
+    top_score REAL NOT NULL,
+    automatic INTEGER NOT NULL DEFAULT 0,
+    outcome TEXT,
+    picked_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_match_picks_pending
+ON match_picks(peer, item) WHERE outcome IS NULL;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS download_feedback (
+    history_id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    peer TEXT NOT NULL,
+    item TEXT NOT NULL,
+    size INTEGER NOT NULL DEFAULT 0,
+    feedback TEXT NOT NULL,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_download_feedback_peer
+ON download_feedback(peer);
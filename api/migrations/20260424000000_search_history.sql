@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS search_history (
+    id TEXT PRIMARY KEY NOT NULL,
+    user_id TEXT NOT NULL,
+    query TEXT NOT NULL,
+    description TEXT NOT NULL,
+    strategy TEXT NOT NULL,
+    search_text TEXT,
+    timeout_secs INTEGER NOT NULL,
+    responses INTEGER NOT NULL DEFAULT 0,
+    candidates INTEGER NOT NULL DEFAULT 0,
+    automatic INTEGER NOT NULL DEFAULT 0,
+    started_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_search_history_user
+ON search_history(user_id, started_at);
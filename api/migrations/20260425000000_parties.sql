@@ -0,0 +1,26 @@
+CREATE TABLE IF NOT EXISTS parties (
+    id TEXT PRIMARY KEY NOT NULL,
+    host_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    token_hash TEXT NOT NULL UNIQUE,
+    folder_id TEXT NOT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    expires_at INTEGER NOT NULL,
+    FOREIGN KEY (host_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY (folder_id) REFERENCES folders(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS party_requests (
+    id TEXT PRIMARY KEY NOT NULL,
+    party_id TEXT NOT NULL,
+    guest_name TEXT NOT NULL,
+    artist TEXT NOT NULL,
+    title TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    error TEXT DEFAULT NULL,
+    created_at INTEGER NOT NULL DEFAULT (strftime('%s', 'now')),
+    FOREIGN KEY (party_id) REFERENCES parties(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_party_requests_party
+ON party_requests(party_id, created_at);
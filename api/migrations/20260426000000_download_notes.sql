@@ -0,0 +1,11 @@
+ALTER TABLE download_history ADD COLUMN download_id TEXT;
+
+CREATE TABLE IF NOT EXISTS download_notes (
+    user_id TEXT NOT NULL,
+    download_id TEXT NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    tags TEXT NOT NULL DEFAULT '[]',
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, download_id),
+    FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE
+);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS import_hook_runs (
+    id TEXT PRIMARY KEY NOT NULL,
+    hook_id TEXT NOT NULL,
+    hook_name TEXT NOT NULL,
+    album_path TEXT NOT NULL,
+    success INTEGER NOT NULL DEFAULT 0,
+    status TEXT NOT NULL,
+    output TEXT NOT NULL DEFAULT '',
+    duration_ms INTEGER NOT NULL DEFAULT 0,
+    ran_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_import_hook_runs_ran_at
+ON import_hook_runs(ran_at);
@@ -0,0 +1,341 @@
+//! Activity stream for external dashboards.
+//!
+//! Every user channel is watched by a small task that turns raw progress
+//! updates into coarse events (queued, state change, import result). Those
+//! and a few explicit events (search started) land in a global ring buffer
+//! and are served as server-sent events on `/api/events`.
+
+#[cfg(feature = "server")]
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "server")]
+use std::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "server")]
+use std::sync::{LazyLock, Mutex};
+
+#[cfg(feature = "server")]
+use axum::extract::Query;
+#[cfg(feature = "server")]
+use axum::http::HeaderMap;
+#[cfg(feature = "server")]
+use axum::response::sse::{Event, KeepAlive, Sse};
+#[cfg(feature = "server")]
+use futures::{Stream, StreamExt};
+#[cfg(feature = "server")]
+use shared::activity::{ActivityEvent, ActivityKind};
+#[cfg(feature = "server")]
+use shared::download::{DownloadEvent, DownloadProgress, DownloadState};
+#[cfg(feature = "server")]
+use tokio::sync::broadcast;
+
+#[cfg(feature = "server")]
+use crate::models::bandwidth_usage::BandwidthUsageRow;
+#[cfg(feature = "server")]
+use crate::models::download_history::{outcome_of, DownloadHistoryRow};
+#[cfg(feature = "server")]
+use crate::models::match_pick::MatchPick;
+#[cfg(feature = "server")]
+use crate::models::user::User;
+#[cfg(feature = "server")]
+use crate::server_fns::ApiTokenSession;
+
+/// Number of past events kept for replay to newly connected clients.
+#[cfg(feature = "server")]
+const ACTIVITY_HISTORY_LEN: usize = 500;
+
+/// Upper bound on download ids remembered per user to detect state changes.
+#[cfg(feature = "server")]
+const TRACKED_DOWNLOADS_LIMIT: usize = 5000;
+
+#[cfg(feature = "server")]
+struct ActivityLog {
+    sender: broadcast::Sender<ActivityEvent>,
+    history: Mutex<VecDeque<ActivityEvent>>,
+    next_id: AtomicU64,
+}
+
+/// Last known state of every download, per username.
+#[cfg(feature = "server")]
+static DOWNLOAD_STATES: LazyLock<Mutex<HashMap<String, HashMap<String, DownloadState>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(feature = "server")]
+static ACTIVITY: LazyLock<ActivityLog> = LazyLock::new(|| ActivityLog {
+    sender: broadcast::channel(256).0,
+    history: Mutex::new(VecDeque::with_capacity(ACTIVITY_HISTORY_LEN)),
+    next_id: AtomicU64::new(1),
+});
+
+/// Append an event to the activity log and push it to connected streams.
+#[cfg(feature = "server")]
+pub fn record_activity(username: &str, kind: ActivityKind) {
+    let event = ActivityEvent {
+        id: ACTIVITY.next_id.fetch_add(1, Ordering::Relaxed),
+        timestamp: chrono::Utc::now().timestamp(),
+        username: username.to_string(),
+        kind,
+    };
+
+    {
+        let mut history = ACTIVITY.history.lock().unwrap_or_else(|e| e.into_inner());
+        if history.len() >= ACTIVITY_HISTORY_LEN {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+    }
+    let _ = ACTIVITY.sender.send(event);
+}
+
+/// Follow a user's download channel and record the transitions worth
+/// reporting, downloads that finished in the download history and the
+/// bytes transfers took. Ends when the channel is dropped.
+#[cfg(feature = "server")]
+pub(crate) async fn watch_user_channel(
+    username: String,
+    mut rx: broadcast::Receiver<DownloadEvent>,
+) {
+    loop {
+        match rx.recv().await {
+            Ok(DownloadEvent::Progress(entries)) => {
+                let mut kinds = Vec::new();
+                let mut finished = Vec::new();
+                let mut transferred = 0;
+                {
+                    let mut states = DOWNLOAD_STATES.lock().unwrap_or_else(|e| e.into_inner());
+                    let last_states = states.entry(username.clone()).or_default();
+                    if last_states.len() > TRACKED_DOWNLOADS_LIMIT {
+                        last_states.retain(|_, state| is_active(state));
+                    }
+                    for entry in entries {
+                        // Only transitions seen here count, so entries that
+                        // were forgotten and show up again aren't recorded twice
+                        let previous = last_states.get(&entry.id);
+                        if let Some(outcome) = outcome_of(&entry.state) {
+                            if previous.is_some_and(|p| p != &entry.state) {
+                                finished.push((entry.clone(), outcome));
+                            }
+                        }
+                        if previous.is_some_and(is_transferring) && !is_transferring(&entry.state) {
+                            transferred += transferred_bytes(&entry);
+                        }
+                        kinds.extend(observe_progress(last_states, entry));
+                    }
+                }
+                for kind in kinds {
+                    record_activity(&username, kind);
+                }
+                if !finished.is_empty() {
+                    record_history(&username, finished).await;
+                }
+                if transferred > 0 {
+                    record_bandwidth(&username, transferred).await;
+                }
+            }
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn record_history(username: &str, finished: Vec<(DownloadProgress, &'static str)>) {
+    let user = match User::get_by_username(username).await {
+        Ok(Some(user)) => user,
+        _ => return,
+    };
+    for (entry, outcome) in finished {
+        if let Err(e) = DownloadHistoryRow::record(&user.id, &entry, outcome).await {
+            tracing::warn!(
+                "Failed to record download history for {}: {}",
+                entry.item,
+                e
+            );
+        }
+        if let Err(e) = MatchPick::set_outcome(&entry.source, &entry.item, outcome).await {
+            tracing::warn!("Failed to record the outcome of {}: {}", entry.item, e);
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+async fn record_bandwidth(username: &str, bytes: u64) {
+    let user = match User::get_by_username(username).await {
+        Ok(Some(user)) => user,
+        _ => return,
+    };
+    if let Err(e) = BandwidthUsageRow::add(&user.id, bytes).await {
+        tracing::warn!("Failed to record bandwidth usage of {}: {}", username, e);
+    }
+}
+
+/// Whether the file is still coming from the peer.
+#[cfg(feature = "server")]
+fn is_transferring(state: &DownloadState) -> bool {
+    matches!(state, DownloadState::Queued | DownloadState::InProgress)
+}
+
+/// Bytes a transfer that just ended took: what arrived before it failed or
+/// was cancelled, the whole file otherwise.
+#[cfg(feature = "server")]
+fn transferred_bytes(entry: &DownloadProgress) -> u64 {
+    match entry.state {
+        DownloadState::Failed(_) | DownloadState::Cancelled => entry.transferred,
+        _ => entry.transferred.max(entry.size),
+    }
+}
+
+/// Whether a download in this state still has something to do.
+#[cfg(feature = "server")]
+pub(crate) fn is_active(state: &DownloadState) -> bool {
+    matches!(
+        state,
+        DownloadState::Queued
+            | DownloadState::InProgress
+            | DownloadState::Completed
+            | DownloadState::ImportWaiting
+            | DownloadState::ImportPaused
+            | DownloadState::Importing
+            | DownloadState::Uploading(_)
+    )
+}
+
+/// Current states of a user's known downloads, as seen on their channel.
+#[cfg(feature = "server")]
+pub fn download_states(username: &str) -> Vec<DownloadState> {
+    DOWNLOAD_STATES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(username)
+        .map(|states| states.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Most recent event of a user matching `filter`, if still in the history.
+#[cfg(feature = "server")]
+pub fn latest_activity(
+    username: &str,
+    filter: impl Fn(&ActivityKind) -> bool,
+) -> Option<ActivityEvent> {
+    ACTIVITY
+        .history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .rev()
+        .find(|e| e.username == username && filter(&e.kind))
+        .cloned()
+}
+
+#[cfg(feature = "server")]
+fn observe_progress(
+    last_states: &mut HashMap<String, DownloadState>,
+    entry: DownloadProgress,
+) -> Option<ActivityKind> {
+    let previous = last_states.insert(entry.id.clone(), entry.state.clone());
+    if previous.as_ref() == Some(&entry.state) {
+        return None;
+    }
+
+    let download_id = entry.id;
+    let item = entry.item;
+    let kind = match (previous, entry.state) {
+        (None, DownloadState::Queued) => ActivityKind::DownloadQueued {
+            download_id,
+            item,
+            batch_label: entry.batch_label,
+        },
+        (_, DownloadState::Imported) => ActivityKind::ImportResult {
+            download_id,
+            item,
+            imported: true,
+            error: None,
+        },
+        (_, DownloadState::ImportSkipped) => ActivityKind::ImportResult {
+            download_id,
+            item,
+            imported: false,
+            error: None,
+        },
+        (Some(DownloadState::Importing), DownloadState::Failed(error)) => {
+            ActivityKind::ImportResult {
+                download_id,
+                item,
+                imported: false,
+                error: Some(error),
+            }
+        }
+        (_, state) => ActivityKind::DownloadState {
+            download_id,
+            item,
+            state,
+        },
+    };
+    Some(kind)
+}
+
+#[cfg(feature = "server")]
+#[derive(Debug, serde::Deserialize)]
+pub struct ActivityStreamParams {
+    /// Replay history after this event id. Defaults to `Last-Event-ID`.
+    pub since: Option<u64>,
+}
+
+/// `GET /api/events`: server-sent events of the token owner's activity.
+///
+/// Events missed since `since` (or the `Last-Event-ID` header browsers send
+/// on reconnect) are replayed from the in-memory history first.
+#[cfg(feature = "server")]
+pub async fn activity_stream(
+    ApiTokenSession(user): ApiTokenSession,
+    Query(params): Query<ActivityStreamParams>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, std::convert::Infallible>>> {
+    let since = params.since.or_else(|| {
+        headers
+            .get("last-event-id")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+    });
+
+    // Subscribe before reading the history so nothing falls in between
+    let rx = ACTIVITY.sender.subscribe();
+    let backlog: Vec<ActivityEvent> = ACTIVITY
+        .history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|e| e.username == user.username && since.is_none_or(|s| e.id > s))
+        .cloned()
+        .collect();
+    let last_replayed = backlog.last().map(|e| e.id).or(since).unwrap_or(0);
+
+    let username = user.username;
+    let live = futures::stream::unfold(rx, |mut rx| async move {
+        loop {
+            match rx.recv().await {
+                Ok(event) => return Some((event, rx)),
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    })
+    .filter(move |e| {
+        let keep = e.username == username && e.id > last_replayed;
+        async move { keep }
+    });
+
+    let stream = futures::stream::iter(backlog)
+        .chain(live)
+        .map(|event| Ok(to_sse_event(&event)));
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+#[cfg(feature = "server")]
+fn to_sse_event(event: &ActivityEvent) -> Event {
+    let data = serde_json::to_string(event).unwrap_or_default();
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.kind.name())
+        .data(data)
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use shared::security::UserRole;
 use shared::system::NavidromeStatus;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -7,6 +8,8 @@ pub struct AuthResponse {
     pub user_id: String,
     #[serde(default)]
     pub navidrome_status: NavidromeStatus,
+    #[serde(default)]
+    pub role: UserRole,
 }
 
 #[cfg(feature = "server")]
@@ -16,6 +19,10 @@ use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation}
 
 pub static EXPIRATION_DAYS: i64 = 30;
 
+/// How long after expiring a token can still be exchanged for a new one,
+/// so a session that lapsed while the tab was open can carry on.
+pub static REFRESH_GRACE_DAYS: i64 = 7;
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // user_id
@@ -58,3 +65,49 @@ pub fn verify_token(token: &str) -> Result<Claims, String> {
 
     Ok(token_data.claims)
 }
+
+/// Like `verify_token`, but also accepts a token that expired within the
+/// refresh grace period.
+#[cfg(feature = "server")]
+pub fn verify_token_for_refresh(token: &str) -> Result<Claims, String> {
+    let mut validation = Validation::default();
+    validation.leeway = (REFRESH_GRACE_DAYS * 24 * 60 * 60) as u64;
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(CONFIG.secret_key().as_bytes()),
+        &validation,
+    )
+    .map_err(|e| e.to_string())?;
+
+    Ok(token_data.claims)
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn token_expired_days_ago(days: i64) -> String {
+        let now = chrono::Utc::now();
+        let claims = Claims {
+            sub: "user".to_string(),
+            username: "alice".to_string(),
+            iat: (now - chrono::Duration::days(EXPIRATION_DAYS + days)).timestamp() as usize,
+            exp: (now - chrono::Duration::days(days)).timestamp() as usize,
+        };
+        let key = EncodingKey::from_secret(CONFIG.secret_key().as_bytes());
+        encode(&Header::default(), &claims, &key).unwrap()
+    }
+
+    #[test]
+    fn recently_expired_tokens_can_be_refreshed() {
+        let token = token_expired_days_ago(REFRESH_GRACE_DAYS - 1);
+        assert!(verify_token(&token).is_err());
+        assert_eq!(verify_token_for_refresh(&token).unwrap().username, "alice");
+    }
+
+    #[test]
+    fn tokens_past_the_grace_period_cannot() {
+        let token = token_expired_days_ago(REFRESH_GRACE_DAYS + 1);
+        assert!(verify_token_for_refresh(&token).is_err());
+    }
+}
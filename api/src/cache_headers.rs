@@ -0,0 +1,113 @@
+//! Cache-Control for every response, and ETags for reads from the API.
+//!
+//! Bundled assets carry a hash of their content in their name, so browsers
+//! can keep them for good. Pages are rendered for the user and always
+//! checked. API reads are tagged, so a browser asking again for the same
+//! search results or library page gets an empty 304 instead of the whole
+//! payload.
+
+#[cfg(feature = "server")]
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+#[cfg(feature = "server")]
+use axum::{
+    body::{Body, HttpBody},
+    extract::Request,
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+#[cfg(feature = "server")]
+use tracing::warn;
+
+/// Largest API response buffered to tag it. Bigger ones are sent as they
+/// come, untagged.
+#[cfg(feature = "server")]
+const MAX_TAGGED_BODY: u64 = 8 * 1024 * 1024;
+
+/// Whether `path` is a bundled asset whose name carries its content hash,
+/// like `/assets/tailwind-dxh1a2b3c.css`.
+#[cfg(feature = "server")]
+fn is_hashed_asset(path: &str) -> bool {
+    path.strip_prefix("/assets/")
+        .and_then(|rest| rest.rsplit('/').next())
+        .is_some_and(|name| name.contains("-dxh"))
+}
+
+#[cfg(feature = "server")]
+fn is_json(response: &Response) -> bool {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"))
+}
+
+/// Axum middleware setting Cache-Control where the handler didn't, and
+/// answering repeated API reads with 304.
+#[cfg(feature = "server")]
+pub async fn cache_headers(request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    let read = request.method() == Method::GET;
+    let if_none_match = request.headers().get(header::IF_NONE_MATCH).cloned();
+    let mut response = next.run(request).await;
+    if response.headers().contains_key(header::CACHE_CONTROL) {
+        return response;
+    }
+
+    let api = path.starts_with("/api/");
+    let tag = api && read && response.status() == StatusCode::OK && is_json(&response);
+    let cache_control = if is_hashed_asset(&path) {
+        "public, max-age=31536000, immutable"
+    } else if path.starts_with("/assets/") {
+        "public, max-age=3600"
+    } else if !api {
+        "no-cache"
+    } else if tag {
+        "private, no-cache"
+    } else {
+        "no-store"
+    };
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(cache_control),
+    );
+    if !tag {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    if !body
+        .size_hint()
+        .upper()
+        .is_some_and(|n| n <= MAX_TAGGED_BODY)
+    {
+        return Response::from_parts(parts, body);
+    }
+    let bytes = match axum::body::to_bytes(body, MAX_TAGGED_BODY as usize).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("Could not read the response to {} to tag it: {}", path, e);
+            parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+            parts.headers.remove(header::CONTENT_LENGTH);
+            return Response::from_parts(parts, Body::empty());
+        }
+    };
+
+    let mut hasher = DefaultHasher::new();
+    bytes.hash(&mut hasher);
+    let etag = format!("W/\"{:016x}\"", hasher.finish());
+    let unchanged = if_none_match
+        .as_ref()
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.split(',').any(|tag| tag.trim() == etag));
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+    if unchanged {
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(header::CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(bytes))
+}
@@ -3,8 +3,12 @@
 //! All environment variables are loaded and validated at startup through this module.
 //! This prevents scattered `env::var()` calls and ensures early failure on missing config.
 
+#[cfg(feature = "server")]
+use shared::logging::{LogFormat, LogRotation};
 #[cfg(feature = "server")]
 use std::path::PathBuf;
+#[cfg(feature = "server")]
+use tower_cookies::cookie::SameSite;
 
 #[cfg(feature = "server")]
 const DEFAULT_SECRET_KEY: &str = "secret";
@@ -33,6 +37,53 @@ fn parse_bool_env(key: &str, default: bool) -> bool {
         .unwrap_or(default)
 }
 
+/// Parse a comma-separated list of CIDR ranges from an environment variable.
+/// Invalid entries are logged and skipped.
+#[cfg(feature = "server")]
+fn parse_networks_env(key: &str) -> Vec<crate::network_acl::Cidr> {
+    std::env::var(key)
+        .unwrap_or_default()
+        .split(',')
+        .filter(|s| !s.trim().is_empty())
+        .filter_map(|s| match crate::network_acl::Cidr::parse(s) {
+            Ok(cidr) => Some(cidr),
+            Err(e) => {
+                tracing::warn!("Ignoring entry of {}: {}", key, e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// How the server terminates TLS itself, when it does.
+#[cfg(feature = "server")]
+#[derive(Debug, Clone)]
+pub enum TlsConfig {
+    /// A certificate chain and private key in PEM files
+    Files { cert: PathBuf, key: PathBuf },
+    /// Certificates obtained and renewed from Let's Encrypt. Needs the
+    /// server built with the `acme` feature.
+    Acme {
+        domains: Vec<String>,
+        contact: Option<String>,
+        cache_dir: PathBuf,
+        staging: bool,
+    },
+}
+
+/// Mail server password reset links are sent through.
+#[cfg(feature = "server")]
+#[derive(Debug, Clone)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// 465 connects over TLS, anything else upgrades with STARTTLS
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// Sender address, e.g. "Soulbeet <soulbeet@example.com>"
+    pub from: String,
+}
+
 /// Application configuration loaded from environment variables.
 #[cfg(feature = "server")]
 #[derive(Debug, Clone)]
@@ -47,6 +98,65 @@ pub struct AppConfig {
     beets_config: PathBuf,
     /// Enable album mode for beets import (groups tracks by folder)
     beets_album_mode: bool,
+    /// Directories the folder browser may list (default: "/music" and the download path)
+    browse_roots: Vec<PathBuf>,
+    /// Where live recordings are filed, relative to the target folder
+    live_path_template: String,
+    /// Where bootlegs are filed, relative to the target folder
+    bootleg_path_template: String,
+    /// Where albums imported from their own tags during a MusicBrainz
+    /// outage are filed, relative to the target folder
+    tag_derived_path_template: String,
+    /// Limit for a single request to slskd (default: 30s)
+    slskd_request_timeout: std::time::Duration,
+    /// Serve HTTPS directly instead of plain HTTP
+    tls: Option<TlsConfig>,
+    /// Mark the auth cookie `Secure`. Off by default, unless the server
+    /// terminates TLS itself, so plain HTTP on a LAN keeps working.
+    cookie_secure: bool,
+    /// SameSite attribute of the auth cookie (default: Lax)
+    cookie_same_site: SameSite,
+    /// Origins besides this host's own that may make requests with the auth
+    /// cookie, for setups where the proxy rewrites the Host header
+    allowed_origins: Vec<String>,
+    /// Send Strict-Transport-Security. Only for instances served over HTTPS.
+    hsts: bool,
+    /// Networks allowed to use the instance at all (default: any)
+    allowed_networks: Vec<crate::network_acl::Cidr>,
+    /// Networks allowed to reach settings and user management (default: any)
+    admin_networks: Vec<crate::network_acl::Cidr>,
+    /// Take the client address from X-Forwarded-For, for instances behind a
+    /// reverse proxy
+    trust_forwarded_for: bool,
+    /// Directory holding plugin programs, one per subdirectory (default: none)
+    plugins_dir: Option<PathBuf>,
+    /// Let post-import hooks run shell commands as the server's user
+    /// (default: off, webhooks only)
+    allow_command_hooks: bool,
+    /// Where cover art fetched for the UI is kept (default: "art_cache")
+    art_cache_dir: PathBuf,
+    /// Most cover art kept on disk, oldest dropped first (default: 500 MiB)
+    art_cache_size: u64,
+    /// Most a user may have staged from the Import view, all of their
+    /// uploads together (default: 4 GiB)
+    max_upload_size: u64,
+    /// Mail server for password reset emails (default: none)
+    smtp: Option<SmtpConfig>,
+    /// Address users reach the instance at, for the links it builds
+    public_url: Option<String>,
+    /// Sub-path the instance is served under behind a reverse proxy, e.g.
+    /// "/soulbeet" (default: none)
+    base_path: String,
+    /// Log filter, e.g. "info,soulbeet=debug" (default: RUST_LOG, else "info")
+    log_filter: String,
+    /// Format of log lines (default: text)
+    log_format: LogFormat,
+    /// Directory log files are written to, besides stdout (default: none)
+    log_dir: Option<PathBuf>,
+    /// When the log file is rotated (default: daily)
+    log_rotation: LogRotation,
+    /// Rotated log files kept, 0 for all (default: 7)
+    log_max_files: usize,
     /// HTTP server port (default: 9765)
     pub port: u16,
     /// HTTP server bind address (default: "0.0.0.0")
@@ -75,17 +185,194 @@ impl AppConfig {
             );
         }
 
+        let download_path = PathBuf::from(
+            std::env::var("DOWNLOAD_PATH").unwrap_or_else(|_| "/downloads".to_string()),
+        );
+
+        let browse_roots = match std::env::var("BROWSE_ROOTS") {
+            Ok(v) if !v.trim().is_empty() => v
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            _ => vec![PathBuf::from("/music"), download_path.clone()],
+        };
+
+        let tls = match (
+            std::env::var("TLS_CERT_PATH").ok(),
+            std::env::var("TLS_KEY_PATH").ok(),
+            std::env::var("ACME_DOMAINS").ok(),
+        ) {
+            (Some(cert), Some(key), _) => Some(TlsConfig::Files {
+                cert: PathBuf::from(cert),
+                key: PathBuf::from(key),
+            }),
+            (Some(_), None, _) | (None, Some(_), _) => {
+                tracing::error!(
+                    "TLS_CERT_PATH and TLS_KEY_PATH must be set together. Serving plain HTTP."
+                );
+                None
+            }
+            (None, None, Some(domains)) if !domains.trim().is_empty() => Some(TlsConfig::Acme {
+                domains: domains
+                    .split(',')
+                    .map(|d| d.trim().to_string())
+                    .filter(|d| !d.is_empty())
+                    .collect(),
+                contact: std::env::var("ACME_EMAIL").ok().filter(|e| !e.is_empty()),
+                cache_dir: PathBuf::from(
+                    std::env::var("ACME_CACHE_DIR").unwrap_or_else(|_| "acme".to_string()),
+                ),
+                staging: parse_bool_env("ACME_STAGING", false),
+            }),
+            _ => None,
+        };
+
+        let smtp = match (std::env::var("SMTP_HOST"), std::env::var("SMTP_FROM")) {
+            (Ok(host), Ok(from)) if !host.trim().is_empty() && !from.trim().is_empty() => {
+                Some(SmtpConfig {
+                    host: host.trim().to_string(),
+                    port: std::env::var("SMTP_PORT")
+                        .ok()
+                        .and_then(|p| p.parse().ok())
+                        .unwrap_or(587),
+                    username: std::env::var("SMTP_USERNAME")
+                        .ok()
+                        .filter(|u| !u.is_empty()),
+                    password: std::env::var("SMTP_PASSWORD")
+                        .ok()
+                        .filter(|p| !p.is_empty()),
+                    from: from.trim().to_string(),
+                })
+            }
+            (Ok(host), _) if !host.trim().is_empty() => {
+                tracing::error!("SMTP_HOST is set without SMTP_FROM. Emails are disabled.");
+                None
+            }
+            _ => None,
+        };
+
+        let cookie_secure = parse_bool_env("COOKIE_SECURE", tls.is_some());
+        let cookie_same_site = match std::env::var("COOKIE_SAME_SITE")
+            .map(|v| v.to_lowercase())
+            .as_deref()
+        {
+            Ok("strict") => SameSite::Strict,
+            Ok("none") if cookie_secure => SameSite::None,
+            Ok("none") => {
+                tracing::warn!(
+                    "COOKIE_SAME_SITE=none requires COOKIE_SECURE=true, browsers would drop \
+                     the cookie. Using lax."
+                );
+                SameSite::Lax
+            }
+            Ok("lax") | Err(_) => SameSite::Lax,
+            Ok(other) => {
+                tracing::warn!("Invalid COOKIE_SAME_SITE '{}', using lax", other);
+                SameSite::Lax
+            }
+        };
+
+        let log_format = match std::env::var("LOG_FORMAT")
+            .map(|v| v.to_lowercase())
+            .as_deref()
+        {
+            Ok("json") => LogFormat::Json,
+            Ok("text") | Err(_) => LogFormat::Text,
+            Ok(other) => {
+                tracing::warn!("Invalid LOG_FORMAT '{}', using text", other);
+                LogFormat::Text
+            }
+        };
+
         Self {
             database_url: std::env::var("DATABASE_URL")
                 .unwrap_or_else(|_| "sqlite:soulbeet.db".to_string()),
             secret_key,
-            download_path: PathBuf::from(
-                std::env::var("DOWNLOAD_PATH").unwrap_or_else(|_| "/downloads".to_string()),
-            ),
+            download_path,
             beets_config: PathBuf::from(
                 std::env::var("BEETS_CONFIG").unwrap_or_else(|_| "beets_config.yaml".to_string()),
             ),
             beets_album_mode: parse_bool_env("BEETS_ALBUM_MODE", false),
+            browse_roots,
+            live_path_template: std::env::var("LIVE_PATH_TEMPLATE")
+                .unwrap_or_else(|_| "Live/{artist}/{year} - {album}".to_string()),
+            bootleg_path_template: std::env::var("BOOTLEG_PATH_TEMPLATE")
+                .unwrap_or_else(|_| "Bootlegs/{artist}/{year} - {album}".to_string()),
+            tag_derived_path_template: std::env::var("TAG_DERIVED_PATH_TEMPLATE")
+                .unwrap_or_else(|_| "{artist}/{year} - {album}".to_string()),
+            slskd_request_timeout: std::time::Duration::from_secs(
+                std::env::var("SLSKD_REQUEST_TIMEOUT_SECS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|secs| *secs > 0)
+                    .unwrap_or(30),
+            ),
+            tls,
+            cookie_secure,
+            cookie_same_site,
+            allowed_origins: std::env::var("ALLOWED_ORIGINS")
+                .map(|v| {
+                    v.split(',')
+                        .map(|o| o.trim().trim_end_matches('/').to_string())
+                        .filter(|o| !o.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            hsts: parse_bool_env("HSTS", false),
+            allowed_networks: parse_networks_env("ALLOWED_NETWORKS"),
+            admin_networks: parse_networks_env("ADMIN_NETWORKS"),
+            trust_forwarded_for: parse_bool_env("TRUST_FORWARDED_FOR", false),
+            plugins_dir: std::env::var("PLUGINS_DIR")
+                .ok()
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from),
+            allow_command_hooks: parse_bool_env("ALLOW_COMMAND_HOOKS", false),
+            art_cache_dir: PathBuf::from(
+                std::env::var("ART_CACHE_DIR").unwrap_or_else(|_| "art_cache".to_string()),
+            ),
+            art_cache_size: std::env::var("ART_CACHE_MAX_MB")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(500)
+                * 1024
+                * 1024,
+            max_upload_size: std::env::var("MAX_UPLOAD_MB")
+                .ok()
+                .and_then(|v| v.parse::<u64>().ok())
+                .filter(|mb| *mb > 0)
+                .unwrap_or(4096)
+                * 1024
+                * 1024,
+            smtp,
+            public_url: std::env::var("PUBLIC_URL")
+                .ok()
+                .map(|u| u.trim().trim_end_matches('/').to_string())
+                .filter(|u| !u.is_empty()),
+            base_path: shared::system::normalize_base_path(
+                &std::env::var("BASE_PATH").unwrap_or_default(),
+            ),
+            log_filter: std::env::var("LOG_LEVEL")
+                .or_else(|_| std::env::var("RUST_LOG"))
+                .ok()
+                .filter(|f| !f.trim().is_empty())
+                .unwrap_or_else(|| "info".to_string()),
+            log_format,
+            log_dir: std::env::var("LOG_DIR")
+                .ok()
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from),
+            log_rotation: LogRotation::from(
+                std::env::var("LOG_ROTATION")
+                    .unwrap_or_default()
+                    .to_lowercase()
+                    .as_str(),
+            ),
+            log_max_files: std::env::var("LOG_MAX_FILES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(7),
             port: std::env::var("PORT")
                 .ok()
                 .and_then(|p| p.parse().ok())
@@ -109,6 +396,66 @@ impl AppConfig {
         &self.download_path
     }
 
+    /// Get the directory plugins are loaded from, if plugins are enabled.
+    pub fn plugins_dir(&self) -> Option<&PathBuf> {
+        self.plugins_dir.as_ref()
+    }
+
+    /// Whether post-import hooks may run shell commands.
+    pub fn allow_command_hooks(&self) -> bool {
+        self.allow_command_hooks
+    }
+
+    /// Get the directory cover art is cached in.
+    pub fn art_cache_dir(&self) -> &PathBuf {
+        &self.art_cache_dir
+    }
+
+    /// Get the most cover art kept on disk, in bytes.
+    pub fn art_cache_size(&self) -> u64 {
+        self.art_cache_size
+    }
+
+    /// Get the largest upload allowed, in bytes.
+    pub fn max_upload_size(&self) -> u64 {
+        self.max_upload_size
+    }
+
+    /// Get the mail server, if emails are set up.
+    pub fn smtp(&self) -> Option<&SmtpConfig> {
+        self.smtp.as_ref()
+    }
+
+    /// Get the sub-path the instance is served under, "" at the root.
+    pub fn base_path(&self) -> &str {
+        &self.base_path
+    }
+
+    /// Get the log filter set in the environment.
+    pub fn log_filter(&self) -> &str {
+        &self.log_filter
+    }
+
+    /// Get the format of log lines.
+    pub fn log_format(&self) -> LogFormat {
+        self.log_format
+    }
+
+    /// Get the directory log files are written to, if file logging is on.
+    pub fn log_dir(&self) -> Option<&PathBuf> {
+        self.log_dir.as_ref()
+    }
+
+    /// Get when the log file is rotated.
+    pub fn log_rotation(&self) -> LogRotation {
+        self.log_rotation
+    }
+
+    /// Get how many rotated log files are kept.
+    pub fn log_max_files(&self) -> usize {
+        self.log_max_files
+    }
+
     /// Get the beets config path.
     pub fn beets_config(&self) -> &PathBuf {
         &self.beets_config
@@ -118,6 +465,84 @@ impl AppConfig {
     pub fn is_album_mode(&self) -> bool {
         self.beets_album_mode
     }
+
+    /// Get the roots the folder browser is allowed to list.
+    pub fn browse_roots(&self) -> &[PathBuf] {
+        &self.browse_roots
+    }
+
+    /// Get the limit for a single request to slskd.
+    pub fn slskd_request_timeout(&self) -> std::time::Duration {
+        self.slskd_request_timeout
+    }
+
+    /// Get how the server terminates TLS, if it does.
+    pub fn tls(&self) -> Option<&TlsConfig> {
+        self.tls.as_ref()
+    }
+
+    /// Scheme the server is reached with directly, for links it builds.
+    pub fn scheme(&self) -> &'static str {
+        if self.tls.is_some() {
+            "https"
+        } else {
+            "http"
+        }
+    }
+
+    /// Get the address links to the instance start with: PUBLIC_URL when
+    /// set, else the scheme, `host` and the base path.
+    pub fn app_url(&self, host: Option<&str>) -> Option<String> {
+        match &self.public_url {
+            Some(url) => Some(url.clone()),
+            None => host.map(|host| format!("{}://{}{}", self.scheme(), host, self.base_path)),
+        }
+    }
+
+    /// Whether the auth cookie is only sent over HTTPS.
+    pub fn cookie_secure(&self) -> bool {
+        self.cookie_secure
+    }
+
+    /// Get the SameSite attribute of the auth cookie.
+    pub fn cookie_same_site(&self) -> SameSite {
+        self.cookie_same_site
+    }
+
+    /// Get the extra origins allowed to make cookie-authenticated requests.
+    pub fn allowed_origins(&self) -> &[String] {
+        &self.allowed_origins
+    }
+
+    /// Whether responses tell browsers to only use HTTPS from now on.
+    pub fn hsts(&self) -> bool {
+        self.hsts
+    }
+
+    /// Get the networks allowed to use the instance. Empty allows any.
+    pub fn allowed_networks(&self) -> &[crate::network_acl::Cidr] {
+        &self.allowed_networks
+    }
+
+    /// Get the networks allowed to reach settings and user management.
+    /// Empty allows any.
+    pub fn admin_networks(&self) -> &[crate::network_acl::Cidr] {
+        &self.admin_networks
+    }
+
+    /// Whether the client address is taken from X-Forwarded-For.
+    pub fn trust_forwarded_for(&self) -> bool {
+        self.trust_forwarded_for
+    }
+
+    /// Get the path template for an import that bypasses the importer.
+    pub fn non_canonical_template(&self, kind: shared::download::NonCanonicalKind) -> &str {
+        match kind {
+            shared::download::NonCanonicalKind::Live => &self.live_path_template,
+            shared::download::NonCanonicalKind::Bootleg => &self.bootleg_path_template,
+            shared::download::NonCanonicalKind::TagDerived => &self.tag_derived_path_template,
+        }
+    }
 }
 
 #[cfg(feature = "server")]
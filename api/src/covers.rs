@@ -0,0 +1,255 @@
+//! Cover art for the pages that show it.
+//!
+//! Covers of library albums are read from the album's directory, either the
+//! image the importer saved there or the one embedded in its tracks, and
+//! kept in memory so a page of covers doesn't reopen every album on each
+//! visit. Covers of releases are fetched once from the Cover Art Archive and
+//! kept on disk, so browsers don't each ask it (slowly) for every result.
+//! They are not resized here, so only the archive's own thumbnail sizes can
+//! be asked for and any other size is refused. The disk cache is pruned to `ART_CACHE_MAX_MB`
+//! and to a month of age.
+
+#[cfg(feature = "server")]
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "server")]
+use std::path::Path;
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use std::time::{Duration, SystemTime};
+
+#[cfg(feature = "server")]
+use axum::body::Bytes;
+#[cfg(feature = "server")]
+use axum::http::{header, StatusCode};
+#[cfg(feature = "server")]
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+#[cfg(feature = "server")]
+use tracing::{info, warn};
+
+#[cfg(feature = "server")]
+use crate::models::folder::Folder;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+
+/// Covers kept in memory; the oldest is dropped past this.
+#[cfg(feature = "server")]
+const MAX_CACHED: usize = 200;
+
+/// Thumbnail sizes the Cover Art Archive serves, in pixels.
+#[cfg(feature = "server")]
+const ART_SIZES: [u32; 3] = [250, 500, 1200];
+
+/// Image types cached on disk, by file extension. Others are served
+/// without being cached.
+#[cfg(feature = "server")]
+const ART_TYPES: [(&str, &str); 4] = [
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("gif", "image/gif"),
+    ("webp", "image/webp"),
+];
+
+/// Cover art older than this is fetched again.
+#[cfg(feature = "server")]
+const ART_MAX_AGE: Duration = Duration::from_secs(30 * 24 * 3600);
+
+#[cfg(feature = "server")]
+#[derive(Default)]
+struct CoverCache {
+    covers: HashMap<String, (String, Bytes)>,
+    order: VecDeque<String>,
+}
+
+#[cfg(feature = "server")]
+static CACHE: LazyLock<RwLock<CoverCache>> = LazyLock::new(|| RwLock::new(CoverCache::default()));
+
+/// URL the cover of the album in `album_dir` is served at.
+#[cfg(feature = "server")]
+pub fn cover_url(album_dir: &str) -> String {
+    format!(
+        "{}/api/covers/{}",
+        crate::config::CONFIG.base_path(),
+        URL_SAFE_NO_PAD.encode(album_dir)
+    )
+}
+
+/// Serve the cover of an album directory, named as by [`cover_url`], if it
+/// is inside a folder the user can see.
+#[cfg(feature = "server")]
+pub async fn album_cover(
+    AuthSession(claims): AuthSession,
+    axum::extract::Path(key): axum::extract::Path<String>,
+) -> Result<impl axum::response::IntoResponse, (StatusCode, String)> {
+    let dir = URL_SAFE_NO_PAD
+        .decode(&key)
+        .ok()
+        .and_then(|d| String::from_utf8(d).ok())
+        .ok_or((StatusCode::BAD_REQUEST, "Invalid cover".to_string()))?;
+    let visible = Folder::is_visible_path(&claims.sub, Path::new(&dir))
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    if !visible {
+        return Err((StatusCode::NOT_FOUND, "No such album".to_string()));
+    }
+
+    let cached = CACHE.read().await.covers.get(&dir).cloned();
+    let (mime, data) = match cached {
+        Some(cover) => cover,
+        None => {
+            let task_dir = dir.clone();
+            let (data, mime) = tokio::task::spawn_blocking(move || {
+                soulbeet::tagging::album_cover(Path::new(&task_dir))
+            })
+            .await
+            .ok()
+            .flatten()
+            .ok_or((StatusCode::NOT_FOUND, "No cover".to_string()))?;
+            let cover = (mime, Bytes::from(data));
+
+            let mut cache = CACHE.write().await;
+            if cache.covers.insert(dir.clone(), cover.clone()).is_none() {
+                cache.order.push_back(dir);
+            }
+            while cache.order.len() > MAX_CACHED {
+                if let Some(oldest) = cache.order.pop_front() {
+                    cache.covers.remove(&oldest);
+                }
+            }
+            cover
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, mime),
+            (header::CACHE_CONTROL, "private, max-age=86400".to_string()),
+        ],
+        data,
+    ))
+}
+
+#[cfg(feature = "server")]
+#[derive(serde::Deserialize)]
+pub struct ArtParams {
+    size: Option<u32>,
+}
+
+/// Serve the front cover of a MusicBrainz release as the Cover Art Archive
+/// thumbnail of `size` (250 by default), from the disk cache when it was
+/// fetched before. The thumbnail is served as the archive sent it, not
+/// resized, so `size` must be one of `ART_SIZES`.
+#[cfg(feature = "server")]
+pub async fn release_art(
+    _auth: AuthSession,
+    axum::extract::Path(release_id): axum::extract::Path<String>,
+    axum::extract::Query(params): axum::extract::Query<ArtParams>,
+) -> Result<impl axum::response::IntoResponse, (StatusCode, String)> {
+    let valid = release_id.len() == 36
+        && release_id
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() || c == '-');
+    if !valid {
+        return Err((StatusCode::BAD_REQUEST, "Invalid release id".to_string()));
+    }
+    let size = params.size.unwrap_or(ART_SIZES[0]);
+    if !ART_SIZES.contains(&size) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("Size must be one of {:?}", ART_SIZES),
+        ));
+    }
+
+    let dir = crate::config::CONFIG.art_cache_dir();
+    let stem = format!("{}-{}", release_id.to_lowercase(), size);
+    let (data, content_type) = match cached_art(dir, &stem).await {
+        Some(cached) => cached,
+        None => {
+            let (data, content_type) = soulbeet::enrichment::release_art(&release_id, size)
+                .await
+                .map_err(|e| (StatusCode::BAD_GATEWAY, e))?
+                .ok_or((StatusCode::NOT_FOUND, "No cover art".to_string()))?;
+            if let Some((ext, _)) = ART_TYPES.iter().find(|(_, t)| *t == content_type) {
+                let file = dir.join(format!("{}.{}", stem, ext));
+                // Written aside and renamed so a reader never sees half a file
+                let partial = file.with_extension(format!("{}.part", uuid::Uuid::new_v4()));
+                let stored = async {
+                    tokio::fs::create_dir_all(dir).await?;
+                    tokio::fs::write(&partial, &data).await?;
+                    tokio::fs::rename(&partial, &file).await
+                };
+                if let Err(e) = stored.await {
+                    warn!("Could not cache cover art of {}: {}", release_id, e);
+                    let _ = tokio::fs::remove_file(&partial).await;
+                }
+            }
+            (data, content_type)
+        }
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type),
+            (header::CACHE_CONTROL, "private, max-age=604800".to_string()),
+        ],
+        data,
+    ))
+}
+
+/// Cover art cached as `stem` with any of the known extensions, with its type.
+#[cfg(feature = "server")]
+async fn cached_art(dir: &Path, stem: &str) -> Option<(Vec<u8>, String)> {
+    for (ext, content_type) in ART_TYPES {
+        if let Ok(data) = tokio::fs::read(dir.join(format!("{}.{}", stem, ext))).await {
+            return Some((data, content_type.to_string()));
+        }
+    }
+    None
+}
+
+/// Remove cover art older than a month, then the oldest until the cache
+/// fits in `ART_CACHE_MAX_MB`.
+#[cfg(feature = "server")]
+pub async fn prune_art_cache() {
+    let dir = crate::config::CONFIG.art_cache_dir();
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return;
+    };
+    let now = SystemTime::now();
+    let mut files = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let Ok(meta) = entry.metadata().await else {
+            continue;
+        };
+        if meta.is_file() {
+            let modified = meta.modified().unwrap_or(now);
+            files.push((modified, meta.len(), entry.path()));
+        }
+    }
+
+    // Newest first, so what is left past the limit is the oldest
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    let limit = crate::config::CONFIG.art_cache_size();
+    let mut kept = 0u64;
+    let mut removed = 0usize;
+    for (modified, len, path) in files {
+        let expired = now.duration_since(modified).unwrap_or_default() > ART_MAX_AGE;
+        if !expired && kept + len <= limit {
+            kept += len;
+            continue;
+        }
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => removed += 1,
+            Err(e) => warn!(
+                "Could not remove cached cover art {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+    if removed > 0 {
+        info!("Removed {} cached covers", removed);
+    }
+}
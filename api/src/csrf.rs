@@ -0,0 +1,91 @@
+//! Cross-site request forgery protection for cookie-authenticated requests.
+//!
+//! Browsers attach the auth cookie to any request made to this host,
+//! including ones a different site triggers. Requests that change state
+//! (anything but GET, HEAD and OPTIONS, and WebSocket upgrades) must
+//! therefore come from a page served by this host: their `Origin` header,
+//! or failing that `Sec-Fetch-Site`, has to say so. Requests without the
+//! auth cookie, such as integrations using an API token, carry no ambient
+//! credentials and are left alone.
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::Request,
+    http::{header, HeaderMap, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+#[cfg(feature = "server")]
+use tracing::warn;
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+
+#[cfg(feature = "server")]
+fn has_auth_cookie(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .any(|c| {
+            c.trim()
+                .strip_prefix(crate::AUTH_COOKIE_NAME)
+                .is_some_and(|rest| rest.starts_with('='))
+        })
+}
+
+/// `scheme://host[:port]` with the scheme dropped, lowercased.
+#[cfg(feature = "server")]
+fn authority(origin: &str) -> String {
+    origin
+        .split_once("://")
+        .map_or(origin, |(_, rest)| rest)
+        .trim_end_matches('/')
+        .to_lowercase()
+}
+
+/// Whether the request was made by a page of this site. Requests that say
+/// nothing about where they come from (non-browser clients) pass.
+#[cfg(feature = "server")]
+pub fn is_same_origin(headers: &HeaderMap) -> bool {
+    let Some(origin) = headers.get(header::ORIGIN).and_then(|v| v.to_str().ok()) else {
+        return !matches!(
+            headers.get("sec-fetch-site").and_then(|v| v.to_str().ok()),
+            Some("cross-site" | "same-site")
+        );
+    };
+    if CONFIG.allowed_origins().iter().any(|o| o == origin) {
+        return true;
+    }
+    // Behind a reverse proxy the public host is in X-Forwarded-Host
+    let host = headers
+        .get("x-forwarded-host")
+        .or_else(|| headers.get(header::HOST))
+        .and_then(|v| v.to_str().ok())
+        .map(|h| h.split(',').next().unwrap_or(h).trim().to_lowercase());
+    host.is_some_and(|host| authority(origin) == host)
+}
+
+/// Axum middleware rejecting cross-site, cookie-authenticated requests
+/// that change state.
+#[cfg(feature = "server")]
+pub async fn verify_origin(request: Request, next: Next) -> Response {
+    let headers = request.headers();
+    let is_upgrade = headers.contains_key(header::UPGRADE);
+    let safe = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if (safe && !is_upgrade) || !has_auth_cookie(headers) || is_same_origin(headers) {
+        return next.run(request).await;
+    }
+
+    warn!(
+        "Rejected cross-site {} {} (origin: {:?})",
+        request.method(),
+        request.uri().path(),
+        headers.get(header::ORIGIN)
+    );
+    (StatusCode::FORBIDDEN, "Cross-site request rejected").into_response()
+}
@@ -0,0 +1,23 @@
+//! Demo mode, for hosting a public instance without a real backend. Built
+//! with the `demo` feature, the fixture download backend and importer from
+//! `soulbeet::demo` replace slskd and beets, and settings can be looked at
+//! but not changed.
+
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// Axum middleware refusing changes to settings, users and folders.
+pub async fn read_only_settings(request: Request, next: Next) -> Response {
+    let read = matches!(
+        *request.method(),
+        Method::GET | Method::HEAD | Method::OPTIONS
+    );
+    if read || !crate::network_acl::is_admin_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+    (StatusCode::FORBIDDEN, "Settings are read-only in the demo").into_response()
+}
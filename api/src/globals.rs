@@ -1,12 +1,14 @@
 #[cfg(feature = "server")]
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 #[cfg(feature = "server")]
 use std::sync::{LazyLock, Once};
 #[cfg(feature = "server")]
 use std::time::Duration;
 
 #[cfg(feature = "server")]
-use shared::download::DownloadEvent;
+use shared::download::{DownloadEvent, DownloadProgress, ImportOverrides};
+#[cfg(feature = "server")]
+use shared::system::MonitorFailure;
 #[cfg(feature = "server")]
 use tokio::sync::{broadcast, RwLock};
 #[cfg(feature = "server")]
@@ -18,14 +20,140 @@ use tracing::{info, warn};
 #[cfg(feature = "server")]
 const CHANNEL_CLEANUP_INTERVAL_SECS: u64 = 300;
 
+/// Interval between checks of the download monitors' heartbeats.
+#[cfg(feature = "server")]
+const HEARTBEAT_CHECK_INTERVAL_SECS: u64 = 60;
+
+/// Interval between checks of the folders' mounts.
+#[cfg(feature = "server")]
+const MOUNT_CHECK_INTERVAL_SECS: u64 = 60;
+
 /// Minimum idle time before a channel is considered stale (10 minutes).
 #[cfg(feature = "server")]
 const CHANNEL_STALE_THRESHOLD_SECS: u64 = 600;
 
+/// Events a user channel holds for a slow receiver before it lags.
+#[cfg(feature = "server")]
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Downloads kept per user for reconnecting clients. Finished ones are
+/// dropped first, oldest update first.
+#[cfg(feature = "server")]
+const SNAPSHOT_LIMIT: usize = 500;
+
+/// Downloads per event when a snapshot is sent.
+#[cfg(feature = "server")]
+const SNAPSHOT_PAGE_SIZE: usize = 100;
+
+/// Latest state of each download a user's channel has carried, keyed like
+/// the Downloads panel (by item), with the order they were last updated in.
+#[cfg(feature = "server")]
+#[derive(Default)]
+pub struct DownloadSnapshot {
+    entries: HashMap<String, (u64, DownloadProgress)>,
+    updates: u64,
+}
+
+#[cfg(feature = "server")]
+impl DownloadSnapshot {
+    fn apply(&mut self, entries: &[DownloadProgress]) {
+        for entry in entries {
+            self.updates += 1;
+            self.entries
+                .insert(entry.item.clone(), (self.updates, entry.clone()));
+        }
+        if self.entries.len() > SNAPSHOT_LIMIT {
+            let mut order: Vec<(bool, u64, String)> = self
+                .entries
+                .iter()
+                .map(|(item, (seq, p))| (crate::activity::is_active(&p.state), *seq, item.clone()))
+                .collect();
+            order.sort_unstable();
+            let excess = self.entries.len() - SNAPSHOT_LIMIT;
+            for (_, _, item) in order.into_iter().take(excess) {
+                self.entries.remove(&item);
+            }
+        }
+    }
+
+    /// Stop sending these items, once the user cleared them.
+    pub fn forget(&mut self, items: &[String]) {
+        for item in items {
+            self.entries.remove(item);
+        }
+    }
+
+    /// Every download in the snapshot, in the order of their last update.
+    pub fn entries(&self) -> Vec<DownloadProgress> {
+        let mut entries: Vec<&(u64, DownloadProgress)> = self.entries.values().collect();
+        entries.sort_unstable_by_key(|(seq, _)| *seq);
+        entries.into_iter().map(|(_, p)| p.clone()).collect()
+    }
+
+    /// The snapshot as progress events, in the order of their last update.
+    pub fn events(&self) -> Vec<DownloadEvent> {
+        self.entries()
+            .chunks(SNAPSHOT_PAGE_SIZE)
+            .map(|page| DownloadEvent::Progress(page.to_vec()))
+            .collect()
+    }
+}
+
+/// Sending half of a user channel. Progress is applied to the snapshot
+/// before it is sent, under the snapshot's lock, so a client subscribing
+/// finds each update either in the snapshot or in its receiver.
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct UserSender {
+    sender: broadcast::Sender<DownloadEvent>,
+    snapshot: std::sync::Arc<std::sync::Mutex<DownloadSnapshot>>,
+}
+
+#[cfg(feature = "server")]
+impl UserSender {
+    fn new(sender: broadcast::Sender<DownloadEvent>) -> Self {
+        Self {
+            sender,
+            snapshot: Default::default(),
+        }
+    }
+
+    /// A sender nobody listens to, for work whose progress is reported
+    /// some other way.
+    pub fn silent() -> Self {
+        Self::new(broadcast::channel(16).0)
+    }
+
+    /// Send `event`, recording the progress it carries first.
+    pub fn send(
+        &self,
+        event: DownloadEvent,
+    ) -> Result<usize, broadcast::error::SendError<DownloadEvent>> {
+        let mut snapshot = self.snapshot.lock().unwrap_or_else(|e| e.into_inner());
+        if let DownloadEvent::Progress(entries) = &event {
+            snapshot.apply(entries);
+        }
+        self.sender.send(event)
+    }
+
+    /// Subscribe to the channel, along with the snapshot as of then.
+    pub fn subscribe(&self) -> (broadcast::Receiver<DownloadEvent>, Vec<DownloadEvent>) {
+        let snapshot = self.snapshot.lock().unwrap_or_else(|e| e.into_inner());
+        (self.sender.subscribe(), snapshot.events())
+    }
+
+    /// Receivers of the channel, the activity watcher included.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
 /// Channel info including the sender and cancellation token for cleanup
 #[cfg(feature = "server")]
 pub struct UserChannel {
-    pub sender: broadcast::Sender<DownloadEvent>,
+    pub sender: UserSender,
+    /// What a newly connected client is sent before live updates
+    pub snapshot: std::sync::Arc<std::sync::Mutex<DownloadSnapshot>>,
     pub cancellation_token: CancellationToken,
     pub active_tasks: std::sync::atomic::AtomicUsize,
     /// Timestamp of last activity (task registration or message send)
@@ -34,9 +162,16 @@ pub struct UserChannel {
 
 #[cfg(feature = "server")]
 impl UserChannel {
-    pub fn new() -> Self {
-        let (sender, _) = broadcast::channel(100);
+    /// Create the channel and attach the activity watcher to it.
+    pub fn new(username: &str) -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        tokio::spawn(crate::activity::watch_user_channel(
+            username.to_string(),
+            sender.subscribe(),
+        ));
+        let sender = UserSender::new(sender);
         Self {
+            snapshot: sender.snapshot.clone(),
             sender,
             cancellation_token: CancellationToken::new(),
             active_tasks: std::sync::atomic::AtomicUsize::new(0),
@@ -51,6 +186,11 @@ impl UserChannel {
             .as_secs()
     }
 
+    /// Number of clients listening, not counting the activity watcher
+    pub fn listener_count(&self) -> usize {
+        self.sender.receiver_count().saturating_sub(1)
+    }
+
     /// Update the last activity timestamp
     pub fn touch(&self) {
         self.last_activity.store(
@@ -96,30 +236,162 @@ impl UserChannel {
 }
 
 #[cfg(feature = "server")]
-impl Default for UserChannel {
-    fn default() -> Self {
-        Self::new()
-    }
+pub static USER_CHANNELS: LazyLock<RwLock<HashMap<String, UserChannel>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// What it takes to start a download monitor again: the tracks it still
+/// watches and where their updates go. Kept current by its heartbeat.
+#[cfg(feature = "server")]
+#[derive(Clone)]
+pub struct MonitorSpec {
+    pub sources: Vec<String>,
+    pub filenames: Vec<String>,
+    pub target_path: std::path::PathBuf,
+    pub tx: UserSender,
+    pub cancellation_token: CancellationToken,
+    pub username: String,
+    pub batch_id: Option<String>,
+    pub batch_label: Option<String>,
+    pub import_overrides: ImportOverrides,
 }
 
+/// A running download monitor, as the heartbeat watchdog sees it.
 #[cfg(feature = "server")]
-pub static USER_CHANNELS: LazyLock<RwLock<HashMap<String, UserChannel>>> =
-    LazyLock::new(|| RwLock::new(HashMap::new()));
+pub struct MonitorTask {
+    pub spec: MonitorSpec,
+    pub last_beat: std::time::Instant,
+    /// Importing an album, which may take longer than a heartbeat allows
+    pub importing: bool,
+    pub restarts: u32,
+    pub abort: Option<tokio::task::AbortHandle>,
+}
+
+/// Running download monitors keyed by monitor id.
+#[cfg(feature = "server")]
+pub static MONITOR_TASKS: LazyLock<std::sync::Mutex<HashMap<String, MonitorTask>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Monitors given up on since the server started, newest first.
+#[cfg(feature = "server")]
+pub static MONITOR_FAILURES: std::sync::Mutex<VecDeque<MonitorFailure>> =
+    std::sync::Mutex::new(VecDeque::new());
 
 #[cfg(feature = "server")]
 pub static DISCOVERY_PROGRESS: LazyLock<
     RwLock<HashMap<String, shared::navidrome::DiscoveryProgress>>,
 > = LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// Idle time after which a shared search session is dropped (1 hour).
+#[cfg(feature = "server")]
+const SEARCH_SESSION_TTL_SECS: u64 = 3600;
+
+/// A download search shared between users. The owner's client keeps polling
+/// the backend; results and picks are fanned out to every participant.
+#[cfg(feature = "server")]
+pub struct SearchSession {
+    pub owner_id: String,
+    pub owner_name: String,
+    pub search_id: String,
+    pub groups: Vec<shared::download::DownloadableGroup>,
+    pub state: shared::download::SearchState,
+    pub selected: std::collections::HashSet<String>,
+    /// Usernames, owner included
+    pub participants: std::collections::BTreeSet<String>,
+    pub last_activity: std::time::Instant,
+}
+
+/// Shared search sessions keyed by session id.
+#[cfg(feature = "server")]
+pub static SEARCH_SESSIONS: LazyLock<RwLock<HashMap<String, SearchSession>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Who started each backend search, so only the owner can share it.
+#[cfg(feature = "server")]
+pub static SEARCH_OWNERS: LazyLock<RwLock<HashMap<String, (String, std::time::Instant)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Results of a user's latest search, kept to find another peer for a file
+/// whose peer refuses to queue it.
+#[cfg(feature = "server")]
+pub struct LatestSearch {
+    pub search_id: String,
+    pub groups: Vec<shared::download::DownloadableGroup>,
+    pub updated: std::time::Instant,
+}
+
+/// Latest search results keyed by username.
+#[cfg(feature = "server")]
+pub static LATEST_SEARCHES: LazyLock<RwLock<HashMap<String, LatestSearch>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// A finished download parked until its owner reviews the tags.
+#[cfg(feature = "server")]
+pub struct HeldImport {
+    pub username: String,
+    pub pending: shared::download::PendingImport,
+    pub entries: Vec<shared::download::DownloadProgress>,
+    pub source_path: String,
+    pub target_path: std::path::PathBuf,
+    pub tx: UserSender,
+    pub as_album: bool,
+    pub overrides: shared::download::ImportOverrides,
+}
+
+/// Imports waiting for tag review, keyed by review id.
+#[cfg(feature = "server")]
+pub static HELD_IMPORTS: LazyLock<RwLock<HashMap<String, HeldImport>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Send a shared search event to every participant of a session.
+#[cfg(feature = "server")]
+pub async fn broadcast_to_session(
+    participants: impl IntoIterator<Item = String>,
+    event: shared::download::SharedSearchEvent,
+) {
+    for username in participants {
+        let (tx, _) = get_or_create_user_channel(&username).await;
+        let _ = tx.send(DownloadEvent::SharedSearch(event.clone()));
+    }
+}
+
+/// Drop idle shared search sessions, forgotten search owners and old
+/// search results.
+#[cfg(feature = "server")]
+pub async fn cleanup_stale_search_sessions() {
+    let ttl = Duration::from_secs(SEARCH_SESSION_TTL_SECS);
+    SEARCH_SESSIONS
+        .write()
+        .await
+        .retain(|_, session| session.last_activity.elapsed() < ttl);
+    SEARCH_OWNERS
+        .write()
+        .await
+        .retain(|_, (_, started)| started.elapsed() < ttl);
+    LATEST_SEARCHES
+        .write()
+        .await
+        .retain(|_, search| search.updated.elapsed() < ttl);
+}
+
+/// Drop cleared downloads from the user's snapshot.
+#[cfg(feature = "server")]
+pub async fn forget_downloads(username: &str, items: &[String]) {
+    if let Some(channel) = USER_CHANNELS.read().await.get(username) {
+        channel
+            .snapshot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .forget(items);
+    }
+}
+
 /// Get or create a user channel, returning the sender and cancellation token
 #[cfg(feature = "server")]
-pub async fn get_or_create_user_channel(
-    username: &str,
-) -> (broadcast::Sender<DownloadEvent>, CancellationToken) {
+pub async fn get_or_create_user_channel(username: &str) -> (UserSender, CancellationToken) {
     let mut map = USER_CHANNELS.write().await;
     let channel = map
         .entry(username.to_string())
-        .or_insert_with(UserChannel::new);
+        .or_insert_with(|| UserChannel::new(username));
     (channel.sender.clone(), channel.cancellation_token.clone())
 }
 
@@ -129,7 +401,7 @@ pub async fn register_user_task(username: &str) -> CancellationToken {
     let mut map = USER_CHANNELS.write().await;
     let channel = map
         .entry(username.to_string())
-        .or_insert_with(UserChannel::new);
+        .or_insert_with(|| UserChannel::new(username));
     channel.add_task();
     channel.cancellation_token.clone()
 }
@@ -151,7 +423,7 @@ pub async fn unregister_user_task(username: &str) {
     if should_cleanup {
         let map = USER_CHANNELS.read().await;
         if let Some(channel) = map.get(username) {
-            if channel.sender.receiver_count() == 0 && channel.task_count() == 0 {
+            if channel.listener_count() == 0 && channel.task_count() == 0 {
                 info!(
                     "User {} has no active tasks or receivers, eligible for cleanup",
                     username
@@ -199,7 +471,7 @@ pub async fn cleanup_stale_channels() {
     let stale_users: Vec<String> = map
         .iter()
         .filter(|(_, channel)| {
-            let no_activity = channel.sender.receiver_count() == 0 && channel.task_count() == 0;
+            let no_activity = channel.listener_count() == 0 && channel.task_count() == 0;
             no_activity && channel.is_stale()
         })
         .map(|(username, _)| username.clone())
@@ -219,6 +491,9 @@ static CLEANUP_TASK_INIT: Once = Once::new();
 #[cfg(feature = "server")]
 pub fn start_channel_cleanup_task() {
     CLEANUP_TASK_INIT.call_once(|| {
+        tokio::spawn(crate::server_fns::settings::apply_musicbrainz_config());
+        tokio::spawn(crate::server_fns::log_levels::apply_saved_log_levels());
+
         tokio::spawn(async {
             let mut interval =
                 tokio::time::interval(Duration::from_secs(CHANNEL_CLEANUP_INTERVAL_SECS));
@@ -226,6 +501,15 @@ pub fn start_channel_cleanup_task() {
                 interval.tick().await;
                 cleanup_stale_channels().await;
                 cleanup_stale_progress().await;
+                cleanup_stale_search_sessions().await;
+                match crate::models::folder::Folder::purge_deleted().await {
+                    Ok(n) if n > 0 => info!("Purged {} deleted folders", n),
+                    Ok(_) => {}
+                    Err(e) => warn!("Failed to purge deleted folders: {}", e),
+                }
+                crate::server_fns::maintenance::run_scheduled_maintenance().await;
+                crate::server_fns::upload::expire_stale_uploads().await;
+                crate::covers::prune_art_cache().await;
             }
         });
         info!(
@@ -233,6 +517,28 @@ pub fn start_channel_cleanup_task() {
             CHANNEL_CLEANUP_INTERVAL_SECS, CHANNEL_STALE_THRESHOLD_SECS
         );
 
+        tokio::spawn(async {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(MOUNT_CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                crate::mounts::check_all_folders().await;
+            }
+        });
+        info!(
+            "Started mount health task (interval: {}s)",
+            MOUNT_CHECK_INTERVAL_SECS
+        );
+
+        tokio::spawn(async {
+            let mut interval =
+                tokio::time::interval(Duration::from_secs(HEARTBEAT_CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                crate::server_fns::download::monitor::check_heartbeats().await;
+            }
+        });
+
         // Start the automation task (sync ratings, discovery)
         tokio::spawn(async {
             // Wait 30s for server to be fully ready
@@ -1,9 +1,22 @@
+pub mod activity;
 pub mod auth;
+pub mod cache_headers;
 pub mod config;
+pub mod covers;
 pub mod crypto;
+pub mod csrf;
 pub mod db;
+#[cfg(feature = "demo")]
+pub mod demo;
 pub mod globals;
+pub mod logging;
+pub mod mailer;
 pub mod models;
+pub mod mounts;
+pub mod network_acl;
+pub mod panics;
+pub mod rate_limit;
+pub mod security_headers;
 pub mod services;
 
 pub mod server_fns;
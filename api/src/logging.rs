@@ -0,0 +1,131 @@
+//! The server's log subscriber.
+//!
+//! Logs always go to stdout, and also to a rotated file in `LOG_DIR` when it
+//! is set, as text or JSON lines. The filter comes from `LOG_LEVEL` (or
+//! `RUST_LOG`), with per-module overrides from the settings applied on top
+//! while the server runs.
+
+#[cfg(feature = "server")]
+use std::sync::{Mutex, OnceLock};
+
+#[cfg(feature = "server")]
+use shared::logging::{filter_directives, LogFormat, LogLevelOverride, LogRotation, LoggingStatus};
+#[cfg(feature = "server")]
+use tracing_appender::non_blocking::WorkerGuard;
+#[cfg(feature = "server")]
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+#[cfg(feature = "server")]
+use tracing_subscriber::{
+    fmt, layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer, Registry,
+};
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+
+/// Prefix of log file names, e.g. `soulbeet.2026-04-28.log`.
+#[cfg(feature = "server")]
+const FILE_PREFIX: &str = "soulbeet";
+
+#[cfg(feature = "server")]
+static FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+
+/// Flushes the file writer once dropped, so it is kept for the whole run.
+#[cfg(feature = "server")]
+static FILE_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+#[cfg(feature = "server")]
+static OVERRIDES: Mutex<Vec<LogLevelOverride>> = Mutex::new(Vec::new());
+
+/// Install the subscriber. Call before anything logs: Dioxus keeps a
+/// subscriber that is already set instead of its own.
+#[cfg(feature = "server")]
+pub fn init() {
+    let filter = EnvFilter::try_new(CONFIG.log_filter()).unwrap_or_else(|e| {
+        eprintln!(
+            "Invalid log filter '{}' ({}), using info",
+            CONFIG.log_filter(),
+            e
+        );
+        EnvFilter::new("info")
+    });
+    let (filter, handle) = reload::Layer::new(filter);
+
+    let format = CONFIG.log_format();
+    let stdout = match format {
+        LogFormat::Text => fmt::layer().boxed(),
+        LogFormat::Json => fmt::layer().json().boxed(),
+    };
+    let file = CONFIG.log_dir().and_then(|dir| {
+        let mut builder = RollingFileAppender::builder()
+            .rotation(match CONFIG.log_rotation() {
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Daily => Rotation::DAILY,
+                LogRotation::Never => Rotation::NEVER,
+            })
+            .filename_prefix(FILE_PREFIX)
+            .filename_suffix("log");
+        if CONFIG.log_max_files() > 0 {
+            builder = builder.max_log_files(CONFIG.log_max_files());
+        }
+        let appender = match builder.build(dir) {
+            Ok(appender) => appender,
+            Err(e) => {
+                eprintln!("Cannot log to {}: {}", dir.display(), e);
+                return None;
+            }
+        };
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        let _ = FILE_GUARD.set(guard);
+        Some(match format {
+            LogFormat::Text => fmt::layer().with_ansi(false).with_writer(writer).boxed(),
+            LogFormat::Json => fmt::layer().json().with_writer(writer).boxed(),
+        })
+    });
+
+    match tracing_subscriber::registry()
+        .with(filter)
+        .with(stdout)
+        .with(file)
+        .try_init()
+    {
+        Ok(()) => {
+            let _ = FILTER.set(handle);
+        }
+        Err(e) => eprintln!("Logging was already set up: {}", e),
+    }
+}
+
+/// Log the modules in `overrides` at their own level from now on,
+/// replacing the previous overrides.
+#[cfg(feature = "server")]
+pub fn set_overrides(overrides: Vec<LogLevelOverride>) -> Result<(), String> {
+    let directives = filter_directives(CONFIG.log_filter(), &overrides);
+    let filter = EnvFilter::try_new(&directives).map_err(|e| e.to_string())?;
+    if let Some(handle) = FILTER.get() {
+        handle.reload(filter).map_err(|e| e.to_string())?;
+    }
+    *OVERRIDES.lock().unwrap_or_else(|e| e.into_inner()) = overrides;
+    Ok(())
+}
+
+/// Logging as currently set up.
+#[cfg(feature = "server")]
+pub fn status() -> LoggingStatus {
+    let overrides = OVERRIDES.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    let active_filter = FILTER
+        .get()
+        .and_then(|handle| handle.with_current(|f| f.to_string()).ok())
+        .unwrap_or_else(|| "set up by the framework".to_string());
+    LoggingStatus {
+        base_filter: CONFIG.log_filter().to_string(),
+        format: CONFIG.log_format(),
+        file_dir: FILE_GUARD
+            .get()
+            .and(CONFIG.log_dir())
+            .map(|d| d.display().to_string()),
+        rotation: CONFIG.log_rotation(),
+        max_files: CONFIG.log_max_files(),
+        overrides,
+        active_filter,
+    }
+}
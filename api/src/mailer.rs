@@ -0,0 +1,58 @@
+//! Outgoing email, for the few messages that can't wait for the user to
+//! open the app (password resets). Disabled unless SMTP is configured.
+
+#[cfg(feature = "server")]
+use lettre::message::header::ContentType;
+#[cfg(feature = "server")]
+use lettre::message::Mailbox;
+#[cfg(feature = "server")]
+use lettre::transport::smtp::authentication::Credentials;
+#[cfg(feature = "server")]
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+
+/// Whether emails can be sent at all.
+#[cfg(feature = "server")]
+pub fn is_configured() -> bool {
+    CONFIG.smtp().is_some()
+}
+
+/// Send a plain text email to `to`.
+#[cfg(feature = "server")]
+pub async fn send(to: &str, subject: &str, body: String) -> Result<(), String> {
+    let smtp = CONFIG.smtp().ok_or("Email is not configured")?;
+    let from: Mailbox = smtp
+        .from
+        .parse()
+        .map_err(|e| format!("Invalid SMTP_FROM: {}", e))?;
+    let to: Mailbox = to
+        .parse()
+        .map_err(|e| format!("Invalid address {}: {}", to, e))?;
+    let message = Message::builder()
+        .from(from)
+        .to(to)
+        .subject(subject)
+        .header(ContentType::TEXT_PLAIN)
+        .body(body)
+        .map_err(|e| e.to_string())?;
+
+    let relay = if smtp.port == 465 {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp.host)
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&smtp.host)
+    }
+    .map_err(|e| e.to_string())?;
+    let mut transport = relay.port(smtp.port);
+    if let (Some(username), Some(password)) = (&smtp.username, &smtp.password) {
+        transport = transport.credentials(Credentials::new(username.clone(), password.clone()));
+    }
+
+    transport
+        .build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| format!("Failed to send email: {}", e))
+}
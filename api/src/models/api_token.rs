@@ -0,0 +1,95 @@
+#[cfg(feature = "server")]
+use super::token::{hash_token, new_token};
+#[cfg(feature = "server")]
+use super::user::User;
+#[cfg(feature = "server")]
+use crate::db::DB;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// Prefix of every generated token, so they are easy to spot in configs and logs.
+pub const API_TOKEN_PREFIX: &str = "sbt_";
+
+/// Long-lived token for integrations (dashboards, Home Assistant) that can't
+/// go through the cookie login. Only a hash of the secret is stored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct ApiToken {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub created_at: i64,
+    pub last_used_at: Option<i64>,
+}
+
+#[cfg(feature = "server")]
+impl ApiToken {
+    /// Create a token for a user. Returns the stored row and the plaintext
+    /// secret, which is never retrievable again.
+    pub async fn create(user_id: &str, name: &str) -> Result<(ApiToken, String), String> {
+        let secret = new_token(40);
+        let plaintext = format!("{}{}", API_TOKEN_PREFIX, secret);
+
+        let token = sqlx::query_as::<_, ApiToken>(
+            "INSERT INTO api_tokens (id, user_id, name, token_hash) VALUES (?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(name)
+        .bind(hash_token(&plaintext))
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok((token, plaintext))
+    }
+
+    pub async fn get_all_by_user(user_id: &str) -> Result<Vec<ApiToken>, String> {
+        sqlx::query_as::<_, ApiToken>(
+            "SELECT * FROM api_tokens WHERE user_id = ? ORDER BY created_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(id: &str, user_id: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM api_tokens WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Resolve a plaintext token to its owner and record the use.
+    pub async fn authenticate(token: &str) -> Result<User, String> {
+        if !token.starts_with(API_TOKEN_PREFIX) {
+            return Err("Invalid API token".to_string());
+        }
+        let hash = hash_token(token);
+
+        let user = sqlx::query_as::<_, User>(
+            "SELECT users.* FROM users JOIN api_tokens ON api_tokens.user_id = users.id \
+             WHERE api_tokens.token_hash = ?",
+        )
+        .bind(&hash)
+        .fetch_optional(&*DB)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("Invalid API token")?;
+
+        sqlx::query("UPDATE api_tokens SET last_used_at = strftime('%s', 'now') WHERE token_hash = ?")
+            .bind(&hash)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(user)
+    }
+}
@@ -6,6 +6,16 @@ use crate::db::DB;
 pub mod keys {
     pub const SLSKD_API_KEY: &str = "slskd_api_key";
     pub const SLSKD_URL: &str = "slskd_url";
+    pub const TRANSFER_CLEANUP_ENABLED: &str = "transfer_cleanup_enabled";
+    pub const TRANSFER_RETENTION_HOURS: &str = "transfer_retention_hours";
+    pub const TRANSFER_CLEANUP_INTERVAL_HOURS: &str = "transfer_cleanup_interval_hours";
+    pub const FEATURE_FLAGS: &str = "feature_flags";
+    pub const SECURITY_POLICY: &str = "security_policy";
+    pub const DOWNLOADS_PAUSED: &str = "downloads_paused";
+    pub const MUSICBRAINZ_MIRROR_URL: &str = "musicbrainz_mirror_url";
+    pub const MUSICBRAINZ_CONTACT: &str = "musicbrainz_contact";
+    pub const IMPORT_HOOKS: &str = "import_hooks";
+    pub const LOG_LEVELS: &str = "log_levels";
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
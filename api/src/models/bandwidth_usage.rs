@@ -0,0 +1,47 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+
+/// Bytes downloaded per user and UTC day.
+#[cfg(feature = "server")]
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct BandwidthUsageRow {
+    pub day: String,
+    pub bytes: i64,
+}
+
+/// The UTC date `days_ago` days before today, as stored.
+#[cfg(feature = "server")]
+pub fn day(days_ago: i64) -> String {
+    (chrono::Utc::now() - chrono::Duration::days(days_ago))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+#[cfg(feature = "server")]
+impl BandwidthUsageRow {
+    /// Count `bytes` downloaded by the user today.
+    pub async fn add(user_id: &str, bytes: u64) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO bandwidth_usage (user_id, day, bytes) VALUES (?, ?, ?) ON CONFLICT(user_id, day) DO UPDATE SET bytes = bytes + excluded.bytes",
+        )
+        .bind(user_id)
+        .bind(day(0))
+        .bind(bytes as i64)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The user's days from `since` on, newest first.
+    pub async fn since(user_id: &str, since: &str) -> Result<Vec<Self>, String> {
+        sqlx::query_as::<_, Self>(
+            "SELECT day, bytes FROM bandwidth_usage WHERE user_id = ? AND day >= ? ORDER BY day DESC",
+        )
+        .bind(user_id)
+        .bind(since)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
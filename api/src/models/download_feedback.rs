@@ -0,0 +1,68 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use crate::models::download_history::DownloadHistoryRow;
+#[cfg(feature = "server")]
+use shared::download::DownloadFeedback;
+#[cfg(feature = "server")]
+use shared::matching::PeerReputation;
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+
+/// A user's verdict on one downloaded file, kept against the peer and the
+/// file it came as (path and size).
+#[cfg(feature = "server")]
+pub struct DownloadFeedbackRow;
+
+#[cfg(feature = "server")]
+#[derive(sqlx::FromRow)]
+struct ReputationRow {
+    peer: String,
+    good: i64,
+    bad: i64,
+}
+
+#[cfg(feature = "server")]
+impl DownloadFeedbackRow {
+    /// Record the feedback on a history entry, replacing any earlier one.
+    pub async fn set(entry: &DownloadHistoryRow, feedback: DownloadFeedback) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO download_feedback (history_id, user_id, peer, item, size, feedback, created_at) VALUES (?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(history_id) DO UPDATE SET feedback = excluded.feedback, created_at = excluded.created_at",
+        )
+        .bind(&entry.id)
+        .bind(&entry.user_id)
+        .bind(&entry.peer)
+        .bind(&entry.item)
+        .bind(entry.size)
+        .bind(feedback.to_setting())
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Good and bad feedback per peer, from every user.
+    pub async fn reputations() -> Result<HashMap<String, PeerReputation>, String> {
+        let rows = sqlx::query_as::<_, ReputationRow>(
+            "SELECT peer, SUM(feedback = 'good') AS good, SUM(feedback != 'good') AS bad \
+             FROM download_feedback GROUP BY peer",
+        )
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (
+                    r.peer,
+                    PeerReputation {
+                        good: r.good.clamp(0, i64::from(u32::MAX)) as u32,
+                        bad: r.bad.clamp(0, i64::from(u32::MAX)) as u32,
+                    },
+                )
+            })
+            .collect())
+    }
+}
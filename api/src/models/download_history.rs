@@ -0,0 +1,212 @@
+use serde::{Deserialize, Serialize};
+use shared::download::{DownloadFeedback, DownloadHistoryEntry, DownloadNote};
+
+#[cfg(feature = "server")]
+use crate::db::DB;
+use crate::models::download_note::parse_stored_tags;
+#[cfg(feature = "server")]
+use shared::download::{DownloadProgress, DownloadState};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct DownloadHistoryRow {
+    pub id: String,
+    pub user_id: String,
+    pub item: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub format: Option<String>,
+    pub peer: String,
+    pub size: i64,
+    pub outcome: String,
+    pub error: Option<String>,
+    pub finished_at: i64,
+    pub imported_path: Option<String>,
+    pub duplicate: bool,
+    /// From `download_feedback`, for the queries joining it
+    #[cfg_attr(feature = "server", sqlx(default))]
+    #[serde(default)]
+    pub feedback: Option<String>,
+    #[cfg_attr(feature = "server", sqlx(default))]
+    #[serde(default)]
+    pub download_id: Option<String>,
+    /// From `download_notes`, for the queries joining it
+    #[cfg_attr(feature = "server", sqlx(default))]
+    #[serde(default)]
+    pub note: Option<String>,
+    /// JSON array, from `download_notes`
+    #[cfg_attr(feature = "server", sqlx(default))]
+    #[serde(default)]
+    pub note_tags: Option<String>,
+}
+
+impl From<DownloadHistoryRow> for DownloadHistoryEntry {
+    fn from(row: DownloadHistoryRow) -> Self {
+        let note = row.note.map(|note| DownloadNote {
+            note,
+            tags: row
+                .note_tags
+                .as_deref()
+                .map(parse_stored_tags)
+                .unwrap_or_default(),
+        });
+        DownloadHistoryEntry {
+            id: row.id,
+            item: row.item,
+            artist: row.artist,
+            album: row.album,
+            format: row.format,
+            peer: row.peer,
+            size: row.size.max(0) as u64,
+            outcome: row.outcome,
+            error: row.error,
+            finished_at: row.finished_at,
+            imported_path: row.imported_path,
+            duplicate: row.duplicate,
+            feedback: row
+                .feedback
+                .as_deref()
+                .and_then(DownloadFeedback::from_setting),
+            download_id: row.download_id,
+            note,
+        }
+    }
+}
+
+/// History outcome of a state, for the states that end a download.
+#[cfg(feature = "server")]
+pub fn outcome_of(state: &DownloadState) -> Option<&'static str> {
+    match state {
+        DownloadState::Imported => Some("imported"),
+        DownloadState::ImportSkipped => Some("skipped"),
+        DownloadState::Failed(_) => Some("failed"),
+        DownloadState::Cancelled => Some("cancelled"),
+        _ => None,
+    }
+}
+
+/// Artist, album and format guessed from the peer's path, which is usually
+/// `...\Artist\Album\01 - Title.flac`. The batch label wins for the album,
+/// and the tags the importer applied win over both.
+#[cfg(feature = "server")]
+fn describe(entry: &DownloadProgress) -> (Option<String>, Option<String>, Option<String>) {
+    let path = entry.item.replace('\\', "/");
+    let mut parts = path.rsplit('/').filter(|p| !p.is_empty());
+    let file = parts.next();
+    let album_dir = parts.next().map(String::from);
+    let artist_dir = parts.next().map(String::from);
+    let format = file
+        .and_then(|f| std::path::Path::new(f).extension())
+        .map(|e| e.to_string_lossy().to_uppercase());
+    let album = entry.batch_label.clone().or(album_dir);
+    match &entry.imported_file {
+        Some(file) => (
+            Some(file.artist.clone())
+                .filter(|a| !a.is_empty())
+                .or(artist_dir),
+            Some(file.album.clone()).filter(|a| !a.is_empty()).or(album),
+            format,
+        ),
+        None => (artist_dir, album, format),
+    }
+}
+
+/// History entries with their feedback and note.
+#[cfg(feature = "server")]
+const ENTRY_SELECT: &str = "SELECT h.*, f.feedback, n.note, n.tags AS note_tags FROM download_history h \
+     LEFT JOIN download_feedback f ON f.history_id = h.id \
+     LEFT JOIN download_notes n ON n.user_id = h.user_id AND n.download_id = COALESCE(h.download_id, h.id)";
+
+#[cfg(feature = "server")]
+impl DownloadHistoryRow {
+    /// Record a finished download.
+    pub async fn record(
+        user_id: &str,
+        entry: &DownloadProgress,
+        outcome: &str,
+    ) -> Result<(), String> {
+        let (artist, album, format) = describe(entry);
+        sqlx::query(
+            "INSERT INTO download_history (id, user_id, item, artist, album, format, peer, size, outcome, error, finished_at, imported_path, duplicate, download_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(&entry.item)
+        .bind(artist)
+        .bind(album)
+        .bind(format)
+        .bind(&entry.source)
+        .bind(entry.size as i64)
+        .bind(outcome)
+        .bind(&entry.error)
+        .bind(chrono::Utc::now().timestamp())
+        .bind(entry.imported_file.as_ref().map(|f| &f.path))
+        .bind(entry.imported_file.as_ref().is_some_and(|f| f.duplicate))
+        .bind(&entry.id)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The user's history, newest first. No limit returns all of it.
+    pub async fn get_by_user(
+        user_id: &str,
+        limit: Option<u32>,
+    ) -> Result<Vec<DownloadHistoryEntry>, String> {
+        let rows = sqlx::query_as::<_, DownloadHistoryRow>(&format!(
+            "{} WHERE h.user_id = ? ORDER BY h.finished_at DESC LIMIT ?",
+            ENTRY_SELECT
+        ))
+        .bind(user_id)
+        .bind(limit.map_or(-1, i64::from))
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    /// The user's downloads tagged `tag`, newest first.
+    pub async fn get_by_tag(user_id: &str, tag: &str) -> Result<Vec<DownloadHistoryEntry>, String> {
+        let rows = sqlx::query_as::<_, DownloadHistoryRow>(&format!(
+            "{} WHERE h.user_id = ? \
+             AND EXISTS (SELECT 1 FROM json_each(n.tags) t WHERE t.value = ? COLLATE NOCASE) \
+             ORDER BY h.finished_at DESC",
+            ENTRY_SELECT
+        ))
+        .bind(user_id)
+        .bind(tag)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    pub async fn get(id: &str, user_id: &str) -> Result<Option<Self>, String> {
+        sqlx::query_as::<_, Self>("SELECT * FROM download_history WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .fetch_optional(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Where the user's latest imports of `artist` ended up, newest first.
+    pub async fn imported_paths_of_artist(
+        user_id: &str,
+        artist: &str,
+        limit: u32,
+    ) -> Result<Vec<String>, String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT imported_path FROM download_history WHERE user_id = ? AND outcome = 'imported' AND imported_path IS NOT NULL AND artist = ? COLLATE NOCASE ORDER BY finished_at DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(artist)
+        .bind(limit)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,95 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use shared::download::DownloadNote;
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+
+/// Notes and tags users put on downloads, kept under the transfer's id so
+/// a note written while a file downloads stays on its history entry.
+#[cfg(feature = "server")]
+pub struct DownloadNoteRow;
+
+#[cfg(feature = "server")]
+#[derive(sqlx::FromRow)]
+struct NoteRow {
+    download_id: String,
+    note: String,
+    tags: String,
+}
+
+/// Tags as stored, a JSON array.
+pub fn parse_stored_tags(tags: &str) -> Vec<String> {
+    serde_json::from_str(tags).unwrap_or_default()
+}
+
+#[cfg(feature = "server")]
+impl DownloadNoteRow {
+    /// The user's notes on the given downloads, by download id.
+    pub async fn get_many(
+        user_id: &str,
+        download_ids: &[String],
+    ) -> Result<HashMap<String, DownloadNote>, String> {
+        let ids = serde_json::to_string(download_ids).map_err(|e| e.to_string())?;
+        let rows = sqlx::query_as::<_, NoteRow>(
+            "SELECT download_id, note, tags FROM download_notes \
+             WHERE user_id = ? AND download_id IN (SELECT value FROM json_each(?))",
+        )
+        .bind(user_id)
+        .bind(ids)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows
+            .into_iter()
+            .map(|r| {
+                (
+                    r.download_id,
+                    DownloadNote {
+                        note: r.note,
+                        tags: parse_stored_tags(&r.tags),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Replace the note on a download. An empty one removes it.
+    pub async fn set(user_id: &str, download_id: &str, note: &DownloadNote) -> Result<(), String> {
+        if note.is_empty() {
+            sqlx::query("DELETE FROM download_notes WHERE user_id = ? AND download_id = ?")
+                .bind(user_id)
+                .bind(download_id)
+                .execute(&*DB)
+                .await
+                .map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+        let tags = serde_json::to_string(&note.tags).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT INTO download_notes (user_id, download_id, note, tags, updated_at) VALUES (?, ?, ?, ?, ?) \
+             ON CONFLICT(user_id, download_id) DO UPDATE SET note = excluded.note, tags = excluded.tags, updated_at = excluded.updated_at",
+        )
+        .bind(user_id)
+        .bind(download_id)
+        .bind(note.note.trim())
+        .bind(tags)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Every tag the user has used, sorted.
+    pub async fn tags(user_id: &str) -> Result<Vec<String>, String> {
+        sqlx::query_scalar::<_, String>(
+            "SELECT DISTINCT t.value FROM download_notes n, json_each(n.tags) t \
+             WHERE n.user_id = ? ORDER BY t.value COLLATE NOCASE",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+}
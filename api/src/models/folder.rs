@@ -1,9 +1,13 @@
 #[cfg(feature = "server")]
 use crate::db::DB;
 use serde::{Deserialize, Serialize};
+use shared::download::ImportMode;
 #[cfg(feature = "server")]
 use uuid::Uuid;
 
+/// How long a deleted folder can still be restored before it is purged.
+pub const FOLDER_UNDO_WINDOW_SECS: i64 = 30;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[cfg_attr(feature = "server", derive(sqlx::FromRow))]
 pub struct Folder {
@@ -11,6 +15,27 @@ pub struct Folder {
     pub user_id: String,
     pub name: String,
     pub path: String,
+    /// `ImportMode` as stored, see `Folder::import_mode`
+    #[serde(default)]
+    pub import_mode: String,
+    /// SFTP or rclone target the folder's imports are uploaded to; the local
+    /// path is then only a staging area
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Listed to every user as a download target. Only the owner can change
+    /// or delete it.
+    #[serde(default)]
+    pub shared: bool,
+}
+
+impl Folder {
+    pub fn import_mode(&self) -> ImportMode {
+        ImportMode::from(self.import_mode.as_str())
+    }
+
+    pub fn is_owned_by(&self, user_id: &str) -> bool {
+        self.user_id == user_id
+    }
 }
 
 #[cfg(feature = "server")]
@@ -33,41 +58,146 @@ impl Folder {
     }
 
     pub async fn get_all_by_user(user_id: &str) -> Result<Vec<Folder>, String> {
-        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE user_id = ?")
+        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE user_id = ? AND deleted_at IS NULL")
             .bind(user_id)
             .fetch_all(&*DB)
             .await
             .map_err(|e| e.to_string())
     }
 
-    pub async fn update(id: &str, name: &str, path: &str) -> Result<(), String> {
-        sqlx::query("UPDATE folders SET name = ?, path = ? WHERE id = ?")
-            .bind(name)
-            .bind(path)
-            .bind(id)
-            .execute(&*DB)
-            .await
-            .map_err(|e| e.to_string())?;
+    /// The user's own folders and the ones other users share.
+    pub async fn get_visible_to(user_id: &str) -> Result<Vec<Folder>, String> {
+        sqlx::query_as::<_, Folder>(
+            "SELECT * FROM folders WHERE (user_id = ? OR shared = 1) AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Update one of the user's folders.
+    pub async fn update(
+        id: &str,
+        user_id: &str,
+        name: &str,
+        path: &str,
+        import_mode: ImportMode,
+        remote: Option<&str>,
+        shared: bool,
+    ) -> Result<(), String> {
+        let result = sqlx::query(
+            "UPDATE folders SET name = ?, path = ?, import_mode = ?, remote = ?, shared = ? \
+             WHERE id = ? AND user_id = ?",
+        )
+        .bind(name)
+        .bind(path)
+        .bind(import_mode.as_str())
+        .bind(remote)
+        .bind(shared)
+        .bind(id)
+        .bind(user_id)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err("Folder not found".to_string());
+        }
         Ok(())
     }
 
-    pub async fn delete(id: &str) -> Result<(), String> {
-        sqlx::query("DELETE FROM folders WHERE id = ?")
-            .bind(id)
-            .execute(&*DB)
-            .await
-            .map_err(|e| e.to_string())?;
+    /// Mark one of the user's folders as deleted. It disappears from
+    /// listings immediately but can be restored until `purge_deleted`
+    /// removes it for good.
+    pub async fn soft_delete(id: &str, user_id: &str) -> Result<(), String> {
+        let result = sqlx::query(
+            "UPDATE folders SET deleted_at = strftime('%s', 'now') \
+             WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
+        )
+        .bind(id)
+        .bind(user_id)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err("Folder not found".to_string());
+        }
+        Ok(())
+    }
+
+    /// Undo a soft delete. Fails once the undo window has elapsed.
+    pub async fn restore(id: &str, user_id: &str) -> Result<(), String> {
+        let result = sqlx::query(
+            "UPDATE folders SET deleted_at = NULL \
+             WHERE id = ? AND user_id = ? AND deleted_at IS NOT NULL AND deleted_at >= strftime('%s', 'now') - ?",
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(FOLDER_UNDO_WINDOW_SECS)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err("Folder can no longer be restored".to_string());
+        }
         Ok(())
     }
 
+    /// Permanently remove folders whose undo window has expired.
+    pub async fn purge_deleted() -> Result<u64, String> {
+        let result = sqlx::query(
+            "DELETE FROM folders WHERE deleted_at IS NOT NULL AND deleted_at < strftime('%s', 'now') - ?",
+        )
+        .bind(FOLDER_UNDO_WINDOW_SECS)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn get_by_id(id: &str) -> Result<Option<Folder>, String> {
-        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ?")
+        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE id = ? AND deleted_at IS NULL")
             .bind(id)
             .fetch_optional(&*DB)
             .await
             .map_err(|e| e.to_string())
     }
 
+    /// The folder at `path` among the ones the user can see, preferring
+    /// their own.
+    pub async fn find_by_path(user_id: &str, path: &str) -> Option<Folder> {
+        let target = std::path::Path::new(path);
+        let mut folders: Vec<Folder> = Self::get_visible_to(user_id)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|f| std::path::Path::new(&f.path) == target)
+            .collect();
+        folders.sort_by_key(|f| !f.is_owned_by(user_id));
+        folders.into_iter().next()
+    }
+
+    /// Whether `path` is inside a folder the user can see. Both sides are
+    /// resolved first, so a symlink inside a folder can't reach past it.
+    pub async fn is_visible_path(user_id: &str, path: &std::path::Path) -> Result<bool, String> {
+        let escapes = path
+            .components()
+            .any(|c| matches!(c, std::path::Component::ParentDir));
+        if escapes || !path.is_absolute() {
+            return Ok(false);
+        }
+        let Some(path) = resolve_path(path).await else {
+            return Ok(false);
+        };
+        for folder in Self::get_visible_to(user_id).await? {
+            let resolved = resolve_path(std::path::Path::new(&folder.path)).await;
+            if resolved.is_some_and(|root| path.starts_with(root)) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
     pub fn discovery_path(&self) -> String {
         format!("{}/Discovery", self.path)
     }
@@ -82,3 +212,19 @@ impl Folder {
         Ok(settings.discovery_enabled && settings.discovery_folder_id.as_deref() == Some(&self.id))
     }
 }
+
+/// `path` with its closest existing parent canonicalized and the rest joined
+/// back on, so symlinks are followed even when the path doesn't exist yet.
+#[cfg(feature = "server")]
+pub async fn resolve_path(path: &std::path::Path) -> Option<std::path::PathBuf> {
+    let mut existing = path;
+    loop {
+        if let Ok(resolved) = tokio::fs::canonicalize(existing).await {
+            let rest = path
+                .strip_prefix(existing)
+                .unwrap_or(std::path::Path::new(""));
+            return Some(resolved.join(rest));
+        }
+        existing = existing.parent()?;
+    }
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// Runs kept in the import log; older ones are dropped as new ones come in.
+#[cfg(feature = "server")]
+const MAX_RUNS_KEPT: i64 = 200;
+
+/// One run of a post-import hook, for the import log.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct ImportHookRun {
+    pub id: String,
+    pub hook_id: String,
+    pub hook_name: String,
+    pub album_path: String,
+    pub success: bool,
+    /// Exit code, HTTP status or why the hook didn't finish
+    pub status: String,
+    pub output: String,
+    pub duration_ms: i64,
+    pub ran_at: i64,
+}
+
+#[cfg(feature = "server")]
+impl ImportHookRun {
+    pub async fn record(
+        hook: &shared::hooks::ImportHook,
+        album_path: &str,
+        outcome: &soulbeet::hooks::HookOutcome,
+        duration_ms: i64,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO import_hook_runs (id, hook_id, hook_name, album_path, success, status, output, duration_ms, ran_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(&hook.id)
+        .bind(&hook.name)
+        .bind(album_path)
+        .bind(outcome.success)
+        .bind(&outcome.status)
+        .bind(&outcome.output)
+        .bind(duration_ms)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            "DELETE FROM import_hook_runs WHERE id NOT IN (SELECT id FROM import_hook_runs ORDER BY ran_at DESC LIMIT ?)",
+        )
+        .bind(MAX_RUNS_KEPT)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The latest runs, newest first.
+    pub async fn recent(limit: u32) -> Result<Vec<Self>, String> {
+        sqlx::query_as::<_, Self>("SELECT * FROM import_hook_runs ORDER BY ran_at DESC LIMIT ?")
+            .bind(limit)
+            .fetch_all(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,129 @@
+#[cfg(feature = "server")]
+use super::folder::Folder;
+#[cfg(feature = "server")]
+use super::token::{hash_token, new_token};
+#[cfg(feature = "server")]
+use super::user::User;
+#[cfg(feature = "server")]
+use crate::db::DB;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// Time-limited signup link handed out by an existing user. The invitee
+/// picks their own username and password; the folder, if any, is created
+/// for them on signup. Only a hash of the token is stored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct Invite {
+    pub id: String,
+    pub created_by: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub folder_name: Option<String>,
+    pub folder_path: Option<String>,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub used_at: Option<i64>,
+    /// Username of whoever signed up with it
+    pub used_by: Option<String>,
+}
+
+impl Invite {
+    pub fn is_pending(&self, now: i64) -> bool {
+        self.used_at.is_none() && self.expires_at > now
+    }
+}
+
+#[cfg(feature = "server")]
+impl Invite {
+    /// Create an invite valid for `valid_for` seconds. Returns the stored row
+    /// and the plaintext token, which is never retrievable again.
+    pub async fn create(
+        created_by: &str,
+        valid_for: i64,
+        folder: Option<(&str, &str)>,
+    ) -> Result<(Invite, String), String> {
+        let token = new_token(32);
+
+        let invite = sqlx::query_as::<_, Invite>(
+            "INSERT INTO invites (id, created_by, token_hash, folder_name, folder_path, expires_at) VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(created_by)
+        .bind(hash_token(&token))
+        .bind(folder.map(|(name, _)| name))
+        .bind(folder.map(|(_, path)| path))
+        .bind(chrono::Utc::now().timestamp() + valid_for)
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok((invite, token))
+    }
+
+    pub async fn get_all() -> Result<Vec<Invite>, String> {
+        sqlx::query_as::<_, Invite>("SELECT * FROM invites ORDER BY created_at DESC")
+            .fetch_all(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete(id: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM invites WHERE id = ?")
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The invite for `token`, if it is unused and hasn't expired.
+    pub async fn find_pending(token: &str) -> Result<Option<Invite>, String> {
+        sqlx::query_as::<_, Invite>(
+            "SELECT * FROM invites WHERE token_hash = ? AND used_at IS NULL AND expires_at > ?",
+        )
+        .bind(hash_token(token))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Create the account an invite is for, with its folder. The invite is
+    /// claimed first so two signups can't share it, and released again if
+    /// the account can't be created (e.g. the username is taken).
+    pub async fn redeem(token: &str, username: &str, password: &str) -> Result<User, String> {
+        let invite = sqlx::query_as::<_, Invite>(
+            "UPDATE invites SET used_at = ?, used_by = ? WHERE token_hash = ? AND used_at IS NULL AND expires_at > ? RETURNING *",
+        )
+        .bind(chrono::Utc::now().timestamp())
+        .bind(username)
+        .bind(hash_token(token))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&*DB)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("This invite link is invalid, used or expired")?;
+
+        let user = match User::create(username, password).await {
+            Ok(user) => user,
+            Err(e) => {
+                let _ =
+                    sqlx::query("UPDATE invites SET used_at = NULL, used_by = NULL WHERE id = ?")
+                        .bind(&invite.id)
+                        .execute(&*DB)
+                        .await;
+                return Err(e);
+            }
+        };
+
+        if let (Some(name), Some(path)) = (&invite.folder_name, &invite.folder_path) {
+            if let Err(e) = tokio::fs::create_dir_all(path).await {
+                tracing::warn!("Could not create invited folder {}: {}", path, e);
+            }
+            Folder::create(&user.id, name, path).await?;
+        }
+        Ok(user)
+    }
+}
@@ -0,0 +1,93 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use shared::analytics::{MatchingReport, RankOutcome};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// A search result picked for download, with where it ranked. Who picked it
+/// is not kept.
+#[cfg(feature = "server")]
+pub struct MatchPick<'a> {
+    pub peer: &'a str,
+    pub item: &'a str,
+    /// 0 for the top candidate
+    pub rank: usize,
+    pub score: f64,
+    pub top_score: f64,
+    pub automatic: bool,
+}
+
+#[cfg(feature = "server")]
+#[derive(sqlx::FromRow)]
+struct RankRow {
+    bucket: i64,
+    automatic: bool,
+    picks: i64,
+    imported: i64,
+    failed: i64,
+    score_gap: f64,
+}
+
+#[cfg(feature = "server")]
+impl MatchPick<'_> {
+    pub async fn record(&self) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO match_picks (id, peer, item, rank, score, top_score, automatic, picked_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(self.peer)
+        .bind(self.item)
+        .bind(self.rank as i64)
+        .bind(self.score)
+        .bind(self.top_score)
+        .bind(self.automatic)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Note how the pending picks of a file ended.
+    pub async fn set_outcome(peer: &str, item: &str, outcome: &str) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE match_picks SET outcome = ? WHERE peer = ? AND item = ? AND outcome IS NULL",
+        )
+        .bind(outcome)
+        .bind(peer)
+        .bind(item)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Picks and outcomes per rank, lower ranks counted together.
+    pub async fn report() -> Result<MatchingReport, String> {
+        let rows = sqlx::query_as::<_, RankRow>(
+            "SELECT MIN(rank, ?) AS bucket, automatic, COUNT(*) AS picks, \
+             COALESCE(SUM(outcome = 'imported'), 0) AS imported, \
+             COALESCE(SUM(outcome = 'failed'), 0) AS failed, \
+             COALESCE(AVG(top_score - score), 0.0) AS score_gap \
+             FROM match_picks GROUP BY automatic, bucket ORDER BY automatic, bucket",
+        )
+        .bind(MatchingReport::LAST_RANK as i64)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(MatchingReport {
+            ranks: rows
+                .into_iter()
+                .map(|r| RankOutcome {
+                    rank: r.bucket.max(0) as u32,
+                    automatic: r.automatic,
+                    picks: r.picks.max(0) as u64,
+                    imported: r.imported.max(0) as u64,
+                    failed: r.failed.max(0) as u64,
+                    mean_score_gap: r.score_gap,
+                })
+                .collect(),
+        })
+    }
+}
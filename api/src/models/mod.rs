@@ -1,10 +1,25 @@
+pub mod api_token;
 pub mod app_config;
+pub mod bandwidth_usage;
 pub mod deletion_review;
 pub mod discovery_candidate;
 pub mod discovery_history;
 pub mod discovery_playlist;
+pub mod download_feedback;
+pub mod download_history;
+pub mod download_note;
 pub mod engine_report;
 pub mod folder;
+pub mod import_hook_run;
+pub mod invite;
+pub mod match_pick;
+pub mod party;
+pub mod password_reset;
+pub mod release_action;
+pub mod search_history;
+pub mod token;
 pub mod user;
 pub mod user_profile;
 pub mod user_settings;
+pub mod watched_artist;
+pub mod wishlist;
@@ -0,0 +1,248 @@
+#[cfg(feature = "server")]
+use super::token::{hash_token, new_token};
+#[cfg(feature = "server")]
+use crate::db::DB;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// A party a user hosts: guests with the link, who need no account, ask
+/// for tracks, and the host picks which get downloaded into the party
+/// folder. Only a hash of the link's token is stored.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct Party {
+    pub id: String,
+    pub host_id: String,
+    pub name: String,
+    #[serde(skip)]
+    pub token_hash: String,
+    pub folder_id: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+impl Party {
+    pub fn is_open(&self, now: i64) -> bool {
+        self.expires_at > now
+    }
+}
+
+/// Where a guest's request stands.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PartyRequestStatus {
+    Pending,
+    /// Approved and handed to auto-download
+    Approved,
+    Rejected,
+    /// Approved, but the download could not be started
+    Failed,
+}
+
+impl PartyRequestStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Approved => "approved",
+            Self::Rejected => "rejected",
+            Self::Failed => "failed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "approved" => Self::Approved,
+            "rejected" => Self::Rejected,
+            "failed" => Self::Failed,
+            _ => Self::Pending,
+        }
+    }
+}
+
+/// A track a guest asked for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct PartyRequest {
+    pub id: String,
+    pub party_id: String,
+    pub guest_name: String,
+    pub artist: String,
+    pub title: String,
+    /// See [`PartyRequestStatus`]
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: i64,
+}
+
+impl PartyRequest {
+    pub fn status(&self) -> PartyRequestStatus {
+        PartyRequestStatus::parse(&self.status)
+    }
+}
+
+#[cfg(feature = "server")]
+impl Party {
+    /// Start a party lasting `valid_for` seconds. Returns it with the
+    /// plaintext token of its link, which is never retrievable again.
+    pub async fn create(
+        host_id: &str,
+        name: &str,
+        folder_id: &str,
+        valid_for: i64,
+    ) -> Result<(Party, String), String> {
+        let token = new_token(32);
+        let party = sqlx::query_as::<_, Party>(
+            "INSERT INTO parties (id, host_id, name, token_hash, folder_id, expires_at) VALUES (?, ?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(host_id)
+        .bind(name)
+        .bind(hash_token(&token))
+        .bind(folder_id)
+        .bind(chrono::Utc::now().timestamp() + valid_for)
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok((party, token))
+    }
+
+    /// Give the party a new link. The old one stops working.
+    pub async fn rotate_token(id: &str, host_id: &str) -> Result<String, String> {
+        let token = new_token(32);
+        let result = sqlx::query("UPDATE parties SET token_hash = ? WHERE id = ? AND host_id = ?")
+            .bind(hash_token(&token))
+            .bind(id)
+            .bind(host_id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        if result.rows_affected() == 0 {
+            return Err("Party not found".to_string());
+        }
+        Ok(token)
+    }
+
+    /// The host's parties that haven't ended, newest first.
+    pub async fn get_open_by_host(host_id: &str) -> Result<Vec<Party>, String> {
+        sqlx::query_as::<_, Party>(
+            "SELECT * FROM parties WHERE host_id = ? AND expires_at > ? ORDER BY created_at DESC",
+        )
+        .bind(host_id)
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn get(id: &str, host_id: &str) -> Result<Option<Party>, String> {
+        sqlx::query_as::<_, Party>("SELECT * FROM parties WHERE id = ? AND host_id = ?")
+            .bind(id)
+            .bind(host_id)
+            .fetch_optional(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The party of a link, if it hasn't ended.
+    pub async fn find_open(token: &str) -> Result<Option<Party>, String> {
+        sqlx::query_as::<_, Party>("SELECT * FROM parties WHERE token_hash = ? AND expires_at > ?")
+            .bind(hash_token(token))
+            .bind(chrono::Utc::now().timestamp())
+            .fetch_optional(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// End a party now. Its requests go with it.
+    pub async fn delete(id: &str, host_id: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM parties WHERE id = ? AND host_id = ?")
+            .bind(id)
+            .bind(host_id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Drop parties that ended more than a day ago, with their requests.
+    pub async fn delete_ended() -> Result<u64, String> {
+        let result = sqlx::query("DELETE FROM parties WHERE expires_at < ?")
+            .bind(chrono::Utc::now().timestamp() - 24 * 3600)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(feature = "server")]
+impl PartyRequest {
+    pub async fn create(
+        party_id: &str,
+        guest_name: &str,
+        artist: &str,
+        title: &str,
+    ) -> Result<PartyRequest, String> {
+        sqlx::query_as::<_, PartyRequest>(
+            "INSERT INTO party_requests (id, party_id, guest_name, artist, title) VALUES (?, ?, ?, ?, ?) RETURNING *",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(party_id)
+        .bind(guest_name)
+        .bind(artist)
+        .bind(title)
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// The party's requests, oldest first.
+    pub async fn get_by_party(party_id: &str) -> Result<Vec<PartyRequest>, String> {
+        sqlx::query_as::<_, PartyRequest>(
+            "SELECT * FROM party_requests WHERE party_id = ? ORDER BY created_at, rowid",
+        )
+        .bind(party_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn count_pending(party_id: &str) -> Result<i64, String> {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM party_requests WHERE party_id = ? AND status = 'pending'",
+        )
+        .bind(party_id)
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Move a pending request to `status`. Returns it, or None if it was
+    /// already decided.
+    pub async fn decide(
+        id: &str,
+        party_id: &str,
+        status: PartyRequestStatus,
+    ) -> Result<Option<PartyRequest>, String> {
+        sqlx::query_as::<_, PartyRequest>(
+            "UPDATE party_requests SET status = ? WHERE id = ? AND party_id = ? AND status = 'pending' RETURNING *",
+        )
+        .bind(status.as_str())
+        .bind(id)
+        .bind(party_id)
+        .fetch_optional(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_failed(id: &str, error: &str) -> Result<(), String> {
+        sqlx::query("UPDATE party_requests SET status = 'failed', error = ? WHERE id = ?")
+            .bind(error)
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,172 @@
+#[cfg(feature = "server")]
+use super::token::{hash_token, new_token};
+#[cfg(feature = "server")]
+use super::user::User;
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// How long a reset link works, in seconds.
+#[cfg(feature = "server")]
+pub const RESET_VALIDITY: i64 = 24 * 3600;
+
+/// Single-use link letting a user choose a new password. Only a hash of the
+/// token is stored. A new link doesn't cancel the user's pending ones, so
+/// asking for resets in someone's name can't break the link they were sent;
+/// using any of them cancels the rest.
+#[cfg(feature = "server")]
+#[derive(Clone, Debug, sqlx::FromRow)]
+pub struct PasswordReset {
+    pub id: String,
+    pub user_id: String,
+    pub token_hash: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+}
+
+#[cfg(feature = "server")]
+impl PasswordReset {
+    /// Add a reset for the user, clearing their expired ones. Returns the
+    /// plaintext token, which is never retrievable again.
+    pub async fn create(user_id: &str) -> Result<String, String> {
+        let token = new_token(32);
+
+        sqlx::query("DELETE FROM password_resets WHERE user_id = ? AND expires_at <= ?")
+            .bind(user_id)
+            .bind(chrono::Utc::now().timestamp())
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT INTO password_resets (id, user_id, token_hash, expires_at) VALUES (?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(hash_token(&token))
+        .bind(chrono::Utc::now().timestamp() + RESET_VALIDITY)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(token)
+    }
+
+    /// The reset for `token`, if it hasn't expired.
+    pub async fn find_pending(token: &str) -> Result<Option<PasswordReset>, String> {
+        sqlx::query_as::<_, PasswordReset>(
+            "SELECT * FROM password_resets WHERE token_hash = ? AND expires_at > ?",
+        )
+        .bind(hash_token(token))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Set the new password and use up the token, with the user's other
+    /// resets. Failed sign-ins are forgotten and any lockout lifted, since
+    /// whoever locked the account out no longer knows the password.
+    pub async fn complete(token: &str, password: &str) -> Result<(), String> {
+        let password_hash = User::hash_password(password)?;
+        let mut tx = DB.begin().await.map_err(|e| e.to_string())?;
+        Self::complete_in(&mut tx, token, &password_hash).await?;
+        tx.commit().await.map_err(|e| e.to_string())
+    }
+
+    /// The writes of `complete`, within the caller's transaction.
+    async fn complete_in(
+        conn: &mut sqlx::SqliteConnection,
+        token: &str,
+        password_hash: &str,
+    ) -> Result<(), String> {
+        let reset = sqlx::query_as::<_, PasswordReset>(
+            "DELETE FROM password_resets WHERE token_hash = ? AND expires_at > ? RETURNING *",
+        )
+        .bind(hash_token(token))
+        .bind(chrono::Utc::now().timestamp())
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or("This reset link is invalid, used or expired")?;
+
+        sqlx::query(
+            "UPDATE users SET password_hash = ?, failed_logins = 0, locked_until = NULL WHERE id = ?",
+        )
+        .bind(password_hash)
+        .bind(&reset.user_id)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| e.to_string())?;
+        sqlx::query("DELETE FROM password_resets WHERE user_id = ?")
+            .bind(&reset.user_id)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completing_a_reset_lifts_the_lockout() {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(async {
+                let pool = sqlx::sqlite::SqlitePoolOptions::new()
+                    .max_connections(1)
+                    .connect("sqlite::memory:")
+                    .await
+                    .unwrap();
+                sqlx::migrate!("./migrations").run(&pool).await.unwrap();
+
+                let now = chrono::Utc::now().timestamp();
+                sqlx::query(
+                    "INSERT INTO users (id, username, password_hash, failed_logins, locked_until) \
+                     VALUES ('u1', 'alice', 'old', 4, ?)",
+                )
+                .bind(now + 900)
+                .execute(&pool)
+                .await
+                .unwrap();
+                for token in ["used", "other"] {
+                    sqlx::query(
+                        "INSERT INTO password_resets (id, user_id, token_hash, expires_at) \
+                         VALUES (?, 'u1', ?, ?)",
+                    )
+                    .bind(token)
+                    .bind(hash_token(token))
+                    .bind(now + RESET_VALIDITY)
+                    .execute(&pool)
+                    .await
+                    .unwrap();
+                }
+
+                let mut tx = pool.begin().await.unwrap();
+                PasswordReset::complete_in(&mut tx, "used", "new")
+                    .await
+                    .unwrap();
+                tx.commit().await.unwrap();
+
+                let (hash, failed, locked): (String, i64, Option<i64>) = sqlx::query_as(
+                    "SELECT password_hash, failed_logins, locked_until FROM users WHERE id = 'u1'",
+                )
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+                assert_eq!(hash, "new");
+                assert_eq!(failed, 0);
+                assert_eq!(locked, None);
+                let left: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM password_resets")
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+                assert_eq!(left, 0);
+            });
+    }
+}
@@ -0,0 +1,59 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+use serde::{Deserialize, Serialize};
+
+/// A user's decision on a release calendar entry.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct ReleaseActionRecord {
+    pub user_id: String,
+    pub release_group_mbid: String,
+    /// "ignore" or "remind"
+    pub action: String,
+    /// Unix timestamp after which a "remind" entry shows up again
+    pub remind_at: Option<i64>,
+    pub created_at: i64,
+}
+
+#[cfg(feature = "server")]
+impl ReleaseActionRecord {
+    pub async fn get_all_by_user(user_id: &str) -> Result<Vec<ReleaseActionRecord>, String> {
+        sqlx::query_as::<_, ReleaseActionRecord>("SELECT * FROM release_actions WHERE user_id = ?")
+            .bind(user_id)
+            .fetch_all(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set(
+        user_id: &str,
+        release_group_mbid: &str,
+        action: &str,
+        remind_at: Option<i64>,
+    ) -> Result<(), String> {
+        sqlx::query(
+            "INSERT INTO release_actions (user_id, release_group_mbid, action, remind_at) VALUES (?, ?, ?, ?)
+             ON CONFLICT(user_id, release_group_mbid) DO UPDATE SET
+                action = excluded.action,
+                remind_at = excluded.remind_at",
+        )
+        .bind(user_id)
+        .bind(release_group_mbid)
+        .bind(action)
+        .bind(remind_at)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn clear(user_id: &str, release_group_mbid: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM release_actions WHERE user_id = ? AND release_group_mbid = ?")
+            .bind(user_id)
+            .bind(release_group_mbid)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
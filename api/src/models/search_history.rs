@@ -0,0 +1,157 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use shared::download::{DownloadQuery, SearchRecord, SearchResult, SearchStrategy, StrategyStats};
+
+/// Searches kept per user; older ones are dropped as new ones start.
+#[cfg(feature = "server")]
+const MAX_SEARCHES_KEPT: i64 = 500;
+
+#[cfg(feature = "server")]
+#[derive(sqlx::FromRow)]
+struct SearchRecordRow {
+    id: String,
+    query: String,
+    description: String,
+    strategy: String,
+    search_text: Option<String>,
+    timeout_secs: i64,
+    responses: i64,
+    candidates: i64,
+    automatic: bool,
+    started_at: i64,
+}
+
+#[cfg(feature = "server")]
+impl SearchRecordRow {
+    /// None for a row whose query no longer parses.
+    fn into_record(self) -> Option<SearchRecord> {
+        Some(SearchRecord {
+            query: serde_json::from_str(&self.query).ok()?,
+            id: self.id,
+            description: self.description,
+            strategy: SearchStrategy::from_setting(&self.strategy).unwrap_or_default(),
+            search_text: self.search_text,
+            timeout_secs: self.timeout_secs.clamp(0, i64::from(u32::MAX)) as u32,
+            responses: self.responses.clamp(0, i64::from(u32::MAX)) as u32,
+            candidates: self.candidates.clamp(0, i64::from(u32::MAX)) as u32,
+            automatic: self.automatic,
+            started_at: self.started_at,
+        })
+    }
+}
+
+#[cfg(feature = "server")]
+#[derive(sqlx::FromRow)]
+struct StrategyRow {
+    strategy: String,
+    searches: i64,
+    with_candidates: i64,
+    mean_responses: f64,
+}
+
+/// The searches users ran from this server, per user.
+#[cfg(feature = "server")]
+pub struct SearchHistoryRow;
+
+#[cfg(feature = "server")]
+impl SearchHistoryRow {
+    /// Record a search that just started.
+    pub async fn start(
+        search_id: &str,
+        user_id: &str,
+        query: &DownloadQuery,
+        description: &str,
+        timeout_secs: u32,
+        automatic: bool,
+    ) -> Result<(), String> {
+        let query_json = serde_json::to_string(query).map_err(|e| e.to_string())?;
+        sqlx::query(
+            "INSERT OR REPLACE INTO search_history (id, user_id, query, description, strategy, timeout_secs, automatic, started_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(search_id)
+        .bind(user_id)
+        .bind(query_json)
+        .bind(description)
+        .bind(query.search_strategy.to_setting())
+        .bind(i64::from(timeout_secs))
+        .bind(automatic)
+        .bind(chrono::Utc::now().timestamp())
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            "DELETE FROM search_history WHERE user_id = ? AND id NOT IN \
+             (SELECT id FROM search_history WHERE user_id = ? ORDER BY started_at DESC LIMIT ?)",
+        )
+        .bind(user_id)
+        .bind(user_id)
+        .bind(MAX_SEARCHES_KEPT)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Note what a poll of the search returned. A poll reporting no text
+    /// keeps the one already noted.
+    pub async fn update(result: &SearchResult) -> Result<(), String> {
+        sqlx::query(
+            "UPDATE search_history SET responses = ?, candidates = ?, \
+             search_text = COALESCE(?, search_text) WHERE id = ?",
+        )
+        .bind(i64::from(result.responses))
+        .bind(result.groups.len() as i64)
+        .bind(&result.search_text)
+        .bind(&result.search_id)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// The user's latest searches, newest first.
+    pub async fn recent(user_id: &str, limit: u32) -> Result<Vec<SearchRecord>, String> {
+        let rows = sqlx::query_as::<_, SearchRecordRow>(
+            "SELECT id, query, description, strategy, search_text, timeout_secs, responses, \
+             candidates, automatic, started_at FROM search_history \
+             WHERE user_id = ? ORDER BY started_at DESC LIMIT ?",
+        )
+        .bind(user_id)
+        .bind(i64::from(limit))
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows
+            .into_iter()
+            .filter_map(SearchRecordRow::into_record)
+            .collect())
+    }
+
+    /// Searches and candidates per strategy over all the user's kept
+    /// searches.
+    pub async fn strategy_stats(user_id: &str) -> Result<Vec<StrategyStats>, String> {
+        let rows = sqlx::query_as::<_, StrategyRow>(
+            "SELECT strategy, COUNT(*) AS searches, \
+             COALESCE(SUM(candidates > 0), 0) AS with_candidates, \
+             COALESCE(AVG(responses), 0.0) AS mean_responses \
+             FROM search_history WHERE user_id = ? GROUP BY strategy ORDER BY searches DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|r| {
+                Some(StrategyStats {
+                    strategy: SearchStrategy::from_setting(&r.strategy)?,
+                    searches: r.searches.clamp(0, i64::from(u32::MAX)) as u32,
+                    with_candidates: r.with_candidates.clamp(0, i64::from(u32::MAX)) as u32,
+                    mean_responses: r.mean_responses,
+                })
+            })
+            .collect())
+    }
+}
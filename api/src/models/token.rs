@@ -0,0 +1,24 @@
+//! Secrets handed out once: API tokens and the invite, password reset and
+//! party links. Only their hash is stored.
+
+#[cfg(feature = "server")]
+use rand::Rng;
+#[cfg(feature = "server")]
+use sha2::{Digest, Sha256};
+
+/// A random alphanumeric secret of `len` characters.
+#[cfg(feature = "server")]
+pub fn new_token(len: usize) -> String {
+    rand::rng()
+        .sample_iter(rand::distr::Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// What is stored in place of a token: its SHA-256, in hex.
+#[cfg(feature = "server")]
+pub fn hash_token(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
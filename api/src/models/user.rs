@@ -8,6 +8,7 @@ use argon2::{
     Argon2,
 };
 use serde::{Deserialize, Serialize};
+use shared::security::UserRole;
 #[cfg(feature = "server")]
 use uuid::Uuid;
 
@@ -20,17 +21,43 @@ pub struct User {
     pub password_hash: String,
     pub navidrome_token: Option<String>,
     pub navidrome_status: String,
+    /// Where password reset links are sent, when mail is set up
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Wrong passwords in a row since the last successful sign-in
+    #[serde(default)]
+    pub failed_logins: i64,
+    /// Unix timestamp until which sign-ins are refused
+    #[serde(default)]
+    pub locked_until: Option<i64>,
+    #[serde(default)]
+    pub role: String,
+}
+
+impl User {
+    /// Whether the account is locked at `now`.
+    pub fn is_locked(&self, now: i64) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+
+    pub fn role(&self) -> UserRole {
+        UserRole::from(self.role.as_str())
+    }
 }
 
 #[cfg(feature = "server")]
 impl User {
-    pub async fn create(username: &str, password: &str) -> Result<User, String> {
+    /// Hash a password to store, salted.
+    pub fn hash_password(password: &str) -> Result<String, String> {
         let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
+        Ok(Argon2::default()
             .hash_password(password.as_bytes(), &salt)
             .map_err(|e| e.to_string())?
-            .to_string();
+            .to_string())
+    }
+
+    pub async fn create(username: &str, password: &str) -> Result<User, String> {
+        let password_hash = Self::hash_password(password)?;
 
         let id = Uuid::new_v4().to_string();
 
@@ -65,13 +92,21 @@ impl User {
     }
 
     pub async fn get_folders(&self) -> Result<Vec<Folder>, String> {
-        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE user_id = ?")
+        sqlx::query_as::<_, Folder>("SELECT * FROM folders WHERE user_id = ? AND deleted_at IS NULL")
             .bind(&self.id)
             .fetch_all(&*DB)
             .await
             .map_err(|e| e.to_string())
     }
 
+    /// Whether any account exists yet.
+    pub async fn any_exist() -> Result<bool, String> {
+        sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM users)")
+            .fetch_one(&*DB)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn get_all() -> Result<Vec<User>, String> {
         sqlx::query_as::<_, User>("SELECT * FROM users")
             .fetch_all(&*DB)
@@ -91,12 +126,7 @@ impl User {
     }
 
     pub async fn update_password(id: &str, password: &str) -> Result<(), String> {
-        let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
-        let password_hash = argon2
-            .hash_password(password.as_bytes(), &salt)
-            .map_err(|e| e.to_string())?
-            .to_string();
+        let password_hash = Self::hash_password(password)?;
 
         sqlx::query("UPDATE users SET password_hash = ? WHERE id = ?")
             .bind(password_hash)
@@ -107,6 +137,64 @@ impl User {
         Ok(())
     }
 
+    pub async fn update_email(id: &str, email: Option<&str>) -> Result<(), String> {
+        sqlx::query("UPDATE users SET email = ? WHERE id = ?")
+            .bind(email)
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Count a wrong password, locking the account for `lock_secs` once
+    /// `max_attempts` are reached in a row. Returns whether it got locked.
+    pub async fn record_failed_login(
+        id: &str,
+        max_attempts: u32,
+        lock_secs: i64,
+    ) -> Result<bool, String> {
+        let failed: i64 = sqlx::query_scalar(
+            "UPDATE users SET failed_logins = failed_logins + 1 WHERE id = ? RETURNING failed_logins",
+        )
+        .bind(id)
+        .fetch_one(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        if failed < i64::from(max_attempts) {
+            return Ok(false);
+        }
+
+        let until = chrono::Utc::now().timestamp() + lock_secs;
+        sqlx::query("UPDATE users SET failed_logins = 0, locked_until = ? WHERE id = ?")
+            .bind(until)
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    /// Forget failed sign-ins and lift any lock.
+    pub async fn unlock(id: &str) -> Result<(), String> {
+        sqlx::query("UPDATE users SET failed_logins = 0, locked_until = NULL WHERE id = ?")
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn update_role(id: &str, role: UserRole) -> Result<(), String> {
+        sqlx::query("UPDATE users SET role = ? WHERE id = ?")
+            .bind(role.as_str())
+            .bind(id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     pub async fn update_username(id: &str, new_username: &str) -> Result<(), String> {
         sqlx::query("UPDATE users SET username = ? WHERE id = ?")
             .bind(new_username)
@@ -1,6 +1,9 @@
 #[cfg(feature = "server")]
 use crate::db::DB;
 use serde::{Deserialize, Serialize};
+use shared::download::RenameRules;
+use shared::quality::QualityProfile;
+use shared::routing::RoutingRules;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
 #[cfg_attr(feature = "server", derive(sqlx::FromRow))]
@@ -24,6 +27,24 @@ pub struct UserSettings {
     pub discovery_navidrome_playlist_id: Option<String>,
     pub discovery_last_generated_at: Option<String>,
     pub default_download_folder_id: Option<String>,
+    /// Source matching mode: "strict", "normal", "loose" or a custom score
+    pub match_mode: Option<String>,
+    /// JSON-encoded `QualityProfile`
+    pub quality_profile: Option<String>,
+    /// JSON-encoded `RenameRules`
+    pub rename_rules: Option<String>,
+    /// Discogs personal access token
+    pub discogs_token: Option<String>,
+    /// Discogs account the token belongs to
+    pub discogs_username: Option<String>,
+    /// Add imported albums to the Discogs collection
+    pub discogs_sync_collection: bool,
+    /// Default Soulseek search timeout in seconds
+    pub search_timeout_secs: Option<u32>,
+    /// Open on the dashboard rather than the bare search
+    pub home_dashboard: bool,
+    /// JSON-encoded `RoutingRules`
+    pub routing_rules: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, Default)]
@@ -60,6 +81,37 @@ pub struct UpdateUserSettings {
     pub discovery_playlist_name: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub default_download_folder_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub match_mode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quality_profile: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rename_rules: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discogs_sync_collection: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_timeout_secs: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_dashboard: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routing_rules: Option<String>,
+}
+
+impl UserSettings {
+    /// The stored quality profile, or the defaults.
+    pub fn quality_profile(&self) -> QualityProfile {
+        QualityProfile::from_json(self.quality_profile.as_deref())
+    }
+
+    /// The stored pre-import rename rules, or the defaults (disabled).
+    pub fn rename_rules(&self) -> RenameRules {
+        RenameRules::from_json(self.rename_rules.as_deref())
+    }
+
+    /// The stored genre routing rules, or none.
+    pub fn routing_rules(&self) -> RoutingRules {
+        RoutingRules::from_json(self.routing_rules.as_deref())
+    }
 }
 
 #[cfg(feature = "server")]
@@ -93,6 +145,15 @@ impl UserSettings {
             discovery_navidrome_playlist_id: None,
             discovery_last_generated_at: None,
             default_download_folder_id: None,
+            match_mode: None,
+            quality_profile: None,
+            rename_rules: None,
+            discogs_token: None,
+            discogs_username: None,
+            discogs_sync_collection: false,
+            search_timeout_secs: None,
+            home_dashboard: false,
+            routing_rules: None,
         }))
     }
 
@@ -138,11 +199,20 @@ impl UserSettings {
         let default_folder = update
             .default_download_folder_id
             .or(current.default_download_folder_id);
+        let match_mode = update.match_mode.or(current.match_mode);
+        let quality_profile = update.quality_profile.or(current.quality_profile);
+        let rename_rules = update.rename_rules.or(current.rename_rules);
+        let discogs_sync = update
+            .discogs_sync_collection
+            .unwrap_or(current.discogs_sync_collection);
+        let search_timeout = update.search_timeout_secs.or(current.search_timeout_secs);
+        let home_dashboard = update.home_dashboard.unwrap_or(current.home_dashboard);
+        let routing_rules = update.routing_rules.or(current.routing_rules);
 
         sqlx::query(
             r#"
-            INSERT INTO user_settings (user_id, default_metadata_provider, last_search_type, auto_delete_enabled, lastfm_api_key, lastfm_username, discovery_promote_threshold, navidrome_banner_dismissed, listenbrainz_username, listenbrainz_token, discovery_enabled, discovery_folder_id, discovery_track_count, discovery_lifetime_days, discovery_profiles, discovery_playlist_name, default_download_folder_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO user_settings (user_id, default_metadata_provider, last_search_type, auto_delete_enabled, lastfm_api_key, lastfm_username, discovery_promote_threshold, navidrome_banner_dismissed, listenbrainz_username, listenbrainz_token, discovery_enabled, discovery_folder_id, discovery_track_count, discovery_lifetime_days, discovery_profiles, discovery_playlist_name, default_download_folder_id, match_mode, quality_profile, rename_rules, discogs_sync_collection, search_timeout_secs, home_dashboard, routing_rules)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             ON CONFLICT(user_id) DO UPDATE SET
                 default_metadata_provider = excluded.default_metadata_provider,
                 last_search_type = excluded.last_search_type,
@@ -159,7 +229,14 @@ impl UserSettings {
                 discovery_lifetime_days = excluded.discovery_lifetime_days,
                 discovery_profiles = excluded.discovery_profiles,
                 discovery_playlist_name = excluded.discovery_playlist_name,
-                default_download_folder_id = excluded.default_download_folder_id
+                default_download_folder_id = excluded.default_download_folder_id,
+                match_mode = excluded.match_mode,
+                quality_profile = excluded.quality_profile,
+                rename_rules = excluded.rename_rules,
+                discogs_sync_collection = excluded.discogs_sync_collection,
+                search_timeout_secs = excluded.search_timeout_secs,
+                home_dashboard = excluded.home_dashboard,
+                routing_rules = excluded.routing_rules
             "#,
         )
         .bind(user_id)
@@ -179,6 +256,13 @@ impl UserSettings {
         .bind(&disc_profiles)
         .bind(&disc_name)
         .bind(&default_folder)
+        .bind(&match_mode)
+        .bind(&quality_profile)
+        .bind(&rename_rules)
+        .bind(discogs_sync)
+        .bind(search_timeout)
+        .bind(home_dashboard)
+        .bind(&routing_rules)
         .execute(&*DB)
         .await
         .map_err(|e| e.to_string())?;
@@ -249,6 +333,25 @@ impl UserSettings {
         Ok(())
     }
 
+    /// Store the Discogs account, or forget it when `account` is `None`.
+    pub async fn set_discogs_account(
+        user_id: &str,
+        account: Option<(&str, &str)>,
+    ) -> Result<(), String> {
+        let (token, username) = account.unzip();
+        sqlx::query(
+            "INSERT INTO user_settings (user_id, discogs_token, discogs_username) VALUES (?, ?, ?)
+             ON CONFLICT(user_id) DO UPDATE SET discogs_token = excluded.discogs_token, discogs_username = excluded.discogs_username",
+        )
+        .bind(user_id)
+        .bind(token)
+        .bind(username)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
     pub async fn reset_navidrome_banner(user_id: &str) -> Result<(), String> {
         sqlx::query("UPDATE user_settings SET navidrome_banner_dismissed = 0 WHERE user_id = ?")
             .bind(user_id)
@@ -0,0 +1,54 @@
+#[cfg(feature = "server")]
+use crate::db::DB;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// An artist whose releases show up on the user's release calendar.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct WatchedArtist {
+    pub id: String,
+    pub user_id: String,
+    pub artist_mbid: String,
+    pub name: String,
+    pub created_at: i64,
+}
+
+#[cfg(feature = "server")]
+impl WatchedArtist {
+    pub async fn get_all_by_user(user_id: &str) -> Result<Vec<WatchedArtist>, String> {
+        sqlx::query_as::<_, WatchedArtist>(
+            "SELECT * FROM watched_artists WHERE user_id = ? ORDER BY name COLLATE NOCASE",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Watch an artist. Watching an artist twice keeps the first entry.
+    pub async fn create(user_id: &str, artist_mbid: &str, name: &str) -> Result<(), String> {
+        sqlx::query(
+            "INSERT OR IGNORE INTO watched_artists (id, user_id, artist_mbid, name) VALUES (?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(user_id)
+        .bind(artist_mbid)
+        .bind(name)
+        .execute(&*DB)
+        .await
+        .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    pub async fn delete(id: &str, user_id: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM watched_artists WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
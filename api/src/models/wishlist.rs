@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use shared::export::ExportedRelease;
+#[cfg(feature = "server")]
+use shared::wishlist::NewWishlistItem;
+
+#[cfg(feature = "server")]
+use crate::db::DB;
+#[cfg(feature = "server")]
+use uuid::Uuid;
+
+/// An album the user wants but hasn't downloaded yet.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "server", derive(sqlx::FromRow))]
+pub struct WishlistItem {
+    pub id: String,
+    pub user_id: String,
+    pub artist: String,
+    pub title: String,
+    pub release_mbid: Option<String>,
+    pub source: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<WishlistItem> for ExportedRelease {
+    fn from(item: WishlistItem) -> Self {
+        ExportedRelease {
+            artist: item.artist,
+            title: item.title,
+            release_mbid: item.release_mbid,
+            release_group_mbid: None,
+            date: None,
+            source: item.source,
+            added_at: Some(item.created_at),
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+impl WishlistItem {
+    pub async fn get_all_by_user(user_id: &str) -> Result<Vec<WishlistItem>, String> {
+        sqlx::query_as::<_, WishlistItem>(
+            "SELECT * FROM wishlist_items WHERE user_id = ? ORDER BY created_at DESC, artist, title",
+        )
+        .bind(user_id)
+        .fetch_all(&*DB)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Add items in one transaction, skipping releases already wished for.
+    /// Returns how many were added.
+    pub async fn add_many(
+        user_id: &str,
+        items: &[NewWishlistItem],
+        source: Option<&str>,
+    ) -> Result<usize, String> {
+        let mut tx = DB.begin().await.map_err(|e| e.to_string())?;
+        let mut added = 0;
+        for item in items {
+            let result = sqlx::query(
+                "INSERT OR IGNORE INTO wishlist_items (id, user_id, artist, title, release_mbid, source) \
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(user_id)
+            .bind(&item.artist)
+            .bind(&item.title)
+            .bind(&item.release_mbid)
+            .bind(source)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| e.to_string())?;
+            added += result.rows_affected() as usize;
+        }
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(added)
+    }
+
+    pub async fn delete(id: &str, user_id: &str) -> Result<(), String> {
+        sqlx::query("DELETE FROM wishlist_items WHERE id = ? AND user_id = ?")
+            .bind(id)
+            .bind(user_id)
+            .execute(&*DB)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
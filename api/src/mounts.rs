@@ -0,0 +1,206 @@
+//! Health of the mounts behind library folders.
+//!
+//! Network shares fail in ways a plain "is it writable" check reports as a
+//! generic error: NFS handles go stale, CIFS and FUSE mounts disconnect,
+//! and a share that hit an I/O error gets remounted read-only. Those are
+//! detected here, imports into an affected folder wait for it instead of
+//! failing, and owners are told through the activity stream and their
+//! download channel.
+
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::io::ErrorKind;
+#[cfg(feature = "server")]
+use std::path::Path;
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use std::time::Duration;
+
+#[cfg(feature = "server")]
+use shared::activity::ActivityKind;
+#[cfg(feature = "server")]
+use shared::download::{DownloadEvent, FolderHealthChange};
+#[cfg(feature = "server")]
+use shared::system::{FolderAlert, MountProblem};
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+#[cfg(feature = "server")]
+use tracing::{info, warn};
+
+#[cfg(feature = "server")]
+use crate::activity::record_activity;
+#[cfg(feature = "server")]
+use crate::globals::get_or_create_user_channel;
+#[cfg(feature = "server")]
+use crate::models::user::User;
+
+/// A hung NFS mount blocks forever, so probes give up after this long.
+#[cfg(feature = "server")]
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a paused import checks whether its folder is back.
+#[cfg(feature = "server")]
+const PAUSED_RECHECK: Duration = Duration::from_secs(30);
+
+/// Problems currently seen, keyed by folder path, with when they started.
+#[cfg(feature = "server")]
+static PROBLEMS: LazyLock<RwLock<HashMap<String, (MountProblem, i64)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[cfg(feature = "server")]
+fn classify(e: &std::io::Error) -> Option<MountProblem> {
+    match e.kind() {
+        ErrorKind::StaleNetworkFileHandle | ErrorKind::NotConnected => Some(MountProblem::Stale),
+        ErrorKind::ReadOnlyFilesystem => Some(MountProblem::ReadOnly),
+        _ => None,
+    }
+}
+
+/// Check that the mount behind `path` answers and takes writes. Errors that
+/// aren't about the mount itself (missing folder, permissions) pass, so
+/// imports keep reporting them as before.
+#[cfg(feature = "server")]
+pub async fn probe(path: &Path) -> Result<(), MountProblem> {
+    let path = path.to_path_buf();
+    let check = async move {
+        if let Err(e) = tokio::fs::metadata(&path).await {
+            return classify(&e).map_or(Ok(()), Err);
+        }
+        let file = path.join(format!(".soulbeet-mount-check-{}", uuid::Uuid::new_v4()));
+        match tokio::fs::write(&file, b"").await {
+            Ok(()) => {
+                let _ = tokio::fs::remove_file(&file).await;
+                Ok(())
+            }
+            Err(e) => classify(&e).map_or(Ok(()), Err),
+        }
+    };
+    tokio::time::timeout(PROBE_TIMEOUT, check)
+        .await
+        .unwrap_or(Err(MountProblem::Unresponsive))
+}
+
+/// Tell the owners of the folders at `path` that it failed or recovered.
+#[cfg(feature = "server")]
+async fn announce(path: &str, problem: Option<&MountProblem>) {
+    let Ok(users) = User::get_all().await else {
+        return;
+    };
+    for user in users {
+        let Ok(folders) = user.get_folders().await else {
+            continue;
+        };
+        for folder in folders.iter().filter(|f| f.path == path) {
+            record_activity(
+                &user.username,
+                ActivityKind::FolderHealth {
+                    folder_id: folder.id.clone(),
+                    path: folder.path.clone(),
+                    healthy: problem.is_none(),
+                    problem: problem.map(|p| p.describe().to_string()),
+                },
+            );
+            let (tx, _) = get_or_create_user_channel(&user.username).await;
+            let _ = tx.send(DownloadEvent::FolderHealth(FolderHealthChange {
+                folder_name: folder.name.clone(),
+                problem: problem.map(|p| p.describe().to_string()),
+            }));
+        }
+    }
+}
+
+/// Probe `path` and record the outcome, announcing changes. Returns the
+/// current problem, if any.
+#[cfg(feature = "server")]
+pub async fn check(path: &str) -> Option<MountProblem> {
+    let problem = probe(Path::new(path)).await.err();
+    let changed = {
+        let mut problems = PROBLEMS.write().await;
+        match &problem {
+            Some(p) => {
+                let previous = problems.get(path).map(|(prev, _)| prev.clone());
+                if previous.as_ref() != Some(p) {
+                    let since = chrono::Utc::now().timestamp();
+                    problems.insert(path.to_string(), (p.clone(), since));
+                    true
+                } else {
+                    false
+                }
+            }
+            None => problems.remove(path).is_some(),
+        }
+    };
+    if changed {
+        match &problem {
+            Some(p) => warn!("Folder {}: {}", path, p.describe()),
+            None => info!("Folder {} is available again", path),
+        }
+        announce(path, problem.as_ref()).await;
+    }
+    problem
+}
+
+/// Probe every configured folder once. Run periodically.
+#[cfg(feature = "server")]
+pub async fn check_all_folders() {
+    let Ok(users) = User::get_all().await else {
+        return;
+    };
+    let mut paths: Vec<String> = Vec::new();
+    for user in users {
+        if let Ok(folders) = user.get_folders().await {
+            paths.extend(folders.into_iter().map(|f| f.path));
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    for path in paths {
+        check(&path).await;
+    }
+}
+
+/// Wait until the mount behind `path` is usable. `on_pause` runs once if
+/// it isn't, before waiting.
+#[cfg(feature = "server")]
+pub async fn wait_until_available(path: &Path, on_pause: impl FnOnce(&MountProblem)) {
+    let path = path.to_string_lossy().to_string();
+    let Some(problem) = check(&path).await else {
+        return;
+    };
+    info!("Pausing import into {}: {}", path, problem.describe());
+    on_pause(&problem);
+    loop {
+        tokio::time::sleep(PAUSED_RECHECK).await;
+        if check(&path).await.is_none() {
+            info!("Resuming import into {}", path);
+            return;
+        }
+    }
+}
+
+/// Current problems with the given user's folders.
+#[cfg(feature = "server")]
+pub async fn alerts_for_user(user_id: &str) -> Vec<FolderAlert> {
+    let problems = PROBLEMS.read().await.clone();
+    if problems.is_empty() {
+        return Vec::new();
+    }
+    let folders = crate::models::folder::Folder::get_all_by_user(user_id)
+        .await
+        .unwrap_or_default();
+    folders
+        .into_iter()
+        .filter_map(|f| {
+            let (problem, since) = problems.get(&f.path)?.clone();
+            Some(FolderAlert {
+                folder_id: f.id,
+                folder_name: f.name,
+                path: f.path,
+                problem,
+                since,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! Network access control by client address.
+//!
+//! `ALLOWED_NETWORKS` limits the whole instance to some CIDR ranges, while
+//! `ADMIN_NETWORKS` only limits settings and user management, so search
+//! and downloads can be reachable from anywhere while configuration stays
+//! on the LAN. Behind a reverse proxy the client address is read from
+//! `X-Forwarded-For`, which is only trusted when `TRUST_FORWARDED_FOR` is
+//! set.
+
+#[cfg(feature = "server")]
+use std::net::{IpAddr, SocketAddr};
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::{ConnectInfo, Request},
+    http::{Extensions, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+#[cfg(feature = "server")]
+use tracing::warn;
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+
+/// Paths of the settings page and of the server fns behind it.
+#[cfg(feature = "server")]
+const ADMIN_PATHS: &[&str] = &[
+    "/settings",
+    "/api/settings",
+    "/api/config",
+    "/api/users",
+    "/api/folders",
+    "/api/tokens",
+    "/api/maintenance",
+    "/api/auth/register",
+];
+
+/// An address range such as `192.168.1.0/24` or `fd00::/8`. A bare
+/// address is a range of one.
+#[cfg(feature = "server")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+#[cfg(feature = "server")]
+impl Cidr {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let network: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("'{}' is not an IP address", addr))?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(p) => p
+                .parse::<u8>()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(|| format!("'{}' is not a valid prefix length", p))?,
+            None => max,
+        };
+        Ok(Self { network, prefix })
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 clients of a dual-stack listener show up as ::ffff:a.b.c.d
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map_or(ip, IpAddr::V4),
+            v4 => v4,
+        };
+        match (self.network, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(feature = "server")]
+pub(crate) fn is_admin_path(path: &str) -> bool {
+    ADMIN_PATHS.iter().any(|prefix| {
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    })
+}
+
+/// The address the request came from, as far as it can be trusted.
+#[cfg(feature = "server")]
+pub(crate) fn client_ip(headers: &HeaderMap, extensions: &Extensions) -> Option<IpAddr> {
+    if CONFIG.trust_forwarded_for() {
+        // The proxy appends the address it saw last
+        let forwarded = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|ip| ip.trim().parse::<IpAddr>().ok())
+            .next_back();
+        if forwarded.is_some() {
+            return forwarded;
+        }
+    }
+    extensions
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip())
+}
+
+/// Whether a client at `ip`, `None` when unknown, may reach `path`.
+#[cfg(feature = "server")]
+fn is_permitted(ip: Option<IpAddr>, path: &str, allowed: &[Cidr], admin: &[Cidr]) -> bool {
+    let admin_required = !admin.is_empty() && is_admin_path(path);
+    if allowed.is_empty() && !admin_required {
+        return true;
+    }
+    ip.is_some_and(|ip| {
+        (allowed.is_empty() || allowed.iter().any(|c| c.contains(ip)))
+            && (!admin_required || admin.iter().any(|c| c.contains(ip)))
+    })
+}
+
+/// Axum middleware refusing requests from outside the configured networks.
+/// When the client address can't be determined, restricted paths are
+/// refused rather than let through.
+#[cfg(feature = "server")]
+pub async fn restrict_networks(request: Request, next: Next) -> Response {
+    let allowed = CONFIG.allowed_networks();
+    let admin = CONFIG.admin_networks();
+    let path = request.uri().path();
+    if allowed.is_empty() && (admin.is_empty() || !is_admin_path(path)) {
+        return next.run(request).await;
+    }
+
+    let ip = client_ip(request.headers(), request.extensions());
+    if is_permitted(ip, path, allowed, admin) {
+        return next.run(request).await;
+    }
+
+    match ip {
+        Some(ip) => warn!("Refused {} from {}: outside allowed networks", path, ip),
+        None => warn!(
+            "Refused {}: client address unknown. Set TRUST_FORWARDED_FOR behind a proxy.",
+            path
+        ),
+    }
+    (StatusCode::FORBIDDEN, "Not available from your network").into_response()
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    fn lan() -> Vec<Cidr> {
+        vec![Cidr::parse("192.168.1.0/24").unwrap()]
+    }
+
+    #[test]
+    fn client_ip_needs_connect_info() {
+        let headers = HeaderMap::new();
+        assert_eq!(client_ip(&headers, &Extensions::new()), None);
+
+        let mut extensions = Extensions::new();
+        let addr: SocketAddr = "192.168.1.20:5000".parse().unwrap();
+        extensions.insert(ConnectInfo(addr));
+        assert_eq!(client_ip(&headers, &extensions), Some(addr.ip()));
+    }
+
+    #[test]
+    fn unknown_client_is_refused_only_where_restricted() {
+        assert!(is_permitted(None, "/api/search", &[], &[]));
+        assert!(is_permitted(None, "/api/search", &[], &lan()));
+        assert!(!is_permitted(None, "/api/settings", &[], &lan()));
+        assert!(!is_permitted(None, "/api/search", &lan(), &[]));
+    }
+
+    #[test]
+    fn known_client_is_checked_against_the_networks() {
+        let inside = Some("192.168.1.20".parse().unwrap());
+        let outside = Some("10.0.0.5".parse().unwrap());
+        assert!(is_permitted(inside, "/api/settings", &lan(), &lan()));
+        assert!(!is_permitted(outside, "/api/search", &lan(), &[]));
+        assert!(is_permitted(outside, "/api/search", &[], &lan()));
+        assert!(!is_permitted(outside, "/api/settings", &[], &lan()));
+    }
+}
@@ -0,0 +1,127 @@
+//! Panic capture.
+//!
+//! Tokio keeps the server up when a task panics, but the task is gone
+//! without a word: a download monitor stops updating and its downloads sit
+//! there forever. The hook installed here logs every panic with what was
+//! being done at the time and keeps the latest ones for the settings page,
+//! and the helpers below let long-running tasks clean up after one.
+
+#[cfg(feature = "server")]
+use std::collections::VecDeque;
+#[cfg(feature = "server")]
+use std::future::Future;
+#[cfg(feature = "server")]
+use std::panic::AssertUnwindSafe;
+#[cfg(feature = "server")]
+use std::sync::Mutex;
+
+#[cfg(feature = "server")]
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+#[cfg(feature = "server")]
+use futures::FutureExt;
+#[cfg(feature = "server")]
+use shared::system::PanicReport;
+#[cfg(feature = "server")]
+use tracing::error;
+
+/// Panics kept for the settings page.
+#[cfg(feature = "server")]
+const KEPT_PANICS: usize = 20;
+
+#[cfg(feature = "server")]
+static RECENT: Mutex<VecDeque<PanicReport>> = Mutex::new(VecDeque::new());
+
+#[cfg(feature = "server")]
+tokio::task_local! {
+    static CONTEXT: String;
+}
+
+/// Replace the default panic hook, which only prints to stderr, with one
+/// that logs a report and keeps it.
+#[cfg(feature = "server")]
+pub fn install() {
+    std::panic::set_hook(Box::new(|info| {
+        let payload = info.payload();
+        let message = payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_default();
+        let thread = std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string();
+        let context = CONTEXT.try_with(|c| c.clone()).ok();
+        let backtrace = std::backtrace::Backtrace::capture();
+        let backtrace = (backtrace.status() == std::backtrace::BacktraceStatus::Captured)
+            .then(|| backtrace.to_string());
+
+        error!(
+            panic.location = %location,
+            panic.thread = %thread,
+            panic.context = context.as_deref().unwrap_or(""),
+            "Panic: {}",
+            message
+        );
+
+        let mut recent = RECENT.lock().unwrap_or_else(|e| e.into_inner());
+        if recent.len() == KEPT_PANICS {
+            recent.pop_back();
+        }
+        recent.push_front(PanicReport {
+            at: chrono::Utc::now().timestamp(),
+            message,
+            location,
+            thread,
+            context,
+            backtrace,
+        });
+    }));
+}
+
+/// Panics since the server started, newest first.
+#[cfg(feature = "server")]
+pub fn recent() -> Vec<PanicReport> {
+    RECENT
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
+/// Run `fut` with `context` attached to any panic it reports.
+#[cfg(feature = "server")]
+pub async fn with_context<F: Future>(context: String, fut: F) -> F::Output {
+    CONTEXT.scope(context, fut).await
+}
+
+/// Run `fut` with `context` attached, returning `None` if it panicked
+/// instead of taking the calling task down with it.
+#[cfg(feature = "server")]
+pub async fn catch<F: Future>(context: String, fut: F) -> Option<F::Output> {
+    CONTEXT
+        .scope(context, AssertUnwindSafe(fut).catch_unwind())
+        .await
+        .ok()
+}
+
+/// Axum middleware answering 500 to a request whose handler panicked,
+/// rather than dropping the connection.
+#[cfg(feature = "server")]
+pub async fn catch_request_panics(request: Request, next: Next) -> Response {
+    let context = format!("{} {}", request.method(), request.uri().path());
+    match catch(context, next.run(request)).await {
+        Some(response) => response,
+        None => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response(),
+    }
+}
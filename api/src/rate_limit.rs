@@ -0,0 +1,47 @@
+//! In-memory rate limiting for endpoints anyone can call, such as password
+//! reset requests. Counts are kept per key (an address, an account) over a
+//! sliding window and are lost on restart.
+
+#[cfg(feature = "server")]
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::sync::Mutex;
+#[cfg(feature = "server")]
+use std::time::{Duration, Instant};
+
+/// At most `max` hits per key within `window`.
+#[cfg(feature = "server")]
+pub struct RateLimiter {
+    max: usize,
+    window: Duration,
+    hits: Mutex<HashMap<String, Vec<Instant>>>,
+}
+
+#[cfg(feature = "server")]
+impl RateLimiter {
+    pub fn new(max: usize, window: Duration) -> Self {
+        Self {
+            max,
+            window,
+            hits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a hit for `key`. False, without counting it, once the key is
+    /// over its limit.
+    pub fn check(&self, key: &str) -> bool {
+        let now = Instant::now();
+        let mut hits = self.hits.lock().unwrap_or_else(|e| e.into_inner());
+        // Forget keys whose hits have all expired, so the map stays small
+        hits.retain(|_, times| {
+            times.retain(|t| now.duration_since(*t) < self.window);
+            !times.is_empty()
+        });
+        let times = hits.entry(key.to_string()).or_default();
+        if times.len() >= self.max {
+            return false;
+        }
+        times.push(now);
+        true
+    }
+}
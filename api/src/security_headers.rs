@@ -0,0 +1,174 @@
+//! Security headers for every response: a Content Security Policy,
+//! framing and referrer rules, and optionally HSTS.
+//!
+//! The server-rendered page carries inline scripts Dioxus writes itself: the
+//! streaming bootstrap after the head and the hydration data. Dioxus has no
+//! way to give them a nonce, so each HTML response gets a fresh one that is
+//! added to those scripts only, recognised by their whole text. Any other
+//! inline script on the page is left without it and blocked.
+
+#[cfg(feature = "server")]
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+#[cfg(feature = "server")]
+use regex::{Captures, Regex};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use tracing::warn;
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+
+/// Largest page buffered to add the nonce. Rendered pages are far smaller.
+#[cfg(feature = "server")]
+const MAX_REWRITTEN_HTML: usize = 16 * 1024 * 1024;
+
+/// The policy for a page with the given nonce. `document::eval` runs its
+/// code through `new Function`, which needs `unsafe-eval`; inline styles
+/// come from `style` attributes, which nonces can't cover. Cover art and
+/// artist images are loaded from several services, so any HTTPS image is
+/// allowed.
+#[cfg(feature = "server")]
+fn content_security_policy(nonce: &str) -> String {
+    [
+        "default-src 'self'".to_string(),
+        format!("script-src 'self' 'nonce-{nonce}' 'unsafe-eval' 'wasm-unsafe-eval'"),
+        "style-src 'self' 'unsafe-inline'".to_string(),
+        "img-src 'self' data: blob: https:".to_string(),
+        "connect-src 'self'".to_string(),
+        "font-src 'self' data:".to_string(),
+        "object-src 'none'".to_string(),
+        "base-uri 'self'".to_string(),
+        "form-action 'self'".to_string(),
+        "frame-ancestors 'none'".to_string(),
+    ]
+    .join("; ")
+}
+
+/// A `<script>` element as dioxus-server writes it: the streaming bootstrap,
+/// which never changes, or hydration data, which only hands base64 (and in
+/// debug builds lists of type names) to the client. A script injected into
+/// the page only matches if it is just as inert.
+#[cfg(feature = "server")]
+static FRAMEWORK_SCRIPT: LazyLock<Regex> = LazyLock::new(|| {
+    // JS list of strings, none of which can close the script
+    let item = r#""(?:[^"\\<]|<[^/"\\]|\\[^<])*""#;
+    let list = format!(r"\[(?:{item}(?:,{item})*)?\]");
+    let data = r#""[A-Za-z0-9+/=]*""#;
+    Regex::new(&format!(
+        concat!(
+            "<script>(?:{bootstrap}",
+            "|window\\.initial_dioxus_hydration_data={data};",
+            "(?:window\\.initial_dioxus_hydration_debug_types={list};",
+            "window\\.initial_dioxus_hydration_debug_locations={list};)?",
+            "|window\\.dx_hydrate\\(\\[[0-9,]*\\], {data}(?:, {list}, {list})?\\))</script>",
+        ),
+        bootstrap = regex::escape(dioxus_interpreter_js::INITIALIZE_STREAMING_JS),
+        data = data,
+        list = list,
+    ))
+    .expect("valid framework script regex")
+});
+
+/// `html` with `nonce` on the scripts Dioxus wrote, and on no other.
+#[cfg(feature = "server")]
+fn with_nonce(html: &str, nonce: &str) -> String {
+    FRAMEWORK_SCRIPT
+        .replace_all(html, |script: &Captures| {
+            script[0].replacen("<script>", &format!("<script nonce=\"{nonce}\">"), 1)
+        })
+        .into_owned()
+}
+
+/// Axum middleware adding the security headers.
+#[cfg(feature = "server")]
+pub async fn security_headers(request: Request, next: Next) -> Response {
+    let nonce = uuid::Uuid::new_v4().simple().to_string();
+    let response = next.run(request).await;
+    let is_html = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("text/html"));
+
+    let mut response = if is_html {
+        let (mut parts, body) = response.into_parts();
+        match axum::body::to_bytes(body, MAX_REWRITTEN_HTML).await {
+            Ok(bytes) => {
+                let html = with_nonce(&String::from_utf8_lossy(&bytes), &nonce);
+                parts.headers.remove(header::CONTENT_LENGTH);
+                Response::from_parts(parts, Body::from(html))
+            }
+            Err(e) => {
+                warn!("Could not add the CSP nonce to a page: {}", e);
+                parts.status = StatusCode::INTERNAL_SERVER_ERROR;
+                parts.headers.remove(header::CONTENT_LENGTH);
+                Response::from_parts(parts, Body::empty())
+            }
+        }
+    } else {
+        response
+    };
+
+    let headers = response.headers_mut();
+    if is_html {
+        if let Ok(csp) = HeaderValue::from_str(&content_security_policy(&nonce)) {
+            headers.insert(header::CONTENT_SECURITY_POLICY, csp);
+        }
+    }
+    headers.insert(header::X_FRAME_OPTIONS, HeaderValue::from_static("DENY"));
+    headers.insert(
+        header::X_CONTENT_TYPE_OPTIONS,
+        HeaderValue::from_static("nosniff"),
+    );
+    headers.insert(
+        header::REFERRER_POLICY,
+        HeaderValue::from_static("same-origin"),
+    );
+    if CONFIG.hsts() {
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_static("max-age=31536000; includeSubDomains"),
+        );
+    }
+    response
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_framework_scripts_get_the_nonce() {
+        let page = format!(
+            concat!(
+                "<head></head><script>{}</script>",
+                "<div id=\"main\"><p>hi</p><script>alert(1)</script></div>",
+                "<script>window.initial_dioxus_hydration_data=\"oWE=\";</script>",
+                "<script>window.dx_hydrate([0,2], \"oWE=\")</script>",
+                "<script>window.dx_hydrate([0], \"\" + alert(1))</script>",
+            ),
+            dioxus_interpreter_js::INITIALIZE_STREAMING_JS
+        );
+        let html = with_nonce(&page, "abc");
+        assert_eq!(html.matches("<script nonce=\"abc\">").count(), 3);
+        assert!(html.contains("<script>alert(1)</script>"));
+        assert!(html.contains("<script>window.dx_hydrate([0], \"\" + alert(1))</script>"));
+    }
+
+    #[test]
+    fn debug_type_lists_are_accepted() {
+        let page = concat!(
+            "<script>window.initial_dioxus_hydration_data=\"\";",
+            "window.initial_dioxus_hydration_debug_types=[\"Option<String>\",\"unknown\"];",
+            "window.initial_dioxus_hydration_debug_locations=[\"ui/src/a.rs:1:2\"];</script>",
+        );
+        assert!(with_nonce(page, "abc").starts_with("<script nonce=\"abc\">"));
+    }
+}
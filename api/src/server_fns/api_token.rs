@@ -0,0 +1,41 @@
+use crate::models::api_token::ApiToken;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+
+/// A freshly created token. `secret` is only ever returned here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedApiToken {
+    pub token: ApiToken,
+    pub secret: String,
+}
+
+#[get("/api/tokens", auth: AuthSession)]
+pub async fn get_api_tokens() -> Result<Vec<ApiToken>, ServerFnError> {
+    ApiToken::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+#[post("/api/tokens", auth: MemberSession)]
+pub async fn create_api_token(name: String) -> Result<CreatedApiToken, ServerFnError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(server_error("Token name cannot be empty"));
+    }
+    let (token, secret) = ApiToken::create(&auth.0.sub, name)
+        .await
+        .map_err(server_error)?;
+    Ok(CreatedApiToken { token, secret })
+}
+
+#[delete("/api/tokens/delete", auth: MemberSession)]
+pub async fn delete_api_token(token_id: String) -> Result<(), ServerFnError> {
+    ApiToken::delete(&token_id, &auth.0.sub)
+        .await
+        .map_err(server_error)
+}
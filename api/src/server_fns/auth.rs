@@ -4,33 +4,62 @@ use dioxus::prelude::*;
 #[cfg(feature = "server")]
 use crate::{
     auth, models,
-    server_fns::{server_error, unauthorized_error},
+    server_fns::{security_policy, server_error, unauthorized_error, validation_error},
     AuthSession,
 };
+#[cfg(feature = "server")]
+use shared::security::LockoutPolicy;
 
 #[cfg(feature = "server")]
-use tower_cookies::{
-    cookie::{time, SameSite},
-    Cookie, Cookies,
-};
+use tower_cookies::{cookie::time, Cookie, Cookies};
+
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
 
 pub const AUTH_COOKIE_NAME: &str = "auth_token";
 
+/// Path of the auth cookie: the sub-path the instance is served under, so
+/// instances sharing a domain keep their sessions apart.
+#[cfg(feature = "server")]
+fn cookie_path() -> String {
+    match CONFIG.base_path() {
+        "" => "/".to_string(),
+        base => format!("{}/", base),
+    }
+}
+
 /// Helper to configure the auth cookie consistently
 #[cfg(feature = "server")]
 fn build_auth_cookie(token: String) -> Cookie<'static> {
-    use crate::auth::EXPIRATION_DAYS;
+    use crate::auth::{EXPIRATION_DAYS, REFRESH_GRACE_DAYS};
 
     let mut cookie = Cookie::new(AUTH_COOKIE_NAME, token);
-    cookie.set_path("/");
+    cookie.set_path(cookie_path());
     cookie.set_http_only(true);
-    cookie.set_same_site(SameSite::Lax);
-    cookie.set_expires(time::OffsetDateTime::now_utc() + time::Duration::days(EXPIRATION_DAYS));
+    cookie.set_secure(CONFIG.cookie_secure());
+    cookie.set_same_site(CONFIG.cookie_same_site());
+    // Outlives the token by the grace period, so an expired token still
+    // reaches `refresh_token`
+    cookie.set_expires(
+        time::OffsetDateTime::now_utc()
+            + time::Duration::days(EXPIRATION_DAYS + REFRESH_GRACE_DAYS),
+    );
     cookie
 }
 
+/// Create the first account of a new instance. Once there is one, users are
+/// added from user management or sign up with an invite.
 #[post("/api/auth/register")]
 pub async fn register(username: String, password: String) -> Result<(), ServerFnError> {
+    let has_users = models::user::User::any_exist()
+        .await
+        .map_err(server_error)?;
+    if has_users {
+        return Err(validation_error(
+            "Sign up with an invite link from an existing user",
+        ));
+    }
+    super::check_password(&password).await?;
     models::user::User::create(&username, &password)
         .await
         .map_err(server_error)
@@ -92,6 +121,43 @@ async fn try_navidrome_auth(username: &str, password: &str) -> NavidromeAuthResu
     }
 }
 
+/// Error for a wrong password. Counts the attempt against `user`, locking
+/// the account when the lockout policy says so.
+#[cfg(feature = "server")]
+async fn reject_login(user: Option<&models::user::User>, lockout: &LockoutPolicy) -> ServerFnError {
+    if let Some(user) = user.filter(|_| lockout.is_enabled()) {
+        let lock_secs = i64::from(lockout.lockout_minutes) * 60;
+        match models::user::User::record_failed_login(&user.id, lockout.max_attempts, lock_secs)
+            .await
+        {
+            Ok(true) => {
+                tracing::warn!(
+                    "Locked {} for {} minute(s) after {} failed sign-ins",
+                    user.username,
+                    lockout.lockout_minutes,
+                    lockout.max_attempts
+                );
+                return locked_error(i64::from(lockout.lockout_minutes));
+            }
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                "Could not count a failed sign-in of {}: {}",
+                user.username,
+                e
+            ),
+        }
+    }
+    unauthorized_error("Invalid username or password")
+}
+
+#[cfg(feature = "server")]
+fn locked_error(minutes: i64) -> ServerFnError {
+    validation_error(format!(
+        "Too many failed sign-ins. This account is locked for {} more minute(s).",
+        minutes
+    ))
+}
+
 #[post("/api/auth/login", cookies: Cookies)]
 pub async fn login(username: String, password: String) -> Result<AuthResponse, ServerFnError> {
     use crate::crypto;
@@ -99,6 +165,24 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
     use models::user::User;
     use shared::system::NavidromeStatus;
 
+    // Locked accounts are refused before their password is even tried
+    let lockout = security_policy().await.map_err(server_error)?.lockout;
+    let known = User::get_by_username(&username)
+        .await
+        .map_err(server_error)?;
+    let now = chrono::Utc::now().timestamp();
+    if let Some(user) = known.as_ref().filter(|u| u.is_locked(now)) {
+        let left = user.locked_until.unwrap_or(now) - now;
+        return Err(locked_error((left + 59) / 60));
+    }
+    // A successful sign-in starts the count of failures over
+    let clear_failures = || async {
+        match known.as_ref().filter(|u| u.failed_logins > 0) {
+            Some(user) => User::unlock(&user.id).await.map_err(server_error),
+            None => Ok(()),
+        }
+    };
+
     match try_navidrome_auth(&username, &password).await {
         NavidromeAuthResult::Success => {
             // Look up or create user
@@ -112,6 +196,8 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
                     .map_err(server_error)?,
             };
 
+            clear_failures().await?;
+
             // Encrypt the Navidrome password and store it
             let encrypted = crypto::encrypt(&password).map_err(server_error)?;
             User::update_navidrome_token(
@@ -138,6 +224,7 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
             cookies.add(build_auth_cookie(token));
 
             Ok(AuthResponse {
+                role: user.role(),
                 username: user.username,
                 user_id: user.id,
                 navidrome_status: NavidromeStatus::Connected,
@@ -145,15 +232,15 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
         }
         NavidromeAuthResult::AuthFailed => {
             // Navidrome rejected the credentials. Fall back to local auth.
-            let user = User::get_by_username(&username)
-                .await
-                .map_err(server_error)?
+            let user = known
+                .clone()
                 .ok_or_else(|| unauthorized_error("Invalid username or password"))?;
 
             // Verify local password
-            let _ = User::verify(&username, &password)
-                .await
-                .map_err(|_| unauthorized_error("Invalid username or password"))?;
+            if User::verify(&username, &password).await.is_err() {
+                return Err(reject_login(Some(&user), &lockout).await);
+            }
+            clear_failures().await?;
 
             // Mark Navidrome status as invalid_credentials
             User::update_navidrome_token(
@@ -172,6 +259,7 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
             cookies.add(build_auth_cookie(token));
 
             Ok(AuthResponse {
+                role: user.role(),
                 username: user.username,
                 user_id: user.id,
                 navidrome_status: NavidromeStatus::InvalidCredentials,
@@ -179,9 +267,10 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
         }
         NavidromeAuthResult::Unreachable => {
             // Navidrome is down. Fall back to local password verification.
-            let user = User::verify(&username, &password)
-                .await
-                .map_err(|_| unauthorized_error("Invalid username or password"))?;
+            let Ok(user) = User::verify(&username, &password).await else {
+                return Err(reject_login(known.as_ref(), &lockout).await);
+            };
+            clear_failures().await?;
 
             // Mark Navidrome status as offline (keep existing token)
             User::update_navidrome_token(
@@ -197,6 +286,7 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
             cookies.add(build_auth_cookie(token));
 
             Ok(AuthResponse {
+                role: user.role(),
                 username: user.username,
                 user_id: user.id,
                 navidrome_status: NavidromeStatus::Offline,
@@ -205,15 +295,26 @@ pub async fn login(username: String, password: String) -> Result<AuthResponse, S
     }
 }
 
-#[post("/api/auth/refresh", auth: AuthSession, cookies: Cookies)]
+/// Swap the session cookie for a fresh one. Takes a token that expired
+/// recently, which is when the UI asks.
+#[post("/api/auth/refresh", cookies: Cookies)]
 pub async fn refresh_token() -> Result<(), ServerFnError> {
-    let claims = auth.0;
+    let claims = cookies
+        .get(AUTH_COOKIE_NAME)
+        .ok_or_else(|| unauthorized_error("No auth token found"))
+        .and_then(|c| auth::verify_token_for_refresh(c.value()).map_err(unauthorized_error))?;
 
-    let _ = models::user::User::get_by_id(&claims.sub)
+    // Deleted and locked accounts don't get to carry on
+    let user = models::user::User::get_by_id(&claims.sub)
         .await
-        .map_err(server_error)?;
+        .map_err(unauthorized_error)?;
+    let now = chrono::Utc::now().timestamp();
+    if user.is_locked(now) {
+        let left = user.locked_until.unwrap_or(now) - now;
+        return Err(locked_error((left + 59) / 60));
+    }
 
-    let token = auth::create_token(claims.sub, claims.username).map_err(server_error)?;
+    let token = auth::create_token(user.id, user.username).map_err(server_error)?;
 
     cookies.add(build_auth_cookie(token));
 
@@ -223,7 +324,7 @@ pub async fn refresh_token() -> Result<(), ServerFnError> {
 #[post("/api/auth/logout", cookies: Cookies)]
 pub async fn logout() -> Result<(), ServerFnError> {
     let mut cookie = Cookie::new(AUTH_COOKIE_NAME, "");
-    cookie.set_path("/");
+    cookie.set_path(cookie_path());
 
     cookies.remove(cookie);
 
@@ -234,14 +335,20 @@ pub async fn logout() -> Result<(), ServerFnError> {
 pub async fn get_current_user() -> Result<Option<AuthResponse>, ServerFnError> {
     let claims = auth.0;
 
-    let status = models::user::User::get_by_id(&claims.sub)
+    let (role, status) = models::user::User::get_by_id(&claims.sub)
         .await
-        .map(|u| shared::system::NavidromeStatus::from(u.navidrome_status))
+        .map(|u| {
+            (
+                u.role(),
+                shared::system::NavidromeStatus::from(u.navidrome_status),
+            )
+        })
         .unwrap_or_default();
 
     Ok(Some(AuthResponse {
         username: claims.username,
         user_id: claims.sub,
         navidrome_status: status,
+        role,
     }))
 }
@@ -0,0 +1,39 @@
+//! Bytes each user downloads, counted per day when their transfers end.
+
+use dioxus::prelude::*;
+use shared::download::BandwidthUsage;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::models::bandwidth_usage::{day, BandwidthUsageRow};
+#[cfg(feature = "server")]
+use crate::AuthSession;
+#[cfg(feature = "server")]
+use shared::download::DailyBandwidth;
+
+/// The user's usage over the last 30 days.
+#[get("/api/downloads/bandwidth", auth: AuthSession)]
+pub async fn get_bandwidth_usage() -> Result<BandwidthUsage, ServerFnError> {
+    let rows = BandwidthUsageRow::since(&auth.0.sub, &day(29))
+        .await
+        .map_err(server_error)?;
+    let total_since = |since: String| -> u64 {
+        rows.iter()
+            .filter(|r| r.day >= since)
+            .map(|r| r.bytes.max(0) as u64)
+            .sum()
+    };
+    Ok(BandwidthUsage {
+        today: total_since(day(0)),
+        week: total_since(day(6)),
+        month: total_since(day(29)),
+        days: rows
+            .iter()
+            .map(|r| DailyBandwidth {
+                day: r.day.clone(),
+                bytes: r.bytes.max(0) as u64,
+            })
+            .collect(),
+    })
+}
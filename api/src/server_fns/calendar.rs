@@ -0,0 +1,354 @@
+//! Release calendar: recent and upcoming releases of watched artists, from
+//! MusicBrainz release group dates, with per-release actions and ICS export.
+//! Also the artists related to a watched one, to find more to follow.
+
+use dioxus::prelude::*;
+use shared::calendar::{CalendarRelease, RelatedArtist, ReleaseAction};
+
+use crate::models::watched_artist::WatchedArtist;
+
+#[cfg(feature = "server")]
+use super::{metadata_error, server_error};
+#[cfg(feature = "server")]
+use crate::models::folder::Folder;
+#[cfg(feature = "server")]
+use crate::models::release_action::ReleaseActionRecord;
+#[cfg(feature = "server")]
+use crate::services::music_importer;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use crate::ApiTokenSession;
+#[cfg(feature = "server")]
+use shared::calendar::RelatedAlbum;
+#[cfg(feature = "server")]
+use soulbeet::http::ArtistReleaseGroup;
+#[cfg(feature = "server")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+
+/// Releases older than this many days drop off the calendar.
+#[cfg(feature = "server")]
+const RECENT_DAYS: i64 = 30;
+
+/// How long an artist's release groups are reused before asking MusicBrainz again.
+#[cfg(feature = "server")]
+const RELEASE_GROUP_TTL_SECS: i64 = 6 * 3600;
+
+/// Related artists looked at for a watched one. Each costs a MusicBrainz
+/// request for their albums.
+#[cfg(feature = "server")]
+const MAX_RELATED: usize = 10;
+
+/// Release groups per artist MBID, with the time they were fetched.
+#[cfg(feature = "server")]
+static RELEASE_GROUPS: LazyLock<RwLock<HashMap<String, (i64, Vec<ArtistReleaseGroup>)>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+#[cfg(feature = "server")]
+async fn release_groups(artist_mbid: &str) -> Result<Vec<ArtistReleaseGroup>, String> {
+    let now = chrono::Utc::now().timestamp();
+    if let Some((fetched_at, groups)) = RELEASE_GROUPS.read().await.get(artist_mbid) {
+        if now - fetched_at < RELEASE_GROUP_TTL_SECS {
+            return Ok(groups.clone());
+        }
+    }
+
+    let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+    let groups = soulbeet::http::artist_release_groups(&client, artist_mbid)
+        .await
+        .map_err(|e| e.to_string())?;
+    RELEASE_GROUPS
+        .write()
+        .await
+        .insert(artist_mbid.to_string(), (now, groups.clone()));
+    Ok(groups)
+}
+
+/// Latest day a possibly partial date can stand for, so "2026" or
+/// "2026-05" still count as recent during that year or month.
+#[cfg(feature = "server")]
+fn latest_day(date: &str) -> String {
+    match date.len() {
+        4 => format!("{}-12-31", date),
+        7 => format!("{}-31", date),
+        _ => date.to_string(),
+    }
+}
+
+/// Calendar entries of a user, oldest first. Ignored releases and those
+/// with a pending reminder are left out.
+#[cfg(feature = "server")]
+async fn calendar_for_user(user_id: &str) -> Result<Vec<CalendarRelease>, String> {
+    let artists = WatchedArtist::get_all_by_user(user_id).await?;
+    let actions: HashMap<String, ReleaseActionRecord> =
+        ReleaseActionRecord::get_all_by_user(user_id)
+            .await?
+            .into_iter()
+            .map(|a| (a.release_group_mbid.clone(), a))
+            .collect();
+
+    let now = chrono::Utc::now();
+    let cutoff = (now - chrono::Duration::days(RECENT_DAYS))
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut releases = Vec::new();
+    for artist in artists {
+        let groups = match release_groups(&artist.artist_mbid).await {
+            Ok(groups) => groups,
+            Err(e) => {
+                warn!("Failed to fetch releases of {}: {}", artist.name, e);
+                continue;
+            }
+        };
+
+        for group in groups {
+            if group.first_release_date.is_empty()
+                || latest_day(&group.first_release_date) < cutoff
+            {
+                continue;
+            }
+            let reminded = match actions.get(&group.id) {
+                Some(a) if a.action == "ignore" => continue,
+                Some(a) if a.remind_at.is_some_and(|at| at > now.timestamp()) => continue,
+                Some(_) => true,
+                None => false,
+            };
+            releases.push(CalendarRelease {
+                release_group_id: group.id,
+                artist_mbid: artist.artist_mbid.clone(),
+                artist: artist.name.clone(),
+                title: group.title,
+                release_type: group.primary_type,
+                date: group.first_release_date,
+                reminded,
+            });
+        }
+    }
+
+    releases.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.artist.cmp(&b.artist)));
+    Ok(releases)
+}
+
+/// Albums in the folders the user can see, as lowercased (artist, album).
+#[cfg(feature = "server")]
+async fn owned_albums(user_id: &str) -> HashSet<(String, String)> {
+    let mut owned = HashSet::new();
+    let Ok(importer) = music_importer(None).await else {
+        return owned;
+    };
+    for folder in Folder::get_visible_to(user_id).await.unwrap_or_default() {
+        match importer
+            .library_index(std::path::Path::new(&folder.path))
+            .await
+        {
+            Ok(entries) => owned.extend(
+                entries
+                    .into_iter()
+                    .map(|e| (e.artist.to_lowercase(), e.album.to_lowercase())),
+            ),
+            Err(e) => warn!("Failed to list the library of {}: {}", folder.name, e),
+        }
+    }
+    owned
+}
+
+/// Escape a value for an iCalendar TEXT property.
+#[cfg(feature = "server")]
+fn ics_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// All-day events for every release with a full date.
+#[cfg(feature = "server")]
+fn to_ics(releases: &[CalendarRelease]) -> String {
+    let stamp = chrono::Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Soulbeet//Release Calendar//EN".to_string(),
+        "X-WR-CALNAME:Soulbeet releases".to_string(),
+    ];
+    for release in releases.iter().filter(|r| r.has_full_date()) {
+        let kind = release.release_type.as_deref().unwrap_or("Release");
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:{}@soulbeet", release.release_group_id),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", release.date.replace('-', "")),
+            format!(
+                "SUMMARY:{}",
+                ics_escape(&format!("{} - {}", release.artist, release.title))
+            ),
+            format!("DESCRIPTION:{}", ics_escape(kind)),
+            format!(
+                "URL:https://musicbrainz.org/release-group/{}",
+                release.release_group_id
+            ),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+#[get("/api/calendar/artists", auth: AuthSession)]
+pub async fn get_watched_artists() -> Result<Vec<WatchedArtist>, ServerFnError> {
+    WatchedArtist::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// Watch an artist by name; the best MusicBrainz match is used unless the
+/// artist's MBID is given.
+#[post("/api/calendar/artists/add", auth: MemberSession)]
+pub async fn watch_artist(name: String, mbid: Option<String>) -> Result<(), ServerFnError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(server_error("Artist name is required"));
+    }
+    let mbid = match mbid {
+        Some(mbid) => mbid,
+        None => {
+            let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+            soulbeet::http::cached_mbid_lookup(&client, name)
+                .await
+                .map_err(server_error)?
+                .ok_or_else(|| {
+                    server_error(format!("No MusicBrainz artist found for '{}'", name))
+                })?
+        }
+    };
+    WatchedArtist::create(&auth.0.sub, &mbid, name)
+        .await
+        .map_err(server_error)
+}
+
+/// Artists linked to a watched one on MusicBrainz, such as band members
+/// and collaborators, with their albums marked as owned or not.
+#[post("/api/calendar/related", auth: MemberSession)]
+pub async fn get_related_artists(artist_mbid: String) -> Result<Vec<RelatedArtist>, ServerFnError> {
+    let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+    let relations = soulbeet::http::artist_relations(&client, &artist_mbid)
+        .await
+        .map_err(|e| metadata_error("musicbrainz", e))?;
+    let watched: HashSet<String> = WatchedArtist::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .into_iter()
+        .map(|a| a.artist_mbid)
+        .collect();
+    let owned = owned_albums(&auth.0.sub).await;
+
+    let mut related = Vec::new();
+    for relation in relations.into_iter().take(MAX_RELATED) {
+        let groups = release_groups(&relation.mbid).await.unwrap_or_else(|e| {
+            warn!("Failed to fetch releases of {}: {}", relation.name, e);
+            Vec::new()
+        });
+        let artist = relation.name.to_lowercase();
+        let mut albums: Vec<RelatedAlbum> = groups
+            .into_iter()
+            .filter(|g| g.primary_type.as_deref() == Some("Album"))
+            .map(|g| RelatedAlbum {
+                owned: owned.contains(&(artist.clone(), g.title.to_lowercase())),
+                release_group_id: g.id,
+                title: g.title,
+                date: g.first_release_date,
+            })
+            .collect();
+        albums.sort_by(|a, b| a.date.cmp(&b.date));
+        related.push(RelatedArtist {
+            watched: watched.contains(&relation.mbid),
+            mbid: relation.mbid,
+            name: relation.name,
+            relation: relation.relation,
+            albums,
+        });
+    }
+    Ok(related)
+}
+
+#[delete("/api/calendar/artists/delete", auth: MemberSession)]
+pub async fn unwatch_artist(artist_id: String) -> Result<(), ServerFnError> {
+    WatchedArtist::delete(&artist_id, &auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// Releases of watched artists from the last month onwards. The first load
+/// after a restart queries MusicBrainz once per artist, so it can be slow.
+#[get("/api/calendar", auth: AuthSession)]
+pub async fn get_release_calendar() -> Result<Vec<CalendarRelease>, ServerFnError> {
+    calendar_for_user(&auth.0.sub).await.map_err(server_error)
+}
+
+/// Releases of watched artists that are already out, newest first.
+#[get("/api/calendar/hits", auth: AuthSession)]
+pub async fn get_watchlist_hits() -> Result<Vec<CalendarRelease>, ServerFnError> {
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let mut releases = calendar_for_user(&auth.0.sub).await.map_err(server_error)?;
+    // Partial dates sort before the days they cover
+    releases.retain(|r| r.date <= today);
+    releases.reverse();
+    Ok(releases)
+}
+
+#[post("/api/calendar/action", auth: MemberSession)]
+pub async fn set_release_action(
+    release_group_id: String,
+    action: ReleaseAction,
+) -> Result<(), ServerFnError> {
+    let user_id = auth.0.sub;
+    let result = match action {
+        ReleaseAction::Ignore => {
+            ReleaseActionRecord::set(&user_id, &release_group_id, "ignore", None).await
+        }
+        ReleaseAction::RemindLater { days } => {
+            let remind_at = chrono::Utc::now() + chrono::Duration::days(days.max(1) as i64);
+            ReleaseActionRecord::set(
+                &user_id,
+                &release_group_id,
+                "remind",
+                Some(remind_at.timestamp()),
+            )
+            .await
+        }
+        ReleaseAction::Clear => ReleaseActionRecord::clear(&user_id, &release_group_id).await,
+    };
+    result.map_err(server_error)
+}
+
+/// The calendar as an iCalendar file, for a one-off download from the UI.
+#[get("/api/calendar/export", auth: AuthSession)]
+pub async fn export_release_calendar() -> Result<String, ServerFnError> {
+    let releases = calendar_for_user(&auth.0.sub).await.map_err(server_error)?;
+    Ok(to_ics(&releases))
+}
+
+/// `GET /api/calendar.ics?token=<api token>`: subscribable calendar feed.
+#[cfg(feature = "server")]
+pub async fn calendar_feed(
+    ApiTokenSession(user): ApiTokenSession,
+) -> Result<impl axum::response::IntoResponse, (axum::http::StatusCode, String)> {
+    let releases = calendar_for_user(&user.id)
+        .await
+        .map_err(|e| (axum::http::StatusCode::INTERNAL_SERVER_ERROR, e))?;
+    Ok((
+        [(
+            axum::http::header::CONTENT_TYPE,
+            "text/calendar; charset=utf-8",
+        )],
+        to_ics(&releases),
+    ))
+}
@@ -0,0 +1,126 @@
+//! Discogs account link: imported albums are added to the collection and
+//! the wantlist can be copied to the wishlist.
+
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::models::{user_settings::UserSettings, wishlist::WishlistItem};
+#[cfg(feature = "server")]
+use crate::MemberSession;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use soulbeet::discogs::DiscogsClient;
+#[cfg(feature = "server")]
+use std::path::Path;
+
+/// Source recorded on wishlist items copied from the wantlist.
+#[cfg(feature = "server")]
+const WANTLIST_SOURCE: &str = "discogs";
+
+#[cfg(feature = "server")]
+async fn client_for_user(user_id: &str) -> Result<DiscogsClient, String> {
+    let settings = UserSettings::get(user_id).await?;
+    match (settings.discogs_token, settings.discogs_username) {
+        (Some(token), Some(username)) if !token.is_empty() => {
+            Ok(DiscogsClient::new(token, username))
+        }
+        _ => Err("Discogs is not connected".to_string()),
+    }
+}
+
+/// Check a personal access token and link the account it belongs to.
+/// Returns the Discogs username.
+#[post("/api/discogs/connect", auth: MemberSession)]
+pub async fn connect_discogs(token: String) -> Result<String, ServerFnError> {
+    let token = token.trim();
+    if token.is_empty() {
+        return Err(server_error("Enter a Discogs personal access token"));
+    }
+    let client = DiscogsClient::connect(token)
+        .await
+        .map_err(|e| server_error(format!("Discogs rejected the token: {}", e)))?;
+    UserSettings::set_discogs_account(&auth.0.sub, Some((token, client.username())))
+        .await
+        .map_err(server_error)?;
+    Ok(client.username().to_string())
+}
+
+#[post("/api/discogs/disconnect", auth: MemberSession)]
+pub async fn disconnect_discogs() -> Result<(), ServerFnError> {
+    UserSettings::set_discogs_account(&auth.0.sub, None)
+        .await
+        .map_err(server_error)
+}
+
+/// Copy the Discogs wantlist to the wishlist. Albums already wished for,
+/// by artist and title, are skipped; returns how many were added.
+#[post("/api/discogs/wantlist/import", auth: MemberSession)]
+pub async fn import_discogs_wantlist() -> Result<usize, ServerFnError> {
+    let client = client_for_user(&auth.0.sub).await.map_err(server_error)?;
+    let wants = client.wantlist().await.map_err(server_error)?;
+
+    let key =
+        |artist: &str, title: &str| format!("{}\0{}", artist.to_lowercase(), title.to_lowercase());
+    let mut known: std::collections::HashSet<String> = WishlistItem::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .iter()
+        .map(|item| key(&item.artist, &item.title))
+        .collect();
+    let new: Vec<_> = wants
+        .into_iter()
+        .filter(|item| known.insert(key(&item.artist, &item.title)))
+        .collect();
+
+    WishlistItem::add_many(&auth.0.sub, &new, Some(WANTLIST_SOURCE))
+        .await
+        .map_err(server_error)
+}
+
+/// Album artist and album of a download about to be imported, when its
+/// owner collects imports on Discogs. Read before import since the importer
+/// moves the files.
+#[cfg(feature = "server")]
+pub async fn album_to_collect(user_id: &str, source_path: &str) -> Option<(String, String)> {
+    let settings = UserSettings::get(user_id).await.ok()?;
+    if !settings.discogs_sync_collection || settings.discogs_token.is_none() {
+        return None;
+    }
+    let source = Path::new(source_path).to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        soulbeet::tagging::audio_files(&source)
+            .iter()
+            .find_map(|path| soulbeet::tagging::read_tags(path).ok())
+            .and_then(|tags| Some((tags.album_artist.or(tags.artist)?, tags.album?)))
+    })
+    .await
+    .ok()
+    .flatten()
+}
+
+/// Add an imported album to the user's Discogs collection. Failures are
+/// only logged; the import itself went through.
+#[cfg(feature = "server")]
+pub async fn collect_album(user_id: &str, artist: &str, album: &str) {
+    let client = match client_for_user(user_id).await {
+        Ok(client) => client,
+        Err(_) => return,
+    };
+    match client.mark_owned(artist, album).await {
+        Ok(Some(release_id)) => info!(
+            "Added {} - {} to the Discogs collection of {} (release {})",
+            artist,
+            album,
+            client.username(),
+            release_id
+        ),
+        Ok(None) => info!(
+            "{} - {} not added to Discogs: no release found or already owned",
+            artist, album
+        ),
+        Err(e) => warn!("Failed to add {} - {} to Discogs: {}", artist, album, e),
+    }
+}
@@ -43,10 +43,12 @@ use crate::models::folder::Folder;
 #[cfg(feature = "server")]
 use crate::models::user_settings::UserSettings;
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::{AuthSession, MemberSession};
 
 #[cfg(feature = "server")]
-use super::server_error;
+use super::search_history::{record_search_poll, record_search_start};
+#[cfg(feature = "server")]
+use super::{forbidden_error, not_found_error, server_error};
 
 /// Get the current user's discovery configuration
 #[get("/api/discovery/config", auth: AuthSession)]
@@ -91,20 +93,20 @@ pub struct TrackActionRequest {
     pub track_id: String,
 }
 
-#[post("/api/discovery/promote", auth: AuthSession)]
+#[post("/api/discovery/promote", auth: MemberSession)]
 pub async fn promote_discovery_track(req: TrackActionRequest) -> Result<(), ServerFnError> {
     let track = DiscoveryTrackRow::get_by_id(&req.track_id)
         .await
         .map_err(server_error)?
-        .ok_or_else(|| server_error("Track not found"))?;
+        .ok_or_else(|| not_found_error("Track not found"))?;
 
     let folder = Folder::get_by_id(&track.folder_id)
         .await
         .map_err(server_error)?
-        .ok_or_else(|| server_error("Folder not found"))?;
+        .ok_or_else(|| not_found_error("Folder not found"))?;
 
     if folder.user_id != auth.0.sub {
-        return Err(server_error("Not authorized to modify this track"));
+        return Err(forbidden_error("Not authorized to modify this track"));
     }
 
     super::navidrome::promote_discovery_track_internal(&req.track_id, &auth.0.sub)
@@ -112,7 +114,7 @@ pub async fn promote_discovery_track(req: TrackActionRequest) -> Result<(), Serv
         .map_err(server_error)
 }
 
-#[post("/api/discovery/remove", auth: AuthSession)]
+#[post("/api/discovery/remove", auth: MemberSession)]
 pub async fn remove_discovery_track(req: TrackActionRequest) -> Result<(), ServerFnError> {
     #[cfg(feature = "server")]
     use crate::models::discovery_history::DiscoveryHistoryRow;
@@ -120,15 +122,15 @@ pub async fn remove_discovery_track(req: TrackActionRequest) -> Result<(), Serve
     let track = DiscoveryTrackRow::get_by_id(&req.track_id)
         .await
         .map_err(server_error)?
-        .ok_or_else(|| server_error("Track not found"))?;
+        .ok_or_else(|| not_found_error("Track not found"))?;
 
     let folder = Folder::get_by_id(&track.folder_id)
         .await
         .map_err(server_error)?
-        .ok_or_else(|| server_error("Folder not found"))?;
+        .ok_or_else(|| not_found_error("Folder not found"))?;
 
     if folder.user_id != auth.0.sub {
-        return Err(server_error("Not authorized to modify this track"));
+        return Err(forbidden_error("Not authorized to modify this track"));
     }
 
     let path = std::path::Path::new(&track.path);
@@ -160,7 +162,7 @@ pub async fn remove_discovery_track(req: TrackActionRequest) -> Result<(), Serve
     Ok(())
 }
 
-#[post("/api/discovery/start-generation", auth: AuthSession)]
+#[post("/api/discovery/start-generation", auth: MemberSession)]
 pub async fn start_discovery_generation() -> Result<(), ServerFnError> {
     use shared::navidrome::{DiscoveryProgress, GenerationStatus, ProfilePhase, ProfileProgress};
 
@@ -305,6 +307,9 @@ pub async fn generate_discovery_playlist_internal(
     if !settings.discovery_enabled {
         return Err("Discovery is not enabled".to_string());
     }
+    if crate::server_fns::download::pause::is_paused().await {
+        return Err("Downloading is paused".to_string());
+    }
     let folder_id = settings
         .discovery_folder_id
         .as_ref()
@@ -487,6 +492,7 @@ pub async fn generate_discovery_playlist_internal(
                     duration: None,
                     mbid: None,
                     release_mbid: None,
+                    details: Default::default(),
                 }];
 
                 let search_id = match backend.start_search(None, &search_tracks).await {
@@ -508,6 +514,15 @@ pub async fn generate_discovery_playlist_internal(
                         continue;
                     }
                 };
+                record_search_start(
+                    &search_id,
+                    user_id,
+                    &shared::download::DownloadQuery::new(search_tracks),
+                    &format!("{} - {}", candidate.artist, candidate.track),
+                    shared::download::DEFAULT_SEARCH_TIMEOUT_SECS,
+                    true,
+                )
+                .await;
 
                 // Poll until we get results or the search times out (slskd search has 120s timeout).
                 // Each poll_search call long-polls for up to 10s internally.
@@ -516,7 +531,10 @@ pub async fn generate_discovery_playlist_internal(
                 for _ in 0..12 {
                     tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
                     let search_result = match backend.poll_search(&search_id).await {
-                        Ok(r) => r,
+                        Ok(r) => {
+                            record_search_poll(&r).await;
+                            r
+                        }
                         Err(e) => {
                             warn!(
                                 "Poll failed for '{}' - {}: {}",
@@ -776,7 +794,7 @@ pub async fn generate_discovery_playlist_internal(
                 let src = std::path::Path::new(&src_path);
                 if let Ok(ref imp) = importer {
                     match imp.import(&[src], &discovery_target, false).await {
-                        Ok(soulbeet::ImportResult::Success) => {
+                        Ok(soulbeet::ImportResult::Success(_)) => {
                             info!(
                                 "Imported '{}' - {} into Discovery/{}",
                                 qt.artist, qt.track, profile_name
@@ -1018,7 +1036,7 @@ pub async fn reconcile_discovery_playlists(user_id: &str) -> Result<(), String>
     Ok(())
 }
 
-#[post("/api/discovery/generate-recommendations", auth: AuthSession)]
+#[post("/api/discovery/generate-recommendations", auth: MemberSession)]
 pub async fn generate_recommendations() -> Result<u32, ServerFnError> {
     generate_recommendations_internal(&auth.0.sub)
         .await
@@ -1085,7 +1103,7 @@ fn find_new_files(
 pub async fn import_or_move(src: &std::path::Path, target: &std::path::Path) -> Result<(), String> {
     match crate::services::music_importer(None).await {
         Ok(imp) => match imp.import(&[src], target, false).await {
-            Ok(soulbeet::ImportResult::Success) => Ok(()),
+            Ok(soulbeet::ImportResult::Success(_)) => Ok(()),
             Ok(soulbeet::ImportResult::Skipped) => {
                 Err("Beets skipped track (duplicate?)".to_string())
             }
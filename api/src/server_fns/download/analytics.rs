@@ -0,0 +1,55 @@
+//! Where the search results users download ranked, and how they ended, to
+//! see whether the ranking puts the right candidate first.
+
+use dioxus::prelude::*;
+use shared::analytics::MatchingReport;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+#[cfg(feature = "server")]
+use shared::download::{DownloadableGroup, DownloadableItem};
+
+#[cfg(feature = "server")]
+use crate::models::match_pick::MatchPick;
+#[cfg(feature = "server")]
+use crate::server_fns::server_error;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+
+/// Record the rank of each of `items` among `groups`, best first. Items not
+/// found in them are left out.
+#[cfg(feature = "server")]
+pub(crate) async fn record_picks(
+    items: &[DownloadableItem],
+    groups: &[DownloadableGroup],
+    automatic: bool,
+) {
+    let Some(top_score) = groups.first().map(|g| g.score) else {
+        return;
+    };
+    for item in items {
+        let Some((rank, group)) = groups
+            .iter()
+            .enumerate()
+            .find(|(_, g)| g.source == item.source && g.items.iter().any(|i| i.id == item.id))
+        else {
+            continue;
+        };
+        let pick = MatchPick {
+            peer: &item.source,
+            item: &item.id,
+            rank,
+            score: group.score,
+            top_score,
+            automatic,
+        };
+        if let Err(e) = pick.record().await {
+            warn!("Could not record the pick of {}: {}", item.id, e);
+        }
+    }
+}
+
+#[get("/api/settings/matching-report", _: AuthSession)]
+pub async fn get_matching_report() -> Result<MatchingReport, ServerFnError> {
+    MatchPick::report().await.map_err(server_error)
+}
@@ -4,9 +4,13 @@ use shared::download::DownloadQuery;
 
 #[cfg(feature = "server")]
 use shared::download::{
-    AutoDownloadEvent, DownloadEvent, DownloadProgress, DownloadableGroup, SearchState,
+    AutoDownloadEvent, DownloadEvent, DownloadProgress, DownloadableGroup, ExpectedRelease,
+    ImportOverrides, SearchOptions, SearchState,
 };
 
+#[cfg(feature = "server")]
+use shared::quality::SearchFilters;
+
 #[cfg(feature = "server")]
 use dioxus::logger::tracing::{info, warn};
 #[cfg(feature = "server")]
@@ -15,11 +19,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 #[cfg(feature = "server")]
-use crate::globals::{get_or_create_user_channel, register_user_task, unregister_user_task};
+use crate::globals::{get_or_create_user_channel, register_user_task};
+#[cfg(feature = "server")]
+use crate::models::{user::User, user_settings::UserSettings};
 #[cfg(feature = "server")]
 use crate::services::{available_download_backends, download_backend};
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::server_fns::search::effective_match_mode;
+#[cfg(feature = "server")]
+use crate::server_fns::search_history::{record_search_poll, record_search_start};
+#[cfg(feature = "server")]
+use crate::server_fns::validate;
+#[cfg(feature = "server")]
+use crate::MemberSession;
 
 #[cfg(feature = "server")]
 use super::monitor::DownloadMonitor;
@@ -42,8 +54,19 @@ pub struct AutoDownloadRequest {
     pub query: DownloadQuery,
     /// Folder ID used for persisting as default_download_folder_id (per D-01)
     pub folder_id: String,
-    /// Resolved folder path for the actual download target directory
+    /// Path of that folder. Filled in by the server from `folder_id`;
+    /// whatever a client sends is ignored.
+    #[serde(default)]
     pub folder_path: String,
+    /// Dry run: search and pick a source, then report the plan instead of
+    /// downloading
+    #[serde(default)]
+    pub simulate: bool,
+    /// Peer of an imported copy this download replaces. Its results are
+    /// left out, and the files go to the folder whatever the routing rules
+    /// say.
+    #[serde(default)]
+    pub replacing_peer: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,14 +77,42 @@ pub enum AutoDownloadResult {
     Error(String),
 }
 
-#[post("/api/auto-download", auth: AuthSession)]
+#[post("/api/auto-download", auth: MemberSession)]
 pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResult, ServerFnError> {
-    let username = auth.0.username;
+    let mut req = req;
+    validate::download_query(&req.query)?;
+    if req.simulate {
+        crate::server_fns::features::require(shared::features::flags::DRY_RUN).await?;
+    }
+    req.folder_path = validate::folder(&auth.0.sub, &req.folder_id).await?.path;
+    req.query.match_mode =
+        Some(effective_match_mode(&auth.0.sub, req.query.match_mode).await);
+    Ok(run_auto_download(auth.0.username, req).await)
+}
+
+/// The search filters of the user's quality profile.
+#[cfg(feature = "server")]
+async fn user_search_filters(username: &str) -> SearchFilters {
+    let Ok(Some(user)) = User::get_by_username(username).await else {
+        return SearchFilters::default();
+    };
+    UserSettings::get(&user.id)
+        .await
+        .map(|s| s.quality_profile().search_filters)
+        .unwrap_or_default()
+}
+
+/// Start the search-score-pick-download pipeline for a user. Progress is
+/// reported through their download channel.
+#[cfg(feature = "server")]
+pub(crate) async fn run_auto_download(
+    username: String,
+    mut req: AutoDownloadRequest,
+) -> AutoDownloadResult {
     let (tx, _) = get_or_create_user_channel(&username).await;
 
-    let mut req = req;
     if let Err(e) = crate::server_fns::search::hydrate_album_tracks(&mut req.query).await {
-        return Ok(AutoDownloadResult::Error(e));
+        return AutoDownloadResult::Error(e);
     }
 
     // Build search description for logging and events
@@ -106,9 +157,7 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
             batch_id,
             error: "No download backends available".to_string(),
         }));
-        return Ok(AutoDownloadResult::Error(
-            "No download backends available".to_string(),
-        ));
+        return AutoDownloadResult::Error("No download backends available".to_string());
     }
 
     // Generate batch_id upfront so we can return it immediately (per D-10)
@@ -117,9 +166,33 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
 
     // Spawn the entire search-score-pick-download pipeline onto a background task.
     // This avoids blocking the HTTP response during the search-poll loop (Research Pitfall 4).
-    let folder_path = req.folder_path.clone();
+    // A routing rule for the release's genre overrides the folder and quality
+    let route = crate::server_fns::routing::route(&username, &req.query).await;
+    for step in &route.trace.steps {
+        info!("Routing '{}': {}", query_desc, step);
+    }
+    let folder_path = match (&route.folder, &req.replacing_peer) {
+        (Some(folder), None) => folder.path.clone(),
+        _ => req.folder_path.clone(),
+    };
     let album = req.query.album.clone();
     let tracks = req.query.tracks.clone();
+    let import_overrides = ImportOverrides {
+        release: album.as_ref().and_then(ExpectedRelease::from_album),
+        ..Default::default()
+    };
+    let min_score = req.query.match_mode.unwrap_or_default().min_score();
+    let filters = route.filters(user_search_filters(&username).await);
+    let strategy = req.query.search_strategy;
+    // Searches are kept in the user's search history
+    let user_id = User::get_by_username(&username)
+        .await
+        .ok()
+        .flatten()
+        .map(|u| u.id);
+    let history_query = req.query.clone();
+    let simulate = req.simulate;
+    let replacing_peer = req.replacing_peer.clone();
     let task_username = username.clone();
 
     tokio::spawn(async move {
@@ -144,15 +217,38 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
                 let backend = Arc::clone(backend);
                 let album = album.clone();
                 let tracks = tracks.clone();
+                let user_id = user_id.clone();
+                let history_query = &history_query;
+                let query_desc = &query_desc;
                 async move {
                     // Start search
-                    let search_id = match backend.start_search(album.as_ref(), &tracks).await {
+                    let options = SearchOptions {
+                        min_score,
+                        filters,
+                        strategy,
+                        ..Default::default()
+                    };
+                    let search_id = match backend
+                        .start_search_with_options(album.as_ref(), &tracks, &options)
+                        .await
+                    {
                         Ok(sid) => sid,
                         Err(e) => {
                             warn!("Backend {} search start failed: {}", id, e);
                             return (id, Vec::<DownloadableGroup>::new());
                         }
                     };
+                    if let Some(user_id) = &user_id {
+                        record_search_start(
+                            &search_id,
+                            user_id,
+                            history_query,
+                            query_desc,
+                            options.timeout_secs,
+                            true,
+                        )
+                        .await;
+                    }
 
                     // Poll until results are ready or timeout. Polls that report
                     // InProgress carry the groups processed so far (the backend
@@ -169,22 +265,25 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
                         tokio::time::sleep(SEARCH_POLL_INTERVAL).await;
 
                         match backend.poll_search(&search_id).await {
-                            Ok(result) => match result.state {
-                                SearchState::Completed | SearchState::TimedOut => {
-                                    if !result.groups.is_empty() {
-                                        latest_groups = result.groups;
+                            Ok(result) => {
+                                record_search_poll(&result).await;
+                                match result.state {
+                                    SearchState::Completed | SearchState::TimedOut => {
+                                        if !result.groups.is_empty() {
+                                            latest_groups = result.groups;
+                                        }
+                                        return (id, latest_groups);
                                     }
-                                    return (id, latest_groups);
-                                }
-                                SearchState::NotFound => {
-                                    return (id, latest_groups);
-                                }
-                                SearchState::InProgress => {
-                                    if !result.groups.is_empty() {
-                                        latest_groups = result.groups;
+                                    SearchState::NotFound => {
+                                        return (id, latest_groups);
+                                    }
+                                    SearchState::InProgress => {
+                                        if !result.groups.is_empty() {
+                                            latest_groups = result.groups;
+                                        }
                                     }
                                 }
-                            },
+                            }
                             Err(e) => {
                                 warn!("Backend {} poll error: {}", id, e);
                                 return (id, latest_groups);
@@ -202,6 +301,7 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
         let mut all_groups: Vec<DownloadableGroup> = results
             .into_iter()
             .flat_map(|(_backend_id, groups)| groups)
+            .filter(|g| replacing_peer.as_ref() != Some(&g.source))
             .collect();
 
         if all_groups.is_empty() {
@@ -216,6 +316,7 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
         // Sort by score descending
         all_groups
             .sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        super::feedback::rank_by_reputation(&mut all_groups).await;
 
         let best_score = all_groups[0].score;
 
@@ -275,6 +376,22 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
 
         let batch_label = picked.title.clone();
 
+        if simulate {
+            super::simulate::report_plan(
+                &task_username,
+                &picked.items,
+                std::path::Path::new(&folder_path),
+                Some((batch_id.clone(), batch_label)),
+            )
+            .await;
+            return;
+        }
+
+        super::analytics::record_picks(&picked.items, std::slice::from_ref(&picked), true).await;
+
+        // Held here while downloading is paused
+        super::pause::wait_until_resumed().await;
+
         // Create target directory
         let target_path_buf = std::path::Path::new(&folder_path).to_path_buf();
         if let Err(e) = tokio::fs::create_dir_all(&target_path_buf).await {
@@ -298,8 +415,20 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
             }
         };
 
-        let queued = match backend.download(items).await {
-            Ok(q) => q,
+        super::bulk::remember_for_retry(
+            &task_username,
+            &items,
+            &target_path_buf,
+            None,
+            &import_overrides,
+        )
+        .await;
+
+        let queued = match backend.download(items.clone()).await {
+            // The other sources found stand in for files the picked one refuses
+            Ok(q) => {
+                super::fallback::substitute_refused(backend.as_ref(), q, &items, &all_groups).await
+            }
             Err(e) => {
                 let _ = tx.send(DownloadEvent::AutoDownload(AutoDownloadEvent::Failed {
                     batch_id: batch_id.clone(),
@@ -366,7 +495,7 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
         // Register task and run monitor (per D-05: normal DownloadProgress takes over)
         let task_cancellation = register_user_task(&task_username).await;
 
-        let mut monitor = DownloadMonitor::new(
+        let monitor = DownloadMonitor::new(
             download_sources,
             download_filenames,
             target_path_buf,
@@ -375,12 +504,12 @@ pub async fn auto_download(req: AutoDownloadRequest) -> Result<AutoDownloadResul
             task_username.clone(),
             Some(batch_id),
             Some(batch_label),
-        );
-        monitor.run().await;
-        unregister_user_task(&task_username).await;
+        )
+        .with_import_overrides(import_overrides);
+        super::monitor::spawn_supervised(monitor);
     });
 
-    Ok(AutoDownloadResult::Accepted {
+    AutoDownloadResult::Accepted {
         batch_id: batch_id_for_response,
-    })
+    }
 }
@@ -0,0 +1,238 @@
+//! Actions on many downloads at once, for the multi-select in the Downloads
+//! panel.
+
+use dioxus::prelude::*;
+use shared::download::QueuedDownload;
+
+use super::CancelDownloadRequest;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::download::{
+    DownloadEvent, DownloadProgress, DownloadState, DownloadableItem, ImportOverrides,
+};
+#[cfg(feature = "server")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "server")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+
+#[cfg(feature = "server")]
+use super::queue_downloads;
+#[cfg(feature = "server")]
+use crate::globals::{get_or_create_user_channel, USER_CHANNELS};
+#[cfg(feature = "server")]
+use crate::services::download_backend;
+#[cfg(feature = "server")]
+use crate::{
+    server_fns::{server_error, slskd_error},
+    MemberSession,
+};
+
+/// Downloads remembered per user for retrying; the oldest are dropped first.
+#[cfg(feature = "server")]
+const RETRY_HISTORY_LIMIT: usize = 2000;
+
+/// How a download was queued, so it can be queued again as is.
+#[cfg(feature = "server")]
+struct RetryContext {
+    item: DownloadableItem,
+    target_path: PathBuf,
+    backend: Option<String>,
+    overrides: ImportOverrides,
+    queued_at: i64,
+}
+
+/// Retry contexts per username, keyed by download item.
+#[cfg(feature = "server")]
+static RETRY_CONTEXTS: LazyLock<RwLock<HashMap<String, HashMap<String, RetryContext>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Keep what is needed to queue these items again later.
+#[cfg(feature = "server")]
+pub(crate) async fn remember_for_retry(
+    username: &str,
+    items: &[DownloadableItem],
+    target_path: &Path,
+    backend: Option<&str>,
+    overrides: &ImportOverrides,
+) {
+    let now = chrono::Utc::now().timestamp();
+    let mut contexts = RETRY_CONTEXTS.write().await;
+    let user_contexts = contexts.entry(username.to_string()).or_default();
+    for item in items {
+        user_contexts.insert(
+            item.id.clone(),
+            RetryContext {
+                item: item.clone(),
+                target_path: target_path.to_path_buf(),
+                backend: backend.map(str::to_string),
+                overrides: overrides.clone(),
+                queued_at: now,
+            },
+        );
+    }
+
+    if user_contexts.len() > RETRY_HISTORY_LIMIT {
+        let mut ages: Vec<i64> = user_contexts.values().map(|c| c.queued_at).collect();
+        ages.sort_unstable();
+        let cutoff = ages[user_contexts.len() - RETRY_HISTORY_LIMIT];
+        user_contexts.retain(|_, c| c.queued_at >= cutoff);
+    }
+}
+
+/// Cancel several downloads. Returns how many were cancelled; failures are
+/// logged and skipped so one stale transfer doesn't block the rest.
+#[post("/api/downloads/cancel_many", auth: MemberSession)]
+pub async fn cancel_downloads(reqs: Vec<CancelDownloadRequest>) -> Result<usize, ServerFnError> {
+    let username = auth.0.username;
+
+    let mut cancelled = Vec::new();
+    for req in reqs {
+        let backend = download_backend(req.backend.as_deref())
+            .await
+            .map_err(|e| slskd_error(format!("download backend not available: {}", e)))?;
+        if let Err(e) = backend.cancel_download(&req.source, &req.id, false).await {
+            warn!("Could not cancel {} from {}: {}", req.id, req.source, e);
+            continue;
+        }
+        let mut progress = DownloadProgress::queued(req.id, req.source, req.item, 0)
+            .with_state(DownloadState::Cancelled);
+        progress.backend = req.backend;
+        cancelled.push(progress);
+    }
+
+    info!(
+        "User {} cancelled {} download(s)",
+        username,
+        cancelled.len()
+    );
+    let count = cancelled.len();
+    if !cancelled.is_empty() {
+        let (tx, _) = get_or_create_user_channel(&username).await;
+        let _ = tx.send(DownloadEvent::Progress(cancelled));
+    }
+    Ok(count)
+}
+
+/// Queue downloads again with the folder, backend and import options they
+/// were first queued with. `items` are the download items shown in the panel.
+/// Those already retried, from another tab say, are skipped.
+#[post("/api/downloads/retry", auth: MemberSession)]
+pub async fn retry_downloads(items: Vec<String>) -> Result<Vec<QueuedDownload>, ServerFnError> {
+    let username = auth.0.username;
+
+    // Taken out of the history while they are queued again, so the same
+    // retry sent from another tab finds nothing to do. Those the channel
+    // already shows as active were retried before.
+    let busy = active_items(&username, &items).await;
+    let taken: Vec<(String, RetryContext)> = {
+        let mut contexts = RETRY_CONTEXTS.write().await;
+        let Some(user_contexts) = contexts.get_mut(&username) else {
+            return Err(server_error(
+                "Nothing to retry; these downloads were queued before the last restart",
+            ));
+        };
+        items
+            .iter()
+            .filter(|item| !busy.contains(*item))
+            .filter_map(|item| user_contexts.remove_entry(item))
+            .collect()
+    };
+
+    // Downloads queued together usually share their options: queue each
+    // set of options as one batch
+    let mut batches: Vec<(
+        PathBuf,
+        Option<String>,
+        ImportOverrides,
+        Vec<DownloadableItem>,
+    )> = Vec::new();
+    for (_, context) in &taken {
+        match batches.iter_mut().find(|(target, backend, overrides, _)| {
+            *target == context.target_path
+                && *backend == context.backend
+                && *overrides == context.overrides
+        }) {
+            Some((_, _, _, batch)) => batch.push(context.item.clone()),
+            None => batches.push((
+                context.target_path.clone(),
+                context.backend.clone(),
+                context.overrides.clone(),
+                vec![context.item.clone()],
+            )),
+        }
+    }
+
+    if batches.is_empty() {
+        if !busy.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Err(server_error(
+            "Nothing to retry; these downloads were queued before the last restart",
+        ));
+    }
+
+    info!("User {} retrying {} download(s)", username, taken.len());
+    let mut queued = Vec::new();
+    let mut result = Ok(());
+    for (target_path, backend, overrides, batch) in batches {
+        match queue_downloads(username.clone(), batch, target_path, backend, overrides).await {
+            Ok(q) => queued.extend(q),
+            Err(e) => {
+                result = Err(e);
+                break;
+            }
+        }
+    }
+
+    // Queueing remembers the downloads again; put back the ones it didn't
+    // get to, to be retried later
+    {
+        let mut contexts = RETRY_CONTEXTS.write().await;
+        let user_contexts = contexts.entry(username).or_default();
+        for (item, context) in taken {
+            user_contexts.entry(item).or_insert(context);
+        }
+    }
+    result.map(|()| queued)
+}
+
+/// The items among `items` the user's channel last showed as active.
+#[cfg(feature = "server")]
+async fn active_items(username: &str, items: &[String]) -> HashSet<String> {
+    let snapshot = USER_CHANNELS
+        .read()
+        .await
+        .get(username)
+        .map(|channel| channel.snapshot.clone());
+    let Some(snapshot) = snapshot else {
+        return HashSet::new();
+    };
+    let entries = snapshot.lock().unwrap_or_else(|e| e.into_inner()).entries();
+    entries
+        .into_iter()
+        .filter(|e| items.contains(&e.item) && crate::activity::is_active(&e.state))
+        .map(|e| e.item)
+        .collect()
+}
+
+/// Forget cleared downloads so they can no longer be retried and aren't
+/// sent to clients that reconnect, and drop them from the user's other tabs.
+#[post("/api/downloads/clear", auth: MemberSession)]
+pub async fn clear_downloads(items: Vec<String>) -> Result<(), ServerFnError> {
+    let username = auth.0.username;
+    if let Some(user_contexts) = RETRY_CONTEXTS.write().await.get_mut(&username) {
+        for item in &items {
+            user_contexts.remove(item);
+        }
+    }
+    crate::globals::forget_downloads(&username, &items).await;
+    let (tx, _) = get_or_create_user_channel(&username).await;
+    let _ = tx.send(DownloadEvent::Cleared(items));
+    Ok(())
+}
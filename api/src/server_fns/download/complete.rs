@@ -0,0 +1,142 @@
+//! Filing the missing tracks of a partial album next to the ones already
+//! in the library. The importer would treat them as a new, incomplete
+//! release and file them as "Album (2)", so they are tagged with the
+//! album's own tags and their number on the release, named like the tracks
+//! around them, moved into its directory and then recorded by the importer
+//! where they are.
+
+use dioxus::logger::tracing::{info, warn};
+use shared::download::{
+    AlbumMerge, DownloadEvent, DownloadProgress, DownloadState, ImportEdits, ImportOverrides,
+};
+use shared::matching::leading_track_number;
+use std::path::{Path, PathBuf};
+
+use super::non_canonical::{move_file, sanitize_segment};
+use crate::globals::UserSender;
+use crate::services::music_importer;
+
+/// File name for a track moved into the album, following the "NN Title"
+/// naming of the tracks already there when they use it.
+fn file_name(file: &Path, title: Option<&str>, number: Option<u32>, numbered: bool) -> PathBuf {
+    let original = PathBuf::from(file.file_name().unwrap_or_default());
+    let (Some(title), Some(number), true) = (title, number, numbered) else {
+        return original;
+    };
+    let name = format!("{:02} {}", number, sanitize_segment(title));
+    match file.extension() {
+        Some(ext) => PathBuf::from(name).with_extension(ext),
+        None => PathBuf::from(name),
+    }
+}
+
+/// Tag, number and move every audio file of the group into the album
+/// directory. Returns the files moved; files whose name is already taken
+/// are left in place.
+fn tag_and_merge(source: &Path, merge: &AlbumMerge) -> Result<Vec<PathBuf>, String> {
+    let album_dir = Path::new(&merge.path);
+    let existing = soulbeet::tagging::audio_files(album_dir);
+    let owned = existing
+        .first()
+        .and_then(|f| soulbeet::tagging::read_tags(f).ok())
+        .ok_or_else(|| format!("No readable tracks in {}", album_dir.display()))?;
+    let numbered = existing.iter().any(|f| {
+        f.file_name()
+            .and_then(|n| leading_track_number(&n.to_string_lossy()))
+            .is_some()
+    });
+
+    let mut moved = Vec::new();
+    for file in soulbeet::tagging::audio_files(source) {
+        let key = file.to_string_lossy().to_string();
+        let title = soulbeet::tagging::read_tags(&file)
+            .ok()
+            .and_then(|t| t.title);
+        let number = title.as_deref().and_then(|t| merge.track_number(t));
+
+        // Track artists and titles come from the downloaded files
+        let mut edits = ImportEdits {
+            album_artist: owned.album_artist.clone().or_else(|| owned.artist.clone()),
+            album: owned.album.clone(),
+            year: owned.year,
+            ..Default::default()
+        };
+        if let Some(number) = number {
+            edits.track_numbers.insert(key, number);
+        }
+        soulbeet::tagging::write_tags(&file, &edits, None)?;
+
+        let dest = album_dir.join(file_name(&file, title.as_deref(), number, numbered));
+        if dest.exists() {
+            warn!("{} already exists, skipping", dest.display());
+            continue;
+        }
+        move_file(&file, &dest).map_err(|e| format!("Failed to move {}: {}", file.display(), e))?;
+        moved.push(dest);
+    }
+    Ok(moved)
+}
+
+pub async fn import_into_album(
+    entries: Vec<DownloadProgress>,
+    source_path: String,
+    merge: &AlbumMerge,
+    overrides: &ImportOverrides,
+    tx: UserSender,
+) -> DownloadState {
+    let send_state = |state: DownloadState| {
+        let error = match &state {
+            DownloadState::Failed(e) => Some(e.clone()),
+            _ => None,
+        };
+        let updated: Vec<_> = entries
+            .iter()
+            .map(|e| DownloadProgress {
+                state: state.clone(),
+                error: error.clone(),
+                ..e.clone()
+            })
+            .collect();
+        let _ = tx.send(DownloadEvent::Progress(updated));
+    };
+    send_state(DownloadState::Importing);
+
+    let source = PathBuf::from(&source_path);
+    let task_merge = merge.clone();
+    let result = tokio::task::spawn_blocking(move || tag_and_merge(&source, &task_merge))
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()));
+
+    let outcome = match result {
+        Ok(moved) if moved.is_empty() => {
+            info!("Nothing new to add to {}", merge.path);
+            DownloadState::ImportSkipped
+        }
+        Ok(moved) => {
+            info!("Added {} track(s) to {}", moved.len(), merge.path);
+            // The files are in place either way; a stale index only hides them
+            let files: Vec<&Path> = moved.iter().map(PathBuf::as_path).collect();
+            match music_importer(overrides.importer.as_deref()).await {
+                Ok(importer) => {
+                    if let Err(e) = importer.add_to_album(Path::new(&merge.path), &files).await {
+                        warn!("Could not record the new tracks of {}: {}", merge.path, e);
+                    }
+                }
+                Err(e) => warn!("Could not record the new tracks of {}: {}", merge.path, e),
+            }
+            DownloadState::Imported
+        }
+        Err(e) => {
+            warn!("Completing {} failed: {}", merge.path, e);
+            let failed = DownloadState::Failed(format!("Import failed: {e}"));
+            send_state(failed.clone());
+            return failed;
+        }
+    };
+    send_state(outcome.clone());
+
+    if let Some(parent) = Path::new(&source_path).parent() {
+        let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+    }
+    outcome
+}
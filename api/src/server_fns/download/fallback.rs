@@ -0,0 +1,157 @@
+//! When a peer refuses to queue some files, the same tracks are asked of the
+//! next best peers of the search instead of failing them right away. The
+//! results of each user's latest search are kept for this.
+
+use dioxus::logger::tracing::{info, warn};
+use shared::download::{
+    DownloadableGroup, DownloadableItem, QueuedDownload, SearchResult, Substitution,
+};
+use shared::matching::substitute_for;
+use soulbeet::DownloadBackend;
+use std::time::Instant;
+
+use crate::globals::{LatestSearch, LATEST_SEARCHES, SEARCH_SESSIONS};
+
+/// Other peers a refused file is asked of before it is given up on.
+const MAX_SUBSTITUTE_PEERS: usize = 3;
+
+/// Keep freshly polled results of a user's search, replacing those of any
+/// earlier search.
+pub async fn remember_results(username: &str, result: &SearchResult) {
+    let mut searches = LATEST_SEARCHES.write().await;
+    let search = searches
+        .entry(username.to_string())
+        .or_insert_with(|| LatestSearch {
+            search_id: result.search_id.clone(),
+            groups: Vec::new(),
+            updated: Instant::now(),
+        });
+    if search.search_id != result.search_id {
+        search.search_id = result.search_id.clone();
+        search.groups.clear();
+    }
+    for group in &result.groups {
+        match search
+            .groups
+            .iter()
+            .position(|g| g.source == group.source && g.group_id == group.group_id)
+        {
+            Some(pos) => search.groups[pos] = group.clone(),
+            None => search.groups.push(group.clone()),
+        }
+    }
+    search.groups.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    search.updated = Instant::now();
+}
+
+/// Results a download of the user may fall back on: those of their latest
+/// search, then those of the shared searches they take part in.
+pub async fn candidates(username: &str) -> Vec<DownloadableGroup> {
+    let mut groups = LATEST_SEARCHES
+        .read()
+        .await
+        .get(username)
+        .map(|s| s.groups.clone())
+        .unwrap_or_default();
+    for session in SEARCH_SESSIONS.read().await.values() {
+        if session.participants.contains(username) {
+            groups.extend(session.groups.iter().cloned());
+        }
+    }
+    groups
+}
+
+/// Replace the refused entries of `queued` by the same tracks queued from
+/// other peers among `candidates`, each recording the file it stands in
+/// for. Entries no peer would take stay failed.
+pub async fn substitute_refused(
+    backend: &dyn DownloadBackend,
+    mut queued: Vec<QueuedDownload>,
+    items: &[DownloadableItem],
+    candidates: &[DownloadableGroup],
+) -> Vec<QueuedDownload> {
+    if candidates.is_empty() || queued.iter().all(|q| q.is_success()) {
+        return queued;
+    }
+
+    // The requested file and why it was refused, for each failed entry
+    let refusals: Vec<Option<(&DownloadableItem, Substitution)>> = queued
+        .iter()
+        .map(|q| {
+            let error = q.error.clone()?;
+            let item = items
+                .iter()
+                .find(|i| i.id == q.id && i.source == q.source)?;
+            Some((
+                item,
+                Substitution {
+                    source: q.source.clone(),
+                    item: q.item.clone(),
+                    error,
+                },
+            ))
+        })
+        .collect();
+    let mut refused: Vec<String> = queued
+        .iter()
+        .filter(|q| !q.is_success())
+        .map(|q| q.source.clone())
+        .collect();
+
+    for _ in 0..MAX_SUBSTITUTE_PEERS {
+        let mut attempts: Vec<(usize, DownloadableItem)> = Vec::new();
+        for (i, refusal) in refusals.iter().enumerate() {
+            let Some((item, _)) = refusal else { continue };
+            if queued[i].is_success() {
+                continue;
+            }
+            if let Some(substitute) = substitute_for(item, candidates, &refused) {
+                if !attempts.iter().any(|(_, a)| a.id == substitute.id) {
+                    attempts.push((i, substitute.clone()));
+                }
+            }
+        }
+        if attempts.is_empty() {
+            break;
+        }
+
+        let results = match backend
+            .download(attempts.iter().map(|(_, a)| a.clone()).collect())
+            .await
+        {
+            Ok(results) => results,
+            Err(e) => {
+                warn!("Could not queue substitutes for refused files: {}", e);
+                break;
+            }
+        };
+        for (i, attempt) in &attempts {
+            let Some(result) = results
+                .iter()
+                .find(|r| r.id == attempt.id && r.source == attempt.source)
+            else {
+                continue;
+            };
+            if !result.is_success() {
+                refused.push(result.source.clone());
+                continue;
+            }
+            let Some((_, substitution)) = &refusals[*i] else {
+                continue;
+            };
+            info!(
+                "{} refused {}, queued from {} instead",
+                substitution.source, substitution.item, result.source
+            );
+            queued[*i] = QueuedDownload {
+                substituted: Some(substitution.clone()),
+                ..result.clone()
+            };
+        }
+    }
+    queued
+}
@@ -0,0 +1,147 @@
+//! One stream of binary socket frames per user, shared by all their tabs.
+//!
+//! Each socket used to subscribe to the user channel and diff the events
+//! on its own, so every open tab repeated the same work. A feed is started
+//! with the user's first socket: it follows the channel once, encodes the
+//! frames once and fans them out. A socket joining later is sent the
+//! snapshot whole, then the shared frames. The feed stops with the last
+//! socket.
+
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use dioxus::logger::tracing::{debug, warn};
+use shared::updates::{DeltaEncoder, UpdateFrame, PROTOCOL_VERSION};
+use tokio::sync::{broadcast, RwLock};
+use tokio_util::sync::CancellationToken;
+
+use super::SocketUpdates;
+use crate::globals::DownloadSnapshot;
+
+/// Frames a feed holds for a slow socket before it lags.
+const FEED_CAPACITY: usize = 256;
+
+struct Feed {
+    frames: broadcast::Sender<UpdateFrame>,
+    snapshot: Arc<Mutex<DownloadSnapshot>>,
+    stop: CancellationToken,
+}
+
+/// Running feeds keyed by username.
+static FEEDS: LazyLock<RwLock<HashMap<String, Feed>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// One socket's place on its user's feed.
+pub(super) struct FeedSubscription {
+    username: String,
+    frames: broadcast::Receiver<UpdateFrame>,
+    snapshot: Arc<Mutex<DownloadSnapshot>>,
+}
+
+impl FeedSubscription {
+    /// Join `username`'s feed, starting one if none is running. Subscribed
+    /// before the snapshot is read: updates reach the snapshot before they
+    /// are sent, so each one is in the snapshot or still to come as a frame.
+    pub(super) async fn join(username: &str) -> Self {
+        let mut feeds = FEEDS.write().await;
+        if feeds
+            .get(username)
+            .is_none_or(|feed| feed.stop.is_cancelled())
+        {
+            let updates = SocketUpdates::subscribe(username).await;
+            let (frames, _) = broadcast::channel(FEED_CAPACITY);
+            let feed = Feed {
+                frames: frames.clone(),
+                snapshot: updates.snapshot.clone(),
+                stop: CancellationToken::new(),
+            };
+            tokio::spawn(run(updates, frames, feed.stop.clone()));
+            debug!("Started the download updates feed of {}", username);
+            feeds.insert(username.to_string(), feed);
+        }
+        let feed = &feeds[username];
+        Self {
+            username: username.to_string(),
+            frames: feed.frames.subscribe(),
+            snapshot: feed.snapshot.clone(),
+        }
+    }
+
+    /// What a socket is sent first, and again after it fell behind: the
+    /// greeting, which resets the client's state, and every download whole.
+    pub(super) fn catch_up(&self) -> Vec<UpdateFrame> {
+        let events = self
+            .snapshot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .events();
+        let mut encoder = DeltaEncoder::default();
+        std::iter::once(UpdateFrame::Hello {
+            version: PROTOCOL_VERSION,
+        })
+        .chain(events.into_iter().filter_map(|e| encoder.encode(e)))
+        .collect()
+    }
+
+    /// Next frames to send, `None` once the feed is gone.
+    pub(super) async fn next(&mut self) -> Option<Vec<UpdateFrame>> {
+        match self.frames.recv().await {
+            Ok(frame) => Some(vec![frame]),
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                warn!(
+                    "Download updates socket lagged, skipped {} frames. Resending the snapshot.",
+                    skipped
+                );
+                Some(self.catch_up())
+            }
+            Err(broadcast::error::RecvError::Closed) => None,
+        }
+    }
+
+    /// Leave the feed, stopping it if this was the user's last socket.
+    pub(super) async fn leave(self) {
+        let Self {
+            username, frames, ..
+        } = self;
+        let mut feeds = FEEDS.write().await;
+        drop(frames);
+        if feeds
+            .get(&username)
+            .is_some_and(|feed| feed.frames.receiver_count() == 0)
+        {
+            if let Some(feed) = feeds.remove(&username) {
+                feed.stop.cancel();
+                debug!("Stopped the download updates feed of {}", username);
+            }
+        }
+    }
+}
+
+/// Follow the user channel and send each event, encoded, to the sockets.
+async fn run(
+    mut updates: SocketUpdates,
+    frames: broadcast::Sender<UpdateFrame>,
+    stop: CancellationToken,
+) {
+    let mut encoder = DeltaEncoder::default();
+    // Sockets are sent the snapshot as they join; the encoder only needs
+    // to know they have it
+    for event in updates.pending.drain(..) {
+        encoder.encode(event);
+    }
+
+    loop {
+        let event = tokio::select! {
+            _ = stop.cancelled() => break,
+            event = updates.next() => event,
+        };
+        let Some(event) = event else { break };
+        if let Some(frame) = encoder.encode(event) {
+            // No socket listening between the last one leaving and the
+            // feed being stopped
+            let _ = frames.send(frame);
+        }
+    }
+    // Makes the next socket start a new feed if the channel went away
+    stop.cancel();
+}
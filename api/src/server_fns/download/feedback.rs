@@ -0,0 +1,97 @@
+//! Users' verdicts on finished downloads. Complaints about a peer lower the
+//! score of its results in later searches, and a bad import can be removed
+//! and put back on the wishlist to be searched for again.
+
+use dioxus::prelude::*;
+use shared::download::DownloadFeedback;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::download::DownloadableGroup;
+#[cfg(feature = "server")]
+use shared::matching::apply_reputation;
+#[cfg(feature = "server")]
+use shared::wishlist::NewWishlistItem;
+
+#[cfg(feature = "server")]
+use crate::models::{
+    download_feedback::DownloadFeedbackRow, download_history::DownloadHistoryRow,
+    wishlist::WishlistItem,
+};
+#[cfg(feature = "server")]
+use crate::server_fns::{cleanup_empty_ancestors, not_found_error, server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::MemberSession;
+
+/// Rescore `groups` by what users said of their peers' earlier files.
+#[cfg(feature = "server")]
+pub(crate) async fn rank_by_reputation(groups: &mut [DownloadableGroup]) {
+    match DownloadFeedbackRow::reputations().await {
+        Ok(reputations) => apply_reputation(groups, &reputations),
+        Err(e) => warn!("Could not load peer reputations: {}", e),
+    }
+}
+
+/// Give feedback on a finished download. With `search_again`, a complaint
+/// also deletes the imported file and wishes for its album again.
+#[post("/api/downloads/history/feedback", auth: MemberSession)]
+pub async fn rate_download(
+    history_id: String,
+    feedback: DownloadFeedback,
+    search_again: bool,
+) -> Result<(), ServerFnError> {
+    let entry = DownloadHistoryRow::get(&history_id, &auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .ok_or_else(|| not_found_error("Download not found"))?;
+
+    let redo = if search_again && feedback.is_complaint() {
+        let (Some(path), Some(artist), Some(album)) =
+            (&entry.imported_path, &entry.artist, &entry.album)
+        else {
+            return Err(validation_error(
+                "Only imports with a known artist and album can be searched for again",
+            ));
+        };
+        Some((path.clone(), artist.clone(), album.clone()))
+    } else {
+        None
+    };
+
+    DownloadFeedbackRow::set(&entry, feedback)
+        .await
+        .map_err(server_error)?;
+    info!(
+        "{} marked {} from {} as {}",
+        auth.0.username,
+        entry.item,
+        entry.peer,
+        feedback.label()
+    );
+
+    let Some((path, artist, album)) = redo else {
+        return Ok(());
+    };
+    let path = std::path::Path::new(&path);
+    if path.exists() {
+        tokio::fs::remove_file(path)
+            .await
+            .map_err(|e| server_error(format!("Failed to delete: {}", e)))?;
+        if let Some(parent) = path.parent() {
+            let _ = cleanup_empty_ancestors(parent).await;
+        }
+    }
+    WishlistItem::add_many(
+        &auth.0.sub,
+        &[NewWishlistItem {
+            artist,
+            title: album,
+            release_mbid: None,
+        }],
+        Some("feedback"),
+    )
+    .await
+    .map_err(server_error)?;
+    Ok(())
+}
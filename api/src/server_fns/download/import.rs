@@ -1,16 +1,79 @@
 #[cfg(feature = "server")]
 use dioxus::logger::tracing::{info, warn};
 #[cfg(feature = "server")]
-use shared::download::{DownloadEvent, DownloadProgress, DownloadState};
+use shared::download::{
+    DownloadEvent, DownloadProgress, DownloadState, ImportEdits, ImportMode, ImportOverrides,
+    ImportTarget, NonCanonicalImport, NonCanonicalKind, TargetImport,
+};
+#[cfg(feature = "server")]
+use shared::hooks::HookContext;
 #[cfg(feature = "server")]
 use soulbeet::ImportResult;
 #[cfg(feature = "server")]
-use std::path::Path;
+use std::collections::HashMap;
+#[cfg(feature = "server")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "server")]
+use std::sync::{Arc, LazyLock};
+
+#[cfg(feature = "server")]
+use crate::globals::{HeldImport, UserSender, HELD_IMPORTS};
+#[cfg(feature = "server")]
+use crate::models::{folder::Folder, user::User, user_settings::UserSettings};
 #[cfg(feature = "server")]
-use tokio::sync::broadcast;
+use crate::services::{importers, music_importer};
+#[cfg(feature = "server")]
+use shared::download::PendingImport;
+
+#[cfg(feature = "server")]
+const UPLOAD_ATTEMPTS: u32 = 3;
+
+#[cfg(feature = "server")]
+const UPLOAD_RETRY_DELAY: std::time::Duration = std::time::Duration::from_secs(30);
+
+#[cfg(feature = "server")]
+static UPLOAD_LOCK: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+
+/// Folder under a library where linked downloads are staged for import.
+#[cfg(feature = "server")]
+const STAGING_DIR: &str = ".soulbeet-staging";
+
+/// Imports in progress, keyed by canonical source path. The slot holds the
+/// outcome of the last import of that source for imports queued behind it.
+#[cfg(feature = "server")]
+type ImportSlot = Arc<tokio::sync::Mutex<Option<DownloadState>>>;
+
+#[cfg(feature = "server")]
+static IMPORT_LOCKS: LazyLock<std::sync::Mutex<HashMap<PathBuf, ImportSlot>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+#[cfg(feature = "server")]
+fn send_state(tx: &UserSender, entries: &[DownloadProgress], state: DownloadState) {
+    let error = match &state {
+        DownloadState::Failed(e) => Some(e.clone()),
+        _ => None,
+    };
+    let updated: Vec<_> = entries
+        .iter()
+        .map(|e| DownloadProgress {
+            state: state.clone(),
+            error: error.clone(),
+            ..e.clone()
+        })
+        .collect();
+    let _ = tx.send(DownloadEvent::Progress(updated));
+}
 
+/// Whether anything is left to import at `source_path`.
 #[cfg(feature = "server")]
-use crate::services::music_importer;
+async fn has_audio_files(source_path: &str) -> bool {
+    let source = PathBuf::from(source_path);
+    tokio::task::spawn_blocking(move || {
+        source.is_file() || !soulbeet::tagging::audio_files(&source).is_empty()
+    })
+    .await
+    .unwrap_or(true)
+}
 
 /// Attempt to clean up a failed download/import file
 #[cfg(feature = "server")]
@@ -24,17 +87,560 @@ async fn cleanup_failed_file(file_path: &str) {
     }
 }
 
+/// Move a remote folder's imported files to its remote target, retrying
+/// with a growing delay. Files that couldn't be uploaded stay in the local
+/// folder and go with the next upload to it.
 #[cfg(feature = "server")]
-pub async fn import_group(
+async fn upload_to_remote(
+    entries: &[DownloadProgress],
+    target_path: &Path,
+    remote: &str,
+    tx: &UserSender,
+) -> Result<(), String> {
+    let target = soulbeet::remote::RemoteTarget::parse(remote)?;
+    // Two uploads of the same folder would fight over the same files
+    let _serialized = UPLOAD_LOCK.lock().await;
+
+    let mut last_error = String::new();
+    for attempt in 1..=UPLOAD_ATTEMPTS {
+        send_state(tx, entries, DownloadState::Uploading(attempt));
+        let mut reported = 0.0;
+        let result = soulbeet::remote::upload(target_path, &target, |percent| {
+            if percent - reported < 5.0 && percent < 100.0 {
+                return;
+            }
+            reported = percent;
+            let updated: Vec<_> = entries
+                .iter()
+                .map(|e| DownloadProgress {
+                    state: DownloadState::Uploading(attempt),
+                    percent,
+                    ..e.clone()
+                })
+                .collect();
+            let _ = tx.send(DownloadEvent::Progress(updated));
+        })
+        .await;
+
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                warn!(
+                    "Upload attempt {} of {} failed: {}",
+                    attempt, UPLOAD_ATTEMPTS, e
+                );
+                last_error = e;
+            }
+        }
+        if attempt < UPLOAD_ATTEMPTS {
+            tokio::time::sleep(UPLOAD_RETRY_DELAY * attempt).await;
+        }
+    }
+    Err(format!(
+        "{}. The files are kept in {} until the next upload",
+        last_error,
+        target_path.display()
+    ))
+}
+
+/// Delete the files of a download that won't be imported, and the folders
+/// it leaves empty.
+#[cfg(feature = "server")]
+async fn discard_download(entries: &[DownloadProgress], source_path: &str) {
+    for entry in entries {
+        cleanup_failed_file(&entry.item).await;
+    }
+    if let Some(parent) = Path::new(source_path).parent() {
+        let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+    }
+}
+
+/// Link a download into a staging folder inside the library, for import
+/// modes that keep the download. Returns the staging folder and the staged
+/// source, or `None` to import the download itself.
+#[cfg(feature = "server")]
+async fn stage_download(
+    source_path: &str,
+    target_path: &Path,
+    mode: ImportMode,
+) -> Option<(PathBuf, PathBuf)> {
+    if !mode.keeps_download() {
+        return None;
+    }
+    // Inside the library so both ends are on the library's filesystem
+    let staging_dir = target_path
+        .join(STAGING_DIR)
+        .join(uuid::Uuid::new_v4().to_string());
+    let source = PathBuf::from(source_path);
+    let dir = staging_dir.clone();
+    let staged =
+        tokio::task::spawn_blocking(move || soulbeet::linking::stage_links(&source, &dir, mode))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+    match staged {
+        Ok(staged) => Some((staging_dir, staged)),
+        Err(e) => {
+            warn!("{}, importing {} normally", e, source_path);
+            None
+        }
+    }
+}
+
+/// Tag files with the tracks the user assigned them by hand, so the
+/// importer matches them to the right track.
+#[cfg(feature = "server")]
+async fn apply_track_mapping(source_path: &str, overrides: &ImportOverrides) {
+    if overrides.track_mapping.is_empty() {
+        return;
+    }
+
+    let source = PathBuf::from(source_path);
+    let mapping = overrides.track_mapping.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let mut edits = ImportEdits::default();
+        let mut mapped = Vec::new();
+        for file in soulbeet::tagging::audio_files(&source) {
+            let name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let Some(track) = mapping.get(&name) else {
+                continue;
+            };
+            let key = file.to_string_lossy().to_string();
+            edits.titles.insert(key.clone(), track.title.clone());
+            if let Some(number) = track.number {
+                edits.track_numbers.insert(key, number);
+            }
+            mapped.push(file);
+        }
+        for file in &mapped {
+            soulbeet::tagging::write_tags(file, &edits, None)?;
+        }
+        Ok::<usize, String>(mapped.len())
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+
+    match result {
+        Ok(count) => info!("Retagged {} manually mapped file(s) in {}", count, source_path),
+        Err(e) => warn!("Could not apply track mapping in {}: {}", source_path, e),
+    }
+}
+
+/// Rename the files of a download with the owner's rename rules, if they
+/// enabled them. Returns the source path to import, which changes when the
+/// source is a single renamed file.
+#[cfg(feature = "server")]
+async fn apply_rename_rules(source_path: String, owner: Option<&User>) -> String {
+    let Some(user) = owner else {
+        return source_path;
+    };
+    let rules = UserSettings::get(&user.id)
+        .await
+        .map(|s| s.rename_rules())
+        .unwrap_or_default();
+    if !rules.enabled {
+        return source_path;
+    }
+
+    let source = PathBuf::from(&source_path);
+    let result =
+        tokio::task::spawn_blocking(move || soulbeet::renaming::apply_renames(&source, &rules))
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()));
+
+    match result {
+        Ok(renames) => {
+            info!("Renamed {} file(s) in {}", renames.len(), source_path);
+            renames
+                .into_iter()
+                .find(|(from, _)| from.as_path() == Path::new(&source_path))
+                .map(|(_, to)| to.to_string_lossy().to_string())
+                .unwrap_or(source_path)
+        }
+        Err(e) => {
+            warn!("Could not rename files in {}: {}", source_path, e);
+            source_path
+        }
+    }
+}
+
+/// Import a group, or hold it for tag review if the user asked for one.
+#[cfg(feature = "server")]
+pub async fn import_or_hold(
     entries: Vec<DownloadProgress>,
     source_path: String,
     target_path: std::path::PathBuf,
-    tx: broadcast::Sender<DownloadEvent>,
+    tx: UserSender,
     as_album: bool,
+    overrides: &ImportOverrides,
+    username: &str,
 ) {
+    let owner = User::get_by_username(username).await.ok().flatten();
+    // Import mode and remote are folder settings, not something the request picks
+    let folder = match &owner {
+        Some(user) => Folder::find_by_path(&user.id, &target_path.to_string_lossy()).await,
+        None => None,
+    };
+    let overrides = &ImportOverrides {
+        import_mode: folder.as_ref().map(Folder::import_mode).unwrap_or_default(),
+        remote: folder.and_then(|f| f.remote),
+        ..overrides.clone()
+    };
+
+    // Track mapping is keyed by the original file names, so it goes first
+    apply_track_mapping(&source_path, overrides).await;
+    let source_path = apply_rename_rules(source_path, owner.as_ref()).await;
+
+    if !overrides.review_tags {
+        let collect = match &owner {
+            Some(user) => super::super::discogs::album_to_collect(&user.id, &source_path).await,
+            None => None,
+        };
+        let state = import_group(entries, source_path, target_path, tx, as_album, overrides).await;
+        if let (DownloadState::Imported, Some(user), Some((artist, album))) =
+            (state, owner, collect)
+        {
+            tokio::spawn(async move {
+                super::super::discogs::collect_album(&user.id, &artist, &album).await;
+            });
+        }
+        return;
+    }
+
+    let source = std::path::PathBuf::from(&source_path);
+    let files = tokio::task::spawn_blocking(move || {
+        soulbeet::tagging::audio_files(&source)
+            .iter()
+            .filter_map(|path| match soulbeet::tagging::read_tags(path) {
+                Ok(tags) => Some(tags),
+                Err(e) => {
+                    warn!("Could not read tags of {}: {}", path.display(), e);
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .unwrap_or_default();
+
+    if files.is_empty() {
+        info!("No taggable files in {}, importing without review", source_path);
+        import_group(entries, source_path, target_path, tx, as_album, overrides).await;
+        return;
+    }
+
+    let label = entries
+        .first()
+        .and_then(|e| e.batch_label.clone())
+        .or_else(|| {
+            Path::new(&source_path)
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+        })
+        .unwrap_or_else(|| source_path.clone());
+    let pending = PendingImport {
+        id: uuid::Uuid::new_v4().to_string(),
+        label,
+        files,
+        target_path: target_path.to_string_lossy().to_string(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+
+    info!("Holding {} for tag review ({})", source_path, pending.id);
+    let _ = tx.send(DownloadEvent::ImportReview(pending.clone()));
+    HELD_IMPORTS.write().await.insert(
+        pending.id.clone(),
+        HeldImport {
+            username: username.to_string(),
+            pending,
+            entries,
+            source_path,
+            target_path,
+            tx,
+            as_album,
+            overrides: ImportOverrides {
+                review_tags: false,
+                ..overrides.clone()
+            },
+        },
+    );
+}
+
+/// Import a group, one import at a time per source folder. A second import
+/// of a folder that is already being imported waits for the first; if that
+/// one took all the files, its outcome is reported for both. Returns the
+/// final state.
+#[cfg(feature = "server")]
+pub async fn import_group(
+    entries: Vec<DownloadProgress>,
+    source_path: String,
+    target_path: std::path::PathBuf,
+    tx: UserSender,
+    as_album: bool,
+    overrides: &ImportOverrides,
+) -> DownloadState {
+    let key = std::fs::canonicalize(&source_path).unwrap_or_else(|_| PathBuf::from(&source_path));
+    let slot = IMPORT_LOCKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .entry(key.clone())
+        .or_default()
+        .clone();
+
+    let mut outcome = match slot.try_lock() {
+        Ok(guard) => guard,
+        Err(_) => {
+            info!("{} is already being imported, waiting", source_path);
+            send_state(&tx, &entries, DownloadState::ImportWaiting);
+            slot.lock().await
+        }
+    };
+
+    let state = match outcome.clone() {
+        Some(previous) if !has_audio_files(&source_path).await => {
+            info!("{} was imported by a concurrent request", source_path);
+            send_state(&tx, &entries, previous.clone());
+            previous
+        }
+        _ => {
+            let entries = import_extra_targets(
+                entries,
+                &source_path,
+                &target_path,
+                &tx,
+                as_album,
+                overrides,
+            )
+            .await;
+            let state =
+                import_group_exclusive(entries, source_path, target_path, tx, as_album, overrides)
+                    .await;
+            *outcome = Some(state.clone());
+            state
+        }
+    };
+    drop(outcome);
+
+    // Forget the folder once nobody else is waiting on it
+    let mut locks = IMPORT_LOCKS.lock().unwrap_or_else(|e| e.into_inner());
+    if locks.get(&key).is_some_and(|s| Arc::strong_count(s) <= 2) {
+        locks.remove(&key);
+    }
+    state
+}
+
+/// Import a copy of the download into each extra target folder, one after
+/// the other, before the main import takes the files. How each went is
+/// reported in the entries' `target_imports`, and the entries are returned
+/// with it so the main import's updates keep it.
+#[cfg(feature = "server")]
+async fn import_extra_targets(
+    mut entries: Vec<DownloadProgress>,
+    source_path: &str,
+    target_path: &Path,
+    tx: &UserSender,
+    as_album: bool,
+    overrides: &ImportOverrides,
+) -> Vec<DownloadProgress> {
+    let mut targets = Vec::new();
+    for target in &overrides.extra_targets {
+        match Folder::get_by_id(&target.folder_id).await {
+            Ok(Some(folder)) if Path::new(&folder.path) != target_path => {
+                targets.push((target, folder))
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => warn!(
+                "Extra import folder {} is gone, skipping it",
+                target.folder_id
+            ),
+            Err(e) => warn!("Could not load import folder {}: {}", target.folder_id, e),
+        }
+    }
+    if targets.is_empty() {
+        return entries;
+    }
+
+    let mut statuses: Vec<_> = targets
+        .iter()
+        .map(|(_, folder)| TargetImport {
+            folder_id: folder.id.clone(),
+            folder: folder.name.clone(),
+            state: DownloadState::Queued,
+        })
+        .collect();
+    for (i, (target, folder)) in targets.iter().enumerate() {
+        statuses[i].state = DownloadState::Importing;
+        report_targets(&mut entries, &statuses, tx);
+        info!("Importing {} into {} as well", source_path, folder.path);
+        let state =
+            import_into_target(&entries, source_path, target, folder, as_album, overrides).await;
+        if let DownloadState::Failed(e) = &state {
+            warn!("Import into {} failed: {}", folder.path, e);
+        }
+        statuses[i].state = state;
+    }
+    report_targets(&mut entries, &statuses, tx);
+    entries
+}
+
+#[cfg(feature = "server")]
+fn report_targets(entries: &mut [DownloadProgress], statuses: &[TargetImport], tx: &UserSender) {
+    for entry in entries.iter_mut() {
+        entry.state = DownloadState::Importing;
+        entry.target_imports = statuses.to_vec();
+    }
+    let _ = tx.send(DownloadEvent::Progress(entries.to_vec()));
+}
+
+/// Import a download into one extra target folder from a copy staged in
+/// it, linked when the folder's import mode allows. The download itself is
+/// left for the main import.
+#[cfg(feature = "server")]
+async fn import_into_target(
+    entries: &[DownloadProgress],
+    source_path: &str,
+    target: &ImportTarget,
+    folder: &Folder,
+    as_album: bool,
+    overrides: &ImportOverrides,
+) -> DownloadState {
+    let target_path = PathBuf::from(&folder.path);
+    let staging_dir = target_path
+        .join(STAGING_DIR)
+        .join(uuid::Uuid::new_v4().to_string());
+    let source = PathBuf::from(source_path);
+    let mode = folder.import_mode();
+    let dir = staging_dir.clone();
+    let staged = tokio::task::spawn_blocking(move || {
+        if mode.keeps_download() {
+            match soulbeet::linking::stage_links(&source, &dir, mode) {
+                Ok(staged) => return Ok(staged),
+                Err(e) => warn!("{}, copying instead", e),
+            }
+        }
+        soulbeet::linking::stage_copies(&source, &dir)
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+    let staged = match staged {
+        Ok(staged) => staged,
+        Err(e) => return DownloadState::Failed(e),
+    };
+
+    // Whatever the import does to its files, it does to the staged copy
+    let staged_entries: Vec<_> = entries
+        .iter()
+        .map(|e| {
+            let item = match Path::new(&e.item).strip_prefix(source_path) {
+                Ok(rel) if rel.as_os_str().is_empty() => staged.clone(),
+                Ok(rel) => staged.join(rel),
+                Err(_) => staged.join(Path::new(&e.item).file_name().unwrap_or_default()),
+            };
+            DownloadProgress {
+                item: item.to_string_lossy().to_string(),
+                target_imports: Vec::new(),
+                ..e.clone()
+            }
+        })
+        .collect();
+    let overrides = ImportOverrides {
+        importer: target.importer.clone(),
+        target_path: None,
+        review_tags: false,
+        complete_album: None,
+        import_mode: ImportMode::Default,
+        remote: folder.remote.clone(),
+        extra_targets: Vec::new(),
+        ..overrides.clone()
+    };
+    // Its progress is reported as the target's status, not the entries' own
+    let silent = UserSender::silent();
+    let state = import_group_exclusive(
+        staged_entries,
+        staged.to_string_lossy().to_string(),
+        target_path,
+        silent,
+        as_album,
+        &overrides,
+    )
+    .await;
+
+    let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+    if let Some(parent) = staging_dir.parent() {
+        let _ = tokio::fs::remove_dir(parent).await;
+    }
+    state
+}
+
+/// Import a group and report progress. Returns the final state.
+#[cfg(feature = "server")]
+async fn import_group_exclusive(
+    entries: Vec<DownloadProgress>,
+    source_path: String,
+    target_path: std::path::PathBuf,
+    tx: UserSender,
+    as_album: bool,
+    overrides: &ImportOverrides,
+) -> DownloadState {
+    // A dropped network share pauses the import rather than failing it
+    crate::mounts::wait_until_available(&target_path, |_| {
+        send_state(&tx, &entries, DownloadState::ImportPaused)
+    })
+    .await;
+
+    if let Some(merge) = &overrides.complete_album {
+        return super::complete::import_into_album(entries, source_path, merge, overrides, tx)
+            .await;
+    }
+
+    // Beets matches everything against MusicBrainz, so while it is down the
+    // download is imported from its own tags rather than failed
+    let mut entries = entries;
+    let mut non_canonical = overrides.non_canonical.clone();
+    let uses_musicbrainz =
+        overrides.importer.as_deref().unwrap_or(importers::BEETS) == importers::BEETS;
+    if non_canonical.is_none() && uses_musicbrainz && !soulbeet::musicbrainz::is_reachable().await {
+        warn!(
+            "MusicBrainz is unreachable, importing {} from its tags",
+            source_path
+        );
+        for entry in &mut entries {
+            entry.tag_derived = true;
+        }
+        non_canonical = Some(NonCanonicalImport {
+            kind: NonCanonicalKind::TagDerived,
+            artist: None,
+            album: None,
+        });
+    }
+
+    if let Some(non_canonical) = &non_canonical {
+        let state = super::non_canonical::import_non_canonical(
+            entries.clone(),
+            source_path,
+            target_path.clone(),
+            tx.clone(),
+            non_canonical,
+        )
+        .await;
+        return match (&state, &overrides.remote) {
+            (DownloadState::Imported, Some(remote)) => {
+                let state = match upload_to_remote(&entries, &target_path, remote, &tx).await {
+                    Ok(()) => DownloadState::Imported,
+                    Err(e) => DownloadState::Failed(e),
+                };
+                send_state(&tx, &entries, state.clone());
+                state
+            }
+            _ => state,
+        };
+    }
+
     info!(
-        "Importing group from: {:?} (album: {})",
-        source_path, as_album
+        "Importing group from: {:?} (album: {}, flags: {:?})",
+        source_path, as_album, overrides.extra_flags
     );
 
     let importing_entries: Vec<_> = entries
@@ -46,7 +652,7 @@ pub async fn import_group(
         .collect();
     let _ = tx.send(DownloadEvent::Progress(importing_entries));
 
-    let importer = match music_importer(None).await {
+    let importer = match music_importer(overrides.importer.as_deref()).await {
         Ok(imp) => imp,
         Err(e) => {
             warn!("Failed to get importer: {}", e);
@@ -59,27 +665,76 @@ pub async fn import_group(
                 })
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(failed_entries));
-            return;
+            return DownloadState::Failed(format!("No importer available: {e}"));
         }
     };
 
-    let source = Path::new(&source_path);
-    match importer.import(&[source], &target_path, as_album).await {
-        Ok(ImportResult::Success) => {
-            info!("Import successful");
+    // Linked imports hand beets a staged copy to move, leaving the download
+    let staging = stage_download(&source_path, &target_path, overrides.import_mode).await;
+    let linked = staging.is_some();
+    let mut extra_flags = overrides.extra_flags.clone();
+    // Beets' own candidate search settles for whichever edition scores best
+    if let Some(release) = overrides.release.as_ref() {
+        if as_album && uses_musicbrainz {
+            extra_flags.push(format!("--search-id={}", release.mbid));
+        }
+    }
+    let source = match &staging {
+        Some((_, staged)) => {
+            extra_flags.push("--move".to_string());
+            staged.clone()
+        }
+        None => PathBuf::from(&source_path),
+    };
+    let result = importer
+        .import_with_flags(&[source.as_path()], &target_path, as_album, &extra_flags)
+        .await;
+    if let Some((staging_dir, _)) = &staging {
+        let _ = tokio::fs::remove_dir_all(staging_dir).await;
+        if let Some(parent) = staging_dir.parent() {
+            let _ = tokio::fs::remove_dir(parent).await;
+        }
+    }
+
+    match result {
+        Ok(ImportResult::Success(mut summary)) => {
+            let expected = overrides.release.as_ref();
+            if let Some(mismatch) = expected.and_then(|r| r.mismatch(&summary)) {
+                summary.warnings.push(mismatch);
+            }
+            info!("{}", summary.describe());
+            for warning in &summary.warnings {
+                warn!("Import warning: {}", warning);
+            }
+            // Clean up empty source directories left after beets moves the files
+            if !linked {
+                if let Some(parent) = Path::new(&source_path).parent() {
+                    let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+                }
+            }
+
+            if let Some(remote) = &overrides.remote {
+                if let Err(e) = upload_to_remote(&entries, &target_path, remote, &tx).await {
+                    send_state(&tx, &entries, DownloadState::Failed(e.clone()));
+                    return DownloadState::Failed(e);
+                }
+            }
+
+            tokio::spawn(super::super::import_hooks::run_after_import(
+                HookContext::from_summary(&summary),
+            ));
+
             let imported_entries: Vec<_> = entries
                 .iter()
                 .map(|e| DownloadProgress {
                     state: DownloadState::Imported,
+                    imported_file: summary.file_for(&e.item).cloned(),
+                    import_summary: Some(summary.clone()),
                     ..e.clone()
                 })
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(imported_entries));
-
-            // Clean up empty source directories left after beets moves the files
-            if let Some(parent) = Path::new(&source_path).parent() {
-                let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
-            }
+            DownloadState::Imported
         }
         Ok(ImportResult::Skipped) => {
             info!("Import skipped items");
@@ -92,12 +747,10 @@ pub async fn import_group(
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(skipped_entries));
 
-            for entry in &entries {
-                cleanup_failed_file(&entry.item).await;
-            }
-            if let Some(parent) = std::path::Path::new(&source_path).parent() {
-                let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+            if !linked {
+                discard_download(&entries, &source_path).await;
             }
+            DownloadState::ImportSkipped
         }
         Ok(ImportResult::Failed(err)) => {
             info!("Import failed: {}", err);
@@ -111,12 +764,10 @@ pub async fn import_group(
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(failed_entries));
 
-            for entry in &entries {
-                cleanup_failed_file(&entry.item).await;
-            }
-            if let Some(parent) = std::path::Path::new(&source_path).parent() {
-                let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+            if !linked {
+                discard_download(&entries, &source_path).await;
             }
+            DownloadState::Failed(format!("Import failed: {err}"))
         }
         Ok(ImportResult::TimedOut) => {
             warn!("Import timed out for: {}", source_path);
@@ -130,12 +781,10 @@ pub async fn import_group(
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(failed_entries));
 
-            for entry in &entries {
-                cleanup_failed_file(&entry.item).await;
-            }
-            if let Some(parent) = std::path::Path::new(&source_path).parent() {
-                let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+            if !linked {
+                discard_download(&entries, &source_path).await;
             }
+            DownloadState::Failed("Import timed out".into())
         }
         Err(e) => {
             warn!("Import error for {}: {}", source_path, e);
@@ -149,12 +798,10 @@ pub async fn import_group(
                 .collect();
             let _ = tx.send(DownloadEvent::Progress(failed_entries));
 
-            for entry in &entries {
-                cleanup_failed_file(&entry.item).await;
-            }
-            if let Some(parent) = std::path::Path::new(&source_path).parent() {
-                let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+            if !linked {
+                discard_download(&entries, &source_path).await;
             }
+            DownloadState::Failed(format!("Import error: {e}"))
         }
     }
 }
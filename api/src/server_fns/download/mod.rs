@@ -1,9 +1,12 @@
-use dioxus::fullstack::{WebSocketOptions, Websocket};
+use dioxus::fullstack::{CborEncoding, WebSocketOptions, Websocket};
 use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
-use shared::download::{DownloadEvent, DownloadableItem, QueuedDownload};
+use shared::download::{
+    DownloadEvent, DownloadProgress, DownloadableItem, ImportOverrides, QueuedDownload,
+};
 #[cfg(feature = "server")]
-use shared::download::DownloadProgress;
+use shared::download::DownloadState;
+use shared::updates::UpdateFrame;
 
 #[cfg(feature = "server")]
 use dioxus::logger::tracing::{info, warn};
@@ -11,92 +14,161 @@ use dioxus::logger::tracing::{info, warn};
 use tokio::sync::broadcast;
 
 #[cfg(feature = "server")]
-use crate::{server_fns::server_error, AuthSession};
+use crate::{
+    server_fns::{forbidden_error, server_error, slskd_error, validate},
+    AuthSession, MemberSession,
+};
 
 #[cfg(feature = "server")]
 use crate::globals::{
-    cleanup_stale_channels, get_or_create_user_channel, register_user_task, unregister_user_task,
-    USER_CHANNELS,
+    cleanup_stale_channels, get_or_create_user_channel, register_user_task, USER_CHANNELS,
 };
 #[cfg(feature = "server")]
 use crate::services::download_backend;
 
 // Local modules
+pub mod analytics;
+pub use analytics::get_matching_report;
 pub mod auto_download;
 pub use auto_download::{auto_download, AutoDownloadRequest, AutoDownloadResult};
+pub mod bulk;
+pub use bulk::{cancel_downloads, clear_downloads, retry_downloads};
+pub mod feedback;
+pub use feedback::rate_download;
+pub mod notes;
+pub use notes::{get_download_notes, get_download_tags, get_tagged_downloads, set_download_note};
+pub mod pause;
+pub use pause::{get_download_pause, set_download_pause};
+pub mod replace;
+pub use replace::replace_download;
 
+#[cfg(feature = "server")]
+pub mod complete;
+#[cfg(feature = "server")]
+pub mod fallback;
+#[cfg(feature = "server")]
+mod feed;
 #[cfg(feature = "server")]
 pub mod import;
 #[cfg(feature = "server")]
 pub mod monitor;
 #[cfg(feature = "server")]
+pub mod non_canonical;
+#[cfg(feature = "server")]
 pub mod process;
 #[cfg(feature = "server")]
+pub mod simulate;
+#[cfg(feature = "server")]
 pub mod utils;
 
 #[cfg(feature = "server")]
 use self::monitor::DownloadMonitor;
 
+/// Queue items with the backend, falling back on `candidates` for the files
+/// a peer refuses.
 #[cfg(feature = "server")]
 async fn do_download(
     items: Vec<DownloadableItem>,
     backend_id: Option<&str>,
+    candidates: &[shared::download::DownloadableGroup],
 ) -> Result<Vec<QueuedDownload>, ServerFnError> {
     let backend = download_backend(backend_id)
         .await
-        .map_err(|e| server_error(format!("download backend not available: {}", e)))?;
+        .map_err(|e| slskd_error(format!("download backend not available: {}", e)))?;
+
+    let queued = backend.download(items.clone()).await.map_err(slskd_error)?;
+    Ok(fallback::substitute_refused(backend.as_ref(), queued, &items, candidates).await)
+}
 
-    backend.download(items).await.map_err(server_error)
+/// Events for one socket, or one feed of binary sockets: the snapshot
+/// first, then live updates, and the snapshot again whenever it falls
+/// behind.
+#[cfg(feature = "server")]
+struct SocketUpdates {
+    rx: broadcast::Receiver<DownloadEvent>,
+    snapshot: std::sync::Arc<std::sync::Mutex<crate::globals::DownloadSnapshot>>,
+    pending: std::collections::VecDeque<DownloadEvent>,
+}
+
+#[cfg(feature = "server")]
+impl SocketUpdates {
+    /// Subscribe `username` to their channel. Subscribed together with the
+    /// snapshot being taken, so each update is in one or the other.
+    async fn subscribe(username: &str) -> Self {
+        let mut map = USER_CHANNELS.write().await;
+        let channel = map
+            .entry(username.to_string())
+            .or_insert_with(|| crate::globals::UserChannel::new(username));
+        let (rx, snapshot) = channel.sender.subscribe();
+        Self {
+            rx,
+            snapshot: channel.snapshot.clone(),
+            pending: snapshot.into(),
+        }
+    }
+
+    fn queue_snapshot(&mut self) {
+        let events = self
+            .snapshot
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .events();
+        self.pending.extend(events);
+    }
+
+    /// Next event to send, `None` once the channel is gone.
+    async fn next(&mut self) -> Option<DownloadEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            match self.rx.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(
+                        "Download updates lagged, skipped {} messages. Resending the snapshot.",
+                        skipped
+                    );
+                    // Catch the client up on what it missed
+                    self.queue_snapshot();
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    info!("Broadcast channel closed");
+                    return None;
+                }
+            }
+        }
+    }
 }
 
-/// WebSocket endpoint for real-time download updates.
-/// Uses WebSocket instead of HTTP streaming for more reliable delivery.
-#[get("/api/downloads/updates", auth: AuthSession)]
+/// WebSocket endpoint for real-time download updates, as JSON events.
+/// Version 1 of the protocol, kept for pages loaded before the binary one.
+#[get("/api/downloads/updates", auth: AuthSession, headers: axum::http::HeaderMap)]
 pub async fn download_updates_ws(
     options: WebSocketOptions,
 ) -> Result<Websocket<(), DownloadEvent>, ServerFnError> {
+    // Any page can open a WebSocket here with the user's cookie
+    if !crate::csrf::is_same_origin(&headers) {
+        return Err(forbidden_error("WebSocket from another site"));
+    }
     let username = auth.0.username;
-
-    let rx = {
-        let map = USER_CHANNELS.read().await;
-        if let Some(channel) = map.get(&username) {
-            channel.sender.subscribe()
-        } else {
-            drop(map);
-            let mut map = USER_CHANNELS.write().await;
-            let channel = map
-                .entry(username.clone())
-                .or_insert_with(crate::globals::UserChannel::new);
-            channel.sender.subscribe()
-        }
-    };
+    let mut updates = SocketUpdates::subscribe(&username).await;
 
     Ok(options.on_upgrade(move |mut socket| async move {
-        let mut rx = rx;
         info!("WebSocket connected for user: {}", username);
 
         loop {
             // handle both broadcast messages and potential socket closure
             tokio::select! {
-                result = rx.recv() => {
-                    match result {
-                        Ok(downloads) => {
-                            if socket.send(downloads).await.is_err() {
-                                info!("WebSocket closed (client disconnected)");
-                                break;
-                            }
-                        }
-                        Err(broadcast::error::RecvError::Lagged(skipped)) => {
-                            warn!(
-                                "Download updates lagged, skipped {} messages. Consider reducing download batch size.",
-                                skipped
-                            );
-                            // lagged is recoverable - continue receiving
-                        }
-                        Err(broadcast::error::RecvError::Closed) => {
-                            info!("Broadcast channel closed");
-                            break;
-                        }
+                event = updates.next() => {
+                    let Some(event) = event else { break };
+                    // Pages this old drop cleared downloads themselves
+                    if matches!(event, DownloadEvent::Cleared(_)) {
+                        continue;
+                    }
+                    if socket.send(event).await.is_err() {
+                        info!("WebSocket closed (client disconnected)");
+                        break;
                     }
                 }
                 result = socket.recv() => {
@@ -115,6 +187,115 @@ pub async fn download_updates_ws(
     }))
 }
 
+/// WebSocket endpoint for real-time download updates, as CBOR frames
+/// carrying only what changed (see `shared::updates`). All sockets of a
+/// user share the frames of one feed.
+#[get("/api/downloads/updates/binary", auth: AuthSession, headers: axum::http::HeaderMap)]
+pub async fn download_updates_binary_ws(
+    options: WebSocketOptions,
+) -> Result<Websocket<(), UpdateFrame, CborEncoding>, ServerFnError> {
+    if !crate::csrf::is_same_origin(&headers) {
+        return Err(forbidden_error("WebSocket from another site"));
+    }
+    let username = auth.0.username;
+    let mut feed = feed::FeedSubscription::join(&username).await;
+
+    Ok(options.on_upgrade(move |mut socket| async move {
+        info!("Binary WebSocket connected for user: {}", username);
+        let mut frames = feed.catch_up();
+
+        'socket: loop {
+            for frame in frames.drain(..) {
+                if socket.send(frame).await.is_err() {
+                    info!("WebSocket closed (client disconnected)");
+                    break 'socket;
+                }
+            }
+            tokio::select! {
+                next = feed.next() => {
+                    let Some(next) = next else { break };
+                    frames = next;
+                }
+                result = socket.recv() => {
+                    if result.is_err() {
+                        info!("WebSocket client disconnected");
+                        break;
+                    }
+                }
+            }
+        }
+
+        feed.leave().await;
+        info!("Binary WebSocket disconnected for user: {}", username);
+        cleanup_stale_channels().await;
+    }))
+}
+
+/// The user's downloads as the panel should show them after a page load:
+/// the latest state of each one sent on their channel, with transfer
+/// progress refreshed from the backend.
+#[get("/api/downloads/active", auth: AuthSession)]
+pub async fn get_active_downloads() -> Result<Vec<DownloadProgress>, ServerFnError> {
+    let snapshot = USER_CHANNELS
+        .read()
+        .await
+        .get(&auth.0.username)
+        .map(|channel| channel.snapshot.clone());
+    let mut entries = snapshot
+        .map(|s| s.lock().unwrap_or_else(|e| e.into_inner()).entries())
+        .unwrap_or_default();
+
+    let transferring =
+        |state: &DownloadState| matches!(state, DownloadState::Queued | DownloadState::InProgress);
+    if !entries.iter().any(|e| transferring(&e.state)) {
+        return Ok(entries);
+    }
+    // The snapshot only moves when a monitor reports, the backend is current
+    let Some(live) = live_downloads().await else {
+        return Ok(entries);
+    };
+    for entry in entries.iter_mut().filter(|e| transferring(&e.state)) {
+        if let Some(current) = live
+            .iter()
+            .find(|l| l.id == entry.id && l.source == entry.source)
+            .filter(|l| transferring(&l.state))
+        {
+            entry.state = current.state.clone();
+            entry.transferred = current.transferred;
+            entry.percent = current.percent;
+            entry.speed = current.speed;
+        }
+    }
+    Ok(entries)
+}
+
+/// How long the backend's downloads are reused, so tabs loading or
+/// reconnecting together ask it once.
+#[cfg(feature = "server")]
+const LIVE_DOWNLOADS_TTL: std::time::Duration = std::time::Duration::from_secs(2);
+
+#[cfg(feature = "server")]
+static LIVE_DOWNLOADS: std::sync::LazyLock<
+    tokio::sync::Mutex<Option<(std::time::Instant, Vec<DownloadProgress>)>>,
+> = std::sync::LazyLock::new(|| tokio::sync::Mutex::new(None));
+
+/// The backend's downloads, fetched at most once per `LIVE_DOWNLOADS_TTL`.
+#[cfg(feature = "server")]
+async fn live_downloads() -> Option<Vec<DownloadProgress>> {
+    // Held over the request, so callers arriving meanwhile wait for it
+    let mut cached = LIVE_DOWNLOADS.lock().await;
+    if let Some((_, live)) = cached
+        .as_ref()
+        .filter(|(at, _)| at.elapsed() < LIVE_DOWNLOADS_TTL)
+    {
+        return Some(live.clone());
+    }
+    let backend = download_backend(None).await.ok()?;
+    let live = backend.get_downloads().await.ok()?;
+    *cached = Some((std::time::Instant::now(), live.clone()));
+    Some(live)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CancelDownloadRequest {
     pub id: String,
@@ -123,18 +304,18 @@ pub struct CancelDownloadRequest {
     pub backend: Option<String>,
 }
 
-#[post("/api/downloads/cancel", auth: AuthSession)]
+#[post("/api/downloads/cancel", auth: MemberSession)]
 pub async fn cancel_download(req: CancelDownloadRequest) -> Result<(), ServerFnError> {
     let username = auth.0.username;
 
     let backend = download_backend(req.backend.as_deref())
         .await
-        .map_err(|e| server_error(format!("download backend not available: {}", e)))?;
+        .map_err(|e| slskd_error(format!("download backend not available: {}", e)))?;
 
     backend
         .cancel_download(&req.source, &req.id, false)
         .await
-        .map_err(server_error)?;
+        .map_err(slskd_error)?;
 
     info!(
         "User {} cancelled download {} from {}",
@@ -156,6 +337,11 @@ pub async fn cancel_download(req: CancelDownloadRequest) -> Result<(), ServerFnE
         backend: req.backend,
         batch_id: None,
         batch_label: None,
+        tag_derived: false,
+        import_summary: None,
+        imported_file: None,
+        planned_path: None,
+        target_imports: Vec::new(),
     };
     let _ = tx.send(DownloadEvent::Progress(vec![cancelled]));
 
@@ -165,16 +351,67 @@ pub async fn cancel_download(req: CancelDownloadRequest) -> Result<(), ServerFnE
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DownloadRequest {
     pub items: Vec<DownloadableItem>,
-    pub target_folder: String,
+    /// Id of the folder to download into, one of the user's or shared with them
+    pub folder_id: String,
     #[serde(default)]
     pub backend: Option<String>,
+    #[serde(default)]
+    pub import_overrides: ImportOverrides,
+    /// Dry run: report what would be downloaded and where, queue nothing
+    #[serde(default)]
+    pub simulate: bool,
 }
 
-#[post("/api/downloads/queue", auth: AuthSession)]
+#[post("/api/downloads/queue", auth: MemberSession)]
 pub async fn download(req: DownloadRequest) -> Result<Vec<QueuedDownload>, ServerFnError> {
     let username = auth.0.username;
+    validate::download_items(&req.items)?;
+    validate::import_overrides(&auth.0.sub, &req.import_overrides).await?;
+    let folder = validate::folder(&auth.0.sub, &req.folder_id).await?;
+    let target_path_buf = match &req.import_overrides.target_path {
+        Some(target) => validate::folder_path(&auth.0.sub, target).await?,
+        None => std::path::PathBuf::from(&folder.path),
+    };
+
+    if req.simulate {
+        super::features::require(shared::features::flags::DRY_RUN).await?;
+        simulate::report_plan(&username, &req.items, &target_path_buf, None).await;
+        return Ok(Vec::new());
+    }
+
+    analytics::record_picks(&req.items, &fallback::candidates(&username).await, false).await;
+
+    queue_downloads(
+        username,
+        req.items,
+        target_path_buf,
+        req.backend,
+        req.import_overrides,
+    )
+    .await
+}
+
+/// Queue items with the backend and start monitoring them, importing into
+/// `target_path_buf` when done.
+#[cfg(feature = "server")]
+pub(crate) async fn queue_downloads(
+    username: String,
+    items: Vec<DownloadableItem>,
+    target_path_buf: std::path::PathBuf,
+    backend_id: Option<String>,
+    import_overrides: ImportOverrides,
+) -> Result<Vec<QueuedDownload>, ServerFnError> {
+    if pause::is_paused().await {
+        return Ok(pause::defer(
+            username,
+            items,
+            target_path_buf,
+            backend_id,
+            import_overrides,
+        )
+        .await);
+    }
 
-    let target_path_buf = std::path::Path::new(&req.target_folder).to_path_buf();
     if let Err(e) = tokio::fs::create_dir_all(&target_path_buf).await {
         return Err(server_error(format!(
             "Failed to create target directory: {}",
@@ -182,15 +419,23 @@ pub async fn download(req: DownloadRequest) -> Result<Vec<QueuedDownload>, Serve
         )));
     }
 
-    let res = do_download(req.items, req.backend.as_deref()).await?;
+    bulk::remember_for_retry(
+        &username,
+        &items,
+        &target_path_buf,
+        backend_id.as_deref(),
+        &import_overrides,
+    )
+    .await;
+
+    let candidates = fallback::candidates(&username).await;
+    let res = do_download(items, backend_id.as_deref(), &candidates).await?;
 
     let (failed, successful): (Vec<_>, Vec<_>) =
         res.iter().cloned().partition(|d| d.error.is_some());
 
     let (tx, _) = get_or_create_user_channel(&username).await;
 
-    let backend_id = req.backend;
-
     if !failed.is_empty() {
         let failed_entries: Vec<DownloadProgress> = failed
             .iter()
@@ -231,24 +476,21 @@ pub async fn download(req: DownloadRequest) -> Result<Vec<QueuedDownload>, Serve
     info!("Started monitoring downloads: {:?}", download_filenames);
 
     // Register this task for cleanup tracking
-    let task_username = username.clone();
     let task_cancellation = register_user_task(&username).await;
 
     // Spawn the monitoring task
-    tokio::spawn(async move {
-        let mut monitor = DownloadMonitor::new(
-            download_sources,
-            download_filenames,
-            target_path,
-            tx,
-            task_cancellation,
-            task_username.clone(),
-            None, // batch_id - will be set by auto_download in Plan 02
-            None, // batch_label - will be set by auto_download in Plan 02
-        );
-        monitor.run().await;
-        unregister_user_task(&task_username).await;
-    });
+    let monitor = DownloadMonitor::new(
+        download_sources,
+        download_filenames,
+        target_path,
+        tx,
+        task_cancellation,
+        username,
+        None, // batch_id - will be set by auto_download in Plan 02
+        None, // batch_label - will be set by auto_download in Plan 02
+    )
+    .with_import_overrides(import_overrides);
+    monitor::spawn_supervised(monitor);
 
     Ok(res)
 }
@@ -3,19 +3,22 @@
 //! This module encapsulates the polling loop that monitors downloads from slskd,
 //! handles per-track timeouts, and triggers processing when downloads complete.
 
-use dioxus::logger::tracing::{debug, info, warn};
-use shared::download::{DownloadEvent, DownloadProgress, DownloadState};
+use dioxus::logger::tracing::{debug, error, info, warn};
+use shared::download::{DownloadEvent, DownloadProgress, DownloadState, ImportOverrides};
+use shared::system::{BreakerState, MonitorFailure};
 use soulbeet::DownloadBackend;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::broadcast;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
 use super::process::process_downloads;
 use crate::config::CONFIG;
+use crate::globals::{
+    unregister_user_task, MonitorSpec, MonitorTask, UserSender, MONITOR_FAILURES, MONITOR_TASKS,
+};
 use crate::services::download_backend;
 
 /// Poll interval for checking download status (2 seconds).
@@ -35,6 +38,19 @@ const ABSENT_TRACK_TIMEOUT: Duration = Duration::from_secs(120);
 /// Consecutive backend resolution failures tolerated before giving up.
 const MAX_BACKEND_FAILURES: u32 = 5;
 
+/// Longest wait between checks while the backend's circuit breaker is open.
+const MAX_BREAKER_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Without a heartbeat for this long, a monitor is taken for dead. Well
+/// above the longest wait on an open circuit breaker.
+const STALE_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// Times a monitor is started again before its downloads are failed.
+const MAX_RESTARTS: u32 = 3;
+
+/// Given-up monitors kept for the settings page.
+const KEPT_FAILURES: usize = 20;
+
 /// State tracking for individual track downloads.
 struct TrackState {
     /// When the track was first seen in slskd's download list.
@@ -54,6 +70,8 @@ struct TrackedFile {
 
 /// Monitors download progress from slskd and triggers processing on completion.
 pub struct DownloadMonitor {
+    /// Key of the monitor in the heartbeat registry.
+    id: String,
     /// Files being monitored (source + filename pairs).
     tracked_files: Vec<TrackedFile>,
     /// Filenames only (for legacy compatibility with process_downloads).
@@ -61,7 +79,7 @@ pub struct DownloadMonitor {
     /// Target directory for imports.
     target_path: PathBuf,
     /// Broadcast sender for UI updates.
-    tx: broadcast::Sender<DownloadEvent>,
+    tx: UserSender,
     /// Per-track state tracking.
     track_states: HashMap<String, TrackState>,
     /// Whether album mode is enabled.
@@ -76,6 +94,8 @@ pub struct DownloadMonitor {
     batch_id: Option<String>,
     /// Human-readable batch label (album name).
     batch_label: Option<String>,
+    /// Per-download importer changes requested by the user.
+    import_overrides: ImportOverrides,
 }
 
 impl DownloadMonitor {
@@ -84,7 +104,7 @@ impl DownloadMonitor {
         sources: Vec<String>,
         filenames: Vec<String>,
         target_path: PathBuf,
-        tx: broadcast::Sender<DownloadEvent>,
+        tx: UserSender,
         cancellation_token: CancellationToken,
         username: String,
         batch_id: Option<String>,
@@ -111,6 +131,7 @@ impl DownloadMonitor {
             .collect();
 
         Self {
+            id: uuid::Uuid::new_v4().to_string(),
             tracked_files,
             filenames,
             target_path,
@@ -122,6 +143,81 @@ impl DownloadMonitor {
             username,
             batch_id,
             batch_label,
+            import_overrides: ImportOverrides::default(),
+        }
+    }
+
+    /// Import finished files with these overrides instead of the defaults.
+    pub fn with_import_overrides(mut self, overrides: ImportOverrides) -> Self {
+        self.import_overrides = overrides;
+        self
+    }
+
+    /// A monitor taking over from one that died, for the tracks it hadn't
+    /// settled yet.
+    fn from_spec(id: String, spec: MonitorSpec) -> Self {
+        let mut monitor = Self::new(
+            spec.sources,
+            spec.filenames,
+            spec.target_path,
+            spec.tx,
+            spec.cancellation_token,
+            spec.username,
+            spec.batch_id,
+            spec.batch_label,
+        )
+        .with_import_overrides(spec.import_overrides);
+        monitor.id = id;
+        monitor
+    }
+
+    /// What a replacement needs: the tracks not processed yet.
+    fn spec(&self) -> MonitorSpec {
+        let pending: Vec<&TrackedFile> = self
+            .tracked_files
+            .iter()
+            .filter(|t| {
+                self.track_states
+                    .get(&t.filename)
+                    .is_some_and(|s| !s.processed)
+            })
+            .collect();
+        MonitorSpec {
+            sources: pending.iter().map(|t| t.source.clone()).collect(),
+            filenames: pending.iter().map(|t| t.filename.clone()).collect(),
+            target_path: self.target_path.clone(),
+            tx: self.tx.clone(),
+            cancellation_token: self.cancellation_token.clone(),
+            username: self.username.clone(),
+            batch_id: self.batch_id.clone(),
+            batch_label: self.batch_label.clone(),
+            import_overrides: self.import_overrides.clone(),
+        }
+    }
+
+    /// What the monitor watches, for logs and reports.
+    fn label(&self) -> String {
+        match &self.batch_label {
+            Some(label) => label.clone(),
+            None => format!("{} files", self.filenames.len()),
+        }
+    }
+
+    /// Tell the watchdog the monitor is alive, and what it still watches.
+    fn beat(&self) {
+        let spec = self.spec();
+        let mut tasks = MONITOR_TASKS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(task) = tasks.get_mut(&self.id) {
+            task.last_beat = std::time::Instant::now();
+            task.spec = spec;
+        }
+    }
+
+    fn set_importing(&self, importing: bool) {
+        let mut tasks = MONITOR_TASKS.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(task) = tasks.get_mut(&self.id) {
+            task.last_beat = std::time::Instant::now();
+            task.importing = importing;
         }
     }
 
@@ -131,11 +227,13 @@ impl DownloadMonitor {
         let mut consecutive_empty = 0;
         let mut poll_count = 0;
         let mut backend_failures: u32 = 0;
+        let mut backing_off = false;
 
         // Poll immediately on first iteration
         interval.tick().await;
 
         loop {
+            self.beat();
             if self.cancellation_token.is_cancelled() {
                 info!(
                     "Download monitoring cancelled for batch {:?}",
@@ -165,6 +263,30 @@ impl DownloadMonitor {
                     continue;
                 }
             };
+
+            // While the breaker is open every request fails without reaching
+            // slskd, so wait out the cooldown instead of polling
+            if let BreakerState::Open { retry_in_secs } = backend.breaker_state().await {
+                if !backing_off {
+                    warn!(
+                        "Download backend unavailable, pausing monitoring for {} for {}s",
+                        self.username, retry_in_secs
+                    );
+                    backing_off = true;
+                }
+                let wait = Duration::from_secs(retry_in_secs.max(POLL_INTERVAL_SECS))
+                    .min(MAX_BREAKER_BACKOFF);
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = self.cancellation_token.cancelled() => {}
+                }
+                continue;
+            }
+            if backing_off {
+                info!("Resuming download monitoring for {}", self.username);
+                backing_off = false;
+            }
+
             match backend.get_downloads().await {
                 Ok(downloads) => {
                     let should_break = self
@@ -460,8 +582,24 @@ impl DownloadMonitor {
                     let dl = download.clone();
                     let tp = self.target_path.clone();
                     let tx_clone = self.tx.clone();
+                    let overrides = self.import_overrides.clone();
+                    let username = self.username.clone();
                     tokio::spawn(async move {
-                        process_downloads(vec![dl], tp, tx_clone).await;
+                        let context = format!("import of {} for {}", dl.item, username);
+                        let mut crashed = DownloadProgress::failed(
+                            dl.id.clone(),
+                            dl.source.clone(),
+                            dl.item.clone(),
+                            "Import crashed, see the server log".to_string(),
+                        );
+                        crashed.backend = dl.backend.clone();
+                        crashed.batch_id = dl.batch_id.clone();
+                        crashed.batch_label = dl.batch_label.clone();
+                        let failed_tx = tx_clone.clone();
+                        let import = process_downloads(vec![dl], tp, tx_clone, overrides, username);
+                        if crate::panics::catch(context, import).await.is_none() {
+                            let _ = failed_tx.send(DownloadEvent::Progress(vec![crashed]));
+                        }
                     });
                 }
 
@@ -528,7 +666,7 @@ impl DownloadMonitor {
 
     /// Mark every unprocessed track as failed and notify the UI. Used when
     /// monitoring must stop early so downloads are never left dangling.
-    fn fail_unprocessed_tracks(&mut self, reason: &str) {
+    fn fail_unprocessed_tracks(&mut self, reason: &str) -> usize {
         let mut failed: Vec<DownloadProgress> = Vec::new();
         for tracked in &self.tracked_files {
             if let Some(state) = self.track_states.get_mut(&tracked.filename) {
@@ -538,10 +676,12 @@ impl DownloadMonitor {
                 }
             }
         }
+        let count = failed.len();
         if !failed.is_empty() {
             let entries = self.stamp_batch(failed);
             let _ = self.tx.send(DownloadEvent::Progress(entries));
         }
+        count
     }
 
     /// Check if all downloads are complete. Returns true if monitoring should stop.
@@ -600,13 +740,163 @@ impl DownloadMonitor {
                 "Album mode: Processing {} successful downloads together",
                 successful.len()
             );
-            process_downloads(successful, self.target_path.clone(), self.tx.clone()).await;
+            self.set_importing(true);
+            process_downloads(
+                successful,
+                self.target_path.clone(),
+                self.tx.clone(),
+                self.import_overrides.clone(),
+                self.username.clone(),
+            )
+            .await;
+            self.set_importing(false);
         } else {
             info!("Album mode: No successful downloads to process");
         }
     }
 }
 
+/// Run `monitor` in its own task, watched through its heartbeat. It is
+/// started again if it panics or stops beating, and its downloads are
+/// failed once that happened too often.
+pub fn spawn_supervised(monitor: DownloadMonitor) {
+    MONITOR_TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(
+            monitor.id.clone(),
+            MonitorTask {
+                spec: monitor.spec(),
+                last_beat: std::time::Instant::now(),
+                importing: false,
+                restarts: 0,
+                abort: None,
+            },
+        );
+    launch(monitor);
+}
+
+fn launch(monitor: DownloadMonitor) {
+    let id = monitor.id.clone();
+    let handle = tokio::spawn(supervise(monitor));
+    if let Some(task) = MONITOR_TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get_mut(&id)
+    {
+        task.abort = Some(handle.abort_handle());
+    }
+}
+
+async fn supervise(mut monitor: DownloadMonitor) {
+    let context = format!(
+        "download monitor for {} ({})",
+        monitor.username,
+        monitor.label()
+    );
+    if crate::panics::catch(context, monitor.run()).await.is_none() {
+        recover(&monitor.id, "panicked").await;
+        return;
+    }
+    MONITOR_TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(&monitor.id);
+    unregister_user_task(&monitor.username).await;
+}
+
+/// Start a dead monitor again from its last heartbeat, or fail what it was
+/// still watching once it died too often.
+async fn recover(id: &str, reason: &str) {
+    let (spec, restarts) = {
+        let mut tasks = MONITOR_TASKS.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(task) = tasks.get_mut(id) else {
+            return;
+        };
+        task.restarts += 1;
+        task.last_beat = std::time::Instant::now();
+        task.importing = false;
+        task.abort = None;
+        (task.spec.clone(), task.restarts)
+    };
+    let mut monitor = DownloadMonitor::from_spec(id.to_string(), spec);
+
+    if restarts <= MAX_RESTARTS {
+        warn!(
+            "Download monitor for {} ({}) {}, restarting it ({}/{})",
+            monitor.username,
+            monitor.label(),
+            reason,
+            restarts,
+            MAX_RESTARTS
+        );
+        launch(monitor);
+        return;
+    }
+
+    MONITOR_TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(id);
+    let failed = monitor.fail_unprocessed_tracks(&format!(
+        "Download monitoring {} again after {} restarts, see Settings > Config",
+        reason, MAX_RESTARTS
+    ));
+    error!(
+        "Download monitor for {} ({}) {} after {} restarts, failed {} downloads",
+        monitor.username,
+        monitor.label(),
+        reason,
+        MAX_RESTARTS,
+        failed
+    );
+    {
+        let mut failures = MONITOR_FAILURES.lock().unwrap_or_else(|e| e.into_inner());
+        if failures.len() == KEPT_FAILURES {
+            failures.pop_back();
+        }
+        failures.push_front(MonitorFailure {
+            at: chrono::Utc::now().timestamp(),
+            username: monitor.username.clone(),
+            label: monitor.label(),
+            restarts: MAX_RESTARTS,
+            reason: reason.to_string(),
+            failed,
+        });
+    }
+    unregister_user_task(&monitor.username).await;
+}
+
+/// Restart the monitors whose heartbeat stopped, e.g. stuck on a request
+/// that never returns.
+pub async fn check_heartbeats() {
+    let stale: Vec<String> = MONITOR_TASKS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .filter(|(_, task)| !task.importing && task.last_beat.elapsed() > STALE_AFTER)
+        .map(|(id, task)| {
+            if let Some(abort) = &task.abort {
+                abort.abort();
+            }
+            id.clone()
+        })
+        .collect();
+    for id in stale {
+        recover(&id, "stopped responding").await;
+    }
+}
+
+/// Monitors given up on since the server started, newest first.
+pub fn recent_failures() -> Vec<MonitorFailure> {
+    MONITOR_FAILURES
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
 /// Build a synthetic terminal progress entry for a track slskd no longer
 /// reports, so the UI can settle its row.
 fn make_failed_progress(tracked: &TrackedFile, reason: &str) -> DownloadProgress {
@@ -623,6 +913,11 @@ fn make_failed_progress(tracked: &TrackedFile, reason: &str) -> DownloadProgress
         backend: None,
         batch_id: None,
         batch_label: None,
+        tag_derived: false,
+        import_summary: None,
+        imported_file: None,
+        planned_path: None,
+        target_imports: Vec::new(),
     }
 }
 
@@ -0,0 +1,213 @@
+//! Importing live recordings and bootlegs. These rarely match MusicBrainz,
+//! so instead of running the importer the files are tagged from the picked
+//! search result and moved under the configured path template. Downloads
+//! imported while MusicBrainz is unreachable take the same route, tagged
+//! from what their own files agree on.
+
+use dioxus::logger::tracing::{info, warn};
+use shared::download::{
+    DownloadEvent, DownloadProgress, DownloadState, ImportEdits, NonCanonicalImport,
+    NonCanonicalKind,
+};
+use std::path::{Path, PathBuf};
+
+use crate::config::CONFIG;
+use crate::globals::UserSender;
+
+/// Characters that can't appear in a path segment on common filesystems.
+const FORBIDDEN_PATH_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Make a tag value safe to use as a single directory name.
+pub(super) fn sanitize_segment(value: &str) -> String {
+    let cleaned: String = value
+        .chars()
+        .map(|c| if FORBIDDEN_PATH_CHARS.contains(&c) { '_' } else { c })
+        .collect();
+    cleaned.trim().trim_start_matches('.').to_string()
+}
+
+/// Render a template such as `Live/{artist}/{year} - {album}` into a
+/// relative path. Separators left dangling by a missing value are dropped.
+fn render_template(template: &str, artist: &str, album: &str, year: Option<u32>) -> PathBuf {
+    let year = year.map(|y| y.to_string()).unwrap_or_default();
+    template
+        .split('/')
+        .map(|segment| {
+            let rendered = segment
+                .replace("{artist}", &sanitize_segment(artist))
+                .replace("{album}", &sanitize_segment(album))
+                .replace("{year}", &year);
+            rendered
+                .trim_matches(|c: char| c.is_whitespace() || c == '-')
+                .to_string()
+        })
+        .filter(|segment| !segment.is_empty() && segment != "." && segment != "..")
+        .collect()
+}
+
+/// First plausible year in a folder name, e.g. "1994-06-12 Paris" or "(2003)".
+fn year_from_name(name: &str) -> Option<u32> {
+    name.split(|c: char| !c.is_ascii_digit())
+        .filter(|part| part.len() == 4)
+        .filter_map(|part| part.parse::<u32>().ok())
+        .find(|year| (1900..=2100).contains(year))
+}
+
+/// Move a file, falling back to copy + delete across filesystems.
+pub(super) fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+/// Tag and file every audio file of the group. Returns how many were moved;
+/// files already present at the destination are left in place.
+fn tag_and_file(
+    files: &[PathBuf],
+    edits: &ImportEdits,
+    destination: &Path,
+) -> Result<usize, String> {
+    std::fs::create_dir_all(destination)
+        .map_err(|e| format!("Failed to create {}: {}", destination.display(), e))?;
+
+    let mut moved = 0;
+    for file in files {
+        soulbeet::tagging::write_tags(file, edits, None)?;
+        let Some(name) = file.file_name() else {
+            continue;
+        };
+        let dest = destination.join(name);
+        if dest.exists() {
+            warn!("{} already exists, skipping", dest.display());
+            continue;
+        }
+        move_file(file, &dest)
+            .map_err(|e| format!("Failed to move {}: {}", file.display(), e))?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+pub async fn import_non_canonical(
+    entries: Vec<DownloadProgress>,
+    source_path: String,
+    target_path: PathBuf,
+    tx: UserSender,
+    import: &NonCanonicalImport,
+) -> DownloadState {
+    let send_state = |state: DownloadState| {
+        let error = match &state {
+            DownloadState::Failed(e) => Some(e.clone()),
+            _ => None,
+        };
+        let updated: Vec<_> = entries
+            .iter()
+            .map(|e| DownloadProgress {
+                state: state.clone(),
+                error: error.clone(),
+                ..e.clone()
+            })
+            .collect();
+        let _ = tx.send(DownloadEvent::Progress(updated));
+    };
+    send_state(DownloadState::Importing);
+
+    let source = PathBuf::from(&source_path);
+    let folder = if source.is_dir() {
+        source.clone()
+    } else {
+        source.parent().map(Path::to_path_buf).unwrap_or_default()
+    };
+    let folder_name = folder
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    let template = CONFIG.non_canonical_template(import.kind).to_string();
+    let hints = import.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let files = soulbeet::tagging::audio_files(&source);
+        if hints.kind == NonCanonicalKind::TagDerived {
+            let derived = soulbeet::tagging::album_from_tags(&source)
+                .ok_or_else(|| "No readable audio files".to_string())?;
+            let album = derived.album;
+            let year = album
+                .release_date
+                .as_deref()
+                .and_then(|d| d.get(..4))
+                .and_then(|y| y.parse::<u32>().ok());
+            // Track artists are kept, compilations would lose them otherwise
+            let edits = ImportEdits {
+                album_artist: Some(album.artist.clone()),
+                album: Some(album.title.clone()),
+                year,
+                ..Default::default()
+            };
+            let destination = target_path.join(render_template(
+                &template,
+                &album.artist,
+                &album.title,
+                year,
+            ));
+            return tag_and_file(&files, &edits, &destination).map(|moved| (moved, destination));
+        }
+        let existing = files
+            .first()
+            .and_then(|f| soulbeet::tagging::read_tags(f).ok())
+            .unwrap_or_default();
+
+        let artist = hints
+            .artist
+            .filter(|a| !a.trim().is_empty())
+            .or(existing.album_artist)
+            .or(existing.artist)
+            .unwrap_or_else(|| "Unknown Artist".to_string());
+        let album = hints
+            .album
+            .filter(|a| !a.trim().is_empty())
+            .unwrap_or_else(|| folder_name.clone());
+        let year = existing.year.or_else(|| year_from_name(&folder_name));
+
+        let edits = ImportEdits {
+            artist: Some(artist.clone()),
+            album_artist: Some(artist.clone()),
+            album: Some(album.clone()),
+            year,
+            ..Default::default()
+        };
+        let destination = target_path.join(render_template(&template, &artist, &album, year));
+        tag_and_file(&files, &edits, &destination).map(|moved| (moved, destination))
+    })
+    .await
+    .unwrap_or_else(|e| Err(e.to_string()));
+
+    let outcome = match result {
+        Ok((0, destination)) => {
+            info!("Nothing new to file into {}", destination.display());
+            DownloadState::ImportSkipped
+        }
+        Ok((moved, destination)) => {
+            info!(
+                "Filed {} {} file(s) into {}",
+                moved,
+                import.kind.label().to_lowercase(),
+                destination.display()
+            );
+            DownloadState::Imported
+        }
+        Err(e) => {
+            warn!("{} import failed for {}: {}", import.kind.label(), source_path, e);
+            let failed = DownloadState::Failed(format!("Import failed: {e}"));
+            send_state(failed.clone());
+            return failed;
+        }
+    };
+    send_state(outcome.clone());
+
+    if let Some(parent) = Path::new(&source_path).parent() {
+        let _ = crate::server_fns::cleanup_empty_ancestors(parent).await;
+    }
+    outcome
+}
@@ -0,0 +1,102 @@
+//! Free-form notes and tags on downloads, like "for dad" or "vinyl rip".
+//! A note written while a file downloads stays on its history entry, and
+//! the history can be filtered by tag.
+
+use std::collections::HashMap;
+
+use dioxus::prelude::*;
+use shared::download::{DownloadHistoryEntry, DownloadNote};
+
+#[cfg(feature = "server")]
+use crate::models::{download_history::DownloadHistoryRow, download_note::DownloadNoteRow};
+#[cfg(feature = "server")]
+use crate::server_fns::{server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+
+/// Longest note accepted, in characters.
+#[cfg(feature = "server")]
+const MAX_NOTE_CHARS: usize = 1000;
+
+/// Most tags on one download.
+#[cfg(feature = "server")]
+const MAX_TAGS: usize = 20;
+
+/// Longest tag accepted, in characters.
+#[cfg(feature = "server")]
+const MAX_TAG_CHARS: usize = 50;
+
+/// Most downloads whose notes can be asked for at once.
+#[cfg(feature = "server")]
+const MAX_NOTE_LOOKUPS: usize = 500;
+
+/// The caller's notes on the given downloads, by download id. Downloads
+/// without one are left out.
+#[post("/api/downloads/notes", auth: MemberSession)]
+pub async fn get_download_notes(
+    download_ids: Vec<String>,
+) -> Result<HashMap<String, DownloadNote>, ServerFnError> {
+    if download_ids.len() > MAX_NOTE_LOOKUPS {
+        return Err(validation_error(format!(
+            "Ask for at most {} notes at once",
+            MAX_NOTE_LOOKUPS
+        )));
+    }
+    DownloadNoteRow::get_many(&auth.0.sub, &download_ids)
+        .await
+        .map_err(server_error)
+}
+
+/// Replace the note and tags on a download, running or finished. An empty
+/// note removes them.
+#[post("/api/downloads/notes/set", auth: MemberSession)]
+pub async fn set_download_note(
+    download_id: String,
+    note: DownloadNote,
+) -> Result<DownloadNote, ServerFnError> {
+    if download_id.trim().is_empty() {
+        return Err(validation_error("No download given"));
+    }
+    let note = DownloadNote {
+        note: note.note.trim().to_string(),
+        tags: DownloadNote::parse_tags(&note.tags.join(",")),
+    };
+    if note.note.chars().count() > MAX_NOTE_CHARS {
+        return Err(validation_error(format!(
+            "Notes are limited to {} characters",
+            MAX_NOTE_CHARS
+        )));
+    }
+    if note.tags.len() > MAX_TAGS {
+        return Err(validation_error(format!(
+            "A download can have up to {} tags",
+            MAX_TAGS
+        )));
+    }
+    if let Some(tag) = note.tags.iter().find(|t| t.chars().count() > MAX_TAG_CHARS) {
+        return Err(validation_error(format!(
+            "The tag '{}' is longer than {} characters",
+            tag, MAX_TAG_CHARS
+        )));
+    }
+    DownloadNoteRow::set(&auth.0.sub, &download_id, &note)
+        .await
+        .map_err(server_error)?;
+    Ok(note)
+}
+
+/// Every tag the caller has put on a download, sorted.
+#[get("/api/downloads/notes/tags", auth: AuthSession)]
+pub async fn get_download_tags() -> Result<Vec<String>, ServerFnError> {
+    DownloadNoteRow::tags(&auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// All of the caller's finished downloads tagged `tag`, newest first.
+#[post("/api/downloads/history/tagged", auth: MemberSession)]
+pub async fn get_tagged_downloads(tag: String) -> Result<Vec<DownloadHistoryEntry>, ServerFnError> {
+    DownloadHistoryRow::get_by_tag(&auth.0.sub, tag.trim())
+        .await
+        .map_err(server_error)
+}
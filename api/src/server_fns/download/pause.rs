@@ -0,0 +1,210 @@
+//! Instance-wide pause of downloading. While paused, downloads asked for are
+//! held in memory and queued once the pause ends; auto-downloads wait before
+//! queueing. The switch is stored in the app config so it survives a
+//! restart, the held downloads do not.
+
+use dioxus::prelude::*;
+use shared::system::DownloadPause;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::download::{
+    DownloadEvent, DownloadProgress, DownloadState, DownloadableItem, ImportOverrides,
+    QueuedDownload,
+};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use tokio::sync::{Mutex, Notify};
+
+#[cfg(feature = "server")]
+use crate::globals::get_or_create_user_channel;
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::server_fns::server_error;
+#[cfg(feature = "server")]
+use crate::services::{available_download_backends, download_backend};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+
+/// A download asked for while paused.
+#[cfg(feature = "server")]
+struct Deferred {
+    username: String,
+    items: Vec<DownloadableItem>,
+    target_path: std::path::PathBuf,
+    backend_id: Option<String>,
+    import_overrides: ImportOverrides,
+}
+
+#[cfg(feature = "server")]
+static DEFERRED: LazyLock<Mutex<Vec<Deferred>>> = LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Wakes the auto-downloads waiting for the pause to end.
+#[cfg(feature = "server")]
+static RESUMED: Notify = Notify::const_new();
+
+#[cfg(feature = "server")]
+pub async fn is_paused() -> bool {
+    AppConfig::get(keys::DOWNLOADS_PAUSED)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true")
+}
+
+#[cfg(feature = "server")]
+pub async fn download_pause() -> DownloadPause {
+    DownloadPause {
+        paused: is_paused().await,
+        deferred: DEFERRED.lock().await.len(),
+    }
+}
+
+/// Hold `items` until the pause ends. They show as queued in the user's
+/// download panel meanwhile.
+#[cfg(feature = "server")]
+pub(crate) async fn defer(
+    username: String,
+    items: Vec<DownloadableItem>,
+    target_path: std::path::PathBuf,
+    backend_id: Option<String>,
+    import_overrides: ImportOverrides,
+) -> Vec<QueuedDownload> {
+    let queued: Vec<QueuedDownload> = items
+        .iter()
+        .map(|i| {
+            QueuedDownload::success(
+                i.id.clone(),
+                i.source.clone(),
+                i.id.clone(),
+                i.size.unwrap_or(0),
+            )
+        })
+        .collect();
+    let entries: Vec<DownloadProgress> = queued
+        .iter()
+        .map(|d| {
+            let mut p =
+                DownloadProgress::queued(d.id.clone(), d.source.clone(), d.item.clone(), d.size);
+            p.backend = backend_id.clone();
+            p
+        })
+        .collect();
+    let (tx, _) = get_or_create_user_channel(&username).await;
+    let _ = tx.send(DownloadEvent::Progress(entries));
+
+    info!(
+        "Downloads are paused, holding {} file(s) for {}",
+        items.len(),
+        username
+    );
+    DEFERRED.lock().await.push(Deferred {
+        username,
+        items,
+        target_path,
+        backend_id,
+        import_overrides,
+    });
+    queued
+}
+
+/// Return once downloading is not paused.
+#[cfg(feature = "server")]
+pub(crate) async fn wait_until_resumed() {
+    loop {
+        let resumed = RESUMED.notified();
+        tokio::pin!(resumed);
+        // Registered before the check so a resume in between is not missed
+        resumed.as_mut().enable();
+        if !is_paused().await {
+            return;
+        }
+        resumed.await;
+    }
+}
+
+/// Queue everything held during the pause.
+#[cfg(feature = "server")]
+async fn resume() {
+    RESUMED.notify_waiters();
+    let deferred = std::mem::take(&mut *DEFERRED.lock().await);
+    for d in deferred {
+        tokio::spawn(async move {
+            let username = d.username.clone();
+            if let Err(e) = super::queue_downloads(
+                d.username,
+                d.items,
+                d.target_path,
+                d.backend_id,
+                d.import_overrides,
+            )
+            .await
+            {
+                warn!("Could not queue the held downloads of {}: {}", username, e);
+            }
+        });
+    }
+}
+
+/// Cancel the transfers in progress on every download backend.
+#[cfg(feature = "server")]
+async fn cancel_active_transfers() {
+    let mut cancelled = 0;
+    for (id, name) in available_download_backends() {
+        let Ok(backend) = download_backend(Some(id)).await else {
+            continue;
+        };
+        let transfers = match backend.get_downloads().await {
+            Ok(transfers) => transfers,
+            Err(e) => {
+                warn!("Could not list the transfers of {}: {}", name, e);
+                continue;
+            }
+        };
+        for transfer in transfers
+            .iter()
+            .filter(|t| matches!(t.state, DownloadState::Queued | DownloadState::InProgress))
+        {
+            match backend
+                .cancel_download(&transfer.source, &transfer.id, false)
+                .await
+            {
+                Ok(()) => cancelled += 1,
+                Err(e) => warn!("Could not cancel {}: {}", transfer.item, e),
+            }
+        }
+    }
+    info!("Cancelled {} transfer(s) for the pause", cancelled);
+}
+
+#[get("/api/downloads/pause", _: AuthSession)]
+pub async fn get_download_pause() -> Result<DownloadPause, ServerFnError> {
+    Ok(download_pause().await)
+}
+
+/// Pause or resume all downloading. Pausing can also cancel the transfers
+/// already running; they are not resumed afterwards.
+#[post("/api/maintenance/pause", auth: MemberSession)]
+pub async fn set_download_pause(
+    paused: bool,
+    cancel_active: bool,
+) -> Result<DownloadPause, ServerFnError> {
+    AppConfig::set(keys::DOWNLOADS_PAUSED, &paused.to_string())
+        .await
+        .map_err(server_error)?;
+    info!(
+        "{} {} downloading",
+        auth.0.username,
+        if paused { "paused" } else { "resumed" }
+    );
+    if paused && cancel_active {
+        cancel_active_transfers().await;
+    }
+    if !paused {
+        resume().await;
+    }
+    Ok(download_pause().await)
+}
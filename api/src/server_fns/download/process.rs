@@ -1,20 +1,20 @@
 #[cfg(feature = "server")]
 use dioxus::logger::tracing::{info, warn};
 #[cfg(feature = "server")]
-use shared::download::{DownloadEvent, DownloadProgress, DownloadState};
+use shared::download::{DownloadEvent, DownloadProgress, DownloadState, ImportOverrides};
 #[cfg(feature = "server")]
 use std::collections::HashMap;
 #[cfg(feature = "server")]
 use std::path::Path;
-#[cfg(feature = "server")]
-use tokio::sync::broadcast;
 
 #[cfg(feature = "server")]
-use super::import::import_group;
+use super::import::import_or_hold;
 #[cfg(feature = "server")]
 use super::utils::resolve_download_path;
 #[cfg(feature = "server")]
 use crate::config::CONFIG;
+#[cfg(feature = "server")]
+use crate::globals::UserSender;
 
 /// Maximum number of retries when waiting for a downloaded file to appear on disk.
 /// With exponential backoff (500ms, 1s, 2s, 4s, 8s), this covers ~15.5s total.
@@ -61,7 +61,9 @@ async fn resolve_download_path_with_retry(filename: &str, download_base: &Path)
 pub async fn process_downloads(
     successful_downloads: Vec<DownloadProgress>,
     target_path: std::path::PathBuf,
-    tx: broadcast::Sender<DownloadEvent>,
+    tx: UserSender,
+    overrides: ImportOverrides,
+    username: String,
 ) {
     if !successful_downloads.is_empty() {
         info!(
@@ -109,15 +111,32 @@ pub async fn process_downloads(
             }
 
             for (source_path, entries) in pending_imports {
-                import_group(entries, source_path, target_path.clone(), tx.clone(), true).await;
+                import_or_hold(
+                    entries,
+                    source_path,
+                    target_path.clone(),
+                    tx.clone(),
+                    true,
+                    &overrides,
+                    &username,
+                )
+                .await;
             }
 
             for download in singletons {
                 if let Some(path) =
                     resolve_download_path_with_retry(&download.item, &download_path_buf).await
                 {
-                    import_group(vec![download], path, target_path.clone(), tx.clone(), false)
-                        .await;
+                    import_or_hold(
+                        vec![download],
+                        path,
+                        target_path.clone(),
+                        tx.clone(),
+                        false,
+                        &overrides,
+                        &username,
+                    )
+                    .await;
                 }
             }
         } else {
@@ -126,8 +145,16 @@ pub async fn process_downloads(
                 if let Some(path) =
                     resolve_download_path_with_retry(&download.item, &download_path_buf).await
                 {
-                    import_group(vec![download], path, target_path.clone(), tx.clone(), false)
-                        .await;
+                    import_or_hold(
+                        vec![download],
+                        path,
+                        target_path.clone(),
+                        tx.clone(),
+                        false,
+                        &overrides,
+                        &username,
+                    )
+                    .await;
                 } else {
                     let failed_entry = DownloadProgress {
                         state: DownloadState::Failed("Could not resolve file path".into()),
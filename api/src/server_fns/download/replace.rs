@@ -0,0 +1,126 @@
+//! Replacing a badly imported album: the album's directory is deleted or
+//! moved aside, and the album is auto-downloaded again into the same folder
+//! from any peer but the one the bad copy came from.
+
+use dioxus::prelude::*;
+
+use super::auto_download::AutoDownloadResult;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::info;
+#[cfg(feature = "server")]
+use shared::download::DownloadQuery;
+#[cfg(feature = "server")]
+use shared::metadata::SearchResult;
+#[cfg(feature = "server")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "server")]
+use super::auto_download::{run_auto_download, AutoDownloadRequest};
+#[cfg(feature = "server")]
+use crate::models::{download_history::DownloadHistoryRow, folder::Folder};
+#[cfg(feature = "server")]
+use crate::server_fns::search::effective_match_mode;
+#[cfg(feature = "server")]
+use crate::server_fns::{
+    cleanup_empty_ancestors, metadata_error, move_aside, not_found_error, server_error,
+    validation_error,
+};
+#[cfg(feature = "server")]
+use crate::services::metadata_provider;
+#[cfg(feature = "server")]
+use crate::MemberSession;
+
+/// Directory replaced albums are moved to when archived, inside the folder.
+#[cfg(feature = "server")]
+const ARCHIVE_DIR: &str = ".replaced";
+
+/// Replace the album a history entry was imported into. The album is looked
+/// up before anything is removed; with `archive`, the old copy is kept
+/// aside instead of deleted.
+#[post("/api/downloads/history/replace", auth: MemberSession)]
+pub async fn replace_download(
+    history_id: String,
+    archive: bool,
+) -> Result<AutoDownloadResult, ServerFnError> {
+    let entry = DownloadHistoryRow::get(&history_id, &auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .ok_or_else(|| not_found_error("Download not found"))?;
+    let (Some(path), Some(artist), Some(album)) = (entry.imported_path, entry.artist, entry.album)
+    else {
+        return Err(validation_error(
+            "Only imports with a known artist and album can be replaced",
+        ));
+    };
+
+    let path = PathBuf::from(path);
+    let folder = Folder::get_visible_to(&auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .into_iter()
+        .filter(|f| path.starts_with(&f.path))
+        .max_by_key(|f| f.path.len())
+        .ok_or_else(|| validation_error("The import is not in one of your folders anymore"))?;
+    let album_dir = path
+        .parent()
+        .filter(|dir| *dir != Path::new(&folder.path))
+        .ok_or_else(|| validation_error("The import is not in an album directory"))?
+        .to_path_buf();
+
+    let provider = metadata_provider(None, None).await.map_err(server_error)?;
+    let release = provider
+        .search_albums(Some(&artist), &album, 5)
+        .await
+        .map_err(|e| metadata_error(provider.id(), e))?
+        .into_iter()
+        .find_map(|r| match r {
+            SearchResult::Album(a) => Some(a),
+            SearchResult::Track(_) => None,
+        })
+        .ok_or_else(|| not_found_error(format!("No release found for {} - {}", artist, album)))?;
+    let release = provider
+        .get_album(&release.id)
+        .await
+        .map_err(|e| metadata_error(provider.id(), e))?;
+
+    if album_dir.exists() {
+        if archive {
+            let target = move_aside(Path::new(&folder.path), &album_dir, ARCHIVE_DIR)
+                .await
+                .map_err(server_error)?;
+            info!(
+                "{} archived {} to {} for replacement",
+                auth.0.username,
+                album_dir.display(),
+                target.display()
+            );
+        } else {
+            tokio::fs::remove_dir_all(&album_dir)
+                .await
+                .map_err(|e| server_error(format!("Failed to delete: {}", e)))?;
+            info!(
+                "{} deleted {} for replacement",
+                auth.0.username,
+                album_dir.display()
+            );
+        }
+        if let Some(parent) = album_dir.parent() {
+            let _ = cleanup_empty_ancestors(parent).await;
+        }
+    }
+
+    let mut query = DownloadQuery::new(release.tracks).album(release.album);
+    query.match_mode = Some(effective_match_mode(&auth.0.sub, None).await);
+    Ok(run_auto_download(
+        auth.0.username,
+        AutoDownloadRequest {
+            query,
+            folder_id: folder.id,
+            folder_path: folder.path,
+            simulate: false,
+            replacing_peer: Some(entry.peer),
+        },
+    )
+    .await)
+}
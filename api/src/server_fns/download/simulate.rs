@@ -0,0 +1,65 @@
+//! Dry runs of the download pipeline. Search and source selection run as
+//! usual, but instead of queueing transfers the user gets the files that
+//! would have been downloaded and where each would be imported. Nothing is
+//! sent to the backend and nothing is written to disk.
+
+use std::path::Path;
+
+use dioxus::logger::tracing::info;
+use shared::download::{DownloadEvent, DownloadProgress, DownloadableItem, RenameRules};
+
+use crate::globals::get_or_create_user_channel;
+use crate::models::{user::User, user_settings::UserSettings};
+
+async fn rename_rules(username: &str) -> RenameRules {
+    let Ok(Some(user)) = User::get_by_username(username).await else {
+        return RenameRules::default();
+    };
+    UserSettings::get(&user.id)
+        .await
+        .map(|s| s.rename_rules())
+        .unwrap_or_default()
+}
+
+/// Where `item` would land in `target`, named as the user's rename rules
+/// would name it.
+fn planned_path(item: &DownloadableItem, target: &Path, rules: &RenameRules) -> String {
+    let normalized = item.id.replace('\\', "/");
+    let file_name = normalized.rsplit('/').next().unwrap_or(&normalized);
+    let file_name = if rules.enabled {
+        soulbeet::renaming::renamed(file_name, rules)
+    } else {
+        file_name.to_string()
+    };
+    target.join(file_name).to_string_lossy().to_string()
+}
+
+/// Report what downloading `items` into `target` would do, as `Simulated`
+/// entries on the user's download channel.
+pub(crate) async fn report_plan(
+    username: &str,
+    items: &[DownloadableItem],
+    target: &Path,
+    batch: Option<(String, String)>,
+) {
+    let rules = rename_rules(username).await;
+    let entries: Vec<DownloadProgress> = items
+        .iter()
+        .map(|item| {
+            let entry = DownloadProgress::simulated(item, planned_path(item, target, &rules));
+            match &batch {
+                Some((id, label)) => entry.with_batch(id.clone(), label.clone()),
+                None => entry,
+            }
+        })
+        .collect();
+
+    info!(
+        "Simulated download of {} file(s) into {:?} for {}",
+        entries.len(),
+        target,
+        username
+    );
+    let (tx, _) = get_or_create_user_channel(username).await;
+    let _ = tx.send(DownloadEvent::Progress(entries));
+}
@@ -0,0 +1,211 @@
+//! CSV and JSON exports of the library index, the download history and
+//! the wishlist.
+
+use dioxus::prelude::*;
+use shared::download::DownloadHistoryEntry;
+use shared::export::{ExportFile, ExportFormat};
+
+#[cfg(feature = "server")]
+use super::label::queued_releases;
+#[cfg(feature = "server")]
+use super::{importer_error, server_error};
+#[cfg(feature = "server")]
+use crate::models::download_history::DownloadHistoryRow;
+#[cfg(feature = "server")]
+use crate::models::folder::Folder;
+#[cfg(feature = "server")]
+use crate::models::wishlist::WishlistItem;
+#[cfg(feature = "server")]
+use crate::services::music_importer;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+#[cfg(feature = "server")]
+use serde::Serialize;
+#[cfg(feature = "server")]
+use shared::export::{to_csv, ExportedRelease, WishlistExport, WISHLIST_EXPORT_VERSION};
+#[cfg(feature = "server")]
+use shared::library::LibraryIndexEntry;
+
+/// Entries shown in the dashboard's download history.
+#[cfg(feature = "server")]
+const RECENT_HISTORY_LIMIT: u32 = 100;
+
+/// A library track with the folder it lives in.
+#[cfg(feature = "server")]
+#[derive(Serialize)]
+struct LibraryExportRow {
+    folder: String,
+    #[serde(flatten)]
+    entry: LibraryIndexEntry,
+}
+
+#[cfg(feature = "server")]
+fn export_file<T: Serialize + ?Sized>(
+    name: &str,
+    format: ExportFormat,
+    data: &T,
+    csv: impl FnOnce() -> String,
+) -> Result<ExportFile, ServerFnError> {
+    let content = match format {
+        ExportFormat::Csv => csv(),
+        ExportFormat::Json => serde_json::to_string_pretty(data).map_err(server_error)?,
+    };
+    Ok(ExportFile {
+        file_name: format!(
+            "soulbeet-{}-{}.{}",
+            name,
+            chrono::Local::now().format("%Y-%m-%d"),
+            format.extension()
+        ),
+        mime: format.mime().to_string(),
+        content,
+    })
+}
+
+/// Every track in the library folders the user can see, shared ones
+/// included, as reported by the importer.
+#[post("/api/export/library", auth: AuthSession)]
+pub async fn export_library(format: ExportFormat) -> Result<ExportFile, ServerFnError> {
+    let folders = Folder::get_visible_to(&auth.0.sub)
+        .await
+        .map_err(server_error)?;
+    let importer = music_importer(None).await.map_err(importer_error)?;
+
+    let mut rows = Vec::new();
+    for folder in folders {
+        let entries = importer
+            .library_index(std::path::Path::new(&folder.path))
+            .await
+            .map_err(server_error)?;
+        rows.extend(entries.into_iter().map(|entry| LibraryExportRow {
+            folder: folder.name.clone(),
+            entry,
+        }));
+    }
+
+    export_file("library", format, &rows, || {
+        to_csv(
+            &[
+                "folder", "artist", "album", "title", "format", "year", "added", "path",
+            ],
+            rows.iter().map(|r| {
+                vec![
+                    r.folder.clone(),
+                    r.entry.artist.clone(),
+                    r.entry.album.clone(),
+                    r.entry.title.clone(),
+                    r.entry.format.clone(),
+                    r.entry.year.map(|y| y.to_string()).unwrap_or_default(),
+                    r.entry.added.clone(),
+                    r.entry.path.clone(),
+                ]
+            }),
+        )
+    })
+}
+
+/// The user's whole download history.
+#[post("/api/export/history", auth: AuthSession)]
+pub async fn export_download_history(format: ExportFormat) -> Result<ExportFile, ServerFnError> {
+    let history = DownloadHistoryRow::get_by_user(&auth.0.sub, None)
+        .await
+        .map_err(server_error)?;
+
+    export_file("history", format, &history, || {
+        to_csv(
+            &[
+                "date",
+                "artist",
+                "album",
+                "format",
+                "peer",
+                "size",
+                "outcome",
+                "error",
+                "item",
+                "library_path",
+                "tags",
+                "note",
+            ],
+            history.iter().map(|h| {
+                vec![
+                    chrono::DateTime::from_timestamp(h.finished_at, 0)
+                        .map(|d| d.to_rfc3339())
+                        .unwrap_or_default(),
+                    h.artist.clone().unwrap_or_default(),
+                    h.album.clone().unwrap_or_default(),
+                    h.format.clone().unwrap_or_default(),
+                    h.peer.clone(),
+                    h.size.to_string(),
+                    h.outcome.clone(),
+                    h.error.clone().unwrap_or_default(),
+                    h.item.clone(),
+                    h.imported_path.clone().unwrap_or_default(),
+                    h.note
+                        .as_ref()
+                        .map(|n| n.tags.join(", "))
+                        .unwrap_or_default(),
+                    h.note.as_ref().map(|n| n.note.clone()).unwrap_or_default(),
+                ]
+            }),
+        )
+    })
+}
+
+/// The most recent finished downloads, newest first.
+#[get("/api/downloads/history", auth: AuthSession)]
+pub async fn get_download_history() -> Result<Vec<DownloadHistoryEntry>, ServerFnError> {
+    DownloadHistoryRow::get_by_user(&auth.0.sub, Some(RECENT_HISTORY_LIMIT))
+        .await
+        .map_err(server_error)
+}
+
+/// The user's wishlist and the releases waiting in their download queue.
+/// The JSON file can be imported into another instance; the CSV has one
+/// row per release, with its MusicBrainz ids.
+#[post("/api/export/wishlist", auth: AuthSession)]
+pub async fn export_wishlist(format: ExportFormat) -> Result<ExportFile, ServerFnError> {
+    let wishlist = WishlistItem::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)?;
+    let export = WishlistExport {
+        version: WISHLIST_EXPORT_VERSION,
+        exported_at: chrono::Utc::now().timestamp(),
+        wishlist: wishlist.into_iter().map(ExportedRelease::from).collect(),
+        queue: queued_releases(&auth.0.username)
+            .await
+            .into_iter()
+            .map(ExportedRelease::from)
+            .collect(),
+    };
+
+    export_file("wishlist", format, &export, || {
+        let rows = export
+            .wishlist
+            .iter()
+            .map(|r| ("wishlist", r))
+            .chain(export.queue.iter().map(|r| ("queue", r)));
+        to_csv(
+            &[
+                "list",
+                "artist",
+                "title",
+                "release_mbid",
+                "release_group_mbid",
+                "date",
+                "source",
+            ],
+            rows.map(|(list, r)| {
+                vec![
+                    list.to_string(),
+                    r.artist.clone(),
+                    r.title.clone(),
+                    r.release_mbid.clone().unwrap_or_default(),
+                    r.release_group_mbid.clone().unwrap_or_default(),
+                    r.date.clone().unwrap_or_default(),
+                    r.source.clone().unwrap_or_default(),
+                ]
+            }),
+        )
+    })
+}
@@ -0,0 +1,58 @@
+//! Feature flags for experimental subsystems, stored as one JSON value in
+//! the app config. Everyone can read them so the UI knows which panels to
+//! show; changing them goes through the settings path.
+
+use dioxus::prelude::*;
+use shared::features::FeatureFlags;
+
+#[cfg(feature = "server")]
+use super::{forbidden_error, server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+
+/// The saved flags. A value that doesn't parse counts as nothing enabled.
+#[cfg(feature = "server")]
+pub async fn feature_flags() -> Result<FeatureFlags, String> {
+    Ok(AppConfig::get(keys::FEATURE_FLAGS)
+        .await?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+/// Refuse the request unless `flag` is enabled.
+#[cfg(feature = "server")]
+pub(crate) async fn require(flag: &str) -> Result<(), ServerFnError> {
+    let flags = feature_flags().await.map_err(server_error)?;
+    if flags.is_enabled(flag) {
+        return Ok(());
+    }
+    let name = shared::features::FEATURE_FLAGS
+        .iter()
+        .find(|f| f.key == flag)
+        .map_or(flag, |f| f.name);
+    Err(forbidden_error(format!(
+        "{} is turned off. Enable it in Settings > Config > Experimental.",
+        name
+    )))
+}
+
+#[get("/api/features", _: AuthSession)]
+pub async fn get_feature_flags() -> Result<FeatureFlags, ServerFnError> {
+    feature_flags().await.map_err(server_error)
+}
+
+#[post("/api/settings/features", _: MemberSession)]
+pub async fn set_feature_flag(key: String, enabled: bool) -> Result<FeatureFlags, ServerFnError> {
+    if !FeatureFlags::is_known(&key) {
+        return Err(validation_error(format!("Unknown feature flag: {}", key)));
+    }
+    let mut flags = feature_flags().await.map_err(server_error)?;
+    flags.set(&key, enabled);
+    let value = serde_json::to_string(&flags).map_err(server_error)?;
+    AppConfig::set(keys::FEATURE_FLAGS, &value)
+        .await
+        .map_err(server_error)?;
+    Ok(flags)
+}
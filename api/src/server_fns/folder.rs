@@ -1,55 +1,91 @@
 use crate::models;
 use dioxus::prelude::*;
+use shared::download::ImportMode;
 use shared::library::DuplicateReport;
+use shared::system::{DirectoryListing, FolderDiagnostics};
 
 #[cfg(feature = "server")]
-use super::server_error;
+use shared::system::DirectoryEntry;
+
+#[cfg(feature = "server")]
+use super::{importer_error, server_error, validate};
 #[cfg(feature = "server")]
 use crate::services::music_importer;
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::{AuthSession, MemberSession};
 #[cfg(feature = "server")]
 use std::path::Path;
 
+/// The user's folders and the ones shared by other users.
 #[get("/api/folders", auth: AuthSession)]
 pub async fn get_user_folders() -> Result<Vec<models::folder::Folder>, ServerFnError> {
     let claims = auth.0;
 
-    models::folder::Folder::get_all_by_user(&claims.sub)
+    models::folder::Folder::get_visible_to(&claims.sub)
         .await
         .map_err(server_error)
 }
 
-#[post("/api/folders", auth: AuthSession)]
+#[post("/api/folders", auth: MemberSession)]
 pub async fn create_user_folder(
     name: String,
     path: String,
 ) -> Result<models::folder::Folder, ServerFnError> {
     let claims = auth.0;
+    let path = validate::browsable_path(&path).await?;
 
     if let Err(e) = tokio::fs::create_dir_all(&path).await {
         return Err(server_error(format!("Failed to create directory: {}", e)));
     }
 
-    models::folder::Folder::create(&claims.sub, &name, &path)
+    models::folder::Folder::create(&claims.sub, &name, &path.to_string_lossy())
         .await
         .map_err(server_error)
 }
 
-#[put("/api/folders/update", _: AuthSession)]
+#[put("/api/folders/update", auth: MemberSession)]
 pub async fn update_folder(
     folder_id: String,
     name: String,
     path: String,
+    import_mode: ImportMode,
+    remote: Option<String>,
+    shared: bool,
 ) -> Result<(), ServerFnError> {
-    models::folder::Folder::update(&folder_id, &name, &path)
+    let path = validate::browsable_path(&path).await?;
+    let remote = match remote.as_deref().map(str::trim).filter(|r| !r.is_empty()) {
+        Some(r) => Some(
+            soulbeet::remote::RemoteTarget::parse(r)
+                .map_err(server_error)?
+                .to_string(),
+        ),
+        None => None,
+    };
+    models::folder::Folder::update(
+        &folder_id,
+        &auth.0.sub,
+        &name,
+        &path.to_string_lossy(),
+        import_mode,
+        remote.as_deref(),
+        shared,
+    )
+    .await
+    .map_err(server_error)
+}
+
+/// Soft-delete a folder. It can be brought back with `restore_folder`
+/// for a short window before the cleanup task purges it.
+#[delete("/api/folders/delete", auth: MemberSession)]
+pub async fn delete_folder(folder_id: String) -> Result<(), ServerFnError> {
+    models::folder::Folder::soft_delete(&folder_id, &auth.0.sub)
         .await
         .map_err(server_error)
 }
 
-#[delete("/api/folders/delete", _: AuthSession)]
-pub async fn delete_folder(folder_id: String) -> Result<(), ServerFnError> {
-    models::folder::Folder::delete(&folder_id)
+#[post("/api/folders/restore", auth: MemberSession)]
+pub async fn restore_folder(folder_id: String) -> Result<(), ServerFnError> {
+    models::folder::Folder::restore(&folder_id, &auth.0.sub)
         .await
         .map_err(server_error)
 }
@@ -74,6 +110,131 @@ pub async fn get_folder_duplicates() -> Result<DuplicateReport, ServerFnError> {
 
     let paths: Vec<&Path> = folders.iter().map(|f| Path::new(&f.path)).collect();
 
-    let importer = music_importer(None).await.map_err(server_error)?;
-    importer.find_duplicates(&paths).await.map_err(server_error)
+    let importer = music_importer(None).await.map_err(importer_error)?;
+    importer
+        .find_duplicates(&paths)
+        .await
+        .map_err(importer_error)
+}
+
+/// Report free space and writability for each folder the user can import
+/// into so a read-only or full mount shows up before an import fails on it.
+#[get("/api/folders/diagnostics", auth: AuthSession)]
+pub async fn folder_diagnostics() -> Result<Vec<FolderDiagnostics>, ServerFnError> {
+    let claims = auth.0;
+
+    let folders = models::folder::Folder::get_visible_to(&claims.sub)
+        .await
+        .map_err(server_error)?;
+
+    let mut diagnostics = Vec::with_capacity(folders.len());
+    for folder in &folders {
+        diagnostics.push(diagnose_folder(folder).await);
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(feature = "server")]
+async fn diagnose_folder(folder: &models::folder::Folder) -> FolderDiagnostics {
+    let path = Path::new(&folder.path);
+    let mut diag = FolderDiagnostics {
+        folder_id: folder.id.clone(),
+        ..Default::default()
+    };
+
+    match tokio::fs::metadata(path).await {
+        Ok(meta) if meta.is_dir() => diag.exists = true,
+        Ok(_) => {
+            diag.error = Some("Path is not a directory".to_string());
+            return diag;
+        }
+        Err(e) => {
+            diag.error = Some(format!("Path is not accessible: {}", e));
+            return diag;
+        }
+    }
+
+    let probe = path.join(format!(".soulbeet-write-test-{}", uuid::Uuid::new_v4()));
+    match tokio::fs::write(&probe, b"").await {
+        Ok(()) => {
+            diag.writable = true;
+            let _ = tokio::fs::remove_file(&probe).await;
+        }
+        Err(e) => diag.error = Some(format!("Folder is not writable: {}", e)),
+    }
+
+    let owned = path.to_path_buf();
+    let (free, total) = tokio::task::spawn_blocking(move || {
+        (
+            fs2::available_space(&owned).ok(),
+            fs2::total_space(&owned).ok(),
+        )
+    })
+    .await
+    .unwrap_or((None, None));
+    diag.free_bytes = free;
+    diag.total_bytes = total;
+
+    diag
+}
+
+/// List sub-directories of `path` for the folder picker. With no path, the
+/// configured browse roots are returned. Paths outside those roots are refused.
+#[post("/api/folders/browse", _: MemberSession)]
+pub async fn browse_directories(path: Option<String>) -> Result<DirectoryListing, ServerFnError> {
+    let roots = super::validate::browse_roots();
+
+    let Some(path) = path.filter(|p| !p.trim().is_empty()) else {
+        return Ok(DirectoryListing {
+            path: None,
+            parent: None,
+            entries: roots
+                .iter()
+                .map(|r| DirectoryEntry {
+                    name: r.to_string_lossy().to_string(),
+                    path: r.to_string_lossy().to_string(),
+                })
+                .collect(),
+        });
+    };
+
+    let dir = tokio::fs::canonicalize(&path)
+        .await
+        .map_err(|e| server_error(format!("Cannot open {}: {}", path, e)))?;
+    let Some(root) = roots.iter().find(|r| dir.starts_with(r)) else {
+        return Err(server_error(format!(
+            "{} is outside the allowed browse roots",
+            dir.display()
+        )));
+    };
+
+    let mut read_dir = tokio::fs::read_dir(&dir)
+        .await
+        .map_err(|e| server_error(format!("Cannot read {}: {}", dir.display(), e)))?;
+    let mut entries = Vec::new();
+    while let Ok(Some(entry)) = read_dir.next_entry().await {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        if entry.file_type().await.map(|t| t.is_dir()).unwrap_or(false) {
+            entries.push(DirectoryEntry {
+                name,
+                path: entry.path().to_string_lossy().to_string(),
+            });
+        }
+    }
+    entries.sort_by_key(|e| e.name.to_lowercase());
+
+    let parent = if dir == *root {
+        None
+    } else {
+        dir.parent().map(|p| p.to_string_lossy().to_string())
+    };
+
+    Ok(DirectoryListing {
+        path: Some(dir.to_string_lossy().to_string()),
+        parent,
+        entries,
+    })
 }
@@ -47,3 +47,105 @@ where
         }
     }
 }
+
+/// A signed-in user who isn't a viewer, for server fns that queue, import
+/// or change something. The role is read from the database so a change
+/// applies right away, not at the next login.
+pub struct MemberSession(pub Claims);
+
+#[cfg(feature = "server")]
+impl<S> FromRequestParts<S> for MemberSession
+where
+    S: Send + Sync,
+{
+    type Rejection = (axum::http::StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let AuthSession(claims) = AuthSession::from_request_parts(parts, state).await?;
+        match crate::models::user::User::get_by_id(&claims.sub).await {
+            Ok(user) if user.role().is_viewer() => {
+                tracing::warn!("Refused {} for viewer {}", parts.uri.path(), user.username);
+                Err((
+                    StatusCode::FORBIDDEN,
+                    "Viewers can browse but not download or change settings".to_string(),
+                ))
+            }
+            Ok(_) => Ok(MemberSession(claims)),
+            Err(e) => Err((StatusCode::UNAUTHORIZED, e)),
+        }
+    }
+}
+
+/// Authenticates integrations with an API token, sent either as
+/// `Authorization: Bearer <token>` or as a `token` query parameter (for
+/// clients such as `EventSource` that can't set headers).
+pub struct ApiTokenSession(pub crate::models::user::User);
+
+#[cfg(feature = "server")]
+impl<S> FromRequestParts<S> for ApiTokenSession
+where
+    S: Send + Sync,
+{
+    type Rejection = (axum::http::StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let from_header = parts
+            .headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .map(|t| t.trim().to_string());
+        let from_query = || {
+            parts.uri.query().and_then(|q| {
+                q.split('&')
+                    .filter_map(|pair| pair.split_once('='))
+                    .find(|(k, _)| *k == "token")
+                    .map(|(_, v)| v.to_string())
+            })
+        };
+
+        let Some(token) = from_header.or_else(from_query) else {
+            return Err((StatusCode::UNAUTHORIZED, "No API token provided".to_string()));
+        };
+
+        match crate::models::api_token::ApiToken::authenticate(&token).await {
+            Ok(user) if user.role().is_viewer() => Err((
+                StatusCode::FORBIDDEN,
+                "Viewers can't use integrations".to_string(),
+            )),
+            Ok(user) => Ok(ApiTokenSession(user)),
+            Err(e) => {
+                tracing::warn!("API token rejected: {}", e);
+                Err((StatusCode::UNAUTHORIZED, e))
+            }
+        }
+    }
+}
+
+/// The address a request came from, for rate limiting endpoints that need
+/// no sign-in. `None` when it can't be told, see `network_acl`.
+pub struct ClientIp(pub Option<std::net::IpAddr>);
+
+#[cfg(feature = "server")]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = (axum::http::StatusCode, String);
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        Ok(ClientIp(crate::network_acl::client_ip(
+            &parts.headers,
+            &parts.extensions,
+        )))
+    }
+}
@@ -0,0 +1,41 @@
+//! Aggregates behind the dashboard home page. The rest of what it shows
+//! comes from the server functions of the other pages.
+
+use dioxus::prelude::*;
+use shared::home::HomeSummary;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::covers::cover_url;
+#[cfg(feature = "server")]
+use crate::models::download_history::DownloadHistoryRow;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+
+/// History entries the summary is built from
+#[cfg(feature = "server")]
+const SUMMARY_HISTORY_LIMIT: u32 = 500;
+
+/// Albums listed under recent imports and in the recently added strip
+#[cfg(feature = "server")]
+const RECENT_IMPORTS: usize = 12;
+
+/// Files imported and failed over the last day, and the latest albums
+/// imported with their covers.
+#[get("/api/home/summary", auth: AuthSession)]
+pub async fn get_home_summary() -> Result<HomeSummary, ServerFnError> {
+    let history = DownloadHistoryRow::get_by_user(&auth.0.sub, Some(SUMMARY_HISTORY_LIMIT))
+        .await
+        .map_err(server_error)?;
+    let since = chrono::Utc::now().timestamp() - 24 * 3600;
+    let mut summary = HomeSummary::from_history(&history, since, RECENT_IMPORTS);
+    for import in &mut summary.recent_imports {
+        let album_dir = import
+            .imported_path
+            .as_deref()
+            .and_then(|p| std::path::Path::new(p).parent());
+        import.cover_url = album_dir.map(|d| cover_url(&d.to_string_lossy()));
+    }
+    Ok(summary)
+}
@@ -0,0 +1,118 @@
+//! Post-import hooks, stored as one JSON value in the app config and edited
+//! through the settings path, and the log of their runs.
+
+use dioxus::prelude::*;
+use shared::hooks::ImportHook;
+
+use crate::models::import_hook_run::ImportHookRun;
+
+#[cfg(feature = "server")]
+use super::{server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::hooks::HookContext;
+
+/// Most hooks that can be configured.
+#[cfg(feature = "server")]
+const MAX_HOOKS: usize = 20;
+
+/// Runs shown in the import log.
+#[cfg(feature = "server")]
+const RUNS_SHOWN: u32 = 50;
+
+/// The saved hooks. A value that doesn't parse counts as none.
+#[cfg(feature = "server")]
+pub async fn import_hooks() -> Result<Vec<ImportHook>, String> {
+    Ok(AppConfig::get(keys::IMPORT_HOOKS)
+        .await?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+/// Run every enabled hook for an import that just succeeded, one after the
+/// other, and log how each went.
+#[cfg(feature = "server")]
+pub(crate) async fn run_after_import(ctx: HookContext) {
+    let hooks = match import_hooks().await {
+        Ok(hooks) => hooks,
+        Err(e) => {
+            warn!("Could not load import hooks: {}", e);
+            return;
+        }
+    };
+    let commands_allowed = crate::config::CONFIG.allow_command_hooks();
+    for hook in hooks.iter().filter(|h| h.enabled) {
+        let started = std::time::Instant::now();
+        // Saved before the opt-in was turned off
+        let outcome = match hook.refused(commands_allowed) {
+            Some(reason) => soulbeet::hooks::HookOutcome {
+                success: false,
+                status: reason,
+                output: String::new(),
+            },
+            None => soulbeet::hooks::run(hook, &ctx).await,
+        };
+        let duration_ms = started.elapsed().as_millis() as i64;
+        if outcome.success {
+            info!(
+                "Import hook {} ran for {} ({})",
+                hook.name, ctx.album_path, outcome.status
+            );
+        } else {
+            warn!(
+                "Import hook {} failed for {}: {}",
+                hook.name, ctx.album_path, outcome.status
+            );
+        }
+        if let Err(e) = ImportHookRun::record(hook, &ctx.album_path, &outcome, duration_ms).await {
+            warn!("Could not log the run of import hook {}: {}", hook.name, e);
+        }
+    }
+}
+
+#[get("/api/settings/hooks", _: AuthSession)]
+pub async fn get_import_hooks() -> Result<Vec<ImportHook>, ServerFnError> {
+    import_hooks().await.map_err(server_error)
+}
+
+/// Replace the hooks. New ones get an id.
+#[post("/api/settings/hooks", _: MemberSession)]
+pub async fn set_import_hooks(hooks: Vec<ImportHook>) -> Result<Vec<ImportHook>, ServerFnError> {
+    if hooks.len() > MAX_HOOKS {
+        return Err(validation_error(format!(
+            "At most {} hooks can be set up",
+            MAX_HOOKS
+        )));
+    }
+    let commands_allowed = crate::config::CONFIG.allow_command_hooks();
+    let mut saved = Vec::with_capacity(hooks.len());
+    for mut hook in hooks {
+        if let Some(problem) = hook.problem().or_else(|| hook.refused(commands_allowed)) {
+            return Err(validation_error(problem));
+        }
+        hook.name = hook.name.trim().to_string();
+        hook.target = hook.target.trim().to_string();
+        if hook.id.is_empty() {
+            hook.id = uuid::Uuid::new_v4().to_string();
+        }
+        saved.push(hook);
+    }
+    let value = serde_json::to_string(&saved).map_err(server_error)?;
+    AppConfig::set(keys::IMPORT_HOOKS, &value)
+        .await
+        .map_err(server_error)?;
+    Ok(saved)
+}
+
+/// The latest hook runs, newest first, with their output.
+#[get("/api/settings/hooks/runs", _: AuthSession)]
+pub async fn get_import_hook_runs() -> Result<Vec<ImportHookRun>, ServerFnError> {
+    ImportHookRun::recent(RUNS_SHOWN)
+        .await
+        .map_err(server_error)
+}
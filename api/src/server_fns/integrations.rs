@@ -0,0 +1,202 @@
+//! Endpoints for home automation (Home Assistant REST sensors and
+//! `rest_command` services). Authenticated with API tokens instead of the
+//! session cookie.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::activity::ActivityEvent;
+use shared::system::SystemHealth;
+
+use super::download::AutoDownloadResult;
+use crate::models::folder::Folder;
+
+#[cfg(feature = "server")]
+use super::download::auto_download::{run_auto_download, AutoDownloadRequest};
+#[cfg(feature = "server")]
+use super::search::effective_match_mode;
+#[cfg(feature = "server")]
+use super::system::check_system_health;
+#[cfg(feature = "server")]
+use super::{server_error, validate};
+#[cfg(feature = "server")]
+use crate::activity::{download_states, latest_activity};
+#[cfg(feature = "server")]
+use crate::models::user_settings::UserSettings;
+#[cfg(feature = "server")]
+use crate::ApiTokenSession;
+#[cfg(feature = "server")]
+use shared::activity::ActivityKind;
+#[cfg(feature = "server")]
+use shared::download::{DownloadQuery, DownloadState};
+#[cfg(feature = "server")]
+use shared::metadata::Track;
+
+/// Flat summary meant to be read by a single REST sensor.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrationStatus {
+    /// Queued, downloading or waiting for import
+    pub active_downloads: usize,
+    pub downloading: usize,
+    pub importing: usize,
+    pub failed: usize,
+    pub health: SystemHealth,
+    pub last_import: Option<ActivityEvent>,
+}
+
+/// A MusicBrainz entity a download can be queued from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MusicBrainzLink {
+    Release(String),
+    Recording(String),
+}
+
+impl MusicBrainzLink {
+    /// Parse `https://musicbrainz.org/release/<mbid>` style URLs (and the
+    /// same with `recording`). A bare release MBID is accepted too.
+    pub fn parse(input: &str) -> Option<Self> {
+        let input = input.trim().trim_end_matches('/');
+        let is_mbid = |s: &str| s.len() == 36 && s.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+
+        if is_mbid(input) {
+            return Some(MusicBrainzLink::Release(input.to_string()));
+        }
+
+        let path = input.split(['?', '#']).next()?;
+        let mut segments = path.rsplit('/');
+        let id = segments.next()?;
+        let kind = segments.next()?;
+        if !is_mbid(id) {
+            return None;
+        }
+        match kind {
+            "release" => Some(MusicBrainzLink::Release(id.to_string())),
+            "recording" => Some(MusicBrainzLink::Recording(id.to_string())),
+            _ => None,
+        }
+    }
+}
+
+#[get("/api/integrations/status", session: ApiTokenSession)]
+pub async fn integration_status() -> Result<IntegrationStatus, ServerFnError> {
+    let user = session.0;
+    let states = download_states(&user.username);
+    let count = |f: fn(&DownloadState) -> bool| states.iter().filter(|s| f(s)).count();
+
+    Ok(IntegrationStatus {
+        active_downloads: count(|s| {
+            matches!(
+                s,
+                DownloadState::Queued
+                    | DownloadState::InProgress
+                    | DownloadState::Completed
+                    | DownloadState::ImportWaiting
+                    | DownloadState::ImportPaused
+                    | DownloadState::Importing
+                    | DownloadState::Uploading(_)
+            )
+        }),
+        downloading: count(|s| matches!(s, DownloadState::InProgress)),
+        importing: count(|s| matches!(s, DownloadState::Importing)),
+        failed: count(|s| matches!(s, DownloadState::Failed(_))),
+        health: check_system_health(&user.id).await,
+        last_import: latest_activity(&user.username, |k| {
+            matches!(k, ActivityKind::ImportResult { .. })
+        }),
+    })
+}
+
+/// The folders downloads can be queued into, to look up a `folder_id`.
+#[get("/api/integrations/folders", session: ApiTokenSession)]
+pub async fn integration_folders() -> Result<Vec<Folder>, ServerFnError> {
+    Folder::get_visible_to(&session.0.id)
+        .await
+        .map_err(server_error)
+}
+
+/// Queue a download from a MusicBrainz release or recording URL. The best
+/// source is picked automatically, exactly like auto-download in the UI:
+/// low-scoring results are left for manual picking in an open browser tab.
+///
+/// `folder_id` is one of the user's folders or a shared one; defaults to
+/// the user's default download folder, or their only folder.
+#[post("/api/integrations/download", session: ApiTokenSession)]
+pub async fn integration_download(
+    url: String,
+    folder_id: Option<String>,
+) -> Result<AutoDownloadResult, ServerFnError> {
+    let user = session.0;
+    let link = MusicBrainzLink::parse(&url)
+        .ok_or_else(|| server_error(format!("Not a MusicBrainz release or recording URL: {}", url)))?;
+
+    let target = match folder_id {
+        Some(folder_id) => validate::folder(&user.id, &folder_id).await?,
+        None => {
+            let default_id = UserSettings::get(&user.id)
+                .await
+                .ok()
+                .and_then(|s| s.default_download_folder_id);
+            let default = match default_id {
+                Some(id) => validate::folder(&user.id, &id).await.ok(),
+                None => None,
+            };
+            match default {
+                Some(f) => f,
+                None => {
+                    let mut folders = Folder::get_visible_to(&user.id)
+                        .await
+                        .map_err(server_error)?;
+                    if folders.len() != 1 {
+                        return Err(server_error(
+                            "No default download folder set; pass `folder_id` explicitly",
+                        ));
+                    }
+                    folders.remove(0)
+                }
+            }
+        }
+    };
+
+    let query = match link {
+        MusicBrainzLink::Release(mbid) => {
+            let provider = crate::services::metadata_provider(None, None)
+                .await
+                .map_err(server_error)?;
+            let album = provider.get_album(&mbid).await.map_err(server_error)?;
+            DownloadQuery::new(album.tracks).album(album.album)
+        }
+        MusicBrainzLink::Recording(mbid) => {
+            let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+            let info = soulbeet::http::cached_recording_lookup(&client, &mbid)
+                .await
+                .map_err(server_error)?
+                .ok_or_else(|| server_error(format!("Recording {} not found", mbid)))?;
+            DownloadQuery::new(vec![Track {
+                id: mbid.clone(),
+                title: info.title,
+                artist: info.artist,
+                album_id: None,
+                album_title: None,
+                release_date: None,
+                duration: None,
+                mbid: Some(mbid),
+                release_mbid: None,
+                details: Default::default(),
+            }])
+        }
+    };
+
+    let mut query = query;
+    query.match_mode = Some(effective_match_mode(&user.id, None).await);
+
+    Ok(run_auto_download(
+        user.username,
+        AutoDownloadRequest {
+            query,
+            folder_id: target.id,
+            folder_path: target.path,
+            simulate: false,
+            replacing_peer: None,
+        },
+    )
+    .await)
+}
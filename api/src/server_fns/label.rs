@@ -0,0 +1,188 @@
+//! A record label's catalog from MusicBrainz, to wish for or download many
+//! of its releases at once. Downloads of a selection are started one after
+//! the other, spaced out so the Soulseek searches do not flood the network.
+
+use dioxus::prelude::*;
+use shared::wishlist::LabelRelease;
+
+#[cfg(feature = "server")]
+use super::download::auto_download::{run_auto_download, AutoDownloadRequest, AutoDownloadResult};
+#[cfg(feature = "server")]
+use super::search::effective_match_mode;
+#[cfg(feature = "server")]
+use super::{metadata_error, validate, validation_error};
+#[cfg(feature = "server")]
+use crate::models::folder::Folder;
+#[cfg(feature = "server")]
+use crate::MemberSession;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::download::DownloadQuery;
+#[cfg(feature = "server")]
+use shared::metadata::Album;
+#[cfg(feature = "server")]
+use std::collections::{HashMap, VecDeque};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use std::time::Duration;
+#[cfg(feature = "server")]
+use tokio::sync::Mutex;
+
+/// Releases that can be queued at once.
+pub const MAX_LABEL_QUEUE: usize = 100;
+
+/// Wait between two queued releases. Each one runs a search on every
+/// download backend.
+#[cfg(feature = "server")]
+const QUEUE_SPACING: Duration = Duration::from_secs(30);
+
+#[cfg(feature = "server")]
+struct QueuedRelease {
+    user_id: String,
+    release: LabelRelease,
+    folder_id: String,
+    folder_path: String,
+}
+
+/// Releases waiting to be downloaded, per username. A user has an entry
+/// while their queue is being worked through.
+#[cfg(feature = "server")]
+static LABEL_QUEUES: LazyLock<Mutex<HashMap<String, VecDeque<QueuedRelease>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The label MBID of `https://musicbrainz.org/label/<mbid>` or of a bare MBID.
+#[cfg(feature = "server")]
+fn label_mbid(input: &str) -> Option<String> {
+    let input = input.trim().trim_end_matches('/');
+    let is_mbid = |s: &str| s.len() == 36 && s.chars().all(|c| c.is_ascii_hexdigit() || c == '-');
+    if is_mbid(input) {
+        return Some(input.to_string());
+    }
+    let path = input.split(['?', '#']).next()?;
+    let mut segments = path.rsplit('/');
+    let id = segments.next()?;
+    (segments.next()? == "label" && is_mbid(id)).then(|| id.to_string())
+}
+
+/// Work through a user's queue until it is empty.
+#[cfg(feature = "server")]
+async fn drain_queue(username: String) {
+    loop {
+        let next = {
+            let mut queues = LABEL_QUEUES.lock().await;
+            let next = queues.get_mut(&username).and_then(|q| q.pop_front());
+            if next.is_none() {
+                queues.remove(&username);
+            }
+            next
+        };
+        let Some(queued) = next else {
+            return;
+        };
+
+        let release = &queued.release;
+        let album = Album {
+            id: release.release_mbid.clone(),
+            title: release.title.clone(),
+            artist: release.artist.clone(),
+            release_date: Some(release.date.clone()).filter(|d| !d.is_empty()),
+            mbid: Some(release.release_mbid.clone()),
+            cover_url: None,
+            details: Default::default(),
+        };
+        let mut query = DownloadQuery::new(Vec::new()).album(album);
+        query.match_mode = Some(effective_match_mode(&queued.user_id, None).await);
+        let req = AutoDownloadRequest {
+            query,
+            folder_id: queued.folder_id,
+            folder_path: queued.folder_path,
+            simulate: false,
+            replacing_peer: None,
+        };
+        match run_auto_download(username.clone(), req).await {
+            AutoDownloadResult::Accepted { .. } => info!(
+                "Label queue of {}: started {} - {}",
+                username, release.artist, release.title
+            ),
+            AutoDownloadResult::Error(e) => warn!(
+                "Label queue of {}: could not start {} - {}: {}",
+                username, release.artist, release.title, e
+            ),
+        }
+        tokio::time::sleep(QUEUE_SPACING).await;
+    }
+}
+
+/// A label's releases, one per release group, oldest first. Takes a
+/// MusicBrainz label URL or MBID.
+#[post("/api/label/releases", _: MemberSession)]
+pub async fn get_label_releases(label: String) -> Result<Vec<LabelRelease>, ServerFnError> {
+    let mbid = label_mbid(&label)
+        .ok_or_else(|| validation_error(format!("Not a MusicBrainz label URL: {}", label)))?;
+    let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+    soulbeet::http::label_releases(&client, &mbid)
+        .await
+        .map_err(|e| metadata_error("musicbrainz", e))
+}
+
+/// Download the given releases into a folder, one every 30 seconds.
+/// Releases already waiting from an earlier call go first. Returns how
+/// many are waiting in all.
+#[post("/api/label/queue", auth: MemberSession)]
+pub async fn queue_label_releases(
+    releases: Vec<LabelRelease>,
+    folder_id: String,
+) -> Result<usize, ServerFnError> {
+    if releases.is_empty() {
+        return Err(validation_error("No releases selected"));
+    }
+    if releases.len() > MAX_LABEL_QUEUE {
+        return Err(validation_error(format!(
+            "At most {} releases can be queued at once",
+            MAX_LABEL_QUEUE
+        )));
+    }
+    let folder = validate::folder(&auth.0.sub, &folder_id).await?;
+    Ok(enqueue_releases(&auth.0.username, &auth.0.sub, releases, &folder).await)
+}
+
+/// Queue releases for a user, starting the queue if it is idle. Returns
+/// how many are waiting in all.
+#[cfg(feature = "server")]
+pub(crate) async fn enqueue_releases(
+    username: &str,
+    user_id: &str,
+    releases: Vec<LabelRelease>,
+    folder: &Folder,
+) -> usize {
+    let mut queues = LABEL_QUEUES.lock().await;
+    let running = queues.contains_key(username);
+    let queue = queues.entry(username.to_string()).or_default();
+    queue.extend(releases.into_iter().map(|release| QueuedRelease {
+        user_id: user_id.to_string(),
+        release,
+        folder_id: folder.id.clone(),
+        folder_path: folder.path.clone(),
+    }));
+    let waiting = queue.len();
+    drop(queues);
+
+    info!("{} queued label releases, {} waiting", username, waiting);
+    if !running {
+        tokio::spawn(drain_queue(username.to_string()));
+    }
+    waiting
+}
+
+/// The releases waiting in a user's queue, next first.
+#[cfg(feature = "server")]
+pub(crate) async fn queued_releases(username: &str) -> Vec<LabelRelease> {
+    LABEL_QUEUES
+        .lock()
+        .await
+        .get(username)
+        .map(|q| q.iter().map(|queued| queued.release.clone()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,227 @@
+//! Albums of a library folder, the tracks a partial one lacks compared
+//! with its release, and removing one.
+
+use dioxus::prelude::*;
+use shared::library::{AlbumCompletion, LibraryAlbum};
+
+#[cfg(feature = "server")]
+use super::{
+    cleanup_empty_ancestors, forbidden_error, importer_error, metadata_error, move_aside,
+    not_found_error, server_error, validate, validation_error,
+};
+#[cfg(feature = "server")]
+use crate::activity::record_activity;
+#[cfg(feature = "server")]
+use crate::models::user_settings::UserSettings;
+#[cfg(feature = "server")]
+use crate::services::{metadata_provider, music_importer};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::info;
+#[cfg(feature = "server")]
+use shared::activity::ActivityKind;
+#[cfg(feature = "server")]
+use shared::library::LibraryIndexEntry;
+#[cfg(feature = "server")]
+use shared::metadata::{AlbumWithTracks, SearchResult};
+#[cfg(feature = "server")]
+use shared::recommendation::CandidateSet;
+#[cfg(feature = "server")]
+use std::collections::{BTreeMap, HashSet};
+
+/// Directory of a folder removed albums are moved to when trashed.
+#[cfg(feature = "server")]
+const TRASH_DIR: &str = ".trash";
+
+/// Releases looked at when matching a library album.
+#[cfg(feature = "server")]
+const RELEASE_CANDIDATES: usize = 5;
+
+/// Tracks of a library folder grouped by the directory they are in.
+#[cfg(feature = "server")]
+async fn albums_by_dir(
+    user_id: &str,
+    folder_id: &str,
+) -> Result<BTreeMap<String, Vec<LibraryIndexEntry>>, ServerFnError> {
+    let folder = validate::folder(user_id, folder_id).await?;
+    let importer = music_importer(None).await.map_err(importer_error)?;
+    let entries = importer
+        .library_index(std::path::Path::new(&folder.path))
+        .await
+        .map_err(importer_error)?;
+
+    let mut albums: BTreeMap<String, Vec<LibraryIndexEntry>> = BTreeMap::new();
+    for entry in entries {
+        let Some(dir) = std::path::Path::new(&entry.path).parent() else {
+            continue;
+        };
+        albums
+            .entry(dir.to_string_lossy().to_string())
+            .or_default()
+            .push(entry);
+    }
+    Ok(albums)
+}
+
+/// Albums in one of the user's folders, by artist and title.
+#[post("/api/library/albums", auth: AuthSession)]
+pub async fn get_library_albums(folder_id: String) -> Result<Vec<LibraryAlbum>, ServerFnError> {
+    let mut albums: Vec<LibraryAlbum> = albums_by_dir(&auth.0.sub, &folder_id)
+        .await?
+        .into_iter()
+        .filter_map(|(path, tracks)| {
+            let first = tracks.first()?;
+            Some(LibraryAlbum {
+                artist: first.artist.clone(),
+                album: first.album.clone(),
+                path,
+                track_count: tracks.len(),
+            })
+        })
+        .collect();
+    albums.sort_by(|a, b| {
+        (a.artist.to_lowercase(), a.album.to_lowercase())
+            .cmp(&(b.artist.to_lowercase(), b.album.to_lowercase()))
+    });
+    Ok(albums)
+}
+
+/// Match a library album to its release and list the tracks it lacks. Of
+/// the first few matches, the release holding most of the owned tracks is
+/// used, the shortest one on a tie so bonus tracks of a deluxe edition
+/// don't count as missing.
+#[post("/api/library/albums/missing", auth: AuthSession)]
+pub async fn find_missing_tracks(
+    folder_id: String,
+    path: String,
+) -> Result<AlbumCompletion, ServerFnError> {
+    let mut albums = albums_by_dir(&auth.0.sub, &folder_id).await?;
+    let owned = albums
+        .remove(&path)
+        .ok_or_else(|| not_found_error("Album not found in this folder"))?;
+    let Some(first) = owned.first() else {
+        return Err(not_found_error("Album not found in this folder"));
+    };
+    let artist = first.artist.clone();
+    let title = first.album.clone();
+
+    let settings = UserSettings::get(&auth.0.sub).await.map_err(server_error)?;
+    let provider = metadata_provider(
+        settings.default_metadata_provider.as_deref(),
+        settings.lastfm_api_key.as_deref(),
+    )
+    .await
+    .map_err(server_error)?;
+
+    let owned_titles: HashSet<String> = owned
+        .iter()
+        .map(|t| CandidateSet::key(&artist, &t.title))
+        .collect();
+    // Titles of the release that are owned, and its track count
+    let fit = |release: &AlbumWithTracks| {
+        let covered = release
+            .tracks
+            .iter()
+            .filter(|t| owned_titles.contains(&CandidateSet::key(&artist, &t.title)))
+            .count();
+        (covered, std::cmp::Reverse(release.tracks.len()))
+    };
+
+    let results = provider
+        .search_albums(Some(&artist), &title, RELEASE_CANDIDATES)
+        .await
+        .map_err(|e| metadata_error(provider.id(), e))?;
+    let mut best: Option<AlbumWithTracks> = None;
+    for result in results {
+        let SearchResult::Album(album) = result else {
+            continue;
+        };
+        let release = provider
+            .get_album(&album.id)
+            .await
+            .map_err(|e| metadata_error(provider.id(), e))?;
+        if best.as_ref().is_none_or(|b| fit(&release) > fit(b)) {
+            best = Some(release);
+        }
+    }
+    let release = best
+        .ok_or_else(|| not_found_error(format!("No release found for {} - {}", artist, title)))?;
+
+    let tracklist = release.tracks.iter().map(|t| t.title.clone()).collect();
+    let missing = release
+        .tracks
+        .into_iter()
+        .filter(|t| !owned_titles.contains(&CandidateSet::key(&artist, &t.title)))
+        .collect();
+
+    Ok(AlbumCompletion {
+        album: release.album,
+        path,
+        owned: owned.len(),
+        missing,
+        tracklist,
+    })
+}
+
+/// Remove an album directory of a library folder: its tracks are dropped
+/// from the library index, then the files are deleted or, with `trash`,
+/// moved to the folder's `.trash`.
+#[post("/api/library/albums/remove", auth: MemberSession)]
+pub async fn remove_library_album(
+    folder_id: String,
+    path: String,
+    trash: bool,
+) -> Result<(), ServerFnError> {
+    let folder = validate::folder(&auth.0.sub, &folder_id).await?;
+    if !folder.is_owned_by(&auth.0.sub) {
+        return Err(forbidden_error("Only the folder's owner can remove albums"));
+    }
+    let album_dir = validate::folder_path(&auth.0.sub, &path).await?;
+    let root = std::path::Path::new(&folder.path);
+    if !album_dir.starts_with(root) || album_dir == root {
+        return Err(validation_error("Not an album directory of this folder"));
+    }
+    if album_dir.starts_with(root.join(TRASH_DIR)) {
+        return Err(validation_error("The album is already in the trash"));
+    }
+    if !album_dir.is_dir() {
+        return Err(not_found_error("Album directory not found"));
+    }
+
+    let importer = music_importer(None).await.map_err(importer_error)?;
+    importer
+        .remove_paths(&[album_dir.as_path()])
+        .await
+        .map_err(importer_error)?;
+
+    if trash {
+        let target = move_aside(root, &album_dir, TRASH_DIR)
+            .await
+            .map_err(server_error)?;
+        info!(
+            "{} moved {} to {}",
+            auth.0.username,
+            album_dir.display(),
+            target.display()
+        );
+    } else {
+        tokio::fs::remove_dir_all(&album_dir)
+            .await
+            .map_err(|e| server_error(format!("Failed to delete: {}", e)))?;
+        info!("{} deleted {}", auth.0.username, album_dir.display());
+    }
+    if let Some(parent) = album_dir.parent() {
+        let _ = cleanup_empty_ancestors(parent).await;
+    }
+
+    record_activity(
+        &auth.0.username,
+        ActivityKind::AlbumRemoved {
+            folder_id: folder.id,
+            path: album_dir.to_string_lossy().into_owned(),
+            trashed: trash,
+        },
+    );
+    Ok(())
+}
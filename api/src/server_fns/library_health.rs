@@ -0,0 +1,157 @@
+//! Statistics of a library folder and a check of its importer's index
+//! against the files on disk, with the fixes that lose nothing: forgetting
+//! tracks whose file is gone, and indexing audio files left out.
+
+use dioxus::prelude::*;
+use shared::library::LibraryHealth;
+
+#[cfg(feature = "server")]
+use super::{
+    forbidden_error, importer_error, server_error, upload::UPLOAD_AUDIO_EXTENSIONS, validate,
+};
+#[cfg(feature = "server")]
+use crate::services::music_importer;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::info;
+#[cfg(feature = "server")]
+use std::collections::{BTreeMap, HashMap, HashSet};
+#[cfg(feature = "server")]
+use std::path::{Path, PathBuf};
+
+/// Audio files under `root`, leaving out hidden directories (trash, archived
+/// albums) and the Discovery tree, which is not part of the library.
+#[cfg(feature = "server")]
+async fn audio_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if name.starts_with('.') {
+                continue;
+            }
+            if path.is_dir() {
+                if dir != root || name != "Discovery" {
+                    stack.push(path);
+                }
+            } else if path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| UPLOAD_AUDIO_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
+
+#[cfg(feature = "server")]
+async fn check(user_id: &str, folder_id: &str) -> Result<LibraryHealth, ServerFnError> {
+    let folder = validate::folder(user_id, folder_id).await?;
+    let root = PathBuf::from(&folder.path);
+    let importer = music_importer(None).await.map_err(importer_error)?;
+    let entries = importer
+        .library_index(&root)
+        .await
+        .map_err(importer_error)?;
+
+    let mut health = LibraryHealth {
+        tracks: entries.len(),
+        ..Default::default()
+    };
+    let mut albums = HashSet::new();
+    let mut formats: HashMap<String, usize> = HashMap::new();
+    for entry in &entries {
+        if let Some(dir) = Path::new(&entry.path).parent() {
+            albums.insert(dir.to_path_buf());
+        }
+        *formats.entry(entry.format.clone()).or_default() += 1;
+        match tokio::fs::metadata(&entry.path).await {
+            Ok(meta) => health.size += meta.len(),
+            Err(_) => health.missing.push(entry.path.clone()),
+        }
+    }
+    health.albums = albums.len();
+    health.formats = formats.into_iter().collect();
+    health
+        .formats
+        .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    if !entries.is_empty() {
+        let indexed: HashSet<&Path> = entries.iter().map(|e| Path::new(&e.path)).collect();
+        health.untracked = audio_files(&root)
+            .await
+            .into_iter()
+            .filter(|f| !indexed.contains(f.as_path()))
+            .map(|f| f.to_string_lossy().into_owned())
+            .collect();
+        health.untracked.sort();
+    }
+    Ok(health)
+}
+
+/// Statistics of one of the user's folders, and the tracks its index and
+/// the disk disagree on.
+#[post("/api/library/health", auth: AuthSession)]
+pub async fn get_library_health(folder_id: String) -> Result<LibraryHealth, ServerFnError> {
+    check(&auth.0.sub, &folder_id).await
+}
+
+/// Forget the missing tracks of a folder's index and index its untracked
+/// files where they are, then check it again.
+#[post("/api/library/health/fix", auth: MemberSession)]
+pub async fn fix_library_health(
+    folder_id: String,
+    forget_missing: bool,
+    index_untracked: bool,
+) -> Result<LibraryHealth, ServerFnError> {
+    let folder = validate::folder(&auth.0.sub, &folder_id).await?;
+    if !folder.is_owned_by(&auth.0.sub) {
+        return Err(forbidden_error("Only the folder's owner can fix its index"));
+    }
+    let health = check(&auth.0.sub, &folder_id).await?;
+    let importer = music_importer(None).await.map_err(importer_error)?;
+
+    if forget_missing && !health.missing.is_empty() {
+        let missing: Vec<&Path> = health.missing.iter().map(Path::new).collect();
+        importer
+            .remove_paths(&missing)
+            .await
+            .map_err(importer_error)?;
+        info!(
+            "{} removed {} missing track(s) from the index",
+            auth.0.username,
+            missing.len()
+        );
+    }
+
+    if index_untracked && !health.untracked.is_empty() {
+        let mut by_dir: BTreeMap<&Path, Vec<&Path>> = BTreeMap::new();
+        for file in &health.untracked {
+            let file = Path::new(file);
+            if let Some(dir) = file.parent() {
+                by_dir.entry(dir).or_default().push(file);
+            }
+        }
+        for (dir, files) in by_dir {
+            importer
+                .add_to_album(dir, &files)
+                .await
+                .map_err(|e| server_error(format!("{}: {}", dir.display(), e)))?;
+        }
+        info!(
+            "{} indexed {} untracked file(s)",
+            auth.0.username,
+            health.untracked.len()
+        );
+    }
+
+    check(&auth.0.sub, &folder_id).await
+}
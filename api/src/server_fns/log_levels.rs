@@ -0,0 +1,70 @@
+//! Per-module log levels, saved in the app config and applied to the running
+//! server, and the logging setup they sit on.
+
+use dioxus::prelude::*;
+use shared::logging::{LogLevelOverride, LoggingStatus};
+
+#[cfg(feature = "server")]
+use super::{server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+
+/// Most modules that can have their own level.
+#[cfg(feature = "server")]
+const MAX_OVERRIDES: usize = 50;
+
+/// Apply the overrides saved in the app config, at startup.
+#[cfg(feature = "server")]
+pub async fn apply_saved_log_levels() {
+    let overrides: Vec<LogLevelOverride> = match AppConfig::get(keys::LOG_LEVELS).await {
+        Ok(value) => value
+            .and_then(|v| serde_json::from_str(&v).ok())
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!("Could not load log levels: {}", e);
+            return;
+        }
+    };
+    if overrides.is_empty() {
+        return;
+    }
+    if let Err(e) = crate::logging::set_overrides(overrides) {
+        warn!("Ignoring saved log levels: {}", e);
+    }
+}
+
+#[get("/api/settings/logging", _: AuthSession)]
+pub async fn get_logging_status() -> Result<LoggingStatus, ServerFnError> {
+    Ok(crate::logging::status())
+}
+
+/// Replace the per-module levels. They apply right away and after restarts.
+#[post("/api/settings/logging", _: MemberSession)]
+pub async fn set_log_levels(
+    overrides: Vec<LogLevelOverride>,
+) -> Result<LoggingStatus, ServerFnError> {
+    if overrides.len() > MAX_OVERRIDES {
+        return Err(validation_error(format!(
+            "At most {} modules can have their own level",
+            MAX_OVERRIDES
+        )));
+    }
+    let mut saved = Vec::with_capacity(overrides.len());
+    for mut o in overrides {
+        if let Some(problem) = o.problem() {
+            return Err(validation_error(problem));
+        }
+        o.target = o.target.trim().to_string();
+        saved.push(o);
+    }
+    crate::logging::set_overrides(saved.clone()).map_err(validation_error)?;
+    let value = serde_json::to_string(&saved).map_err(server_error)?;
+    AppConfig::set(keys::LOG_LEVELS, &value)
+        .await
+        .map_err(server_error)?;
+    Ok(crate::logging::status())
+}
@@ -0,0 +1,169 @@
+//! Housekeeping of the download backend. slskd keeps every transfer in its
+//! list until told otherwise, which makes each progress poll slower; this
+//! prunes finished transfers on a schedule.
+
+use dioxus::prelude::*;
+use shared::system::TransferCleanupStatus;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::services::{available_download_backends, download_backend};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "server")]
+use std::sync::LazyLock;
+#[cfg(feature = "server")]
+use tokio::sync::RwLock;
+
+#[cfg(feature = "server")]
+const DEFAULT_RETENTION_HOURS: u32 = 24;
+#[cfg(feature = "server")]
+const DEFAULT_INTERVAL_HOURS: u32 = 24;
+
+/// Outcome of the last run. Kept in memory, so it resets on restart and
+/// the first scheduled run happens shortly after startup.
+#[cfg(feature = "server")]
+#[derive(Default)]
+struct LastRun {
+    at: Option<i64>,
+    removed: Option<usize>,
+    error: Option<String>,
+}
+
+#[cfg(feature = "server")]
+static LAST_RUN: LazyLock<RwLock<LastRun>> = LazyLock::new(|| RwLock::new(LastRun::default()));
+
+#[cfg(feature = "server")]
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+#[cfg(feature = "server")]
+async fn config_hours(key: &str, default: u32) -> u32 {
+    AppConfig::get(key)
+        .await
+        .ok()
+        .flatten()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[cfg(feature = "server")]
+async fn cleanup_status() -> TransferCleanupStatus {
+    let enabled = AppConfig::get(keys::TRANSFER_CLEANUP_ENABLED)
+        .await
+        .ok()
+        .flatten()
+        .is_some_and(|v| v == "true");
+    let last = LAST_RUN.read().await;
+    TransferCleanupStatus {
+        enabled,
+        retention_hours: config_hours(keys::TRANSFER_RETENTION_HOURS, DEFAULT_RETENTION_HOURS)
+            .await,
+        interval_hours: config_hours(
+            keys::TRANSFER_CLEANUP_INTERVAL_HOURS,
+            DEFAULT_INTERVAL_HOURS,
+        )
+        .await,
+        last_run: last.at,
+        last_removed: last.removed,
+        last_error: last.error.clone(),
+    }
+}
+
+/// Prune finished transfers older than `retention_hours` from every
+/// configured download backend and record the outcome.
+#[cfg(feature = "server")]
+async fn prune_transfers(retention_hours: u32) {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    let older_than = std::time::Duration::from_secs(u64::from(retention_hours) * 3600);
+    let mut removed = 0;
+    let mut errors = Vec::new();
+    for (id, name) in available_download_backends() {
+        let backend = match download_backend(Some(id)).await {
+            Ok(backend) => backend,
+            // Not configured yet, nothing to clean
+            Err(_) => continue,
+        };
+        match backend.prune_transfers(older_than).await {
+            Ok(n) => removed += n,
+            Err(e) => {
+                warn!("Transfer cleanup failed on {}: {}", name, e);
+                errors.push(format!("{}: {}", name, e));
+            }
+        }
+    }
+    info!("Transfer cleanup removed {} finished transfer(s)", removed);
+
+    *LAST_RUN.write().await = LastRun {
+        at: Some(chrono::Utc::now().timestamp()),
+        removed: Some(removed),
+        error: (!errors.is_empty()).then(|| errors.join("; ")),
+    };
+    RUNNING.store(false, Ordering::SeqCst);
+}
+
+/// Called from the periodic cleanup task; runs the transfer cleanup when it
+/// is enabled and its interval has elapsed.
+#[cfg(feature = "server")]
+pub async fn run_scheduled_maintenance() {
+    let status = cleanup_status().await;
+    if !status.enabled {
+        return;
+    }
+    let interval_secs = i64::from(status.interval_hours.max(1)) * 3600;
+    let due = status
+        .last_run
+        .is_none_or(|at| chrono::Utc::now().timestamp() - at >= interval_secs);
+    if due {
+        prune_transfers(status.retention_hours).await;
+    }
+}
+
+#[get("/api/maintenance/transfers", _: AuthSession)]
+pub async fn get_transfer_cleanup() -> Result<TransferCleanupStatus, ServerFnError> {
+    Ok(cleanup_status().await)
+}
+
+#[post("/api/maintenance/transfers", _: MemberSession)]
+pub async fn update_transfer_cleanup(
+    enabled: bool,
+    retention_hours: u32,
+    interval_hours: u32,
+) -> Result<TransferCleanupStatus, ServerFnError> {
+    if interval_hours == 0 {
+        return Err(server_error(
+            "The cleanup interval must be at least one hour",
+        ));
+    }
+    AppConfig::set(keys::TRANSFER_CLEANUP_ENABLED, &enabled.to_string())
+        .await
+        .map_err(server_error)?;
+    AppConfig::set(keys::TRANSFER_RETENTION_HOURS, &retention_hours.to_string())
+        .await
+        .map_err(server_error)?;
+    AppConfig::set(
+        keys::TRANSFER_CLEANUP_INTERVAL_HOURS,
+        &interval_hours.to_string(),
+    )
+    .await
+    .map_err(server_error)?;
+    Ok(cleanup_status().await)
+}
+
+/// Run the cleanup immediately with the saved retention, whether or not
+/// the schedule is enabled.
+#[post("/api/maintenance/transfers/run", _: MemberSession)]
+pub async fn run_transfer_cleanup_now() -> Result<TransferCleanupStatus, ServerFnError> {
+    let status = cleanup_status().await;
+    prune_transfers(status.retention_hours).await;
+    Ok(cleanup_status().await)
+}
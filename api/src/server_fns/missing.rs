@@ -0,0 +1,148 @@
+//! "You listen to this but don't own it": ListenBrainz listening history and
+//! recommendations compared with the library index, and the same check for
+//! the tracks of an album about to be searched.
+
+use dioxus::prelude::*;
+use shared::metadata::Track;
+use shared::recommendation::MissingMusic;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::models::{folder::Folder, user_settings::UserSettings};
+#[cfg(feature = "server")]
+use crate::services::music_importer;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+#[cfg(feature = "server")]
+use shared::recommendation::{CandidateSet, TimePeriod};
+#[cfg(feature = "server")]
+use soulbeet::listenbrainz::client::ListenBrainzClient;
+#[cfg(feature = "server")]
+use std::collections::HashSet;
+
+/// Top releases and recordings taken from the listening history.
+#[cfg(feature = "server")]
+const HISTORY_COUNT: u32 = 100;
+
+/// Albums and tracks in the user's folders, as matching keys.
+#[cfg(feature = "server")]
+async fn owned_keys(user_id: &str) -> Result<(HashSet<String>, HashSet<String>), String> {
+    let folders = Folder::get_all_by_user(user_id).await?;
+    let importer = music_importer(None).await?;
+    let mut albums = HashSet::new();
+    let mut tracks = HashSet::new();
+    for folder in folders {
+        let entries = importer
+            .library_index(std::path::Path::new(&folder.path))
+            .await
+            .map_err(|e| e.to_string())?;
+        for entry in entries {
+            albums.insert(CandidateSet::key(&entry.artist, &entry.album));
+            tracks.insert(CandidateSet::key(&entry.artist, &entry.title));
+        }
+    }
+    Ok((albums, tracks))
+}
+
+/// Albums and tracks from the user's ListenBrainz top lists and
+/// recommendation playlists that aren't in any of their folders.
+#[get("/api/missing-music", auth: AuthSession)]
+pub async fn get_missing_music() -> Result<Vec<MissingMusic>, ServerFnError> {
+    let settings = UserSettings::get(&auth.0.sub).await.map_err(server_error)?;
+    let username = settings
+        .listenbrainz_username
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| server_error("Set your ListenBrainz username in Settings first"))?;
+    let client = ListenBrainzClient::new(username, settings.listenbrainz_token);
+
+    let (owned_albums, owned_tracks) = owned_keys(&auth.0.sub).await.map_err(server_error)?;
+    let mut seen = HashSet::new();
+    let mut missing = Vec::new();
+
+    let releases = client
+        .get_top_releases(TimePeriod::AllTime, HISTORY_COUNT)
+        .await
+        .map_err(server_error)?;
+    for release in releases.payload.releases {
+        let key = CandidateSet::key(&release.artist_name, &release.release_name);
+        if owned_albums.contains(&key) || !seen.insert(format!("album:{}", key)) {
+            continue;
+        }
+        missing.push(MissingMusic {
+            artist: release.artist_name,
+            title: release.release_name,
+            is_album: true,
+            listen_count: release.listen_count,
+            recommended: false,
+            mbid: release.release_mbid,
+        });
+    }
+
+    let recordings = client
+        .get_top_recordings(TimePeriod::AllTime, HISTORY_COUNT)
+        .await
+        .map_err(server_error)?;
+    for recording in recordings.payload.recordings {
+        let key = CandidateSet::key(&recording.artist_name, &recording.track_name);
+        if owned_tracks.contains(&key) || !seen.insert(format!("track:{}", key)) {
+            continue;
+        }
+        missing.push(MissingMusic {
+            artist: recording.artist_name,
+            title: recording.track_name,
+            is_album: false,
+            listen_count: recording.listen_count,
+            recommended: false,
+            mbid: recording.recording_mbid,
+        });
+    }
+
+    // Recommendations need a token and are only generated for active users
+    match client.get_recommendation_playlists().await {
+        Ok(playlists) => {
+            for track in playlists
+                .playlists
+                .into_iter()
+                .flat_map(|p| p.playlist.track)
+            {
+                let key = CandidateSet::key(&track.creator, &track.title);
+                if owned_tracks.contains(&key) || !seen.insert(format!("track:{}", key)) {
+                    continue;
+                }
+                let mbid = track
+                    .identifier
+                    .as_deref()
+                    .and_then(|id| id.rsplit('/').next())
+                    .map(String::from);
+                missing.push(MissingMusic {
+                    artist: track.creator,
+                    title: track.title,
+                    is_album: false,
+                    listen_count: 0,
+                    recommended: true,
+                    mbid,
+                });
+            }
+        }
+        Err(e) => warn!("Could not fetch ListenBrainz recommendations: {}", e),
+    }
+
+    // Most listened first; recommendations have no listens and stay last
+    missing.sort_by(|a, b| b.listen_count.cmp(&a.listen_count));
+    Ok(missing)
+}
+
+/// Ids of the given tracks already in one of the user's folders, matched on
+/// artist and title.
+#[post("/api/library/owned-tracks", auth: AuthSession)]
+pub async fn get_owned_tracks(tracks: Vec<Track>) -> Result<Vec<String>, ServerFnError> {
+    let (_, owned_tracks) = owned_keys(&auth.0.sub).await.map_err(server_error)?;
+    Ok(tracks
+        .into_iter()
+        .filter(|t| owned_tracks.contains(&CandidateSet::key(&t.artist, &t.title)))
+        .map(|t| t.id)
+        .collect())
+}
@@ -1,41 +1,165 @@
 use dioxus::prelude::*;
+use shared::error::{ApiError, ApiErrorKind};
 
+pub mod api_token;
 pub mod auth;
+pub mod bandwidth;
+pub mod calendar;
+pub mod discogs;
 pub mod discovery;
 pub mod download;
+pub mod export;
+pub mod features;
 pub mod folder;
 pub mod guard;
+pub mod home;
+pub mod import_hooks;
+pub mod integrations;
+pub mod label;
+pub mod library;
+pub mod library_health;
+pub mod log_levels;
+pub mod maintenance;
+pub mod missing;
 pub mod navidrome;
+pub mod party;
+pub mod password_reset;
+pub mod review;
+pub mod routing;
 pub mod search;
+pub mod search_history;
+pub mod security;
+pub mod session;
 pub mod settings;
 pub mod system;
+pub mod upload;
 pub mod user;
+#[cfg(feature = "server")]
+pub(crate) mod validate;
+pub mod wishlist;
 
+pub use api_token::*;
 pub use auth::*;
+pub use bandwidth::*;
+pub use calendar::*;
+pub use discogs::*;
 pub use discovery::*;
 pub use download::*;
+pub use export::*;
+pub use features::*;
 pub use folder::*;
 pub use guard::*;
+pub use home::*;
+pub use import_hooks::*;
+pub use integrations::*;
+pub use label::*;
+pub use library::*;
+pub use library_health::*;
+pub use log_levels::*;
+pub use maintenance::*;
+pub use missing::*;
 pub use navidrome::*;
+pub use party::*;
+pub use password_reset::*;
+pub use review::*;
+pub use routing::*;
 pub use search::*;
+pub use search_history::*;
+pub use security::*;
+pub use session::*;
 pub use settings::*;
 pub use system::*;
+pub use upload::*;
 pub use user::*;
+pub use wishlist::*;
 
-pub fn server_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+/// An error of the given kind. The kind travels in the error details so
+/// the UI can tell an slskd outage from a typo.
+pub fn api_error<E: std::fmt::Display>(kind: ApiErrorKind, e: E) -> ServerFnError {
+    let error = ApiError::new(kind, e.to_string());
     ServerFnError::ServerError {
-        message: e.to_string(),
-        code: 500,
-        details: None,
+        code: kind.status(),
+        details: serde_json::to_value(&error).ok(),
+        message: error.message,
     }
 }
 
+pub fn server_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::Internal, e)
+}
+
 pub fn unauthorized_error<E: std::fmt::Display>(e: E) -> ServerFnError {
-    ServerFnError::ServerError {
-        message: e.to_string(),
-        code: 401,
-        details: None,
-    }
+    api_error(ApiErrorKind::Auth, e)
+}
+
+pub fn forbidden_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::Forbidden, e)
+}
+
+pub fn validation_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::Validation, e)
+}
+
+pub fn not_found_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::NotFound, e)
+}
+
+pub fn slskd_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::Slskd, e)
+}
+
+pub fn importer_error<E: std::fmt::Display>(e: E) -> ServerFnError {
+    api_error(ApiErrorKind::Importer, e)
+}
+
+/// Error from a metadata provider. Only MusicBrainz failures are reported
+/// as such; a missing release stays a not-found.
+#[cfg(feature = "server")]
+pub fn metadata_error(provider_id: &str, e: soulbeet::error::SoulseekError) -> ServerFnError {
+    let kind = match &e {
+        soulbeet::error::SoulseekError::Api { status: 404, .. } => ApiErrorKind::NotFound,
+        soulbeet::error::SoulseekError::Api { status: 400, .. } => ApiErrorKind::Validation,
+        _ if provider_id == "musicbrainz" => ApiErrorKind::MusicBrainz,
+        _ => ApiErrorKind::Internal,
+    };
+    api_error(kind, e)
+}
+
+/// Link to `path` on the instance, for links users pass on, at the address
+/// the request came to unless PUBLIC_URL says otherwise.
+#[cfg(feature = "server")]
+pub fn app_link(headers: &axum::http::HeaderMap, path: &str) -> Result<String, ServerFnError> {
+    let host = headers
+        .get(axum::http::header::HOST)
+        .and_then(|h| h.to_str().ok());
+    crate::config::CONFIG
+        .app_url(host)
+        .map(|url| format!("{}{}", url, path))
+        .ok_or_else(|| server_error("Set PUBLIC_URL to build links to this instance"))
+}
+
+/// Move `dir` into the `bin` directory of `folder`, under its name and the
+/// time, returning where it went. Bins are hidden directories, so media
+/// servers scanning the folder skip them.
+#[cfg(feature = "server")]
+pub async fn move_aside(
+    folder: &std::path::Path,
+    dir: &std::path::Path,
+    bin: &str,
+) -> Result<std::path::PathBuf, String> {
+    let name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "album".to_string());
+    let bin = folder.join(bin);
+    tokio::fs::create_dir_all(&bin)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", bin.display(), e))?;
+    let target = bin.join(format!("{} ({})", name, chrono::Utc::now().timestamp()));
+    tokio::fs::rename(dir, &target)
+        .await
+        .map_err(|e| format!("Failed to move {}: {}", dir.display(), e))?;
+    Ok(target)
 }
 
 /// Remove a directory if empty, then recurse upward to its parent.
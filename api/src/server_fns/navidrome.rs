@@ -15,14 +15,14 @@ use crate::models::user_settings::UserSettings;
 #[cfg(feature = "server")]
 use crate::services::navidrome_client_for_user;
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::{AuthSession, MemberSession};
 #[cfg(feature = "server")]
 use shared::navidrome::DiscoveryStatus;
 
 #[cfg(feature = "server")]
 use super::server_error;
 
-#[post("/api/navidrome/sync-ratings", auth: AuthSession)]
+#[post("/api/navidrome/sync-ratings", auth: MemberSession)]
 pub async fn sync_ratings() -> Result<SyncResult, ServerFnError> {
     sync_ratings_internal(&auth.0.sub)
         .await
@@ -0,0 +1,256 @@
+//! Party mode: a host shares a link for the evening, guests without an
+//! account ask for tracks through it, and the host approves the ones that
+//! get downloaded into the party folder. Guests only see the party's name
+//! and its queue.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::models::party::{Party, PartyRequest};
+
+#[cfg(feature = "server")]
+use super::download::auto_download::{run_auto_download, AutoDownloadRequest, AutoDownloadResult};
+#[cfg(feature = "server")]
+use super::search::effective_match_mode;
+#[cfg(feature = "server")]
+use super::{not_found_error, server_error, validate, validation_error};
+#[cfg(feature = "server")]
+use crate::models::party::PartyRequestStatus;
+#[cfg(feature = "server")]
+use crate::models::user::User;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use shared::download::DownloadQuery;
+#[cfg(feature = "server")]
+use shared::metadata::Track;
+
+/// Longest a party can last, in hours.
+#[cfg(feature = "server")]
+const MAX_PARTY_HOURS: u32 = 48;
+
+/// Requests waiting for the host before guests are asked to hold on.
+#[cfg(feature = "server")]
+const MAX_PENDING_REQUESTS: i64 = 50;
+
+/// Longest guest name, artist or title accepted, in characters.
+#[cfg(feature = "server")]
+const MAX_FIELD_CHARS: usize = 200;
+
+/// A party with the token of its link. The token is only returned when the
+/// party starts or gets a new link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartyLink {
+    pub party: Party,
+    pub token: String,
+}
+
+/// What a guest sees of a party.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PartyGuestView {
+    pub name: String,
+    /// Username of the host
+    pub host: String,
+    pub expires_at: i64,
+    /// Every request so far, oldest first
+    pub requests: Vec<PartyRequest>,
+}
+
+/// A field sent by a guest, trimmed, or an error naming it.
+#[cfg(feature = "server")]
+fn guest_field(value: &str, what: &str) -> Result<String, ServerFnError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(validation_error(format!("The {} is missing", what)));
+    }
+    if value.chars().count() > MAX_FIELD_CHARS {
+        return Err(validation_error(format!(
+            "The {} is longer than {} characters",
+            what, MAX_FIELD_CHARS
+        )));
+    }
+    Ok(value.to_string())
+}
+
+/// One of the caller's parties.
+#[cfg(feature = "server")]
+async fn hosted_party(host_id: &str, party_id: &str) -> Result<Party, ServerFnError> {
+    Party::get(party_id, host_id)
+        .await
+        .map_err(server_error)?
+        .ok_or_else(|| not_found_error("Party not found"))
+}
+
+/// The party of a guest link, if it is still on.
+#[cfg(feature = "server")]
+async fn open_party(token: &str) -> Result<Party, ServerFnError> {
+    Party::find_open(token)
+        .await
+        .map_err(server_error)?
+        .ok_or_else(|| not_found_error("This party link is invalid or the party is over"))
+}
+
+/// Start a party lasting `hours`, downloading into the folder.
+#[post("/api/party/start", auth: MemberSession)]
+pub async fn start_party(
+    name: String,
+    folder_id: String,
+    hours: u32,
+) -> Result<PartyLink, ServerFnError> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err(validation_error("Name the party"));
+    }
+    if !(1..=MAX_PARTY_HOURS).contains(&hours) {
+        return Err(validation_error(format!(
+            "Parties last between 1 and {} hours",
+            MAX_PARTY_HOURS
+        )));
+    }
+    let folder = validate::folder(&auth.0.sub, &folder_id).await?;
+
+    if let Err(e) = Party::delete_ended().await {
+        warn!("Could not drop ended parties: {}", e);
+    }
+    let (party, token) = Party::create(&auth.0.sub, name, &folder.id, i64::from(hours) * 3600)
+        .await
+        .map_err(server_error)?;
+    info!("{} started party '{}'", auth.0.username, party.name);
+    Ok(PartyLink { party, token })
+}
+
+/// The caller's parties that are still on.
+#[get("/api/party/list", auth: AuthSession)]
+pub async fn get_parties() -> Result<Vec<Party>, ServerFnError> {
+    Party::get_open_by_host(&auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// Replace a party's link, for when the old one went too far.
+#[post("/api/party/link", auth: MemberSession)]
+pub async fn renew_party_link(party_id: String) -> Result<PartyLink, ServerFnError> {
+    let party = hosted_party(&auth.0.sub, &party_id).await?;
+    let token = Party::rotate_token(&party.id, &auth.0.sub)
+        .await
+        .map_err(server_error)?;
+    Ok(PartyLink { party, token })
+}
+
+/// End a party now. Downloads already started carry on.
+#[post("/api/party/end", auth: MemberSession)]
+pub async fn end_party(party_id: String) -> Result<(), ServerFnError> {
+    Party::delete(&party_id, &auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+#[post("/api/party/requests", auth: MemberSession)]
+pub async fn get_party_requests(party_id: String) -> Result<Vec<PartyRequest>, ServerFnError> {
+    let party = hosted_party(&auth.0.sub, &party_id).await?;
+    PartyRequest::get_by_party(&party.id)
+        .await
+        .map_err(server_error)
+}
+
+/// Approve or reject a pending request. An approved track is downloaded
+/// into the party folder, as the host.
+#[post("/api/party/decide", auth: MemberSession)]
+pub async fn decide_party_request(
+    party_id: String,
+    request_id: String,
+    approve: bool,
+) -> Result<PartyRequest, ServerFnError> {
+    let party = hosted_party(&auth.0.sub, &party_id).await?;
+    // Checked before deciding, so a request isn't approved with nowhere to go
+    let (status, folder) = if approve {
+        let folder = validate::folder(&auth.0.sub, &party.folder_id).await?;
+        (PartyRequestStatus::Approved, Some(folder))
+    } else {
+        (PartyRequestStatus::Rejected, None)
+    };
+    let mut request = PartyRequest::decide(&request_id, &party.id, status)
+        .await
+        .map_err(server_error)?
+        .ok_or_else(|| validation_error("This request was already handled"))?;
+    let Some(folder) = folder else {
+        return Ok(request);
+    };
+
+    let track = Track {
+        id: String::new(),
+        title: request.title.clone(),
+        artist: request.artist.clone(),
+        album_id: None,
+        album_title: None,
+        release_date: None,
+        duration: None,
+        mbid: None,
+        release_mbid: None,
+        details: Default::default(),
+    };
+    let mut query = DownloadQuery::new(vec![track]);
+    query.match_mode = Some(effective_match_mode(&auth.0.sub, None).await);
+    let req = AutoDownloadRequest {
+        query,
+        folder_id: folder.id,
+        folder_path: folder.path,
+        simulate: false,
+        replacing_peer: None,
+    };
+    if let AutoDownloadResult::Error(e) = run_auto_download(auth.0.username, req).await {
+        PartyRequest::set_failed(&request.id, &e)
+            .await
+            .map_err(server_error)?;
+        request.status = PartyRequestStatus::Failed.as_str().to_string();
+        request.error = Some(e);
+    }
+    Ok(request)
+}
+
+/// A party and its queue, for a guest with the link.
+#[post("/api/party/guest/view")]
+pub async fn get_guest_party(token: String) -> Result<PartyGuestView, ServerFnError> {
+    let party = open_party(&token).await?;
+    let host = User::get_by_id(&party.host_id)
+        .await
+        .map_err(server_error)?
+        .username;
+    let requests = PartyRequest::get_by_party(&party.id)
+        .await
+        .map_err(server_error)?;
+    Ok(PartyGuestView {
+        name: party.name,
+        host,
+        expires_at: party.expires_at,
+        requests,
+    })
+}
+
+/// Ask the host of a party for a track.
+#[post("/api/party/guest/request")]
+pub async fn request_party_track(
+    token: String,
+    guest_name: String,
+    artist: String,
+    title: String,
+) -> Result<PartyRequest, ServerFnError> {
+    let party = open_party(&token).await?;
+    let guest_name = guest_field(&guest_name, "name")?;
+    let artist = guest_field(&artist, "artist")?;
+    let title = guest_field(&title, "title")?;
+
+    let pending = PartyRequest::count_pending(&party.id)
+        .await
+        .map_err(server_error)?;
+    if pending >= MAX_PENDING_REQUESTS {
+        return Err(validation_error(
+            "The host has a lot of requests to go through, try again in a bit",
+        ));
+    }
+    PartyRequest::create(&party.id, &guest_name, &artist, &title)
+        .await
+        .map_err(server_error)
+}
@@ -0,0 +1,151 @@
+//! Password resets, so a forgotten password doesn't mean editing the
+//! database. Any user can hand out a reset link from user management, and
+//! when email is set up users can have one sent to their address.
+
+use dioxus::prelude::*;
+
+#[cfg(feature = "server")]
+use super::{app_link, server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+#[cfg(feature = "server")]
+use crate::mailer;
+#[cfg(feature = "server")]
+use crate::models::password_reset::PasswordReset;
+#[cfg(feature = "server")]
+use crate::models::user::User;
+#[cfg(feature = "server")]
+use crate::rate_limit::RateLimiter;
+#[cfg(feature = "server")]
+use crate::{ClientIp, MemberSession};
+#[cfg(feature = "server")]
+use std::{sync::LazyLock, time::Duration};
+
+/// Reset emails one address may ask for per hour, whatever the accounts.
+#[cfg(feature = "server")]
+static REQUESTS_PER_IP: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(10, Duration::from_secs(3600)));
+
+/// Reset emails sent to one account per hour.
+#[cfg(feature = "server")]
+static REQUESTS_PER_ACCOUNT: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(3, Duration::from_secs(3600)));
+
+/// Whether reset links can be sent by email: mail is configured and the
+/// address of the instance is known to put in the link. Anyone can ask for
+/// an email, so the Host of the request isn't trusted for it.
+#[cfg(feature = "server")]
+fn email_resets_enabled() -> bool {
+    mailer::is_configured() && CONFIG.app_url(None).is_some()
+}
+
+/// A reset link for a user, to pass on to them.
+#[post("/api/users/reset-link", _: MemberSession, headers: axum::http::HeaderMap)]
+pub async fn create_password_reset(user_id: String) -> Result<String, ServerFnError> {
+    let user = User::get_by_id(&user_id).await.map_err(server_error)?;
+    let token = PasswordReset::create(&user.id)
+        .await
+        .map_err(server_error)?;
+    app_link(&headers, &format!("/reset-password?token={}", token))
+}
+
+/// Set the address a user's reset links are sent to.
+#[post("/api/users/email", _: MemberSession)]
+pub async fn update_user_email(user_id: String, email: String) -> Result<(), ServerFnError> {
+    let email = email.trim();
+    if !email.is_empty() && !email.contains('@') {
+        return Err(validation_error("Enter a valid email address"));
+    }
+    User::update_email(&user_id, Some(email).filter(|e| !e.is_empty()))
+        .await
+        .map_err(server_error)
+}
+
+#[get("/api/password-reset/email")]
+pub async fn password_reset_by_email() -> Result<bool, ServerFnError> {
+    Ok(email_resets_enabled())
+}
+
+/// Email a reset link to the user, if they have an address. Answers the
+/// same right away either way, sending in the background, so the form
+/// doesn't tell which usernames exist.
+#[post("/api/password-reset/request", client: ClientIp)]
+pub async fn request_password_reset(username: String) -> Result<(), ServerFnError> {
+    if !email_resets_enabled() {
+        return Err(validation_error(
+            "Password resets by email are not set up. Ask whoever runs this instance for a reset link.",
+        ));
+    }
+    let ip = client.0.map(|ip| ip.to_string()).unwrap_or_default();
+    if !REQUESTS_PER_IP.check(&ip) {
+        return Err(validation_error(
+            "Too many reset requests. Try again in an hour.",
+        ));
+    }
+    tokio::spawn(send_reset_email(username.trim().to_string()));
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn send_reset_email(username: String) {
+    let user = match User::get_by_username(&username).await {
+        Ok(Some(user)) => user,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::error!("Could not look up {} for a password reset: {}", username, e);
+            return;
+        }
+    };
+    let Some(email) = user.email.as_deref() else {
+        return;
+    };
+    if !REQUESTS_PER_ACCOUNT.check(&user.id) {
+        tracing::warn!(
+            "Not sending another reset email to {}: too many requests",
+            user.username
+        );
+        return;
+    }
+
+    let token = match PasswordReset::create(&user.id).await {
+        Ok(token) => token,
+        Err(e) => {
+            tracing::error!("Could not create a reset of {}: {}", user.username, e);
+            return;
+        }
+    };
+    let link = format!(
+        "{}/reset-password?token={}",
+        CONFIG.app_url(None).unwrap_or_default(),
+        token
+    );
+    let body = format!(
+        "Someone asked to reset the password of the Soulbeet account \"{}\".\n\n\
+         Choose a new password within 24 hours here:\n{}\n\n\
+         If it wasn't you, ignore this email. Your password stays as it is.\n",
+        user.username, link
+    );
+    if let Err(e) = mailer::send(email, "Reset your Soulbeet password", body).await {
+        tracing::error!("Could not send the reset email of {}: {}", user.username, e);
+    }
+}
+
+/// Whether `token` is a reset that can still be used.
+#[post("/api/password-reset/check")]
+pub async fn check_password_reset(token: String) -> Result<bool, ServerFnError> {
+    PasswordReset::find_pending(&token)
+        .await
+        .map(|r| r.is_some())
+        .map_err(server_error)
+}
+
+#[post("/api/password-reset/complete")]
+pub async fn reset_password(token: String, password: String) -> Result<(), ServerFnError> {
+    if password.is_empty() {
+        return Err(validation_error("Choose a new password"));
+    }
+    super::check_password(&password).await?;
+    PasswordReset::complete(&token, &password)
+        .await
+        .map_err(validation_error)
+}
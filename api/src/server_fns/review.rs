@@ -0,0 +1,85 @@
+//! Tag review step between download and import. Downloads started with
+//! `review_tags` are held until the user corrects (or accepts) their tags.
+
+use dioxus::prelude::*;
+use shared::download::{ImportEdits, PendingImport};
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::globals::HELD_IMPORTS;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+
+#[get("/api/imports/pending", auth: AuthSession)]
+pub async fn get_pending_imports() -> Result<Vec<PendingImport>, ServerFnError> {
+    let username = auth.0.username;
+    let mut pending: Vec<PendingImport> = HELD_IMPORTS
+        .read()
+        .await
+        .values()
+        .filter(|held| held.username == username)
+        .map(|held| held.pending.clone())
+        .collect();
+    pending.sort_by_key(|p| p.created_at);
+    Ok(pending)
+}
+
+/// Write `edits` to the held files and run the import. Empty edits import
+/// the files as they are.
+#[post("/api/imports/review", auth: MemberSession)]
+pub async fn apply_import_review(
+    review_id: String,
+    edits: ImportEdits,
+) -> Result<(), ServerFnError> {
+    let held = {
+        let mut held_imports = HELD_IMPORTS.write().await;
+        match held_imports.get(&review_id) {
+            Some(held) if held.username == auth.0.username => held_imports.remove(&review_id),
+            _ => None,
+        }
+    }
+    .ok_or_else(|| server_error("Import not found or already reviewed"))?;
+
+    if !edits.is_empty() {
+        let cover = match edits.cover_url.as_deref().filter(|u| !u.trim().is_empty()) {
+            Some(url) => match soulbeet::tagging::fetch_cover(url.trim()).await {
+                Ok(picture) => Some(picture),
+                Err(e) => {
+                    // Put it back so the user can fix the URL and retry
+                    HELD_IMPORTS.write().await.insert(review_id, held);
+                    return Err(server_error(e));
+                }
+            },
+            None => None,
+        };
+
+        let paths: Vec<String> = held.pending.files.iter().map(|f| f.path.clone()).collect();
+        let to_write = edits.clone();
+        let written = tokio::task::spawn_blocking(move || {
+            paths.iter().try_for_each(|path| {
+                soulbeet::tagging::write_tags(std::path::Path::new(path), &to_write, cover.as_ref())
+            })
+        })
+        .await
+        .map_err(server_error)?;
+        if let Err(e) = written {
+            HELD_IMPORTS.write().await.insert(review_id, held);
+            return Err(server_error(e));
+        }
+    }
+
+    tokio::spawn(async move {
+        super::download::import::import_group(
+            held.entries,
+            held.source_path,
+            held.target_path,
+            held.tx,
+            held.as_album,
+            &held.overrides,
+        )
+        .await;
+    });
+
+    Ok(())
+}
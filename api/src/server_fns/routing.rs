@@ -0,0 +1,244 @@
+//! Routing rules: a release's genres pick the folder it is downloaded into
+//! and the quality it must have. The rules are stored with the user's
+//! settings and evaluated when a search or download starts. Without a rule,
+//! a folder is suggested from where the artist went before or from folders
+//! named after the release's genre.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::download::DownloadQuery;
+use shared::routing::RoutingTrace;
+
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+#[cfg(feature = "server")]
+use shared::quality::SearchFilters;
+#[cfg(feature = "server")]
+use shared::routing::RoutingRule;
+
+#[cfg(feature = "server")]
+use crate::models::{
+    download_history::DownloadHistoryRow, folder::Folder, user::User, user_settings::UserSettings,
+};
+#[cfg(feature = "server")]
+use crate::server_fns::{server_error, validate};
+#[cfg(feature = "server")]
+use crate::services::metadata_provider;
+#[cfg(feature = "server")]
+use crate::MemberSession;
+
+/// What the rules make of a release, for the download dialog.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingPreview {
+    pub trace: RoutingTrace,
+    /// Folder the matching rule sends the release to
+    pub folder_id: Option<String>,
+}
+
+/// Folder the download dialog preselects, and why.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderSuggestion {
+    pub folder_id: String,
+    pub reason: String,
+}
+
+/// Earlier imports of an artist looked at for a suggestion.
+#[cfg(feature = "server")]
+const ARTIST_HISTORY: u32 = 50;
+
+/// Folder names shorter than this are not matched against genres.
+#[cfg(feature = "server")]
+const MIN_GENRE_FOLDER_NAME: usize = 3;
+
+/// The rule applying to a release, with the folder it sends it to.
+#[cfg(feature = "server")]
+#[derive(Default)]
+pub(crate) struct Route {
+    pub rule: Option<RoutingRule>,
+    pub folder: Option<Folder>,
+    pub trace: RoutingTrace,
+}
+
+#[cfg(feature = "server")]
+impl Route {
+    /// `filters` with the quality requirements of the rule, if any.
+    pub fn filters(&self, filters: SearchFilters) -> SearchFilters {
+        match &self.rule {
+            Some(rule) => rule.apply(filters),
+            None => filters,
+        }
+    }
+}
+
+/// Genres of the release a query is for: those it came with, or else those
+/// of the release fetched from the metadata provider.
+#[cfg(feature = "server")]
+async fn release_genres(query: &DownloadQuery) -> Vec<String> {
+    let known = query
+        .album
+        .as_ref()
+        .map(|a| &a.details)
+        .into_iter()
+        .chain(query.tracks.iter().map(|t| &t.details))
+        .find(|d| !d.genres.is_empty());
+    if let Some(details) = known {
+        return details.genres.clone();
+    }
+
+    let release_id = query
+        .album
+        .as_ref()
+        .map(|a| a.id.clone())
+        .or_else(|| query.tracks.iter().find_map(|t| t.album_id.clone()));
+    let Some(release_id) = release_id else {
+        return Vec::new();
+    };
+    let provider = match metadata_provider(None, None).await {
+        Ok(provider) => provider,
+        Err(e) => {
+            warn!("No metadata provider to look up genres: {}", e);
+            return Vec::new();
+        }
+    };
+    match provider.get_album(&release_id).await {
+        Ok(album) => album.album.details.genres,
+        Err(e) => {
+            warn!("Could not look up the genres of {}: {}", release_id, e);
+            Vec::new()
+        }
+    }
+}
+
+/// Evaluate the user's routing rules for the release of `query`. A rule
+/// whose folder the user can no longer see is passed over.
+#[cfg(feature = "server")]
+pub(crate) async fn route(username: &str, query: &DownloadQuery) -> Route {
+    let Ok(Some(user)) = User::get_by_username(username).await else {
+        return Route::default();
+    };
+    let rules = match UserSettings::get(&user.id).await {
+        Ok(settings) => settings.routing_rules(),
+        Err(_) => return Route::default(),
+    };
+    if rules.is_empty() {
+        return Route::default();
+    }
+
+    let mut trace = rules.evaluate(&release_genres(query).await);
+    let Some(rule) = trace.rule(&rules).cloned() else {
+        return Route {
+            trace,
+            ..Default::default()
+        };
+    };
+    match validate::folder(&user.id, &rule.folder_id).await {
+        Ok(folder) => {
+            trace
+                .steps
+                .push(format!("Downloading into {}", folder.path));
+            Route {
+                rule: Some(rule),
+                folder: Some(folder),
+                trace,
+            }
+        }
+        Err(_) => {
+            trace.matched = None;
+            trace.steps.push(
+                "The rule's folder is no longer one of yours, using the chosen folder".to_string(),
+            );
+            Route {
+                trace,
+                ..Default::default()
+            }
+        }
+    }
+}
+
+/// How the user's routing rules apply to the release of `query`.
+#[post("/api/routing/preview", auth: MemberSession)]
+pub async fn preview_routing(query: DownloadQuery) -> Result<RoutingPreview, ServerFnError> {
+    validate::download_query(&query)?;
+    let route = route(&auth.0.username, &query).await;
+    Ok(RoutingPreview {
+        folder_id: route.folder.map(|f| f.id),
+        trace: route.trace,
+    })
+}
+
+/// The folder holding `path`; the innermost one when folders are nested.
+#[cfg(feature = "server")]
+fn folder_of<'a>(folders: &'a [Folder], path: &str) -> Option<&'a Folder> {
+    let path = std::path::Path::new(path);
+    folders
+        .iter()
+        .filter(|f| path.starts_with(&f.path))
+        .max_by_key(|f| f.path.len())
+}
+
+/// The folder most of the artist's earlier imports went to, with how many.
+#[cfg(feature = "server")]
+async fn artist_folder(user_id: &str, artist: &str, folders: &[Folder]) -> Option<(String, usize)> {
+    let paths = DownloadHistoryRow::imported_paths_of_artist(user_id, artist, ARTIST_HISTORY)
+        .await
+        .map_err(|e| warn!("Could not read the history of {}: {}", artist, e))
+        .ok()?;
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for folder in paths.iter().filter_map(|p| folder_of(folders, p)) {
+        match counts.iter_mut().find(|(id, _)| *id == folder.id) {
+            Some((_, n)) => *n += 1,
+            None => counts.push((folder.id.clone(), 1)),
+        }
+    }
+    // Ties go to the folder used most recently, listed first
+    counts.into_iter().rev().max_by_key(|(_, n)| *n)
+}
+
+/// A folder of the release, from the user's past choices for the artist,
+/// or else a folder named after one of the release's genres. Nothing when
+/// the user has a single folder, which the dialog picks anyway.
+#[post("/api/routing/suggest-folder", auth: MemberSession)]
+pub async fn suggest_folder(
+    query: DownloadQuery,
+) -> Result<Option<FolderSuggestion>, ServerFnError> {
+    validate::download_query(&query)?;
+    let folders = Folder::get_visible_to(&auth.0.sub)
+        .await
+        .map_err(server_error)?;
+    if folders.len() < 2 {
+        return Ok(None);
+    }
+
+    let artist = query
+        .album
+        .as_ref()
+        .map(|a| a.artist.clone())
+        .or_else(|| query.tracks.first().map(|t| t.artist.clone()))
+        .filter(|a| !a.trim().is_empty());
+    if let Some(artist) = artist {
+        if let Some((folder_id, count)) = artist_folder(&auth.0.sub, &artist, &folders).await {
+            let reason = match count {
+                1 => format!("Where you put {} last time", artist),
+                n => format!("Where {} of your downloads of {} went", n, artist),
+            };
+            return Ok(Some(FolderSuggestion { folder_id, reason }));
+        }
+    }
+
+    let genres = release_genres(&query).await;
+    let by_genre = genres.iter().find_map(|genre| {
+        let genre = genre.to_lowercase();
+        folders
+            .iter()
+            .find(|f| {
+                let name = f.name.trim().to_lowercase();
+                name.len() >= MIN_GENRE_FOLDER_NAME
+                    && (genre.contains(&name) || name.contains(&genre))
+            })
+            .map(|f| FolderSuggestion {
+                folder_id: f.id.clone(),
+                reason: format!("Named after the genre {}", genre),
+            })
+    });
+    Ok(by_genre)
+}
@@ -2,7 +2,7 @@ use dioxus::prelude::*;
 use serde::{Deserialize, Serialize};
 use shared::{
     download::{DownloadQuery, SearchResult as DownloadSearchResult},
-    metadata::{AlbumWithTracks, Provider, SearchResults},
+    metadata::{AlbumWithTracks, IdentifierKind, Provider, SearchResults},
 };
 
 #[cfg(feature = "server")]
@@ -10,7 +10,18 @@ use crate::models::user_settings::UserSettings;
 #[cfg(feature = "server")]
 use crate::services::{download_backend, metadata_provider};
 #[cfg(feature = "server")]
-use crate::{server_fns::server_error, AuthSession};
+use crate::activity::record_activity;
+#[cfg(feature = "server")]
+use crate::globals::SEARCH_OWNERS;
+#[cfg(feature = "server")]
+use shared::activity::ActivityKind;
+#[cfg(feature = "server")]
+use shared::download::{MatchMode, SearchOptions, DEFAULT_SEARCH_TIMEOUT_SECS};
+#[cfg(feature = "server")]
+use crate::{
+    server_fns::{metadata_error, server_error, slskd_error, validate},
+    AuthSession, MemberSession,
+};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SearchQuery {
@@ -18,6 +29,10 @@ pub struct SearchQuery {
     pub query: String,
     #[serde(default)]
     pub provider: Option<String>,
+    /// Treat `query` as a barcode, catalog number or ISRC. Always answered
+    /// by MusicBrainz, whatever the provider.
+    #[serde(default)]
+    pub identifier: Option<IdentifierKind>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -36,20 +51,27 @@ pub struct PollQuery {
 
 #[post("/api/metadata/search/album", auth: AuthSession)]
 pub async fn search_album(input: SearchQuery) -> Result<SearchResults, ServerFnError> {
+    validate::search_query(&input.query, input.artist.as_deref())?;
     let user_settings = UserSettings::get(&auth.0.sub).await.map_err(server_error)?;
-    let provider = metadata_provider(
-        input.provider.as_deref(),
-        user_settings.lastfm_api_key.as_deref(),
-    )
-    .await
-    .map_err(server_error)?;
-
-    let provider_enum: Provider = provider.id().parse().unwrap_or_default();
-    let results = provider
-        .search_albums(input.artist.as_deref(), &input.query, 25)
+    let provider_id = match input.identifier {
+        Some(_) => Some("musicbrainz"),
+        None => input.provider.as_deref(),
+    };
+    let provider = metadata_provider(provider_id, user_settings.lastfm_api_key.as_deref())
         .await
         .map_err(server_error)?;
 
+    let provider_enum: Provider = provider.id().parse().unwrap_or_default();
+    let results = match input.identifier {
+        Some(kind) => provider.search_by_identifier(kind, &input.query, 25).await,
+        None => {
+            provider
+                .search_albums(input.artist.as_deref(), &input.query, 25)
+                .await
+        }
+    }
+    .map_err(|e| metadata_error(provider.id(), e))?;
+
     Ok(SearchResults {
         provider: provider_enum,
         results,
@@ -58,20 +80,27 @@ pub async fn search_album(input: SearchQuery) -> Result<SearchResults, ServerFnE
 
 #[post("/api/metadata/search/track", auth: AuthSession)]
 pub async fn search_track(input: SearchQuery) -> Result<SearchResults, ServerFnError> {
+    validate::search_query(&input.query, input.artist.as_deref())?;
     let user_settings = UserSettings::get(&auth.0.sub).await.map_err(server_error)?;
-    let provider = metadata_provider(
-        input.provider.as_deref(),
-        user_settings.lastfm_api_key.as_deref(),
-    )
-    .await
-    .map_err(server_error)?;
-
-    let provider_enum: Provider = provider.id().parse().unwrap_or_default();
-    let results = provider
-        .search_tracks(input.artist.as_deref(), &input.query, 25)
+    let provider_id = match input.identifier {
+        Some(_) => Some("musicbrainz"),
+        None => input.provider.as_deref(),
+    };
+    let provider = metadata_provider(provider_id, user_settings.lastfm_api_key.as_deref())
         .await
         .map_err(server_error)?;
 
+    let provider_enum: Provider = provider.id().parse().unwrap_or_default();
+    let results = match input.identifier {
+        Some(kind) => provider.search_by_identifier(kind, &input.query, 25).await,
+        None => {
+            provider
+                .search_tracks(input.artist.as_deref(), &input.query, 25)
+                .await
+        }
+    }
+    .map_err(|e| metadata_error(provider.id(), e))?;
+
     Ok(SearchResults {
         provider: provider_enum,
         results,
@@ -89,7 +118,28 @@ pub async fn find_album(input: AlbumQuery) -> Result<AlbumWithTracks, ServerFnEr
     .await
     .map_err(server_error)?;
 
-    provider.get_album(&input.id).await.map_err(server_error)
+    provider
+        .get_album(&input.id)
+        .await
+        .map_err(|e| metadata_error(provider.id(), e))
+}
+
+/// Resolve a pasted release link (MusicBrainz, Discogs, Spotify, Bandcamp or
+/// RateYourMusic) to the MusicBrainz release, with its tracklist.
+#[post("/api/metadata/resolve-url", _: AuthSession)]
+pub async fn resolve_release_url(url: String) -> Result<AlbumWithTracks, ServerFnError> {
+    let client = soulbeet::http::build_client(&soulbeet::http::user_agent());
+    let release_id = soulbeet::resolver::resolve_release(&client, &url)
+        .await
+        .map_err(|e| metadata_error("musicbrainz", e))?;
+
+    let provider = metadata_provider(Some("musicbrainz"), None)
+        .await
+        .map_err(server_error)?;
+    provider
+        .get_album(&release_id)
+        .await
+        .map_err(|e| metadata_error(provider.id(), e))
 }
 
 /// Album queries arrive from the UI with an empty track list; source matching
@@ -104,7 +154,12 @@ pub(crate) async fn hydrate_album_tracks(query: &mut DownloadQuery) -> Result<()
         return Ok(());
     };
 
-    let provider = metadata_provider(None, None)
+    // Albums found on Spotify carry Spotify ids, MusicBrainz ones otherwise
+    let provider_id = album
+        .id
+        .starts_with(soulbeet::spotify::ALBUM_ID_PREFIX)
+        .then_some(crate::services::providers::SPOTIFY);
+    let provider = metadata_provider(provider_id, None)
         .await
         .map_err(|e| format!("metadata provider unavailable: {e}"))?;
     let album_with_tracks = provider
@@ -112,32 +167,115 @@ pub(crate) async fn hydrate_album_tracks(query: &mut DownloadQuery) -> Result<()
         .await
         .map_err(|e| format!("could not resolve tracklist for '{}': {e}", album.title))?;
     query.tracks = album_with_tracks.tracks;
+    // Kept for the routing rules, which would otherwise fetch the album again
+    if let Some(album) = query.album.as_mut() {
+        if album.details.genres.is_empty() {
+            album.details.genres = album_with_tracks.album.details.genres;
+        }
+    }
     Ok(())
 }
 
-#[post("/api/download/search/start", _: AuthSession)]
+/// Bounds on a requested search timeout, in seconds. slskd keeps a search
+/// open for the whole timeout, so very long ones tie up its search slots.
+#[cfg(feature = "server")]
+const MIN_SEARCH_TIMEOUT_SECS: u32 = 10;
+#[cfg(feature = "server")]
+const MAX_SEARCH_TIMEOUT_SECS: u32 = 600;
+
+/// The matching mode picked for a search, or else the user's default.
+#[cfg(feature = "server")]
+pub(crate) async fn effective_match_mode(user_id: &str, requested: Option<MatchMode>) -> MatchMode {
+    if let Some(mode) = requested {
+        return mode;
+    }
+    UserSettings::get(user_id)
+        .await
+        .ok()
+        .and_then(|s| s.match_mode)
+        .and_then(|m| MatchMode::from_setting(&m))
+        .unwrap_or_default()
+}
+
+#[post("/api/download/search/start", auth: MemberSession)]
 pub async fn start_download_search(data: DownloadQuery) -> Result<String, ServerFnError> {
+    validate::download_query(&data)?;
     let mut data = data;
     hydrate_album_tracks(&mut data).await.map_err(server_error)?;
 
     let backend = download_backend(data.backend.as_deref())
         .await
-        .map_err(|e| server_error(format!("download backend not available: {}", e)))?;
+        .map_err(|e| slskd_error(format!("download backend not available: {}", e)))?;
 
-    backend
-        .start_search(data.album.as_ref(), &data.tracks)
+    let match_mode = effective_match_mode(&auth.0.sub, data.match_mode).await;
+    let settings = UserSettings::get(&auth.0.sub).await.ok();
+    let profile = settings
+        .as_ref()
+        .map(|s| s.quality_profile())
+        .unwrap_or_default();
+    let timeout_secs = data
+        .search_timeout_secs
+        .or_else(|| settings.as_ref().and_then(|s| s.search_timeout_secs))
+        .unwrap_or(DEFAULT_SEARCH_TIMEOUT_SECS)
+        .clamp(MIN_SEARCH_TIMEOUT_SECS, MAX_SEARCH_TIMEOUT_SECS);
+    let route = super::routing::route(&auth.0.username, &data).await;
+    let options = SearchOptions {
+        min_score: match_mode.min_score(),
+        diversity: profile.diversity,
+        timeout_secs,
+        filters: route.filters(profile.search_filters),
+        strategy: data.search_strategy,
+    };
+    let search_id = backend
+        .start_search_with_options(data.album.as_ref(), &data.tracks, &options)
         .await
-        .map_err(server_error)
+        .map_err(slskd_error)?;
+
+    SEARCH_OWNERS.write().await.insert(
+        search_id.clone(),
+        (auth.0.sub.clone(), std::time::Instant::now()),
+    );
+
+    let query = match (&data.album, data.tracks.as_slice()) {
+        (Some(album), _) => format!("{} - {}", album.artist, album.title),
+        (None, [track]) => format!("{} - {}", track.artist, track.title),
+        (None, tracks) => format!("{} tracks", tracks.len()),
+    };
+    super::search_history::record_search_start(
+        &search_id,
+        &auth.0.sub,
+        &data,
+        &query,
+        timeout_secs,
+        false,
+    )
+    .await;
+    record_activity(
+        &auth.0.username,
+        ActivityKind::SearchStarted {
+            search_id: search_id.clone(),
+            query,
+        },
+    );
+
+    Ok(search_id)
 }
 
-#[post("/api/download/search/poll", _: AuthSession)]
+#[post("/api/download/search/poll", auth: MemberSession)]
 pub async fn poll_download_search(input: PollQuery) -> Result<DownloadSearchResult, ServerFnError> {
     let backend = download_backend(input.backend.as_deref())
         .await
-        .map_err(|e| server_error(format!("download backend not available: {}", e)))?;
+        .map_err(|e| slskd_error(format!("download backend not available: {}", e)))?;
 
-    backend
+    let mut result = backend
         .poll_search(&input.search_id)
         .await
-        .map_err(server_error)
+        .map_err(slskd_error)?;
+    super::download::feedback::rank_by_reputation(&mut result.groups).await;
+    super::search_history::record_search_poll(&result).await;
+
+    super::session::publish_search_results(&result).await;
+    super::download::fallback::remember_results(&auth.0.username, &result).await;
+
+    Ok(result)
 }
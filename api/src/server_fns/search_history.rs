@@ -0,0 +1,67 @@
+//! Soulseek searches users ran, with how many peers answered and how many
+//! results matched, to compare search strategies and run a search again.
+
+use dioxus::prelude::*;
+use shared::download::SearchHistory;
+
+#[cfg(feature = "server")]
+use crate::models::search_history::SearchHistoryRow;
+#[cfg(feature = "server")]
+use crate::server_fns::server_error;
+#[cfg(feature = "server")]
+use crate::AuthSession;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::warn;
+#[cfg(feature = "server")]
+use shared::download::{DownloadQuery, SearchResult};
+
+/// Searches listed in the history view.
+#[cfg(feature = "server")]
+const RECENT_SEARCHES_LIMIT: u32 = 100;
+
+/// Note a search that just started. The search goes on if this fails.
+#[cfg(feature = "server")]
+pub(crate) async fn record_search_start(
+    search_id: &str,
+    user_id: &str,
+    query: &DownloadQuery,
+    description: &str,
+    timeout_secs: u32,
+    automatic: bool,
+) {
+    if let Err(e) = SearchHistoryRow::start(
+        search_id,
+        user_id,
+        query,
+        description,
+        timeout_secs,
+        automatic,
+    )
+    .await
+    {
+        warn!("Could not record search {}: {}", search_id, e);
+    }
+}
+
+/// Note the responses and candidates a poll of a search returned.
+#[cfg(feature = "server")]
+pub(crate) async fn record_search_poll(result: &SearchResult) {
+    if let Err(e) = SearchHistoryRow::update(result).await {
+        warn!("Could not update search {}: {}", result.search_id, e);
+    }
+}
+
+/// The user's latest searches, newest first, with how each strategy fared.
+#[get("/api/download/search/history", auth: AuthSession)]
+pub async fn get_search_history() -> Result<SearchHistory, ServerFnError> {
+    let searches = SearchHistoryRow::recent(&auth.0.sub, RECENT_SEARCHES_LIMIT)
+        .await
+        .map_err(server_error)?;
+    let strategies = SearchHistoryRow::strategy_stats(&auth.0.sub)
+        .await
+        .map_err(server_error)?;
+    Ok(SearchHistory {
+        searches,
+        strategies,
+    })
+}
@@ -0,0 +1,69 @@
+//! Password policy and account lockout, stored as one JSON value in the
+//! app config and edited through the settings path.
+
+use dioxus::prelude::*;
+use shared::security::SecurityPolicy;
+
+#[cfg(feature = "server")]
+use super::{server_error, validation_error};
+#[cfg(feature = "server")]
+use crate::models::app_config::{keys, AppConfig};
+#[cfg(feature = "server")]
+use crate::models::user::User;
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use shared::security::PasswordPolicy;
+
+/// Longest lockout the settings accept, in minutes (one day).
+#[cfg(feature = "server")]
+const MAX_LOCKOUT_MINUTES: u32 = 24 * 60;
+
+/// The saved policy. A value that doesn't parse counts as the defaults.
+#[cfg(feature = "server")]
+pub async fn security_policy() -> Result<SecurityPolicy, String> {
+    Ok(AppConfig::get(keys::SECURITY_POLICY)
+        .await?
+        .and_then(|v| serde_json::from_str(&v).ok())
+        .unwrap_or_default())
+}
+
+/// Refuse `password` unless it meets the password policy.
+#[cfg(feature = "server")]
+pub(crate) async fn check_password(password: &str) -> Result<(), ServerFnError> {
+    let policy = security_policy().await.map_err(server_error)?;
+    policy.password.check(password).map_err(validation_error)
+}
+
+#[get("/api/settings/security", _: AuthSession)]
+pub async fn get_security_policy() -> Result<SecurityPolicy, ServerFnError> {
+    security_policy().await.map_err(server_error)
+}
+
+#[post("/api/settings/security", _: MemberSession)]
+pub async fn set_security_policy(policy: SecurityPolicy) -> Result<SecurityPolicy, ServerFnError> {
+    if !(1..=PasswordPolicy::MAX_MIN_LENGTH).contains(&policy.password.min_length) {
+        return Err(validation_error(format!(
+            "The minimum password length must be between 1 and {}",
+            PasswordPolicy::MAX_MIN_LENGTH
+        )));
+    }
+    if policy.lockout.is_enabled()
+        && !(1..=MAX_LOCKOUT_MINUTES).contains(&policy.lockout.lockout_minutes)
+    {
+        return Err(validation_error(
+            "Accounts can be locked for 1 minute to 1 day",
+        ));
+    }
+    let value = serde_json::to_string(&policy).map_err(server_error)?;
+    AppConfig::set(keys::SECURITY_POLICY, &value)
+        .await
+        .map_err(server_error)?;
+    Ok(policy)
+}
+
+/// Let a locked user sign in again right away.
+#[post("/api/users/unlock", _: MemberSession)]
+pub async fn unlock_user(user_id: String) -> Result<(), ServerFnError> {
+    User::unlock(&user_id).await.map_err(server_error)
+}
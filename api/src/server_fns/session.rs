@@ -0,0 +1,200 @@
+use dioxus::prelude::*;
+use shared::download::SharedSearchSnapshot;
+
+#[cfg(feature = "server")]
+use super::server_error;
+#[cfg(feature = "server")]
+use crate::globals::{broadcast_to_session, SearchSession, SEARCH_OWNERS, SEARCH_SESSIONS};
+#[cfg(feature = "server")]
+use crate::MemberSession;
+#[cfg(feature = "server")]
+use shared::download::{SearchResult as DownloadSearchResult, SearchState, SharedSearchEvent};
+#[cfg(feature = "server")]
+use std::time::Instant;
+
+#[cfg(feature = "server")]
+fn snapshot(session_id: &str, session: &SearchSession) -> SharedSearchSnapshot {
+    SharedSearchSnapshot {
+        session_id: session_id.to_string(),
+        owner: session.owner_name.clone(),
+        groups: session.groups.clone(),
+        state: session.state.clone(),
+        selected: session.selected.iter().cloned().collect(),
+        participants: session.participants.iter().cloned().collect(),
+    }
+}
+
+/// Merge freshly polled results into any session sharing this search and
+/// forward them to its participants.
+#[cfg(feature = "server")]
+pub(crate) async fn publish_search_results(result: &DownloadSearchResult) {
+    let mut sessions = SEARCH_SESSIONS.write().await;
+    let Some((session_id, session)) = sessions
+        .iter_mut()
+        .find(|(_, s)| s.search_id == result.search_id)
+    else {
+        return;
+    };
+
+    for group in &result.groups {
+        match session
+            .groups
+            .iter()
+            .position(|g| g.source == group.source && g.group_id == group.group_id)
+        {
+            Some(pos) => session.groups[pos] = group.clone(),
+            None => session.groups.push(group.clone()),
+        }
+    }
+    session.groups.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    session.state = result.state.clone();
+    session.last_activity = Instant::now();
+
+    let event = SharedSearchEvent::Results {
+        session_id: session_id.clone(),
+        groups: session.groups.clone(),
+        state: session.state.clone(),
+    };
+    let participants: Vec<String> = session.participants.iter().cloned().collect();
+    drop(sessions);
+
+    broadcast_to_session(participants, event).await;
+}
+
+/// Open a shared session for one of the caller's running searches. The
+/// results gathered so far are seeded by the client. Returns the session id.
+#[post("/api/download/session/share", auth: MemberSession)]
+pub async fn share_search_session(
+    search_id: String,
+    groups: Vec<shared::download::DownloadableGroup>,
+) -> Result<String, ServerFnError> {
+    let claims = auth.0;
+
+    let owner = SEARCH_OWNERS
+        .read()
+        .await
+        .get(&search_id)
+        .map(|(owner, _)| owner.clone());
+    if owner.as_deref() != Some(claims.sub.as_str()) {
+        return Err(server_error("Only the user who started a search can share it"));
+    }
+
+    let mut sessions = SEARCH_SESSIONS.write().await;
+    if let Some((id, _)) = sessions.iter().find(|(_, s)| s.search_id == search_id) {
+        return Ok(id.clone());
+    }
+
+    let session_id = uuid::Uuid::new_v4().to_string();
+    sessions.insert(
+        session_id.clone(),
+        SearchSession {
+            owner_id: claims.sub,
+            owner_name: claims.username.clone(),
+            search_id,
+            groups,
+            state: SearchState::InProgress,
+            selected: Default::default(),
+            participants: [claims.username].into_iter().collect(),
+            last_activity: Instant::now(),
+        },
+    );
+    Ok(session_id)
+}
+
+/// Join a shared session and get its current state.
+#[post("/api/download/session/join", auth: MemberSession)]
+pub async fn join_search_session(session_id: String) -> Result<SharedSearchSnapshot, ServerFnError> {
+    let mut sessions = SEARCH_SESSIONS.write().await;
+    let session = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| server_error("This shared search has ended"))?;
+
+    let newly_joined = session.participants.insert(auth.0.username);
+    session.last_activity = Instant::now();
+    let snap = snapshot(&session_id, session);
+    drop(sessions);
+
+    if newly_joined {
+        broadcast_to_session(
+            snap.participants.clone(),
+            SharedSearchEvent::Participants {
+                session_id,
+                usernames: snap.participants.clone(),
+            },
+        )
+        .await;
+    }
+    Ok(snap)
+}
+
+/// Replace the session's picked tracks and notify everyone else.
+#[post("/api/download/session/selection", auth: MemberSession)]
+pub async fn update_session_selection(
+    session_id: String,
+    selected: Vec<String>,
+) -> Result<(), ServerFnError> {
+    let username = auth.0.username;
+    let mut sessions = SEARCH_SESSIONS.write().await;
+    let session = sessions
+        .get_mut(&session_id)
+        .ok_or_else(|| server_error("This shared search has ended"))?;
+    if !session.participants.contains(&username) {
+        return Err(server_error("You are not part of this shared search"));
+    }
+
+    session.selected = selected.iter().cloned().collect();
+    session.last_activity = Instant::now();
+    let others: Vec<String> = session
+        .participants
+        .iter()
+        .filter(|p| **p != username)
+        .cloned()
+        .collect();
+    drop(sessions);
+
+    broadcast_to_session(
+        others,
+        SharedSearchEvent::Selection {
+            session_id,
+            selected,
+            by: username,
+        },
+    )
+    .await;
+    Ok(())
+}
+
+/// Leave a session. When the owner leaves, the session is closed for everyone.
+#[post("/api/download/session/leave", auth: MemberSession)]
+pub async fn leave_search_session(session_id: String) -> Result<(), ServerFnError> {
+    let claims = auth.0;
+    let mut sessions = SEARCH_SESSIONS.write().await;
+    let Some(session) = sessions.get_mut(&session_id) else {
+        return Ok(());
+    };
+
+    if session.owner_id == claims.sub {
+        let participants: Vec<String> = session.participants.iter().cloned().collect();
+        sessions.remove(&session_id);
+        drop(sessions);
+        broadcast_to_session(participants, SharedSearchEvent::Closed { session_id }).await;
+        return Ok(());
+    }
+
+    session.participants.remove(&claims.username);
+    let usernames: Vec<String> = session.participants.iter().cloned().collect();
+    drop(sessions);
+    broadcast_to_session(
+        usernames.clone(),
+        SharedSearchEvent::Participants {
+            session_id,
+            usernames,
+        },
+    )
+    .await;
+    Ok(())
+}
@@ -1,11 +1,12 @@
 use dioxus::prelude::*;
+use shared::system::MusicBrainzConfig;
 
 pub use crate::models::user_settings::{UpdateUserSettings, UserSettings};
 
 #[cfg(feature = "server")]
 use crate::models::app_config::AppConfig;
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::{AuthSession, MemberSession};
 
 #[cfg(feature = "server")]
 use super::server_error;
@@ -17,7 +18,7 @@ pub async fn get_user_settings() -> Result<UserSettings, ServerFnError> {
 }
 
 /// Update current user's settings
-#[post("/api/settings", auth: AuthSession)]
+#[post("/api/settings", auth: MemberSession)]
 pub async fn update_user_settings(
     update: UpdateUserSettings,
 ) -> Result<UserSettings, ServerFnError> {
@@ -124,6 +125,16 @@ async fn cleanup_stale_discovery_playlists(
     .await;
 }
 
+/// Show what `rules` would rename the given file names to, without
+/// touching any file.
+#[post("/api/settings/rename_preview", _: MemberSession)]
+pub async fn preview_rename_rules(
+    rules: shared::download::RenameRules,
+    file_names: Vec<String>,
+) -> Result<Vec<shared::download::RenamePreview>, ServerFnError> {
+    Ok(soulbeet::renaming::preview(&file_names, &rules))
+}
+
 /// Get list of available metadata providers
 #[get("/api/settings/providers", auth: AuthSession)]
 pub async fn get_metadata_providers() -> Result<Vec<ProviderInfo>, ServerFnError> {
@@ -175,7 +186,7 @@ pub async fn get_app_config() -> Result<AppConfigValues, ServerFnError> {
     })
 }
 
-#[post("/api/config", _: AuthSession)]
+#[post("/api/config", _: MemberSession)]
 pub async fn update_app_config(config: AppConfigValues) -> Result<AppConfigValues, ServerFnError> {
     use crate::models::app_config::keys;
     use crate::services::reload_providers;
@@ -198,3 +209,55 @@ pub async fn update_app_config(config: AppConfigValues) -> Result<AppConfigValue
 
     get_app_config().await
 }
+
+/// Load the MusicBrainz mirror and user agent contact from the app config.
+#[cfg(feature = "server")]
+pub async fn apply_musicbrainz_config() {
+    use crate::models::app_config::keys;
+
+    let mirror_url = AppConfig::get(keys::MUSICBRAINZ_MIRROR_URL)
+        .await
+        .ok()
+        .flatten();
+    let contact = AppConfig::get(keys::MUSICBRAINZ_CONTACT)
+        .await
+        .ok()
+        .flatten();
+    soulbeet::http::configure_musicbrainz(MusicBrainzConfig {
+        mirror_url,
+        contact,
+    });
+}
+
+#[get("/api/config/musicbrainz", _: AuthSession)]
+pub async fn get_musicbrainz_config() -> Result<MusicBrainzConfig, ServerFnError> {
+    Ok(soulbeet::http::musicbrainz_config())
+}
+
+/// Point MusicBrainz lookups at a mirror, or back at musicbrainz.org when
+/// the URL is empty, and set the contact sent in the user agent. Imports
+/// keep the MusicBrainz server set in the beets config.
+#[post("/api/config/musicbrainz", _: MemberSession)]
+pub async fn update_musicbrainz_config(
+    config: MusicBrainzConfig,
+) -> Result<MusicBrainzConfig, ServerFnError> {
+    use crate::models::app_config::keys;
+
+    if let Some(url) = config.mirror_url.as_deref().map(str::trim) {
+        if !url.is_empty() && url.contains(char::is_whitespace) {
+            return Err(super::validation_error("The mirror URL has spaces in it"));
+        }
+    }
+    for (key, value) in [
+        (keys::MUSICBRAINZ_MIRROR_URL, &config.mirror_url),
+        (keys::MUSICBRAINZ_CONTACT, &config.contact),
+    ] {
+        let saved = match value.as_deref().map(str::trim) {
+            Some(v) if !v.is_empty() => AppConfig::set(key, v).await,
+            _ => AppConfig::delete(key).await,
+        };
+        saved.map_err(server_error)?;
+    }
+    apply_musicbrainz_config().await;
+    Ok(soulbeet::http::musicbrainz_config())
+}
@@ -1,8 +1,8 @@
 use dioxus::prelude::*;
-use shared::system::{AvailableBackends, SystemHealth};
+use shared::system::{AvailableBackends, MonitorFailure, PanicReport, SystemHealth};
 
 #[cfg(feature = "server")]
-use shared::system::BackendInfo;
+use shared::system::{BackendInfo, MusicBrainzStatus};
 
 #[cfg(feature = "server")]
 use crate::services::{
@@ -18,42 +18,56 @@ use dioxus::logger::tracing::debug;
 pub async fn get_system_health() -> Result<SystemHealth, ServerFnError> {
     #[cfg(feature = "server")]
     {
-        let downloader_online = match download_backend(None).await {
-            Ok(backend) => backend.health_check().await,
-            Err(_) => false,
-        };
+        Ok(check_system_health(&auth.0.sub).await)
+    }
+    #[cfg(not(feature = "server"))]
+    Ok(SystemHealth::default())
+}
 
-        let beets_ready = match music_importer(None).await {
-            Ok(importer) => importer.health_check().await,
-            Err(_) => false,
-        };
+#[cfg(feature = "server")]
+pub(crate) async fn check_system_health(user_id: &str) -> SystemHealth {
+    let (downloader_online, downloader_breaker) = match download_backend(None).await {
+        Ok(backend) => (backend.health_check().await, backend.breaker_state().await),
+        Err(_) => (false, Default::default()),
+    };
+
+    let beets_ready = match music_importer(None).await {
+        Ok(importer) => importer.health_check().await,
+        Err(_) => false,
+    };
 
-        let navidrome_online = match navidrome_client_for_user(&auth.0.sub).await {
-            Ok(client) => match client.ping().await {
-                Ok(()) => true,
-                Err(e) => {
-                    let err_str = e.to_string();
-                    if err_str.contains("Circuit breaker open") {
-                        debug!(
-                            "Evicted stale Navidrome client for user {} after circuit-breaker-open ping",
-                            auth.0.sub
-                        );
-                        evict_navidrome_client(&auth.0.sub).await;
-                    }
-                    false
+    let navidrome_online = match navidrome_client_for_user(user_id).await {
+        Ok(client) => match client.ping().await {
+            Ok(()) => true,
+            Err(e) => {
+                let err_str = e.to_string();
+                if err_str.contains("Circuit breaker open") {
+                    debug!(
+                        "Evicted stale Navidrome client for user {} after circuit-breaker-open ping",
+                        user_id
+                    );
+                    evict_navidrome_client(user_id).await;
                 }
-            },
-            Err(_) => false,
-        };
+                false
+            }
+        },
+        Err(_) => false,
+    };
 
-        Ok(SystemHealth {
-            downloader_online,
-            beets_ready,
-            navidrome_online,
-        })
+    SystemHealth {
+        downloader_online,
+        downloader_breaker,
+        beets_ready,
+        navidrome_online,
+        folder_alerts: crate::mounts::alerts_for_user(user_id).await,
+        download_pause: super::download::pause::download_pause().await,
+        musicbrainz: MusicBrainzStatus {
+            endpoint: soulbeet::musicbrainz::server(),
+            user_agent: soulbeet::http::user_agent(),
+            mirror: soulbeet::http::musicbrainz_config().mirror_url.is_some(),
+            reachable: soulbeet::musicbrainz::is_reachable().await,
+        },
     }
-    #[cfg(not(feature = "server"))]
-    Ok(SystemHealth::default())
 }
 
 #[get("/api/system/backends", _: AuthSession)]
@@ -87,3 +101,36 @@ pub async fn get_backends() -> Result<AvailableBackends, ServerFnError> {
     #[cfg(not(feature = "server"))]
     Ok(AvailableBackends::default())
 }
+
+/// Address of the instance, for the links users share with others.
+#[get("/api/system/app-url", _: AuthSession, headers: axum::http::HeaderMap)]
+pub async fn get_app_url() -> Result<String, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        super::app_link(&headers, "")
+    }
+    #[cfg(not(feature = "server"))]
+    Ok(String::new())
+}
+
+/// Panics caught since the server started, newest first.
+#[get("/api/settings/crashes", _: AuthSession)]
+pub async fn get_panic_reports() -> Result<Vec<PanicReport>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        Ok(crate::panics::recent())
+    }
+    #[cfg(not(feature = "server"))]
+    Ok(Vec::new())
+}
+
+/// Download monitors that kept dying and were given up on, newest first.
+#[get("/api/settings/crashes/monitors", _: AuthSession)]
+pub async fn get_monitor_failures() -> Result<Vec<MonitorFailure>, ServerFnError> {
+    #[cfg(feature = "server")]
+    {
+        Ok(super::download::monitor::recent_failures())
+    }
+    #[cfg(not(feature = "server"))]
+    Ok(Vec::new())
+}
@@ -0,0 +1,347 @@
+//! Importing audio files uploaded from the browser.
+//!
+//! Each file is streamed as the raw request body, one request per file, so
+//! large FLACs are never held in memory. They are staged under
+//! `DOWNLOAD_PATH/.uploads/<user>/<upload_id>`, then handed to the same
+//! import pipeline used for Soulseek downloads. Uploads left unimported are
+//! removed after a day.
+
+use dioxus::fullstack::FileStream;
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "server")]
+use super::{server_error, validate, validation_error};
+#[cfg(feature = "server")]
+use crate::config::CONFIG;
+#[cfg(feature = "server")]
+use crate::globals::{get_or_create_user_channel, register_user_task, unregister_user_task};
+#[cfg(feature = "server")]
+use crate::MemberSession;
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use futures::StreamExt;
+#[cfg(feature = "server")]
+use shared::download::{DownloadEvent, DownloadProgress, DownloadState};
+#[cfg(feature = "server")]
+use std::path::{Component, Path, PathBuf};
+#[cfg(feature = "server")]
+use std::time::{Duration, SystemTime};
+#[cfg(feature = "server")]
+use tokio::io::AsyncWriteExt;
+
+/// Extensions accepted for upload. Anything else is rejected up front.
+pub const UPLOAD_AUDIO_EXTENSIONS: &[&str] =
+    &["flac", "mp3", "m4a", "ogg", "opus", "wav", "aac", "wma", "aiff"];
+
+/// How long an upload may sit staged without being imported or discarded.
+#[cfg(feature = "server")]
+const STAGING_TTL: Duration = Duration::from_secs(24 * 3600);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportUploadRequest {
+    pub upload_id: String,
+    /// One of the user's folders or a shared one.
+    pub folder_id: String,
+    #[serde(default)]
+    pub as_album: bool,
+}
+
+/// Where all of a user's uploads are staged.
+#[cfg(feature = "server")]
+fn user_staging_dir(user_id: &str) -> PathBuf {
+    CONFIG.download_path().join(".uploads").join(user_id)
+}
+
+#[cfg(feature = "server")]
+fn staging_dir(user_id: &str, upload_id: &str) -> Result<PathBuf, ServerFnError> {
+    let valid = !upload_id.is_empty()
+        && upload_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+    if !valid {
+        return Err(validation_error("Invalid upload id"));
+    }
+    Ok(user_staging_dir(user_id).join(upload_id))
+}
+
+/// Reject absolute paths and `..` so an upload can never escape its staging dir.
+#[cfg(feature = "server")]
+fn sanitize_relative_path(relative: &str) -> Result<PathBuf, ServerFnError> {
+    let path = Path::new(relative);
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(part) => clean.push(part),
+            Component::CurDir => {}
+            _ => return Err(validation_error(format!("Invalid file path: {}", relative))),
+        }
+    }
+    let ext = clean
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !UPLOAD_AUDIO_EXTENSIONS.contains(&ext.as_str()) {
+        return Err(validation_error(format!("Not an audio file: {}", relative)));
+    }
+    Ok(clean)
+}
+
+/// Stage one file of an upload, streamed from the request body. Everything
+/// the user has staged, across all their uploads, may not add up to more
+/// than `MAX_UPLOAD_MB`. Returns the size of the file.
+///
+/// `upload_id` is generated by the client to group the files of one drop;
+/// `relative_path` is the file's path within the dropped folder, e.g.
+/// "Album/01 - Track.flac". Sending a file again replaces it.
+#[post("/api/uploads/file?upload_id&relative_path", auth: MemberSession)]
+pub async fn upload_file(
+    upload_id: String,
+    relative_path: String,
+    file: FileStream,
+) -> Result<u64, ServerFnError> {
+    let dir = staging_dir(&auth.0.sub, &upload_id)?;
+    let file_path = dir.join(sanitize_relative_path(&relative_path)?);
+
+    let budget = staged_budget(
+        &user_staging_dir(&auth.0.sub),
+        &file_path,
+        CONFIG.max_upload_size(),
+    )
+    .await
+    .map_err(|e| server_error(format!("Failed to read staged uploads: {}", e)))?;
+    if file.size().is_some_and(|size| size > budget) {
+        return Err(too_large());
+    }
+
+    if let Some(parent) = file_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| server_error(format!("Failed to create staging directory: {}", e)))?;
+    }
+
+    let written = write_staged(&file_path, file, budget).await;
+    if written.is_err() {
+        // Never leave a partial file behind to be imported
+        let _ = tokio::fs::remove_file(&file_path).await;
+    }
+    written
+}
+
+/// What is left of `limit` once everything staged under `user_dir` is counted,
+/// except the file about to be replaced. Other uploads of the same user count
+/// too, so splitting a drop into many upload ids doesn't raise the cap.
+#[cfg(feature = "server")]
+async fn staged_budget(user_dir: &Path, replacing: &Path, limit: u64) -> std::io::Result<u64> {
+    let mut staged = Vec::new();
+    if user_dir.exists() {
+        collect_files(user_dir, &mut staged).await?;
+    }
+    Ok(limit.saturating_sub(
+        staged
+            .iter()
+            .filter(|(path, _)| path != replacing)
+            .map(|(_, size)| size)
+            .sum(),
+    ))
+}
+
+#[cfg(feature = "server")]
+fn too_large() -> ServerFnError {
+    validation_error(format!(
+        "Upload is larger than the {} MB limit",
+        CONFIG.max_upload_size() / (1024 * 1024)
+    ))
+}
+
+/// Write the streamed file to `path`, refusing it once it exceeds `budget` bytes.
+#[cfg(feature = "server")]
+async fn write_staged(
+    path: &Path,
+    mut file: FileStream,
+    budget: u64,
+) -> Result<u64, ServerFnError> {
+    let mut out = tokio::fs::File::create(path)
+        .await
+        .map_err(|e| server_error(format!("Failed to open staged file: {}", e)))?;
+
+    let mut written = 0u64;
+    while let Some(chunk) = file.next().await {
+        let chunk = chunk.map_err(|e| server_error(format!("Upload interrupted: {}", e)))?;
+        written += chunk.len() as u64;
+        if written > budget {
+            return Err(too_large());
+        }
+        out.write_all(&chunk)
+            .await
+            .map_err(|e| server_error(format!("Failed to write file: {}", e)))?;
+    }
+    out.flush().await.map_err(server_error)?;
+
+    Ok(written)
+}
+
+/// Throw away a staged upload without importing it.
+#[delete("/api/uploads/discard", auth: MemberSession)]
+pub async fn discard_upload(upload_id: String) -> Result<(), ServerFnError> {
+    let dir = staging_dir(&auth.0.sub, &upload_id)?;
+    if dir.exists() {
+        tokio::fs::remove_dir_all(&dir)
+            .await
+            .map_err(|e| server_error(format!("Failed to discard upload: {}", e)))?;
+    }
+    Ok(())
+}
+
+/// Import a staged upload into one of the user's folders or a shared one. Progress is
+/// reported through the downloads channel like any other import.
+#[post("/api/uploads/import", auth: MemberSession)]
+pub async fn import_upload(req: ImportUploadRequest) -> Result<(), ServerFnError> {
+    let claims = auth.0;
+    let dir = staging_dir(&claims.sub, &req.upload_id)?;
+
+    let folder = validate::folder(&claims.sub, &req.folder_id).await?;
+
+    let mut files = Vec::new();
+    if dir.exists() {
+        collect_files(&dir, &mut files)
+            .await
+            .map_err(|e| server_error(format!("Failed to read upload: {}", e)))?;
+    }
+    if files.is_empty() {
+        return Err(validation_error("Upload contains no files"));
+    }
+
+    let entries: Vec<DownloadProgress> = files
+        .iter()
+        .map(|(path, size)| {
+            DownloadProgress::queued(
+                uuid::Uuid::new_v4().to_string(),
+                "upload".to_string(),
+                path.to_string_lossy().to_string(),
+                *size,
+            )
+            .with_backend("upload")
+            .with_state(DownloadState::Completed)
+        })
+        .collect();
+
+    let (tx, _) = get_or_create_user_channel(&claims.username).await;
+    let _ = tx.send(DownloadEvent::Progress(entries.clone()));
+
+    let username = claims.username.clone();
+    let _cancel = register_user_task(&username).await;
+    let target = PathBuf::from(&folder.path);
+    let source = dir.to_string_lossy().to_string();
+    tokio::spawn(async move {
+        super::download::import::import_group(
+            entries,
+            source,
+            target,
+            tx,
+            req.as_album,
+            &Default::default(),
+        )
+        .await;
+        unregister_user_task(&username).await;
+    });
+
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+async fn collect_files(dir: &Path, out: &mut Vec<(PathBuf, u64)>) -> std::io::Result<()> {
+    let mut read_dir = tokio::fs::read_dir(dir).await?;
+    while let Some(entry) = read_dir.next_entry().await? {
+        let meta = entry.metadata().await?;
+        if meta.is_dir() {
+            Box::pin(collect_files(&entry.path(), out)).await?;
+        } else {
+            out.push((entry.path(), meta.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Remove uploads staged more than a day ago that were never imported or
+/// discarded, e.g. because the tab was closed halfway.
+#[cfg(feature = "server")]
+pub async fn expire_stale_uploads() {
+    let root = CONFIG.download_path().join(".uploads");
+    let Ok(mut users) = tokio::fs::read_dir(&root).await else {
+        return;
+    };
+    while let Ok(Some(user)) = users.next_entry().await {
+        let Ok(mut uploads) = tokio::fs::read_dir(user.path()).await else {
+            continue;
+        };
+        while let Ok(Some(upload)) = uploads.next_entry().await {
+            let dir = upload.path();
+            if !last_modified(&dir)
+                .await
+                .and_then(|t| t.elapsed().ok())
+                .is_some_and(|age| age > STAGING_TTL)
+            {
+                continue;
+            }
+            match tokio::fs::remove_dir_all(&dir).await {
+                Ok(()) => info!("Removed stale upload {}", dir.display()),
+                Err(e) => warn!("Failed to remove stale upload {}: {}", dir.display(), e),
+            }
+        }
+    }
+}
+
+/// Latest modification time of `path` or anything under it.
+#[cfg(feature = "server")]
+async fn last_modified(path: &Path) -> Option<SystemTime> {
+    let mut latest = tokio::fs::metadata(path).await.ok()?.modified().ok()?;
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let Ok(meta) = entry.metadata().await else {
+                continue;
+            };
+            if let Ok(modified) = meta.modified() {
+                latest = latest.max(modified);
+            }
+            if meta.is_dir() {
+                pending.push(entry.path());
+            }
+        }
+    }
+    Some(latest)
+}
+
+#[cfg(all(test, feature = "server"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_uploads_count_toward_the_limit() {
+        let user_dir =
+            std::env::temp_dir().join(format!("soulbeet-uploads-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(user_dir.join("first/Album")).unwrap();
+        std::fs::create_dir_all(user_dir.join("second")).unwrap();
+        std::fs::write(user_dir.join("first/Album/01.flac"), [0u8; 300]).unwrap();
+        std::fs::write(user_dir.join("second/02.flac"), [0u8; 200]).unwrap();
+
+        let budget = |replacing: PathBuf| {
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .unwrap()
+                .block_on(staged_budget(&user_dir, &replacing, 1000))
+                .unwrap()
+        };
+        assert_eq!(budget(user_dir.join("third/03.flac")), 500);
+        assert_eq!(budget(user_dir.join("second/02.flac")), 700);
+
+        std::fs::remove_dir_all(&user_dir).unwrap();
+    }
+}
@@ -1,16 +1,46 @@
 #[cfg(feature = "server")]
-use super::server_error;
+use super::{app_link, server_error, validation_error};
 use crate::models;
+use crate::models::invite::Invite;
 #[cfg(feature = "server")]
-use crate::AuthSession;
+use crate::{AuthSession, MemberSession};
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::security::UserRole;
+
+/// Invites made at once, at most.
+#[cfg(feature = "server")]
+const MAX_INVITES: u32 = 20;
+
+/// Longest an invite may stay valid, in hours (30 days).
+#[cfg(feature = "server")]
+const MAX_INVITE_HOURS: u32 = 30 * 24;
+
+/// A freshly created invite. `token` is only ever returned here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreatedInvite {
+    pub invite: Invite,
+    pub token: String,
+    /// Signup link to send to the new user.
+    pub link: String,
+}
 
 #[get("/api/users", _: AuthSession)]
 pub async fn get_users() -> Result<Vec<models::user::User>, ServerFnError> {
     models::user::User::get_all().await.map_err(server_error)
 }
 
-#[post("/api/users/username", auth: AuthSession)]
+/// Add an account from user management. Anyone else signs up with an invite.
+#[post("/api/users/create", _: MemberSession)]
+pub async fn create_user(username: String, password: String) -> Result<(), ServerFnError> {
+    super::check_password(&password).await?;
+    models::user::User::create(&username, &password)
+        .await
+        .map_err(server_error)
+        .map(|_| ())
+}
+
+#[post("/api/users/username", auth: MemberSession)]
 pub async fn update_username(new_username: String) -> Result<(), ServerFnError> {
     if new_username.trim().is_empty() {
         return Err(server_error("Username cannot be empty"));
@@ -20,16 +50,123 @@ pub async fn update_username(new_username: String) -> Result<(), ServerFnError>
         .map_err(server_error)
 }
 
-#[post("/api/users/password", _: AuthSession)]
+#[post("/api/users/password", _: MemberSession)]
 pub async fn update_user_password(user_id: String, password: String) -> Result<(), ServerFnError> {
+    super::check_password(&password).await?;
     models::user::User::update_password(&user_id, &password)
         .await
         .map_err(server_error)
 }
 
-#[delete("/api/users/delete", _: AuthSession)]
+/// Make a user a member or a viewer. Nobody can make themselves a viewer,
+/// which could leave no one able to change it back.
+#[post("/api/users/role", auth: MemberSession)]
+pub async fn set_user_role(user_id: String, role: UserRole) -> Result<(), ServerFnError> {
+    if role.is_viewer() && user_id == auth.0.sub {
+        return Err(validation_error("You can't make yourself a viewer"));
+    }
+    models::user::User::update_role(&user_id, role)
+        .await
+        .map_err(server_error)
+}
+
+#[delete("/api/users/delete", _: MemberSession)]
 pub async fn delete_user(user_id: String) -> Result<(), ServerFnError> {
     models::user::User::delete(&user_id)
         .await
         .map_err(server_error)
 }
+
+#[get("/api/users/invites", _: AuthSession)]
+pub async fn get_invites() -> Result<Vec<Invite>, ServerFnError> {
+    Invite::get_all().await.map_err(server_error)
+}
+
+/// Create `count` signup links valid for `valid_hours`, each giving the new
+/// user the folder at `folder_path` when one is set. The path must be
+/// inside the browse roots.
+#[post("/api/users/invites", auth: MemberSession, headers: axum::http::HeaderMap)]
+pub async fn create_invites(
+    count: u32,
+    valid_hours: u32,
+    folder_name: Option<String>,
+    folder_path: Option<String>,
+) -> Result<Vec<CreatedInvite>, ServerFnError> {
+    if !(1..=MAX_INVITES).contains(&count) {
+        return Err(validation_error(format!(
+            "Create between 1 and {} invites at a time",
+            MAX_INVITES
+        )));
+    }
+    if !(1..=MAX_INVITE_HOURS).contains(&valid_hours) {
+        return Err(validation_error("Invites can last at most 30 days"));
+    }
+    let folder_path = folder_path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    // Created on signup, so held to the folder picker's roots now
+    let folder = match folder_path {
+        Some(path) => {
+            let path = super::validate::browsable_path(&path)
+                .await?
+                .to_string_lossy()
+                .to_string();
+            let name = folder_name
+                .map(|n| n.trim().to_string())
+                .filter(|n| !n.is_empty())
+                .unwrap_or_else(|| "Music".to_string());
+            Some((name, path))
+        }
+        None => None,
+    };
+
+    let mut created = Vec::new();
+    for _ in 0..count {
+        let (invite, token) = Invite::create(
+            &auth.0.sub,
+            i64::from(valid_hours) * 3600,
+            folder.as_ref().map(|(n, p)| (n.as_str(), p.as_str())),
+        )
+        .await
+        .map_err(server_error)?;
+        let link = app_link(&headers, &format!("/register?token={}", token))?;
+        created.push(CreatedInvite {
+            invite,
+            token,
+            link,
+        });
+    }
+    Ok(created)
+}
+
+#[delete("/api/users/invites/delete", _: MemberSession)]
+pub async fn delete_invite(invite_id: String) -> Result<(), ServerFnError> {
+    Invite::delete(&invite_id).await.map_err(server_error)
+}
+
+/// Whether `token` is an invite that can still be used.
+#[post("/api/invites/check")]
+pub async fn check_invite(token: String) -> Result<bool, ServerFnError> {
+    Invite::find_pending(&token)
+        .await
+        .map(|i| i.is_some())
+        .map_err(server_error)
+}
+
+/// Sign up with an invite, as the user it was meant for.
+#[post("/api/invites/redeem")]
+pub async fn redeem_invite(
+    token: String,
+    username: String,
+    password: String,
+) -> Result<(), ServerFnError> {
+    let username = username.trim();
+    if username.is_empty() || password.is_empty() {
+        return Err(validation_error("Username and password are required"));
+    }
+    super::check_password(&password).await?;
+    Invite::redeem(&token, username, &password)
+        .await
+        .map_err(validation_error)
+        .map(|_| ())
+}
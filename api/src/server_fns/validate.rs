@@ -0,0 +1,249 @@
+//! Checks on what clients send to server functions. Each returns a
+//! validation error naming the problem, so bad input is turned away before
+//! it reaches slskd, the metadata providers or the filesystem.
+
+use std::path::{Component, Path, PathBuf};
+
+use dioxus::prelude::ServerFnError;
+use shared::download::{DownloadQuery, DownloadableItem, ImportOverrides};
+
+use crate::models::folder::{resolve_path, Folder};
+
+use super::{server_error, validation_error};
+
+/// Longest search query accepted, in characters
+pub const MAX_QUERY_LEN: usize = 300;
+
+/// Most tracks or files a single request may search for or download
+pub const MAX_TRACKS_PER_REQUEST: usize = 500;
+
+/// A metadata search: a non-empty query of reasonable length.
+pub fn search_query(query: &str, artist: Option<&str>) -> Result<(), ServerFnError> {
+    if query.trim().is_empty() {
+        return Err(validation_error("Enter something to search for"));
+    }
+    if query.chars().count() > MAX_QUERY_LEN
+        || artist.is_some_and(|a| a.chars().count() > MAX_QUERY_LEN)
+    {
+        return Err(validation_error(format!(
+            "Search is too long (at most {} characters)",
+            MAX_QUERY_LEN
+        )));
+    }
+    Ok(())
+}
+
+/// A download search: an album or at least one track, and not too many.
+pub fn download_query(query: &DownloadQuery) -> Result<(), ServerFnError> {
+    if query.album.is_none() && query.tracks.is_empty() {
+        return Err(validation_error("Nothing to search for"));
+    }
+    if query.tracks.len() > MAX_TRACKS_PER_REQUEST {
+        return Err(validation_error(format!(
+            "Too many tracks in one search ({}, at most {})",
+            query.tracks.len(),
+            MAX_TRACKS_PER_REQUEST
+        )));
+    }
+    Ok(())
+}
+
+/// Files to download: at least one, not too many, each naming its source.
+pub fn download_items(items: &[DownloadableItem]) -> Result<(), ServerFnError> {
+    if items.is_empty() {
+        return Err(validation_error("No files selected"));
+    }
+    if items.len() > MAX_TRACKS_PER_REQUEST {
+        return Err(validation_error(format!(
+            "Too many files in one download ({}, at most {})",
+            items.len(),
+            MAX_TRACKS_PER_REQUEST
+        )));
+    }
+    if items
+        .iter()
+        .any(|i| i.id.trim().is_empty() || i.source.trim().is_empty())
+    {
+        return Err(validation_error("A selected file has no source"));
+    }
+    Ok(())
+}
+
+/// The folder with `folder_id`, if the user owns it or it is shared with them.
+pub async fn folder(user_id: &str, folder_id: &str) -> Result<Folder, ServerFnError> {
+    Folder::get_visible_to(user_id)
+        .await
+        .map_err(server_error)?
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| validation_error("Folder not found among your folders"))
+}
+
+/// An absolute path inside a folder the user can see, returned without
+/// `.` components or a trailing slash. Relative paths and `..` are refused
+/// outright rather than resolved; symlinks are followed for the visibility
+/// check only.
+pub async fn folder_path(user_id: &str, path: &str) -> Result<PathBuf, ServerFnError> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err(validation_error("Choose a folder"));
+    }
+    if path.contains('\0') {
+        return Err(validation_error("Folder path contains invalid characters"));
+    }
+    let path = Path::new(path);
+    if !path.is_absolute() {
+        return Err(validation_error("Folder path must be absolute"));
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(validation_error("Folder path must not contain '..'"));
+    }
+    let path: PathBuf = path.components().collect();
+
+    let visible = Folder::is_visible_path(user_id, &path)
+        .await
+        .map_err(server_error)?;
+    if !visible {
+        return Err(validation_error(
+            "Folder must be one of your folders or a shared one",
+        ));
+    }
+    Ok(path)
+}
+
+/// The configured browse roots that exist, resolved.
+pub fn browse_roots() -> Vec<PathBuf> {
+    crate::config::CONFIG
+        .browse_roots()
+        .iter()
+        .filter_map(|r| std::fs::canonicalize(r).ok())
+        .collect()
+}
+
+/// An absolute path inside the browse roots, which may not exist yet. Its
+/// closest existing parent is resolved, so a symlink can't lead outside.
+pub async fn browsable_path(path: &str) -> Result<PathBuf, ServerFnError> {
+    path_within(path, &browse_roots()).await
+}
+
+async fn path_within(path: &str, roots: &[PathBuf]) -> Result<PathBuf, ServerFnError> {
+    let path = Path::new(path.trim());
+    if !path.is_absolute() {
+        return Err(validation_error("Folder path must be absolute"));
+    }
+    if path.components().any(|c| c == Component::ParentDir) {
+        return Err(validation_error("Folder path must not contain '..'"));
+    }
+    let path: PathBuf = path.components().collect();
+
+    let resolved = resolve_path(&path)
+        .await
+        .ok_or_else(|| validation_error("Folder path is outside the allowed browse roots"))?;
+    if !roots.iter().any(|root| resolved.starts_with(root)) {
+        return Err(validation_error(format!(
+            "{} is outside the allowed browse roots",
+            path.display()
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Import overrides within what users may change: known importers,
+/// allowlisted flags, and destinations and extra libraries inside folders
+/// they can see.
+pub async fn import_overrides(
+    user_id: &str,
+    overrides: &ImportOverrides,
+) -> Result<(), ServerFnError> {
+    let importers = crate::services::available_importers();
+    let importer_choices = overrides.importer.iter().chain(
+        overrides
+            .extra_targets
+            .iter()
+            .filter_map(|t| t.importer.as_ref()),
+    );
+    for importer in importer_choices {
+        if !importers.iter().any(|(id, _)| id == importer) {
+            return Err(validation_error(format!("Unknown importer: {}", importer)));
+        }
+    }
+
+    for flag in &overrides.extra_flags {
+        if !shared::download::IMPORT_FLAG_ALLOWLIST
+            .iter()
+            .any(|(allowed, _)| allowed == flag)
+        {
+            return Err(validation_error(format!(
+                "Import flag not allowed: {}",
+                flag
+            )));
+        }
+    }
+
+    if let Some(target) = &overrides.target_path {
+        folder_path(user_id, target).await?;
+    }
+    if let Some(merge) = &overrides.complete_album {
+        folder_path(user_id, &merge.path).await?;
+    }
+    for (i, target) in overrides.extra_targets.iter().enumerate() {
+        let folder = folder(user_id, &target.folder_id).await?;
+        if overrides.extra_targets[..i]
+            .iter()
+            .any(|t| t.folder_id == target.folder_id)
+        {
+            return Err(validation_error(format!(
+                "{} is picked twice as an extra library",
+                folder.name
+            )));
+        }
+    }
+    // Handed to the importer on its command line
+    if let Some(release) = &overrides.release {
+        let is_mbid = release.mbid.len() == 36
+            && release
+                .mbid
+                .chars()
+                .all(|c| c.is_ascii_hexdigit() || c == '-');
+        if !is_mbid {
+            return Err(validation_error(format!(
+                "Not a MusicBrainz release id: {}",
+                release.mbid
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+            .block_on(future)
+    }
+
+    #[test]
+    fn paths_outside_the_browse_roots_are_refused() {
+        let base = std::env::temp_dir().join(format!("soulbeet-roots-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(base.join("music")).unwrap();
+        let roots = vec![std::fs::canonicalize(base.join("music")).unwrap()];
+        let inside = base.join("music/New Album");
+
+        assert!(block_on(path_within(&inside.to_string_lossy(), &roots)).is_ok());
+        assert!(block_on(path_within("/etc/whatever", &roots)).is_err());
+        assert!(block_on(path_within(&base.to_string_lossy(), &roots)).is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink("/etc", base.join("music/etc")).unwrap();
+            let through_link = base.join("music/etc/whatever");
+            assert!(block_on(path_within(&through_link.to_string_lossy(), &roots)).is_err());
+        }
+
+        std::fs::remove_dir_all(&base).unwrap();
+    }
+}
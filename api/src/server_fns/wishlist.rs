@@ -0,0 +1,209 @@
+//! Wishlist of albums to get later, filled by hand, in bulk from "best
+//! of" charts, or from another instance's export.
+
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+use shared::export::WishlistExport;
+use shared::wishlist::{ChartEntry, ChartMatch, NewWishlistItem};
+
+use crate::models::wishlist::WishlistItem;
+
+#[cfg(feature = "server")]
+use super::label::{enqueue_releases, queued_releases};
+#[cfg(feature = "server")]
+use super::{server_error, validate, validation_error};
+#[cfg(feature = "server")]
+use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "server")]
+use crate::{AuthSession, MemberSession};
+#[cfg(feature = "server")]
+use shared::export::{new_releases, ExportedRelease, WISHLIST_EXPORT_VERSION};
+
+/// Entries resolved per request; the UI sends a long chart in batches so it
+/// can show progress.
+pub const CHART_RESOLVE_BATCH: usize = 10;
+
+/// Most entries read from one wishlist file.
+#[cfg(feature = "server")]
+const MAX_IMPORT_ENTRIES: usize = 5000;
+
+/// What importing a wishlist file did.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WishlistImport {
+    /// Added to the wishlist
+    pub added: usize,
+    /// Queued for download
+    pub queued: usize,
+    /// Already wanted here, or without an artist or title
+    pub skipped: usize,
+}
+
+#[get("/api/wishlist", auth: AuthSession)]
+pub async fn get_wishlist() -> Result<Vec<WishlistItem>, ServerFnError> {
+    WishlistItem::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// Add albums to the wishlist. Releases already on it are skipped; returns
+/// how many were added.
+#[post("/api/wishlist/add", auth: MemberSession)]
+pub async fn add_to_wishlist(
+    items: Vec<NewWishlistItem>,
+    source: Option<String>,
+) -> Result<usize, ServerFnError> {
+    WishlistItem::add_many(&auth.0.sub, &items, source.as_deref())
+        .await
+        .map_err(server_error)
+}
+
+#[delete("/api/wishlist/delete", auth: MemberSession)]
+pub async fn remove_from_wishlist(item_id: String) -> Result<(), ServerFnError> {
+    WishlistItem::delete(&item_id, &auth.0.sub)
+        .await
+        .map_err(server_error)
+}
+
+/// Read chart entries from a chart URL (Album of the Year, RateYourMusic)
+/// or from a pasted list with one "Artist - Album" per line.
+#[post("/api/wishlist/chart/parse", _: MemberSession)]
+pub async fn parse_chart(source: String) -> Result<Vec<ChartEntry>, ServerFnError> {
+    let source = source.trim();
+    let is_url = source.starts_with("http://") || source.starts_with("https://");
+    if is_url && !source.contains(char::is_whitespace) {
+        let client = soulbeet::http::build_client("soulful/0.1 (https://github.com/soulful)");
+        return soulbeet::charts::fetch_chart(&client, source)
+            .await
+            .map_err(server_error);
+    }
+
+    let entries = soulbeet::charts::parse_chart_text(source);
+    if entries.is_empty() {
+        return Err(server_error(
+            "No entries found; use one \"Artist - Album\" per line",
+        ));
+    }
+    Ok(entries)
+}
+
+/// Match chart entries to MusicBrainz releases. Entries that fail to match
+/// come back without an album so they can still be added by name.
+#[post("/api/wishlist/chart/resolve", _: MemberSession)]
+pub async fn resolve_chart_entries(
+    entries: Vec<ChartEntry>,
+) -> Result<Vec<ChartMatch>, ServerFnError> {
+    if entries.len() > CHART_RESOLVE_BATCH {
+        return Err(server_error(format!(
+            "At most {} entries can be resolved at once",
+            CHART_RESOLVE_BATCH
+        )));
+    }
+
+    let mut matches = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let album = match soulbeet::charts::resolve_entry(&entry).await {
+            Ok(album) => album,
+            Err(e) => {
+                warn!(
+                    "Failed to resolve '{}' by '{}': {}",
+                    entry.title,
+                    entry.artist,
+                    e
+                );
+                None
+            }
+        };
+        matches.push(ChartMatch { entry, album });
+    }
+    Ok(matches)
+}
+
+/// Merge a wishlist file from [`super::export_wishlist`] into the user's
+/// wishlist. Releases already wished for or queued are skipped, by MBID or
+/// else by artist and title. With a folder, the file's queue is queued
+/// again into it; otherwise its releases join the wishlist too.
+#[post("/api/wishlist/import", auth: MemberSession)]
+pub async fn import_wishlist(
+    file: WishlistExport,
+    queue_folder_id: Option<String>,
+) -> Result<WishlistImport, ServerFnError> {
+    if file.version > WISHLIST_EXPORT_VERSION {
+        return Err(validation_error(
+            "This wishlist file comes from a newer version of soulbeet",
+        ));
+    }
+    let total = file.wishlist.len() + file.queue.len();
+    if total > MAX_IMPORT_ENTRIES {
+        return Err(validation_error(format!(
+            "A wishlist file can hold up to {} releases",
+            MAX_IMPORT_ENTRIES
+        )));
+    }
+    let folder = match queue_folder_id.filter(|id| !id.is_empty()) {
+        Some(id) => Some(validate::folder(&auth.0.sub, &id).await?),
+        None => None,
+    };
+
+    let mut known: Vec<ExportedRelease> = WishlistItem::get_all_by_user(&auth.0.sub)
+        .await
+        .map_err(server_error)?
+        .into_iter()
+        .map(ExportedRelease::from)
+        .collect();
+    known.extend(
+        queued_releases(&auth.0.username)
+            .await
+            .into_iter()
+            .map(ExportedRelease::from),
+    );
+
+    let mut wished = new_releases(&known, file.wishlist);
+    known.extend(wished.iter().cloned());
+    let mut to_queue = Vec::new();
+    for release in new_releases(&known, file.queue) {
+        match (&folder, release.to_label_release()) {
+            (Some(_), Some(label_release)) => to_queue.push(label_release),
+            _ => wished.push(release),
+        }
+    }
+
+    // One insert per source, so imported entries keep theirs
+    let mut added = 0;
+    let mut sources: Vec<Option<String>> = Vec::new();
+    for release in &wished {
+        if !sources.contains(&release.source) {
+            sources.push(release.source.clone());
+        }
+    }
+    for source in sources {
+        let items: Vec<NewWishlistItem> = wished
+            .iter()
+            .filter(|r| r.source == source)
+            .map(|r| NewWishlistItem {
+                artist: r.artist.trim().to_string(),
+                title: r.title.trim().to_string(),
+                release_mbid: r.release_mbid.clone(),
+            })
+            .collect();
+        let source = source.unwrap_or_else(|| "import".to_string());
+        added += WishlistItem::add_many(&auth.0.sub, &items, Some(&source))
+            .await
+            .map_err(server_error)?;
+    }
+
+    let queued = to_queue.len();
+    if let Some(folder) = &folder {
+        if !to_queue.is_empty() {
+            enqueue_releases(&auth.0.username, &auth.0.sub, to_queue, folder).await;
+        }
+    }
+    info!(
+        "{} imported a wishlist: {} added, {} queued",
+        auth.0.username, added, queued
+    );
+    Ok(WishlistImport {
+        added,
+        queued,
+        skipped: total - added - queued,
+    })
+}
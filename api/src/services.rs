@@ -9,11 +9,15 @@ use soulbeet::{
     beets::BeetsImporter,
     musicbrainz::MusicBrainzProvider,
     navidrome::NavidromeClientBuilder,
+    plugin::{PluginDownloadBackend, PluginKind, PluginMetadataProvider},
     slskd::{DownloadConfig, SoulseekClientBuilder},
     DownloadBackend, LastFmProvider, MetadataProvider, MusicImporter, NavidromeClient,
+    SpotifyProvider,
 };
 #[cfg(feature = "server")]
 use tokio::sync::RwLock;
+#[cfg(feature = "server")]
+use tracing::{info, warn};
 
 #[cfg(feature = "server")]
 use crate::models::app_config::{keys, AppConfig};
@@ -21,6 +25,7 @@ use crate::models::app_config::{keys, AppConfig};
 pub mod providers {
     pub const MUSICBRAINZ: &str = "musicbrainz";
     pub const LASTFM: &str = "lastfm";
+    pub const SPOTIFY: &str = "spotify";
 }
 
 pub mod downloaders {
@@ -43,26 +48,102 @@ static DOWNLOAD_BACKENDS: LazyLock<RwLock<HashMap<String, Arc<dyn DownloadBacken
 static MUSIC_IMPORTERS: LazyLock<RwLock<HashMap<String, Arc<dyn MusicImporter>>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
+/// The demo backend and importer, used in place of every configured one.
+#[cfg(feature = "demo")]
+static DEMO_SERVICES: LazyLock<soulbeet::Services> =
+    LazyLock::new(|| soulbeet::demo::services(crate::config::CONFIG.download_path().clone()));
+
+/// Providers and backends implemented by programs in `PLUGINS_DIR`. They
+/// are loaded once and survive `reload_providers`, as their programs keep
+/// running.
+#[cfg(feature = "server")]
+#[derive(Default)]
+struct Plugins {
+    metadata: Vec<Arc<dyn MetadataProvider>>,
+    download: Vec<Arc<dyn DownloadBackend>>,
+}
+
+#[cfg(feature = "server")]
+static PLUGINS: LazyLock<Plugins> = LazyLock::new(|| {
+    let mut plugins = Plugins::default();
+    let Some(dir) = crate::config::CONFIG.plugins_dir() else {
+        return plugins;
+    };
+    let builtin = [
+        providers::MUSICBRAINZ,
+        providers::LASTFM,
+        providers::SPOTIFY,
+        downloaders::SLSKD,
+    ];
+    for plugin in soulbeet::plugin::discover(dir) {
+        if builtin.contains(&plugin.manifest.id.as_str()) {
+            warn!(
+                "Skipping plugin in {}: '{}' is a built-in id",
+                plugin.dir.display(),
+                plugin.manifest.id
+            );
+            continue;
+        }
+        info!(
+            "Loaded plugin {} ({:?}) from {}",
+            plugin.manifest.name,
+            plugin.manifest.kind,
+            plugin.dir.display()
+        );
+        match plugin.manifest.kind {
+            PluginKind::Metadata => plugins
+                .metadata
+                .push(Arc::new(PluginMetadataProvider::new(plugin))),
+            PluginKind::Download => plugins
+                .download
+                .push(Arc::new(PluginDownloadBackend::new(plugin))),
+        }
+    }
+    plugins
+});
+
 #[cfg(feature = "server")]
 static NAVIDROME_CLIENTS: LazyLock<RwLock<HashMap<String, Arc<NavidromeClient>>>> =
     LazyLock::new(|| RwLock::new(HashMap::new()));
 
 #[cfg(feature = "server")]
 pub fn available_metadata_providers() -> Vec<(&'static str, &'static str)> {
-    vec![
+    let mut available = vec![
         (providers::MUSICBRAINZ, "MusicBrainz"),
         (providers::LASTFM, "Last.fm"),
-    ]
+    ];
+    // Every search would fail without its client credentials
+    if SpotifyProvider::from_env().is_some() {
+        available.push((providers::SPOTIFY, "Spotify"));
+    }
+    available.extend(PLUGINS.metadata.iter().map(|p| (p.id(), p.name())));
+    available
 }
 
 #[cfg(feature = "server")]
 pub fn available_download_backends() -> Vec<(&'static str, &'static str)> {
-    vec![(downloaders::SLSKD, "Soulseek")]
+    #[cfg(feature = "demo")]
+    {
+        DEMO_SERVICES.list_downloads()
+    }
+    #[cfg(not(feature = "demo"))]
+    {
+        let mut available = vec![(downloaders::SLSKD, "Soulseek")];
+        available.extend(PLUGINS.download.iter().map(|p| (p.id(), p.name())));
+        available
+    }
 }
 
 #[cfg(feature = "server")]
 pub fn available_importers() -> Vec<(&'static str, &'static str)> {
-    vec![(importers::BEETS, "Beets")]
+    #[cfg(feature = "demo")]
+    {
+        DEMO_SERVICES.list_importers()
+    }
+    #[cfg(not(feature = "demo"))]
+    {
+        vec![(importers::BEETS, "Beets")]
+    }
 }
 
 #[cfg(feature = "server")]
@@ -77,6 +158,11 @@ async fn init_metadata_provider(
                 .ok_or("Last.fm API key not configured")?;
             Ok(Arc::new(LastFmProvider::new(api_key.to_string())))
         }
+        providers::SPOTIFY => {
+            let provider =
+                SpotifyProvider::from_env().ok_or("Spotify client credentials not configured")?;
+            Ok(Arc::new(provider))
+        }
         _ => Ok(Arc::new(MusicBrainzProvider::new())),
     }
 }
@@ -105,6 +191,7 @@ async fn init_download_backend(id: &str) -> Result<Arc<dyn DownloadBackend>, Str
                     max_retries: 3,
                     retry_base_delay_ms: 1000,
                 })
+                .request_timeout(crate::config::CONFIG.slskd_request_timeout())
                 .build()
                 .map_err(|e| e.to_string())?;
 
@@ -121,6 +208,10 @@ pub async fn metadata_provider(
 ) -> Result<Arc<dyn MetadataProvider>, String> {
     let requested = id.unwrap_or(providers::MUSICBRAINZ);
 
+    if let Some(plugin) = PLUGINS.metadata.iter().find(|p| p.id() == requested) {
+        return Ok(plugin.clone());
+    }
+
     // Don't cache Last.fm providers since keys are per-user
     if requested != providers::LASTFM {
         if let Some(provider) = METADATA_PROVIDERS.read().await.get(requested) {
@@ -149,8 +240,17 @@ pub async fn metadata_provider(
 
 #[cfg(feature = "server")]
 pub async fn download_backend(id: Option<&str>) -> Result<Arc<dyn DownloadBackend>, String> {
+    #[cfg(feature = "demo")]
+    if let Some(backend) = DEMO_SERVICES.download(None) {
+        return Ok(backend.clone());
+    }
+
     let requested = id.unwrap_or(downloaders::SLSKD);
 
+    if let Some(plugin) = PLUGINS.download.iter().find(|p| p.id() == requested) {
+        return Ok(plugin.clone());
+    }
+
     if let Some(backend) = DOWNLOAD_BACKENDS.read().await.get(requested) {
         return Ok(backend.clone());
     }
@@ -173,6 +273,11 @@ fn init_importer(id: &str) -> Result<Arc<dyn MusicImporter>, String> {
 
 #[cfg(feature = "server")]
 pub async fn music_importer(id: Option<&str>) -> Result<Arc<dyn MusicImporter>, String> {
+    #[cfg(feature = "demo")]
+    if let Some(importer) = DEMO_SERVICES.importer(None) {
+        return Ok(importer.clone());
+    }
+
     let requested = id.unwrap_or(importers::BEETS);
 
     if let Some(importer) = MUSIC_IMPORTERS.read().await.get(requested) {
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use crate::download::DownloadState;
+
+/// One entry of the activity stream served to external dashboards.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEvent {
+    /// Monotonic id, usable as the SSE `Last-Event-ID`
+    pub id: u64,
+    /// Unix timestamp (seconds)
+    pub timestamp: i64,
+    pub username: String,
+    #[serde(flatten)]
+    pub kind: ActivityKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ActivityKind {
+    SearchStarted {
+        search_id: String,
+        query: String,
+    },
+    DownloadQueued {
+        download_id: String,
+        item: String,
+        batch_label: Option<String>,
+    },
+    DownloadState {
+        download_id: String,
+        item: String,
+        state: DownloadState,
+    },
+    ImportResult {
+        download_id: String,
+        item: String,
+        imported: bool,
+        error: Option<String>,
+    },
+    /// A folder's mount failed or came back
+    FolderHealth {
+        folder_id: String,
+        path: String,
+        healthy: bool,
+        problem: Option<String>,
+    },
+    /// An album was removed from a library folder, or moved to its trash
+    AlbumRemoved {
+        folder_id: String,
+        path: String,
+        trashed: bool,
+    },
+}
+
+impl ActivityKind {
+    /// Name used as the SSE `event:` field.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActivityKind::SearchStarted { .. } => "search_started",
+            ActivityKind::DownloadQueued { .. } => "download_queued",
+            ActivityKind::DownloadState { .. } => "download_state",
+            ActivityKind::ImportResult { .. } => "import_result",
+            ActivityKind::FolderHealth { .. } => "folder_health",
+            ActivityKind::AlbumRemoved { .. } => "album_removed",
+        }
+    }
+}
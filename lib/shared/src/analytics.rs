@@ -0,0 +1,46 @@
+//! Local analytics of how well search results are ranked: which candidate
+//! users end up downloading and whether it imports. Kept on the server
+//! only, without user names, to tune the weights of
+//! [`rank_match`](crate::matching::rank_match).
+
+use serde::{Deserialize, Serialize};
+
+/// Picks of candidates at one rank of the results and how they ended.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RankOutcome {
+    /// 0 for the top candidate; the last bucket also holds every lower rank
+    pub rank: u32,
+    /// Picked by auto-download rather than by hand
+    pub automatic: bool,
+    pub picks: u64,
+    pub imported: u64,
+    pub failed: u64,
+    /// Mean score difference with the top candidate
+    pub mean_score_gap: f64,
+}
+
+impl RankOutcome {
+    /// Share of the finished picks that failed.
+    pub fn failure_rate(&self) -> Option<f64> {
+        let finished = self.imported + self.failed;
+        (finished > 0).then(|| self.failed as f64 / finished as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MatchingReport {
+    pub ranks: Vec<RankOutcome>,
+}
+
+impl MatchingReport {
+    /// Ranks at or past this one are counted together.
+    pub const LAST_RANK: u32 = 3;
+
+    /// Share of the manual picks that went to the top candidate.
+    pub fn top_pick_rate(&self) -> Option<f64> {
+        let manual = || self.ranks.iter().filter(|r| !r.automatic);
+        let total: u64 = manual().map(|r| r.picks).sum();
+        let top: u64 = manual().filter(|r| r.rank == 0).map(|r| r.picks).sum();
+        (total > 0).then(|| top as f64 / total as f64)
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// A release group of a watched artist, placed on the release calendar.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CalendarRelease {
+    pub release_group_id: String,
+    pub artist_mbid: String,
+    pub artist: String,
+    pub title: String,
+    /// MusicBrainz primary type, e.g. "Album" or "EP"
+    pub release_type: Option<String>,
+    /// First release date as given by MusicBrainz: YYYY, YYYY-MM or YYYY-MM-DD
+    pub date: String,
+    /// Set when the release came back after a "remind me later"
+    #[serde(default)]
+    pub reminded: bool,
+}
+
+impl CalendarRelease {
+    /// Whether the date is a full day, which is required for calendar export.
+    pub fn has_full_date(&self) -> bool {
+        self.date.len() == 10
+    }
+}
+
+/// What to do with a calendar entry.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ReleaseAction {
+    /// Hide the release from the calendar for good
+    Ignore,
+    /// Hide the release for a number of days
+    RemindLater { days: u32 },
+    /// Undo a previous ignore or reminder
+    Clear,
+}
+
+/// An album of a related artist, and whether the user's library holds it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedAlbum {
+    pub release_group_id: String,
+    pub title: String,
+    /// First release date as given by MusicBrainz, possibly partial
+    pub date: String,
+    pub owned: bool,
+}
+
+/// An artist linked to a watched one on MusicBrainz, with their albums.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RelatedArtist {
+    pub mbid: String,
+    pub name: String,
+    /// How they relate to the watched artist, e.g. "Member"
+    pub relation: String,
+    pub watched: bool,
+    /// Oldest first
+    pub albums: Vec<RelatedAlbum>,
+}
+
+impl RelatedArtist {
+    pub fn owned_count(&self) -> usize {
+        self.albums.iter().filter(|a| a.owned).count()
+    }
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::metadata::{Album, Track};
+use crate::quality::{DiversityOptions, SearchFilters};
 
 #[derive(Serialize, Clone, PartialEq, Deserialize, Debug, Default)]
 pub struct DownloadQuery {
@@ -8,6 +9,14 @@ pub struct DownloadQuery {
     pub tracks: Vec<Track>,
     #[serde(default)]
     pub backend: Option<String>,
+    /// Overrides the user's matching mode for this search
+    #[serde(default)]
+    pub match_mode: Option<MatchMode>,
+    /// Overrides the user's search timeout for this search, in seconds
+    #[serde(default)]
+    pub search_timeout_secs: Option<u32>,
+    #[serde(default)]
+    pub search_strategy: SearchStrategy,
 }
 
 impl DownloadQuery {
@@ -16,6 +25,9 @@ impl DownloadQuery {
             album: None,
             tracks,
             backend: None,
+            match_mode: None,
+            search_timeout_secs: None,
+            search_strategy: SearchStrategy::default(),
         }
     }
 
@@ -36,6 +48,197 @@ impl From<Track> for DownloadQuery {
     }
 }
 
+/// How long a search collects responses when the user hasn't picked a
+/// timeout.
+pub const DEFAULT_SEARCH_TIMEOUT_SECS: u32 = 120;
+
+/// Timeouts offered when starting a search, in seconds.
+pub const SEARCH_TIMEOUT_CHOICES: [u32; 3] = [30, 60, 120];
+
+/// Per-search tuning handed to the download backend.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SearchOptions {
+    /// Files scoring below this against the release are dropped
+    pub min_score: f64,
+    pub diversity: DiversityOptions,
+    /// How long the backend keeps collecting responses
+    pub timeout_secs: u32,
+    pub filters: SearchFilters,
+    #[serde(default)]
+    pub strategy: SearchStrategy,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            min_score: MatchMode::default().min_score(),
+            diversity: DiversityOptions::default(),
+            timeout_secs: DEFAULT_SEARCH_TIMEOUT_SECS,
+            filters: SearchFilters::default(),
+            strategy: SearchStrategy::default(),
+        }
+    }
+}
+
+/// Shape of the text sent to Soulseek for a search. Peers name their
+/// folders in all sorts of ways, and a release no peer answers for under
+/// one shape can turn up under another.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// The shapes of `SEQUENCE` in turn, until one gets answers
+    #[default]
+    Auto,
+    /// `artist title`
+    ArtistTitle,
+    /// `title`
+    TitleOnly,
+    /// `artist title year`
+    WithYear,
+    /// `"artist" "title"`
+    Quoted,
+    /// `artist.title`, as in scene-style file names
+    Dotted,
+}
+
+impl SearchStrategy {
+    pub const ALL: [SearchStrategy; 6] = [
+        SearchStrategy::Auto,
+        SearchStrategy::ArtistTitle,
+        SearchStrategy::TitleOnly,
+        SearchStrategy::WithYear,
+        SearchStrategy::Quoted,
+        SearchStrategy::Dotted,
+    ];
+
+    /// Shapes tried by `Auto`, widest net last. A year only narrows a
+    /// search that already found nothing, so it is left out.
+    pub const SEQUENCE: [SearchStrategy; 4] = [
+        SearchStrategy::ArtistTitle,
+        SearchStrategy::Dotted,
+        SearchStrategy::Quoted,
+        SearchStrategy::TitleOnly,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            SearchStrategy::Auto => "Auto",
+            SearchStrategy::ArtistTitle => "Artist + title",
+            SearchStrategy::TitleOnly => "Title only",
+            SearchStrategy::WithYear => "With year",
+            SearchStrategy::Quoted => "Quoted",
+            SearchStrategy::Dotted => "Dotted",
+        }
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|s| s.to_setting() == value)
+    }
+
+    pub fn to_setting(&self) -> &'static str {
+        match self {
+            SearchStrategy::Auto => "auto",
+            SearchStrategy::ArtistTitle => "artist_title",
+            SearchStrategy::TitleOnly => "title_only",
+            SearchStrategy::WithYear => "with_year",
+            SearchStrategy::Quoted => "quoted",
+            SearchStrategy::Dotted => "dotted",
+        }
+    }
+
+    /// Search texts for `artist` and `subject` (the album or track title),
+    /// in the order to try them. `year` is the release year, if known;
+    /// without it `WithYear` searches by artist and title.
+    pub fn queries(&self, artist: &str, subject: &str, year: Option<&str>) -> Vec<String> {
+        let (artist, subject) = (artist.trim(), subject.trim());
+        let shapes = match self {
+            SearchStrategy::Auto => Self::SEQUENCE.to_vec(),
+            shape => vec![*shape],
+        };
+        let mut queries: Vec<String> = Vec::new();
+        for shape in shapes {
+            let query = match shape {
+                SearchStrategy::TitleOnly => subject.to_string(),
+                SearchStrategy::WithYear => match year {
+                    Some(year) => format!("{} {} {}", artist, subject, year),
+                    None => format!("{} {}", artist, subject),
+                },
+                SearchStrategy::Quoted => format!("\"{}\" \"{}\"", artist, subject),
+                SearchStrategy::Dotted => artist
+                    .split_whitespace()
+                    .chain(subject.split_whitespace())
+                    .collect::<Vec<_>>()
+                    .join("."),
+                SearchStrategy::Auto | SearchStrategy::ArtistTitle => {
+                    format!("{} {}", artist, subject)
+                }
+            };
+            if !queries.contains(&query) {
+                queries.push(query);
+            }
+        }
+        queries
+    }
+}
+
+/// How closely a file path must match the searched release to be offered.
+/// Rare releases are often shared under messy paths and need loose
+/// matching; popular ones have plenty of well-named copies to be strict with.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum MatchMode {
+    Strict,
+    #[default]
+    Normal,
+    Loose,
+    /// Minimum match score between 0 and 1
+    Custom(f64),
+}
+
+impl MatchMode {
+    pub const PRESETS: [MatchMode; 3] = [MatchMode::Strict, MatchMode::Normal, MatchMode::Loose];
+
+    /// Minimum match score a file needs to be kept.
+    pub fn min_score(&self) -> f64 {
+        match self {
+            MatchMode::Strict => 0.75,
+            MatchMode::Normal => 0.6,
+            MatchMode::Loose => 0.45,
+            MatchMode::Custom(score) => score.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            MatchMode::Strict => "Strict",
+            MatchMode::Normal => "Normal",
+            MatchMode::Loose => "Loose",
+            MatchMode::Custom(_) => "Custom",
+        }
+    }
+
+    /// Parse the stored form: a preset name or a custom score like "0.55".
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "strict" => Some(MatchMode::Strict),
+            "normal" => Some(MatchMode::Normal),
+            "loose" => Some(MatchMode::Loose),
+            other => other
+                .parse::<f64>()
+                .ok()
+                .filter(|score| (0.0..=1.0).contains(score))
+                .map(MatchMode::Custom),
+        }
+    }
+
+    pub fn to_setting(&self) -> String {
+        match self {
+            MatchMode::Custom(score) => format!("{:.2}", score.clamp(0.0, 1.0)),
+            preset => preset.label().to_lowercase(),
+        }
+    }
+}
+
 /// A downloadable item from a search result
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DownloadableItem {
@@ -83,6 +286,9 @@ pub struct DownloadableGroup {
     pub quality: String,
     /// Overall score for ranking
     pub score: f64,
+    /// Share of the expected tracks in the group, when the backend knows it
+    #[serde(default)]
+    pub completeness: Option<f64>,
 }
 
 impl DownloadableGroup {
@@ -107,6 +313,12 @@ pub struct SearchResult {
     pub groups: Vec<DownloadableGroup>,
     pub has_more: bool,
     pub state: SearchState,
+    /// Peers that answered the search, whether or not their files matched
+    #[serde(default)]
+    pub responses: u32,
+    /// Text the backend searched for, when it tells
+    #[serde(default)]
+    pub search_text: Option<String>,
 }
 
 /// State of a download operation
@@ -115,11 +327,563 @@ pub enum DownloadState {
     Queued,
     InProgress,
     Completed,
+    /// Downloaded, waiting for another import of the same folder to finish
+    ImportWaiting,
+    /// Downloaded, waiting for the target folder's mount to come back
+    ImportPaused,
     Importing,
+    /// Imported, moving the files to the folder's remote target. Holds the
+    /// attempt number, from 1.
+    Uploading(u32),
     Imported,
     ImportSkipped,
     Failed(String),
     Cancelled,
+    /// Dry run: found and planned, but never queued
+    Simulated,
+}
+
+/// Beets import flags a user may add to a single download. Anything else is
+/// rejected server-side. Pairs of (flag, description).
+pub const IMPORT_FLAG_ALLOWLIST: &[(&str, &str)] = &[
+    ("--flat", "Import a whole directory tree as one album"),
+    ("--group-albums", "Split files into albums by their tags"),
+    ("--noautotag", "Keep existing tags, skip MusicBrainz matching"),
+    ("--nowrite", "Don't write tags to the files"),
+    ("--noincremental", "Re-import directories seen before"),
+];
+
+/// Per-download changes to how the finished files are imported.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportOverrides {
+    /// Importer to use instead of the default one
+    #[serde(default)]
+    pub importer: Option<String>,
+    /// Extra importer flags, limited to `IMPORT_FLAG_ALLOWLIST`
+    #[serde(default)]
+    pub extra_flags: Vec<String>,
+    /// Import destination instead of the selected folder
+    #[serde(default)]
+    pub target_path: Option<String>,
+    /// Hold the finished files until their tags have been reviewed
+    #[serde(default)]
+    pub review_tags: bool,
+    /// Album in the library these files complete. They are tagged like the
+    /// tracks already there and moved in, instead of going through the
+    /// importer.
+    #[serde(default)]
+    pub complete_album: Option<AlbumMerge>,
+    /// Skip MusicBrainz matching and file as a live recording or bootleg
+    #[serde(default)]
+    pub non_canonical: Option<NonCanonicalImport>,
+    /// Release the files were searched for, to match them against
+    #[serde(default)]
+    pub release: Option<ExpectedRelease>,
+    /// Tracks picked by hand for some files, keyed by `remote_file_name`.
+    /// Written to the file tags before import.
+    #[serde(default)]
+    pub track_mapping: std::collections::HashMap<String, MappedTrack>,
+    /// Link instead of moving into the library. Taken from the target
+    /// folder's setting at import time.
+    #[serde(default)]
+    pub import_mode: ImportMode,
+    /// Remote target of the import folder, uploaded to after import. Taken
+    /// from the folder at import time.
+    #[serde(default)]
+    pub remote: Option<String>,
+    /// Further folders that get their own copy of the download, each
+    /// imported independently of the main one
+    #[serde(default)]
+    pub extra_targets: Vec<ImportTarget>,
+}
+
+/// Another library a download is imported into, next to the selected
+/// folder. The folder's own import mode and remote apply.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportTarget {
+    pub folder_id: String,
+    /// Importer for this folder instead of the default one
+    #[serde(default)]
+    pub importer: Option<String>,
+}
+
+/// How the import of a download into one of its extra targets went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TargetImport {
+    pub folder_id: String,
+    /// Folder name, for display
+    pub folder: String,
+    pub state: DownloadState,
+}
+
+/// Expected track a downloaded file was assigned to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MappedTrack {
+    pub title: String,
+    /// Position on the release, when known
+    #[serde(default)]
+    pub number: Option<u32>,
+}
+
+/// File name part of a path on a peer, which may use either separator.
+pub fn remote_file_name(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
+}
+
+/// How finished downloads are placed into a library folder.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImportMode {
+    /// Whatever the importer is configured to do (usually move)
+    #[default]
+    Default,
+    /// Hard-link files into the library, keeping the download in place.
+    /// Both folders must be on the same filesystem.
+    Hardlink,
+    /// Copy-on-write clone of each file (btrfs, xfs), keeping the download
+    Reflink,
+}
+
+impl ImportMode {
+    pub const ALL: [ImportMode; 3] = [
+        ImportMode::Default,
+        ImportMode::Hardlink,
+        ImportMode::Reflink,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportMode::Default => "default",
+            ImportMode::Hardlink => "hardlink",
+            ImportMode::Reflink => "reflink",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ImportMode::Default => "Importer default",
+            ImportMode::Hardlink => "Hard link",
+            ImportMode::Reflink => "Reflink",
+        }
+    }
+
+    /// Whether the download is kept in place after import.
+    pub fn keeps_download(&self) -> bool {
+        !matches!(self, ImportMode::Default)
+    }
+}
+
+impl From<&str> for ImportMode {
+    fn from(s: &str) -> Self {
+        match s {
+            "hardlink" => ImportMode::Hardlink,
+            "reflink" => ImportMode::Reflink,
+            _ => ImportMode::Default,
+        }
+    }
+}
+
+/// Kinds of release that rarely match MusicBrainz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NonCanonicalKind {
+    Live,
+    Bootleg,
+    /// Any release, imported from its own tags while MusicBrainz is down
+    TagDerived,
+}
+
+impl NonCanonicalKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            NonCanonicalKind::Live => "Live",
+            NonCanonicalKind::Bootleg => "Bootleg",
+            NonCanonicalKind::TagDerived => "Tag-derived",
+        }
+    }
+}
+
+/// Import tagged from the picked search result and the folder name instead
+/// of a MusicBrainz match, filed under the live/bootleg path template.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NonCanonicalImport {
+    pub kind: NonCanonicalKind,
+    /// Falls back to the existing file tags
+    #[serde(default)]
+    pub artist: Option<String>,
+    /// Falls back to the download folder name
+    #[serde(default)]
+    pub album: Option<String>,
+}
+
+impl ImportOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.importer.is_none()
+            && self.extra_flags.is_empty()
+            && self.target_path.is_none()
+            && !self.review_tags
+            && self.complete_album.is_none()
+            && self.non_canonical.is_none()
+            && self.release.is_none()
+            && self.track_mapping.is_empty()
+            && self.extra_targets.is_empty()
+    }
+}
+
+/// MusicBrainz release picked in the search. Beets is pointed at it, so an
+/// 18-track deluxe edition isn't matched to the 12-track standard one.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExpectedRelease {
+    pub mbid: String,
+    #[serde(default)]
+    pub track_count: Option<u32>,
+    #[serde(default)]
+    pub country: Option<String>,
+}
+
+impl ExpectedRelease {
+    /// The release of a provider album, when it has a MusicBrainz id.
+    pub fn from_album(album: &Album) -> Option<Self> {
+        Some(Self {
+            mbid: album.mbid.clone()?,
+            track_count: album.details.track_count,
+            country: album.details.country.clone(),
+        })
+    }
+
+    /// Warning for an import the importer matched to another release.
+    /// Nothing when it matched this one or didn't say.
+    pub fn mismatch(&self, summary: &ImportSummary) -> Option<String> {
+        let matched = summary.release_id.as_deref()?;
+        if matched == self.mbid {
+            return None;
+        }
+        let details: Vec<String> = [
+            self.track_count.map(|n| format!("{} tracks", n)),
+            self.country.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let requested = match details.as_slice() {
+            [] => self.mbid.clone(),
+            _ => format!("{} ({})", self.mbid, details.join(", ")),
+        };
+        Some(format!(
+            "Matched release {} instead of the requested {}",
+            matched, requested
+        ))
+    }
+}
+
+/// Files added to an album already in the library.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AlbumMerge {
+    /// Directory holding the album's tracks
+    pub path: String,
+    /// Titles of the release in order, to number the new tracks by
+    #[serde(default)]
+    pub tracklist: Vec<String>,
+}
+
+impl AlbumMerge {
+    /// Position on the release of the track titled `title`, from 1.
+    pub fn track_number(&self, title: &str) -> Option<u32> {
+        let wanted = crate::matching::clean_name(title).to_lowercase();
+        self.tracklist
+            .iter()
+            .position(|t| crate::matching::clean_name(t).to_lowercase() == wanted)
+            .map(|i| i as u32 + 1)
+    }
+}
+
+/// Tags of one downloaded file, as read before import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FileTags {
+    pub path: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub year: Option<u32>,
+    pub track: Option<u32>,
+    pub has_cover: bool,
+}
+
+/// A finished download waiting for the user to review its tags.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingImport {
+    pub id: String,
+    /// Album name or file name, for display
+    pub label: String,
+    pub files: Vec<FileTags>,
+    pub target_path: String,
+    pub created_at: i64,
+}
+
+/// Tag corrections written to the files of a pending import. `None` keeps
+/// the existing value.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportEdits {
+    pub artist: Option<String>,
+    pub album_artist: Option<String>,
+    pub album: Option<String>,
+    pub year: Option<u32>,
+    /// Image to embed as front cover, replacing any existing one
+    pub cover_url: Option<String>,
+    /// New titles keyed by file path
+    #[serde(default)]
+    pub titles: std::collections::HashMap<String, String>,
+    /// New track numbers keyed by file path
+    #[serde(default)]
+    pub track_numbers: std::collections::HashMap<String, u32>,
+}
+
+impl ImportEdits {
+    pub fn is_empty(&self) -> bool {
+        self.artist.is_none()
+            && self.album_artist.is_none()
+            && self.album.is_none()
+            && self.year.is_none()
+            && self.cover_url.is_none()
+            && self.titles.is_empty()
+            && self.track_numbers.is_empty()
+    }
+}
+
+/// How downloaded files are renamed before import, for importer setups
+/// that read information from file names.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenameRules {
+    pub enabled: bool,
+    /// Drop leading track numbers and trailing `[tags]` or years
+    pub strip_track_junk: bool,
+    /// Underscores to spaces, unicode dashes to `-`, collapse whitespace
+    pub normalize_separators: bool,
+    /// Fold accented latin letters to ASCII
+    pub transliterate: bool,
+    /// File name without extension. `{number}` is the track number found
+    /// in the original name, `{title}` the cleaned rest.
+    pub template: String,
+}
+
+impl Default for RenameRules {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strip_track_junk: true,
+            normalize_separators: true,
+            transliterate: false,
+            template: "{number} - {title}".to_string(),
+        }
+    }
+}
+
+impl RenameRules {
+    /// Parse the stored JSON form, falling back to defaults when unset or invalid.
+    pub fn from_json(json: Option<&str>) -> Self {
+        json.and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// A file name before and after applying `RenameRules`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenamePreview {
+    pub from: String,
+    pub to: String,
+}
+
+/// What a user thought of a finished download.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadFeedback {
+    Good,
+    WrongAlbum,
+    FakeFlac,
+    BadRip,
+}
+
+impl DownloadFeedback {
+    pub const COMPLAINTS: [DownloadFeedback; 3] = [
+        DownloadFeedback::WrongAlbum,
+        DownloadFeedback::FakeFlac,
+        DownloadFeedback::BadRip,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DownloadFeedback::Good => "Good",
+            DownloadFeedback::WrongAlbum => "Wrong album",
+            DownloadFeedback::FakeFlac => "Fake FLAC",
+            DownloadFeedback::BadRip => "Bad rip",
+        }
+    }
+
+    pub fn is_complaint(&self) -> bool {
+        *self != DownloadFeedback::Good
+    }
+
+    pub fn from_setting(value: &str) -> Option<Self> {
+        match value {
+            "good" => Some(DownloadFeedback::Good),
+            "wrong_album" => Some(DownloadFeedback::WrongAlbum),
+            "fake_flac" => Some(DownloadFeedback::FakeFlac),
+            "bad_rip" => Some(DownloadFeedback::BadRip),
+            _ => None,
+        }
+    }
+
+    pub fn to_setting(&self) -> &'static str {
+        match self {
+            DownloadFeedback::Good => "good",
+            DownloadFeedback::WrongAlbum => "wrong_album",
+            DownloadFeedback::FakeFlac => "fake_flac",
+            DownloadFeedback::BadRip => "bad_rip",
+        }
+    }
+}
+
+/// A user's own note and tags on a download, like "for dad" or
+/// "needs replacing".
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq, Eq, Debug)]
+pub struct DownloadNote {
+    #[serde(default)]
+    pub note: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl DownloadNote {
+    /// Tags from comma-separated text, trimmed, without repeats.
+    pub fn parse_tags(text: &str) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        for tag in text.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        tags
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.note.trim().is_empty() && self.tags.is_empty()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+}
+
+/// A finished download, kept for the user's history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DownloadHistoryEntry {
+    #[serde(default)]
+    pub id: String,
+    pub item: String,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    /// File extension, upper-cased
+    pub format: Option<String>,
+    /// Soulseek user the file came from
+    pub peer: String,
+    pub size: u64,
+    /// "imported", "skipped", "failed" or "cancelled"
+    pub outcome: String,
+    pub error: Option<String>,
+    /// Unix timestamp
+    pub finished_at: i64,
+    /// Where the file ended up in the library, for imported downloads
+    #[serde(default)]
+    pub imported_path: Option<String>,
+    /// Imported although already in the library
+    #[serde(default)]
+    pub duplicate: bool,
+    #[serde(default)]
+    pub feedback: Option<DownloadFeedback>,
+    /// Id of the transfer while it ran, which its note is kept under
+    #[serde(default)]
+    pub download_id: Option<String>,
+    #[serde(default)]
+    pub note: Option<DownloadNote>,
+}
+
+impl DownloadHistoryEntry {
+    /// What the entry's note is kept under: the transfer's id, or the
+    /// entry's own for downloads recorded before notes existed.
+    pub fn note_key(&self) -> &str {
+        self.download_id.as_deref().unwrap_or(&self.id)
+    }
+}
+
+/// A Soulseek search a user ran and what came back.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchRecord {
+    /// The backend's search id
+    pub id: String,
+    /// What was searched for, to run the search again
+    pub query: DownloadQuery,
+    /// `Artist - Title` of the search
+    pub description: String,
+    pub strategy: SearchStrategy,
+    /// Last text the backend searched for, once a poll reported it
+    pub search_text: Option<String>,
+    pub timeout_secs: u32,
+    /// Peers that answered the last search text
+    pub responses: u32,
+    /// Results that scored above the matching threshold
+    pub candidates: u32,
+    /// Run by auto-download rather than from the search page
+    pub automatic: bool,
+    /// Unix timestamp
+    pub started_at: i64,
+}
+
+/// How the searches made with one strategy went.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyStats {
+    pub strategy: SearchStrategy,
+    pub searches: u32,
+    /// Searches with at least one candidate
+    pub with_candidates: u32,
+    pub mean_responses: f64,
+}
+
+impl StrategyStats {
+    /// Share of the searches that found a candidate.
+    pub fn hit_rate(&self) -> Option<f64> {
+        (self.searches > 0).then(|| self.with_candidates as f64 / self.searches as f64)
+    }
+}
+
+/// A user's recent searches, with how each strategy fared over all of
+/// their kept searches.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SearchHistory {
+    pub searches: Vec<SearchRecord>,
+    pub strategies: Vec<StrategyStats>,
+}
+
+/// Bytes a user downloaded on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyBandwidth {
+    /// UTC date, `YYYY-MM-DD`
+    pub day: String,
+    pub bytes: u64,
+}
+
+/// Bytes a user downloaded lately, counted when transfers end.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthUsage {
+    pub today: u64,
+    /// The last 7 days, today included
+    pub week: u64,
+    /// The last 30 days, today included
+    pub month: u64,
+    /// Days of the last 30 with something downloaded, newest first
+    pub days: Vec<DailyBandwidth>,
 }
 
 /// Progress of a single download
@@ -153,6 +917,110 @@ pub struct DownloadProgress {
     /// Human-readable batch label (album name)
     #[serde(default)]
     pub batch_label: Option<String>,
+    /// Imported from the files' own tags because MusicBrainz was unreachable
+    #[serde(default)]
+    pub tag_derived: bool,
+    /// Where the import put the files, once imported
+    #[serde(default)]
+    pub import_summary: Option<ImportSummary>,
+    /// The library file this download became, once imported
+    #[serde(default)]
+    pub imported_file: Option<ImportedFile>,
+    /// Where a simulated download would have been imported
+    #[serde(default)]
+    pub planned_path: Option<String>,
+    /// Imports into the extra target folders, one per folder
+    #[serde(default)]
+    pub target_imports: Vec<TargetImport>,
+}
+
+/// What an import did with a download: where the files landed and which
+/// tags the importer changed on the way.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// Folder holding the imported files
+    pub destination: String,
+    /// Number of files imported, 0 when the importer doesn't say
+    pub tracks: usize,
+    /// Formats of the imported files, e.g. "FLAC"
+    pub formats: Vec<String>,
+    pub corrections: Vec<TagCorrection>,
+    /// Each imported file with the tags it ended up with
+    #[serde(default)]
+    pub files: Vec<ImportedFile>,
+    /// Problems the importer reported without failing
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// MusicBrainz release the importer matched, when it says
+    #[serde(default)]
+    pub release_id: Option<String>,
+}
+
+/// One file as the importer left it in the library.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ImportedFile {
+    /// Final path in the library
+    pub path: String,
+    pub artist: String,
+    pub album: String,
+    pub title: String,
+    /// Already in the library; the importer kept both copies or replaced one
+    pub duplicate: bool,
+}
+
+/// A tag the importer rewrote, such as an album title fixed to match
+/// MusicBrainz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TagCorrection {
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl ImportSummary {
+    /// "Imported to /music/Artist/Album (14 tracks, FLAC)"
+    pub fn describe(&self) -> String {
+        let mut details = Vec::new();
+        match self.tracks {
+            0 => {}
+            1 => details.push("1 track".to_string()),
+            n => details.push(format!("{} tracks", n)),
+        }
+        if !self.formats.is_empty() {
+            details.push(self.formats.join("/"));
+        }
+        if details.is_empty() {
+            format!("Imported to {}", self.destination)
+        } else {
+            format!("Imported to {} ({})", self.destination, details.join(", "))
+        }
+    }
+
+    /// The imported file a downloaded `item` became, matched on its title
+    /// appearing in the downloaded file name. The longest matching title
+    /// wins, so "Intro" doesn't claim "Intro (Reprise)".
+    pub fn file_for(&self, item: &str) -> Option<&ImportedFile> {
+        if let [only] = self.files.as_slice() {
+            return Some(only);
+        }
+        fn simplify(s: &str) -> String {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        }
+        let name = item.replace('\\', "/");
+        let stem = std::path::Path::new(name.rsplit('/').next().unwrap_or(&name))
+            .file_stem()
+            .map(|s| simplify(&s.to_string_lossy()))
+            .unwrap_or_default();
+        self.files
+            .iter()
+            .map(|f| (f, simplify(&f.title)))
+            .filter(|(_, title)| !title.is_empty() && stem.contains(title.as_str()))
+            .max_by_key(|(_, title)| title.len())
+            .map(|(f, _)| f)
+    }
 }
 
 impl DownloadProgress {
@@ -170,6 +1038,11 @@ impl DownloadProgress {
             backend: None,
             batch_id: None,
             batch_label: None,
+            tag_derived: false,
+            import_summary: None,
+            imported_file: None,
+            planned_path: None,
+            target_imports: Vec::new(),
         }
     }
 
@@ -187,6 +1060,11 @@ impl DownloadProgress {
             backend: None,
             batch_id: None,
             batch_label: None,
+            tag_derived: false,
+            import_summary: None,
+            imported_file: None,
+            planned_path: None,
+            target_imports: Vec::new(),
         }
     }
 
@@ -200,11 +1078,33 @@ impl DownloadProgress {
         self
     }
 
+    /// A dry-run entry for `item`, which would have been imported as
+    /// `planned_path`.
+    pub fn simulated(item: &DownloadableItem, planned_path: String) -> Self {
+        Self {
+            state: DownloadState::Simulated,
+            planned_path: Some(planned_path),
+            ..Self::queued(
+                item.id.clone(),
+                item.source.clone(),
+                item.id.clone(),
+                item.size.unwrap_or_default(),
+            )
+        }
+    }
+
     pub fn with_batch(mut self, batch_id: String, batch_label: String) -> Self {
         self.batch_id = Some(batch_id);
         self.batch_label = Some(batch_label);
         self
     }
+    /// Whether the transfer finished but importing the file failed.
+    pub fn is_failed_import(&self) -> bool {
+        match &self.state {
+            DownloadState::Failed(e) => e.starts_with("Import ") || e.starts_with("No importer"),
+            _ => false,
+        }
+    }
 }
 
 /// Response from queueing downloads
@@ -215,6 +1115,19 @@ pub struct QueuedDownload {
     pub item: String,
     pub size: u64,
     pub error: Option<String>,
+    /// The file this one stands in for, when its peer refused it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub substituted: Option<Substitution>,
+}
+
+/// A file a peer refused to queue, replaced by the same track from another
+/// peer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Substitution {
+    pub source: String,
+    pub item: String,
+    /// Why the peer refused it
+    pub error: String,
 }
 
 impl QueuedDownload {
@@ -225,6 +1138,7 @@ impl QueuedDownload {
             item,
             size,
             error: None,
+            substituted: None,
         }
     }
 
@@ -235,6 +1149,7 @@ impl QueuedDownload {
             item,
             size: 0,
             error: Some(error),
+            substituted: None,
         }
     }
 
@@ -250,6 +1165,69 @@ pub enum DownloadEvent {
     Progress(Vec<DownloadProgress>),
     /// Auto-download lifecycle events (displayed inline on search rows, not in download panel)
     AutoDownload(AutoDownloadEvent),
+    /// Updates for a search session shared with other users
+    SharedSearch(SharedSearchEvent),
+    /// A finished download is waiting for tag review before import
+    ImportReview(PendingImport),
+    /// One of the user's folders failed or recovered
+    FolderHealth(FolderHealthChange),
+    /// Downloads cleared from the panel in one tab, by item, to drop from
+    /// the others
+    Cleared(Vec<String>),
+}
+
+/// Change in a folder's mount health, pushed so the browser can notify.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FolderHealthChange {
+    pub folder_name: String,
+    /// What went wrong, `None` once the folder is usable again
+    pub problem: Option<String>,
+}
+
+/// Events fanned out to every participant of a shared search session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum SharedSearchEvent {
+    /// New or updated result groups from the owner's search
+    Results {
+        session_id: String,
+        groups: Vec<DownloadableGroup>,
+        state: SearchState,
+    },
+    /// The set of picked tracks changed
+    Selection {
+        session_id: String,
+        selected: Vec<String>,
+        by: String,
+    },
+    /// Someone joined or left
+    Participants {
+        session_id: String,
+        usernames: Vec<String>,
+    },
+    /// The owner ended the session
+    Closed { session_id: String },
+}
+
+impl SharedSearchEvent {
+    pub fn session_id(&self) -> &str {
+        match self {
+            Self::Results { session_id, .. }
+            | Self::Selection { session_id, .. }
+            | Self::Participants { session_id, .. }
+            | Self::Closed { session_id } => session_id,
+        }
+    }
+}
+
+/// Current state of a shared search session, returned when joining
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SharedSearchSnapshot {
+    pub session_id: String,
+    pub owner: String,
+    pub groups: Vec<DownloadableGroup>,
+    pub state: SearchState,
+    pub selected: Vec<String>,
+    pub participants: Vec<String>,
 }
 
 /// Events from the auto-download pipeline, sent over WebSocket
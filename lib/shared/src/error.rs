@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+/// What a server function failed on. Sent along with the error message so
+/// the UI can explain the failure and offer a retry where one can help.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorKind {
+    /// Not logged in, or the session expired
+    Auth,
+    /// Logged in, but not allowed to do this
+    Forbidden,
+    /// The request itself is wrong; sending it again won't help
+    Validation,
+    NotFound,
+    /// slskd failed or couldn't be reached
+    Slskd,
+    /// MusicBrainz failed, is rate limiting or couldn't be reached
+    MusicBrainz,
+    /// The importer (beets) failed
+    Importer,
+    /// The server couldn't be reached. Only set by the client.
+    Network,
+    #[default]
+    Internal,
+}
+
+impl ApiErrorKind {
+    /// HTTP status the error is sent with.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::Auth => 401,
+            Self::Forbidden => 403,
+            Self::Validation => 400,
+            Self::NotFound => 404,
+            Self::Slskd | Self::MusicBrainz => 502,
+            Self::Network => 503,
+            Self::Importer | Self::Internal => 500,
+        }
+    }
+
+    /// Kind implied by a bare HTTP status, for errors sent without one.
+    pub fn from_status(status: u16) -> Self {
+        match status {
+            401 => Self::Auth,
+            403 => Self::Forbidden,
+            400 | 422 => Self::Validation,
+            404 => Self::NotFound,
+            _ => Self::Internal,
+        }
+    }
+
+    /// Whether the same request may succeed if tried again.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Slskd | Self::MusicBrainz | Self::Network | Self::Internal
+        )
+    }
+}
+
+/// A failed server function call as the UI sees it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ApiError {
+    pub kind: ApiErrorKind,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(kind: ApiErrorKind, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+        }
+    }
+
+    /// Sentence to show the user, with the server's message where it says
+    /// more than a generic one would.
+    pub fn describe(&self) -> String {
+        match self.kind {
+            ApiErrorKind::Auth => "Session expired. Please log in again.".to_string(),
+            ApiErrorKind::Forbidden => "You don't have permission for this action.".to_string(),
+            ApiErrorKind::Validation => self.message.clone(),
+            ApiErrorKind::NotFound => "The requested item was not found.".to_string(),
+            ApiErrorKind::Slskd => {
+                "Soulseek (slskd) is not responding. Check that it is running.".to_string()
+            }
+            ApiErrorKind::MusicBrainz => {
+                "MusicBrainz is busy or unreachable. Try again in a moment.".to_string()
+            }
+            ApiErrorKind::Importer => format!("The import failed: {}", self.message),
+            ApiErrorKind::Network => {
+                "Could not reach the server. Check your connection.".to_string()
+            }
+            ApiErrorKind::Internal => "Something went wrong. Try again.".to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
@@ -0,0 +1,216 @@
+//! Spreadsheet-friendly exports of the library and the download history,
+//! and the wishlist file moved between instances.
+
+use serde::{Deserialize, Serialize};
+
+use crate::wishlist::LabelRelease;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn mime(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// A generated export, saved by the browser under `file_name`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportFile {
+    pub file_name: String,
+    pub mime: String,
+    pub content: String,
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// RFC 4180 CSV with a header row and CRLF line endings.
+pub fn to_csv<I>(headers: &[&str], rows: I) -> String
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let mut out = String::new();
+    let header: Vec<String> = headers.iter().map(|h| csv_field(h)).collect();
+    out.push_str(&header.join(","));
+    out.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push_str("\r\n");
+    }
+    out
+}
+
+/// Version of the wishlist file format written by this build.
+pub const WISHLIST_EXPORT_VERSION: u32 = 1;
+
+/// A wanted release as written to a wishlist file. The MusicBrainz ids
+/// are what other instances and tools like Lidarr go by.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportedRelease {
+    pub artist: String,
+    pub title: String,
+    #[serde(default)]
+    pub release_mbid: Option<String>,
+    #[serde(default)]
+    pub release_group_mbid: Option<String>,
+    /// YYYY, YYYY-MM or YYYY-MM-DD, when known
+    #[serde(default)]
+    pub date: Option<String>,
+    /// Where the entry came from, e.g. a chart URL
+    #[serde(default)]
+    pub source: Option<String>,
+    /// Unix timestamp it was added at
+    #[serde(default)]
+    pub added_at: Option<i64>,
+}
+
+impl ExportedRelease {
+    /// Same release: same MBID when both have one, otherwise same artist
+    /// and title, ignoring case.
+    pub fn same_release(&self, other: &ExportedRelease) -> bool {
+        match (&self.release_mbid, &other.release_mbid) {
+            (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+            _ => {
+                self.artist.trim().eq_ignore_ascii_case(other.artist.trim())
+                    && self.title.trim().eq_ignore_ascii_case(other.title.trim())
+            }
+        }
+    }
+}
+
+impl From<LabelRelease> for ExportedRelease {
+    fn from(release: LabelRelease) -> Self {
+        ExportedRelease {
+            artist: release.artist,
+            title: release.title,
+            release_mbid: Some(release.release_mbid),
+            release_group_mbid: Some(release.release_group_id).filter(|id| !id.is_empty()),
+            date: Some(release.date).filter(|d| !d.is_empty()),
+            source: None,
+            added_at: None,
+        }
+    }
+}
+
+impl ExportedRelease {
+    /// The release to queue for download. Only releases with an MBID can be.
+    pub fn to_label_release(&self) -> Option<LabelRelease> {
+        Some(LabelRelease {
+            release_mbid: self.release_mbid.clone()?,
+            release_group_id: self.release_group_mbid.clone().unwrap_or_default(),
+            title: self.title.clone(),
+            artist: self.artist.clone(),
+            date: self.date.clone().unwrap_or_default(),
+            primary_type: None,
+        })
+    }
+}
+
+/// A user's wishlist and the releases waiting in their download queue.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WishlistExport {
+    pub version: u32,
+    /// Unix timestamp
+    pub exported_at: i64,
+    #[serde(default)]
+    pub wishlist: Vec<ExportedRelease>,
+    #[serde(default)]
+    pub queue: Vec<ExportedRelease>,
+}
+
+/// The entries of `incoming` that are in neither `existing` nor earlier in
+/// `incoming`, in their order.
+pub fn new_releases(
+    existing: &[ExportedRelease],
+    incoming: Vec<ExportedRelease>,
+) -> Vec<ExportedRelease> {
+    let mut fresh: Vec<ExportedRelease> = Vec::new();
+    for release in incoming {
+        if release.artist.trim().is_empty() || release.title.trim().is_empty() {
+            continue;
+        }
+        let known = existing
+            .iter()
+            .chain(fresh.iter())
+            .any(|r| r.same_release(&release));
+        if !known {
+            fresh.push(release);
+        }
+    }
+    fresh
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_fields_that_need_it() {
+        let csv = to_csv(
+            &["artist", "title"],
+            vec![
+                vec!["Crosby, Stills & Nash".to_string(), "Plain".to_string()],
+                vec!["Björk".to_string(), "The \"Hit\"".to_string()],
+            ],
+        );
+        assert_eq!(
+            csv,
+            "artist,title\r\n\"Crosby, Stills & Nash\",Plain\r\nBjörk,\"The \"\"Hit\"\"\"\r\n"
+        );
+    }
+
+    fn release(artist: &str, title: &str, mbid: Option<&str>) -> ExportedRelease {
+        ExportedRelease {
+            artist: artist.to_string(),
+            title: title.to_string(),
+            release_mbid: mbid.map(String::from),
+            release_group_mbid: None,
+            date: None,
+            source: None,
+            added_at: None,
+        }
+    }
+
+    #[test]
+    fn merging_skips_releases_already_wanted() {
+        let existing = vec![
+            release("Portishead", "Dummy", Some("mbid-1")),
+            release("Burial", "Untrue", None),
+        ];
+        let incoming = vec![
+            // Same MBID, different spelling
+            release("portishead", "Dummy (Remastered)", Some("MBID-1")),
+            // Same name as one without an MBID
+            release("burial", "untrue", Some("mbid-2")),
+            // Same name as a wanted one, but another release
+            release("Portishead", "Dummy", Some("mbid-3")),
+            release("Massive Attack", "Mezzanine", Some("mbid-4")),
+            release("Massive Attack", "Mezzanine", Some("mbid-4")),
+            release("", "Untitled", None),
+        ];
+        let fresh = new_releases(&existing, incoming);
+        let mbids: Vec<_> = fresh.iter().map(|r| r.release_mbid.as_deref()).collect();
+        assert_eq!(mbids, vec![Some("mbid-3"), Some("mbid-4")]);
+    }
+}
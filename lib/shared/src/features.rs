@@ -0,0 +1,56 @@
+//! Runtime switches for experimental subsystems. Admins turn them on in
+//! settings; the server refuses requests to disabled subsystems and the UI
+//! hides their panels.
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+pub mod flags {
+    /// The SIM toggle on the search page, planning downloads without
+    /// queueing them.
+    pub const DRY_RUN: &str = "dry_run";
+}
+
+/// An experimental subsystem that can be switched on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeatureFlag {
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Every flag the settings page offers, in display order.
+pub const FEATURE_FLAGS: &[FeatureFlag] = &[FeatureFlag {
+    key: flags::DRY_RUN,
+    name: "Dry-run downloads",
+    description: "Show the SIM toggle on the search page to preview where files would be \
+                  saved without downloading them.",
+}];
+
+/// The flags that are turned on. Experimental subsystems are off until
+/// enabled, so a missing key means disabled.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureFlags {
+    #[serde(default)]
+    pub enabled: BTreeSet<String>,
+}
+
+impl FeatureFlags {
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.enabled.contains(key)
+    }
+
+    pub fn set(&mut self, key: &str, enabled: bool) {
+        if enabled {
+            self.enabled.insert(key.to_string());
+        } else {
+            self.enabled.remove(key);
+        }
+    }
+
+    /// Whether `key` names a flag in [`FEATURE_FLAGS`].
+    pub fn is_known(key: &str) -> bool {
+        FEATURE_FLAGS.iter().any(|f| f.key == key)
+    }
+}
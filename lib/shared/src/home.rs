@@ -0,0 +1,109 @@
+//! Aggregates for the dashboard home page.
+
+use serde::{Deserialize, Serialize};
+
+use crate::download::DownloadHistoryEntry;
+
+/// Files of one album imported together, as the dashboard lists them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RecentImport {
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub tracks: usize,
+    /// Unix timestamp of the latest file
+    pub finished_at: i64,
+    /// Where the latest file ended up in the library
+    pub imported_path: Option<String>,
+    /// Where the album's cover is served, when it is in the library
+    #[serde(default)]
+    pub cover_url: Option<String>,
+}
+
+/// Download history boiled down for the dashboard.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HomeSummary {
+    /// Files imported since the start of the period
+    pub imported: usize,
+    /// Files that failed since the start of the period
+    pub failed: usize,
+    /// Latest imports, newest first, one per album
+    pub recent_imports: Vec<RecentImport>,
+}
+
+impl HomeSummary {
+    /// Summarize `history` (newest first): counts of files finished since
+    /// `since`, and the last `limit` imported albums.
+    pub fn from_history(history: &[DownloadHistoryEntry], since: i64, limit: usize) -> Self {
+        let recent = history.iter().filter(|h| h.finished_at >= since);
+        let mut summary = HomeSummary {
+            imported: recent.clone().filter(|h| h.outcome == "imported").count(),
+            failed: recent.filter(|h| h.outcome == "failed").count(),
+            recent_imports: Vec::new(),
+        };
+
+        let imports = &mut summary.recent_imports;
+        for entry in history.iter().filter(|h| h.outcome == "imported") {
+            if let Some(import) = imports
+                .iter_mut()
+                .find(|r| r.artist == entry.artist && r.album == entry.album)
+            {
+                import.tracks += 1;
+            } else if imports.len() < limit {
+                imports.push(RecentImport {
+                    artist: entry.artist.clone(),
+                    album: entry.album.clone(),
+                    tracks: 1,
+                    finished_at: entry.finished_at,
+                    imported_path: entry.imported_path.clone(),
+                    cover_url: None,
+                });
+            }
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(album: &str, outcome: &str, finished_at: i64) -> DownloadHistoryEntry {
+        DownloadHistoryEntry {
+            id: String::new(),
+            item: format!("{album}.flac"),
+            artist: Some("Artist".to_string()),
+            album: Some(album.to_string()),
+            format: Some("FLAC".to_string()),
+            peer: "peer".to_string(),
+            size: 1,
+            outcome: outcome.to_string(),
+            error: None,
+            finished_at,
+            imported_path: None,
+            duplicate: false,
+            feedback: None,
+            download_id: None,
+            note: None,
+        }
+    }
+
+    #[test]
+    fn folds_tracks_of_an_album_into_one_import() {
+        let history = vec![
+            entry("B", "imported", 40),
+            entry("A", "failed", 30),
+            entry("A", "imported", 20),
+            entry("B", "imported", 10),
+            entry("C", "imported", 5),
+        ];
+        let summary = HomeSummary::from_history(&history, 15, 2);
+        assert_eq!(summary.imported, 2);
+        assert_eq!(summary.failed, 1);
+        let albums: Vec<_> = summary
+            .recent_imports
+            .iter()
+            .map(|r| (r.album.as_deref().unwrap(), r.tracks, r.finished_at))
+            .collect();
+        assert_eq!(albums, vec![("B", 2, 40), ("A", 1, 20)]);
+    }
+}
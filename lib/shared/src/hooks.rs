@@ -0,0 +1,258 @@
+//! Post-import hooks: a command or a webhook run after each successful
+//! import, told where the album landed.
+
+use serde::{Deserialize, Serialize};
+
+use crate::download::ImportSummary;
+
+/// Most output kept from a hook run, in bytes.
+pub const MAX_HOOK_OUTPUT: usize = 8 * 1024;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HookKind {
+    /// A shell command, given the album through environment variables only
+    #[default]
+    Command,
+    /// A URL the album is POSTed to as JSON
+    Webhook,
+}
+
+impl HookKind {
+    pub const ALL: [HookKind; 2] = [HookKind::Command, HookKind::Webhook];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookKind::Command => "command",
+            HookKind::Webhook => "webhook",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HookKind::Command => "Command",
+            HookKind::Webhook => "Webhook",
+        }
+    }
+}
+
+impl From<&str> for HookKind {
+    fn from(s: &str) -> Self {
+        match s {
+            "webhook" => HookKind::Webhook,
+            _ => HookKind::Command,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ImportHook {
+    /// Empty for a hook that hasn't been saved yet
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    pub kind: HookKind,
+    /// Command line or URL
+    pub target: String,
+    pub enabled: bool,
+    pub timeout_secs: u32,
+}
+
+impl Default for ImportHook {
+    fn default() -> Self {
+        Self {
+            id: String::new(),
+            name: String::new(),
+            kind: HookKind::Command,
+            target: String::new(),
+            enabled: true,
+            timeout_secs: Self::DEFAULT_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl ImportHook {
+    pub const DEFAULT_TIMEOUT_SECS: u32 = 30;
+    /// Longest a hook may run (ten minutes)
+    pub const MAX_TIMEOUT_SECS: u32 = 600;
+
+    /// Why the hook can't be saved, if it can't.
+    pub fn problem(&self) -> Option<String> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Some("Every hook needs a name".to_string());
+        }
+        if self.target.trim().is_empty() {
+            return Some(format!("{} has no {}", name, self.target_label()));
+        }
+        if self.kind == HookKind::Webhook
+            && !(self.target.starts_with("http://") || self.target.starts_with("https://"))
+        {
+            return Some(format!(
+                "{}: the URL must start with http:// or https://",
+                name
+            ));
+        }
+        if !(1..=Self::MAX_TIMEOUT_SECS).contains(&self.timeout_secs) {
+            return Some(format!(
+                "{}: the timeout must be between 1 and {} seconds",
+                name,
+                Self::MAX_TIMEOUT_SECS
+            ));
+        }
+        None
+    }
+
+    /// Why the hook can't be saved or run on a server that does or doesn't
+    /// allow command hooks, if it can't. Commands run as the server's user,
+    /// so they need the admin of the machine to opt in.
+    pub fn refused(&self, commands_allowed: bool) -> Option<String> {
+        (self.kind == HookKind::Command && !commands_allowed).then(|| {
+            format!(
+                "{}: command hooks are turned off on this server (ALLOW_COMMAND_HOOKS)",
+                self.name.trim()
+            )
+        })
+    }
+
+    pub fn target_label(&self) -> &'static str {
+        match self.kind {
+            HookKind::Command => "command",
+            HookKind::Webhook => "URL",
+        }
+    }
+}
+
+/// What a hook is told about the import.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HookContext {
+    /// Folder holding the imported files
+    pub album_path: String,
+    pub artist: String,
+    pub album: String,
+    /// Formats of the imported files, comma separated, e.g. "FLAC"
+    pub format: String,
+}
+
+impl HookContext {
+    /// Names of the environment variables a command hook gets, in the
+    /// order of `env`.
+    pub const ENV_NAMES: [&'static str; 4] = [
+        "SOULBEET_ALBUM_PATH",
+        "SOULBEET_ARTIST",
+        "SOULBEET_ALBUM",
+        "SOULBEET_FORMAT",
+    ];
+
+    pub fn from_summary(summary: &ImportSummary) -> Self {
+        let first = summary.files.first();
+        Self {
+            album_path: summary.destination.clone(),
+            artist: first.map(|f| f.artist.clone()).unwrap_or_default(),
+            album: first.map(|f| f.album.clone()).unwrap_or_default(),
+            format: summary.formats.join(","),
+        }
+    }
+
+    /// The environment of a command hook. Nothing else from the server's
+    /// environment is passed on but `PATH`.
+    pub fn env(&self) -> [(&'static str, String); 4] {
+        let [path, artist, album, format] = Self::ENV_NAMES;
+        [
+            (path, self.album_path.clone()),
+            (artist, self.artist.clone()),
+            (album, self.album.clone()),
+            (format, self.format.clone()),
+        ]
+    }
+}
+
+/// `output` cut to `MAX_HOOK_OUTPUT` bytes on a character boundary, keeping
+/// the end, where errors usually are.
+pub fn truncate_output(output: &str) -> String {
+    let output = output.trim();
+    if output.len() <= MAX_HOOK_OUTPUT {
+        return output.to_string();
+    }
+    let mut start = output.len() - MAX_HOOK_OUTPUT;
+    while !output.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("...{}", &output[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::ImportedFile;
+
+    #[test]
+    fn context_comes_from_the_first_imported_file() {
+        let summary = ImportSummary {
+            destination: "/music/Low/Things We Lost in the Fire".into(),
+            formats: vec!["FLAC".into(), "MP3".into()],
+            files: vec![ImportedFile {
+                path: "/music/Low/Things We Lost in the Fire/01.flac".into(),
+                artist: "Low".into(),
+                album: "Things We Lost in the Fire".into(),
+                title: "Sunflower".into(),
+                duplicate: false,
+            }],
+            ..Default::default()
+        };
+        let ctx = HookContext::from_summary(&summary);
+        assert_eq!(ctx.artist, "Low");
+        assert_eq!(ctx.format, "FLAC,MP3");
+        assert_eq!(
+            ctx.env()[0],
+            (
+                "SOULBEET_ALBUM_PATH",
+                "/music/Low/Things We Lost in the Fire".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn long_output_keeps_its_end() {
+        let output = format!("{}é{}", "a".repeat(MAX_HOOK_OUTPUT), "error: disk full");
+        let kept = truncate_output(&output);
+        assert!(kept.starts_with("..."));
+        assert!(kept.ends_with("error: disk full"));
+        assert!(kept.len() <= MAX_HOOK_OUTPUT + 3);
+    }
+
+    #[test]
+    fn webhooks_need_an_http_url() {
+        let hook = ImportHook {
+            name: "Plex".into(),
+            kind: HookKind::Webhook,
+            target: "plex.local/refresh".into(),
+            ..Default::default()
+        };
+        assert!(hook.problem().is_some());
+        let hook = ImportHook {
+            target: "http://plex.local/refresh".into(),
+            ..hook
+        };
+        assert_eq!(hook.problem(), None);
+    }
+
+    #[test]
+    fn command_hooks_need_the_opt_in() {
+        let command = ImportHook {
+            name: "Refresh".into(),
+            kind: HookKind::Command,
+            target: "touch /tmp/refresh".into(),
+            ..Default::default()
+        };
+        assert!(command.refused(false).is_some());
+        assert_eq!(command.refused(true), None);
+
+        let webhook = ImportHook {
+            kind: HookKind::Webhook,
+            target: "http://plex.local/refresh".into(),
+            ..command
+        };
+        assert_eq!(webhook.refused(false), None);
+    }
+}
@@ -1,7 +1,22 @@
+pub mod activity;
+pub mod analytics;
+pub mod calendar;
 pub mod download;
+pub mod error;
+pub mod export;
+pub mod features;
+pub mod home;
+pub mod hooks;
 pub mod library;
+pub mod logging;
+pub mod matching;
 pub mod metadata;
 pub mod navidrome;
+pub mod quality;
 pub mod recommendation;
+pub mod routing;
+pub mod security;
 pub mod slskd;
 pub mod system;
+pub mod updates;
+pub mod wishlist;
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::metadata::{Album, Track};
+
 /// A track from a beets library
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LibraryTrack {
@@ -26,3 +28,59 @@ pub struct DuplicateReport {
     pub total_duplicate_tracks: usize,
     pub libraries_scanned: Vec<String>,
 }
+
+/// A track of a library folder, as listed for exports
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryIndexEntry {
+    pub artist: String,
+    pub album: String,
+    pub title: String,
+    /// Audio format as reported by the importer, e.g. "FLAC"
+    pub format: String,
+    pub year: Option<u32>,
+    /// When the track was added to the library
+    pub added: String,
+    pub path: String,
+}
+
+/// An album of a library folder: the tracks sharing a directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LibraryAlbum {
+    pub artist: String,
+    pub album: String,
+    /// Directory holding the tracks
+    pub path: String,
+    pub track_count: usize,
+}
+
+/// A library album compared with its release tracklist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlbumCompletion {
+    /// The release the album was matched to
+    pub album: Album,
+    /// Directory holding the tracks already owned
+    pub path: String,
+    pub owned: usize,
+    /// Tracks of the release not in the directory
+    pub missing: Vec<Track>,
+    /// Titles of every track of the release, in order
+    #[serde(default)]
+    pub tracklist: Vec<String>,
+}
+
+/// Size and formats of a library folder, and where the importer's index
+/// and the files on disk disagree
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LibraryHealth {
+    pub tracks: usize,
+    pub albums: usize,
+    /// Bytes taken by the indexed tracks still on disk
+    pub size: u64,
+    /// Tracks per format, most common first
+    pub formats: Vec<(String, usize)>,
+    /// Indexed tracks whose file is gone
+    pub missing: Vec<String>,
+    /// Audio files in the folder the index doesn't list. Empty when the
+    /// importer keeps no index.
+    pub untracked: Vec<String>,
+}
@@ -0,0 +1,152 @@
+//! Where the server's logs go and how verbose each part of it is.
+
+use serde::{Deserialize, Serialize};
+
+/// Levels a module can be set to, quietest last.
+pub const LEVELS: [&str; 6] = ["trace", "debug", "info", "warn", "error", "off"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    /// One human readable line per event
+    #[default]
+    Text,
+    /// One JSON object per line, for log collectors
+    Json,
+}
+
+impl LogFormat {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogFormat::Text => "text",
+            LogFormat::Json => "json",
+        }
+    }
+}
+
+/// When the log file is rotated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogRotation {
+    Hourly,
+    #[default]
+    Daily,
+    /// A single file that keeps growing
+    Never,
+}
+
+impl LogRotation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogRotation::Hourly => "hourly",
+            LogRotation::Daily => "daily",
+            LogRotation::Never => "never",
+        }
+    }
+}
+
+impl From<&str> for LogRotation {
+    fn from(s: &str) -> Self {
+        match s {
+            "hourly" => LogRotation::Hourly,
+            "never" => LogRotation::Never,
+            _ => LogRotation::Daily,
+        }
+    }
+}
+
+/// A module logged at its own level, e.g. `soulbeet::import` at debug.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogLevelOverride {
+    /// Module path the level applies to, with everything below it
+    pub target: String,
+    pub level: String,
+}
+
+impl LogLevelOverride {
+    /// Why the override can't be applied, if it can't.
+    pub fn problem(&self) -> Option<String> {
+        let target = self.target.trim();
+        if target.is_empty() {
+            return Some("Every level override needs a module".to_string());
+        }
+        if !target
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '-')
+        {
+            return Some(format!("{} is not a module path", target));
+        }
+        if !LEVELS.contains(&self.level.as_str()) {
+            return Some(format!("{}: unknown level {}", target, self.level));
+        }
+        None
+    }
+}
+
+/// The filter the server logs with: `base`, then each override, later ones
+/// winning for the same module.
+pub fn filter_directives(base: &str, overrides: &[LogLevelOverride]) -> String {
+    let mut directives: Vec<String> = base
+        .split(',')
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect();
+    for o in overrides {
+        directives.push(format!("{}={}", o.target.trim(), o.level));
+    }
+    directives.join(",")
+}
+
+/// Logging as the server is currently set up.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LoggingStatus {
+    /// Filter from the environment, before overrides
+    pub base_filter: String,
+    pub format: LogFormat,
+    /// Directory log files are written to, if any
+    pub file_dir: Option<String>,
+    pub rotation: LogRotation,
+    /// Rotated files kept. 0 keeps them all.
+    pub max_files: usize,
+    /// Overrides saved in the settings
+    pub overrides: Vec<LogLevelOverride>,
+    /// What the server filters with right now
+    pub active_filter: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overrides_come_after_the_base_filter() {
+        let overrides = vec![LogLevelOverride {
+            target: " soulbeet::import ".into(),
+            level: "debug".into(),
+        }];
+        assert_eq!(
+            filter_directives("info, sqlx=warn,", &overrides),
+            "info,sqlx=warn,soulbeet::import=debug"
+        );
+    }
+
+    #[test]
+    fn overrides_need_a_module_and_a_known_level() {
+        let ok = LogLevelOverride {
+            target: "api::server_fns".into(),
+            level: "trace".into(),
+        };
+        assert_eq!(ok.problem(), None);
+        let bad_level = LogLevelOverride {
+            level: "verbose".into(),
+            ..ok.clone()
+        };
+        assert!(bad_level.problem().is_some());
+        let injected = LogLevelOverride {
+            target: "api=trace,hyper".into(),
+            ..ok
+        };
+        assert!(injected.problem().is_some());
+    }
+}
@@ -1,6 +1,15 @@
+//! Scoring of a shared file's path against the release searched for.
+//! Pure text processing, so the client can score results the same way the
+//! server ranked them.
+
+use crate::download::{DownloadableGroup, DownloadableItem};
+use crate::slskd::MatchResult;
 use regex::Regex;
-use shared::slskd::MatchResult;
-use std::{collections::HashSet, path::Path, sync::LazyLock};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::LazyLock,
+};
 
 static RE_NON_WORD: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[^\w\s]").unwrap());
 static RE_LEAD_TRACK_FIXED: LazyLock<Regex> =
@@ -64,7 +73,8 @@ fn dice_sim(a: &CleanedText, b: &CleanedText) -> f64 {
     }
 }
 
-fn clean_name(name: &str) -> String {
+/// Strip leading track numbers, trailing `[tags]` and trailing years.
+pub fn clean_name(name: &str) -> String {
     let name = name.replace('_', " ");
     let mut cleaned = RE_LEAD_TRACK_FIXED.replace(&name, "").to_string();
     cleaned = RE_TRAIL_BRACKET.replace(&cleaned, "").to_string();
@@ -72,6 +82,13 @@ fn clean_name(name: &str) -> String {
     cleaned.trim().to_string()
 }
 
+/// Track number prefix of a file name, e.g. "03" or vinyl side "A2".
+pub fn leading_track_number(name: &str) -> Option<String> {
+    RE_LEAD_TRACK_FIXED
+        .captures(name)
+        .map(|caps| caps[1].to_string())
+}
+
 fn extract_track_title(stem: &str) -> String {
     let stem_clean = clean_name(stem);
     if let Some(pos) = stem_clean.rfind(" - ") {
@@ -178,6 +195,7 @@ fn score_track(stem: &CleanedText, expected_tracks: &[CleanedText]) -> (f64, Cle
         .unwrap_or((0.0, CleanedText::new("")))
 }
 
+/// How well `filename` matches the searched artist, album and tracks.
 pub fn rank_match(
     filename: &str,
     searched_artist: Option<&str>,
@@ -262,3 +280,167 @@ pub fn rank_match(
         total_score,
     }
 }
+
+/// The same track as `item` from the best of `groups` whose peer is not in
+/// `refused`, to queue when `item`'s peer turns the download down. Groups
+/// are expected best first, as searches return them.
+pub fn substitute_for<'a>(
+    item: &DownloadableItem,
+    groups: &'a [DownloadableGroup],
+    refused: &[String],
+) -> Option<&'a DownloadableItem> {
+    let title = clean_name(&item.title).to_lowercase();
+    if title.is_empty() {
+        return None;
+    }
+    groups
+        .iter()
+        .filter(|g| g.source != item.source && !refused.contains(&g.source))
+        .find_map(|g| {
+            g.items
+                .iter()
+                .find(|i| clean_name(&i.title).to_lowercase() == title)
+        })
+}
+
+/// What users said of the files they got from a peer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PeerReputation {
+    pub good: u32,
+    pub bad: u32,
+}
+
+impl PeerReputation {
+    /// Each complaint more than praises costs this much of a result's score.
+    const PENALTY_PER_COMPLAINT: f64 = 0.1;
+    /// Results are never pushed below this share of their score.
+    const MIN_WEIGHT: f64 = 0.5;
+
+    /// Factor the score of the peer's results is multiplied by.
+    pub fn weight(&self) -> f64 {
+        let complaints = self.bad.saturating_sub(self.good);
+        (1.0 - f64::from(complaints) * Self::PENALTY_PER_COMPLAINT).max(Self::MIN_WEIGHT)
+    }
+}
+
+/// Lower the score of results from peers users complained about, keeping
+/// `groups` best first.
+pub fn apply_reputation(
+    groups: &mut [DownloadableGroup],
+    reputations: &HashMap<String, PeerReputation>,
+) {
+    if reputations.is_empty() {
+        return;
+    }
+    for group in groups.iter_mut() {
+        if let Some(reputation) = reputations.get(&group.source) {
+            group.score *= reputation.weight();
+        }
+    }
+    groups.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn well_named_file_scores_high() {
+        let result = rank_match(
+            "@@music\\Radiohead\\Kid A (2000) [FLAC]\\02 - Kid A.flac",
+            Some("Radiohead"),
+            Some("Kid A"),
+            &["Everything in Its Right Place", "Kid A"],
+        );
+        assert_eq!(result.matched_track, "Kid A");
+        assert!(result.total_score > 0.9, "{:?}", result);
+    }
+
+    #[test]
+    fn unrelated_file_scores_low() {
+        let result = rank_match(
+            "@@music\\Various\\Summer Hits\\07 - Dance Anthem.mp3",
+            Some("Radiohead"),
+            Some("Kid A"),
+            &["Everything in Its Right Place", "Kid A"],
+        );
+        assert!(result.total_score < 0.3, "{:?}", result);
+    }
+
+    #[test]
+    fn strips_track_numbers_tags_and_years() {
+        assert_eq!(clean_name("03. Idioteque [24bit]"), "Idioteque");
+        assert_eq!(clean_name("Kid A (2000)"), "Kid A");
+        assert_eq!(
+            leading_track_number("A2 - Side Track").as_deref(),
+            Some("A2")
+        );
+    }
+
+    fn item(source: &str, title: &str) -> DownloadableItem {
+        DownloadableItem {
+            id: format!("{}\\{}.flac", source, title),
+            source: source.to_string(),
+            title: title.to_string(),
+            artist: "Radiohead".to_string(),
+            album: "Kid A".to_string(),
+            size: None,
+            duration: None,
+            quality: "FLAC".to_string(),
+            quality_score: 1.0,
+            backend_data: None,
+        }
+    }
+
+    fn group(source: &str, titles: &[&str]) -> DownloadableGroup {
+        DownloadableGroup {
+            source: source.to_string(),
+            group_id: source.to_string(),
+            title: "Kid A".to_string(),
+            artist: None,
+            item_count: titles.len(),
+            total_size: 0,
+            items: titles.iter().map(|t| item(source, t)).collect(),
+            quality: "FLAC".to_string(),
+            score: 1.0,
+            completeness: None,
+        }
+    }
+
+    #[test]
+    fn substitute_comes_from_the_next_willing_peer() {
+        let groups = vec![
+            group("alice", &["Kid A", "Idioteque"]),
+            group("bob", &["Idioteque"]),
+            group("carol", &["Kid A", "idioteque"]),
+        ];
+        let refused = item("alice", "Idioteque");
+        assert_eq!(
+            substitute_for(&refused, &groups, &[]).map(|i| i.source.as_str()),
+            Some("bob")
+        );
+        assert_eq!(
+            substitute_for(&refused, &groups, &["bob".to_string()]).map(|i| i.source.as_str()),
+            Some("carol")
+        );
+        assert!(substitute_for(&item("alice", "Optimistic"), &groups, &[]).is_none());
+    }
+
+    #[test]
+    fn complaints_push_a_peer_down() {
+        let mut groups = vec![group("alice", &["Kid A"]), group("bob", &["Kid A"])];
+        groups[1].score = 0.95;
+        let reputations = HashMap::from([
+            ("alice".to_string(), PeerReputation { good: 1, bad: 3 }),
+            ("bob".to_string(), PeerReputation { good: 2, bad: 0 }),
+        ]);
+        apply_reputation(&mut groups, &reputations);
+        assert_eq!(groups[0].source, "bob");
+        assert!((groups[1].score - 0.8).abs() < 1e-9);
+        assert_eq!(PeerReputation { good: 0, bad: 50 }.weight(), 0.5);
+    }
+}
@@ -7,6 +7,7 @@ pub enum Provider {
     #[default]
     MusicBrainz,
     LastFm,
+    Spotify,
 }
 
 impl std::fmt::Display for Provider {
@@ -14,6 +15,7 @@ impl std::fmt::Display for Provider {
         match self {
             Provider::MusicBrainz => write!(f, "musicbrainz"),
             Provider::LastFm => write!(f, "lastfm"),
+            Provider::Spotify => write!(f, "spotify"),
         }
     }
 }
@@ -25,6 +27,7 @@ impl std::str::FromStr for Provider {
         match s.to_lowercase().as_str() {
             "musicbrainz" => Ok(Provider::MusicBrainz),
             "lastfm" => Ok(Provider::LastFm),
+            "spotify" => Ok(Provider::Spotify),
             _ => Err(format!("Unknown provider: {}", s)),
         }
     }
@@ -68,6 +71,9 @@ pub struct Track {
     /// The MusicBrainz release ID for the album, if known.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub release_mbid: Option<String>,
+    /// Label, country and type of the album the track belongs to.
+    #[serde(default, skip_serializing_if = "ReleaseDetails::is_empty")]
+    pub details: ReleaseDetails,
 }
 
 /// An album from a metadata provider.
@@ -87,6 +93,65 @@ pub struct Album {
     /// URL to the album cover image.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cover_url: Option<String>,
+    /// Label, country, track count and type, to tell releases of the same
+    /// name apart.
+    #[serde(default, skip_serializing_if = "ReleaseDetails::is_empty")]
+    pub details: ReleaseDetails,
+}
+
+/// What sets a release apart from others with the same title, as far as
+/// the provider knows it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReleaseDetails {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Country of release (ISO 3166-1 code, or "XW" for worldwide).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub track_count: Option<u32>,
+    /// Album, EP, Single...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub primary_type: Option<String>,
+    /// Genres the provider files the release under.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub genres: Vec<String>,
+}
+
+impl ReleaseDetails {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The known details in one line, e.g. "EP · 6 tracks · GB · Warp".
+    pub fn summary(&self) -> String {
+        let tracks = self.track_count.map(|n| match n {
+            1 => "1 track".to_string(),
+            n => format!("{} tracks", n),
+        });
+        [
+            self.primary_type.clone(),
+            tracks,
+            self.country.clone(),
+            self.label.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
+
+/// Id prefix of albums put together from the tags of downloaded files
+/// rather than fetched from a provider.
+pub const TAG_DERIVED_PREFIX: &str = "tags:";
+
+impl Album {
+    /// Whether the album was read from file tags, as happens when
+    /// MusicBrainz can't be reached.
+    pub fn is_tag_derived(&self) -> bool {
+        self.id.starts_with(TAG_DERIVED_PREFIX)
+    }
 }
 
 /// An album with its full track listing.
@@ -95,3 +160,52 @@ pub struct AlbumWithTracks {
     pub album: Album,
     pub tracks: Vec<Track>,
 }
+
+/// Identifier a release or recording can be looked up by directly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentifierKind {
+    /// UPC/EAN printed on the release
+    Barcode,
+    /// Label catalog number, e.g. "WARPCD92"
+    CatalogNumber,
+    /// International Standard Recording Code of a single recording
+    Isrc,
+}
+
+impl IdentifierKind {
+    /// Detect an identifier search. Explicit prefixes (`barcode:`, `upc:`,
+    /// `ean:`, `catno:`, `isrc:`) always win; bare 8-14 digit numbers are
+    /// taken as barcodes and 12-character `CCXXXYYNNNNN` codes as ISRCs.
+    /// Returns the kind and the identifier without prefix.
+    pub fn detect(query: &str) -> Option<(IdentifierKind, String)> {
+        let query = query.trim();
+        if let Some((prefix, value)) = query.split_once(':') {
+            let kind = match prefix.trim().to_lowercase().as_str() {
+                "barcode" | "upc" | "ean" => Some(IdentifierKind::Barcode),
+                "catno" | "cat" => Some(IdentifierKind::CatalogNumber),
+                "isrc" => Some(IdentifierKind::Isrc),
+                _ => None,
+            };
+            let value = value.trim();
+            if let Some(kind) = kind.filter(|_| !value.is_empty()) {
+                return Some((kind, value.to_string()));
+            }
+        }
+
+        let compact: String = query.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+        if (8..=14).contains(&compact.len()) && compact.chars().all(|c| c.is_ascii_digit()) {
+            return Some((IdentifierKind::Barcode, compact));
+        }
+
+        let bytes = compact.as_bytes();
+        let is_isrc = bytes.len() == 12
+            && bytes[..2].iter().all(u8::is_ascii_alphabetic)
+            && bytes[2..5].iter().all(u8::is_ascii_alphanumeric)
+            && bytes[5..].iter().all(u8::is_ascii_digit);
+        if is_isrc {
+            return Some((IdentifierKind::Isrc, compact.to_uppercase()));
+        }
+        None
+    }
+}
@@ -0,0 +1,580 @@
+use serde::{Deserialize, Serialize};
+
+use crate::download::{DownloadableGroup, DownloadableItem};
+use crate::slskd::{self, AlbumResult};
+
+/// A user's preferences for picking between sources.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct QualityProfile {
+    #[serde(default)]
+    pub diversity: DiversityOptions,
+    #[serde(default)]
+    pub search_filters: SearchFilters,
+    #[serde(default)]
+    pub ranking: RankingWeights,
+}
+
+impl QualityProfile {
+    /// Parse the stored JSON form, falling back to defaults when unset or invalid.
+    pub fn from_json(json: Option<&str>) -> Self {
+        json.and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+/// Which kinds of candidates are guaranteed a place at the top of the
+/// results, ahead of higher-scoring near-duplicates.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DiversityOptions {
+    /// Best candidate of each format tier (lossless, high and low bitrate lossy)
+    pub per_format: bool,
+    /// Best candidate of each completeness bucket (complete, most, partial)
+    pub per_completeness: bool,
+}
+
+impl Default for DiversityOptions {
+    fn default() -> Self {
+        Self {
+            per_format: true,
+            per_completeness: true,
+        }
+    }
+}
+
+impl DiversityOptions {
+    pub fn is_enabled(&self) -> bool {
+        self.per_format || self.per_completeness
+    }
+}
+
+/// How much audio quality, completeness and peer speed count when
+/// re-ranking results already on screen. Each weight is between 0 and 1;
+/// all zero keeps the order from the search.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct RankingWeights {
+    #[serde(default)]
+    pub quality: f64,
+    #[serde(default)]
+    pub completeness: f64,
+    #[serde(default)]
+    pub speed: f64,
+}
+
+impl RankingWeights {
+    pub fn is_neutral(&self) -> bool {
+        self.total() <= 0.0
+    }
+
+    fn total(&self) -> f64 {
+        self.quality.max(0.0) + self.completeness.max(0.0) + self.speed.max(0.0)
+    }
+
+    /// The candidate's search score averaged with its weighted quality,
+    /// completeness and speed, so the match itself still counts.
+    pub fn score<T: Candidate>(&self, candidate: &T) -> f64 {
+        let total = self.total();
+        if total <= 0.0 {
+            return candidate.score();
+        }
+        let weighted = (self.quality.max(0.0) * candidate.quality()
+            + self.completeness.max(0.0) * candidate.completeness().unwrap_or(1.0)
+            + self.speed.max(0.0) * candidate.speed())
+            / total;
+        (candidate.score() + weighted) / 2.0
+    }
+}
+
+/// Responses dropped before ranking. Minimum file count is passed on to
+/// slskd; the others are applied to what it returns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct SearchFilters {
+    /// Lossy files below this bitrate (kbps) are dropped. Lossless files and
+    /// files without a reported bitrate are kept.
+    #[serde(default)]
+    pub min_bitrate: Option<u32>,
+    /// Peers offering fewer files than this for the search are ignored
+    #[serde(default)]
+    pub min_response_files: Option<u32>,
+    /// Only keep peers with a free upload slot
+    #[serde(default)]
+    pub free_slot_only: bool,
+    /// Only keep lossless files
+    #[serde(default)]
+    pub lossless_only: bool,
+}
+
+impl SearchFilters {
+    /// Bitrates offered as a minimum, in kbps.
+    pub const BITRATE_CHOICES: [u32; 4] = [128, 192, 256, 320];
+
+    pub fn keeps_file(&self, extension: &str, bitrate: Option<i32>) -> bool {
+        if self.lossless_only {
+            return FormatTier::of(extension, bitrate) == FormatTier::Lossless;
+        }
+        let Some(min) = self.min_bitrate else {
+            return true;
+        };
+        FormatTier::of(extension, bitrate) == FormatTier::Lossless
+            || bitrate.is_none_or(|br| i64::from(br) >= i64::from(min))
+    }
+
+    pub fn keeps_peer(&self, file_count: usize, has_free_upload_slot: bool) -> bool {
+        if self.free_slot_only && !has_free_upload_slot {
+            return false;
+        }
+        self.min_response_files
+            .is_none_or(|min| file_count >= min as usize)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum FormatTier {
+    Lossless,
+    HighBitrate,
+    LowBitrate,
+}
+
+impl FormatTier {
+    /// Tier of a file extension, with the bitrate deciding between lossy tiers.
+    pub fn of(extension: &str, bitrate: Option<i32>) -> Self {
+        match extension.to_lowercase().as_str() {
+            "flac" | "wav" | "alac" | "aiff" | "ape" | "wv" => FormatTier::Lossless,
+            _ if bitrate.is_some_and(|br| br >= 256) => FormatTier::HighBitrate,
+            _ => FormatTier::LowBitrate,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CompletenessBucket {
+    Complete,
+    /// At least half of the expected tracks
+    Most,
+    Partial,
+}
+
+impl CompletenessBucket {
+    pub fn of(completeness: f64) -> Self {
+        if completeness >= 1.0 {
+            CompletenessBucket::Complete
+        } else if completeness >= 0.5 {
+            CompletenessBucket::Most
+        } else {
+            CompletenessBucket::Partial
+        }
+    }
+}
+
+/// A ranked search candidate that can be diversified.
+pub trait Candidate {
+    fn score(&self) -> f64;
+    fn format_tier(&self) -> FormatTier;
+    /// Share of the expected tracks present, when known
+    fn completeness(&self) -> Option<f64>;
+
+    /// Audio quality from 0 to 1
+    fn quality(&self) -> f64 {
+        match self.format_tier() {
+            FormatTier::Lossless => 1.0,
+            FormatTier::HighBitrate => 0.7,
+            FormatTier::LowBitrate => 0.4,
+        }
+    }
+
+    /// How soon the source is likely to deliver, from 0 to 1
+    fn speed(&self) -> f64 {
+        0.5
+    }
+}
+
+/// Upload speed (bytes/s) at which a peer counts as fully fast.
+const FAST_PEER_SPEED: f64 = 5.0 * 1024.0 * 1024.0;
+
+/// Speed of a Soulseek peer: its upload speed, discounted by the queue
+/// ahead when no upload slot is free.
+fn peer_speed(upload_speed: i32, has_free_upload_slot: bool, queue_length: i32) -> f64 {
+    let speed = (f64::from(upload_speed.max(0)) / FAST_PEER_SPEED).min(1.0);
+    if has_free_upload_slot {
+        speed
+    } else {
+        speed / (1.0 + f64::from(queue_length.max(0)) / 10.0)
+    }
+}
+
+impl Candidate for AlbumResult {
+    fn score(&self) -> f64 {
+        self.score
+    }
+
+    fn format_tier(&self) -> FormatTier {
+        let bitrate = self
+            .tracks
+            .iter()
+            .filter_map(|t| t.base.bitrate)
+            .min();
+        FormatTier::of(&self.dominant_quality, bitrate)
+    }
+
+    fn completeness(&self) -> Option<f64> {
+        Some(self.completeness)
+    }
+
+    fn speed(&self) -> f64 {
+        peer_speed(
+            self.upload_speed,
+            self.has_free_upload_slot,
+            self.queue_length,
+        )
+    }
+}
+
+impl Candidate for DownloadableGroup {
+    fn score(&self) -> f64 {
+        self.score
+    }
+
+    fn format_tier(&self) -> FormatTier {
+        // Items don't carry a bitrate; estimate it from size and duration
+        let bitrate = self
+            .items
+            .iter()
+            .filter_map(|i| match (i.size, i.duration) {
+                (Some(size), Some(duration)) if duration > 0 => {
+                    Some((size * 8 / 1000 / duration as u64) as i32)
+                }
+                _ => None,
+            })
+            .min();
+        FormatTier::of(&self.quality, bitrate)
+    }
+
+    fn completeness(&self) -> Option<f64> {
+        self.completeness
+    }
+
+    fn quality(&self) -> f64 {
+        if self.items.is_empty() {
+            return 0.0;
+        }
+        self.items.iter().map(|i| i.quality_score).sum::<f64>() / self.items.len() as f64
+    }
+
+    fn speed(&self) -> f64 {
+        // Only slskd items carry the peer's figures
+        self.items
+            .first()
+            .and_then(|i| i.backend_data.as_deref())
+            .and_then(|data| serde_json::from_str::<slskd::SearchResult>(data).ok())
+            .map(|r| peer_speed(r.upload_speed, r.has_free_upload_slot, r.queue_length))
+            .unwrap_or(0.5)
+    }
+}
+
+/// Reorder candidates so the best of every format tier and completeness
+/// bucket comes first (by score among themselves), followed by the rest by
+/// score. Without this, ten near-identical MP3 rips can push the first FLAC
+/// off the visible list.
+pub fn diversify<T: Candidate>(candidates: Vec<T>, options: &DiversityOptions) -> Vec<T> {
+    diversify_by(candidates, options, T::score)
+}
+
+/// [`diversify`] with the scores given by `weights`, for re-ranking in the
+/// browser without another search.
+pub fn rerank<T: Candidate>(
+    candidates: Vec<T>,
+    options: &DiversityOptions,
+    weights: &RankingWeights,
+) -> Vec<T> {
+    diversify_by(candidates, options, |c| weights.score(c))
+}
+
+fn diversify_by<T: Candidate>(
+    mut candidates: Vec<T>,
+    options: &DiversityOptions,
+    score: impl Fn(&T) -> f64,
+) -> Vec<T> {
+    candidates.sort_by(|a, b| {
+        score(b)
+            .partial_cmp(&score(a))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    if !options.is_enabled() {
+        return candidates;
+    }
+
+    let mut seen_tiers = Vec::new();
+    let mut seen_buckets = Vec::new();
+    let mut leaders = Vec::new();
+    for (index, candidate) in candidates.iter().enumerate() {
+        let mut is_leader = false;
+        if options.per_format {
+            let tier = candidate.format_tier();
+            if !seen_tiers.contains(&tier) {
+                seen_tiers.push(tier);
+                is_leader = true;
+            }
+        }
+        if options.per_completeness {
+            if let Some(bucket) = candidate.completeness().map(CompletenessBucket::of) {
+                if !seen_buckets.contains(&bucket) {
+                    seen_buckets.push(bucket);
+                    is_leader = true;
+                }
+            }
+        }
+        if is_leader {
+            leaders.push(index);
+        }
+    }
+
+    let (head, tail): (Vec<_>, Vec<_>) = candidates
+        .into_iter()
+        .enumerate()
+        .partition(|(index, _)| leaders.contains(index));
+    head.into_iter()
+        .chain(tail)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+/// What a selection of files adds up to, shown before it is queued.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SelectionSummary {
+    pub files: usize,
+    pub peers: usize,
+    /// Bytes, counting only the files whose size is known
+    pub total_size: u64,
+    /// Seconds the slowest peer needs at the speed it advertises, when
+    /// every peer advertises one
+    pub estimated_secs: Option<u64>,
+    /// Formats of the files, e.g. "FLAC", in order of appearance
+    pub formats: Vec<String>,
+    /// Lossy files known to be below the high bitrate tier
+    pub low_bitrate_files: usize,
+}
+
+impl SelectionSummary {
+    pub fn of(items: &[DownloadableItem]) -> Self {
+        let mut summary = SelectionSummary {
+            files: items.len(),
+            ..Default::default()
+        };
+        // Bytes and advertised speed per peer
+        let mut peers: Vec<(&str, u64, Option<i32>)> = Vec::new();
+        for item in items {
+            let result = item
+                .backend_data
+                .as_deref()
+                .and_then(|d| serde_json::from_str::<slskd::SearchResult>(d).ok());
+            let bitrate = result.as_ref().and_then(|r| r.bitrate);
+            let speed = result.as_ref().map(|r| r.upload_speed);
+            let size = item.size.unwrap_or(0);
+            summary.total_size += size;
+
+            let format = item.quality.to_uppercase();
+            if !summary.formats.contains(&format) {
+                summary.formats.push(format);
+            }
+            if bitrate.is_some() && FormatTier::of(&item.quality, bitrate) == FormatTier::LowBitrate
+            {
+                summary.low_bitrate_files += 1;
+            }
+
+            match peers.iter_mut().find(|(peer, _, _)| *peer == item.source) {
+                Some((_, bytes, _)) => *bytes += size,
+                None => peers.push((&item.source, size, speed)),
+            }
+        }
+        summary.peers = peers.len();
+        summary.estimated_secs = peers
+            .iter()
+            .map(|(_, bytes, speed)| match speed {
+                Some(speed) if *speed > 0 => Some(bytes / *speed as u64),
+                _ => None,
+            })
+            .try_fold(0, |slowest, secs| Some(slowest.max(secs?)));
+        summary
+    }
+
+    /// Quality problems worth a second look before downloading.
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.formats.len() > 1 {
+            warnings.push(format!("Mixed formats: {}", self.formats.join(", ")));
+        }
+        match self.low_bitrate_files {
+            0 => {}
+            1 => warnings.push("1 file is below 256 kbps".to_string()),
+            n => warnings.push(format!("{} files are below 256 kbps", n)),
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fake {
+        score: f64,
+        tier: FormatTier,
+        completeness: f64,
+    }
+
+    impl Candidate for Fake {
+        fn score(&self) -> f64 {
+            self.score
+        }
+        fn format_tier(&self) -> FormatTier {
+            self.tier
+        }
+        fn completeness(&self) -> Option<f64> {
+            Some(self.completeness)
+        }
+    }
+
+    fn fake(score: f64, tier: FormatTier, completeness: f64) -> Fake {
+        Fake {
+            score,
+            tier,
+            completeness,
+        }
+    }
+
+    #[test]
+    fn lifts_best_of_each_tier() {
+        let mut candidates: Vec<Fake> = (0..10)
+            .map(|i| fake(0.9 - i as f64 * 0.01, FormatTier::HighBitrate, 1.0))
+            .collect();
+        candidates.push(fake(0.5, FormatTier::Lossless, 1.0));
+
+        let ordered = diversify(candidates, &DiversityOptions::default());
+        assert_eq!(ordered[0].tier, FormatTier::HighBitrate);
+        assert_eq!(ordered[1].tier, FormatTier::Lossless);
+        assert_eq!(ordered.len(), 11);
+    }
+
+    #[test]
+    fn disabled_is_plain_score_order() {
+        let candidates = vec![
+            fake(0.4, FormatTier::Lossless, 1.0),
+            fake(0.8, FormatTier::LowBitrate, 1.0),
+            fake(0.6, FormatTier::LowBitrate, 0.3),
+        ];
+        let options = DiversityOptions {
+            per_format: false,
+            per_completeness: false,
+        };
+        let scores: Vec<f64> = diversify(candidates, &options)
+            .iter()
+            .map(|c| c.score)
+            .collect();
+        assert_eq!(scores, vec![0.8, 0.6, 0.4]);
+    }
+
+    #[test]
+    fn lifts_best_partial_candidate() {
+        let candidates = vec![
+            fake(0.9, FormatTier::Lossless, 1.0),
+            fake(0.8, FormatTier::Lossless, 1.0),
+            fake(0.7, FormatTier::Lossless, 0.6),
+        ];
+        let options = DiversityOptions {
+            per_format: false,
+            per_completeness: true,
+        };
+        let ordered = diversify(candidates, &options);
+        assert_eq!(ordered[1].score, 0.7);
+    }
+
+    #[test]
+    fn weights_reorder_by_quality() {
+        let candidates = vec![
+            fake(0.8, FormatTier::LowBitrate, 1.0),
+            fake(0.7, FormatTier::Lossless, 1.0),
+        ];
+        let options = DiversityOptions {
+            per_format: false,
+            per_completeness: false,
+        };
+        let weights = RankingWeights {
+            quality: 1.0,
+            ..Default::default()
+        };
+        let ordered = rerank(candidates, &options, &weights);
+        assert_eq!(ordered[0].tier, FormatTier::Lossless);
+        assert!(RankingWeights::default().is_neutral());
+    }
+
+    #[test]
+    fn min_bitrate_spares_lossless_files() {
+        let filters = SearchFilters {
+            min_bitrate: Some(256),
+            ..Default::default()
+        };
+        assert!(!filters.keeps_file("mp3", Some(192)));
+        assert!(filters.keeps_file("mp3", Some(320)));
+        assert!(filters.keeps_file("flac", Some(0)));
+        assert!(filters.keeps_file("ogg", None));
+    }
+
+    fn item(
+        peer: &str,
+        quality: &str,
+        bitrate: Option<i32>,
+        speed: i32,
+        size: u64,
+    ) -> DownloadableItem {
+        let result = slskd::SearchResult {
+            username: peer.to_string(),
+            filename: format!("track.{quality}"),
+            size: size as i64,
+            bitrate,
+            duration: None,
+            sample_rate: None,
+            bit_depth: None,
+            has_free_upload_slot: true,
+            upload_speed: speed,
+            queue_length: 0,
+        };
+        DownloadableItem {
+            id: result.filename.clone(),
+            source: peer.to_string(),
+            title: "Track".to_string(),
+            artist: "Artist".to_string(),
+            album: "Album".to_string(),
+            size: Some(size),
+            duration: None,
+            quality: quality.to_string(),
+            quality_score: 0.5,
+            backend_data: serde_json::to_string(&result).ok(),
+        }
+    }
+
+    #[test]
+    fn summarizes_a_selection() {
+        let items = vec![
+            item("a", "flac", None, 1000, 30_000),
+            item("a", "flac", None, 1000, 30_000),
+            item("b", "mp3", Some(192), 500, 5_000),
+        ];
+        let summary = SelectionSummary::of(&items);
+        assert_eq!(summary.files, 3);
+        assert_eq!(summary.peers, 2);
+        assert_eq!(summary.total_size, 65_000);
+        assert_eq!(summary.estimated_secs, Some(60));
+        assert_eq!(summary.warnings().len(), 2);
+
+        let unknown_speed = vec![
+            item("a", "flac", None, 1000, 10),
+            item("c", "flac", None, 0, 10),
+        ];
+        let summary = SelectionSummary::of(&unknown_speed);
+        assert_eq!(summary.estimated_secs, None);
+        assert!(summary.warnings().is_empty());
+    }
+}
@@ -474,3 +474,17 @@ impl EngineReport {
         out
     }
 }
+
+/// Something the user listens to that isn't in their library.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MissingMusic {
+    pub artist: String,
+    /// Album title, or track title when `is_album` is false
+    pub title: String,
+    pub is_album: bool,
+    /// Listens over the user's history; zero for recommendations
+    pub listen_count: u64,
+    /// Suggested by ListenBrainz rather than listened to
+    pub recommended: bool,
+    pub mbid: Option<String>,
+}
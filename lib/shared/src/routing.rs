@@ -0,0 +1,182 @@
+//! Per-user rules sending releases of a genre to their own folder, with
+//! their own quality requirements, e.g. classical into `/music/classical`
+//! in FLAC only.
+
+use serde::{Deserialize, Serialize};
+
+use crate::quality::SearchFilters;
+
+/// Where downloads of a genre go, and what quality they must have.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingRule {
+    /// Matched without regard to case against the release's genres; "jazz"
+    /// also catches "acid jazz".
+    pub genre: String,
+    /// Folder the release is downloaded into
+    pub folder_id: String,
+    /// Only lossless files are downloaded
+    #[serde(default)]
+    pub lossless_only: bool,
+    /// Replaces the minimum bitrate of the quality profile, in kbps
+    #[serde(default)]
+    pub min_bitrate: Option<u32>,
+}
+
+impl RoutingRule {
+    /// The first of `genres` this rule applies to.
+    pub fn matching_genre<'a>(&self, genres: &'a [String]) -> Option<&'a String> {
+        let wanted = self.genre.trim().to_lowercase();
+        if wanted.is_empty() {
+            return None;
+        }
+        genres.iter().find(|g| g.to_lowercase().contains(&wanted))
+    }
+
+    /// `filters` tightened by the rule's quality requirements.
+    pub fn apply(&self, filters: SearchFilters) -> SearchFilters {
+        SearchFilters {
+            lossless_only: filters.lossless_only || self.lossless_only,
+            min_bitrate: self.min_bitrate.or(filters.min_bitrate),
+            ..filters
+        }
+    }
+
+    /// The quality requirement in a few words, e.g. "lossless only".
+    pub fn quality_label(&self) -> String {
+        match (self.lossless_only, self.min_bitrate) {
+            (true, _) => "lossless only".to_string(),
+            (false, Some(kbps)) => format!("{} kbps or better", kbps),
+            (false, None) => "any quality".to_string(),
+        }
+    }
+}
+
+/// A user's rules, tried in order; the first matching one applies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingRules {
+    pub rules: Vec<RoutingRule>,
+}
+
+/// How the rules were evaluated for one release.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RoutingTrace {
+    /// Genres the release was found to have
+    pub genres: Vec<String>,
+    /// Index of the rule that applies, if any
+    pub matched: Option<usize>,
+    /// One line per rule tried, in order
+    pub steps: Vec<String>,
+}
+
+impl RoutingTrace {
+    pub fn rule<'a>(&self, rules: &'a RoutingRules) -> Option<&'a RoutingRule> {
+        self.matched.and_then(|i| rules.rules.get(i))
+    }
+}
+
+impl RoutingRules {
+    /// Parse the stored JSON form, falling back to no rules when unset or invalid.
+    pub fn from_json(json: Option<&str>) -> Self {
+        json.and_then(|j| serde_json::from_str(j).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Find the rule for a release with `genres`, noting why each rule
+    /// before it was passed over.
+    pub fn evaluate(&self, genres: &[String]) -> RoutingTrace {
+        let mut trace = RoutingTrace {
+            genres: genres.to_vec(),
+            ..Default::default()
+        };
+        if genres.is_empty() {
+            trace
+                .steps
+                .push("No genre known for this release, no rule applies".to_string());
+            return trace;
+        }
+        for (i, rule) in self.rules.iter().enumerate() {
+            match rule.matching_genre(genres) {
+                Some(genre) => {
+                    trace.steps.push(format!(
+                        "Rule {} \"{}\" matches genre \"{}\": {}",
+                        i + 1,
+                        rule.genre,
+                        genre,
+                        rule.quality_label()
+                    ));
+                    trace.matched = Some(i);
+                    return trace;
+                }
+                None => {
+                    trace
+                        .steps
+                        .push(format!("Rule {} \"{}\" does not match", i + 1, rule.genre))
+                }
+            }
+        }
+        trace
+            .steps
+            .push("No rule matches, using the chosen folder".to_string());
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(genre: &str, folder_id: &str) -> RoutingRule {
+        RoutingRule {
+            genre: genre.to_string(),
+            folder_id: folder_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let rules = RoutingRules {
+            rules: vec![
+                rule("podcast", "pods"),
+                rule("Classical", "classical"),
+                rule("baroque", "baroque"),
+            ],
+        };
+        let genres = vec!["baroque".to_string(), "classical".to_string()];
+        let trace = rules.evaluate(&genres);
+        assert_eq!(trace.matched, Some(1));
+        assert_eq!(trace.steps.len(), 2);
+        assert_eq!(trace.rule(&rules).unwrap().folder_id, "classical");
+
+        let trace = rules.evaluate(&[]);
+        assert_eq!(trace.matched, None);
+        assert_eq!(rules.evaluate(&["jazz".to_string()]).matched, None);
+    }
+
+    #[test]
+    fn rule_tightens_the_filters() {
+        let flac_only = RoutingRule {
+            lossless_only: true,
+            ..rule("classical", "c")
+        };
+        let filters = SearchFilters {
+            min_bitrate: Some(192),
+            ..Default::default()
+        };
+        let routed = flac_only.apply(filters);
+        assert!(routed.lossless_only);
+        assert!(!routed.keeps_file("mp3", Some(320)));
+        assert!(routed.keeps_file("flac", None));
+
+        let any = rule("podcast", "p").apply(filters);
+        assert_eq!(any, filters);
+    }
+}
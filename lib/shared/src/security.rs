@@ -0,0 +1,183 @@
+//! Rules for local passwords and for locking accounts after repeated failed
+//! sign-ins, and the roles that decide what a user may do. Admins edit them
+//! in settings; the server applies them to every password a user picks, to
+//! every login and to every request.
+
+use serde::{Deserialize, Serialize};
+
+/// Well-known passwords from public breach lists, lowercase.
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+/// What a new password must look like. Passwords set before a change keep
+/// working; the policy applies the next time one is chosen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasswordPolicy {
+    pub min_length: usize,
+    /// Both lowercase and uppercase letters
+    pub require_mixed_case: bool,
+    pub require_digit: bool,
+    /// A character that is neither a letter nor a digit
+    pub require_symbol: bool,
+    /// Refuse passwords found in the embedded list of breached passwords
+    pub reject_common: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            require_mixed_case: false,
+            require_digit: false,
+            require_symbol: false,
+            reject_common: true,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// Longest minimum length the settings accept.
+    pub const MAX_MIN_LENGTH: usize = 128;
+
+    /// Check `password` against the policy, explaining the first rule it
+    /// breaks.
+    pub fn check(&self, password: &str) -> Result<(), String> {
+        if password.chars().count() < self.min_length {
+            return Err(format!(
+                "Passwords must be at least {} characters long",
+                self.min_length
+            ));
+        }
+        let has_lower = password.chars().any(char::is_lowercase);
+        let has_upper = password.chars().any(char::is_uppercase);
+        if self.require_mixed_case && !(has_lower && has_upper) {
+            return Err("Passwords must mix lowercase and uppercase letters".to_string());
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            return Err("Passwords must contain a digit".to_string());
+        }
+        if self.require_symbol && password.chars().all(char::is_alphanumeric) {
+            return Err("Passwords must contain a symbol".to_string());
+        }
+        if self.reject_common && is_common_password(password) {
+            return Err(
+                "This password appears in lists of breached passwords. Choose another one."
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn is_common_password(password: &str) -> bool {
+    let password = password.to_lowercase();
+    COMMON_PASSWORDS.lines().any(|p| p == password)
+}
+
+/// Locking an account after too many wrong passwords in a row.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockoutPolicy {
+    /// Failed sign-ins in a row that lock the account; 0 never locks
+    pub max_attempts: u32,
+    /// How long a locked account stays locked
+    pub lockout_minutes: u32,
+}
+
+impl Default for LockoutPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 0,
+            lockout_minutes: 15,
+        }
+    }
+}
+
+impl LockoutPolicy {
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 0
+    }
+}
+
+/// Both policies, saved together.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityPolicy {
+    #[serde(default)]
+    pub password: PasswordPolicy,
+    #[serde(default)]
+    pub lockout: LockoutPolicy,
+}
+
+/// What a user may do. Members have full access; viewers can search
+/// metadata and browse the library and history, but not download, import
+/// or change settings.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    #[default]
+    Member,
+    Viewer,
+}
+
+impl UserRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Viewer => "viewer",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Member => "Member",
+            Self::Viewer => "Viewer",
+        }
+    }
+
+    pub fn is_viewer(&self) -> bool {
+        matches!(self, Self::Viewer)
+    }
+}
+
+impl From<&str> for UserRole {
+    fn from(s: &str) -> Self {
+        match s {
+            "viewer" => Self::Viewer,
+            _ => Self::Member,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explains_the_first_broken_rule() {
+        let policy = PasswordPolicy {
+            min_length: 10,
+            require_mixed_case: true,
+            require_digit: true,
+            require_symbol: true,
+            reject_common: true,
+        };
+        assert!(policy.check("short").unwrap_err().contains("10 characters"));
+        assert!(policy
+            .check("alllowercase")
+            .unwrap_err()
+            .contains("uppercase"));
+        assert!(policy.check("MixedCaseOnly").unwrap_err().contains("digit"));
+        assert!(policy.check("MixedCase123").unwrap_err().contains("symbol"));
+        assert!(policy.check("Mixed-Case-123").is_ok());
+    }
+
+    #[test]
+    fn rejects_breached_passwords_regardless_of_case() {
+        let policy = PasswordPolicy::default();
+        assert!(policy.check("Password123").is_err());
+        assert!(policy.check("correct horse battery").is_ok());
+        let lenient = PasswordPolicy {
+            reject_common: false,
+            ..policy
+        };
+        assert!(lenient.check("Password123").is_ok());
+    }
+}
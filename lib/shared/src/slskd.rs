@@ -22,6 +22,73 @@ pub struct DownloadResponse {
     pub error: Option<String>,
 }
 
+impl DownloadResponse {
+    /// Why the file could not be queued, read from the error slskd gave.
+    pub fn reason(&self) -> Option<EnqueueFailure> {
+        self.error.as_deref().map(EnqueueFailure::classify)
+    }
+}
+
+/// Why slskd or the peer refused to queue a download.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnqueueFailure {
+    /// The peer no longer shares the file
+    NotShared,
+    /// The peer's queue limit for us is reached ("too many files")
+    QueueFull,
+    UserOffline,
+    /// The peer banned us or does not let us download
+    Denied,
+    /// The peer or slskd did not answer in time
+    Unreachable,
+    /// Anything slskd says that is not recognized
+    Other,
+}
+
+impl EnqueueFailure {
+    /// Classify an error body or message from slskd.
+    pub fn classify(error: &str) -> Self {
+        let error = error.to_lowercase();
+        let has = |words: &[&str]| words.iter().any(|w| error.contains(w));
+        if has(&["not shared", "file not found", "no such file"]) {
+            Self::NotShared
+        } else if has(&[
+            "too many files",
+            "too many megabytes",
+            "queue full",
+            "queue limit",
+        ]) {
+            Self::QueueFull
+        } else if has(&["offline"]) {
+            Self::UserOffline
+        } else if has(&["banned", "denied", "not allowed", "forbidden"]) {
+            Self::Denied
+        } else if has(&["timed out", "timeout", "unreachable", "connection refused"]) {
+            Self::Unreachable
+        } else {
+            Self::Other
+        }
+    }
+
+    /// Whether asking again may work. A peer that is gone, refuses us or no
+    /// longer has the file gives the same answer on every try.
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Unreachable | Self::Other)
+    }
+
+    /// What to tell the user, `None` when the raw error says more.
+    pub fn message(&self) -> Option<&'static str> {
+        match self {
+            Self::NotShared => Some("The peer no longer shares this file"),
+            Self::QueueFull => Some("The peer's upload queue for you is full, try again later"),
+            Self::UserOffline => Some("The peer is offline"),
+            Self::Denied => Some("The peer does not let you download from them"),
+            Self::Unreachable => Some("The peer could not be reached"),
+            Self::Other => None,
+        }
+    }
+}
+
 /// Download states ordered by display priority (active first, errors last)
 #[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
 pub enum DownloadState {
@@ -105,10 +172,39 @@ impl FileEntry {
         self.state.clone()
     }
 
+    /// Whether slskd is done with the transfer, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.state.iter().any(|s| {
+            matches!(
+                s,
+                DownloadState::Downloaded
+                    | DownloadState::Errored
+                    | DownloadState::TimedOut
+                    | DownloadState::Aborted
+                    | DownloadState::Cancelled
+                    | DownloadState::Rejected
+            )
+        })
+    }
+
+    /// When the transfer ended, or was requested if slskd has no end time.
+    /// slskd omits the UTC offset, so timestamps without one are read as UTC.
+    pub fn finished_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let raw = self.ended_at.as_deref().unwrap_or(&self.requested_at);
+        chrono::DateTime::parse_from_rfc3339(raw)
+            .map(|dt| dt.with_timezone(&chrono::Utc))
+            .or_else(|_| {
+                chrono::NaiveDateTime::parse_from_str(raw, "%Y-%m-%dT%H:%M:%S%.f")
+                    .map(|naive| naive.and_utc())
+            })
+            .ok()
+    }
+
     /// Create a new FileEntry from a DownloadResponse with a specified state.
     ///
     /// This is the primary factory method for creating FileEntry objects,
     /// reducing code duplication in the download module.
+    #[cfg(feature = "server")]
     pub fn from_download_response(
         response: &DownloadResponse,
         state: DownloadState,
@@ -138,6 +234,7 @@ impl FileEntry {
     }
 
     /// Create a queued FileEntry from a DownloadResponse.
+    #[cfg(feature = "server")]
     pub fn queued(response: &DownloadResponse) -> Self {
         let mut entry = Self::from_download_response(
             response,
@@ -149,6 +246,7 @@ impl FileEntry {
     }
 
     /// Create an errored FileEntry from a DownloadResponse.
+    #[cfg(feature = "server")]
     pub fn errored(response: &DownloadResponse) -> Self {
         Self::from_download_response(
             response,
@@ -165,6 +263,7 @@ impl FileEntry {
     }
 
     /// Create a timeout error entry from an existing FileEntry.
+    #[cfg(feature = "server")]
     pub fn as_timeout(&self) -> Self {
         Self {
             id: self.id.clone(),
@@ -372,7 +471,7 @@ impl<'de> Deserialize<'de> for FlattenedFiles {
     }
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MatchResult {
     pub guessed_artist: String,
     pub guessed_album: String,
@@ -485,6 +584,9 @@ pub struct AlbumResult {
     pub upload_speed: i32,
     pub queue_length: i32,
     pub score: f64,
+    /// Share of the expected tracks found, from 0 to 1
+    #[serde(default)]
+    pub completeness: f64,
 }
 
 impl AlbumResult {
@@ -560,6 +662,7 @@ impl From<AlbumResult> for crate::download::DownloadableGroup {
             items: album.tracks.into_iter().map(Into::into).collect(),
             quality: album.dominant_quality,
             score: album.score,
+            completeness: Some(album.completeness),
         }
     }
 }
@@ -601,6 +704,11 @@ impl From<FileEntry> for crate::download::DownloadProgress {
             backend: Some("slskd".into()),
             batch_id: None,
             batch_label: None,
+            tag_derived: false,
+            import_summary: None,
+            imported_file: None,
+            planned_path: None,
+            target_imports: Vec::new(),
         }
     }
 }
@@ -634,12 +742,14 @@ impl From<DownloadState> for crate::download::DownloadState {
 
 impl From<DownloadResponse> for crate::download::QueuedDownload {
     fn from(resp: DownloadResponse) -> Self {
+        let friendly = resp.reason().and_then(|r| r.message());
         Self {
             id: resp.filename.clone(),
             source: resp.username,
             item: resp.filename,
             size: resp.size,
-            error: resp.error,
+            error: friendly.map(str::to_string).or(resp.error),
+            substituted: None,
         }
     }
 }
@@ -718,6 +828,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn finished_at_reads_timestamps_without_offset() {
+        let mut entry: FileEntry = serde_json::from_value(json!({
+            "id": "890f943c-02e1-4d45-af76-d55e3d855684",
+            "username": "peer",
+            "direction": "Download",
+            "filename": "shared\\Artist\\Album\\01. Track.flac",
+            "size": 1024,
+            "state": "Completed, Succeeded",
+            "stateDescription": "",
+            "requestedAt": "2026-07-19T05:11:22Z",
+            "endedAt": "2026-07-19T05:20:01.1965396",
+            "bytesTransferred": 1024,
+            "bytesRemaining": 0,
+            "percentComplete": 100.0
+        }))
+        .expect("FileEntry should deserialize");
+        assert!(entry.is_finished());
+        assert_eq!(entry.finished_at().map(|t| t.timestamp()), Some(1784438401));
+
+        entry.ended_at = None;
+        assert_eq!(entry.finished_at().map(|t| t.timestamp()), Some(1784437882));
+    }
+
     #[test]
     fn completed_states_map_to_their_outcome() {
         assert_eq!(mapped(json!("Completed, Succeeded")), DS::Completed);
@@ -757,4 +891,22 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn enqueue_errors_are_classified() {
+        let reason = |e: &str| EnqueueFailure::classify(e);
+        assert_eq!(reason("File not shared."), EnqueueFailure::NotShared);
+        assert_eq!(reason("Too many files"), EnqueueFailure::QueueFull);
+        assert_eq!(
+            reason("User 'peer' is offline"),
+            EnqueueFailure::UserOffline
+        );
+        assert_eq!(
+            reason("Failed after 3 retries: API error: 500 - Operation timed out"),
+            EnqueueFailure::Unreachable
+        );
+        assert_eq!(reason("Download failed"), EnqueueFailure::Other);
+        assert!(!reason("Banned").is_retryable());
+        assert!(reason("Download failed").message().is_none());
+    }
 }
@@ -65,11 +65,61 @@ impl From<String> for NavidromeStatus {
     }
 }
 
+/// State of the circuit breaker in front of a backend's API.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BreakerState {
+    #[default]
+    Closed,
+    /// Requests are rejected without being sent until the cooldown ends
+    Open { retry_in_secs: u64 },
+    /// Cooldown over, the next request probes whether the backend is back
+    HalfOpen,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct SystemHealth {
     pub downloader_online: bool,
+    /// Circuit breaker in front of the download backend
+    #[serde(default)]
+    pub downloader_breaker: BreakerState,
     pub beets_ready: bool,
     pub navidrome_online: bool,
+    /// The user's folders whose mount is currently unusable
+    #[serde(default)]
+    pub folder_alerts: Vec<FolderAlert>,
+    #[serde(default)]
+    pub download_pause: DownloadPause,
+    #[serde(default)]
+    pub musicbrainz: MusicBrainzStatus,
+}
+
+/// Where MusicBrainz requests go and the contact sent in their user agent.
+/// Unset fields fall back to musicbrainz.org and the project page.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
+pub struct MusicBrainzConfig {
+    /// Base URL of a self-hosted mirror, e.g. `http://mb.lan:5000`
+    pub mirror_url: Option<String>,
+    /// Email or URL MusicBrainz can reach the operator at
+    pub contact: Option<String>,
+}
+
+/// MusicBrainz as the server currently talks to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MusicBrainzStatus {
+    pub endpoint: String,
+    pub user_agent: String,
+    /// Mirrors are not rate limited
+    pub mirror: bool,
+    pub reachable: bool,
+}
+
+/// Instance-wide switch holding back new downloads, e.g. during a backup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DownloadPause {
+    pub paused: bool,
+    /// Downloads requested since, queued once the pause ends
+    pub deferred: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -84,3 +134,155 @@ pub struct AvailableBackends {
     pub download: Vec<BackendInfo>,
     pub importer: Vec<BackendInfo>,
 }
+
+/// Disk and permission status of a library folder on the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct FolderDiagnostics {
+    pub folder_id: String,
+    pub exists: bool,
+    /// A probe file could be created and removed in the folder.
+    pub writable: bool,
+    pub free_bytes: Option<u64>,
+    pub total_bytes: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl FolderDiagnostics {
+    /// Human readable free space, e.g. "12.4 GB free".
+    pub fn free_space_label(&self) -> Option<String> {
+        const GB: f64 = 1024.0 * 1024.0 * 1024.0;
+        const MB: f64 = 1024.0 * 1024.0;
+        self.free_bytes.map(|bytes| {
+            let bytes = bytes as f64;
+            if bytes >= GB {
+                format!("{:.1} GB free", bytes / GB)
+            } else {
+                format!("{:.0} MB free", bytes / MB)
+            }
+        })
+    }
+
+    /// True when an import into this folder is expected to fail.
+    pub fn has_problem(&self) -> bool {
+        !self.exists || !self.writable
+    }
+}
+
+/// Why a folder's mount can't take imports right now.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum MountProblem {
+    /// Stale NFS handle or a disconnected network share
+    Stale,
+    /// Remounted read-only, usually after an I/O error
+    ReadOnly,
+    /// The mount didn't answer in time
+    Unresponsive,
+}
+
+impl MountProblem {
+    pub fn describe(&self) -> &'static str {
+        match self {
+            MountProblem::Stale => "mount is stale or disconnected",
+            MountProblem::ReadOnly => "mount went read-only",
+            MountProblem::Unresponsive => "mount is not responding",
+        }
+    }
+}
+
+/// A library folder whose mount stopped working. Imports into it are
+/// paused until it comes back.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FolderAlert {
+    pub folder_id: String,
+    pub folder_name: String,
+    pub path: String,
+    pub problem: MountProblem,
+    /// Unix timestamp of when the problem was first seen
+    pub since: i64,
+}
+
+/// Schedule and last outcome of the job that prunes finished transfers
+/// from the download backend.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct TransferCleanupStatus {
+    pub enabled: bool,
+    /// Finished transfers younger than this are kept. 0 removes them all.
+    pub retention_hours: u32,
+    pub interval_hours: u32,
+    /// Unix timestamp of the last run, if any since the server started.
+    pub last_run: Option<i64>,
+    pub last_removed: Option<usize>,
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub path: String,
+}
+
+/// One level of the server-side folder browser.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct DirectoryListing {
+    /// The listed directory, or `None` when showing the allowed roots.
+    pub path: Option<String>,
+    /// Where "up" leads. `None` while at the top level or at a root.
+    pub parent: Option<String>,
+    pub entries: Vec<DirectoryEntry>,
+}
+
+/// A panic caught on the server.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PanicReport {
+    /// Unix timestamp
+    pub at: i64,
+    pub message: String,
+    /// Source file and line
+    pub location: String,
+    pub thread: String,
+    /// What was being done, e.g. the request or the download
+    pub context: Option<String>,
+    /// Only captured with RUST_BACKTRACE set
+    pub backtrace: Option<String>,
+}
+
+/// A download monitor that kept dying and was given up on, failing the
+/// downloads it was still watching.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MonitorFailure {
+    /// Unix timestamp
+    pub at: i64,
+    pub username: String,
+    /// Album or file the monitor was watching
+    pub label: String,
+    pub restarts: u32,
+    /// Why it died the last time
+    pub reason: String,
+    /// Downloads marked failed
+    pub failed: usize,
+}
+
+/// Sub-path the app is served under behind a reverse proxy, as `/soulbeet`:
+/// one leading slash, no trailing one. Empty when served at the root.
+pub fn normalize_base_path(path: &str) -> String {
+    let path = path.trim().trim_matches('/');
+    if path.is_empty() {
+        String::new()
+    } else {
+        format!("/{}", path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_path_is_normalized() {
+        assert_eq!(normalize_base_path(""), "");
+        assert_eq!(normalize_base_path(" / "), "");
+        assert_eq!(normalize_base_path("soulbeet"), "/soulbeet");
+        assert_eq!(normalize_base_path("/soulbeet/"), "/soulbeet");
+        assert_eq!(normalize_base_path("/apps/soulbeet"), "/apps/soulbeet");
+    }
+}
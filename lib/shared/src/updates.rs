@@ -0,0 +1,245 @@
+//! Compact form of the download updates sent over the WebSocket.
+//!
+//! Progress of every active download is pushed every couple of seconds,
+//! and between two pushes usually only its transfer moved. The encoder
+//! keeps what it already sent on a connection and sends a download whole
+//! only the first time or when more than its transfer changed; the decoder
+//! on the other end rebuilds the full events. Frames are sent as CBOR.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::download::{DownloadEvent, DownloadProgress};
+
+/// Version of the frames below. Version 1 is plain JSON `DownloadEvent`s,
+/// still served to clients loaded before version 2.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// One message on the binary downloads socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum UpdateFrame {
+    /// First frame of a connection: the version the server speaks
+    Hello { version: u32 },
+    /// Downloads that changed since the last frame about them
+    Progress(Vec<ProgressDelta>),
+    /// Any other event, as is
+    Event(DownloadEvent),
+}
+
+/// Change to one download, keyed by its item like the downloads panel.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ProgressDelta {
+    /// A download not sent before, or changed beyond its transfer
+    Full(Box<DownloadProgress>),
+    /// A download already sent whose transfer moved on
+    Transfer {
+        item: String,
+        transferred: u64,
+        percent: f64,
+        speed: f64,
+    },
+}
+
+/// Turns the events of one connection into frames.
+#[derive(Debug, Default)]
+pub struct DeltaEncoder {
+    sent: HashMap<String, DownloadProgress>,
+}
+
+impl DeltaEncoder {
+    /// Frame for `event`, `None` when the client already has all of it.
+    pub fn encode(&mut self, event: DownloadEvent) -> Option<UpdateFrame> {
+        let downloads = match event {
+            DownloadEvent::Progress(downloads) => downloads,
+            DownloadEvent::Cleared(ref items) => {
+                for item in items {
+                    self.sent.remove(item);
+                }
+                return Some(UpdateFrame::Event(event));
+            }
+            event => return Some(UpdateFrame::Event(event)),
+        };
+        let deltas: Vec<ProgressDelta> = downloads
+            .into_iter()
+            .filter_map(|d| self.delta(d))
+            .collect();
+        (!deltas.is_empty()).then_some(UpdateFrame::Progress(deltas))
+    }
+
+    fn delta(&mut self, download: DownloadProgress) -> Option<ProgressDelta> {
+        let delta = match self.sent.get(&download.item) {
+            Some(prev) if *prev == download => return None,
+            Some(prev) if only_transfer_moved(prev, &download) => ProgressDelta::Transfer {
+                item: download.item.clone(),
+                transferred: download.transferred,
+                percent: download.percent,
+                speed: download.speed,
+            },
+            _ => ProgressDelta::Full(Box::new(download.clone())),
+        };
+        self.sent.insert(download.item.clone(), download);
+        Some(delta)
+    }
+}
+
+fn only_transfer_moved(prev: &DownloadProgress, next: &DownloadProgress) -> bool {
+    let moved = DownloadProgress {
+        transferred: next.transferred,
+        percent: next.percent,
+        speed: next.speed,
+        ..prev.clone()
+    };
+    moved == *next
+}
+
+/// Rebuilds the events from the frames of one connection.
+#[derive(Debug, Default)]
+pub struct DeltaDecoder {
+    known: HashMap<String, DownloadProgress>,
+}
+
+impl DeltaDecoder {
+    /// The event carried by `frame`, if any. Fails on a server speaking a
+    /// newer version, which means this client is out of date.
+    pub fn decode(&mut self, frame: UpdateFrame) -> Result<Option<DownloadEvent>, String> {
+        match frame {
+            UpdateFrame::Hello { version } if version > PROTOCOL_VERSION => Err(format!(
+                "Server speaks version {} of the updates protocol, this page {}",
+                version, PROTOCOL_VERSION
+            )),
+            UpdateFrame::Hello { .. } => {
+                self.known.clear();
+                Ok(None)
+            }
+            UpdateFrame::Event(DownloadEvent::Cleared(items)) => {
+                for item in &items {
+                    self.known.remove(item);
+                }
+                Ok(Some(DownloadEvent::Cleared(items)))
+            }
+            UpdateFrame::Event(event) => Ok(Some(event)),
+            UpdateFrame::Progress(deltas) => {
+                let downloads: Vec<DownloadProgress> =
+                    deltas.into_iter().filter_map(|d| self.apply(d)).collect();
+                Ok((!downloads.is_empty()).then_some(DownloadEvent::Progress(downloads)))
+            }
+        }
+    }
+
+    fn apply(&mut self, delta: ProgressDelta) -> Option<DownloadProgress> {
+        match delta {
+            ProgressDelta::Full(download) => {
+                let download = *download;
+                self.known.insert(download.item.clone(), download.clone());
+                Some(download)
+            }
+            ProgressDelta::Transfer {
+                item,
+                transferred,
+                percent,
+                speed,
+            } => {
+                // Only sent for downloads sent whole before on this connection
+                let download = self.known.get_mut(&item)?;
+                download.transferred = transferred;
+                download.percent = percent;
+                download.speed = speed;
+                Some(download.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::download::DownloadState;
+
+    fn progress(item: &str, transferred: u64, state: DownloadState) -> DownloadProgress {
+        DownloadProgress {
+            id: format!("id-{item}"),
+            source: "user".to_string(),
+            item: item.to_string(),
+            size: 1000,
+            transferred,
+            state,
+            percent: transferred as f64 / 10.0,
+            speed: 50.0,
+            error: None,
+            backend: None,
+            batch_id: None,
+            batch_label: None,
+            tag_derived: false,
+            import_summary: None,
+            imported_file: None,
+            planned_path: None,
+            target_imports: Vec::new(),
+        }
+    }
+
+    fn decoded(decoder: &mut DeltaDecoder, frame: Option<UpdateFrame>) -> Vec<DownloadProgress> {
+        match decoder.decode(frame.expect("a frame")) {
+            Ok(Some(DownloadEvent::Progress(downloads))) => downloads,
+            other => panic!("expected progress, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn transfers_are_sent_as_deltas_and_rebuilt() {
+        let mut encoder = DeltaEncoder::default();
+        let mut decoder = DeltaDecoder::default();
+
+        let first = vec![progress("a.flac", 100, DownloadState::InProgress)];
+        let frame = encoder.encode(DownloadEvent::Progress(first.clone()));
+        assert!(matches!(
+            &frame,
+            Some(UpdateFrame::Progress(d)) if matches!(d[0], ProgressDelta::Full(_))
+        ));
+        assert_eq!(decoded(&mut decoder, frame), first);
+
+        let moved = vec![progress("a.flac", 400, DownloadState::InProgress)];
+        let frame = encoder.encode(DownloadEvent::Progress(moved.clone()));
+        assert!(matches!(
+            &frame,
+            Some(UpdateFrame::Progress(d)) if matches!(d[0], ProgressDelta::Transfer { .. })
+        ));
+        assert_eq!(decoded(&mut decoder, frame), moved);
+
+        let done = vec![progress("a.flac", 1000, DownloadState::Completed)];
+        let frame = encoder.encode(DownloadEvent::Progress(done.clone()));
+        assert_eq!(decoded(&mut decoder, frame), done);
+
+        // Cleared downloads are forgotten on both ends and sent whole again
+        let cleared = encoder.encode(DownloadEvent::Cleared(vec!["a.flac".to_string()]));
+        assert!(decoder.decode(cleared.expect("a frame")).is_ok());
+        let frame = encoder.encode(DownloadEvent::Progress(done.clone()));
+        assert!(matches!(
+            &frame,
+            Some(UpdateFrame::Progress(d)) if matches!(d[0], ProgressDelta::Full(_))
+        ));
+        assert_eq!(decoded(&mut decoder, frame), done);
+    }
+
+    #[test]
+    fn unchanged_downloads_and_newer_servers() {
+        let mut encoder = DeltaEncoder::default();
+        let download = vec![progress("a.flac", 100, DownloadState::InProgress)];
+        assert!(encoder
+            .encode(DownloadEvent::Progress(download.clone()))
+            .is_some());
+        assert!(encoder.encode(DownloadEvent::Progress(download)).is_none());
+
+        let mut decoder = DeltaDecoder::default();
+        assert!(decoder
+            .decode(UpdateFrame::Hello {
+                version: PROTOCOL_VERSION
+            })
+            .is_ok());
+        assert!(decoder
+            .decode(UpdateFrame::Hello {
+                version: PROTOCOL_VERSION + 1
+            })
+            .is_err());
+    }
+}
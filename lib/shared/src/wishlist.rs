@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+
+use crate::metadata::Album;
+
+/// One line of a "best of" chart, before it is matched on MusicBrainz.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartEntry {
+    pub position: Option<u32>,
+    pub artist: String,
+    pub title: String,
+    pub year: Option<String>,
+}
+
+/// A chart entry with the MusicBrainz release it was matched to, if any.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChartMatch {
+    pub entry: ChartEntry,
+    pub album: Option<Album>,
+}
+
+/// An album to add to the wishlist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NewWishlistItem {
+    pub artist: String,
+    pub title: String,
+    pub release_mbid: Option<String>,
+}
+
+/// A release in a record label's catalog, one per release group.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LabelRelease {
+    pub release_mbid: String,
+    pub release_group_id: String,
+    pub title: String,
+    pub artist: String,
+    /// YYYY, YYYY-MM or YYYY-MM-DD; empty when MusicBrainz has no date
+    pub date: String,
+    /// "Album", "EP", "Single"...; `None` when not set on MusicBrainz
+    pub primary_type: Option<String>,
+}
+
+impl LabelRelease {
+    pub fn year(&self) -> Option<u32> {
+        self.date.get(..4)?.parse().ok()
+    }
+}
+
+/// Which releases of a label's catalog to show. Releases without a date
+/// are left out once a year bound is set.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LabelFilter {
+    pub from_year: Option<u32>,
+    pub to_year: Option<u32>,
+    pub primary_type: Option<String>,
+}
+
+impl LabelFilter {
+    pub fn matches(&self, release: &LabelRelease) -> bool {
+        if let Some(wanted) = &self.primary_type {
+            if release.primary_type.as_deref() != Some(wanted.as_str()) {
+                return false;
+            }
+        }
+        if self.from_year.is_none() && self.to_year.is_none() {
+            return true;
+        }
+        release.year().is_some_and(|year| {
+            self.from_year.is_none_or(|from| year >= from)
+                && self.to_year.is_none_or(|to| year <= to)
+        })
+    }
+}
@@ -1,4 +1,5 @@
-pub use shared::library::{DuplicateGroup, DuplicateReport, LibraryTrack};
+use shared::download::{ImportSummary, ImportedFile, TagCorrection};
+pub use shared::library::{DuplicateGroup, DuplicateReport, LibraryIndexEntry, LibraryTrack};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
@@ -8,6 +9,8 @@ use std::{
 use tokio::process::Command;
 use tracing::{info, warn};
 
+use crate::tagging::most_common;
+
 /// Timeout for beets import process (5 minutes)
 const IMPORT_TIMEOUT_SECS: u64 = 300;
 
@@ -30,7 +33,7 @@ fn import_lock_for(target: &Path) -> Arc<tokio::sync::Mutex<()>> {
 #[derive(Debug)]
 pub enum ImportResult {
     /// Import completed successfully
-    Success,
+    Success(ImportSummary),
     /// Import was skipped (e.g., duplicate detection)
     Skipped,
     /// Import failed with an error message
@@ -94,6 +97,7 @@ fn validate_sources(sources: &[String]) -> Result<(), ImportError> {
 /// * `sources` - List of source file/directory paths to import
 /// * `target` - Target directory for the music library
 /// * `as_album` - If true, import as album; if false, import as singletons
+/// * `extra_flags` - Additional `beet import` flags, already validated by the caller
 ///
 /// # Returns
 /// * `Ok(ImportResult)` - The result of the import operation
@@ -102,6 +106,7 @@ pub async fn import(
     sources: Vec<String>,
     target: &Path,
     as_album: bool,
+    extra_flags: &[String],
 ) -> Result<ImportResult, ImportError> {
     // Validate sources exist before attempting import
     validate_sources(&sources)?;
@@ -137,6 +142,8 @@ pub async fn import(
         cmd.arg("-s"); // singleton mode
     }
 
+    cmd.args(extra_flags);
+
     for source in &sources {
         cmd.arg(source);
     }
@@ -145,6 +152,9 @@ pub async fn import(
     // We need stdout/stderr capture, so configure that
     cmd.stdout(std::process::Stdio::piped());
     cmd.stderr(std::process::Stdio::piped());
+    // Tags as they were before beets retagged and moved the files
+    let original = AlbumTags::from_sources(&sources);
+    let started = chrono::Local::now();
     let mut child = cmd.spawn()?;
 
     // Wait with timeout, killing the process if it takes too long
@@ -156,7 +166,16 @@ pub async fn import(
             // Process completed - read captured output
             let stdout = read_child_stdout(child.stdout.take()).await;
             let stderr = read_child_stderr(child.stderr.take()).await;
-            process_beets_output(status, &stdout, &stderr, &sources)
+            match process_beets_output(status, &stdout, &stderr, &sources)? {
+                ImportResult::Success(_) => {
+                    let imported = imported_since(&config_path, &library_path, started).await;
+                    let output = format!("{}\n{}", stdout, stderr);
+                    Ok(ImportResult::Success(summarize(
+                        imported, target, &original, &output,
+                    )))
+                }
+                other => Ok(other),
+            }
         }
         Ok(Err(e)) => Err(ImportError::Io(e)),
         Err(_) => {
@@ -218,7 +237,7 @@ fn process_beets_output(
             Ok(ImportResult::Skipped)
         } else {
             info!("Beet import successful");
-            Ok(ImportResult::Success)
+            Ok(ImportResult::Success(ImportSummary::default()))
         }
     } else {
         // Combine both streams for error reporting since beets can be inconsistent
@@ -237,6 +256,218 @@ fn process_beets_output(
     }
 }
 
+/// Album-level tags of a set of files, each the value most files agree on.
+#[derive(Debug, Default, PartialEq)]
+struct AlbumTags {
+    artist: Option<String>,
+    album: Option<String>,
+    year: Option<String>,
+    /// Only set for a single file
+    title: Option<String>,
+}
+
+impl AlbumTags {
+    fn from_sources(sources: &[String]) -> Self {
+        let files: Vec<_> = sources
+            .iter()
+            .flat_map(|s| crate::tagging::audio_files(Path::new(s)))
+            .filter_map(|f| crate::tagging::read_tags(&f).ok())
+            .collect();
+        let years: Vec<String> = files
+            .iter()
+            .filter_map(|t| t.year.map(|y| y.to_string()))
+            .collect();
+        Self {
+            artist: most_common(files.iter().filter_map(|t| t.album_artist.as_deref()))
+                .or_else(|| most_common(files.iter().filter_map(|t| t.artist.as_deref()))),
+            album: most_common(files.iter().filter_map(|t| t.album.as_deref())),
+            year: most_common(years.iter().map(String::as_str)),
+            title: match files.as_slice() {
+                [only] => only.title.clone(),
+                _ => None,
+            },
+        }
+    }
+
+    /// Fields that differ from `before`, skipping ones either side lacks.
+    fn corrections_from(&self, before: &AlbumTags) -> Vec<TagCorrection> {
+        [
+            ("Artist", &before.artist, &self.artist),
+            ("Album", &before.album, &self.album),
+            ("Year", &before.year, &self.year),
+            ("Title", &before.title, &self.title),
+        ]
+        .into_iter()
+        .filter_map(|(field, before, after)| match (before, after) {
+            (Some(before), Some(after)) if before != after => Some(TagCorrection {
+                field: field.to_string(),
+                before: before.clone(),
+                after: after.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+    }
+}
+
+/// A file beets added to the library, as `beet ls` reports it.
+struct ImportedItem {
+    path: PathBuf,
+    format: String,
+    artist: String,
+    album_artist: String,
+    album: String,
+    year: String,
+    title: String,
+    /// MusicBrainz release id, empty for files imported as-is
+    release_id: String,
+}
+
+/// Items added to the library since `since`. Imports into one library are
+/// serialized, so these are the ones the last import added.
+async fn imported_since(
+    config_path: &str,
+    library_path: &Path,
+    since: chrono::DateTime<chrono::Local>,
+) -> Result<Vec<ImportedItem>, String> {
+    let output = Command::new("beet")
+        .arg("-c")
+        .arg(config_path)
+        .arg("-l")
+        .arg(library_path)
+        .arg("ls")
+        .arg("-f")
+        .arg("$path|||$format|||$artist|||$albumartist|||$album|||$year|||$title|||$mb_albumid")
+        .arg(format!("added:{}..", since.format("%Y-%m-%dT%H:%M:%S")))
+        .output()
+        .await
+        .map_err(|e| format!("Failed to query library: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Beet ls failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split("|||").collect();
+            if parts.len() < 8 {
+                return None;
+            }
+            Some(ImportedItem {
+                path: PathBuf::from(parts[0]),
+                format: parts[1].to_string(),
+                artist: parts[2].to_string(),
+                album_artist: parts[3].to_string(),
+                album: parts[4].to_string(),
+                year: parts[5].to_string(),
+                title: parts[6].to_string(),
+                release_id: parts[7].to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Lines of beets output worth showing next to a successful import.
+fn output_warnings(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|line| {
+            let lower = line.to_lowercase();
+            lower.contains("warning") || lower.contains("could not")
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Whether beets found what it imported already in the library. With
+/// `duplicate_action` set to keep, merge or remove it imports anyway.
+fn reported_duplicate(output: &str) -> bool {
+    output.to_lowercase().contains("already in the library")
+}
+
+/// Deepest folder containing every path.
+fn common_folder<'a>(mut paths: impl Iterator<Item = &'a Path>) -> Option<PathBuf> {
+    let mut folder = paths.next()?.parent()?.to_path_buf();
+    for path in paths {
+        while !path.starts_with(&folder) {
+            if !folder.pop() {
+                return None;
+            }
+        }
+    }
+    Some(folder)
+}
+
+/// Where an import put its files, and which tags beets changed compared to
+/// `original`. Falls back to the bare target when the library couldn't be
+/// queried.
+fn summarize(
+    imported: Result<Vec<ImportedItem>, String>,
+    target: &Path,
+    original: &AlbumTags,
+    output: &str,
+) -> ImportSummary {
+    let mut warnings = output_warnings(output);
+    let items = match imported {
+        Ok(items) => items,
+        Err(e) => {
+            warn!("Could not list imported items: {}", e);
+            warnings.push("Could not list the imported files".to_string());
+            Vec::new()
+        }
+    };
+    let duplicate = reported_duplicate(output);
+
+    let mut formats: Vec<String> = Vec::new();
+    for item in &items {
+        if !item.format.is_empty() && !formats.contains(&item.format) {
+            formats.push(item.format.clone());
+        }
+    }
+    let years: Vec<&str> = items
+        .iter()
+        .map(|i| i.year.as_str())
+        .filter(|y| *y != "0")
+        .collect();
+    let imported = AlbumTags {
+        artist: most_common(items.iter().map(|i| i.album_artist.as_str()))
+            .or_else(|| most_common(items.iter().map(|i| i.artist.as_str()))),
+        album: most_common(items.iter().map(|i| i.album.as_str())),
+        year: most_common(years.into_iter()),
+        title: match items.as_slice() {
+            [only] => Some(only.title.clone()).filter(|t| !t.is_empty()),
+            _ => None,
+        },
+    };
+
+    ImportSummary {
+        destination: common_folder(items.iter().map(|i| i.path.as_path()))
+            .unwrap_or_else(|| target.to_path_buf())
+            .to_string_lossy()
+            .to_string(),
+        tracks: items.len(),
+        formats,
+        corrections: imported.corrections_from(original),
+        files: items
+            .iter()
+            .map(|i| ImportedFile {
+                path: i.path.to_string_lossy().to_string(),
+                artist: i.artist.clone(),
+                album: i.album.clone(),
+                title: i.title.clone(),
+                duplicate,
+            })
+            .collect(),
+        warnings,
+        release_id: most_common(items.iter().map(|i| i.release_id.as_str())),
+    }
+}
+
 /// Query tracks from a beets library database
 async fn query_library(library_path: &Path) -> Result<Vec<LibraryTrack>, String> {
     if !library_path.exists() {
@@ -294,6 +525,161 @@ async fn query_library(library_path: &Path) -> Result<Vec<LibraryTrack>, String>
     Ok(tracks)
 }
 
+/// List every track of the library at `library_root` with the fields used
+/// in exports.
+pub async fn library_index(library_root: &Path) -> Result<Vec<LibraryIndexEntry>, String> {
+    let library_path = library_root.join(".beets_library.db");
+    if !library_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let config_path =
+        std::env::var("BEETS_CONFIG").unwrap_or_else(|_| "beets_config.yaml".to_string());
+
+    let output = Command::new("beet")
+        .arg("-c")
+        .arg(&config_path)
+        .arg("-l")
+        .arg(&library_path)
+        .arg("ls")
+        .arg("-f")
+        .arg("$artist|||$album|||$title|||$format|||$year|||$added|||$path")
+        .output()
+        .await
+        .map_err(|e| format!("Failed to query library: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("no items") || stderr.is_empty() {
+            return Ok(Vec::new());
+        }
+        return Err(format!("Beet ls failed: {}", stderr));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split("|||").collect();
+            if parts.len() < 7 {
+                return None;
+            }
+            Some(LibraryIndexEntry {
+                artist: parts[0].to_string(),
+                album: parts[1].to_string(),
+                title: parts[2].to_string(),
+                format: parts[3].to_string(),
+                // beets prints 0 for an unknown year
+                year: parts[4].parse().ok().filter(|y| *y > 0),
+                added: parts[5].to_string(),
+                path: parts[6].to_string(),
+            })
+        })
+        .collect())
+}
+
+/// Record files already filed into an album directory in the library that
+/// holds it, as singletons keeping their tags and place. The library is the
+/// nearest directory above `album_dir` with a beets database.
+pub async fn add_in_place(files: &[String], album_dir: &Path) -> Result<(), String> {
+    let Some(library_root) = album_dir
+        .ancestors()
+        .find(|dir| dir.join(".beets_library.db").is_file())
+    else {
+        return Err(format!("No beets library holds {}", album_dir.display()));
+    };
+
+    let lock = import_lock_for(library_root);
+    let _serialized = lock.lock().await;
+
+    let config_path =
+        std::env::var("BEETS_CONFIG").unwrap_or_else(|_| "beets_config.yaml".to_string());
+
+    let output = Command::new("beet")
+        .arg("-c")
+        .arg(&config_path)
+        .arg("-l")
+        .arg(library_root.join(".beets_library.db"))
+        .arg("import")
+        .arg("-q")
+        .arg("-s") // singletons: the album entry stays as it is
+        .arg("-A") // keep the tags written when filing
+        .arg("-C") // leave the files where they are
+        .args(files)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run beet: {}", e))?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "Beet import failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        ));
+    }
+    info!(
+        "Added {} file(s) in {} to the library at {}",
+        files.len(),
+        album_dir.display(),
+        library_root.display()
+    );
+    Ok(())
+}
+
+/// Queries passed to one `beet remove`, joined by beets' OR separator.
+const REMOVE_BATCH: usize = 50;
+
+/// Remove the tracks at or under `paths` from the library holding the first
+/// of them, leaving any files alone.
+pub async fn remove_in_place(paths: &[&Path]) -> Result<(), String> {
+    let Some(library_root) = paths.first().and_then(|first| {
+        first
+            .ancestors()
+            .find(|dir| dir.join(".beets_library.db").is_file())
+    }) else {
+        return Ok(());
+    };
+
+    let lock = import_lock_for(library_root);
+    let _serialized = lock.lock().await;
+
+    let config_path =
+        std::env::var("BEETS_CONFIG").unwrap_or_else(|_| "beets_config.yaml".to_string());
+
+    for batch in paths.chunks(REMOVE_BATCH) {
+        let mut queries = Vec::new();
+        for path in batch {
+            if !queries.is_empty() {
+                queries.push(",".to_string());
+            }
+            queries.push(format!("path:{}", path.display()));
+        }
+        let output = Command::new("beet")
+            .arg("-c")
+            .arg(&config_path)
+            .arg("-l")
+            .arg(library_root.join(".beets_library.db"))
+            .arg("remove")
+            .arg("-f") // no confirmation prompt
+            .args(&queries)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run beet: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Beet remove failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+    }
+    info!(
+        "Removed {} path(s) from the library at {}",
+        paths.len(),
+        library_root.display()
+    );
+    Ok(())
+}
+
 /// Find duplicate tracks across multiple library folders
 ///
 /// # Arguments
@@ -398,15 +784,25 @@ impl crate::MusicImporter for BeetsImporter {
         sources: &[&Path],
         target: &Path,
         as_album: bool,
+    ) -> crate::error::Result<crate::ImportResult> {
+        self.import_with_flags(sources, target, as_album, &[]).await
+    }
+
+    async fn import_with_flags(
+        &self,
+        sources: &[&Path],
+        target: &Path,
+        as_album: bool,
+        extra_flags: &[String],
     ) -> crate::error::Result<crate::ImportResult> {
         let sources_str: Vec<String> = sources
             .iter()
             .map(|p| p.to_string_lossy().to_string())
             .collect();
 
-        match import(sources_str, target, as_album).await {
+        match import(sources_str, target, as_album, extra_flags).await {
             Ok(result) => Ok(match result {
-                ImportResult::Success => crate::ImportResult::Success,
+                ImportResult::Success(summary) => crate::ImportResult::Success(summary),
                 ImportResult::Skipped => crate::ImportResult::Skipped,
                 ImportResult::Failed(msg) => crate::ImportResult::Failed(msg),
                 ImportResult::TimedOut => crate::ImportResult::TimedOut,
@@ -427,6 +823,37 @@ impl crate::MusicImporter for BeetsImporter {
             })
     }
 
+    async fn library_index(&self, library: &Path) -> crate::error::Result<Vec<LibraryIndexEntry>> {
+        library_index(library)
+            .await
+            .map_err(|e| crate::error::SoulseekError::Api {
+                status: 500,
+                message: e,
+            })
+    }
+
+    async fn add_to_album(&self, album_dir: &Path, files: &[&Path]) -> crate::error::Result<()> {
+        let files: Vec<String> = files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        add_in_place(&files, album_dir)
+            .await
+            .map_err(|e| crate::error::SoulseekError::Api {
+                status: 500,
+                message: e,
+            })
+    }
+
+    async fn remove_paths(&self, paths: &[&Path]) -> crate::error::Result<()> {
+        remove_in_place(paths)
+            .await
+            .map_err(|e| crate::error::SoulseekError::Api {
+                status: 500,
+                message: e,
+            })
+    }
+
     async fn health_check(&self) -> bool {
         Command::new("beet")
             .arg("--version")
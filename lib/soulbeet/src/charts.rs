@@ -0,0 +1,220 @@
+//! "Best of" charts as a wishlist source.
+//!
+//! Entries come either from a pasted list ("1. Artist - Album (2020)") or
+//! from a chart page. Album of the Year chart pages are scraped directly;
+//! RateYourMusic usually blocks scrapers, in which case the user is asked
+//! to paste the list instead.
+
+use regex::Regex;
+use reqwest::Client;
+use shared::metadata::{Album, SearchResult};
+use shared::wishlist::ChartEntry;
+use std::sync::LazyLock;
+use tracing::debug;
+
+use crate::error::{Result, SoulseekError};
+use crate::musicbrainz::{search, SearchType};
+
+/// Upper bound on entries taken from a single chart.
+pub const MAX_CHART_ENTRIES: usize = 500;
+
+/// Leading "12.", "12)" or "#12" position marker. A bare number is left
+/// alone so artists like "311" survive.
+static POSITION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:#(\d{1,4})[.):]?|(\d{1,4})[.):])\s+").expect("valid position regex")
+});
+
+/// Trailing "(2020)" or "[2020]" year.
+static TRAILING_YEAR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s*[(\[]((?:19|20)\d{2})[)\]]\s*$").expect("valid year regex")
+});
+
+/// AOTY list titles: `<h2 class="albumListTitle"><a ...>12. Artist - Album</a></h2>`
+static AOTY_TITLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"class="albumListTitle"[^>]*>\s*(?:<[^>]+>\s*)*([^<]+)<"#)
+        .expect("valid aoty regex")
+});
+
+/// RYM chart rows carry artist and release in separate links.
+static RYM_ROW: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?s)class="artist"[^>]*>(?:<[^>]+>)*([^<]+)<.*?class="release"[^>]*>(?:<[^>]+>)*([^<]+)<"#,
+    )
+    .expect("valid rym regex")
+});
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// Parse one "Artist - Album" line. Tabs (from spreadsheet pastes) are
+/// accepted as the separator too.
+fn parse_line(line: &str) -> Option<ChartEntry> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+
+    let (position, rest) = match POSITION.captures(line) {
+        Some(caps) => (
+            caps.get(1)
+                .or(caps.get(2))
+                .and_then(|m| m.as_str().parse().ok()),
+            &line[caps.get(0).map(|m| m.end()).unwrap_or(0)..],
+        ),
+        None => (None, line),
+    };
+
+    let (year, rest) = match TRAILING_YEAR.captures(rest) {
+        Some(caps) => (
+            Some(caps[1].to_string()),
+            &rest[..caps.get(0).map(|m| m.start()).unwrap_or(rest.len())],
+        ),
+        None => (None, rest),
+    };
+
+    let (artist, title) = [" - ", " – ", " — ", "\t"]
+        .iter()
+        .find_map(|sep| rest.split_once(sep))?;
+    let artist = artist.trim();
+    let title = title.trim();
+    if artist.is_empty() || title.is_empty() {
+        return None;
+    }
+
+    Some(ChartEntry {
+        position,
+        artist: artist.to_string(),
+        title: title.to_string(),
+        year,
+    })
+}
+
+/// Parse a pasted chart, one album per line. Lines that don't look like
+/// "Artist - Album" are ignored.
+pub fn parse_chart_text(text: &str) -> Vec<ChartEntry> {
+    text.lines()
+        .filter_map(parse_line)
+        .take(MAX_CHART_ENTRIES)
+        .collect()
+}
+
+fn parse_aoty(html: &str) -> Vec<ChartEntry> {
+    AOTY_TITLE
+        .captures_iter(html)
+        .filter_map(|caps| parse_line(&decode_entities(&caps[1])))
+        .take(MAX_CHART_ENTRIES)
+        .collect()
+}
+
+fn parse_rym(html: &str) -> Vec<ChartEntry> {
+    RYM_ROW
+        .captures_iter(html)
+        .enumerate()
+        .map(|(i, caps)| ChartEntry {
+            position: Some(i as u32 + 1),
+            artist: decode_entities(caps[1].trim()),
+            title: decode_entities(caps[2].trim()),
+            year: None,
+        })
+        .take(MAX_CHART_ENTRIES)
+        .collect()
+}
+
+/// Download a chart page and read its entries.
+pub async fn fetch_chart(client: &Client, url: &str) -> Result<Vec<ChartEntry>> {
+    let lower = url.to_lowercase();
+    let parser: fn(&str) -> Vec<ChartEntry> = if lower.contains("albumoftheyear.org") {
+        parse_aoty
+    } else if lower.contains("rateyourmusic.com") {
+        parse_rym
+    } else {
+        return Err(SoulseekError::Api {
+            status: 400,
+            message: "Only Album of the Year and RateYourMusic chart links are supported; \
+                      paste the list instead"
+                .to_string(),
+        });
+    };
+
+    let response = client.get(url).send().await?;
+    let status = response.status();
+    if !status.is_success() {
+        debug!("Chart page {} answered {}", url, status);
+        return Err(SoulseekError::Api {
+            status: status.as_u16(),
+            message: "The chart page could not be loaded; paste the list instead".to_string(),
+        });
+    }
+
+    let entries = parser(&response.text().await?);
+    if entries.is_empty() {
+        return Err(SoulseekError::Api {
+            status: 422,
+            message: "No albums found on that page; paste the list instead".to_string(),
+        });
+    }
+    Ok(entries)
+}
+
+/// Best MusicBrainz album match for a chart entry, preferring one released
+/// in the entry's year when it has one.
+pub async fn resolve_entry(entry: &ChartEntry) -> Result<Option<Album>> {
+    let artist = Some(entry.artist.clone());
+    let results = search(&artist, &entry.title, SearchType::Album, 5).await?;
+
+    let albums: Vec<Album> = results
+        .into_iter()
+        .filter_map(|r| match r {
+            SearchResult::Album(album) => Some(album),
+            SearchResult::Track(_) => None,
+        })
+        .collect();
+
+    let same_year = entry.year.as_ref().and_then(|year| {
+        albums.iter().find(|a| {
+            a.release_date
+                .as_deref()
+                .is_some_and(|date| date.starts_with(year.as_str()))
+        })
+    });
+    Ok(same_year.or(albums.first()).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_numbered_lines_with_year() {
+        let entries =
+            parse_chart_text("1. Slowdive - Souvlaki (1993)\n\n2) My Bloody Valentine – Loveless");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].position, Some(1));
+        assert_eq!(entries[0].artist, "Slowdive");
+        assert_eq!(entries[0].title, "Souvlaki");
+        assert_eq!(entries[0].year.as_deref(), Some("1993"));
+        assert_eq!(entries[1].artist, "My Bloody Valentine");
+        assert_eq!(entries[1].title, "Loveless");
+        assert_eq!(entries[1].year, None);
+    }
+
+    #[test]
+    fn keeps_hyphenated_names() {
+        let entries = parse_chart_text("311 - Grassroots\nJay-Z - The Blueprint");
+        assert_eq!(entries[0].position, None);
+        assert_eq!(entries[0].artist, "311");
+        assert_eq!(entries[1].artist, "Jay-Z");
+        assert_eq!(entries[1].title, "The Blueprint");
+    }
+
+    #[test]
+    fn skips_unparseable_lines() {
+        assert!(parse_chart_text("Top albums of 2020\n---").is_empty());
+    }
+}
@@ -0,0 +1,393 @@
+//! Stand-ins for slskd and beets used by the public demo. Searches answer
+//! with made-up sources built from the query, downloads progress on a timer
+//! and imports only pretend to move files, so the whole flow can be shown
+//! without a Soulseek account or a music library.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use shared::download::{
+    DownloadProgress, DownloadState, DownloadableGroup, DownloadableItem, ImportSummary,
+    ImportedFile, QueuedDownload, SearchResult, SearchState,
+};
+use shared::library::DuplicateReport;
+use shared::metadata::{Album, Track};
+
+use crate::error::{Result, SoulseekError};
+use crate::services::{Services, ServicesBuilder};
+use crate::traits::{DownloadBackend, ImportResult, MusicImporter};
+
+/// Peers offering every search result: (name, file extension, quality, score).
+const PEERS: &[(&str, &str, &str, f64)] = &[
+    ("demo_lossless_archive", "flac", "FLAC", 0.95),
+    ("vinyl_rips_demo", "flac", "FLAC 24bit", 0.88),
+    ("mp3_collector_demo", "mp3", "320kbps MP3", 0.74),
+];
+
+/// How long a fake transfer takes.
+const TRANSFER_TIME: Duration = Duration::from_secs(12);
+
+/// Searches "complete" after this long, so the searching state shows.
+const SEARCH_TIME: Duration = Duration::from_secs(3);
+
+/// Size reported for every fake file.
+const FILE_SIZE: u64 = 24 * 1024 * 1024;
+
+/// Tracks offered for an album searched without a tracklist.
+const PLACEHOLDER_TRACKS: usize = 10;
+
+/// Characters slskd replaces when storing a download.
+fn sanitize(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// The results every demo peer returns for a search.
+fn fixture_groups(album: Option<&Album>, tracks: &[Track]) -> Vec<DownloadableGroup> {
+    let artist = album
+        .map(|a| a.artist.clone())
+        .or_else(|| tracks.first().map(|t| t.artist.clone()))
+        .unwrap_or_else(|| "Unknown Artist".to_string());
+    let album_title = album
+        .map(|a| a.title.clone())
+        .or_else(|| tracks.first().and_then(|t| t.album_title.clone()))
+        .unwrap_or_else(|| "Singles".to_string());
+    let titles: Vec<String> = if tracks.is_empty() {
+        (1..=PLACEHOLDER_TRACKS)
+            .map(|n| format!("Track {}", n))
+            .collect()
+    } else {
+        tracks.iter().map(|t| t.title.clone()).collect()
+    };
+
+    PEERS
+        .iter()
+        .map(|(peer, extension, quality, score)| {
+            let folder = sanitize(&format!("{} - {} [{}]", artist, album_title, quality));
+            let group_id = format!("Music\\{}", folder);
+            let items: Vec<DownloadableItem> = titles
+                .iter()
+                .enumerate()
+                .map(|(i, title)| DownloadableItem {
+                    id: format!(
+                        "{}\\{:02} - {}.{}",
+                        group_id,
+                        i + 1,
+                        sanitize(title),
+                        extension
+                    ),
+                    source: peer.to_string(),
+                    title: title.clone(),
+                    artist: artist.clone(),
+                    album: album_title.clone(),
+                    size: Some(FILE_SIZE),
+                    duration: None,
+                    quality: quality.to_string(),
+                    quality_score: *score,
+                    backend_data: None,
+                })
+                .collect();
+            DownloadableGroup {
+                source: peer.to_string(),
+                group_id,
+                title: album_title.clone(),
+                artist: Some(artist.clone()),
+                item_count: items.len(),
+                total_size: FILE_SIZE * items.len() as u64,
+                items,
+                quality: quality.to_string(),
+                score: *score,
+                completeness: Some(1.0),
+            }
+        })
+        .collect()
+}
+
+struct Transfer {
+    item: DownloadableItem,
+    started: Instant,
+    cancelled: bool,
+    /// Placeholder file written once the transfer "finished"
+    written: bool,
+}
+
+/// A download backend serving fixture search results and transfers that
+/// complete on a timer. Finished transfers leave an empty file in
+/// `download_dir` where slskd would have put the real one.
+pub struct DemoBackend {
+    download_dir: PathBuf,
+    next_search: AtomicU64,
+    searches: Mutex<HashMap<String, (Instant, Vec<DownloadableGroup>)>>,
+    transfers: Mutex<HashMap<String, Transfer>>,
+}
+
+impl DemoBackend {
+    pub fn new(download_dir: impl Into<PathBuf>) -> Self {
+        Self {
+            download_dir: download_dir.into(),
+            next_search: AtomicU64::new(1),
+            searches: Mutex::new(HashMap::new()),
+            transfers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Where slskd would store `filename`: its last two path components
+    /// under the download directory.
+    fn local_path(&self, filename: &str) -> PathBuf {
+        let normalized = filename.replace('\\', "/");
+        let mut parts = normalized.rsplit('/').filter(|p| !p.is_empty());
+        let file = parts.next().unwrap_or("track");
+        match parts.next() {
+            Some(dir) => self.download_dir.join(sanitize(dir)).join(sanitize(file)),
+            None => self.download_dir.join(sanitize(file)),
+        }
+    }
+}
+
+#[async_trait]
+impl DownloadBackend for DemoBackend {
+    fn id(&self) -> &'static str {
+        "demo"
+    }
+
+    fn name(&self) -> &'static str {
+        "Demo"
+    }
+
+    async fn start_search(&self, album: Option<&Album>, tracks: &[Track]) -> Result<String> {
+        let search_id = format!(
+            "demo-search-{}",
+            self.next_search.fetch_add(1, Ordering::Relaxed)
+        );
+        self.searches
+            .lock()
+            .expect("demo searches poisoned")
+            .insert(
+                search_id.clone(),
+                (Instant::now(), fixture_groups(album, tracks)),
+            );
+        Ok(search_id)
+    }
+
+    async fn poll_search(&self, search_id: &str) -> Result<SearchResult> {
+        let searches = self.searches.lock().expect("demo searches poisoned");
+        let Some((started, groups)) = searches.get(search_id) else {
+            return Ok(SearchResult {
+                search_id: search_id.to_string(),
+                groups: Vec::new(),
+                has_more: false,
+                state: SearchState::NotFound,
+                responses: 0,
+                search_text: None,
+            });
+        };
+        let done = started.elapsed() >= SEARCH_TIME;
+        Ok(SearchResult {
+            search_id: search_id.to_string(),
+            groups: groups.clone(),
+            has_more: false,
+            state: if done {
+                SearchState::Completed
+            } else {
+                SearchState::InProgress
+            },
+            responses: groups.len() as u32,
+            search_text: None,
+        })
+    }
+
+    async fn download(&self, items: Vec<DownloadableItem>) -> Result<Vec<QueuedDownload>> {
+        let mut transfers = self.transfers.lock().expect("demo transfers poisoned");
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let queued = QueuedDownload::success(
+                    item.id.clone(),
+                    item.source.clone(),
+                    item.id.clone(),
+                    item.size.unwrap_or(FILE_SIZE),
+                );
+                transfers.insert(
+                    item.id.clone(),
+                    Transfer {
+                        item,
+                        started: Instant::now(),
+                        cancelled: false,
+                        written: false,
+                    },
+                );
+                queued
+            })
+            .collect())
+    }
+
+    async fn get_downloads(&self) -> Result<Vec<DownloadProgress>> {
+        let mut transfers = self.transfers.lock().expect("demo transfers poisoned");
+        let mut progress = Vec::new();
+        for (id, transfer) in transfers.iter_mut() {
+            let size = transfer.item.size.unwrap_or(FILE_SIZE);
+            let fraction =
+                (transfer.started.elapsed().as_secs_f64() / TRANSFER_TIME.as_secs_f64()).min(1.0);
+            let state = if transfer.cancelled {
+                DownloadState::Cancelled
+            } else if fraction >= 1.0 {
+                if !transfer.written {
+                    let path = self.local_path(id);
+                    if let Some(parent) = path.parent() {
+                        let _ = std::fs::create_dir_all(parent);
+                    }
+                    transfer.written = std::fs::write(&path, b"").is_ok();
+                }
+                DownloadState::Completed
+            } else {
+                DownloadState::InProgress
+            };
+            let mut entry = DownloadProgress::queued(
+                id.clone(),
+                transfer.item.source.clone(),
+                id.clone(),
+                size,
+            );
+            entry.state = state;
+            entry.transferred = (size as f64 * fraction) as u64;
+            entry.percent = fraction * 100.0;
+            entry.speed = if fraction < 1.0 {
+                size as f64 / TRANSFER_TIME.as_secs_f64()
+            } else {
+                0.0
+            };
+            progress.push(entry);
+        }
+        Ok(progress)
+    }
+
+    async fn cancel_download(
+        &self,
+        _username: &str,
+        download_id: &str,
+        remove: bool,
+    ) -> Result<()> {
+        let mut transfers = self.transfers.lock().expect("demo transfers poisoned");
+        if remove {
+            transfers.remove(download_id);
+            return Ok(());
+        }
+        match transfers.get_mut(download_id) {
+            Some(transfer) => {
+                transfer.cancelled = true;
+                Ok(())
+            }
+            None => Err(SoulseekError::Api {
+                status: 404,
+                message: format!("No demo transfer {}", download_id),
+            }),
+        }
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
+
+/// An importer that reports success and deletes the placeholder files, as
+/// if beets had moved them into the library.
+pub struct DemoImporter;
+
+#[async_trait]
+impl MusicImporter for DemoImporter {
+    fn id(&self) -> &'static str {
+        "demo"
+    }
+
+    fn name(&self) -> &'static str {
+        "Demo"
+    }
+
+    async fn import(
+        &self,
+        sources: &[&Path],
+        target: &Path,
+        _as_album: bool,
+    ) -> Result<ImportResult> {
+        let mut summary = ImportSummary {
+            destination: target.to_string_lossy().to_string(),
+            ..Default::default()
+        };
+        for source in sources {
+            let folder = if source.is_dir() {
+                source.file_name()
+            } else {
+                source.parent().and_then(Path::file_name)
+            };
+            let destination = match folder {
+                Some(folder) => target.join(folder),
+                None => target.to_path_buf(),
+            };
+            summary.destination = destination.to_string_lossy().to_string();
+
+            for file in crate::tagging::audio_files(source) {
+                let Some(name) = file.file_name() else {
+                    continue;
+                };
+                let extension = file
+                    .extension()
+                    .map(|e| e.to_string_lossy().to_uppercase())
+                    .unwrap_or_default();
+                if !extension.is_empty() && !summary.formats.contains(&extension) {
+                    summary.formats.push(extension);
+                }
+                let stem = file
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let title = stem
+                    .split_once(" - ")
+                    .map_or(stem.as_str(), |(_, title)| title)
+                    .to_string();
+                summary.files.push(ImportedFile {
+                    path: destination.join(name).to_string_lossy().to_string(),
+                    title,
+                    ..Default::default()
+                });
+                let _ = std::fs::remove_file(&file);
+            }
+        }
+        summary.tracks = summary.files.len();
+        Ok(ImportResult::Success(summary))
+    }
+
+    async fn find_duplicates(&self, libraries: &[&Path]) -> Result<DuplicateReport> {
+        Ok(DuplicateReport {
+            duplicates: Vec::new(),
+            total_duplicate_tracks: 0,
+            libraries_scanned: libraries
+                .iter()
+                .map(|l| l.to_string_lossy().to_string())
+                .collect(),
+        })
+    }
+
+    async fn health_check(&self) -> bool {
+        true
+    }
+}
+
+/// Services for a demo instance: the demo backend and importer, with
+/// finished downloads written under `download_dir`, and Spotify when its
+/// credentials are set.
+pub fn services(download_dir: impl Into<PathBuf>) -> Services {
+    ServicesBuilder::new()
+        .add_spotify_from_env()
+        .add_download(DemoBackend::new(download_dir))
+        .add_importer(DemoImporter)
+        .build()
+        .expect("demo services include an importer")
+}
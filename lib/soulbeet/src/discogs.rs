@@ -0,0 +1,291 @@
+//! Discogs collection and wantlist, through a personal access token.
+//!
+//! Imported albums are added to the "Uncategorized" folder of the user's
+//! collection, and the wantlist can be pulled into the wishlist. Discogs
+//! has no MusicBrainz ids, so both directions match on artist and title.
+
+use reqwest::{Client, Method, RequestBuilder};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use shared::wishlist::NewWishlistItem;
+use tracing::debug;
+
+use crate::error::{Result, SoulseekError};
+use crate::http::{build_client, discogs_rate_limit, resilient_send};
+
+const DISCOGS_API_BASE: &str = "https://api.discogs.com";
+
+/// Discogs rejects requests without a descriptive user agent.
+const USER_AGENT: &str = "soulbeet/0.1 +https://github.com/terry90/soulbeet";
+
+/// Every collection has this folder; releases can't be added to "All".
+const UNCATEGORIZED_FOLDER: u32 = 1;
+
+const WANTLIST_PAGE_SIZE: u32 = 100;
+
+/// Upper bound on wantlist pages fetched, 5000 releases.
+const MAX_WANTLIST_PAGES: u32 = 50;
+
+#[derive(Debug, Deserialize)]
+struct Identity {
+    username: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    pages: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct WantlistPage {
+    pagination: Pagination,
+    #[serde(default)]
+    wants: Vec<Want>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Want {
+    basic_information: BasicInformation,
+}
+
+#[derive(Debug, Deserialize)]
+struct BasicInformation {
+    title: String,
+    #[serde(default)]
+    artists: Vec<ArtistCredit>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ArtistCredit {
+    name: String,
+    /// Join phrase to the next artist, e.g. "&" or "Feat."
+    #[serde(default)]
+    join: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResult {
+    id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct CollectionInstances {
+    #[serde(default)]
+    releases: Vec<serde_json::Value>,
+}
+
+/// Discogs disambiguates artists with a " (2)" suffix and marks name
+/// variations with a trailing "*"; neither is part of the name.
+fn clean_artist_name(name: &str) -> &str {
+    let name = name.trim().trim_end_matches('*');
+    match name
+        .rsplit_once(" (")
+        .and_then(|(base, suffix)| Some((base, suffix.strip_suffix(')')?)))
+    {
+        Some((base, number))
+            if !number.is_empty() && number.chars().all(|c| c.is_ascii_digit()) =>
+        {
+            base
+        }
+        _ => name,
+    }
+}
+
+fn artist_credit(artists: &[ArtistCredit]) -> String {
+    let mut credit = String::new();
+    for (i, artist) in artists.iter().enumerate() {
+        credit.push_str(clean_artist_name(&artist.name));
+        if i + 1 < artists.len() {
+            match artist.join.trim() {
+                "" | "," => credit.push_str(", "),
+                join => {
+                    credit.push(' ');
+                    credit.push_str(join);
+                    credit.push(' ');
+                }
+            }
+        }
+    }
+    credit
+}
+
+pub struct DiscogsClient {
+    client: Client,
+    token: String,
+    username: String,
+}
+
+impl DiscogsClient {
+    pub fn new(token: impl Into<String>, username: impl Into<String>) -> Self {
+        Self {
+            client: build_client(USER_AGENT),
+            token: token.into(),
+            username: username.into(),
+        }
+    }
+
+    /// Check `token` and look up the account it belongs to.
+    pub async fn connect(token: &str) -> Result<Self> {
+        let mut client = Self::new(token, "");
+        let identity: Identity = client
+            .send_json(Method::GET, "/oauth/identity", &[])
+            .await?;
+        client.username = identity.username;
+        Ok(client)
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    fn request(&self, method: &Method, url: &str, query: &[(&str, String)]) -> RequestBuilder {
+        self.client
+            .request(method.clone(), url)
+            .header("Authorization", format!("Discogs token={}", self.token))
+            .query(query)
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<reqwest::Response> {
+        let url = format!("{}{}", DISCOGS_API_BASE, path);
+        debug!("Discogs {} {}", method, url);
+        discogs_rate_limit().await;
+        resilient_send(
+            || self.request(&method, &url, query),
+            &format!("Discogs {} {}", method, path),
+        )
+        .await
+    }
+
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T> {
+        self.send(method, path, query)
+            .await?
+            .json()
+            .await
+            .map_err(|e| SoulseekError::Api {
+                status: 500,
+                message: format!("Failed to parse Discogs response: {}", e),
+            })
+    }
+
+    fn user_path(&self) -> String {
+        format!(
+            "/users/{}",
+            url::form_urlencoded::byte_serialize(self.username.as_bytes()).collect::<String>()
+        )
+    }
+
+    /// Every release on the wantlist, as wishlist items.
+    pub async fn wantlist(&self) -> Result<Vec<NewWishlistItem>> {
+        let path = format!("{}/wants", self.user_path());
+        let mut items = Vec::new();
+        let mut page = 1;
+        loop {
+            let response: WantlistPage = self
+                .send_json(
+                    Method::GET,
+                    &path,
+                    &[
+                        ("page", page.to_string()),
+                        ("per_page", WANTLIST_PAGE_SIZE.to_string()),
+                    ],
+                )
+                .await?;
+            items.extend(response.wants.into_iter().map(|want| NewWishlistItem {
+                artist: artist_credit(&want.basic_information.artists),
+                title: want.basic_information.title,
+                release_mbid: None,
+            }));
+            if page >= response.pagination.pages || page >= MAX_WANTLIST_PAGES {
+                break;
+            }
+            page += 1;
+        }
+        Ok(items)
+    }
+
+    /// Best matching release for an album, by Discogs' own ranking.
+    pub async fn find_release(&self, artist: &str, title: &str) -> Result<Option<u64>> {
+        let response: SearchResponse = self
+            .send_json(
+                Method::GET,
+                "/database/search",
+                &[
+                    ("type", "release".to_string()),
+                    ("artist", artist.to_string()),
+                    ("release_title", title.to_string()),
+                    ("per_page", "1".to_string()),
+                ],
+            )
+            .await?;
+        Ok(response.results.first().map(|r| r.id))
+    }
+
+    pub async fn in_collection(&self, release_id: u64) -> Result<bool> {
+        let path = format!("{}/collection/releases/{}", self.user_path(), release_id);
+        let instances: CollectionInstances = self.send_json(Method::GET, &path, &[]).await?;
+        Ok(!instances.releases.is_empty())
+    }
+
+    pub async fn add_to_collection(&self, release_id: u64) -> Result<()> {
+        let path = format!(
+            "{}/collection/folders/{}/releases/{}",
+            self.user_path(),
+            UNCATEGORIZED_FOLDER,
+            release_id
+        );
+        self.send(Method::POST, &path, &[]).await?;
+        Ok(())
+    }
+
+    /// Add an album to the collection unless a copy of it is already there.
+    /// Returns the release added, or `None` when Discogs doesn't know the
+    /// album or it was already owned.
+    pub async fn mark_owned(&self, artist: &str, title: &str) -> Result<Option<u64>> {
+        let Some(release_id) = self.find_release(artist, title).await? else {
+            return Ok(None);
+        };
+        if self.in_collection(release_id).await? {
+            return Ok(None);
+        }
+        self.add_to_collection(release_id).await?;
+        Ok(Some(release_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cleans_artist_credits() {
+        let artists = vec![
+            ArtistCredit {
+                name: "Nirvana (2)".into(),
+                join: "&".into(),
+            },
+            ArtistCredit {
+                name: "The Band*".into(),
+                join: String::new(),
+            },
+        ];
+        assert_eq!(artist_credit(&artists), "Nirvana & The Band");
+        assert_eq!(clean_artist_name("Boards Of Canada"), "Boards Of Canada");
+        assert_eq!(clean_artist_name("Sunn O))) (3)"), "Sunn O)))");
+    }
+}
@@ -354,7 +354,7 @@ async fn collect_known_artists(providers: &[Arc<dyn ScrobbleProvider>]) -> HashS
 /// Only looks up candidates that have a recording MBID and no release_year yet.
 /// Limited to `max_lookups` to keep API calls bounded.
 async fn enrich_release_years(candidates: &mut CandidateSet, max_lookups: usize) {
-    let client = crate::http::build_client("soulful/0.1 (https://github.com/soulful)");
+    let client = crate::http::build_client(&crate::http::user_agent());
 
     // Sort by score descending and only enrich the top candidates
     let mut by_score: Vec<String> = candidates.candidates.keys().cloned().collect();
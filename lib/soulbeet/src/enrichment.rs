@@ -0,0 +1,170 @@
+//! Lookups made on top of the release fetch when an album is opened, such as
+//! its cover art. They run side by side, a few at a time, and the album is
+//! returned once they are done or the time budget runs out, whichever comes
+//! first. MusicBrainz requests still go through its rate limiter, so lookups
+//! answered from a cache or by another service gain the most.
+
+use futures::future::BoxFuture;
+use futures::StreamExt;
+use reqwest::Client;
+use shared::metadata::AlbumWithTracks;
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+use crate::http::build_client;
+
+/// Lookups running at the same time for one album
+const MAX_CONCURRENT_LOOKUPS: usize = 4;
+
+/// Time the lookups get before the album is returned without the rest
+const ENRICHMENT_BUDGET: Duration = Duration::from_millis(800);
+
+/// How long an enriched album is served from the cache
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Albums kept in the cache before the oldest are dropped
+const MAX_CACHED_ALBUMS: usize = 256;
+
+/// Change a finished lookup makes to the album.
+pub type Patch = Box<dyn FnOnce(&mut AlbumWithTracks) + Send>;
+
+/// A lookup for one album, resolving to its change, if it found anything.
+pub type Lookup = BoxFuture<'static, Option<Patch>>;
+
+/// Enriched albums by release id, with when they were stored
+static CACHE: LazyLock<Mutex<HashMap<String, (Instant, AlbumWithTracks)>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static CLIENT: LazyLock<Client> =
+    LazyLock::new(|| build_client("soulful/0.1 (https://github.com/soulful)"));
+
+/// The enriched album for `release_id`, if it was opened recently.
+pub fn cached(release_id: &str) -> Option<AlbumWithTracks> {
+    let cache = CACHE.lock().ok()?;
+    let (stored, album) = cache.get(release_id)?;
+    (stored.elapsed() < CACHE_TTL).then(|| album.clone())
+}
+
+fn store(album: &AlbumWithTracks) {
+    let Ok(mut cache) = CACHE.lock() else {
+        return;
+    };
+    cache.retain(|_, (stored, _)| stored.elapsed() < CACHE_TTL);
+    if cache.len() >= MAX_CACHED_ALBUMS {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, (stored, _))| *stored)
+            .map(|(id, _)| id.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(album.album.id.clone(), (Instant::now(), album.clone()));
+}
+
+/// Run `lookups` against `album` and apply what they find. The result is
+/// only cached when every lookup finished in time, so an album opened
+/// during a slow spell is completed the next time.
+pub async fn enrich(mut album: AlbumWithTracks, lookups: Vec<Lookup>) -> AlbumWithTracks {
+    let total = lookups.len();
+    let mut finished = 0;
+    let mut results = futures::stream::iter(lookups).buffer_unordered(MAX_CONCURRENT_LOOKUPS);
+    let deadline = tokio::time::Instant::now() + ENRICHMENT_BUDGET;
+
+    while finished < total {
+        match tokio::time::timeout_at(deadline, results.next()).await {
+            Ok(Some(patch)) => {
+                finished += 1;
+                if let Some(patch) = patch {
+                    patch(&mut album);
+                }
+            }
+            Ok(None) => break,
+            Err(_) => {
+                debug!(
+                    "Enrichment of {} stopped after {}/{} lookups",
+                    album.album.id, finished, total
+                );
+                return album;
+            }
+        }
+    }
+
+    store(&album);
+    album
+}
+
+/// Front cover from the Cover Art Archive, preferring its 500px thumbnail.
+/// Releases without artwork keep the client-side fallback.
+pub fn cover_art(release_id: &str) -> Lookup {
+    #[derive(serde::Deserialize)]
+    struct CoverArtListing {
+        images: Vec<CoverArtImage>,
+    }
+    #[derive(serde::Deserialize)]
+    struct CoverArtImage {
+        #[serde(default)]
+        front: bool,
+        image: String,
+        #[serde(default)]
+        thumbnails: HashMap<String, String>,
+    }
+
+    let url = format!("https://coverartarchive.org/release/{}", release_id);
+    Box::pin(async move {
+        let listing: CoverArtListing = CLIENT
+            .get(&url)
+            .send()
+            .await
+            .ok()?
+            .error_for_status()
+            .ok()?
+            .json()
+            .await
+            .ok()?;
+        let front = listing.images.into_iter().find(|i| i.front)?;
+        let cover = front
+            .thumbnails
+            .get("500")
+            .or_else(|| front.thumbnails.get("large"))
+            .cloned()
+            .unwrap_or(front.image);
+        Some(Box::new(move |album: &mut AlbumWithTracks| {
+            album.album.cover_url = Some(cover);
+        }) as Patch)
+    })
+}
+
+/// Front cover of a release as the Cover Art Archive thumbnail of `size`
+/// pixels (250, 500 or 1200) with its content type, or `None` when the
+/// release has no artwork.
+pub async fn release_art(release_id: &str, size: u32) -> Result<Option<(Vec<u8>, String)>, String> {
+    let url = format!(
+        "https://coverartarchive.org/release/{}/front-{}",
+        release_id, size
+    );
+    let response = CLIENT
+        .get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch cover art: {}", e))?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    let response = response
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch cover art: {}", e))?;
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("image/jpeg")
+        .to_string();
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to fetch cover art: {}", e))?;
+    Ok(Some((bytes.to_vec(), content_type)))
+}
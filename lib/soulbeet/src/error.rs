@@ -25,6 +25,9 @@ pub enum SoulseekError {
 
     #[error("Could not find a username for the given download ID")]
     UsernameNotFound,
+
+    #[error("Plugin '{plugin}': {message}")]
+    Plugin { plugin: String, message: String },
 }
 
 impl SoulseekError {
@@ -35,6 +38,9 @@ impl SoulseekError {
             SoulseekError::UserOffline { .. } => false,
             SoulseekError::NotConfigured => false,
             SoulseekError::Api { status, .. } if *status == 401 || *status == 403 => false,
+            SoulseekError::Api { message, .. } => {
+                shared::slskd::EnqueueFailure::classify(message).is_retryable()
+            }
             _ => true,
         }
     }
@@ -0,0 +1,113 @@
+//! Running post-import hooks. Commands run through `sh -c` with a clean
+//! environment: the album comes in as `SOULBEET_*` variables, never pasted
+//! into the command line, so tags can't inject anything.
+
+use std::process::Stdio;
+use std::time::Duration;
+
+use reqwest::Client;
+use shared::hooks::{truncate_output, HookContext, HookKind, ImportHook};
+use tokio::process::Command;
+
+/// How a hook run went.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookOutcome {
+    pub success: bool,
+    /// Exit code, HTTP status or why it didn't finish
+    pub status: String,
+    /// Captured stdout and stderr, or the response body
+    pub output: String,
+}
+
+impl HookOutcome {
+    fn failed(status: impl Into<String>) -> Self {
+        Self {
+            success: false,
+            status: status.into(),
+            output: String::new(),
+        }
+    }
+}
+
+/// Run `hook` for an import, giving up after its timeout.
+pub async fn run(hook: &ImportHook, ctx: &HookContext) -> HookOutcome {
+    let timeout = Duration::from_secs(u64::from(hook.timeout_secs));
+    match hook.kind {
+        HookKind::Command => run_command(&hook.target, ctx, timeout).await,
+        HookKind::Webhook => call_webhook(&hook.target, ctx, timeout).await,
+    }
+}
+
+async fn run_command(command: &str, ctx: &HookContext, timeout: Duration) -> HookOutcome {
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(command)
+        .env_clear()
+        .envs(ctx.env())
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Ok(path) = std::env::var("PATH") {
+        cmd.env("PATH", path);
+    }
+
+    let child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) => return HookOutcome::failed(format!("could not start: {}", e)),
+    };
+    match tokio::time::timeout(timeout, child.wait_with_output()).await {
+        Ok(Ok(output)) => {
+            let mut text = String::from_utf8_lossy(&output.stdout)
+                .trim_end()
+                .to_string();
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if !stderr.trim().is_empty() {
+                if !text.is_empty() {
+                    text.push('\n');
+                }
+                text.push_str(&stderr);
+            }
+            HookOutcome {
+                success: output.status.success(),
+                status: match output.status.code() {
+                    Some(code) => format!("exit {}", code),
+                    None => "killed by a signal".to_string(),
+                },
+                output: truncate_output(&text),
+            }
+        }
+        Ok(Err(e)) => HookOutcome::failed(format!("did not finish: {}", e)),
+        // Dropping the child on timeout kills it
+        Err(_) => HookOutcome::failed(format!("timed out after {}s", timeout.as_secs())),
+    }
+}
+
+async fn call_webhook(url: &str, ctx: &HookContext, timeout: Duration) -> HookOutcome {
+    let client = match Client::builder().timeout(timeout).build() {
+        Ok(client) => client,
+        Err(e) => return HookOutcome::failed(e.to_string()),
+    };
+    let body = serde_json::json!({
+        "event": "import",
+        "album_path": ctx.album_path,
+        "artist": ctx.artist,
+        "album": ctx.album,
+        "format": ctx.format,
+    });
+    match client.post(url).json(&body).send().await {
+        Ok(response) => {
+            let status = response.status();
+            let text = response.text().await.unwrap_or_default();
+            HookOutcome {
+                success: status.is_success(),
+                status: format!("HTTP {}", status.as_u16()),
+                output: truncate_output(&text),
+            }
+        }
+        Err(e) if e.is_timeout() => {
+            HookOutcome::failed(format!("timed out after {}s", timeout.as_secs()))
+        }
+        Err(e) => HookOutcome::failed(e.to_string()),
+    }
+}
@@ -5,6 +5,8 @@ use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use reqwest::{Client, RequestBuilder, Response};
+use shared::system::{BreakerState, MusicBrainzConfig};
+use shared::wishlist::LabelRelease;
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 
@@ -16,8 +18,15 @@ use crate::error::{Result, SoulseekError};
 struct CircuitBreakerState {
     failure_count: u64,
     last_failure_time: Option<DateTime<Utc>>,
+    /// When a request was let through after the cooldown, while it hasn't
+    /// finished. A probe whose caller gave up counts as lost after another
+    /// cooldown.
+    probe_started: Option<DateTime<Utc>>,
 }
 
+/// Opens after `failure_threshold` consecutive failures. Once the cooldown
+/// has passed it goes half-open: a single probe request is let through and
+/// its outcome closes the breaker or restarts the cooldown.
 #[derive(Debug)]
 pub struct CircuitBreaker {
     state: Mutex<CircuitBreakerState>,
@@ -31,36 +40,67 @@ impl CircuitBreaker {
             state: Mutex::new(CircuitBreakerState {
                 failure_count: 0,
                 last_failure_time: None,
+                probe_started: None,
             }),
             failure_threshold,
             reset_timeout: chrono::Duration::seconds(reset_timeout_secs),
         }
     }
 
+    /// Whether a request should be rejected. Past the cooldown, the first
+    /// caller gets through as the probe and the others are still rejected.
     pub async fn is_open(&self) -> bool {
         let mut state = self.state.lock().await;
         if state.failure_count < self.failure_threshold {
             return false;
         }
-        if let Some(last_time) = state.last_failure_time {
-            if Utc::now() - last_time > self.reset_timeout {
-                state.failure_count = 0;
-                state.last_failure_time = None;
-                return false;
-            }
+        let now = Utc::now();
+        let cooled_down = state
+            .last_failure_time
+            .is_none_or(|last_time| now - last_time > self.reset_timeout);
+        let probing = state
+            .probe_started
+            .is_some_and(|started| now - started <= self.reset_timeout);
+        if cooled_down && !probing {
+            state.probe_started = Some(now);
+            return false;
         }
         true
     }
 
     pub async fn record_success(&self) {
         let mut state = self.state.lock().await;
+        if state.probe_started.is_some() {
+            info!("Circuit breaker probe succeeded, closing");
+        }
         state.failure_count = 0;
+        state.last_failure_time = None;
+        state.probe_started = None;
     }
 
     pub async fn record_failure(&self) {
         let mut state = self.state.lock().await;
         state.failure_count += 1;
         state.last_failure_time = Some(Utc::now());
+        state.probe_started = None;
+    }
+
+    pub async fn state(&self) -> BreakerState {
+        let state = self.state.lock().await;
+        if state.failure_count < self.failure_threshold {
+            return BreakerState::Closed;
+        }
+        let remaining = state
+            .last_failure_time
+            .map(|last_time| (last_time + self.reset_timeout - Utc::now()).num_seconds())
+            .unwrap_or(0);
+        if remaining > 0 {
+            BreakerState::Open {
+                retry_in_secs: remaining as u64,
+            }
+        } else {
+            BreakerState::HalfOpen
+        }
     }
 
     pub async fn failure_count(&self) -> u64 {
@@ -90,15 +130,42 @@ const MAX_RETRIES: u32 = 3;
 const BASE_DELAY_MS: u64 = 500;
 const MAX_DELAY_MS: u64 = 5000;
 
+/// Longest `Retry-After` honored, so a misbehaving server can't park a
+/// request for hours.
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
 /// Status codes that warrant a retry (server-side transient errors).
 fn is_retryable(status: u16) -> bool {
     matches!(status, 429 | 500 | 502 | 503 | 504)
 }
 
+/// Delay a `Retry-After` header asks for, given in seconds or as an HTTP
+/// date.
+pub fn retry_after(resp: &Response) -> Option<Duration> {
+    let value = resp
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+    let delay = match value.parse::<u64>() {
+        Ok(secs) => Duration::from_secs(secs),
+        Err(_) => {
+            let at = DateTime::parse_from_rfc2822(value).ok()?;
+            (at.with_timezone(&Utc) - Utc::now())
+                .to_std()
+                .unwrap_or(Duration::ZERO)
+        }
+    };
+    Some(delay.min(MAX_RETRY_AFTER))
+}
+
 /// Execute an HTTP request with retries and exponential backoff.
 ///
 /// Retries on network errors, 429, 500, 502, 503, 504.
 /// Does NOT retry on 4xx client errors (except 429).
+/// A `Retry-After` on a retried response replaces the backoff delay and
+/// holds back other requests to the same rate-limited service.
 pub async fn resilient_send(
     build_request: impl Fn() -> RequestBuilder,
     context: &str,
@@ -108,10 +175,14 @@ pub async fn resilient_send(
         message: format!("{}: no attempts made", context),
     };
 
+    let mut asked_delay: Option<Duration> = None;
+
     for attempt in 0..=MAX_RETRIES {
         if attempt > 0 {
-            let delay = (BASE_DELAY_MS * 2u64.pow(attempt - 1)).min(MAX_DELAY_MS);
-            tokio::time::sleep(Duration::from_millis(delay)).await;
+            let delay = asked_delay.take().unwrap_or_else(|| {
+                Duration::from_millis((BASE_DELAY_MS * 2u64.pow(attempt - 1)).min(MAX_DELAY_MS))
+            });
+            tokio::time::sleep(delay).await;
         }
 
         let resp = match build_request().send().await {
@@ -139,6 +210,12 @@ pub async fn resilient_send(
         }
 
         if is_retryable(status) && attempt < MAX_RETRIES {
+            asked_delay = retry_after(&resp);
+            if let Some(delay) = asked_delay {
+                if let Some(limiter) = resp.url().host_str().and_then(limiter_for_host) {
+                    limiter.hold(delay).await;
+                }
+            }
             warn!(
                 "{}: attempt {} got {}, retrying",
                 context,
@@ -162,6 +239,58 @@ pub async fn resilient_send(
     Err(last_err)
 }
 
+// --- MusicBrainz endpoint ---
+
+const MUSICBRAINZ_URL: &str = "https://musicbrainz.org";
+
+static MB_CONFIG: LazyLock<std::sync::RwLock<MusicBrainzConfig>> =
+    LazyLock::new(|| std::sync::RwLock::new(MusicBrainzConfig::default()));
+
+/// Point MusicBrainz requests at a mirror and set the user agent contact.
+/// Takes effect for the next request.
+pub fn configure_musicbrainz(config: MusicBrainzConfig) {
+    let config = MusicBrainzConfig {
+        mirror_url: config
+            .mirror_url
+            .map(|u| u.trim().trim_end_matches('/').to_string())
+            .filter(|u| !u.is_empty())
+            .map(|u| {
+                if u.contains("://") {
+                    u
+                } else {
+                    format!("https://{u}")
+                }
+            }),
+        contact: config
+            .contact
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty()),
+    };
+    if let Ok(mut current) = MB_CONFIG.write() {
+        *current = config;
+    }
+}
+
+pub fn musicbrainz_config() -> MusicBrainzConfig {
+    MB_CONFIG.read().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// Base URL of the MusicBrainz server in use, without a trailing slash.
+pub fn musicbrainz_url() -> String {
+    musicbrainz_config()
+        .mirror_url
+        .unwrap_or_else(|| MUSICBRAINZ_URL.to_string())
+}
+
+/// User agent sent to MusicBrainz, which asks for a way to reach whoever
+/// runs the client.
+pub fn user_agent() -> String {
+    let contact = musicbrainz_config()
+        .contact
+        .unwrap_or_else(|| "https://github.com/terry90/soulbeet".to_string());
+    format!("Soulbeet/{} ( {} )", env!("CARGO_PKG_VERSION"), contact)
+}
+
 /// Build a reqwest Client with standard timeouts.
 pub fn build_client(user_agent: &str) -> Client {
     Client::builder()
@@ -187,12 +316,24 @@ impl RateLimiter {
 
     async fn wait(&self, min_interval: Duration) {
         let mut last = self.0.lock().await;
-        let elapsed = last.elapsed();
-        if elapsed < min_interval {
-            tokio::time::sleep(min_interval - elapsed).await;
+        let now = Instant::now();
+        if *last > now {
+            // Held back by the service
+            tokio::time::sleep(*last - now).await;
+        } else if now - *last < min_interval {
+            tokio::time::sleep(min_interval - (now - *last)).await;
         }
         *last = Instant::now();
     }
+
+    /// Let no request through for `delay`, as the service asked.
+    async fn hold(&self, delay: Duration) {
+        let mut last = self.0.lock().await;
+        let until = Instant::now() + delay;
+        if until > *last {
+            *last = until;
+        }
+    }
 }
 
 // MusicBrainz: 1 req/sec (documented hard limit, 503 if exceeded)
@@ -201,15 +342,39 @@ static MB_LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::new);
 static LFM_LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::new);
 // ListenBrainz: ~2 req/sec (uses response headers, but we preemptively limit)
 static LB_LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::new);
+// Discogs: 60 req/min for authenticated requests
+static DISCOGS_LIMITER: LazyLock<RateLimiter> = LazyLock::new(RateLimiter::new);
 
 const MB_INTERVAL: Duration = Duration::from_millis(1100);
 const LFM_INTERVAL: Duration = Duration::from_millis(1000);
 const LB_INTERVAL: Duration = Duration::from_millis(500);
+const DISCOGS_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Limiter of the service behind `host`, if it has one.
+fn limiter_for_host(host: &str) -> Option<&'static RateLimiter> {
+    match host {
+        "musicbrainz.org" => Some(&MB_LIMITER),
+        "ws.audioscrobbler.com" => Some(&LFM_LIMITER),
+        "api.listenbrainz.org" => Some(&LB_LIMITER),
+        "api.discogs.com" => Some(&DISCOGS_LIMITER),
+        _ => None,
+    }
+}
 
 pub async fn mb_rate_limit() {
+    // Self-hosted mirrors have no rate limit
+    if musicbrainz_config().mirror_url.is_some() {
+        return;
+    }
     MB_LIMITER.wait(MB_INTERVAL).await;
 }
 
+/// Hold back MusicBrainz requests for `delay`, after it answered that it is
+/// rate limiting us.
+pub async fn mb_back_off(delay: Duration) {
+    MB_LIMITER.hold(delay).await;
+}
+
 pub async fn lastfm_rate_limit() {
     LFM_LIMITER.wait(LFM_INTERVAL).await;
 }
@@ -218,6 +383,10 @@ pub async fn lb_rate_limit() {
     LB_LIMITER.wait(LB_INTERVAL).await;
 }
 
+pub async fn discogs_rate_limit() {
+    DISCOGS_LIMITER.wait(DISCOGS_INTERVAL).await;
+}
+
 // --- MBID cache (avoids repeated MusicBrainz lookups for the same artist) ---
 
 static MBID_CACHE: LazyLock<Mutex<HashMap<String, Option<String>>>> =
@@ -242,7 +411,8 @@ pub async fn cached_mbid_lookup(client: &Client, artist: &str) -> Result<Option<
     // Wrap in double quotes to escape Lucene special chars (AC/DC, Guns N' Roses, etc.)
     let quoted = format!("\"{}\"", artist.replace('"', "\\\""));
     let url = format!(
-        "https://musicbrainz.org/ws/2/artist/?query=artist:{}&fmt=json&limit=1",
+        "{}/ws/2/artist/?query=artist:{}&fmt=json&limit=1",
+        musicbrainz_url(),
         url::form_urlencoded::byte_serialize(quoted.as_bytes()).collect::<String>()
     );
 
@@ -317,7 +487,8 @@ pub async fn cached_mbid_lookup(client: &Client, artist: &str) -> Result<Option<
         mb_rate_limit().await;
         let quoted = format!("\"{}\"", primary.replace('"', "\\\""));
         let url = format!(
-            "https://musicbrainz.org/ws/2/artist/?query=artist:{}&fmt=json&limit=1",
+            "{}/ws/2/artist/?query=artist:{}&fmt=json&limit=1",
+            musicbrainz_url(),
             url::form_urlencoded::byte_serialize(quoted.as_bytes()).collect::<String>()
         );
 
@@ -405,7 +576,8 @@ pub async fn cached_recording_lookup(client: &Client, mbid: &str) -> Result<Opti
     mb_rate_limit().await;
 
     let url = format!(
-        "https://musicbrainz.org/ws/2/recording/{}?inc=artist-credits+releases&fmt=json",
+        "{}/ws/2/recording/{}?inc=artist-credits+releases&fmt=json",
+        musicbrainz_url(),
         mbid
     );
 
@@ -505,3 +677,319 @@ pub async fn cached_recording_lookup(client: &Client, mbid: &str) -> Result<Opti
         .insert(mbid.to_string(), Some(info.clone()));
     Ok(Some(info))
 }
+
+// --- External URL relationships (Discogs, Bandcamp, ... -> MB release) ---
+
+/// Find the MusicBrainz release linked to an external page, such as a
+/// Discogs release URL, through MusicBrainz URL relationships.
+pub async fn release_for_external_url(client: &Client, resource: &str) -> Result<Option<String>> {
+    mb_rate_limit().await;
+
+    let resp = client
+        .get(format!("{}/ws/2/url", musicbrainz_url()))
+        .query(&[("resource", resource), ("inc", "release-rels"), ("fmt", "json")])
+        .send()
+        .await?;
+
+    // MB answers 404 for URLs nobody has linked yet
+    if resp.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !resp.status().is_success() {
+        return Err(SoulseekError::Api {
+            status: resp.status().as_u16(),
+            message: format!("MusicBrainz URL lookup failed for {}", resource),
+        });
+    }
+
+    #[derive(serde::Deserialize)]
+    struct MbUrl {
+        #[serde(default)]
+        relations: Vec<MbRelation>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbRelation {
+        #[serde(default)]
+        release: Option<MbReleaseRef>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbReleaseRef {
+        id: String,
+    }
+
+    let data: MbUrl = resp.json().await?;
+    Ok(data
+        .relations
+        .into_iter()
+        .find_map(|r| r.release.map(|release| release.id)))
+}
+
+// --- Artist release groups (release calendar) ---
+
+#[derive(Clone, Debug)]
+pub struct ArtistReleaseGroup {
+    pub id: String,
+    pub title: String,
+    pub primary_type: Option<String>,
+    /// YYYY, YYYY-MM or YYYY-MM-DD; empty when MusicBrainz has no date
+    pub first_release_date: String,
+}
+
+/// Albums, EPs and singles of an artist, as MusicBrainz release groups.
+/// Announced releases are included once editors have entered them.
+pub async fn artist_release_groups(
+    client: &Client,
+    artist_mbid: &str,
+) -> Result<Vec<ArtistReleaseGroup>> {
+    #[derive(serde::Deserialize)]
+    struct MbBrowse {
+        #[serde(default, rename = "release-groups")]
+        release_groups: Vec<MbReleaseGroup>,
+        #[serde(default, rename = "release-group-count")]
+        count: usize,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbReleaseGroup {
+        id: String,
+        title: String,
+        #[serde(default, rename = "primary-type")]
+        primary_type: Option<String>,
+        #[serde(default, rename = "first-release-date")]
+        first_release_date: Option<String>,
+    }
+
+    const PAGE_SIZE: usize = 100;
+    let mut groups = Vec::new();
+    let mut offset = 0;
+    loop {
+        mb_rate_limit().await;
+        let offset_param = offset.to_string();
+        let limit_param = PAGE_SIZE.to_string();
+        let resp = client
+            .get(format!("{}/ws/2/release-group", musicbrainz_url()))
+            .query(&[
+                ("artist", artist_mbid),
+                ("type", "album|ep|single"),
+                ("fmt", "json"),
+                ("limit", &limit_param),
+                ("offset", &offset_param),
+            ])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(SoulseekError::Api {
+                status: resp.status().as_u16(),
+                message: format!("MusicBrainz release group browse failed for {}", artist_mbid),
+            });
+        }
+
+        let page: MbBrowse = resp.json().await?;
+        let fetched = page.release_groups.len();
+        groups.extend(page.release_groups.into_iter().map(|rg| ArtistReleaseGroup {
+            id: rg.id,
+            title: rg.title,
+            primary_type: rg.primary_type,
+            first_release_date: rg.first_release_date.unwrap_or_default(),
+        }));
+
+        offset += fetched;
+        if fetched < PAGE_SIZE || offset >= page.count {
+            break;
+        }
+    }
+    Ok(groups)
+}
+
+// --- Artist relationships (related artists) ---
+
+#[derive(Clone, Debug)]
+pub struct ArtistRelation {
+    pub mbid: String,
+    pub name: String,
+    /// How the other artist relates to the one looked up, e.g. "Member"
+    /// or "Member of"
+    pub relation: String,
+}
+
+/// Wording of an artist-artist relationship type seen from the artist
+/// looked up: `forward` when that artist is the relationship's subject.
+fn relation_label(kind: &str, forward: bool) -> String {
+    match (kind, forward) {
+        ("member of band", true) => "Member of".to_string(),
+        ("member of band", false) => "Member".to_string(),
+        ("collaboration", true) => "Collaboration".to_string(),
+        ("collaboration", false) => "Collaborator".to_string(),
+        ("subgroup", true) => "Subgroup of".to_string(),
+        ("subgroup", false) => "Subgroup".to_string(),
+        ("is person", _) => "Also performs as".to_string(),
+        (other, _) => {
+            let mut chars = other.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+    }
+}
+
+/// Artists linked to an artist on MusicBrainz: band members, the bands a
+/// person is in, collaborations and the like. Each artist is listed once,
+/// under its first relationship.
+pub async fn artist_relations(client: &Client, artist_mbid: &str) -> Result<Vec<ArtistRelation>> {
+    mb_rate_limit().await;
+
+    let resp = client
+        .get(format!("{}/ws/2/artist/{}", musicbrainz_url(), artist_mbid))
+        .query(&[("inc", "artist-rels"), ("fmt", "json")])
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        return Err(SoulseekError::Api {
+            status: resp.status().as_u16(),
+            message: format!("MusicBrainz artist lookup failed for {}", artist_mbid),
+        });
+    }
+
+    #[derive(serde::Deserialize)]
+    struct MbArtist {
+        #[serde(default)]
+        relations: Vec<MbRelation>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbRelation {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(default)]
+        direction: String,
+        #[serde(default)]
+        artist: Option<MbArtistRef>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbArtistRef {
+        id: String,
+        name: String,
+    }
+
+    let data: MbArtist = resp.json().await?;
+    let mut related: Vec<ArtistRelation> = Vec::new();
+    for relation in data.relations {
+        let Some(artist) = relation.artist else {
+            continue;
+        };
+        if artist.id == artist_mbid || related.iter().any(|r| r.mbid == artist.id) {
+            continue;
+        }
+        related.push(ArtistRelation {
+            mbid: artist.id,
+            name: artist.name,
+            relation: relation_label(&relation.kind, relation.direction == "forward"),
+        });
+    }
+    Ok(related)
+}
+
+// --- Label catalogs ---
+
+/// Releases put out by a record label, one per release group: the earliest
+/// release of each, oldest first.
+pub async fn label_releases(client: &Client, label_mbid: &str) -> Result<Vec<LabelRelease>> {
+    #[derive(serde::Deserialize)]
+    struct MbBrowse {
+        #[serde(default)]
+        releases: Vec<MbRelease>,
+        #[serde(default, rename = "release-count")]
+        count: usize,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbRelease {
+        id: String,
+        title: String,
+        #[serde(default)]
+        date: Option<String>,
+        #[serde(default, rename = "artist-credit")]
+        artist_credit: Vec<MbArtistCredit>,
+        #[serde(rename = "release-group")]
+        release_group: Option<MbReleaseGroupRef>,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbArtistCredit {
+        name: String,
+        #[serde(default)]
+        joinphrase: String,
+    }
+    #[derive(serde::Deserialize)]
+    struct MbReleaseGroupRef {
+        id: String,
+        #[serde(default, rename = "primary-type")]
+        primary_type: Option<String>,
+    }
+
+    const PAGE_SIZE: usize = 100;
+    let mut releases: Vec<LabelRelease> = Vec::new();
+    let mut offset = 0;
+    loop {
+        mb_rate_limit().await;
+        let offset_param = offset.to_string();
+        let limit_param = PAGE_SIZE.to_string();
+        let resp = client
+            .get(format!("{}/ws/2/release", musicbrainz_url()))
+            .query(&[
+                ("label", label_mbid),
+                ("inc", "artist-credits+release-groups"),
+                ("fmt", "json"),
+                ("limit", &limit_param),
+                ("offset", &offset_param),
+            ])
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(SoulseekError::Api {
+                status: resp.status().as_u16(),
+                message: format!("MusicBrainz release browse failed for label {}", label_mbid),
+            });
+        }
+
+        let page: MbBrowse = resp.json().await?;
+        let fetched = page.releases.len();
+        for release in page.releases {
+            let Some(group) = release.release_group else {
+                continue;
+            };
+            let candidate = LabelRelease {
+                release_mbid: release.id,
+                release_group_id: group.id,
+                title: release.title,
+                artist: release
+                    .artist_credit
+                    .iter()
+                    .map(|c| format!("{}{}", c.name, c.joinphrase))
+                    .collect(),
+                date: release.date.unwrap_or_default(),
+                primary_type: group.primary_type,
+            };
+            let known = releases
+                .iter_mut()
+                .find(|r| r.release_group_id == candidate.release_group_id);
+            match known {
+                None => releases.push(candidate),
+                // An undated release counts as the latest
+                Some(known) => {
+                    let earlier = !candidate.date.is_empty()
+                        && (known.date.is_empty() || candidate.date < known.date);
+                    if earlier {
+                        *known = candidate;
+                    }
+                }
+            }
+        }
+
+        offset += fetched;
+        if fetched < PAGE_SIZE || offset >= page.count {
+            break;
+        }
+    }
+    releases.sort_by(|a, b| {
+        (a.date.is_empty(), &a.date, &a.title).cmp(&(b.date.is_empty(), &b.date, &b.title))
+    });
+    Ok(releases)
+}
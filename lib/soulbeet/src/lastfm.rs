@@ -769,6 +769,7 @@ impl crate::MetadataProvider for LastFmProvider {
                     release_date: None,
                     mbid,
                     cover_url,
+                    details: Default::default(),
                 })
             })
             .collect())
@@ -799,6 +800,7 @@ impl crate::MetadataProvider for LastFmProvider {
                     duration: None,
                     mbid,
                     release_mbid: None,
+                    details: Default::default(),
                 })
             })
             .collect())
@@ -836,6 +838,7 @@ impl crate::MetadataProvider for LastFmProvider {
                                     duration: format_duration(track.duration),
                                     mbid: track_mbid,
                                     release_mbid: album_mbid.clone(),
+                                    details: Default::default(),
                                 }
                             })
                             .collect()
@@ -850,6 +853,7 @@ impl crate::MetadataProvider for LastFmProvider {
                         release_date: info.wiki.and_then(|w| w.published),
                         mbid: album_mbid,
                         cover_url,
+                        details: Default::default(),
                     },
                     tracks,
                 });
@@ -1,19 +1,33 @@
 pub mod beets;
+pub mod charts;
+#[cfg(feature = "demo")]
+pub mod demo;
+pub mod discogs;
 pub mod engine;
+pub mod enrichment;
 pub mod error;
+pub mod hooks;
 pub mod http;
 pub mod lastfm;
+pub mod linking;
 pub mod listenbrainz;
 pub mod musicbrainz;
 pub mod navidrome;
+pub mod plugin;
+pub mod remote;
+pub mod renaming;
+pub mod resolver;
 pub mod services;
 pub mod slskd;
+pub mod spotify;
+pub mod tagging;
 pub mod traits;
 
 pub use lastfm::LastFmProvider;
 pub use listenbrainz::ListenBrainzProvider;
 pub use navidrome::{NavidromeClient, NavidromeClientBuilder};
 pub use services::{Services, ServicesBuilder};
+pub use spotify::SpotifyProvider;
 pub use traits::{
     CandidateGenerator, DownloadBackend, FallbackMetadataProvider, ImportResult, MetadataProvider,
     MusicImporter, ScrobbleProvider,
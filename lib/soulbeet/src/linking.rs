@@ -0,0 +1,115 @@
+//! Staging a download as hard links or reflinks, so the importer can move
+//! the links into the library while the original files stay where slskd
+//! put them (for seeding, or to import again later).
+
+use shared::download::ImportMode;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Copy-on-write clone of `src` into a new file at `dst`.
+#[cfg(target_os = "linux")]
+fn reflink(src: &Path, dst: &Path) -> io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source = std::fs::File::open(src)?;
+    let target = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dst)?;
+    // SAFETY: both descriptors are open for the duration of the call
+    let ret = unsafe { libc::ioctl(target.as_raw_fd(), libc::FICLONE as _, source.as_raw_fd()) };
+    if ret == -1 {
+        let err = io::Error::last_os_error();
+        drop(target);
+        let _ = std::fs::remove_file(dst);
+        return Err(err);
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn reflink(_src: &Path, _dst: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reflinks are only supported on Linux",
+    ))
+}
+
+fn link_file(src: &Path, dst: &Path, mode: ImportMode) -> io::Result<()> {
+    match mode {
+        ImportMode::Hardlink => std::fs::hard_link(src, dst),
+        ImportMode::Reflink => reflink(src, dst),
+        ImportMode::Default => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the default import mode doesn't link files",
+        )),
+    }
+}
+
+/// Recreate `src` at `dst`, placing each file with `place`.
+fn place_tree(
+    src: &Path,
+    dst: &Path,
+    place: &impl Fn(&Path, &Path) -> io::Result<()>,
+) -> io::Result<()> {
+    if src.is_file() {
+        return place(src, dst);
+    }
+    std::fs::create_dir_all(dst)?;
+    for entry in std::fs::read_dir(src)? {
+        let entry = entry?;
+        let target = dst.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            place_tree(&entry.path(), &target, place)?;
+        } else {
+            place(&entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Recreate `source` under `staging_dir` with `place`, cleaning up on failure.
+fn stage_with(
+    source: &Path,
+    staging_dir: &Path,
+    verb: &str,
+    place: impl Fn(&Path, &Path) -> io::Result<()>,
+) -> Result<PathBuf, String> {
+    let name = source
+        .file_name()
+        .ok_or_else(|| format!("{} has no file name", source.display()))?;
+    let staged = staging_dir.join(name);
+
+    let result =
+        std::fs::create_dir_all(staging_dir).and_then(|_| place_tree(source, &staged, &place));
+    match result {
+        Ok(()) => Ok(staged),
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(staging_dir);
+            Err(format!("Failed to {} {}: {}", verb, source.display(), e))
+        }
+    }
+}
+
+/// Recreate `source` (a folder or a single file) under `staging_dir` with
+/// every file linked rather than copied, and return the staged path.
+///
+/// `staging_dir` must be on the same filesystem as `source`. Nothing is left
+/// behind on failure; a cross-device or unsupported link comes back as the
+/// underlying io error.
+pub fn stage_links(source: &Path, staging_dir: &Path, mode: ImportMode) -> Result<PathBuf, String> {
+    stage_with(
+        source,
+        staging_dir,
+        &mode.label().to_lowercase(),
+        |src, dst| link_file(src, dst, mode),
+    )
+}
+
+/// Like [`stage_links`], but with plain copies, which work across
+/// filesystems. Used to give another library its own copy of a download.
+pub fn stage_copies(source: &Path, staging_dir: &Path) -> Result<PathBuf, String> {
+    stage_with(source, staging_dir, "copy", |src, dst| {
+        std::fs::copy(src, dst).map(|_| ())
+    })
+}
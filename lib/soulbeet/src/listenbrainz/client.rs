@@ -156,6 +156,21 @@ impl ListenBrainzClient {
         self.get_json(&path, "top recordings").await
     }
 
+    pub async fn get_top_releases(
+        &self,
+        period: TimePeriod,
+        count: u32,
+    ) -> Result<TopReleasesResponse> {
+        let range = time_period_to_range(period);
+        let path = format!(
+            "/1/stats/user/{}/releases?range={}&count={}",
+            self.encoded_username(),
+            range,
+            count
+        );
+        self.get_json(&path, "top releases").await
+    }
+
     // --- Similar Users ---
 
     pub async fn get_similar_users(&self) -> Result<Vec<SimilarUser>> {
@@ -80,6 +80,28 @@ pub struct LbRecording {
     pub listen_count: u64,
 }
 
+// --- Stats: Top Releases ---
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TopReleasesResponse {
+    pub payload: TopReleasesPayload,
+}
+
+#[derive(Debug, Deserialize, Default)]
+pub struct TopReleasesPayload {
+    #[serde(default)]
+    pub releases: Vec<LbRelease>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LbRelease {
+    pub release_name: String,
+    pub artist_name: String,
+    #[serde(default)]
+    pub release_mbid: Option<String>,
+    pub listen_count: u64,
+}
+
 // --- Similar Users ---
 
 /// The LB API returns `{"payload": [{"user_name": "...", "similarity": 0.19}, ...]}`
@@ -1,5 +1,6 @@
 use musicbrainz_rs::{
     entity::{
+        artist::{Artist, ArtistSearchQuery},
         artist_credit::ArtistCredit,
         recording::{Recording, RecordingSearchQuery},
         release::{Release, ReleaseStatus},
@@ -7,8 +8,17 @@ use musicbrainz_rs::{
     },
     Fetch, MusicBrainzClient, Search,
 };
-use shared::metadata::{Album, AlbumWithTracks, SearchResult, Track};
-use std::{collections::HashSet, future::Future, sync::OnceLock, time::Duration};
+use regex::Regex;
+use shared::metadata::{
+    Album, AlbumWithTracks, IdentifierKind, ReleaseDetails, SearchResult, Track,
+};
+use shared::system::MusicBrainzConfig;
+use std::{
+    collections::{HashMap, HashSet},
+    future::Future,
+    sync::{Arc, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
 use tokio::time::sleep;
 use tracing::{info, warn};
 
@@ -24,26 +34,62 @@ const BASE_DELAY_MS: u64 = 500;
 /// Maximum backoff delay cap (milliseconds)
 const MAX_BACKOFF_MS: u64 = 5000;
 
-// This ensures the client is initialized only once with a proper user agent.
-fn musicbrainz_client() -> &'static MusicBrainzClient {
-    static CLIENT: OnceLock<MusicBrainzClient> = OnceLock::new();
-    CLIENT.get_or_init(|| {
-        let version = env!("CARGO_PKG_VERSION");
-        let mut client = MusicBrainzClient::new(&format!(
-            "Soulbeet/{version} ( https://github.com/terry90/soulbeet )"
-        ))
-        .expect("Failed to create MusicBrainz client - invalid user agent format");
-        // MUSICBRAINZ_HOST points the client at a MusicBrainz mirror
-        // (host[:port], no scheme). Mirrors the `musicbrainz.host` option
-        // beets exposes for the same purpose.
-        if let Ok(host) = std::env::var("MUSICBRAINZ_HOST") {
-            let host = host.trim();
-            if !host.is_empty() {
-                client.musicbrainz_domain = host.to_string();
-            }
+/// Pause of all MusicBrainz requests after being told we are rate limited
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(3);
+
+/// Other names of an artist searched on top of the credited one
+const MAX_ARTIST_ALIASES: usize = 2;
+
+/// Alias lookups already made, keyed by the credited name's `name_key`
+static ALIAS_CACHE: LazyLock<Mutex<HashMap<String, Vec<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// How long the outcome of a reachability check is reused
+const REACHABILITY_TTL: Duration = Duration::from_secs(60);
+
+/// Last reachability check, with when it was made
+static REACHABILITY: Mutex<Option<(Instant, bool)>> = Mutex::new(None);
+
+/// Client for the current MusicBrainz settings, with those settings.
+static CLIENT: Mutex<Option<(MusicBrainzConfig, Arc<MusicBrainzClient>)>> = Mutex::new(None);
+
+/// The client, built again when the mirror or user agent changed.
+fn musicbrainz_client() -> Arc<MusicBrainzClient> {
+    let config = crate::http::musicbrainz_config();
+    let mut cached = CLIENT.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some((built_for, client)) = cached.as_ref() {
+        if *built_for == config {
+            return client.clone();
         }
-        client
-    })
+    }
+
+    let mut client = MusicBrainzClient::new(&crate::http::user_agent())
+        .expect("Failed to create MusicBrainz client - invalid user agent format");
+    // A mirror set in the settings wins over MUSICBRAINZ_HOST (host[:port],
+    // no scheme), which mirrors the `musicbrainz.host` option beets exposes
+    // for the same purpose.
+    let host = match &config.mirror_url {
+        Some(url) => Some(
+            url.split_once("://")
+                .map_or(url.as_str(), |(_, h)| h)
+                .to_string(),
+        ),
+        None => std::env::var("MUSICBRAINZ_HOST")
+            .ok()
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty()),
+    };
+    if let Some(host) = host {
+        client.musicbrainz_domain = host;
+    }
+    let client = Arc::new(client);
+    *cached = Some((config, client.clone()));
+    client
+}
+
+/// Host MusicBrainz lookups are sent to.
+pub fn server() -> String {
+    musicbrainz_client().musicbrainz_domain.clone()
 }
 
 /// Formats the artist credits into a single, comma-separated string.
@@ -70,52 +116,92 @@ fn format_duration(duration_ms: &Option<u32>) -> Option<String> {
     })
 }
 
-/// Check if an error is retryable (transient network/server issues)
-fn is_retryable_error(error: &musicbrainz_rs::Error) -> bool {
-    let error_str = format!("{:?}", error);
-    let error_lower = error_str.to_lowercase();
-
-    // Retry on timeout, connection, and 5xx server errors
-    if error_lower.contains("timeout")
-        || error_lower.contains("connection")
-        || error_lower.contains("timed out")
-        || error_lower.contains("503")
-        || error_lower.contains("502")
-        || error_lower.contains("500")
-        || error_lower.contains("429") // Rate limited - should retry after backoff
-        || error_lower.contains("service unavailable")
-    {
-        return true;
-    }
-
-    // Don't retry client errors (4xx except 429)
-    if error_lower.contains("400")
-        || error_lower.contains("401")
-        || error_lower.contains("403")
-        || error_lower.contains("404")
-        || error_lower.contains("bad request")
-        || error_lower.contains("not found")
-        || error_lower.contains("unauthorized")
-    {
-        return false;
-    }
-
-    // Default to retrying for unknown errors (network issues, etc.)
-    true
+/// HTTP status in the debug output of a client error, e.g. "Status(503)"
+static ERROR_STATUS: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)status(?:\(|: )(\d{3})").expect("valid status regex"));
+
+/// A failed MusicBrainz request, sorted by what the caller can do about it.
+#[derive(Debug, thiserror::Error)]
+pub enum MusicBrainzError {
+    /// MusicBrainz answers 503 (and sometimes 429) past its rate limit
+    #[error("MusicBrainz is rate limiting requests")]
+    RateLimited,
+    #[error("Not found on MusicBrainz")]
+    NotFound,
+    #[error("MusicBrainz did not answer in time")]
+    Timeout,
+    #[error("MusicBrainz is unavailable: {0}")]
+    Unavailable(String),
+    #[error("MusicBrainz rejected the request: {0}")]
+    Rejected(String),
+}
+
+impl MusicBrainzError {
+    /// Sort a client error by the HTTP status it carries. The client doesn't
+    /// expose responses, so the status is read from its debug output.
+    fn classify(error: &musicbrainz_rs::Error) -> Self {
+        let debug = format!("{:?}", error);
+        let status = ERROR_STATUS
+            .captures(&debug)
+            .and_then(|c| c[1].parse::<u16>().ok());
+        match status {
+            Some(429 | 503) => Self::RateLimited,
+            Some(404) => Self::NotFound,
+            Some(400..=499) => Self::Rejected(error.to_string()),
+            Some(_) => Self::Unavailable(error.to_string()),
+            None => {
+                let lower = debug.to_lowercase();
+                if lower.contains("timed out") || lower.contains("timeout") {
+                    Self::Timeout
+                } else if lower.contains("notfound") || lower.contains("not found") {
+                    Self::NotFound
+                } else {
+                    // Connection failures and unreadable responses
+                    Self::Unavailable(error.to_string())
+                }
+            }
+        }
+    }
+
+    /// Whether the same request may succeed later.
+    pub fn is_transient(&self) -> bool {
+        !matches!(self, Self::NotFound | Self::Rejected(_))
+    }
+
+    /// HTTP status the error is reported to clients with.
+    pub fn status(&self) -> u16 {
+        match self {
+            Self::RateLimited => 429,
+            Self::NotFound => 404,
+            Self::Timeout => 504,
+            Self::Unavailable(_) => 503,
+            Self::Rejected(_) => 400,
+        }
+    }
+}
+
+impl From<MusicBrainzError> for crate::error::SoulseekError {
+    fn from(e: MusicBrainzError) -> Self {
+        crate::error::SoulseekError::Api {
+            status: e.status(),
+            message: e.to_string(),
+        }
+    }
 }
 
 /// Retries an async operation with exponential backoff and request timeout.
 /// Only retries transient errors (network issues, timeouts, 5xx responses).
-/// Does NOT retry client errors (4xx) or permanent failures.
+/// Does NOT retry client errors (4xx) or permanent failures. Being rate
+/// limited holds back every MusicBrainz request, not only this one.
 async fn with_retry<T, F, Fut>(
     operation_name: &str,
     mut operation: F,
-) -> Result<T, musicbrainz_rs::Error>
+) -> Result<T, MusicBrainzError>
 where
     F: FnMut() -> Fut,
     Fut: Future<Output = Result<T, musicbrainz_rs::Error>>,
 {
-    let mut last_error = None;
+    let mut last_error = MusicBrainzError::Timeout;
 
     for attempt in 0..MAX_RETRIES {
         // Respect MusicBrainz rate limit (1 req/sec)
@@ -125,54 +211,42 @@ where
         let result =
             tokio::time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), operation()).await;
 
-        match result {
+        let error = match result {
             Ok(Ok(value)) => return Ok(value),
-            Ok(Err(e)) => {
-                // Check if this error is retryable
-                if !is_retryable_error(&e) {
-                    warn!(
-                        "{} failed with non-retryable error: {:?}",
-                        operation_name, e
-                    );
-                    return Err(e);
-                }
+            Ok(Err(e)) => MusicBrainzError::classify(&e),
+            Err(_timeout) => MusicBrainzError::Timeout,
+        };
+        if !error.is_transient() {
+            warn!(
+                "{} failed with non-retryable error: {}",
+                operation_name, error
+            );
+            return Err(error);
+        }
 
-                last_error = Some(e);
-                if attempt < MAX_RETRIES - 1 {
-                    let delay = std::cmp::min(BASE_DELAY_MS * 2u64.pow(attempt), MAX_BACKOFF_MS);
-                    warn!(
-                        "{} failed (attempt {}/{}), retrying in {}ms: {:?}",
-                        operation_name,
-                        attempt + 1,
-                        MAX_RETRIES,
-                        delay,
-                        last_error
-                    );
-                    sleep(Duration::from_millis(delay)).await;
-                }
-            }
-            Err(_timeout) => {
-                // Request timed out
-                warn!(
-                    "{} timed out after {}s (attempt {}/{})",
-                    operation_name,
-                    REQUEST_TIMEOUT_SECS,
-                    attempt + 1,
-                    MAX_RETRIES
-                );
-                last_error = Some(musicbrainz_rs::Error::MaxRetriesExceeded);
-                if attempt < MAX_RETRIES - 1 {
-                    let delay = std::cmp::min(BASE_DELAY_MS * 2u64.pow(attempt), MAX_BACKOFF_MS);
-                    sleep(Duration::from_millis(delay)).await;
-                }
+        if attempt < MAX_RETRIES - 1 {
+            let delay = Duration::from_millis(std::cmp::min(
+                BASE_DELAY_MS * 2u64.pow(attempt),
+                MAX_BACKOFF_MS,
+            ));
+            warn!(
+                "{} failed (attempt {}/{}), retrying in {}ms: {}",
+                operation_name,
+                attempt + 1,
+                MAX_RETRIES,
+                delay.as_millis(),
+                error
+            );
+            if matches!(error, MusicBrainzError::RateLimited) {
+                crate::http::mb_back_off(delay.max(RATE_LIMIT_BACKOFF)).await;
+            } else {
+                sleep(delay).await;
             }
         }
+        last_error = error;
     }
 
-    match last_error {
-        Some(e) => Err(e),
-        None => Err(musicbrainz_rs::Error::MaxRetriesExceeded),
-    }
+    Err(last_error)
 }
 
 /// An enumeration to specify the type of search.
@@ -180,6 +254,134 @@ where
 pub enum SearchType {
     Track,
     Album,
+    /// Exact lookup by barcode, catalog number (releases) or ISRC (recordings)
+    Identifier(IdentifierKind),
+}
+
+/// Lucene field for an identifier search, with the value quoted.
+fn identifier_query(kind: IdentifierKind, value: &str) -> String {
+    let field = match kind {
+        IdentifierKind::Barcode => "barcode",
+        IdentifierKind::CatalogNumber => "catno",
+        IdentifierKind::Isrc => "isrc",
+    };
+    format!("{}:\"{}\"", field, value.replace(['"', '\\'], ""))
+}
+
+fn primary_type_name(primary_type: &ReleaseGroupPrimaryType) -> String {
+    match primary_type {
+        ReleaseGroupPrimaryType::Album => "Album".to_string(),
+        ReleaseGroupPrimaryType::Ep => "EP".to_string(),
+        ReleaseGroupPrimaryType::Single => "Single".to_string(),
+        other => format!("{:?}", other),
+    }
+}
+
+/// Label, country, track count, type and genres, as far as the release
+/// carries them. Search results include all but the genres; a fetch needs
+/// the labels, release group and genres included.
+fn release_details(release: &Release) -> ReleaseDetails {
+    // Genres voted on the release group cover releases that have none
+    let group_genres = release
+        .release_group
+        .as_ref()
+        .and_then(|rg| rg.genres.as_ref());
+    let mut genres: Vec<String> = Vec::new();
+    for genre in release.genres.iter().chain(group_genres).flatten() {
+        if !genres.contains(&genre.name) {
+            genres.push(genre.name.clone());
+        }
+    }
+
+    ReleaseDetails {
+        label: release.label_info.as_ref().and_then(|infos| {
+            infos
+                .iter()
+                .find_map(|info| info.label.as_ref().map(|l| l.name.clone()))
+        }),
+        country: release.country.clone(),
+        track_count: release
+            .media
+            .as_ref()
+            .map(|media| media.iter().map(|m| m.track_count).sum())
+            .filter(|count| *count > 0),
+        primary_type: release
+            .release_group
+            .as_ref()
+            .and_then(|rg| rg.primary_type.as_ref())
+            .map(primary_type_name),
+        genres,
+    }
+}
+
+/// Fill in the label, country and track count of albums found through
+/// their release group, whose search answer lists releases without them.
+/// One release search covers the whole page; if it fails the albums are
+/// shown with what they have.
+async fn add_release_details(client: &MusicBrainzClient, albums: &mut [Album]) {
+    if albums.is_empty() {
+        return;
+    }
+    let search_query = albums
+        .iter()
+        .map(|a| format!("reid:{}", a.id))
+        .collect::<Vec<_>>()
+        .join(" OR ");
+    let limit = albums.len().min(100) as u8;
+    let found = with_retry("MusicBrainz release details", || {
+        let search_query = search_query.clone();
+        async move {
+            Release::search(search_query)
+                .limit(limit)
+                .execute_with_client(client)
+                .await
+        }
+    })
+    .await;
+
+    match found {
+        Ok(found) => {
+            for release in found.entities {
+                if let Some(album) = albums.iter_mut().find(|a| a.id == release.id) {
+                    // The release group's own type is already known
+                    let primary_type = album.details.primary_type.clone();
+                    album.details = ReleaseDetails {
+                        primary_type,
+                        ..release_details(&release)
+                    };
+                }
+            }
+        }
+        Err(e) => warn!("Could not fetch release details: {}", e),
+    }
+}
+
+fn release_to_album(release: &Release) -> Album {
+    Album {
+        id: release.id.clone(),
+        title: release.title.clone(),
+        artist: format_artist_credit(&release.artist_credit),
+        release_date: release.date.as_ref().map(|d| d.0.clone()),
+        mbid: Some(release.id.clone()),
+        cover_url: None,
+        details: release_details(release),
+    }
+}
+
+fn recording_to_track(recording: &Recording) -> Track {
+    let first_release = recording.releases.as_ref().and_then(|r| r.first());
+    Track {
+        id: recording.id.clone(),
+        title: recording.title.clone(),
+        artist: format_artist_credit(&recording.artist_credit),
+        album_id: first_release.map(|release| release.id.clone()),
+        album_title: first_release.map(|r| r.title.clone()),
+        release_date: first_release.and_then(|r| r.date.clone().map(|d| d.0)),
+        duration: format_duration(&recording.length),
+        mbid: Some(recording.id.clone()),
+        release_mbid: first_release.map(|r| r.id.clone()),
+        details: first_release.map(release_details).unwrap_or_default(),
+    }
 }
 
 /// Performs a refined search for music, prioritizing canonical releases.
@@ -188,8 +390,9 @@ pub async fn search(
     query: &str,
     search_type: SearchType,
     limit: u8,
-) -> Result<Vec<SearchResult>, musicbrainz_rs::Error> {
-    let client = musicbrainz_client();
+) -> Result<Vec<SearchResult>, MusicBrainzError> {
+    let handle = musicbrainz_client();
+    let client = handle.as_ref();
     let mut results = Vec::new();
 
     info!(
@@ -245,20 +448,8 @@ pub async fn search(
                 );
 
                 if !unique_tracks.contains(&key) {
-                    let first_release = recording.releases.as_ref().and_then(|r| r.first());
-                    let track = Track {
-                        id: recording.id.clone(),
-                        title: recording.title.clone(),
-                        artist: artist_name.clone(),
-                        album_id: first_release.map(|release| release.id.clone()),
-                        album_title: first_release.map(|r| r.title.clone()),
-                        release_date: first_release.and_then(|r| r.date.clone().map(|d| d.0)),
-                        duration: format_duration(&recording.length),
-                        mbid: Some(recording.id.clone()),
-                        release_mbid: first_release.map(|r| r.id.clone()),
-                    };
                     unique_tracks.insert(key);
-                    results.push(SearchResult::Track(track));
+                    results.push(SearchResult::Track(recording_to_track(&recording)));
                 }
             }
         }
@@ -290,6 +481,7 @@ pub async fn search(
                     .unwrap_or(std::cmp::Ordering::Equal)
             });
 
+            let mut albums = Vec::new();
             for release_group in release_groups {
                 if release_group.primary_type != Some(ReleaseGroupPrimaryType::Album)
                     && release_group.primary_type != Some(ReleaseGroupPrimaryType::Ep)
@@ -306,25 +498,205 @@ pub async fn search(
                     // If no official release was found, take the first one available
                     let final_release = best_release.clone();
 
-                    results.push(SearchResult::Album(Album {
+                    albums.push(Album {
                         id: final_release.id.clone(),
                         title: release_group.title.clone(),
                         artist: format_artist_credit(&release_group.artist_credit),
                         release_date: final_release.date.as_ref().map(|d| d.0.clone()),
                         mbid: Some(final_release.id.clone()),
                         cover_url: None,
-                    }));
+                        details: ReleaseDetails {
+                            primary_type: release_group
+                                .primary_type
+                                .as_ref()
+                                .map(primary_type_name),
+                            ..Default::default()
+                        },
+                    });
                 }
             }
+            add_release_details(client, &mut albums).await;
+            results.extend(albums.into_iter().map(SearchResult::Album));
+        }
+        SearchType::Identifier(IdentifierKind::Isrc) => {
+            let search_query = identifier_query(IdentifierKind::Isrc, query);
+            let search_results = with_retry("MusicBrainz ISRC search", || {
+                let search_query = search_query.clone();
+                async move {
+                    Recording::search(search_query)
+                        .limit(limit)
+                        .with_releases()
+                        .execute_with_client(client)
+                        .await
+                }
+            })
+            .await?;
+
+            results.extend(
+                search_results
+                    .entities
+                    .iter()
+                    .map(|recording| SearchResult::Track(recording_to_track(recording))),
+            );
+        }
+        SearchType::Identifier(kind) => {
+            let search_query = identifier_query(kind, query);
+            let search_results = with_retry("MusicBrainz release identifier search", || {
+                let search_query = search_query.clone();
+                async move {
+                    Release::search(search_query)
+                        .limit(limit)
+                        .execute_with_client(client)
+                        .await
+                }
+            })
+            .await?;
+
+            results.extend(
+                search_results
+                    .entities
+                    .iter()
+                    .map(|release| SearchResult::Album(release_to_album(release))),
+            );
         }
     }
 
     Ok(results)
 }
 
+/// Letters and digits only, lowercased: "CAN" and "Can" are the same name
+/// to a Soulseek search.
+fn name_key(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// The names worth searching besides `credited`, strongest first: the
+/// artist's main name, then primary aliases, then aliases in another script
+/// than the credited name (transliterations), then the rest. `aliases` are
+/// (name, primary) pairs.
+fn strongest_variants(credited: &str, canonical: &str, aliases: &[(String, bool)]) -> Vec<String> {
+    let credited_ascii = credited.is_ascii();
+    let mut ranked: Vec<(u8, &str)> = std::iter::once((0, canonical))
+        .chain(aliases.iter().map(|(name, primary)| {
+            let rank = if *primary {
+                1
+            } else if name.is_ascii() != credited_ascii {
+                2
+            } else {
+                3
+            };
+            (rank, name.as_str())
+        }))
+        .collect();
+    ranked.sort_by_key(|(rank, _)| *rank);
+
+    let mut seen = HashSet::from([name_key(credited)]);
+    ranked
+        .into_iter()
+        .filter(|(_, name)| {
+            let key = name_key(name);
+            !key.is_empty() && seen.insert(key)
+        })
+        .map(|(_, name)| name.to_string())
+        .take(MAX_ARTIST_ALIASES)
+        .collect()
+}
+
+/// Other names `artist` is known under on MusicBrainz, for searching
+/// Soulseek, where international artists are often shared under a
+/// transliteration or their native spelling. Empty when the best matching
+/// artist doesn't carry the name, to avoid searching for someone else.
+pub async fn artist_aliases(artist: &str) -> Result<Vec<String>, MusicBrainzError> {
+    let key = name_key(artist);
+    if key.is_empty() {
+        return Ok(Vec::new());
+    }
+    if let Some(cached) = ALIAS_CACHE.lock().ok().and_then(|c| c.get(&key).cloned()) {
+        return Ok(cached);
+    }
+
+    let handle = musicbrainz_client();
+    let client = handle.as_ref();
+    let results = with_retry("MusicBrainz artist search", || {
+        let search_query = ArtistSearchQuery::query_builder().artist(artist).build();
+        async move {
+            Artist::search(search_query)
+                .limit(1)
+                .execute_with_client(client)
+                .await
+        }
+    })
+    .await?;
+
+    let variants = results
+        .entities
+        .into_iter()
+        .next()
+        .map(|found| {
+            let aliases: Vec<(String, bool)> = found
+                .aliases
+                .unwrap_or_default()
+                .into_iter()
+                .map(|a| (a.name, a.primary.unwrap_or(false)))
+                .collect();
+            let is_same_artist = name_key(&found.name) == key
+                || aliases.iter().any(|(name, _)| name_key(name) == key);
+            if is_same_artist {
+                strongest_variants(artist, &found.name, &aliases)
+            } else {
+                Vec::new()
+            }
+        })
+        .unwrap_or_default();
+
+    if !variants.is_empty() {
+        info!("Searching '{}' also as {:?}", artist, variants);
+    }
+    if let Ok(mut cache) = ALIAS_CACHE.lock() {
+        cache.insert(key, variants.clone());
+    }
+    Ok(variants)
+}
+
+/// Whether MusicBrainz (or the configured mirror) answers at all. A single
+/// attempt with a short timeout, so callers can fall back quickly during an
+/// outage; rejections and rate limiting still count as reachable.
+pub async fn is_reachable() -> bool {
+    if let Some((checked, reachable)) = REACHABILITY.lock().ok().and_then(|r| *r) {
+        if checked.elapsed() < REACHABILITY_TTL {
+            return reachable;
+        }
+    }
+
+    crate::http::mb_rate_limit().await;
+    // Various Artists, which is always there
+    let probe = Artist::fetch()
+        .id("89ad4ac3-39f7-470e-963a-56509c546377")
+        .execute_with_client(&musicbrainz_client());
+    let reachable = match tokio::time::timeout(Duration::from_secs(5), probe).await {
+        Ok(Ok(_)) => true,
+        Ok(Err(e)) => !matches!(
+            MusicBrainzError::classify(&e),
+            MusicBrainzError::Unavailable(_) | MusicBrainzError::Timeout
+        ),
+        Err(_) => false,
+    };
+    if !reachable {
+        warn!("MusicBrainz is unreachable");
+    }
+    if let Ok(mut last) = REACHABILITY.lock() {
+        *last = Some((Instant::now(), reachable));
+    }
+    reachable
+}
+
 /// Fetches a release (album) by its ID and returns it with its full tracklist.
-pub async fn find_album(release_id: &str) -> Result<AlbumWithTracks, musicbrainz_rs::Error> {
-    let client = musicbrainz_client();
+pub async fn find_album(release_id: &str) -> Result<AlbumWithTracks, MusicBrainzError> {
+    let handle = musicbrainz_client();
+    let client = handle.as_ref();
 
     // Fetch the release with recordings (tracks) and artist credits for the tracks.
     let release = with_retry("MusicBrainz album fetch", || async {
@@ -332,11 +704,15 @@ pub async fn find_album(release_id: &str) -> Result<AlbumWithTracks, musicbrainz
             .id(release_id)
             .with_recordings()
             .with_artist_credits()
+            .with_labels()
+            .with_release_groups()
+            .with_genres()
             .execute_with_client(client)
             .await
     })
     .await?;
 
+    let details = release_details(&release);
     let mut tracks = Vec::new();
 
     // A release contains media (like CD 1, CD 2), and each medium has tracks.
@@ -355,6 +731,7 @@ pub async fn find_album(release_id: &str) -> Result<AlbumWithTracks, musicbrainz
                             duration: format_duration(&recording.length),
                             mbid: Some(recording.id.clone()),
                             release_mbid: Some(release.id.clone()),
+                            details: details.clone(),
                         });
                     }
                 }
@@ -370,6 +747,7 @@ pub async fn find_album(release_id: &str) -> Result<AlbumWithTracks, musicbrainz
         release_date: release.date.map(|d| d.0),
         mbid: Some(release.id),
         cover_url: None,
+        details,
     };
 
     let album_with_tracks = AlbumWithTracks { album, tracks };
@@ -410,10 +788,7 @@ impl crate::MetadataProvider for MusicBrainzProvider {
         let artist_opt = artist.map(String::from);
         search(&artist_opt, query, SearchType::Album, limit.min(100) as u8)
             .await
-            .map_err(|e| crate::error::SoulseekError::Api {
-                status: 500,
-                message: e.to_string(),
-            })
+            .map_err(Into::into)
     }
 
     async fn search_tracks(
@@ -425,18 +800,60 @@ impl crate::MetadataProvider for MusicBrainzProvider {
         let artist_opt = artist.map(String::from);
         search(&artist_opt, query, SearchType::Track, limit.min(100) as u8)
             .await
-            .map_err(|e| crate::error::SoulseekError::Api {
-                status: 500,
-                message: e.to_string(),
-            })
+            .map_err(Into::into)
+    }
+
+    async fn search_by_identifier(
+        &self,
+        kind: IdentifierKind,
+        value: &str,
+        limit: usize,
+    ) -> crate::error::Result<Vec<SearchResult>> {
+        search(
+            &None,
+            value,
+            SearchType::Identifier(kind),
+            limit.min(100) as u8,
+        )
+        .await
+        .map_err(Into::into)
     }
 
     async fn get_album(&self, id: &str) -> crate::error::Result<AlbumWithTracks> {
-        find_album(id)
-            .await
-            .map_err(|e| crate::error::SoulseekError::Api {
-                status: 500,
-                message: e.to_string(),
-            })
+        if let Some(album) = crate::enrichment::cached(id) {
+            return Ok(album);
+        }
+        let album = find_album(id).await?;
+        let lookups = vec![crate::enrichment::cover_art(&album.album.id)];
+        Ok(crate::enrichment::enrich(album, lookups).await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases(names: &[(&str, bool)]) -> Vec<(String, bool)> {
+        names.iter().map(|(n, p)| (n.to_string(), *p)).collect()
+    }
+
+    #[test]
+    fn skips_case_and_punctuation_variants() {
+        let variants = strongest_variants("CAN", "Can", &aliases(&[("C.A.N.", false)]));
+        assert!(variants.is_empty());
+    }
+
+    #[test]
+    fn prefers_primary_aliases_then_transliterations() {
+        let variants = strongest_variants(
+            "東京事変",
+            "東京事変",
+            &aliases(&[
+                ("Tokio Jihen", false),
+                ("Tokyo Incidents", true),
+                ("Tokyo Jihen", false),
+            ]),
+        );
+        assert_eq!(variants, vec!["Tokyo Incidents", "Tokio Jihen"]);
     }
 }
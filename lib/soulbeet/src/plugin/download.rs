@@ -0,0 +1,105 @@
+use async_trait::async_trait;
+use serde_json::json;
+use shared::download::{
+    DownloadProgress, DownloadableItem, QueuedDownload, SearchOptions, SearchResult,
+};
+use shared::metadata::{Album, Track};
+
+use super::process::PluginProcess;
+use super::{leak, Plugin};
+use crate::error::Result;
+use crate::traits::DownloadBackend;
+
+/// A download backend driven through a plugin program. The program does
+/// the searching and transferring; files must end up in the download
+/// directory like slskd's for the import to find them.
+pub struct PluginDownloadBackend {
+    id: &'static str,
+    name: &'static str,
+    process: PluginProcess,
+}
+
+impl PluginDownloadBackend {
+    pub fn new(plugin: Plugin) -> Self {
+        Self {
+            id: leak(&plugin.manifest.id),
+            name: leak(&plugin.manifest.name),
+            process: PluginProcess::new(plugin),
+        }
+    }
+
+    pub fn plugin(&self) -> &Plugin {
+        self.process.plugin()
+    }
+}
+
+#[async_trait]
+impl DownloadBackend for PluginDownloadBackend {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn start_search(&self, album: Option<&Album>, tracks: &[Track]) -> Result<String> {
+        self.start_search_with_options(album, tracks, &SearchOptions::default())
+            .await
+    }
+
+    async fn start_search_with_options(
+        &self,
+        album: Option<&Album>,
+        tracks: &[Track],
+        options: &SearchOptions,
+    ) -> Result<String> {
+        self.process
+            .call(
+                "start_search",
+                json!({ "album": album, "tracks": tracks, "options": options }),
+            )
+            .await
+    }
+
+    async fn poll_search(&self, search_id: &str) -> Result<SearchResult> {
+        self.process
+            .call("poll_search", json!({ "search_id": search_id }))
+            .await
+    }
+
+    async fn download(&self, items: Vec<DownloadableItem>) -> Result<Vec<QueuedDownload>> {
+        self.process
+            .call("download", json!({ "items": items }))
+            .await
+    }
+
+    async fn get_downloads(&self) -> Result<Vec<DownloadProgress>> {
+        self.process.call("get_downloads", json!({})).await
+    }
+
+    async fn cancel_download(&self, username: &str, download_id: &str, remove: bool) -> Result<()> {
+        self.process
+            .call(
+                "cancel_download",
+                json!({ "username": username, "download_id": download_id, "remove": remove }),
+            )
+            .await
+    }
+
+    async fn health_check(&self) -> bool {
+        self.process
+            .call::<bool>("health_check", json!({}))
+            .await
+            .unwrap_or(false)
+    }
+
+    async fn prune_transfers(&self, older_than: std::time::Duration) -> Result<usize> {
+        self.process
+            .call(
+                "prune_transfers",
+                json!({ "older_than_secs": older_than.as_secs() }),
+            )
+            .await
+    }
+}
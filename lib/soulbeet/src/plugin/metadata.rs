@@ -0,0 +1,86 @@
+use async_trait::async_trait;
+use serde_json::json;
+use shared::metadata::{AlbumWithTracks, IdentifierKind, SearchResult};
+
+use super::process::PluginProcess;
+use super::{leak, Plugin};
+use crate::error::Result;
+use crate::traits::MetadataProvider;
+
+/// A metadata provider answering through a plugin program.
+pub struct PluginMetadataProvider {
+    id: &'static str,
+    name: &'static str,
+    process: PluginProcess,
+}
+
+impl PluginMetadataProvider {
+    pub fn new(plugin: Plugin) -> Self {
+        Self {
+            id: leak(&plugin.manifest.id),
+            name: leak(&plugin.manifest.name),
+            process: PluginProcess::new(plugin),
+        }
+    }
+
+    pub fn plugin(&self) -> &Plugin {
+        self.process.plugin()
+    }
+}
+
+#[async_trait]
+impl MetadataProvider for PluginMetadataProvider {
+    fn id(&self) -> &'static str {
+        self.id
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn search_albums(
+        &self,
+        artist: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        self.process
+            .call(
+                "search_albums",
+                json!({ "artist": artist, "query": query, "limit": limit }),
+            )
+            .await
+    }
+
+    async fn search_tracks(
+        &self,
+        artist: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        self.process
+            .call(
+                "search_tracks",
+                json!({ "artist": artist, "query": query, "limit": limit }),
+            )
+            .await
+    }
+
+    async fn get_album(&self, id: &str) -> Result<AlbumWithTracks> {
+        self.process.call("get_album", json!({ "id": id })).await
+    }
+
+    async fn search_by_identifier(
+        &self,
+        kind: IdentifierKind,
+        value: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        self.process
+            .call(
+                "search_by_identifier",
+                json!({ "kind": kind, "value": value, "limit": limit }),
+            )
+            .await
+    }
+}
@@ -0,0 +1,237 @@
+//! Metadata providers and download backends implemented by external
+//! programs.
+//!
+//! A plugin is a directory under the plugins directory holding a
+//! `plugin.json` manifest, the program to run and optionally a
+//! `config.json`. The program is started once and kept running; it reads
+//! one JSON request per line on stdin and answers each with one JSON line
+//! on stdout:
+//!
+//! ```text
+//! > {"id":1,"method":"search_albums","params":{"artist":null,"query":"kid a","limit":10}}
+//! < {"id":1,"result":[...]}
+//! < {"id":2,"error":"rate limited"}
+//! ```
+//!
+//! The first request is always `initialize`, carrying the protocol version
+//! and the plugin's `config.json`. Parameters and results use the same
+//! JSON as the rest of Soulbeet; the methods are those of
+//! [`MetadataProvider`](crate::MetadataProvider) and
+//! [`DownloadBackend`](crate::DownloadBackend) in snake case. Anything the
+//! program writes to stderr ends up in the server log.
+//!
+//! Plugins run in their own directory with an empty environment apart from
+//! `PATH`, and get a private `data/` directory as `HOME`. They never see
+//! the server's environment, where secrets live, nor another plugin's
+//! config.
+
+mod download;
+mod metadata;
+mod process;
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+pub use download::PluginDownloadBackend;
+pub use metadata::PluginMetadataProvider;
+
+/// Version of the protocol sent in `initialize`.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "plugin.json";
+const CONFIG_FILE: &str = "config.json";
+const DATA_DIR: &str = "data";
+
+fn default_timeout_secs() -> u64 {
+    30
+}
+
+/// Which trait a plugin implements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluginKind {
+    Metadata,
+    Download,
+}
+
+/// Contents of `plugin.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginManifest {
+    /// Identifier used in settings and URLs: lowercase letters, digits,
+    /// `-` and `_`
+    pub id: String,
+    pub name: String,
+    pub kind: PluginKind,
+    /// Program to run. A path is relative to the plugin directory and must
+    /// stay inside it; a bare name such as `python3` is looked up on `PATH`.
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// How long a single request may take before the program is restarted
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// A plugin found on disk, with its manifest checked.
+#[derive(Debug, Clone)]
+pub struct Plugin {
+    pub manifest: PluginManifest,
+    pub dir: PathBuf,
+    program: PathBuf,
+}
+
+impl Plugin {
+    /// Load the plugin in `dir`.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let dir = dir
+            .canonicalize()
+            .map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let manifest_path = dir.join(MANIFEST_FILE);
+        let raw = std::fs::read_to_string(&manifest_path)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+        let manifest: PluginManifest = serde_json::from_str(&raw)
+            .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+
+        let valid_id = !manifest.id.is_empty()
+            && manifest
+                .id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '-' | '_'));
+        if !valid_id {
+            return Err(format!(
+                "{}: invalid plugin id '{}'",
+                manifest_path.display(),
+                manifest.id
+            ));
+        }
+
+        let program = if manifest.command.contains('/') {
+            let program = dir
+                .join(&manifest.command)
+                .canonicalize()
+                .map_err(|e| format!("{}: {}", manifest.command, e))?;
+            if !program.starts_with(&dir) {
+                return Err(format!(
+                    "{}: command '{}' is outside the plugin directory",
+                    manifest_path.display(),
+                    manifest.command
+                ));
+            }
+            program
+        } else {
+            PathBuf::from(&manifest.command)
+        };
+
+        Ok(Self {
+            manifest,
+            dir,
+            program,
+        })
+    }
+
+    /// The plugin's own settings, or null without a `config.json`.
+    fn config(&self) -> Result<serde_json::Value, String> {
+        let path = self.dir.join(CONFIG_FILE);
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => serde_json::from_str(&raw).map_err(|e| format!("{}: {}", CONFIG_FILE, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(serde_json::Value::Null),
+            Err(e) => Err(format!("{}: {}", CONFIG_FILE, e)),
+        }
+    }
+
+    fn data_dir(&self) -> PathBuf {
+        self.dir.join(DATA_DIR)
+    }
+}
+
+/// Every plugin under `plugins_dir`, one per subdirectory. Plugins that
+/// fail to load are logged and left out, as are later ones reusing an id.
+pub fn discover(plugins_dir: &Path) -> Vec<Plugin> {
+    let entries = match std::fs::read_dir(plugins_dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!("Cannot read plugins in {}: {}", plugins_dir.display(), e);
+            }
+            return Vec::new();
+        }
+    };
+
+    let mut dirs: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.join(MANIFEST_FILE).is_file())
+        .collect();
+    dirs.sort();
+
+    let mut plugins: Vec<Plugin> = Vec::new();
+    for dir in dirs {
+        match Plugin::load(&dir) {
+            Ok(plugin) if plugins.iter().any(|p| p.manifest.id == plugin.manifest.id) => {
+                warn!(
+                    "Skipping plugin in {}: id '{}' is already used",
+                    dir.display(),
+                    plugin.manifest.id
+                );
+            }
+            Ok(plugin) => plugins.push(plugin),
+            Err(e) => warn!("Skipping plugin: {}", e),
+        }
+    }
+    plugins
+}
+
+/// Plugin names live as long as the server; the traits want `&'static str`.
+fn leak(s: &str) -> &'static str {
+    Box::leak(s.to_string().into_boxed_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plugin_dir(name: &str, manifest: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("soulbeet-plugin-{}-{}", std::process::id(), name));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(MANIFEST_FILE), manifest).unwrap();
+        dir
+    }
+
+    #[test]
+    fn loads_manifest_with_bare_command() {
+        let dir = plugin_dir(
+            "valid",
+            r#"{"id": "bandcamp", "name": "Bandcamp", "kind": "metadata", "command": "python3", "args": ["main.py"]}"#,
+        );
+        let plugin = Plugin::load(&dir).unwrap();
+        assert_eq!(plugin.manifest.kind, PluginKind::Metadata);
+        assert_eq!(plugin.manifest.timeout_secs, 30);
+        assert_eq!(plugin.program, PathBuf::from("python3"));
+        assert_eq!(plugin.config().unwrap(), serde_json::Value::Null);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_invalid_id() {
+        let dir = plugin_dir(
+            "bad-id",
+            r#"{"id": "../slskd", "name": "Evil", "kind": "download", "command": "sh"}"#,
+        );
+        assert!(Plugin::load(&dir).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_command_outside_plugin_dir() {
+        let dir = plugin_dir(
+            "escape",
+            r#"{"id": "escape", "name": "Escape", "kind": "download", "command": "../../bin/sh"}"#,
+        );
+        let err = Plugin::load(&dir).unwrap_err();
+        assert!(err.contains("outside the plugin directory"), "{}", err);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,195 @@
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+use super::{Plugin, PROTOCOL_VERSION};
+use crate::error::{Result, SoulseekError};
+
+#[derive(Serialize)]
+struct Request<'a> {
+    id: u64,
+    method: &'a str,
+    params: Value,
+}
+
+#[derive(Deserialize)]
+struct Response {
+    id: u64,
+    #[serde(default)]
+    result: Value,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+struct Running {
+    // Held so the program is killed when this is dropped
+    _child: Child,
+    stdin: ChildStdin,
+    stdout: Lines<BufReader<ChildStdout>>,
+}
+
+/// The running program of a plugin. It is started on the first request
+/// and restarted on the next one after it exits, misbehaves or times out.
+/// Requests are sent one at a time.
+pub(super) struct PluginProcess {
+    plugin: Plugin,
+    running: Mutex<Option<Running>>,
+    next_id: AtomicU64,
+}
+
+impl PluginProcess {
+    pub(super) fn new(plugin: Plugin) -> Self {
+        Self {
+            plugin,
+            running: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    pub(super) fn plugin(&self) -> &Plugin {
+        &self.plugin
+    }
+
+    fn error(&self, message: impl Into<String>) -> SoulseekError {
+        SoulseekError::Plugin {
+            plugin: self.plugin.manifest.id.clone(),
+            message: message.into(),
+        }
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_secs(self.plugin.manifest.timeout_secs.max(1))
+    }
+
+    async fn spawn(&self) -> Result<Running> {
+        let config = self.plugin.config().map_err(|e| self.error(e))?;
+        let data_dir = self.plugin.data_dir();
+        std::fs::create_dir_all(&data_dir)
+            .map_err(|e| self.error(format!("cannot create {}: {}", data_dir.display(), e)))?;
+
+        let mut cmd = Command::new(&self.plugin.program);
+        cmd.args(&self.plugin.manifest.args)
+            .current_dir(&self.plugin.dir)
+            .env_clear()
+            .env("HOME", &data_dir)
+            .env("TMPDIR", &data_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .kill_on_drop(true);
+        if let Some(path) = std::env::var_os("PATH") {
+            cmd.env("PATH", path);
+        }
+
+        let mut child = cmd.spawn().map_err(|e| {
+            self.error(format!(
+                "failed to run {}: {}",
+                self.plugin.program.display(),
+                e
+            ))
+        })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(self.error("no stdio pipes"));
+        };
+        let mut running = Running {
+            _child: child,
+            stdin,
+            stdout: BufReader::new(stdout).lines(),
+        };
+
+        let params = serde_json::json!({
+            "protocol_version": PROTOCOL_VERSION,
+            "config": config,
+        });
+        self.exchange(&mut running, "initialize", params)
+            .await?
+            .map_err(|e| self.error(format!("initialize failed: {}", e)))?;
+        info!("Started plugin {}", self.plugin.manifest.id);
+        Ok(running)
+    }
+
+    /// Send one request and wait for its answer. The outer error means
+    /// the program is out of step and must be restarted; the inner one is
+    /// an error the plugin reported. Lines that aren't a response to the
+    /// request are logged and skipped.
+    async fn exchange(
+        &self,
+        running: &mut Running,
+        method: &str,
+        params: Value,
+    ) -> Result<std::result::Result<Value, String>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let mut line = serde_json::to_string(&Request { id, method, params })
+            .map_err(|e| self.error(e.to_string()))?;
+        line.push('\n');
+
+        let answer = async {
+            running
+                .stdin
+                .write_all(line.as_bytes())
+                .await
+                .map_err(|e| self.error(format!("write failed: {}", e)))?;
+            running
+                .stdin
+                .flush()
+                .await
+                .map_err(|e| self.error(format!("write failed: {}", e)))?;
+            loop {
+                let line = running
+                    .stdout
+                    .next_line()
+                    .await
+                    .map_err(|e| self.error(format!("read failed: {}", e)))?
+                    .ok_or_else(|| self.error("exited"))?;
+                match serde_json::from_str::<Response>(&line) {
+                    Ok(response) if response.id == id => return Result::Ok(response),
+                    Ok(response) => warn!(
+                        "Plugin {}: dropping answer to stale request {}",
+                        self.plugin.manifest.id, response.id
+                    ),
+                    Err(_) => warn!("Plugin {}: {}", self.plugin.manifest.id, line),
+                }
+            }
+        };
+
+        let response = tokio::time::timeout(self.timeout(), answer)
+            .await
+            .map_err(|_| self.error(format!("no answer to {} in time", method)))??;
+        Ok(match response.error {
+            Some(error) => Err(error),
+            None => Ok(response.result),
+        })
+    }
+
+    /// Call `method`, starting the program first if needed.
+    pub(super) async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T> {
+        let mut guard = self.running.lock().await;
+        let mut running = match guard.take() {
+            Some(running) => running,
+            None => self.spawn().await?,
+        };
+
+        let answer = match self.exchange(&mut running, method, params).await {
+            Ok(answer) => {
+                *guard = Some(running);
+                answer
+            }
+            Err(e) => {
+                // Dropping `running` kills the program
+                warn!("Restarting plugin {}: {}", self.plugin.manifest.id, e);
+                return Err(e);
+            }
+        };
+        let value = answer.map_err(|e| self.error(e))?;
+        serde_json::from_value(value)
+            .map_err(|e| self.error(format!("unexpected answer to {}: {}", method, e)))
+    }
+}
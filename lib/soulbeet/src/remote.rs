@@ -0,0 +1,262 @@
+//! Uploading an imported library folder to a remote target with rclone.
+//! The local folder acts as a staging area: beets imports into it as usual
+//! and its contents are then moved to the remote, keeping only the beets
+//! library database behind.
+
+use regex::Regex;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// Files of the staging folder that stay local.
+const LOCAL_ONLY: &[&str] = &["/.beets_library.db", "/.soulbeet-staging/**"];
+
+/// Percentage in rclone's one-line stats, e.g.
+/// "1.2 MiB / 10 MiB, 12%, 1 MiB/s, ETA 8s".
+static STATS_PERCENT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r", (\d{1,3})%,").expect("valid stats regex"));
+
+/// Where a remote folder's files are uploaded to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemoteTarget {
+    /// `sftp://[user@]host[:port]/path`, authenticated with the ssh agent or
+    /// the key file rclone is configured with (`RCLONE_SFTP_KEY_FILE`)
+    Sftp {
+        user: Option<String>,
+        host: String,
+        port: Option<u16>,
+        path: String,
+    },
+    /// `remote:path`, a remote from the rclone config file
+    Rclone { remote: String, path: String },
+}
+
+impl RemoteTarget {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        if let Some(rest) = s.strip_prefix("sftp://") {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let (user, host_port) = match authority.rsplit_once('@') {
+                Some((user, host)) => (Some(user.to_string()), host),
+                None => (None, authority),
+            };
+            let (host, port) = match host_port.split_once(':') {
+                Some((host, port)) => (
+                    host,
+                    Some(
+                        port.parse::<u16>()
+                            .map_err(|_| format!("Invalid SFTP port '{}'", port))?,
+                    ),
+                ),
+                None => (host_port, None),
+            };
+            if host.is_empty() {
+                return Err("The SFTP target has no host".to_string());
+            }
+            // Both end up in an rclone connection string, where `,` or `=`
+            // would add backend options (`ssh=` runs a command)
+            if !host
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'))
+            {
+                return Err(format!("Invalid SFTP host '{}'", host));
+            }
+            let user = user.filter(|u| !u.is_empty());
+            if let Some(user) = &user {
+                if !user
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'))
+                {
+                    return Err(format!("Invalid SFTP user '{}'", user));
+                }
+            }
+            return Ok(RemoteTarget::Sftp {
+                user,
+                host: host.to_string(),
+                port,
+                path: format!("/{}", path),
+            });
+        }
+
+        match s.split_once(':') {
+            Some((remote, path))
+                if !remote.is_empty()
+                    && remote
+                        .chars()
+                        .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ' ')) =>
+            {
+                Ok(RemoteTarget::Rclone {
+                    remote: remote.to_string(),
+                    path: path.to_string(),
+                })
+            }
+            _ => Err(format!(
+                "'{}' is not a remote target, expected sftp://user@host/path or remote:path",
+                s
+            )),
+        }
+    }
+
+    /// Destination as an rclone path. SFTP targets become on-the-fly
+    /// remotes so they need no rclone config.
+    fn rclone_destination(&self) -> String {
+        match self {
+            RemoteTarget::Sftp {
+                user,
+                host,
+                port,
+                path,
+            } => {
+                let mut remote = format!(":sftp,host={}", host);
+                if let Some(user) = user {
+                    remote.push_str(&format!(",user={}", user));
+                }
+                if let Some(port) = port {
+                    remote.push_str(&format!(",port={}", port));
+                }
+                format!("{}:{}", remote, path)
+            }
+            RemoteTarget::Rclone { remote, path } => format!("{}:{}", remote, path),
+        }
+    }
+}
+
+impl std::fmt::Display for RemoteTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RemoteTarget::Sftp {
+                user,
+                host,
+                port,
+                path,
+            } => {
+                write!(f, "sftp://")?;
+                if let Some(user) = user {
+                    write!(f, "{}@", user)?;
+                }
+                write!(f, "{}", host)?;
+                if let Some(port) = port {
+                    write!(f, ":{}", port)?;
+                }
+                write!(f, "{}", path)
+            }
+            RemoteTarget::Rclone { remote, path } => write!(f, "{}:{}", remote, path),
+        }
+    }
+}
+
+/// Move everything under `local` to `target`, reporting progress in percent.
+/// Files that fail to upload stay in `local` and are picked up by the next
+/// upload.
+pub async fn upload(
+    local: &Path,
+    target: &RemoteTarget,
+    mut on_progress: impl FnMut(f64),
+) -> Result<(), String> {
+    let rclone = std::env::var("RCLONE_PATH").unwrap_or_else(|_| "rclone".to_string());
+    let mut cmd = Command::new(&rclone);
+    cmd.arg("move")
+        .arg(local)
+        .arg(target.rclone_destination())
+        .arg("--delete-empty-src-dirs")
+        .arg("--stats")
+        .arg("1s")
+        .arg("--stats-one-line")
+        .arg("--stats-log-level")
+        .arg("NOTICE");
+    for pattern in LOCAL_ONLY {
+        cmd.arg("--exclude").arg(pattern);
+    }
+    cmd.stdout(Stdio::null()).stderr(Stdio::piped());
+
+    info!("Uploading {} to {}", local.display(), target);
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", rclone, e))?;
+
+    // Keep the last lines that aren't stats for the error message
+    let mut errors = Vec::new();
+    if let Some(stderr) = child.stderr.take() {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            match STATS_PERCENT
+                .captures(&line)
+                .and_then(|c| c[1].parse::<f64>().ok())
+            {
+                Some(percent) => on_progress(percent.min(100.0)),
+                None if line.contains("ERROR") || line.contains("Failed") => {
+                    warn!("rclone: {}", line);
+                    errors.push(line);
+                }
+                None => {}
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("rclone did not finish: {}", e))?;
+    if status.success() {
+        on_progress(100.0);
+        Ok(())
+    } else {
+        let detail = errors
+            .last()
+            .map(|l| l.rsplit(": ").next().unwrap_or(l).to_string())
+            .unwrap_or_else(|| status.to_string());
+        Err(format!("Upload to {} failed: {}", target, detail))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_sftp_targets() {
+        let target = RemoteTarget::parse("sftp://me@nas:2222/volume1/music").unwrap();
+        assert_eq!(
+            target,
+            RemoteTarget::Sftp {
+                user: Some("me".into()),
+                host: "nas".into(),
+                port: Some(2222),
+                path: "/volume1/music".into(),
+            }
+        );
+        assert_eq!(
+            target.rclone_destination(),
+            ":sftp,host=nas,user=me,port=2222:/volume1/music"
+        );
+        assert!(RemoteTarget::parse("sftp:///music").is_err());
+    }
+
+    #[test]
+    fn refuses_rclone_options_in_sftp_targets() {
+        for target in [
+            "sftp://me@nas,ssh=touch /tmp/x/music",
+            "sftp://me,ssh=touch /tmp/x@nas/music",
+            "sftp://me@nas=x/music",
+            "sftp://me@'nas'/music",
+            "sftp://\"me\"@nas/music",
+        ] {
+            assert!(RemoteTarget::parse(target).is_err(), "{}", target);
+        }
+    }
+
+    #[test]
+    fn parses_rclone_remotes() {
+        assert_eq!(
+            RemoteTarget::parse("nas:music/library").unwrap(),
+            RemoteTarget::Rclone {
+                remote: "nas".into(),
+                path: "music/library".into(),
+            }
+        );
+        assert!(RemoteTarget::parse("/mnt/music").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Renaming downloaded files before import, for importer configurations
+//! that read the track number or title from file names. The cleaning is the
+//! same as the one used to match search results against tracks.
+
+use shared::download::{RenamePreview, RenameRules};
+use shared::matching::{clean_name, leading_track_number};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+use crate::tagging::audio_files;
+
+/// Characters that can't appear in a file name on common filesystems.
+const FORBIDDEN_CHARS: &[char] = &['/', '\\', ':', '*', '?', '"', '<', '>', '|'];
+
+/// Latin letters that don't decompose into a base letter and an accent.
+fn fold_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'Æ' => "AE",
+        'œ' => "oe",
+        'Œ' => "OE",
+        'ø' => "o",
+        'Ø' => "O",
+        'ł' => "l",
+        'Ł' => "L",
+        'đ' => "d",
+        'Đ' => "D",
+        'þ' => "th",
+        'Þ' => "Th",
+        _ => return None,
+    })
+}
+
+/// Fold accented latin letters to ASCII. Other scripts are kept as they
+/// are rather than turned into guesses.
+fn transliterate(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else if let Some(folded) = fold_letter(c) {
+            out.push_str(folded);
+        } else {
+            let decomposed: String = std::iter::once(c)
+                .nfkd()
+                .filter(|d| !unicode_normalization::char::is_combining_mark(*d))
+                .collect();
+            if decomposed.is_empty() {
+                out.push(c);
+            } else {
+                out.push_str(&decomposed);
+            }
+        }
+    }
+    out
+}
+
+fn normalize_separators(s: &str) -> String {
+    let replaced: String = s
+        .chars()
+        .map(|c| match c {
+            '_' => ' ',
+            '‐' | '‑' | '‒' | '–' | '—' | '―' => '-',
+            c => c,
+        })
+        .collect();
+    replaced.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Zero-pad plain track numbers so files sort in order; vinyl sides such as
+/// "A2" are kept.
+fn pad_number(number: &str) -> String {
+    match number.parse::<u32>() {
+        Ok(n) => format!("{:02}", n),
+        Err(_) => number.to_string(),
+    }
+}
+
+/// New name for a file name (with extension) under `rules`.
+pub fn renamed(file_name: &str, rules: &RenameRules) -> String {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+
+    let unscored = stem.replace('_', " ");
+    let number = leading_track_number(&unscored).map(|n| pad_number(&n));
+    let mut title = if rules.strip_track_junk {
+        clean_name(&stem)
+    } else {
+        stem.clone()
+    };
+    if rules.normalize_separators {
+        title = normalize_separators(&title);
+    }
+    if rules.transliterate {
+        title = transliterate(&title);
+    }
+
+    let template = if rules.template.trim().is_empty() {
+        "{title}"
+    } else {
+        rules.template.as_str()
+    };
+    let rendered = template
+        .replace("{number}", number.as_deref().unwrap_or_default())
+        .replace("{title}", &title);
+    // A missing number leaves its separator dangling
+    let rendered: String = rendered
+        .trim_matches(|c: char| c.is_whitespace() || c == '-' || c == '.')
+        .chars()
+        .map(|c| if FORBIDDEN_CHARS.contains(&c) { '_' } else { c })
+        .collect();
+
+    if rendered.is_empty() {
+        file_name.to_string()
+    } else {
+        format!("{}{}", rendered, extension)
+    }
+}
+
+/// Renames `rules` would make under `source` (a folder or a single file).
+/// Files whose new name is taken, by an existing file or an earlier rename,
+/// keep their name.
+pub fn plan_renames(source: &Path, rules: &RenameRules) -> Vec<(PathBuf, PathBuf)> {
+    let mut taken: HashSet<PathBuf> = HashSet::new();
+    let mut plan = Vec::new();
+    for file in audio_files(source) {
+        let Some(name) = file.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let new_name = renamed(&name, rules);
+        if new_name == name {
+            taken.insert(file);
+            continue;
+        }
+        let target = file.with_file_name(&new_name);
+        if target.exists() || !taken.insert(target.clone()) {
+            continue;
+        }
+        plan.push((file, target));
+    }
+    plan
+}
+
+/// Apply `rules` to the audio files under `source`. Returns the renames
+/// made, as (old, new) paths.
+pub fn apply_renames(
+    source: &Path,
+    rules: &RenameRules,
+) -> Result<Vec<(PathBuf, PathBuf)>, String> {
+    let plan = plan_renames(source, rules);
+    for (from, to) in &plan {
+        std::fs::rename(from, to)
+            .map_err(|e| format!("Failed to rename {}: {}", from.display(), e))?;
+    }
+    Ok(plan)
+}
+
+/// Dry run of `rules` over bare file names.
+pub fn preview(file_names: &[String], rules: &RenameRules) -> Vec<RenamePreview> {
+    file_names
+        .iter()
+        .map(|name| RenamePreview {
+            from: name.clone(),
+            to: renamed(name, rules),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules() -> RenameRules {
+        RenameRules {
+            enabled: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strips_junk_and_keeps_the_number() {
+        assert_eq!(
+            renamed("3 - Some_Song  [FLAC].flac", &rules()),
+            "03 - Some Song.flac"
+        );
+        assert_eq!(renamed("B2. Side Two.mp3", &rules()), "B2 - Side Two.mp3");
+    }
+
+    #[test]
+    fn drops_the_separator_without_a_number() {
+        assert_eq!(renamed("Some Song (2004).flac", &rules()), "Some Song.flac");
+    }
+
+    #[test]
+    fn transliterates_latin_only() {
+        let rules = RenameRules {
+            transliterate: true,
+            ..rules()
+        };
+        assert_eq!(
+            renamed("01 - Björk – Jóga.flac", &rules),
+            "01 - Bjork - Joga.flac"
+        );
+        assert_eq!(renamed("01 - 東京.flac", &rules), "01 - 東京.flac");
+    }
+}
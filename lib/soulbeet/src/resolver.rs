@@ -0,0 +1,253 @@
+//! Resolve release links from other sites to a MusicBrainz release.
+//!
+//! Links are first looked up through MusicBrainz URL relationships, which
+//! editors maintain for Discogs, Spotify and Bandcamp. When a page is not
+//! linked, its artist and title are read (from the Discogs API, page
+//! metadata, or the URL itself) and matched with a MusicBrainz album search.
+
+use regex::Regex;
+use reqwest::Client;
+use shared::metadata::SearchResult;
+use std::sync::LazyLock;
+use tracing::{debug, info};
+
+use crate::error::{Result, SoulseekError};
+use crate::http::release_for_external_url;
+use crate::musicbrainz::{search, SearchType};
+
+/// A release page on a supported site.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReleaseLink {
+    /// MusicBrainz release MBID
+    MusicBrainz(String),
+    /// Discogs release id
+    Discogs(String),
+    /// Spotify album id
+    Spotify(String),
+    /// Bandcamp album URL
+    Bandcamp(String),
+    /// RateYourMusic artist and album slugs
+    RateYourMusic { artist: String, album: String },
+}
+
+fn is_mbid(s: &str) -> bool {
+    s.len() == 36 && s.chars().all(|c| c.is_ascii_hexdigit() || c == '-')
+}
+
+/// Path segments of a URL, without scheme, host, query or fragment.
+fn path_segments(url: &str) -> Vec<&str> {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let path = without_scheme.split(['?', '#']).next().unwrap_or_default();
+    path.split('/').skip(1).filter(|s| !s.is_empty()).collect()
+}
+
+fn host(url: &str) -> String {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    without_scheme
+        .split(['/', '?', '#'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+/// Segment following `marker` in the path, e.g. the id after `release`.
+fn segment_after<'a>(segments: &[&'a str], marker: &str) -> Option<&'a str> {
+    segments
+        .iter()
+        .position(|s| *s == marker)
+        .and_then(|i| segments.get(i + 1).copied())
+}
+
+impl ReleaseLink {
+    pub fn parse(url: &str) -> Option<Self> {
+        let url = url.trim();
+        let host = host(url);
+        let segments = path_segments(url);
+
+        if host.ends_with("musicbrainz.org") {
+            return segment_after(&segments, "release")
+                .filter(|id| is_mbid(id))
+                .map(|id| ReleaseLink::MusicBrainz(id.to_string()));
+        }
+        if host.ends_with("discogs.com") {
+            let id: String = segment_after(&segments, "release")?
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            return (!id.is_empty()).then_some(ReleaseLink::Discogs(id));
+        }
+        if host == "open.spotify.com" {
+            return segment_after(&segments, "album")
+                .map(|id| ReleaseLink::Spotify(id.to_string()));
+        }
+        if host.ends_with("bandcamp.com") && segments.first() == Some(&"album") {
+            let clean = url.split(['?', '#']).next().unwrap_or(url);
+            return Some(ReleaseLink::Bandcamp(clean.to_string()));
+        }
+        if host.ends_with("rateyourmusic.com") {
+            // /release/album/<artist>/<album>/
+            let kind_index = segments.iter().position(|s| *s == "release")?;
+            let artist = segments.get(kind_index + 2)?;
+            let album = segments.get(kind_index + 3)?;
+            return Some(ReleaseLink::RateYourMusic {
+                artist: artist.to_string(),
+                album: album.to_string(),
+            });
+        }
+        None
+    }
+
+    /// URL as stored in MusicBrainz URL relationships, when the site has them.
+    fn canonical_url(&self) -> Option<String> {
+        match self {
+            ReleaseLink::Discogs(id) => Some(format!("https://www.discogs.com/release/{}", id)),
+            ReleaseLink::Spotify(id) => Some(format!("https://open.spotify.com/album/{}", id)),
+            ReleaseLink::Bandcamp(url) => Some(url.replacen("http://", "https://", 1)),
+            ReleaseLink::MusicBrainz(_) | ReleaseLink::RateYourMusic { .. } => None,
+        }
+    }
+}
+
+static OG_META: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"<meta[^>]+property="og:(title|description)"[^>]+content="([^"]*)""#)
+        .expect("valid og meta regex")
+});
+
+/// Discogs disambiguates homonyms as "Name (2)".
+static DISCOGS_HOMONYM: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\s\(\d+\)$").expect("valid homonym regex"));
+
+fn decode_entities(s: &str) -> String {
+    s.replace("&amp;", "&")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+}
+
+/// `og:title` and `og:description` of a page.
+async fn page_metadata(client: &Client, url: &str) -> Result<(String, String)> {
+    let html = client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await?;
+    let mut title = String::new();
+    let mut description = String::new();
+    for caps in OG_META.captures_iter(&html) {
+        let value = decode_entities(&caps[2]);
+        match &caps[1] {
+            "title" => title = value,
+            _ => description = value,
+        }
+    }
+    Ok((title, description))
+}
+
+/// "some-album-name_2" -> "some album name"
+fn unslug(slug: &str) -> String {
+    let base = slug.split('_').next().unwrap_or(slug);
+    base.replace('-', " ")
+}
+
+/// Artist and album title shown on the linked page.
+async fn release_names(client: &Client, link: &ReleaseLink) -> Result<Option<(String, String)>> {
+    match link {
+        ReleaseLink::MusicBrainz(_) => Ok(None),
+        ReleaseLink::Discogs(id) => {
+            #[derive(serde::Deserialize)]
+            struct DiscogsRelease {
+                title: String,
+                #[serde(default)]
+                artists: Vec<DiscogsArtist>,
+            }
+            #[derive(serde::Deserialize)]
+            struct DiscogsArtist {
+                name: String,
+            }
+
+            let release: DiscogsRelease = client
+                .get(format!("https://api.discogs.com/releases/{}", id))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let artist = release
+                .artists
+                .first()
+                .map(|a| DISCOGS_HOMONYM.replace(&a.name, "").to_string())
+                .unwrap_or_default();
+            Ok(Some((artist, release.title)))
+        }
+        ReleaseLink::Spotify(id) => {
+            // og:description reads "Artist · Album · 2020 · 12 songs."
+            let (title, description) =
+                page_metadata(client, &format!("https://open.spotify.com/album/{}", id)).await?;
+            let artist = description.split(" · ").next().unwrap_or_default().to_string();
+            Ok(Some((artist, title)))
+        }
+        ReleaseLink::Bandcamp(url) => {
+            // og:title reads "Album, by Artist"
+            let (title, _) = page_metadata(client, url).await?;
+            Ok(title
+                .rsplit_once(", by ")
+                .map(|(album, artist)| (artist.to_string(), album.to_string())))
+        }
+        ReleaseLink::RateYourMusic { artist, album } => {
+            // RYM pages sit behind bot protection; the slugs are enough to search
+            Ok(Some((unslug(artist), unslug(album))))
+        }
+    }
+}
+
+/// Resolve a release link to a MusicBrainz release id.
+pub async fn resolve_release(client: &Client, url: &str) -> Result<String> {
+    let link = ReleaseLink::parse(url).ok_or_else(|| SoulseekError::Api {
+        status: 400,
+        message: "Unsupported link; paste a MusicBrainz, Discogs, Spotify, Bandcamp or \
+                  RateYourMusic release URL"
+            .to_string(),
+    })?;
+
+    if let ReleaseLink::MusicBrainz(id) = &link {
+        return Ok(id.clone());
+    }
+
+    if let Some(canonical) = link.canonical_url() {
+        match release_for_external_url(client, &canonical).await {
+            Ok(Some(id)) => {
+                info!("Resolved {} through MusicBrainz relationships", canonical);
+                return Ok(id);
+            }
+            Ok(None) => debug!("{} is not linked on MusicBrainz", canonical),
+            Err(e) => debug!("URL relationship lookup failed for {}: {}", canonical, e),
+        }
+    }
+
+    let (artist, title) = release_names(client, &link)
+        .await?
+        .filter(|(_, title)| !title.trim().is_empty())
+        .ok_or_else(|| SoulseekError::Api {
+            status: 404,
+            message: "Could not read the release from that page".to_string(),
+        })?;
+
+    info!("Matching '{}' by '{}' on MusicBrainz", title, artist);
+    let artist = Some(artist).filter(|a| !a.trim().is_empty());
+    let results = search(&artist, &title, SearchType::Album, 5).await?;
+
+    results
+        .into_iter()
+        .find_map(|r| match r {
+            SearchResult::Album(album) => Some(album.id),
+            SearchResult::Track(_) => None,
+        })
+        .ok_or_else(|| SoulseekError::Api {
+            status: 404,
+            message: format!("No MusicBrainz release found for '{}'", title),
+        })
+}
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use shared::features::FeatureFlags;
+
 use crate::{DownloadBackend, MetadataProvider, MusicImporter};
 
 pub struct Services {
@@ -10,6 +12,7 @@ pub struct Services {
     default_metadata: Option<String>,
     default_download: Option<String>,
     default_importer: Option<String>,
+    features: FeatureFlags,
 }
 
 impl Services {
@@ -39,6 +42,11 @@ impl Services {
     pub fn list_importers(&self) -> Vec<(&str, &str)> {
         self.importer.values().map(|p| (p.id(), p.name())).collect()
     }
+
+    /// The feature flags these services were built with.
+    pub fn features(&self) -> &FeatureFlags {
+        &self.features
+    }
 }
 
 pub struct ServicesBuilder {
@@ -48,6 +56,7 @@ pub struct ServicesBuilder {
     default_metadata: Option<String>,
     default_download: Option<String>,
     default_importer: Option<String>,
+    features: FeatureFlags,
 }
 
 impl ServicesBuilder {
@@ -59,9 +68,17 @@ impl ServicesBuilder {
             default_metadata: None,
             default_download: None,
             default_importer: None,
+            features: FeatureFlags::default(),
         }
     }
 
+    /// The flags deciding which experimental services get registered. Set
+    /// this before adding any of them.
+    pub fn features(mut self, features: FeatureFlags) -> Self {
+        self.features = features;
+        self
+    }
+
     pub fn add_metadata(mut self, provider: impl MetadataProvider + 'static) -> Self {
         let id = provider.id().to_string();
         if self.default_metadata.is_none() {
@@ -89,6 +106,41 @@ impl ServicesBuilder {
         self
     }
 
+    /// Add the Spotify provider, only if its client credentials are set
+    /// (`SPOTIFY_CLIENT_ID` and `SPOTIFY_CLIENT_SECRET`).
+    pub fn add_spotify_from_env(self) -> Self {
+        match crate::SpotifyProvider::from_env() {
+            Some(provider) => self.add_metadata(provider),
+            None => self,
+        }
+    }
+
+    /// Add an experimental download backend, only if `flag` is enabled.
+    pub fn add_experimental_download(
+        self,
+        flag: &str,
+        backend: impl DownloadBackend + 'static,
+    ) -> Self {
+        if self.features.is_enabled(flag) {
+            self.add_download(backend)
+        } else {
+            self
+        }
+    }
+
+    /// Add an experimental importer, only if `flag` is enabled.
+    pub fn add_experimental_importer(
+        self,
+        flag: &str,
+        importer: impl MusicImporter + 'static,
+    ) -> Self {
+        if self.features.is_enabled(flag) {
+            self.add_importer(importer)
+        } else {
+            self
+        }
+    }
+
     pub fn default_metadata(mut self, id: &str) -> Self {
         self.default_metadata = Some(id.to_string());
         self
@@ -116,6 +168,7 @@ impl ServicesBuilder {
             default_metadata: self.default_metadata,
             default_download: self.default_download,
             default_importer: self.default_importer,
+            features: self.features,
         })
     }
 }
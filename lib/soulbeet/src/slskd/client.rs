@@ -8,18 +8,49 @@ use chrono::{DateTime, Duration, Utc};
 use reqwest::{Client, Method, Response};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use shared::{
+    download::SearchOptions,
     metadata::{Album, Track},
+    quality::{diversify, SearchFilters},
     slskd::{AlbumResult, DownloadResponse, FileEntry, FlattenedFiles, SearchState, TrackResult},
 };
-use std::{collections::HashMap, sync::Arc, time::Duration as StdDuration};
+use std::{
+    collections::HashMap,
+    sync::{Arc, LazyLock},
+    time::Duration as StdDuration,
+};
 use tokio::sync::Mutex;
 use tracing::{debug, info, warn};
 use url::Url;
 
 const MAX_SEARCH_RESULTS: usize = 50;
 
+/// How long starting a search waits for the artist's aliases
+const ALIAS_LOOKUP_TIMEOUT_SECS: u64 = 5;
+
+/// Least time left on a search for it to be made again with another query
+const MIN_FALLBACK_SEARCH_SECS: i64 = 10;
+
 const HTTP_CONNECT_TIMEOUT_SECS: u64 = 10;
 const HTTP_REQUEST_TIMEOUT_SECS: u64 = 30;
+/// Monitors, searches and the activity feed all poll slskd every few
+/// seconds, so keep enough idle connections around to not reconnect.
+const HTTP_POOL_MAX_IDLE_PER_HOST: usize = 16;
+const HTTP_POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+const HTTP_TCP_KEEPALIVE_SECS: u64 = 30;
+
+/// Connection pool shared by every slskd client, so a backend rebuilt after
+/// a settings change keeps the warm connections. Request timeouts are set
+/// per request.
+static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
+    Client::builder()
+        .connect_timeout(StdDuration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
+        .pool_max_idle_per_host(HTTP_POOL_MAX_IDLE_PER_HOST)
+        .pool_idle_timeout(StdDuration::from_secs(HTTP_POOL_IDLE_TIMEOUT_SECS))
+        .tcp_keepalive(StdDuration::from_secs(HTTP_TCP_KEEPALIVE_SECS))
+        .tcp_nodelay(true)
+        .build()
+        .expect("failed to build slskd HTTP client")
+});
 
 /// Configuration for download batching to avoid overwhelming the slskd API.
 #[derive(Debug, Clone)]
@@ -53,6 +84,50 @@ struct SearchContext {
     start_time: DateTime<Utc>,
     timeout: Duration,
     seen_response_count: usize,
+    options: SearchOptions,
+    /// (slskd search id, artist name) of the searches for the artist's
+    /// other names, polled and merged along with this one
+    alias_searches: Vec<(String, String)>,
+    /// slskd search polled for the release. Starts as the id the search is
+    /// known by, and changes when the search is made again with another
+    /// query.
+    slskd_id: String,
+    /// Text of the slskd search polled
+    query: String,
+    /// Search texts still to try, in order, if the current one gets no
+    /// results
+    fallback_queries: Vec<String>,
+}
+
+impl SearchContext {
+    fn poll(
+        &self,
+        albums: Vec<AlbumResult>,
+        has_more: bool,
+        state: SearchState,
+        responses: usize,
+    ) -> SearchPoll {
+        SearchPoll {
+            albums,
+            has_more,
+            state,
+            responses,
+            query: Some(self.query.clone()),
+        }
+    }
+}
+
+/// What a poll of a search found so far.
+#[derive(Debug)]
+pub struct SearchPoll {
+    pub albums: Vec<AlbumResult>,
+    pub has_more: bool,
+    pub state: SearchState,
+    /// Peers that answered the current search text, the artist's other
+    /// names included, whether or not their files matched
+    pub responses: usize,
+    /// The current search text, unless the search is unknown
+    pub query: Option<String>,
 }
 
 #[derive(Debug)]
@@ -60,6 +135,9 @@ pub struct SoulseekClient {
     base_url: Url,
     api_key: Option<String>,
     client: Client,
+    /// Limit for a whole request, response body included, so a hung
+    /// socket can't stall a caller
+    request_timeout: StdDuration,
     search_timestamps: Arc<Mutex<Vec<DateTime<Utc>>>>,
     active_searches: Arc<Mutex<HashMap<String, SearchContext>>>,
     max_searches_per_window: usize,
@@ -75,6 +153,7 @@ pub struct SoulseekClientBuilder {
     max_searches_per_window: Option<usize>,
     rate_limit_window_seconds: Option<i64>,
     download_config: Option<DownloadConfig>,
+    request_timeout: Option<StdDuration>,
 }
 
 impl SoulseekClientBuilder {
@@ -104,25 +183,22 @@ impl SoulseekClientBuilder {
         self
     }
 
+    pub fn request_timeout(mut self, timeout: StdDuration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
     pub fn build(self) -> Result<SoulseekClient> {
         let base_url_str = self.base_url.ok_or(SoulseekError::NotConfigured)?;
         let base_url = Url::parse(base_url_str.trim_end_matches('/'))?;
 
-        // Build HTTP client with proper timeouts
-        let client = Client::builder()
-            .connect_timeout(StdDuration::from_secs(HTTP_CONNECT_TIMEOUT_SECS))
-            .timeout(StdDuration::from_secs(HTTP_REQUEST_TIMEOUT_SECS))
-            .pool_idle_timeout(StdDuration::from_secs(90))
-            .build()
-            .map_err(|e| SoulseekError::Api {
-                status: 0,
-                message: format!("Failed to build HTTP client: {}", e),
-            })?;
-
         Ok(SoulseekClient {
             base_url,
             api_key: self.api_key,
-            client,
+            client: HTTP_CLIENT.clone(),
+            request_timeout: self
+                .request_timeout
+                .unwrap_or(StdDuration::from_secs(HTTP_REQUEST_TIMEOUT_SECS)),
             search_timestamps: Arc::new(Mutex::new(Vec::new())),
             active_searches: Arc::new(Mutex::new(HashMap::new())),
             max_searches_per_window: self.max_searches_per_window.unwrap_or(35),
@@ -160,7 +236,10 @@ impl SoulseekClient {
 
             let url = self.base_url.join(&format!("api/v0/{endpoint}"))?;
             debug!("Request: {} {} (attempt {})", method, url, attempt + 1);
-            let mut request = self.client.request(method.clone(), url);
+            let mut request = self
+                .client
+                .request(method.clone(), url)
+                .timeout(self.request_timeout);
             if let Some(key) = &self.api_key {
                 request = request.header("X-API-Key", key);
             }
@@ -283,31 +362,26 @@ impl SoulseekClient {
         Ok(())
     }
 
-    pub async fn start_search(
+    /// Take a search slot if one is free right now, without waiting.
+    async fn try_take_search_slot(&self) -> bool {
+        let mut timestamps = self.search_timestamps.lock().await;
+        let now = Utc::now();
+        let window_start = now - self.rate_limit_window;
+        timestamps.retain(|&ts| ts > window_start);
+        if timestamps.len() >= self.max_searches_per_window {
+            return false;
+        }
+        timestamps.push(now);
+        true
+    }
+
+    /// Create a search on slskd and return its id.
+    async fn post_search(
         &self,
-        album: Option<Album>,
-        tracks: Vec<Track>,
+        query: &str,
         timeout: Duration,
+        filters: &SearchFilters,
     ) -> Result<String> {
-        self.wait_for_rate_limit().await?;
-
-        let track_titles: Vec<String> = tracks.iter().map(|t| t.title.clone()).collect();
-
-        let query = match album {
-            Some(ref album) => match tracks.len() {
-                1 => format!("{} {}", album.artist.trim(), tracks[0].title.trim()),
-                _ => format!("{} {}", album.artist.trim(), album.title.trim()),
-            },
-            // No album, should be a single track search
-            None => format!("{} {}", tracks[0].artist.trim(), tracks[0].title.trim()),
-        };
-
-        info!(
-            "Starting search for: '{}' with timeout {}ms",
-            query,
-            timeout.num_milliseconds()
-        );
-
         #[derive(Serialize)]
         #[serde(rename_all = "camelCase")]
         struct SearchRequest<'a> {
@@ -315,12 +389,15 @@ impl SoulseekClient {
             timeout: i64,
             filter_responses: bool,
             minimum_peer_upload_speed: u32,
+            #[serde(skip_serializing_if = "Option::is_none")]
+            minimum_response_file_count: Option<u32>,
         }
         let request_body = SearchRequest {
-            search_text: &query,
+            search_text: query,
             timeout: timeout.num_milliseconds(),
             filter_responses: true,
             minimum_peer_upload_speed: 10,
+            minimum_response_file_count: filters.min_response_files,
         };
 
         #[derive(Deserialize)]
@@ -330,20 +407,106 @@ impl SoulseekClient {
         let search_id_resp: SearchId = self
             .make_request(Method::POST, "searches", Some(&request_body))
             .await?;
-        let search_id = search_id_resp.id;
+        Ok(search_id_resp.id)
+    }
+
+    /// Search the release again under the other names its artist is known
+    /// by on MusicBrainz. Only free rate limit slots are used, and a slow or
+    /// failed alias lookup just means no extra searches. Returns the
+    /// (search id, artist name) of each search started.
+    async fn start_alias_searches(
+        &self,
+        artist: &str,
+        subject: &str,
+        timeout: Duration,
+        filters: &SearchFilters,
+    ) -> Vec<(String, String)> {
+        let lookup = tokio::time::timeout(
+            StdDuration::from_secs(ALIAS_LOOKUP_TIMEOUT_SECS),
+            crate::musicbrainz::artist_aliases(artist),
+        )
+        .await;
+        let aliases = match lookup {
+            Ok(Ok(aliases)) => aliases,
+            Ok(Err(e)) => {
+                debug!("Alias lookup for '{}' failed: {}", artist, e);
+                return Vec::new();
+            }
+            Err(_) => {
+                debug!("Alias lookup for '{}' timed out", artist);
+                return Vec::new();
+            }
+        };
+
+        let mut searches = Vec::new();
+        for alias in aliases {
+            if !self.try_take_search_slot().await {
+                debug!("No free search slot left for alias '{}'", alias);
+                break;
+            }
+            let query = format!("{} {}", alias.trim(), subject);
+            match self.post_search(&query, timeout, filters).await {
+                Ok(id) => {
+                    info!("Alias search for: '{}' with ID: {}", query, id);
+                    searches.push((id, alias));
+                }
+                Err(e) => warn!("Alias search for '{}' failed: {}", query, e),
+            }
+        }
+        searches
+    }
+
+    pub async fn start_search(
+        &self,
+        album: Option<Album>,
+        tracks: Vec<Track>,
+        timeout: Duration,
+        options: SearchOptions,
+    ) -> Result<String> {
+        self.wait_for_rate_limit().await?;
+
+        let track_titles: Vec<String> = tracks.iter().map(|t| t.title.clone()).collect();
+
+        let (artist, subject) = match album {
+            Some(ref album) => match tracks.len() {
+                1 => (album.artist.clone(), tracks[0].title.trim()),
+                _ => (album.artist.clone(), album.title.trim()),
+            },
+            // No album, should be a single track search
+            None => (tracks[0].artist.clone(), tracks[0].title.trim()),
+        };
+        let year = album
+            .as_ref()
+            .and_then(|a| a.release_date.as_deref())
+            .and_then(|d| d.get(..4));
+        let mut queries = options.strategy.queries(&artist, subject, year);
+        let query = queries.remove(0);
+
+        info!(
+            "Starting search for: '{}' with timeout {}ms",
+            query,
+            timeout.num_milliseconds()
+        );
+
+        let search_id = self.post_search(&query, timeout, &options.filters).await?;
+        let alias_searches = self
+            .start_alias_searches(&artist, subject, timeout, &options.filters)
+            .await;
 
         self.active_searches.lock().await.insert(
             search_id.clone(),
             SearchContext {
                 album: album.as_ref().map(|a| a.title.clone()),
-                artist: album
-                    .as_ref()
-                    .map(|a| a.artist.clone())
-                    .unwrap_or_else(|| tracks[0].artist.clone()),
+                artist,
                 track_titles,
                 start_time: Utc::now(),
                 timeout,
                 seen_response_count: 0,
+                options,
+                alias_searches,
+                slskd_id: search_id.clone(),
+                query,
+                fallback_queries: queries,
             },
         );
 
@@ -351,10 +514,120 @@ impl SoulseekClient {
         Ok(search_id)
     }
 
-    pub async fn poll_search(
-        &self,
-        search_id: String,
-    ) -> Result<(Vec<AlbumResult>, bool, SearchState)> {
+    /// Responses so far to the alias searches of a search, with the artist
+    /// name each was made under. A failing alias search counts as empty.
+    async fn alias_responses(&self, context: &SearchContext) -> Vec<(String, Vec<SearchResponse>)> {
+        let mut responses = Vec::with_capacity(context.alias_searches.len());
+        for (id, artist) in &context.alias_searches {
+            let endpoint = format!("searches/{}/responses", id);
+            let found = self
+                .make_request::<Vec<SearchResponse>, ()>(Method::GET, &endpoint, None)
+                .await
+                .unwrap_or_else(|e| {
+                    debug!("Alias search {} poll failed: {}", id, e);
+                    Vec::new()
+                });
+            responses.push((artist.clone(), found));
+        }
+        responses
+    }
+
+    /// Score the responses of a search and its alias searches as one result
+    /// set, each against the artist name it was searched under.
+    fn search_results(
+        context: &SearchContext,
+        responses: &[SearchResponse],
+        alias_responses: &[(String, Vec<SearchResponse>)],
+    ) -> Vec<AlbumResult> {
+        let track_titles_ref: Vec<&str> = context.track_titles.iter().map(|s| s.as_str()).collect();
+        let process = |artist: &str, responses: &[SearchResponse]| {
+            processing::process_search_responses(
+                responses,
+                artist,
+                context.album.as_deref(),
+                &track_titles_ref,
+                context.options.min_score,
+                &context.options.filters,
+            )
+        };
+        let mut sets = vec![process(&context.artist, responses)];
+        sets.extend(
+            alias_responses
+                .iter()
+                .map(|(artist, responses)| process(artist.as_str(), responses.as_slice())),
+        );
+        diversify(
+            processing::merge_album_results(sets),
+            &context.options.diversity,
+        )
+    }
+
+    /// Whether slskd is done with a search and all its alias searches.
+    async fn all_searches_complete(&self, search_id: &str, context: &SearchContext) -> bool {
+        if !self.is_search_complete(search_id).await {
+            return false;
+        }
+        for (id, _) in &context.alias_searches {
+            if !self.is_search_complete(id).await {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Forget a search and delete it, with its alias searches, from slskd.
+    async fn finish_search(&self, search_id: &str, context: &SearchContext) {
+        self.active_searches.lock().await.remove(search_id);
+        let _ = self.delete_search(&context.slskd_id).await;
+        for (id, _) in &context.alias_searches {
+            let _ = self.delete_search(id).await;
+        }
+    }
+
+    /// Search again with the next fallback query after the current one
+    /// ended without results. Returns whether a new search was started;
+    /// there must be enough of the timeout left for it to be worth it.
+    async fn search_next_query(&self, search_id: &str, context: &SearchContext) -> bool {
+        let Some(query) = context.fallback_queries.first() else {
+            return false;
+        };
+        let remaining = context.timeout - (Utc::now() - context.start_time);
+        if remaining < Duration::seconds(MIN_FALLBACK_SEARCH_SECS) {
+            return false;
+        }
+        if self.wait_for_rate_limit().await.is_err() {
+            return false;
+        }
+        let _ = self.delete_search(&context.slskd_id).await;
+        for (id, _) in &context.alias_searches {
+            let _ = self.delete_search(id).await;
+        }
+
+        match self
+            .post_search(query, remaining, &context.options.filters)
+            .await
+        {
+            Ok(id) => {
+                info!(
+                    "Search {} found nothing, searching for '{}' with ID: {}",
+                    search_id, query, id
+                );
+                if let Some(ctx) = self.active_searches.lock().await.get_mut(search_id) {
+                    ctx.slskd_id = id;
+                    ctx.query = ctx.fallback_queries.remove(0);
+                    ctx.alias_searches.clear();
+                    ctx.seen_response_count = 0;
+                }
+                true
+            }
+            Err(e) => {
+                warn!("Fallback search for '{}' failed: {}", query, e);
+                false
+            }
+        }
+    }
+
+    pub async fn poll_search(&self, search_id: String) -> Result<SearchPoll> {
         let poll_start = Utc::now();
         // Long-poll duration: hold the request for up to 10 seconds waiting for new data
         let long_poll_timeout = Duration::seconds(10);
@@ -367,23 +640,37 @@ impl SoulseekClient {
 
             let context = match context {
                 Some(ctx) => ctx,
-                None => return Ok((vec![], false, SearchState::NotFound)),
+                None => {
+                    return Ok(SearchPoll {
+                        albums: vec![],
+                        has_more: false,
+                        state: SearchState::NotFound,
+                        responses: 0,
+                        query: None,
+                    })
+                }
             };
 
             if (Utc::now() - context.start_time) >= context.timeout {
                 info!("Search timeout reached");
-                self.active_searches.lock().await.remove(&search_id);
-                let _ = self.delete_search(&search_id).await;
-                return Ok((vec![], false, SearchState::Completed));
+                self.finish_search(&search_id, &context).await;
+                return Ok(context.poll(
+                    vec![],
+                    false,
+                    SearchState::Completed,
+                    context.seen_response_count,
+                ));
             }
 
-            let endpoint = format!("searches/{}/responses", search_id);
+            let endpoint = format!("searches/{}/responses", context.slskd_id);
             match self
                 .make_request::<Vec<SearchResponse>, ()>(Method::GET, &endpoint, None)
                 .await
             {
                 Ok(current_responses) => {
-                    let total_len = current_responses.len();
+                    let alias_responses = self.alias_responses(&context).await;
+                    let total_len = current_responses.len()
+                        + alias_responses.iter().map(|(_, r)| r.len()).sum::<usize>();
 
                     if total_len > context.seen_response_count {
                         // Update seen count
@@ -394,60 +681,65 @@ impl SoulseekClient {
                             }
                         }
 
-                        let track_titles_ref: Vec<&str> =
-                            context.track_titles.iter().map(|s| s.as_str()).collect();
-                        let mut albums = processing::process_search_responses(
-                            &current_responses,
-                            &context.artist,
-                            context.album.as_deref(),
-                            &track_titles_ref,
-                        );
-
-                        albums.sort_by(|a, b| {
-                            b.score
-                                .partial_cmp(&a.score)
-                                .unwrap_or(std::cmp::Ordering::Equal)
-                        });
+                        let mut albums =
+                            Self::search_results(&context, &current_responses, &alias_responses);
 
                         if albums.len() > MAX_SEARCH_RESULTS {
                             albums.truncate(MAX_SEARCH_RESULTS);
-                            self.active_searches.lock().await.remove(&search_id);
-                            let _ = self.delete_search(&search_id).await;
-                            return Ok((albums, false, SearchState::Completed));
+                            self.finish_search(&search_id, &context).await;
+                            return Ok(context.poll(
+                                albums,
+                                false,
+                                SearchState::Completed,
+                                total_len,
+                            ));
                         } else {
-                            return Ok((albums, true, SearchState::InProgress));
+                            return Ok(context.poll(
+                                albums,
+                                true,
+                                SearchState::InProgress,
+                                total_len,
+                            ));
                         }
                     } else {
                         // No new data. slskd ends a search a short while after
                         // responses stop arriving; once it reports completion
                         // there is nothing more to wait for, so return what we
-                        // have instead of spinning until our own 120s timeout.
-                        if self.is_search_complete(&search_id).await {
-                            self.active_searches.lock().await.remove(&search_id);
-                            let _ = self.delete_search(&search_id).await;
-
-                            let track_titles_ref: Vec<&str> =
-                                context.track_titles.iter().map(|s| s.as_str()).collect();
-                            let mut albums = processing::process_search_responses(
+                        // have instead of spinning until our own timeout.
+                        if self
+                            .all_searches_complete(&context.slskd_id, &context)
+                            .await
+                        {
+                            let mut albums = Self::search_results(
+                                &context,
                                 &current_responses,
-                                &context.artist,
-                                context.album.as_deref(),
-                                &track_titles_ref,
+                                &alias_responses,
                             );
-                            albums.sort_by(|a, b| {
-                                b.score
-                                    .partial_cmp(&a.score)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            });
+                            if albums.is_empty()
+                                && self.search_next_query(&search_id, &context).await
+                            {
+                                continue;
+                            }
+                            self.finish_search(&search_id, &context).await;
                             albums.truncate(MAX_SEARCH_RESULTS);
 
                             info!("Search {} completed on slskd side", search_id);
-                            return Ok((albums, false, SearchState::Completed));
+                            return Ok(context.poll(
+                                albums,
+                                false,
+                                SearchState::Completed,
+                                total_len,
+                            ));
                         }
 
                         if (Utc::now() - poll_start) > long_poll_timeout {
                             // Long poll expired, return "no update" but "in progress"
-                            return Ok((vec![], true, SearchState::InProgress));
+                            return Ok(context.poll(
+                                vec![],
+                                true,
+                                SearchState::InProgress,
+                                total_len,
+                            ));
                         }
 
                         // Wait a bit before retrying slskd
@@ -456,14 +748,18 @@ impl SoulseekClient {
                     }
                 }
                 Err(SoulseekError::Api { status: 404, .. }) => {
-                    self.active_searches.lock().await.remove(&search_id);
+                    self.finish_search(&search_id, &context).await;
                     info!("Search 404");
-                    return Ok((vec![], false, SearchState::NotFound));
+                    return Ok(context.poll(
+                        vec![],
+                        false,
+                        SearchState::NotFound,
+                        context.seen_response_count,
+                    ));
                 }
                 Err(e) => {
                     // Clean up search context on any error to prevent leaks
-                    self.active_searches.lock().await.remove(&search_id);
-                    let _ = self.delete_search(&search_id).await;
+                    self.finish_search(&search_id, &context).await;
                     warn!("Search {} failed with error, cleaning up: {}", search_id, e);
                     return Err(e);
                 }
@@ -523,7 +819,7 @@ impl SoulseekClient {
     async fn download_batch_with_retry(
         &self,
         username: &str,
-        batch: Vec<DownloadRequestFile>,
+        mut batch: Vec<DownloadRequestFile>,
         batch_idx: usize,
     ) -> Vec<DownloadResponse> {
         let config = &self.download_config;
@@ -531,6 +827,8 @@ impl SoulseekClient {
         const MAX_BACKOFF_MS: u64 = 30_000;
 
         let mut last_error: Option<SoulseekError> = None;
+        // Files settled in earlier attempts, queued or refused for good
+        let mut settled: Vec<DownloadResponse> = Vec::new();
 
         for attempt in 0..=config.max_retries {
             if attempt > 0 {
@@ -546,7 +844,24 @@ impl SoulseekClient {
             }
 
             match self.send_download_batch(username, &batch, batch_idx).await {
-                Ok(responses) => return responses,
+                Ok(responses) => {
+                    let (transient, done): (Vec<_>, Vec<_>) = responses
+                        .into_iter()
+                        .partition(|r| r.reason().is_some_and(|r| r.is_retryable()));
+                    settled.extend(done);
+                    batch.retain(|f| transient.iter().any(|r| r.filename == f.filename));
+                    if batch.is_empty() || attempt == config.max_retries {
+                        settled.extend(transient);
+                        return settled;
+                    }
+                    // Refusals that will not change are kept, only the rest is asked again
+                    warn!(
+                        "Batch {} for '{}': {} files failed to enqueue, retrying them",
+                        batch_idx,
+                        username,
+                        batch.len()
+                    );
+                }
                 Err(e) => {
                     warn!(
                         "Batch {} for '{}' attempt {} failed: {}",
@@ -558,15 +873,13 @@ impl SoulseekClient {
                             "Batch {} for '{}': error is non-retryable, stopping",
                             batch_idx, username
                         );
-                        return batch
-                            .iter()
-                            .map(|f| DownloadResponse {
-                                username: username.to_string(),
-                                filename: f.filename.clone(),
-                                size: f.size as u64,
-                                error: Some(e.to_string()),
-                            })
-                            .collect();
+                        settled.extend(batch.iter().map(|f| DownloadResponse {
+                            username: username.to_string(),
+                            filename: f.filename.clone(),
+                            size: f.size as u64,
+                            error: Some(e.to_string()),
+                        }));
+                        return settled;
                     }
                     last_error = Some(e);
                 }
@@ -583,15 +896,13 @@ impl SoulseekClient {
             batch_idx, username, error_msg
         );
 
-        batch
-            .iter()
-            .map(|f| DownloadResponse {
-                username: username.to_string(),
-                filename: f.filename.clone(),
-                size: f.size as u64,
-                error: Some(error_msg.clone()),
-            })
-            .collect()
+        settled.extend(batch.iter().map(|f| DownloadResponse {
+            username: username.to_string(),
+            filename: f.filename.clone(),
+            size: f.size as u64,
+            error: Some(error_msg.clone()),
+        }));
+        settled
     }
 
     async fn send_download_batch(
@@ -952,6 +1263,60 @@ impl SoulseekClient {
             .await
     }
 
+    /// Remove every finished transfer (succeeded or failed) from slskd's
+    /// download list.
+    pub async fn clear_all_completed_downloads(&self) -> Result<()> {
+        info!("Clearing all completed downloads");
+        self.make_request(
+            Method::DELETE,
+            "transfers/downloads/all/completed",
+            None::<()>,
+        )
+        .await
+    }
+
+    /// Remove finished transfers that ended more than `older_than` ago.
+    /// Returns how many were removed. Transfers still in flight are kept.
+    pub async fn prune_downloads(&self, older_than: Duration) -> Result<usize> {
+        if older_than <= Duration::zero() {
+            let finished = self
+                .get_all_downloads()
+                .await?
+                .iter()
+                .filter(|e| e.is_finished())
+                .count();
+            self.clear_all_completed_downloads().await?;
+            return Ok(finished);
+        }
+
+        let cutoff = Utc::now() - older_than;
+        let stale: Vec<FileEntry> = self
+            .get_all_downloads()
+            .await?
+            .into_iter()
+            .filter(|e| e.is_finished() && e.finished_at().is_some_and(|t| t <= cutoff))
+            .collect();
+
+        let mut removed = 0;
+        for entry in &stale {
+            let endpoint = format!(
+                "transfers/downloads/{}/{}?remove=true",
+                entry.username, entry.id
+            );
+            match self
+                .make_request::<(), ()>(Method::DELETE, &endpoint, None)
+                .await
+            {
+                Ok(()) | Err(SoulseekError::Api { status: 404, .. }) => removed += 1,
+                Err(e) => warn!("Failed to remove transfer {}: {}", entry.filename, e),
+            }
+        }
+        if removed > 0 {
+            info!("Removed {} finished transfer(s) from slskd", removed);
+        }
+        Ok(removed)
+    }
+
     pub async fn delete_search(&self, search_id: &str) -> Result<()> {
         let endpoint = format!("searches/{search_id}");
         debug!("Deleting search {}", search_id);
@@ -1068,18 +1433,30 @@ impl crate::DownloadBackend for SoulseekClient {
     }
 
     async fn start_search(&self, album: Option<&Album>, tracks: &[Track]) -> Result<String> {
-        let timeout = Duration::seconds(120);
-        self.start_search(album.cloned(), tracks.to_vec(), timeout)
+        self.start_search_with_options(album, tracks, &SearchOptions::default())
+            .await
+    }
+
+    async fn start_search_with_options(
+        &self,
+        album: Option<&Album>,
+        tracks: &[Track],
+        options: &SearchOptions,
+    ) -> Result<String> {
+        let timeout = Duration::seconds(options.timeout_secs.into());
+        self.start_search(album.cloned(), tracks.to_vec(), timeout, *options)
             .await
     }
 
     async fn poll_search(&self, search_id: &str) -> Result<shared::download::SearchResult> {
-        let (results, has_more, state) = self.poll_search(search_id.to_string()).await?;
+        let poll = self.poll_search(search_id.to_string()).await?;
         Ok(shared::download::SearchResult {
             search_id: search_id.to_string(),
-            groups: results.into_iter().map(Into::into).collect(),
-            has_more,
-            state: state.into(),
+            groups: poll.albums.into_iter().map(Into::into).collect(),
+            has_more: poll.has_more,
+            state: poll.state.into(),
+            responses: poll.responses as u32,
+            search_text: poll.query,
         })
     }
 
@@ -1110,6 +1487,15 @@ impl crate::DownloadBackend for SoulseekClient {
         self.cancel_download(username, download_id, remove).await
     }
 
+    async fn breaker_state(&self) -> shared::system::BreakerState {
+        self.circuit_breaker.state().await
+    }
+
+    async fn prune_transfers(&self, older_than: StdDuration) -> Result<usize> {
+        let older_than = Duration::from_std(older_than).unwrap_or_else(|_| Duration::days(36500));
+        self.prune_downloads(older_than).await
+    }
+
     async fn health_check(&self) -> bool {
         match self.check_connection().await {
             Ok(()) => true,
@@ -1,6 +1,5 @@
 mod client;
 mod models;
 mod processing;
-mod utils;
 
 pub use client::*;
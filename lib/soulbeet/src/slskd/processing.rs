@@ -1,17 +1,22 @@
-use super::utils;
 use crate::slskd::models::SearchResponse;
 use itertools::Itertools;
+use shared::matching;
+use shared::quality::SearchFilters;
 use shared::slskd::{AlbumResult, MatchResult, SearchResult, TrackResult};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
+/// Group search responses into album candidates. Files scoring below
+/// `min_score` against the searched release, and responses or files
+/// `filters` rule out, are dropped.
 pub fn process_search_responses(
     responses: &[SearchResponse],
     searched_artist: &str,
     searched_album: Option<&str>,
     expected_tracks: &[&str],
+    min_score: f64,
+    filters: &SearchFilters,
 ) -> Vec<AlbumResult> {
-    const MIN_SCORE_THRESHOLD: f64 = 0.6;
     let audio_extensions: HashSet<&str> = ["flac", "wav", "m4a", "ogg", "aac", "wma", "mp3"]
         .iter()
         .copied()
@@ -19,6 +24,7 @@ pub fn process_search_responses(
 
     let scored_files: Vec<(MatchResult, SearchResult)> = responses
         .iter()
+        .filter(|resp| filters.keeps_peer(resp.files.len(), resp.has_free_upload_slot))
         .flat_map(|resp| {
             resp.files.iter().filter_map(|file| {
                 let path = Path::new(&file.filename);
@@ -27,20 +33,23 @@ pub fn process_search_responses(
                     .and_then(|s| s.to_str())
                     .map(|s| s.to_lowercase());
 
-                if let Some(ext) = ext {
+                if let Some(ext) = &ext {
                     if !audio_extensions.contains(ext.as_str()) {
                         return None;
                     }
                 }
+                if !filters.keeps_file(ext.as_deref().unwrap_or_default(), file.bit_rate) {
+                    return None;
+                }
 
-                let rank_result = utils::rank_match(
+                let rank_result = matching::rank_match(
                     &file.filename,
                     Some(searched_artist),
                     searched_album,
                     expected_tracks,
                 );
 
-                if rank_result.total_score < MIN_SCORE_THRESHOLD {
+                if rank_result.total_score < min_score {
                     return None;
                 }
 
@@ -64,6 +73,24 @@ pub fn process_search_responses(
     find_best_albums(&scored_files, expected_tracks)
 }
 
+/// Merge the results of searches for the same release under different
+/// artist names. A folder found by several of them is kept once, with its
+/// best score.
+pub fn merge_album_results(sets: Vec<Vec<AlbumResult>>) -> Vec<AlbumResult> {
+    let mut merged: Vec<AlbumResult> = Vec::new();
+    for album in sets.into_iter().flatten() {
+        match merged
+            .iter_mut()
+            .find(|m| m.username == album.username && m.album_path == album.album_path)
+        {
+            Some(existing) if existing.score < album.score => *existing = album,
+            Some(_) => {}
+            None => merged.push(album),
+        }
+    }
+    merged
+}
+
 fn find_best_albums(
     scored_files: &[(MatchResult, SearchResult)],
     expected_tracks: &[&str],
@@ -158,6 +185,7 @@ fn find_best_albums(
                 upload_speed: first_track.upload_speed,
                 queue_length: first_track.queue_length,
                 score: album_quality_score,
+                completeness,
             })
         })
         .collect()
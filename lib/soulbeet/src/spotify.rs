@@ -0,0 +1,405 @@
+//! Spotify Web API as a metadata provider.
+//!
+//! Spotify's search is far more forgiving of typos and partial titles than
+//! MusicBrainz, so it makes a good first stop for finding what to download.
+//! It is queried with the app's own client credentials; nothing is tied to
+//! a Spotify account. Albums and tracks come back without MusicBrainz ids,
+//! and the importer matches them like any other download.
+
+use std::time::{Duration, Instant};
+
+use reqwest::Client;
+use serde::Deserialize;
+use shared::metadata::{Album, AlbumWithTracks, ReleaseDetails, SearchResult, Track};
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::error::{Result, SoulseekError};
+
+const SPOTIFY_API_BASE: &str = "https://api.spotify.com/v1";
+const SPOTIFY_TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+
+/// Prefix of the album ids handed out by this provider, which are Spotify
+/// URIs.
+pub const ALBUM_ID_PREFIX: &str = "spotify:album:";
+
+/// Spotify caps search pages at 50 items.
+const MAX_PAGE: usize = 50;
+
+/// Tokens are renewed this long before Spotify says they expire.
+const TOKEN_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyArtist {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyImage {
+    url: String,
+    #[serde(default)]
+    width: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyAlbum {
+    uri: String,
+    name: String,
+    #[serde(default)]
+    artists: Vec<SpotifyArtist>,
+    #[serde(default)]
+    release_date: Option<String>,
+    #[serde(default)]
+    images: Vec<SpotifyImage>,
+    #[serde(default)]
+    album_type: Option<String>,
+    #[serde(default)]
+    total_tracks: Option<u32>,
+    /// Only on full album objects
+    #[serde(default)]
+    label: Option<String>,
+    #[serde(default)]
+    genres: Vec<String>,
+    #[serde(default)]
+    tracks: Option<Page<SpotifyTrack>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpotifyTrack {
+    uri: String,
+    name: String,
+    #[serde(default)]
+    artists: Vec<SpotifyArtist>,
+    #[serde(default)]
+    duration_ms: Option<u64>,
+    /// Absent on the tracks listed inside an album
+    #[serde(default)]
+    album: Option<SpotifyAlbum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Page<T> {
+    items: Vec<T>,
+    #[serde(default)]
+    next: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlbumSearchResponse {
+    albums: Page<SpotifyAlbum>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TrackSearchResponse {
+    tracks: Page<SpotifyTrack>,
+}
+
+struct AccessToken {
+    value: String,
+    expires_at: Instant,
+}
+
+pub struct SpotifyProvider {
+    client: Client,
+    client_id: String,
+    client_secret: String,
+    token: Mutex<Option<AccessToken>>,
+}
+
+impl SpotifyProvider {
+    pub fn new(client_id: impl Into<String>, client_secret: impl Into<String>) -> Self {
+        Self {
+            client: Client::builder()
+                .timeout(Duration::from_secs(15))
+                .connect_timeout(Duration::from_secs(5))
+                .build()
+                .expect("failed to build HTTP client"),
+            client_id: client_id.into(),
+            client_secret: client_secret.into(),
+            token: Mutex::new(None),
+        }
+    }
+
+    /// From `SPOTIFY_CLIENT_ID` and `SPOTIFY_CLIENT_SECRET`, when both are set.
+    pub fn from_env() -> Option<Self> {
+        let id = std::env::var("SPOTIFY_CLIENT_ID").ok()?;
+        let secret = std::env::var("SPOTIFY_CLIENT_SECRET").ok()?;
+        (!id.is_empty() && !secret.is_empty()).then(|| Self::new(id, secret))
+    }
+
+    /// A valid access token, fetched again with the client credentials once
+    /// the last one is about to expire.
+    async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some(t) = token.as_ref().filter(|t| t.expires_at > Instant::now()) {
+            return Ok(t.value.clone());
+        }
+
+        let client = self.client.clone();
+        let response = crate::http::resilient_send(
+            || {
+                client
+                    .post(SPOTIFY_TOKEN_URL)
+                    .basic_auth(&self.client_id, Some(&self.client_secret))
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/x-www-form-urlencoded",
+                    )
+                    .body("grant_type=client_credentials")
+            },
+            "Spotify token",
+        )
+        .await?;
+        let fresh: TokenResponse = response.json().await.map_err(|e| SoulseekError::Api {
+            status: 500,
+            message: format!("Failed to parse Spotify token response: {}", e),
+        })?;
+
+        let lifetime = Duration::from_secs(fresh.expires_in).saturating_sub(TOKEN_MARGIN);
+        *token = Some(AccessToken {
+            value: fresh.access_token.clone(),
+            expires_at: Instant::now() + lifetime,
+        });
+        Ok(fresh.access_token)
+    }
+
+    /// GET `url` with the access token. A 401 means the token was revoked
+    /// before its time, so it is renewed and the request sent once more.
+    async fn api_request<T: serde::de::DeserializeOwned>(&self, url: &str) -> Result<T> {
+        let mut renewed = false;
+        let response = loop {
+            let token = self.access_token().await?;
+            let client = self.client.clone();
+            match crate::http::resilient_send(|| client.get(url).bearer_auth(&token), "Spotify API")
+                .await
+            {
+                Err(SoulseekError::Api { status: 401, .. }) if !renewed => {
+                    *self.token.lock().await = None;
+                    renewed = true;
+                }
+                other => break other?,
+            }
+        };
+
+        response.json().await.map_err(|e| SoulseekError::Api {
+            status: 500,
+            message: format!("Failed to parse Spotify response: {}", e),
+        })
+    }
+
+    async fn search<T: serde::de::DeserializeOwned>(
+        &self,
+        kind: &str,
+        artist: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<T> {
+        let limit = limit.clamp(1, MAX_PAGE).to_string();
+        let q = search_query(artist, query);
+        let url = reqwest::Url::parse_with_params(
+            &format!("{}/search", SPOTIFY_API_BASE),
+            &[("q", q.as_str()), ("type", kind), ("limit", limit.as_str())],
+        )?;
+        self.api_request(url.as_str()).await
+    }
+}
+
+/// Free text for the title, so Spotify's fuzzy matching applies, and a
+/// field filter for the artist when there is one.
+fn search_query(artist: Option<&str>, query: &str) -> String {
+    match artist.map(str::trim).filter(|a| !a.is_empty()) {
+        Some(artist) => format!("{} artist:\"{}\"", query.trim(), artist.replace('"', "")),
+        None => query.trim().to_string(),
+    }
+}
+
+fn artist_names(artists: &[SpotifyArtist]) -> String {
+    artists
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The largest image, which Spotify lists first but doesn't promise to.
+fn largest_image(images: &[SpotifyImage]) -> Option<String> {
+    images
+        .iter()
+        .max_by_key(|i| i.width.unwrap_or(0))
+        .map(|i| i.url.clone())
+}
+
+fn format_duration(duration_ms: Option<u64>) -> Option<String> {
+    duration_ms.map(|ms| {
+        let s = ms / 1000;
+        format!("{:02}:{:02}", s / 60, s % 60)
+    })
+}
+
+/// "album" → "Album", as MusicBrainz spells release types.
+fn primary_type(album_type: Option<&str>) -> Option<String> {
+    let album_type = album_type?;
+    let mut chars = album_type.chars();
+    let first = chars.next()?;
+    Some(first.to_uppercase().chain(chars).collect())
+}
+
+fn to_album(album: &SpotifyAlbum) -> Album {
+    Album {
+        id: album.uri.clone(),
+        title: album.name.clone(),
+        artist: artist_names(&album.artists),
+        release_date: album.release_date.clone(),
+        mbid: None,
+        cover_url: largest_image(&album.images),
+        details: ReleaseDetails {
+            label: album.label.clone().filter(|l| !l.is_empty()),
+            country: None,
+            track_count: album.total_tracks,
+            primary_type: primary_type(album.album_type.as_deref()),
+            genres: album.genres.clone(),
+        },
+    }
+}
+
+/// A track, with the album it belongs to when the listing leaves it out.
+fn to_track(track: SpotifyTrack, album: Option<&Album>) -> Track {
+    let listed = track.album.as_ref().map(to_album);
+    let album = listed.as_ref().or(album);
+    Track {
+        id: track.uri,
+        title: track.name,
+        artist: artist_names(&track.artists),
+        album_id: album.map(|a| a.id.clone()),
+        album_title: album.map(|a| a.title.clone()),
+        release_date: album.and_then(|a| a.release_date.clone()),
+        duration: format_duration(track.duration_ms),
+        mbid: None,
+        release_mbid: None,
+        details: album.map(|a| a.details.clone()).unwrap_or_default(),
+    }
+}
+
+#[async_trait::async_trait]
+impl crate::MetadataProvider for SpotifyProvider {
+    fn id(&self) -> &'static str {
+        "spotify"
+    }
+
+    fn name(&self) -> &'static str {
+        "Spotify"
+    }
+
+    async fn search_albums(
+        &self,
+        artist: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let data: AlbumSearchResponse = self.search("album", artist, query, limit).await?;
+        Ok(data
+            .albums
+            .items
+            .iter()
+            .map(|a| SearchResult::Album(to_album(a)))
+            .collect())
+    }
+
+    async fn search_tracks(
+        &self,
+        artist: Option<&str>,
+        query: &str,
+        limit: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let data: TrackSearchResponse = self.search("track", artist, query, limit).await?;
+        Ok(data
+            .tracks
+            .items
+            .into_iter()
+            .map(|t| SearchResult::Track(to_track(t, None)))
+            .collect())
+    }
+
+    async fn get_album(&self, id: &str) -> Result<AlbumWithTracks> {
+        let spotify_id = id.strip_prefix(ALBUM_ID_PREFIX).unwrap_or(id);
+        info!("Fetching album from Spotify: {}", spotify_id);
+        let mut data: SpotifyAlbum = self
+            .api_request(&format!("{}/albums/{}", SPOTIFY_API_BASE, spotify_id))
+            .await?;
+
+        let album = to_album(&data);
+        let mut page = data.tracks.take();
+        let mut tracks = Vec::new();
+        // Albums list their first 50 tracks; box sets go on in more pages
+        while let Some(current) = page {
+            tracks.extend(current.items.into_iter().map(|t| to_track(t, Some(&album))));
+            page = match current.next {
+                Some(next) => Some(self.api_request(&next).await?),
+                None => None,
+            };
+        }
+
+        Ok(AlbumWithTracks { album, tracks })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_albums_and_their_tracks() {
+        let data: SpotifyAlbum = serde_json::from_value(serde_json::json!({
+            "uri": "spotify:album:2noRn2Aes5aoNVsU6iWThc",
+            "name": "Discovery",
+            "artists": [{ "name": "Daft Punk" }],
+            "release_date": "2001-03-12",
+            "album_type": "album",
+            "total_tracks": 14,
+            "label": "Parlophone (France)",
+            "genres": [],
+            "images": [
+                { "url": "https://i.scdn.co/small", "width": 64 },
+                { "url": "https://i.scdn.co/large", "width": 640 }
+            ],
+            "tracks": {
+                "items": [{
+                    "uri": "spotify:track:0DiWol3AO6WpXZgp0goxAV",
+                    "name": "One More Time",
+                    "artists": [{ "name": "Daft Punk" }, { "name": "Romanthony" }],
+                    "duration_ms": 320357
+                }],
+                "next": null
+            }
+        }))
+        .unwrap();
+
+        let album = to_album(&data);
+        assert!(album.id.starts_with(ALBUM_ID_PREFIX));
+        assert_eq!(album.cover_url.as_deref(), Some("https://i.scdn.co/large"));
+        assert_eq!(album.details.primary_type.as_deref(), Some("Album"));
+        assert_eq!(album.details.label.as_deref(), Some("Parlophone (France)"));
+
+        let track = data.tracks.unwrap().items.remove(0);
+        let track = to_track(track, Some(&album));
+        assert_eq!(track.artist, "Daft Punk, Romanthony");
+        assert_eq!(track.duration.as_deref(), Some("05:20"));
+        assert_eq!(track.album_id.as_deref(), Some(album.id.as_str()));
+        assert_eq!(track.release_date.as_deref(), Some("2001-03-12"));
+    }
+
+    #[test]
+    fn artist_goes_in_a_field_filter() {
+        assert_eq!(search_query(None, " discovry "), "discovry");
+        assert_eq!(
+            search_query(Some("Daft \"Punk\""), "discovry"),
+            "discovry artist:\"Daft Punk\""
+        );
+    }
+}
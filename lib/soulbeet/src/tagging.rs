@@ -0,0 +1,276 @@
+//! Reading and rewriting tags of downloaded files before they are imported,
+//! so the importer matches on corrected metadata.
+
+use lofty::config::WriteOptions;
+use lofty::file::TaggedFile;
+use lofty::picture::{Picture, PictureType};
+use lofty::prelude::*;
+use lofty::tag::Tag;
+use shared::download::{FileTags, ImportEdits};
+use shared::metadata::{Album, AlbumWithTracks, Track, TAG_DERIVED_PREFIX};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::http::build_client;
+
+/// Extensions of files whose tags can be reviewed.
+const TAGGABLE_EXTENSIONS: &[&str] = &["flac", "mp3", "m4a", "ogg", "opus", "wav", "aac", "aiff"];
+
+/// Largest cover image accepted from a URL.
+const MAX_COVER_BYTES: usize = 10 * 1024 * 1024;
+
+/// Names (without extension) of cover images saved next to the tracks.
+const COVER_FILE_NAMES: &[&str] = &["cover", "folder", "front", "album"];
+
+fn is_taggable(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| TAGGABLE_EXTENSIONS.contains(&e.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+/// Audio files under `source` (recursively), or `source` itself if it is a file.
+pub fn audio_files(source: &Path) -> Vec<PathBuf> {
+    if source.is_file() {
+        return if is_taggable(source) {
+            vec![source.to_path_buf()]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let mut files = Vec::new();
+    let mut dirs = vec![source.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if is_taggable(&path) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+pub fn read_tags(path: &Path) -> Result<FileTags, String> {
+    let tagged = lofty::read_from_path(path).map_err(|e| e.to_string())?;
+    Ok(file_tags(path, &tagged))
+}
+
+fn file_tags(path: &Path, tagged: &TaggedFile) -> FileTags {
+    let mut tags = FileTags {
+        path: path.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    if let Some(tag) = tagged.primary_tag().or_else(|| tagged.first_tag()) {
+        tags.title = tag.title().map(|s| s.to_string());
+        tags.artist = tag.artist().map(|s| s.to_string());
+        tags.album = tag.album().map(|s| s.to_string());
+        tags.album_artist = tag.get_string(&ItemKey::AlbumArtist).map(str::to_string);
+        tags.year = tag.year();
+        tags.track = tag.track();
+        tags.has_cover = !tag.pictures().is_empty();
+    }
+    tags
+}
+
+/// Value most of `values` agree on, the first seen winning ties.
+pub(crate) fn most_common<'a>(values: impl Iterator<Item = &'a str>) -> Option<String> {
+    let mut counts: Vec<(&str, usize)> = Vec::new();
+    for value in values.map(str::trim).filter(|v| !v.is_empty()) {
+        match counts.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((value, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(value, _)| value.to_string())
+}
+
+/// Album and tracklist described by the tags of the audio files under
+/// `source`, for importing without a metadata provider. Album fields are the
+/// ones most files agree on; the folder name stands in for a missing album.
+pub fn album_from_tags(source: &Path) -> Option<AlbumWithTracks> {
+    let mut files: Vec<(FileTags, Duration)> = audio_files(source)
+        .iter()
+        .filter_map(|path| {
+            let tagged = lofty::read_from_path(path).ok()?;
+            Some((file_tags(path, &tagged), tagged.properties().duration()))
+        })
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+
+    let folder_name = if source.is_dir() {
+        source.file_name()
+    } else {
+        source.parent().and_then(Path::file_name)
+    }
+    .map(|n| n.to_string_lossy().to_string())
+    .unwrap_or_default();
+
+    let artist = most_common(files.iter().filter_map(|(t, _)| t.album_artist.as_deref()))
+        .or_else(|| most_common(files.iter().filter_map(|(t, _)| t.artist.as_deref())))
+        .unwrap_or_else(|| "Unknown Artist".to_string());
+    let title =
+        most_common(files.iter().filter_map(|(t, _)| t.album.as_deref())).unwrap_or(folder_name);
+    let years: Vec<String> = files
+        .iter()
+        .filter_map(|(t, _)| t.year.map(|y| y.to_string()))
+        .collect();
+    let year = most_common(years.iter().map(String::as_str));
+
+    let album = Album {
+        id: format!("{}{} - {}", TAG_DERIVED_PREFIX, artist, title),
+        title,
+        artist,
+        release_date: year,
+        mbid: None,
+        cover_url: None,
+        details: Default::default(),
+    };
+
+    files.sort_by(|(a, _), (b, _)| (a.track, &a.path).cmp(&(b.track, &b.path)));
+    let tracks = files
+        .into_iter()
+        .map(|(tags, duration)| {
+            let path = Path::new(&tags.path);
+            Track {
+                title: tags.title.unwrap_or_else(|| {
+                    path.file_stem()
+                        .map(|s| s.to_string_lossy().to_string())
+                        .unwrap_or_default()
+                }),
+                artist: tags.artist.unwrap_or_else(|| album.artist.clone()),
+                album_id: Some(album.id.clone()),
+                album_title: Some(album.title.clone()),
+                release_date: album.release_date.clone(),
+                duration: Some(format!(
+                    "{:02}:{:02}",
+                    duration.as_secs() / 60,
+                    duration.as_secs() % 60
+                )),
+                mbid: None,
+                release_mbid: None,
+                details: Default::default(),
+                id: tags.path,
+            }
+        })
+        .collect();
+
+    Some(AlbumWithTracks { album, tracks })
+}
+
+/// Write `edits` to a single file. `cover` replaces the front cover if given.
+pub fn write_tags(path: &Path, edits: &ImportEdits, cover: Option<&Picture>) -> Result<(), String> {
+    let mut tagged = lofty::read_from_path(path).map_err(|e| e.to_string())?;
+    if tagged.primary_tag().is_none() {
+        let tag_type = tagged.primary_tag_type();
+        tagged.insert_tag(Tag::new(tag_type));
+    }
+    let tag = tagged
+        .primary_tag_mut()
+        .ok_or_else(|| format!("{} does not support tags", path.display()))?;
+
+    if let Some(artist) = &edits.artist {
+        tag.set_artist(artist.clone());
+    }
+    if let Some(album_artist) = &edits.album_artist {
+        tag.insert_text(ItemKey::AlbumArtist, album_artist.clone());
+    }
+    if let Some(album) = &edits.album {
+        tag.set_album(album.clone());
+    }
+    if let Some(year) = edits.year {
+        tag.set_year(year);
+    }
+    let key = path.to_string_lossy();
+    if let Some(title) = edits.titles.get(key.as_ref()) {
+        tag.set_title(title.clone());
+    }
+    if let Some(number) = edits.track_numbers.get(key.as_ref()) {
+        tag.set_track(*number);
+    }
+    if let Some(picture) = cover {
+        tag.remove_picture_type(PictureType::CoverFront);
+        tag.push_picture(picture.clone());
+    }
+
+    tag.save_to_path(path, WriteOptions::default())
+        .map_err(|e| format!("Failed to write tags to {}: {}", path.display(), e))
+}
+
+/// Content type of a cover image, by file extension.
+fn image_mime(path: &Path) -> Option<&'static str> {
+    match path.extension()?.to_str()?.to_lowercase().as_str() {
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "png" => Some("image/png"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Cover of the album in `dir` and its content type: the image saved in the
+/// directory (`cover.jpg` and the like), or else the front cover embedded in
+/// its first track.
+pub fn album_cover(dir: &Path) -> Option<(Vec<u8>, String)> {
+    let image = std::fs::read_dir(dir).ok()?.flatten().find_map(|entry| {
+        let path = entry.path();
+        let stem = path.file_stem()?.to_string_lossy().to_lowercase();
+        let mime = image_mime(&path)?;
+        COVER_FILE_NAMES
+            .contains(&stem.as_str())
+            .then_some((path, mime))
+    });
+    if let Some((path, mime)) = image {
+        if let Ok(data) = std::fs::read(&path) {
+            return Some((data, mime.to_string()));
+        }
+    }
+
+    let first = audio_files(dir).into_iter().next()?;
+    let tagged = lofty::read_from_path(&first).ok()?;
+    let tag = tagged.primary_tag().or_else(|| tagged.first_tag())?;
+    let picture = tag
+        .get_picture_type(PictureType::CoverFront)
+        .or_else(|| tag.pictures().first())?;
+    let mime = picture
+        .mime_type()
+        .map(|m| m.as_str().to_string())
+        .unwrap_or_else(|| "image/jpeg".to_string());
+    Some((picture.data().to_vec(), mime))
+}
+
+/// Download an image to embed as front cover.
+pub async fn fetch_cover(url: &str) -> Result<Picture, String> {
+    let client = build_client("soulful/0.1 (https://github.com/soulful)");
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to fetch cover: {}", e))?;
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to fetch cover: {}", e))?;
+    if bytes.len() > MAX_COVER_BYTES {
+        return Err("Cover image is too large".to_string());
+    }
+
+    let mut picture =
+        Picture::from_reader(&mut bytes.as_ref()).map_err(|e| format!("Not an image: {}", e))?;
+    picture.set_pic_type(PictureType::CoverFront);
+    Ok(picture)
+}
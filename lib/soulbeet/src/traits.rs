@@ -1,12 +1,18 @@
 use async_trait::async_trait;
 use shared::{
-    download::{DownloadProgress, DownloadableItem, QueuedDownload, SearchResult},
-    library::DuplicateReport,
-    metadata::{Album, AlbumWithTracks, SearchResult as MetadataSearchResult, Track},
+    download::{
+        DownloadProgress, DownloadableItem, ImportSummary, QueuedDownload, SearchOptions,
+        SearchResult,
+    },
+    library::{DuplicateReport, LibraryIndexEntry},
+    metadata::{
+        Album, AlbumWithTracks, IdentifierKind, SearchResult as MetadataSearchResult, Track,
+    },
     recommendation::{
         ArtistPopularity, CandidateSet, Listen, ProfileConfig, RankedArtist, RankedTrack,
         SignalReport, SimilarArtist, SimilarTrack, TimePeriod, UserMusicProfile, WeightedTag,
     },
+    system::BreakerState,
 };
 use std::path::Path;
 
@@ -32,6 +38,21 @@ pub trait MetadataProvider: Send + Sync {
     ) -> Result<Vec<MetadataSearchResult>>;
 
     async fn get_album(&self, id: &str) -> Result<AlbumWithTracks>;
+
+    /// Exact lookup by barcode, catalog number or ISRC. Providers without
+    /// identifier search return an error.
+    async fn search_by_identifier(
+        &self,
+        kind: IdentifierKind,
+        value: &str,
+        limit: usize,
+    ) -> Result<Vec<MetadataSearchResult>> {
+        let _ = (kind, value, limit);
+        Err(crate::error::SoulseekError::Api {
+            status: 501,
+            message: format!("{} does not support identifier search", self.name()),
+        })
+    }
 }
 
 #[async_trait]
@@ -40,17 +61,44 @@ pub trait DownloadBackend: Send + Sync {
     fn name(&self) -> &'static str;
 
     async fn start_search(&self, album: Option<&Album>, tracks: &[Track]) -> Result<String>;
+
+    /// Start a search tuned by `options` (match threshold, result
+    /// diversity). Backends without match scoring ignore them.
+    async fn start_search_with_options(
+        &self,
+        album: Option<&Album>,
+        tracks: &[Track],
+        options: &SearchOptions,
+    ) -> Result<String> {
+        let _ = options;
+        self.start_search(album, tracks).await
+    }
     async fn poll_search(&self, search_id: &str) -> Result<SearchResult>;
     async fn download(&self, items: Vec<DownloadableItem>) -> Result<Vec<QueuedDownload>>;
     async fn get_downloads(&self) -> Result<Vec<DownloadProgress>>;
     async fn cancel_download(&self, username: &str, download_id: &str, remove: bool)
         -> Result<()>;
     async fn health_check(&self) -> bool;
+
+    /// State of the breaker guarding calls to the backend. Backends without
+    /// one are always closed.
+    async fn breaker_state(&self) -> BreakerState {
+        BreakerState::Closed
+    }
+
+    /// Drop finished transfers that ended more than `older_than` ago from the
+    /// backend's own transfer list. Returns how many were removed.
+    async fn prune_transfers(&self, older_than: std::time::Duration) -> Result<usize> {
+        let _ = older_than;
+        Ok(0)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum ImportResult {
-    Success,
+    /// Imported. Importers report where each file went, the tags it ended
+    /// up with and whether it was a duplicate, plus any warnings.
+    Success(ImportSummary),
     Skipped,
     Failed(String),
     TimedOut,
@@ -68,7 +116,44 @@ pub trait MusicImporter: Send + Sync {
         as_album: bool,
     ) -> Result<ImportResult>;
 
+    /// Import with extra importer-specific command line flags. Importers
+    /// without flags ignore them.
+    async fn import_with_flags(
+        &self,
+        sources: &[&Path],
+        target: &Path,
+        as_album: bool,
+        extra_flags: &[String],
+    ) -> Result<ImportResult> {
+        let _ = extra_flags;
+        self.import(sources, target, as_album).await
+    }
+
     async fn find_duplicates(&self, libraries: &[&Path]) -> Result<DuplicateReport>;
+
+    /// Every track of the library at `library`. Importers that keep no
+    /// index of their own return nothing.
+    async fn library_index(&self, library: &Path) -> Result<Vec<LibraryIndexEntry>> {
+        let _ = library;
+        Ok(Vec::new())
+    }
+
+    /// Record files moved into an album directory of a library by hand, so
+    /// they show up in `library_index`. Importers that keep no index of
+    /// their own have nothing to do.
+    async fn add_to_album(&self, album_dir: &Path, files: &[&Path]) -> Result<()> {
+        let _ = (album_dir, files);
+        Ok(())
+    }
+
+    /// Forget the tracks at or under `paths`, all in one library: files
+    /// gone missing, or an album directory about to be deleted. Importers
+    /// that keep no index of their own have nothing to do.
+    async fn remove_paths(&self, paths: &[&Path]) -> Result<()> {
+        let _ = paths;
+        Ok(())
+    }
+
     async fn health_check(&self) -> bool;
 }
 
@@ -130,6 +215,25 @@ impl MetadataProvider for FallbackMetadataProvider {
         Ok(vec![])
     }
 
+    async fn search_by_identifier(
+        &self,
+        kind: IdentifierKind,
+        value: &str,
+        limit: usize,
+    ) -> Result<Vec<MetadataSearchResult>> {
+        for provider in &self.providers {
+            match provider.search_by_identifier(kind, value, limit).await {
+                Ok(results) if !results.is_empty() => return Ok(results),
+                Ok(_) => continue,
+                Err(e) => {
+                    tracing::warn!("{} failed: {}", provider.name(), e);
+                    continue;
+                }
+            }
+        }
+        Ok(vec![])
+    }
+
     async fn get_album(&self, id: &str) -> Result<AlbumWithTracks> {
         for provider in &self.providers {
             match provider.get_album(id).await {
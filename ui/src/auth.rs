@@ -4,19 +4,30 @@ use dioxus::prelude::*;
 #[derive(Clone, Copy, Debug)]
 pub struct Auth {
     state: Signal<Option<AuthResponse>>,
+    /// Set when the session ran out, rather than the user signing out
+    expired: Signal<bool>,
+    /// Artist and query of the last search, run again after signing back in
+    /// if the session ran out
+    last_search: Signal<Option<(String, String)>>,
 }
 
 impl Auth {
     pub fn new(state: Signal<Option<AuthResponse>>) -> Self {
-        Self { state }
+        Self {
+            state,
+            expired: Signal::new(false),
+            last_search: Signal::new(None),
+        }
     }
 
     pub fn login(&mut self, response: AuthResponse) {
+        self.expired.set(false);
         self.state.set(Some(response));
     }
 
     pub async fn logout(&mut self) {
         let _ = api::logout().await;
+        self.last_search.set(None);
         self.state.set(None);
     }
 
@@ -24,27 +35,63 @@ impl Auth {
     /// If it is, logs the user out locally.
     /// Returns true if the error was handled (user logged out), false otherwise.
     pub fn handle_error(&mut self, error: &ServerFnError) -> bool {
-        if let ServerFnError::ServerError { code: 401, .. } = error {
-            self.state.set(None);
+        if is_unauthorized(error) {
+            self.expire();
             return true;
         }
         false
     }
 
-    /// Wraps a server function call to automatically handle authentication errors.
-    pub async fn call<T>(
-        mut self,
-        fut: impl std::future::Future<Output = Result<T, ServerFnError>>,
-    ) -> Result<T, ServerFnError> {
-        match fut.await {
-            Ok(val) => Ok(val),
-            Err(e) => {
-                self.handle_error(&e);
+    /// Wraps a server function call to handle authentication errors: the
+    /// session is renewed if it expired recently and the call made once
+    /// more, and the user is sent back to the login page otherwise. `call`
+    /// builds the request, so that it can be sent again.
+    pub async fn call<T, F>(mut self, mut call: impl FnMut() -> F) -> Result<T, ServerFnError>
+    where
+        F: std::future::Future<Output = Result<T, ServerFnError>>,
+    {
+        match call().await {
+            Err(e) if is_unauthorized(&e) && self.is_logged_in() => {
+                if api::refresh_token().await.is_ok() {
+                    match call().await {
+                        Err(e) if is_unauthorized(&e) => {}
+                        result => return result,
+                    }
+                }
+                self.expire();
                 Err(e)
             }
+            result => result,
+        }
+    }
+
+    fn expire(&mut self) {
+        if self.state.peek().is_some() {
+            self.expired.set(true);
+            self.state.set(None);
+        }
+    }
+
+    /// Whether the user was signed out because the session ran out.
+    pub fn session_expired(&self) -> bool {
+        (self.expired)()
+    }
+
+    /// Remember the search being run, to bring it back if the session runs
+    /// out before the user is done with it.
+    pub fn remember_search(&mut self, artist: Option<String>, query: String) {
+        let search = (!query.trim().is_empty()).then(|| (artist.unwrap_or_default(), query));
+        if *self.last_search.peek() != search {
+            self.last_search.set(search);
         }
     }
 
+    /// The search to run again after signing back in, if the session ran
+    /// out during one. Signing out forgets it; it is only handed out once.
+    pub fn take_resumed_search(&mut self) -> Option<(String, String)> {
+        self.last_search.write().take()
+    }
+
     pub fn user_id(&self) -> Option<String> {
         self.state.read().as_ref().map(|a| a.user_id.clone())
     }
@@ -64,6 +111,18 @@ impl Auth {
             .map(|a| a.navidrome_status)
             .unwrap_or_default()
     }
+
+    /// Whether the user may only browse: no downloads, imports or settings.
+    pub fn is_viewer(&self) -> bool {
+        self.state
+            .read()
+            .as_ref()
+            .is_some_and(|a| a.role.is_viewer())
+    }
+}
+
+fn is_unauthorized(error: &ServerFnError) -> bool {
+    matches!(error, ServerFnError::ServerError { code: 401, .. })
 }
 
 pub fn use_auth() -> Auth {
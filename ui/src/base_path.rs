@@ -0,0 +1,20 @@
+//! Sub-path the app is served under behind a reverse proxy, such as
+//! `/soulbeet`. The browser code can't ask the server for it before it has
+//! loaded, so it is built in: set BASE_PATH when bundling the app as well as
+//! when running the server.
+
+use std::sync::LazyLock;
+
+static BASE_PATH: LazyLock<String> = LazyLock::new(|| {
+    shared::system::normalize_base_path(option_env!("BASE_PATH").unwrap_or_default())
+});
+
+/// The sub-path, "" when served at the root.
+pub fn base_path() -> &'static str {
+    &BASE_PATH
+}
+
+/// `path`, absolute on the server, as the browser has to ask for it.
+pub fn app_path(path: &str) -> String {
+    format!("{}{}", base_path(), path)
+}
@@ -1,19 +1,30 @@
 use dioxus::prelude::*;
 
-use crate::Button;
+use crate::{Button, ButtonVariant};
 
 #[derive(Props, PartialEq, Clone)]
 pub struct Props {
     is_selection_empty: bool,
     on_select: EventHandler,
+    /// Tracks not in the library, when some of the album already is
+    missing_count: Option<usize>,
+    on_select_missing: EventHandler,
 }
 
 #[component]
 pub fn AlbumFooter(props: Props) -> Element {
     rsx! {
-      div { class: "p-4 border-t border-white/10 mt-auto",
+      div { class: "p-4 border-t border-white/10 mt-auto flex gap-2",
+        if let Some(missing) = props.missing_count {
+          Button {
+            variant: ButtonVariant::Secondary,
+            disabled: missing == 0,
+            onclick: move |_| props.on_select_missing.call(()),
+            "SELECT MISSING ONLY ({missing})"
+          }
+        }
         Button {
-          class: "w-full",
+          class: "flex-grow",
           disabled: props.is_selection_empty,
           onclick: move |_| props.on_select.call(()),
           div { class: "flex items-center justify-center gap-2",
@@ -6,6 +6,7 @@ use shared::{
 use std::collections::HashSet;
 
 use crate::album::{footer::AlbumFooter, track_list::TrackList};
+use crate::use_auth;
 
 mod footer;
 mod header;
@@ -27,6 +28,18 @@ pub struct Props {
 pub fn Album(props: Props) -> Element {
     let mut selected_tracks = use_signal(HashSet::<String>::new);
     let tracks = use_signal(|| props.data.tracks.clone());
+    let auth = use_auth();
+    // Ids of tracks already in the library, empty until the index answers
+    let mut owned_tracks = use_signal(HashSet::<String>::new);
+
+    use_effect(move || {
+        let tracks = tracks.read().clone();
+        spawn(async move {
+            if let Ok(ids) = auth.call(|| api::get_owned_tracks(tracks.clone())).await {
+                owned_tracks.set(ids.into_iter().collect());
+            }
+        });
+    });
 
     let all_selected =
         selected_tracks.read().len() == tracks.read().len() && !tracks.read().is_empty();
@@ -42,6 +55,21 @@ pub fn Album(props: Props) -> Element {
         }
     };
 
+    let handle_select_missing = move |_| {
+        let owned = owned_tracks.read();
+        selected_tracks.set(
+            tracks
+                .read()
+                .iter()
+                .filter(|t| !owned.contains(&t.id))
+                .map(|t| t.id.clone())
+                .collect(),
+        );
+    };
+
+    let owned_count = owned_tracks.read().len();
+    let missing_count = (owned_count > 0).then(|| tracks.read().len().saturating_sub(owned_count));
+
     let handle_track_toggle = move |track_id: String| {
         let mut selected = selected_tracks.write();
         if selected.contains(&track_id) {
@@ -55,12 +83,15 @@ pub fn Album(props: Props) -> Element {
         TrackList {
             tracks,
             selected_tracks,
+            owned_tracks,
             on_toggle_select_all: handle_select_all,
             on_track_toggle: handle_track_toggle,
             all_selected,
         }
         AlbumFooter {
             is_selection_empty: selected_tracks.read().is_empty(),
+            missing_count,
+            on_select_missing: handle_select_missing,
             on_select: move |_| {
                 let selected_ids = selected_tracks.read();
                 let tracks: Vec<Track> = tracks
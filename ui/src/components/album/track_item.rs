@@ -7,6 +7,8 @@ use crate::Checkbox;
 pub struct Props {
     track: Track,
     is_selected: bool,
+    /// Already in one of the user's folders
+    is_owned: bool,
     on_toggle: EventHandler<String>,
 }
 
@@ -18,6 +20,7 @@ pub fn TrackItem(props: Props) -> Element {
       li {
         class: "flex items-center gap-3 p-2 rounded-md cursor-pointer transition-colors border border-transparent",
         class: if props.is_selected { "bg-beet-leaf/10 border-beet-leaf/30" } else { "hover:bg-white/5 border-white/5" },
+        class: if props.is_owned && !props.is_selected { "opacity-50" },
         onclick: move |_| props.on_toggle.call(track_id.clone()),
         Checkbox { is_selected: props.is_selected }
 
@@ -26,6 +29,13 @@ pub fn TrackItem(props: Props) -> Element {
           class: if props.is_selected { "text-beet-leaf" } else { "text-gray-300" },
           "{props.track.title}"
         }
+        if props.is_owned {
+          span {
+            class: "font-mono text-[10px] uppercase tracking-wider px-1.5 py-0.5 rounded bg-white/10 text-gray-400",
+            title: "Already in your library",
+            "Owned"
+          }
+        }
         if let Some(duration) = &props.track.duration {
           span {
             class: "font-mono text-xs",
@@ -9,6 +9,7 @@ use crate::{album::track_item::TrackItem, Checkbox};
 pub struct Props {
     tracks: Signal<Vec<Track>>,
     selected_tracks: Signal<HashSet<String>>,
+    owned_tracks: Signal<HashSet<String>>,
     on_toggle_select_all: EventHandler,
     on_track_toggle: EventHandler<String>,
     all_selected: bool,
@@ -29,6 +30,7 @@ pub fn TrackList(props: Props) -> Element {
             key: "{track.id}",
             track: track.clone(),
             is_selected: props.selected_tracks.read().contains(&track.id),
+            is_owned: props.owned_tracks.read().contains(&track.id),
             on_toggle: props.on_track_toggle,
           }
         }
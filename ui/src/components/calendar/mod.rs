@@ -0,0 +1,455 @@
+use api::models::watched_artist::WatchedArtist;
+use api::{
+    export_release_calendar, get_related_artists, get_release_calendar, get_watched_artists,
+    set_release_action, unwatch_artist, watch_artist,
+};
+use base64::Engine;
+use dioxus::prelude::*;
+use shared::calendar::{CalendarRelease, RelatedArtist, ReleaseAction};
+
+use crate::{friendly_error, use_auth};
+
+/// Days a "remind me later" hides a release for.
+const REMIND_AFTER_DAYS: u32 = 7;
+
+/// "2026-05-12" -> "May 2026". Year-only dates are grouped under the year.
+fn month_label(date: &str) -> String {
+    let Some(month) = date.get(..7) else {
+        return date.to_string();
+    };
+    chrono::NaiveDate::parse_from_str(&format!("{}-01", month), "%Y-%m-%d")
+        .map(|d| d.format("%B %Y").to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+/// Recent and upcoming releases of watched artists, grouped by month.
+/// `on_download` receives (artist, title) to search for the release.
+#[component]
+pub fn ReleaseCalendar(
+    refresh: Signal<u32>,
+    on_download: EventHandler<(String, String)>,
+) -> Element {
+    let auth = use_auth();
+    let mut releases = use_signal(|| None::<Vec<CalendarRelease>>);
+    let mut error = use_signal(String::new);
+    let mut ics_href = use_signal(|| None::<String>);
+    // This page's origin, so the feed link carries the scheme it is served with
+    let mut origin = use_signal(String::new);
+    use_effect(move || {
+        if let Some(o) = web_sys::window().and_then(|w| w.location().origin().ok()) {
+            origin.set(format!("{}{}", o, crate::base_path()));
+        }
+    });
+
+    let fetch_releases = move || async move {
+        match auth.call(get_release_calendar).await {
+            Ok(list) => releases.set(Some(list)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    use_effect(move || {
+        let _ = refresh();
+        releases.set(None);
+        spawn(fetch_releases());
+    });
+
+    let handle_action = move |release_group_id: String, action: ReleaseAction| async move {
+        match auth
+            .call(|| set_release_action(release_group_id.clone(), action))
+            .await
+        {
+            Ok(()) => fetch_releases().await,
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let handle_export = move |_| async move {
+        match auth.call(export_release_calendar).await {
+            Ok(ics) => {
+                let encoded = base64::engine::general_purpose::STANDARD.encode(ics);
+                ics_href.set(Some(format!("data:text/calendar;base64,{}", encoded)));
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let today = chrono::Local::now().format("%Y-%m-%d").to_string();
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            div { class: "flex justify-between items-center mb-4",
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Release Calendar" }
+                if let Some(href) = ics_href() {
+                    a {
+                        class: "retro-btn rounded text-sm",
+                        href: "{href}",
+                        download: "soulbeet-releases.ics",
+                        onclick: move |_| ics_href.set(None),
+                        "Save .ics"
+                    }
+                } else {
+                    button { class: "retro-btn rounded text-sm", onclick: handle_export, "Export .ics" }
+                }
+            }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Subscribe from a calendar app with "
+                code { class: "text-beet-leaf", "{origin}/api/calendar.ics?token=<api token>" }
+                "."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            match releases() {
+                None => rsx! {
+                    p { class: "text-gray-500 font-mono text-sm animate-pulse", "Checking MusicBrainz for releases..." }
+                },
+                Some(list) if list.is_empty() => rsx! {
+                    p { class: "text-gray-500 font-mono italic text-sm",
+                        "No recent or upcoming releases. Watch some artists below."
+                    }
+                },
+                Some(list) => {
+                    let mut months: Vec<(String, Vec<CalendarRelease>)> = Vec::new();
+                    for release in list {
+                        let label = month_label(&release.date);
+                        match months.last_mut() {
+                            Some((last, group)) if *last == label => group.push(release),
+                            _ => months.push((label, vec![release])),
+                        }
+                    }
+                    rsx! {
+                        div { class: "space-y-6",
+                            for (label, group) in months {
+                                div { key: "{label}",
+                                    h3 { class: "text-xs font-mono uppercase tracking-widest text-gray-500 mb-2 border-b border-white/5 pb-1",
+                                        "{label}"
+                                    }
+                                    ul { class: "space-y-2",
+                                        for release in group {
+                                            {
+                                                let is_upcoming = release.date.as_str() > today.as_str();
+                                                let query = (release.artist.clone(), release.title.clone());
+                                                let ignore_id = release.release_group_id.clone();
+                                                let remind_id = release.release_group_id.clone();
+                                                let kind = release.release_type.clone().unwrap_or_else(|| "Release".to_string());
+                                                rsx! {
+                                                    li {
+                                                        key: "{release.release_group_id}",
+                                                        class: "flex justify-between items-center gap-4 bg-white/5 border border-white/5 p-3 rounded",
+                                                        div { class: "min-w-0",
+                                                            div { class: "font-bold text-white font-display truncate", "{release.title}" }
+                                                            div { class: "text-xs font-mono text-gray-500 truncate",
+                                                                "{release.artist} · {kind} · {release.date}"
+                                                                if is_upcoming {
+                                                                    span { class: "ml-2 text-beet-leaf", "upcoming" }
+                                                                }
+                                                                if release.reminded {
+                                                                    span { class: "ml-2 text-yellow-500", "reminder" }
+                                                                }
+                                                            }
+                                                        }
+                                                        div { class: "flex gap-3 shrink-0",
+                                                            if !is_upcoming {
+                                                                button {
+                                                                    class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                                                                    onclick: move |_| on_download.call(query.clone()),
+                                                                    "[ Download ]"
+                                                                }
+                                                            }
+                                                            button {
+                                                                class: "text-xs uppercase tracking-wider font-bold text-gray-400 hover:text-white transition-colors cursor-pointer",
+                                                                title: "Hide for {REMIND_AFTER_DAYS} days",
+                                                                onclick: move |_| {
+                                                                    spawn(handle_action(
+                                                                        remind_id.clone(),
+                                                                        ReleaseAction::RemindLater { days: REMIND_AFTER_DAYS },
+                                                                    ));
+                                                                },
+                                                                "[ Later ]"
+                                                            }
+                                                            button {
+                                                                class: "text-xs uppercase tracking-wider font-bold text-red-400 hover:text-red-300 transition-colors cursor-pointer",
+                                                                onclick: move |_| {
+                                                                    spawn(handle_action(ignore_id.clone(), ReleaseAction::Ignore));
+                                                                },
+                                                                "[ Ignore ]"
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Add and remove the artists followed on the release calendar, and browse
+/// the artists related to them. `on_download` receives (artist, title) to
+/// search for an album of a related artist.
+#[component]
+pub fn WatchedArtists(
+    on_change: EventHandler<()>,
+    on_download: EventHandler<(String, String)>,
+) -> Element {
+    let auth = use_auth();
+    let mut artists = use_signal(Vec::<WatchedArtist>::new);
+    let mut new_name = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut adding = use_signal(|| false);
+    let mut exploring = use_signal(|| None::<WatchedArtist>);
+
+    let fetch_artists = move || async move {
+        match auth.call(get_watched_artists).await {
+            Ok(list) => artists.set(list),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    use_future(move || async move {
+        fetch_artists().await;
+    });
+
+    let handle_add = move |_| async move {
+        error.set(String::new());
+        if new_name().trim().is_empty() {
+            return;
+        }
+        adding.set(true);
+        match auth.call(|| watch_artist(new_name(), None)).await {
+            Ok(()) => {
+                new_name.set(String::new());
+                fetch_artists().await;
+                on_change.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        adding.set(false);
+    };
+
+    let handle_remove = move |id: String| async move {
+        match auth.call(|| unwatch_artist(id.clone())).await {
+            Ok(()) => {
+                fetch_artists().await;
+                on_change.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            h2 { class: "text-xl font-bold mb-4 text-beet-accent font-display", "Watched Artists" }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            div { class: "flex gap-2 mb-4",
+                input {
+                    class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                    value: "{new_name}",
+                    oninput: move |e| new_name.set(e.value()),
+                    placeholder: "Artist name",
+                    "type": "text",
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: adding(),
+                    onclick: handle_add,
+                    "Watch"
+                }
+            }
+
+            if artists.read().is_empty() {
+                p { class: "text-gray-500 font-mono italic text-sm", "Not watching any artists." }
+            } else {
+                div { class: "flex flex-wrap gap-2",
+                    for artist in artists.read().iter().cloned() {
+                        {
+                            let id = artist.id.clone();
+                            let related = artist.clone();
+                            rsx! {
+                                span {
+                                    key: "{artist.id}",
+                                    class: "inline-flex items-center gap-2 px-3 py-1 rounded-full bg-white/5 border border-white/10 text-sm font-mono",
+                                    "{artist.name}"
+                                    button {
+                                        class: "text-xs text-gray-500 hover:text-beet-accent transition-colors cursor-pointer",
+                                        title: "Related artists",
+                                        onclick: move |_| exploring.set(Some(related.clone())),
+                                        "related"
+                                    }
+                                    button {
+                                        class: "text-gray-500 hover:text-red-400 transition-colors cursor-pointer",
+                                        aria_label: "Stop watching {artist.name}",
+                                        onclick: move |_| {
+                                            spawn(handle_remove(id.clone()));
+                                        },
+                                        "×"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(artist) = exploring() {
+                RelatedArtists {
+                    key: "{artist.id}",
+                    artist,
+                    on_close: move |_| exploring.set(None),
+                    on_watch: move |_| {
+                        spawn(async move {
+                            fetch_artists().await;
+                            on_change.call(());
+                        });
+                    },
+                    on_download,
+                }
+            }
+        }
+    }
+}
+
+/// Artists linked to a watched one on MusicBrainz, each with how much of
+/// their albums the library holds, to watch them or search for an album.
+#[component]
+fn RelatedArtists(
+    artist: WatchedArtist,
+    on_close: EventHandler<()>,
+    on_watch: EventHandler<()>,
+    on_download: EventHandler<(String, String)>,
+) -> Element {
+    let auth = use_auth();
+    let mbid = artist.artist_mbid.clone();
+    let mut related = use_resource(move || {
+        let mbid = mbid.clone();
+        async move { auth.call(|| get_related_artists(mbid.clone())).await }
+    });
+    let mut error = use_signal(String::new);
+
+    let handle_watch = move |other: RelatedArtist| async move {
+        error.set(String::new());
+        match auth
+            .call(|| watch_artist(other.name.clone(), Some(other.mbid.clone())))
+            .await
+        {
+            Ok(()) => {
+                related.restart();
+                on_watch.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let body = match &*related.read() {
+        None => rsx! {
+            p { class: "text-gray-500 font-mono text-sm animate-pulse", "Looking up related artists..." }
+        },
+        Some(Err(e)) => rsx! {
+            p { class: "text-red-400 font-mono text-sm", {friendly_error(e)} }
+        },
+        Some(Ok(list)) if list.is_empty() => rsx! {
+            p { class: "text-gray-500 font-mono italic text-sm", "MusicBrainz lists no related artists." }
+        },
+        Some(Ok(list)) => rsx! {
+            ul { class: "space-y-3",
+                for other in list.iter().cloned() {
+                    {
+                        let owned = other.owned_count();
+                        let total = other.albums.len();
+                        let watched = other.clone();
+                        rsx! {
+                            li {
+                                key: "{other.mbid}",
+                                class: "bg-white/5 border border-white/5 p-3 rounded space-y-2",
+                                div { class: "flex justify-between items-center gap-4",
+                                    div { class: "min-w-0",
+                                        div { class: "font-bold text-white font-display truncate", "{other.name}" }
+                                        div { class: "text-xs font-mono text-gray-500",
+                                            "{other.relation} · {owned} of {total} albums owned"
+                                        }
+                                    }
+                                    if other.watched {
+                                        span { class: "text-xs uppercase tracking-wider font-bold text-gray-500 shrink-0", "[ Watching ]" }
+                                    } else {
+                                        button {
+                                            class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer shrink-0",
+                                            onclick: move |_| {
+                                                spawn(handle_watch(watched.clone()));
+                                            },
+                                            "[ Watch ]"
+                                        }
+                                    }
+                                }
+                                if !other.albums.is_empty() {
+                                    div { class: "flex flex-wrap gap-1",
+                                        for album in other.albums.iter().cloned() {
+                                            {
+                                                let query = (other.name.clone(), album.title.clone());
+                                                let year = album.date.get(..4).unwrap_or("").to_string();
+                                                rsx! {
+                                                    if album.owned {
+                                                        span {
+                                                            key: "{album.release_group_id}",
+                                                            class: "px-2 py-0.5 rounded text-xs font-mono bg-beet-leaf/10 text-beet-leaf",
+                                                            title: "In your library",
+                                                            "{album.title} {year}"
+                                                        }
+                                                    } else {
+                                                        button {
+                                                            key: "{album.release_group_id}",
+                                                            class: "px-2 py-0.5 rounded text-xs font-mono bg-white/5 text-gray-400 hover:text-white cursor-pointer",
+                                                            title: "Search for this album",
+                                                            onclick: move |_| on_download.call(query.clone()),
+                                                            "{album.title} {year}"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "mt-6 border-t border-white/10 pt-4",
+            div { class: "flex justify-between items-center mb-3",
+                h3 { class: "text-lg font-bold text-white font-display", "Related to {artist.name}" }
+                button {
+                    class: "text-gray-500 hover:text-white transition-colors cursor-pointer",
+                    aria_label: "Close related artists",
+                    onclick: move |_| on_close.call(()),
+                    "×"
+                }
+            }
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            {body}
+        }
+    }
+}
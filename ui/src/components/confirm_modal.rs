@@ -9,12 +9,21 @@ pub struct ConfirmModalProps {
     pub confirm_label: String,
     #[props(default = false)]
     pub danger: bool,
+    /// When set, the confirm button stays disabled until this exact text is typed.
+    #[props(default)]
+    pub require_text: Option<String>,
     pub on_confirm: EventHandler,
     pub on_cancel: EventHandler,
 }
 
 #[component]
 pub fn ConfirmModal(props: ConfirmModalProps) -> Element {
+    let mut typed = use_signal(String::new);
+    let can_confirm = props
+        .require_text
+        .as_ref()
+        .is_none_or(|expected| typed() == *expected);
+
     let confirm_class = if props.danger {
         "px-3 py-1.5 text-sm font-mono rounded bg-red-900/50 text-red-400 hover:bg-red-800/50 cursor-pointer"
     } else {
@@ -28,6 +37,20 @@ pub fn ConfirmModal(props: ConfirmModalProps) -> Element {
                 h2 { class: "text-lg font-bold text-white font-display", "Confirm" }
             },
             p { class: "text-gray-300 text-sm font-mono mb-4", "{props.message}" }
+            if let Some(expected) = props.require_text.clone() {
+                label { class: "block text-xs font-mono text-gray-400 mb-1",
+                    "Type "
+                    span { class: "text-white font-bold", "{expected}" }
+                    " to confirm"
+                }
+                input {
+                    class: "w-full p-2 mb-4 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm",
+                    value: "{typed}",
+                    oninput: move |e| typed.set(e.value()),
+                    autofocus: true,
+                    "type": "text",
+                }
+            }
             div { class: "flex justify-end gap-2",
                 button {
                     class: "px-3 py-1.5 text-sm font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
@@ -35,8 +58,13 @@ pub fn ConfirmModal(props: ConfirmModalProps) -> Element {
                     "Cancel"
                 }
                 button {
-                    class: confirm_class,
-                    onclick: move |_| props.on_confirm.call(()),
+                    class: "{confirm_class} disabled:opacity-40 disabled:cursor-not-allowed",
+                    disabled: !can_confirm,
+                    onclick: move |_| {
+                        if can_confirm {
+                            props.on_confirm.call(());
+                        }
+                    },
                     "{props.confirm_label}"
                 }
             }
@@ -1,14 +1,24 @@
 use dioxus::prelude::*;
 use shared::metadata::Album;
 
+/// Front cover of a MusicBrainz release, through the server's art cache.
+/// `size` is 250, 500 or 1200, the thumbnail sizes the server serves.
+pub fn release_art_url(release_mbid: &str, size: u32) -> String {
+    format!(
+        "{}/api/art/{}?size={}",
+        crate::base_path(),
+        release_mbid,
+        size
+    )
+}
+
 fn get_album_cover_url(album: &Album) -> Option<String> {
-    if let Some(url) = &album.cover_url {
-        return Some(url.clone());
+    match (&album.cover_url, &album.mbid) {
+        // Cover Art Archive images go through the cache like the rest
+        (Some(url), _) if !url.contains("coverartarchive.org") => Some(url.clone()),
+        (_, Some(mbid)) => Some(release_art_url(mbid, 250)),
+        (url, None) => url.clone(),
     }
-    album
-        .mbid
-        .as_ref()
-        .map(|mbid| format!("https://coverartarchive.org/release/{}/front-250", mbid))
 }
 
 #[component]
@@ -0,0 +1,155 @@
+use api::models::folder::Folder;
+use api::{fix_library_health, get_library_health, get_user_folders};
+use dioxus::prelude::*;
+use shared::library::LibraryHealth;
+
+use super::StatCard;
+use crate::downloads::format_size;
+use crate::{friendly_error, use_auth};
+
+/// Paths listed per problem before the rest is summed up.
+const SHOWN_PATHS: usize = 20;
+
+#[component]
+fn PathList(title: String, paths: Vec<String>) -> Element {
+    let hidden = paths.len().saturating_sub(SHOWN_PATHS);
+    rsx! {
+        div { class: "space-y-1",
+            h4 { class: "text-xs font-mono uppercase tracking-wider text-gray-500", "{title} ({paths.len()})" }
+            ul { class: "max-h-48 overflow-y-auto text-xs font-mono text-gray-300 space-y-0.5",
+                for path in paths.iter().take(SHOWN_PATHS) {
+                    li { class: "truncate", title: "{path}", "{path}" }
+                }
+            }
+            if hidden > 0 {
+                p { class: "text-xs font-mono text-gray-500", "and {hidden} more" }
+            }
+        }
+    }
+}
+
+/// Statistics of a library folder and a check of the beets index against
+/// the files on disk.
+#[component]
+pub fn LibraryHealthTab() -> Element {
+    let auth = use_auth();
+    let mut folders = use_signal(Vec::<Folder>::new);
+    let mut folder_id = use_signal(String::new);
+    let mut health = use_signal(|| None::<LibraryHealth>);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+
+    use_future(move || async move {
+        if let Ok(list) = auth.call(get_user_folders).await {
+            if let Some(first) = list.first() {
+                folder_id.set(first.id.clone());
+            }
+            folders.set(list);
+        }
+    });
+
+    let handle_check = move |_| async move {
+        error.set(String::new());
+        busy.set(true);
+        match auth.call(|| get_library_health(folder_id())).await {
+            Ok(h) => health.set(Some(h)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let fix = move |forget_missing: bool, index_untracked: bool| async move {
+        error.set(String::new());
+        busy.set(true);
+        match auth
+            .call(|| fix_library_health(folder_id(), forget_missing, index_untracked))
+            .await
+        {
+            Ok(h) => health.set(Some(h)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex items-center justify-between gap-4",
+                h3 { class: "text-sm font-semibold text-white", "Library Health" }
+                div { class: "flex gap-2",
+                    select {
+                        class: "p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-gray-300 font-mono text-sm",
+                        onchange: move |e| {
+                            folder_id.set(e.value());
+                            health.set(None);
+                        },
+                        for folder in folders() {
+                            option {
+                                value: "{folder.id}",
+                                selected: folder.id == folder_id(),
+                                "{folder.name}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "retro-btn rounded text-sm",
+                        disabled: busy() || folder_id().is_empty(),
+                        onclick: handle_check,
+                        if busy() { "Checking..." } else { "Check" }
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                div { class: "p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            if let Some(h) = health() {
+                div { class: "grid grid-cols-3 gap-4",
+                    StatCard { label: "Tracks", value: h.tracks.to_string() }
+                    StatCard { label: "Albums", value: h.albums.to_string() }
+                    StatCard { label: "Size", value: format_size(h.size) }
+                }
+                if !h.formats.is_empty() {
+                    div { class: "bg-beet-panel border border-white/10 p-4 rounded-lg",
+                        h3 { class: "text-sm font-semibold text-white mb-3", "Formats" }
+                        div { class: "flex flex-wrap gap-2",
+                            for (format, count) in h.formats.iter() {
+                                span { class: "px-2 py-1 bg-beet-dark rounded text-xs font-mono text-gray-300",
+                                    "{format} ({count})"
+                                }
+                            }
+                        }
+                    }
+                }
+
+                if h.missing.is_empty() && h.untracked.is_empty() {
+                    p { class: "text-sm font-mono text-beet-leaf", "The index matches the files on disk." }
+                }
+                if !h.missing.is_empty() {
+                    div { class: "bg-beet-panel border border-white/10 p-4 rounded-lg space-y-3",
+                        PathList { title: "Indexed but missing on disk", paths: h.missing.clone() }
+                        button {
+                            class: "retro-btn rounded text-sm",
+                            disabled: busy(),
+                            onclick: move |_| fix(true, false),
+                            "Remove from index"
+                        }
+                    }
+                }
+                if !h.untracked.is_empty() {
+                    div { class: "bg-beet-panel border border-white/10 p-4 rounded-lg space-y-3",
+                        PathList { title: "On disk but not indexed", paths: h.untracked.clone() }
+                        button {
+                            class: "retro-btn rounded text-sm",
+                            disabled: busy(),
+                            onclick: move |_| fix(false, true),
+                            "Add to index in place"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,11 +1,24 @@
+use base64::Engine;
 use dioxus::prelude::*;
+use shared::download::DownloadFeedback;
+use shared::export::{ExportFile, ExportFormat};
 use shared::navidrome::LibraryStats;
 
+use crate::downloads::{format_size, NoteEditor};
+use crate::{friendly_error, use_auth, Checkbox};
+
+mod library_health;
+pub use library_health::LibraryHealthTab;
+
+mod search_history;
+pub use search_history::SearchHistoryTab;
+
 #[derive(PartialEq, Clone, Copy, Default)]
 pub enum DashboardTab {
     #[default]
     Overview,
     History,
+    Library,
     Discovery,
 }
 
@@ -30,6 +43,7 @@ pub fn DashboardTabs(active: DashboardTab, on_change: EventHandler<DashboardTab>
         nav { class: "flex items-center gap-1 bg-beet-panel/50 p-1.5 rounded-lg border border-white/5 backdrop-blur-sm overflow-x-auto whitespace-nowrap",
             {tab("Overview", DashboardTab::Overview)}
             {tab("History", DashboardTab::History)}
+            {tab("Library", DashboardTab::Library)}
             {tab("Discovery", DashboardTab::Discovery)}
         }
     }
@@ -113,6 +127,47 @@ fn StatCard(label: &'static str, value: String) -> Element {
     }
 }
 
+/// What the user downloaded lately, in bytes taken from the peers.
+#[component]
+pub fn BandwidthOverview() -> Element {
+    let usage = use_resource(|| async { api::get_bandwidth_usage().await });
+    let Some(Ok(usage)) = usage.read().clone() else {
+        return rsx! {};
+    };
+    let max = usage.days.iter().map(|d| d.bytes).max().unwrap_or(1).max(1);
+
+    rsx! {
+        div { class: "space-y-4",
+            h3 { class: "text-sm font-semibold text-white", "Bandwidth" }
+            div { class: "grid grid-cols-3 gap-4",
+                StatCard { label: "Today", value: format_size(usage.today) }
+                StatCard { label: "Last 7 Days", value: format_size(usage.week) }
+                StatCard { label: "Last 30 Days", value: format_size(usage.month) }
+            }
+            if !usage.days.is_empty() {
+                div { class: "bg-beet-panel border border-white/10 p-4 rounded-lg space-y-2",
+                    for day in usage.days.iter() {
+                        div { key: "{day.day}", class: "flex items-center gap-3",
+                            span { class: "text-xs font-mono text-gray-400 w-24 shrink-0",
+                                "{day.day}"
+                            }
+                            div { class: "flex-1 bg-beet-dark rounded-full h-3 overflow-hidden",
+                                div {
+                                    class: "h-full bg-beet-leaf/70 rounded-full",
+                                    style: "width: {day.bytes * 100 / max}%",
+                                }
+                            }
+                            span { class: "text-xs font-mono text-gray-400 w-20 text-right shrink-0",
+                                {format_size(day.bytes)}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
 #[component]
 pub fn DeletionHistoryTab() -> Element {
     let history = use_resource(|| async { api::get_deletion_history().await });
@@ -150,3 +205,309 @@ pub fn DeletionHistoryTab() -> Element {
         }
     }
 }
+
+/// What an [`ExportButtons`] row exports.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ExportKind {
+    Library,
+    History,
+    Wishlist,
+}
+
+/// CSV and JSON export buttons. The export is generated on click and then
+/// offered as a link to save.
+#[component]
+pub fn ExportButtons(kind: ExportKind) -> Element {
+    let auth = use_auth();
+    let mut file = use_signal(|| None::<ExportFile>);
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(String::new);
+
+    let handle_export = move |format: ExportFormat| async move {
+        error.set(String::new());
+        loading.set(true);
+        let result = match kind {
+            ExportKind::Library => auth.call(|| api::export_library(format)).await,
+            ExportKind::History => auth.call(|| api::export_download_history(format)).await,
+            ExportKind::Wishlist => auth.call(|| api::export_wishlist(format)).await,
+        };
+        match result {
+            Ok(f) => file.set(Some(f)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        loading.set(false);
+    };
+
+    rsx! {
+        div { class: "flex items-center gap-2",
+            if !error().is_empty() {
+                span { class: "text-xs font-mono text-red-400", "{error}" }
+            }
+            if let Some(f) = file() {
+                a {
+                    class: "retro-btn rounded text-sm",
+                    href: format!(
+                        "data:{};base64,{}",
+                        f.mime,
+                        base64::engine::general_purpose::STANDARD.encode(&f.content),
+                    ),
+                    download: "{f.file_name}",
+                    onclick: move |_| file.set(None),
+                    "Save {f.file_name}"
+                }
+            } else if loading() {
+                span { class: "text-xs font-mono text-gray-400 animate-pulse", "Exporting..." }
+            } else {
+                button {
+                    class: "retro-btn rounded text-sm",
+                    onclick: move |_| handle_export(ExportFormat::Csv),
+                    "Export CSV"
+                }
+                button {
+                    class: "retro-btn rounded text-sm",
+                    onclick: move |_| handle_export(ExportFormat::Json),
+                    "Export JSON"
+                }
+            }
+        }
+    }
+}
+
+#[component]
+pub fn DownloadHistoryTab() -> Element {
+    let mut tag = use_signal(|| None::<String>);
+    let mut tags = use_resource(|| async { api::get_download_tags().await.unwrap_or_default() });
+    let history = use_resource(move || async move {
+        match tag() {
+            Some(tag) => api::get_tagged_downloads(tag).await,
+            None => api::get_download_history().await,
+        }
+    });
+
+    let items = match &*history.read() {
+        Some(Ok(items)) => items.clone(),
+        _ => vec![],
+    };
+    let all_tags = tags.read().clone().unwrap_or_default();
+
+    rsx! {
+        div { class: "space-y-4",
+            div { class: "flex items-center justify-between",
+                h3 { class: "text-sm font-semibold text-white", "Download History" }
+                ExportButtons { kind: ExportKind::History }
+            }
+
+            if !all_tags.is_empty() {
+                div { class: "flex flex-wrap items-center gap-1 text-xs font-mono",
+                    span { class: "text-gray-500 mr-1", "Tags" }
+                    for name in all_tags {
+                        {
+                            let active = tag().as_deref() == Some(name.as_str());
+                            let picked = name.clone();
+                            rsx! {
+                                button {
+                                    key: "{name}",
+                                    class: if active { "px-1.5 rounded border border-beet-accent bg-beet-accent/20 text-beet-accent cursor-pointer" } else { "px-1.5 rounded border border-white/10 text-gray-400 hover:text-white cursor-pointer" },
+                                    onclick: move |_| tag.set(if active { None } else { Some(picked.clone()) }),
+                                    "{name}"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if items.is_empty() {
+                p { class: "text-gray-500 font-mono text-sm",
+                    if tag().is_some() { "No downloads with this tag." } else { "No finished downloads yet." }
+                }
+            } else {
+                div { class: "space-y-1 max-h-96 overflow-y-auto",
+                    for item in items {
+                        {
+                            let name = item.item.rsplit(['/', '\\']).next().unwrap_or(&item.item).to_string();
+                            let date = chrono::DateTime::from_timestamp(item.finished_at, 0)
+                                .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                                .unwrap_or_default();
+                            let outcome_class = match item.outcome.as_str() {
+                                "imported" => "text-beet-leaf",
+                                "failed" => "text-red-400",
+                                _ => "text-gray-400",
+                            };
+                            rsx! {
+                                div { class: "p-2 bg-beet-panel border border-white/10 rounded text-sm",
+                                    key: "{item.id}",
+                                    div { class: "flex items-center justify-between",
+                                        title: item.error.clone().unwrap_or_default(),
+                                        div { class: "flex-1 min-w-0 truncate",
+                                            span { class: "text-white", "{name}" }
+                                            if let Some(album) = &item.album {
+                                                span { class: "text-gray-400 mx-2", "-" }
+                                                span { class: "text-gray-400", "{album}" }
+                                            }
+                                        }
+                                        span { class: "text-xs font-mono text-gray-500 ml-2", "{item.peer}" }
+                                        span { class: "text-xs font-mono text-gray-500 ml-2", "{date}" }
+                                        span { class: "text-xs font-mono ml-2 {outcome_class}", "{item.outcome}" }
+                                        if item.outcome == "imported" && !item.id.is_empty() {
+                                            FeedbackButtons {
+                                                history_id: item.id.clone(),
+                                                feedback: item.feedback,
+                                                can_search_again: item.imported_path.is_some() && item.artist.is_some() && item.album.is_some(),
+                                            }
+                                            if item.imported_path.is_some() && item.artist.is_some() && item.album.is_some() {
+                                                ReplaceButton { history_id: item.id.clone() }
+                                            }
+                                        }
+                                    }
+                                    if !item.id.is_empty() {
+                                        NoteEditor {
+                                            download_id: item.note_key().to_string(),
+                                            note: item.note.clone(),
+                                            on_saved: move |_| tags.restart(),
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Thumbs up, or a complaint about what an imported download turned out to
+/// be, optionally deleting it to search for the album again.
+#[component]
+fn FeedbackButtons(
+    history_id: String,
+    feedback: Option<DownloadFeedback>,
+    can_search_again: bool,
+) -> Element {
+    let history_id = use_signal(|| history_id);
+    let mut current = use_signal(|| feedback);
+    let mut complaining = use_signal(|| false);
+    let mut search_again = use_signal(|| false);
+    let mut searched_again = use_signal(|| false);
+    let mut error = use_signal(String::new);
+
+    let rate = move |feedback: DownloadFeedback| async move {
+        error.set(String::new());
+        let redo = search_again() && feedback.is_complaint();
+        match api::rate_download(history_id(), feedback, redo).await {
+            Ok(()) => {
+                current.set(Some(feedback));
+                complaining.set(false);
+                searched_again.set(redo);
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "flex items-center gap-1 ml-2 text-xs font-mono shrink-0",
+            if searched_again() {
+                span { class: "text-gray-400", "Deleted, on wishlist" }
+            } else if complaining() {
+                for complaint in DownloadFeedback::COMPLAINTS {
+                    button {
+                        class: "px-1.5 py-0.5 rounded border border-white/10 text-gray-300 hover:text-red-400 cursor-pointer",
+                        onclick: move |_| rate(complaint),
+                        "{complaint.label()}"
+                    }
+                }
+                if can_search_again {
+                    div {
+                        class: "flex items-center gap-1 cursor-pointer text-gray-400",
+                        title: "Delete the file and add its album to the wishlist",
+                        onclick: move |_| search_again.set(!search_again()),
+                        Checkbox { is_selected: search_again() }
+                        "search again"
+                    }
+                }
+                button {
+                    class: "px-1.5 py-0.5 text-gray-500 hover:text-white cursor-pointer",
+                    onclick: move |_| complaining.set(false),
+                    "Cancel"
+                }
+            } else {
+                if let Some(feedback) = current() {
+                    span {
+                        class: if feedback.is_complaint() { "text-red-400" } else { "text-beet-leaf" },
+                        "{feedback.label()}"
+                    }
+                }
+                button {
+                    class: "px-1 text-gray-500 hover:text-beet-leaf cursor-pointer",
+                    title: "Good copy",
+                    onclick: move |_| rate(DownloadFeedback::Good),
+                    "👍"
+                }
+                button {
+                    class: "px-1 text-gray-500 hover:text-red-400 cursor-pointer",
+                    title: "Report a problem with this file",
+                    onclick: move |_| complaining.set(true),
+                    "👎"
+                }
+            }
+            if !error().is_empty() {
+                span { class: "text-red-400", title: "{error}", "!" }
+            }
+        }
+    }
+}
+
+/// Delete or archive an imported album and download it again from another
+/// peer, into the same folder.
+#[component]
+fn ReplaceButton(history_id: String) -> Element {
+    let history_id = use_signal(|| history_id);
+    let mut confirming = use_signal(|| false);
+    let mut status = use_signal(String::new);
+
+    let replace = move |archive: bool| async move {
+        confirming.set(false);
+        status.set("Replacing...".to_string());
+        match api::replace_download(history_id(), archive).await {
+            Ok(api::AutoDownloadResult::Accepted { .. }) => {
+                status.set("Searching again".to_string())
+            }
+            Ok(api::AutoDownloadResult::Error(e)) => status.set(e),
+            Err(e) => status.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "flex items-center gap-1 ml-2 text-xs font-mono shrink-0",
+            if confirming() {
+                button {
+                    class: "px-1.5 py-0.5 rounded border border-red-500/30 text-red-400 hover:bg-red-900/30 cursor-pointer",
+                    title: "Delete the album's files, then download it again",
+                    onclick: move |_| replace(false),
+                    "Delete & replace"
+                }
+                button {
+                    class: "px-1.5 py-0.5 rounded border border-white/10 text-gray-300 hover:text-white cursor-pointer",
+                    title: "Move the album's files to .replaced in the folder, then download it again",
+                    onclick: move |_| replace(true),
+                    "Archive & replace"
+                }
+                button {
+                    class: "px-1.5 py-0.5 text-gray-500 hover:text-white cursor-pointer",
+                    onclick: move |_| confirming.set(false),
+                    "Cancel"
+                }
+            } else if !status().is_empty() {
+                span { class: "text-gray-400", "{status}" }
+            } else {
+                button {
+                    class: "px-1.5 py-0.5 text-gray-500 hover:text-white cursor-pointer",
+                    title: "Download this album again from another peer",
+                    onclick: move |_| confirming.set(true),
+                    "Replace"
+                }
+            }
+        }
+    }
+}
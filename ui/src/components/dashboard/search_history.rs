@@ -0,0 +1,116 @@
+use api::get_search_history;
+use dioxus::prelude::*;
+use shared::download::{DownloadQuery, StrategyStats};
+
+use crate::{friendly_error, use_auth};
+
+fn percent(rate: Option<f64>) -> String {
+    rate.map(|r| format!("{:.0}%", r * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+#[component]
+fn StrategyTable(strategies: Vec<StrategyStats>) -> Element {
+    rsx! {
+        table { class: "w-full text-xs font-mono",
+            thead {
+                tr { class: "text-gray-500 uppercase tracking-wider text-left",
+                    th { class: "pb-2", "Strategy" }
+                    th { class: "pb-2 text-right", "Searches" }
+                    th { class: "pb-2 text-right", "Found" }
+                    th { class: "pb-2 text-right", "Hit rate" }
+                    th { class: "pb-2 text-right", "Avg. peers" }
+                }
+            }
+            tbody {
+                for stats in strategies.iter() {
+                    tr { class: "text-gray-300 border-t border-white/5",
+                        td { class: "py-1", "{stats.strategy.label()}" }
+                        td { class: "py-1 text-right", "{stats.searches}" }
+                        td { class: "py-1 text-right", "{stats.with_candidates}" }
+                        td { class: "py-1 text-right", {percent(stats.hit_rate())} }
+                        td { class: "py-1 text-right", "{stats.mean_responses:.1}" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The user's recent Soulseek searches: the text sent, how many peers
+/// answered and how many results matched, with a way to run one again.
+#[component]
+pub fn SearchHistoryTab(on_rerun: EventHandler<DownloadQuery>) -> Element {
+    let auth = use_auth();
+    let history = use_resource(move || async move { auth.call(get_search_history).await });
+
+    let body = match &*history.read() {
+        None => rsx! {
+            p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." }
+        },
+        Some(Err(e)) => rsx! {
+            p { class: "text-sm font-mono text-red-400", {friendly_error(e)} }
+        },
+        Some(Ok(history)) if history.searches.is_empty() => rsx! {
+            p { class: "text-gray-500 font-mono text-sm", "No searches yet." }
+        },
+        Some(Ok(history)) => rsx! {
+            if !history.strategies.is_empty() {
+                div { class: "bg-beet-panel border border-white/10 p-4 rounded-lg",
+                    StrategyTable { strategies: history.strategies.clone() }
+                }
+            }
+            div { class: "space-y-1 max-h-96 overflow-y-auto",
+                for record in history.searches.iter().cloned() {
+                    {
+                        let date = chrono::DateTime::from_timestamp(record.started_at, 0)
+                            .map(|d| d.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        let found_class = if record.candidates > 0 { "text-beet-leaf" } else { "text-red-400" };
+                        let query = record.query.clone();
+                        rsx! {
+                            div {
+                                key: "{record.id}",
+                                class: "flex items-center gap-2 p-2 bg-beet-panel border border-white/10 rounded text-sm",
+                                div { class: "flex-1 min-w-0",
+                                    p { class: "text-white truncate", "{record.description}" }
+                                    if let Some(text) = &record.search_text {
+                                        p { class: "text-xs font-mono text-gray-500 truncate", title: "{text}",
+                                            "“{text}”"
+                                        }
+                                    }
+                                }
+                                span { class: "text-xs font-mono text-gray-500 shrink-0",
+                                    "{record.strategy.label()}"
+                                    if record.automatic {
+                                        " · auto"
+                                    }
+                                }
+                                span {
+                                    class: "text-xs font-mono text-gray-400 shrink-0",
+                                    title: "Peers that answered, waited for up to {record.timeout_secs}s",
+                                    "{record.responses} peers"
+                                }
+                                span { class: "text-xs font-mono shrink-0 {found_class}", "{record.candidates} found" }
+                                span { class: "text-xs font-mono text-gray-500 shrink-0", "{date}" }
+                                button {
+                                    class: "px-1.5 py-0.5 text-xs font-mono text-gray-500 hover:text-white cursor-pointer shrink-0",
+                                    title: "Run this search again",
+                                    onclick: move |_| on_rerun.call(query.clone()),
+                                    "Run again"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "space-y-4",
+            h3 { class: "text-sm font-semibold text-white", "Search History" }
+            {body}
+        }
+    }
+}
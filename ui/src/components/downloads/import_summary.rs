@@ -0,0 +1,52 @@
+use dioxus::prelude::*;
+use shared::download::ImportSummary;
+
+/// Where an imported album landed, the tags the importer corrected and any
+/// warnings, with a button copying the folder path.
+#[component]
+pub fn ImportSummaryCard(label: Option<String>, summary: ImportSummary) -> Element {
+    let mut copied = use_signal(|| false);
+    let destination = summary.destination.clone();
+
+    let copy_path = move |_| {
+        let path = serde_json::to_string(&destination).unwrap_or_default();
+        document::eval(&format!("navigator.clipboard.writeText({path})"));
+        copied.set(true);
+    };
+
+    rsx! {
+      div { class: "bg-green-500/5 border border-green-500/30 p-4 rounded-lg",
+        if let Some(label) = label {
+          div { class: "text-sm font-bold text-white truncate mb-1", title: "{label}", "{label}" }
+        }
+        div { class: "flex items-start justify-between gap-2",
+          div { class: "text-xs text-green-300 font-mono break-all", "{summary.describe()}" }
+          button {
+            class: "text-[10px] font-mono uppercase tracking-wider border border-white/10 rounded px-1.5 py-0.5 text-gray-400 hover:text-white hover:border-white/30 transition-colors cursor-pointer shrink-0",
+            title: "Copy the folder path",
+            onclick: copy_path,
+            if copied() { "Copied" } else { "Copy path" }
+          }
+        }
+        if !summary.corrections.is_empty() {
+          ul { class: "mt-2 space-y-0.5 text-xs font-mono text-gray-400",
+            for correction in summary.corrections.iter() {
+              li {
+                span { class: "text-gray-500", "{correction.field}: " }
+                span { class: "line-through", "{correction.before}" }
+                " \u{2192} "
+                span { class: "text-white", "{correction.after}" }
+              }
+            }
+          }
+        }
+        if !summary.warnings.is_empty() {
+          ul { class: "mt-2 space-y-0.5 text-xs font-mono text-yellow-300",
+            for warning in summary.warnings.iter() {
+              li { "{warning}" }
+            }
+          }
+        }
+      }
+    }
+}
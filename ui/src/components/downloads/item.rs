@@ -1,8 +1,32 @@
 use dioxus::prelude::*;
-use shared::download::{DownloadProgress, DownloadState};
+use shared::download::{DownloadNote, DownloadProgress, DownloadState};
+
+use super::NoteEditor;
+use crate::Checkbox;
+
+/// Label and colour of an import into one of a download's extra folders.
+fn target_status(state: &DownloadState) -> (&'static str, &'static str) {
+    match state {
+        DownloadState::Queued => ("waiting", "text-gray-500"),
+        DownloadState::Importing | DownloadState::ImportPaused | DownloadState::ImportWaiting => {
+            ("importing", "text-beet-leaf")
+        }
+        DownloadState::Uploading(_) => ("uploading", "text-beet-leaf"),
+        DownloadState::Imported => ("imported", "text-green-300"),
+        DownloadState::ImportSkipped => ("skipped", "text-yellow-300"),
+        DownloadState::Failed(_) => ("failed", "text-red-400"),
+        _ => ("", "text-gray-500"),
+    }
+}
 
 #[component]
-pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProgress>) -> Element {
+pub fn DownloadItem(
+    file: DownloadProgress,
+    is_selected: bool,
+    note: Option<DownloadNote>,
+    on_cancel: EventHandler<DownloadProgress>,
+    on_select: EventHandler<String>,
+) -> Element {
     let state = &file.state;
     let is_cancellable = matches!(state, DownloadState::Queued | DownloadState::InProgress);
 
@@ -25,12 +49,30 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
             "bg-beet-leaf/20 text-beet-leaf",
             "DOWNLOADED",
         ),
+        DownloadState::ImportWaiting => (
+            "Waiting for import",
+            "border-beet-leaf/30",
+            "border border-beet-leaf/50 text-beet-leaf",
+            "WAIT",
+        ),
+        DownloadState::ImportPaused => (
+            "Paused: target folder unavailable",
+            "border-yellow-500/30",
+            "border border-yellow-500/50 text-yellow-300",
+            "PAUSED",
+        ),
         DownloadState::Importing => (
             "Importing...",
             "border-beet-leaf/50",
             "bg-beet-leaf/20 text-beet-leaf",
             "BEETS",
         ),
+        DownloadState::Uploading(_) => (
+            "Uploading to remote",
+            "border-beet-leaf/50",
+            "bg-beet-leaf/20 text-beet-leaf",
+            "UP",
+        ),
         DownloadState::Imported => (
             "Imported",
             "border-green-500/50",
@@ -55,6 +97,12 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
             "bg-orange-500/20 text-orange-300",
             "CANCEL",
         ),
+        DownloadState::Simulated => (
+            "Simulated: nothing was downloaded",
+            "border-dashed border-purple-500/40",
+            "bg-purple-500/20 text-purple-300",
+            "SIM",
+        ),
     };
 
     let percent = file.percent as i32;
@@ -73,14 +121,20 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
     };
 
     let error_msg = file.error.clone();
+    let select_key = file.item.clone();
 
     rsx! {
       div { class: "bg-white/5 border {border_class} p-4 rounded-lg hover:border-beet-accent/50 transition-colors group",
         div { class: "flex justify-between items-start mb-2",
           div {
-            class: "text-sm font-bold text-white truncate w-3/4 pr-2",
-            title: "{file.item}",
-            "{display_name}"
+            class: "flex items-center gap-2 w-3/4 pr-2 min-w-0 cursor-pointer",
+            onclick: move |_| on_select.call(select_key.clone()),
+            Checkbox { is_selected }
+            div {
+              class: "text-sm font-bold text-white truncate",
+              title: "{file.item}",
+              "{display_name}"
+            }
           }
           div { class: "flex items-center gap-1.5 shrink-0",
             span {
@@ -115,7 +169,10 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
           span { "{percent}%" }
         }
         // Progress Bar
-        if matches!(state, DownloadState::InProgress | DownloadState::Importing) {
+        if matches!(
+            state,
+            DownloadState::InProgress | DownloadState::Importing | DownloadState::Uploading(_)
+        ) {
           div { class: "h-2 w-full bg-gray-800 rounded-full overflow-hidden relative",
             div {
               class: "h-full bg-beet-accent absolute top-0 left-0 transition-all duration-300",
@@ -134,6 +191,32 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
             div { class: "text-xs text-red-400 mt-1 break-words", "{err}" }
           }
         }
+        if let Some(imported) = file.imported_file.as_ref().filter(|_| matches!(state, DownloadState::Imported)) {
+          div { class: "flex items-center gap-2 text-xs text-gray-400 font-mono mt-1 min-w-0",
+            span { class: "truncate", title: "{imported.path}", "{imported.path}" }
+            if imported.duplicate {
+              span {
+                class: "text-[10px] border border-yellow-500/50 text-yellow-300 px-1 rounded uppercase shrink-0",
+                title: "This file was already in the library",
+                "DUP"
+              }
+            }
+          }
+        }
+        if let Some(planned) = &file.planned_path {
+          div {
+            class: "text-xs text-purple-300 font-mono mt-1 truncate",
+            title: "{planned}",
+            "\u{2192} {planned}"
+          }
+        }
+        if file.tag_derived && matches!(state, DownloadState::Imported) {
+          div {
+            class: "text-xs text-yellow-300 font-mono mt-1",
+            title: "MusicBrainz was unreachable, so the album, artist and year were taken from the files' own tags",
+            "Tag-derived metadata"
+          }
+        }
         if matches!(state, DownloadState::Importing) {
           div { class: "flex items-center gap-2 text-xs text-gray-300 font-mono mt-2",
             svg {
@@ -157,11 +240,46 @@ pub fn DownloadItem(file: DownloadProgress, on_cancel: EventHandler<DownloadProg
             "Moving and tagging..."
           }
         }
+        if let DownloadState::Uploading(attempt) = state {
+          div { class: "text-xs text-gray-300 font-mono mt-2",
+            if *attempt > 1 {
+              "Upload failed, retrying (attempt {attempt})"
+            } else {
+              "Uploading to remote library..."
+            }
+          }
+        }
+        if !file.target_imports.is_empty() {
+          div { class: "mt-1 space-y-0.5 text-xs font-mono",
+            for target in file.target_imports.iter() {
+              {
+                  let (label, class) = target_status(&target.state);
+                  let error = match &target.state {
+                      DownloadState::Failed(e) => e.clone(),
+                      _ => String::new(),
+                  };
+                  rsx! {
+                    div { key: "{target.folder_id}", class: "flex items-center gap-2 min-w-0",
+                      span { class: "text-gray-500 truncate", "\u{2192} {target.folder}" }
+                      span {
+                        class: "shrink-0 {class}",
+                        title: "{error}",
+                        "{label}"
+                      }
+                    }
+                  }
+              }
+            }
+          }
+        }
+        if !file.id.is_empty() {
+          NoteEditor { download_id: file.id.clone(), note }
+        }
       }
     }
 }
 
-fn format_size(bytes: u64) -> String {
+pub(crate) fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
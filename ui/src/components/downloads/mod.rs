@@ -1,11 +1,44 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use dioxus::prelude::*;
-use shared::download::{DownloadProgress, DownloadState};
+use shared::download::{DownloadProgress, DownloadState, ImportSummary};
 
+mod import_summary;
 mod item;
+mod note;
 use api::CancelDownloadRequest;
+use import_summary::ImportSummaryCard;
+pub(crate) use item::format_size;
 use item::DownloadItem;
+pub(crate) use note::NoteEditor;
+
+use crate::friendly_error;
+
+/// Downloads of the session, for pages that summarize them.
+#[derive(Clone, Copy)]
+pub struct ActiveDownloads(pub Signal<HashMap<String, DownloadProgress>>);
+
+pub(crate) fn is_active(state: &DownloadState) -> bool {
+    matches!(
+        state,
+        DownloadState::Queued
+            | DownloadState::InProgress
+            | DownloadState::ImportWaiting
+            | DownloadState::ImportPaused
+            | DownloadState::Importing
+            | DownloadState::Uploading(_)
+            | DownloadState::Completed // Downloads that are completed but not yet imported
+    )
+}
+
+fn cancel_request(file: &DownloadProgress) -> CancelDownloadRequest {
+    CancelDownloadRequest {
+        id: file.id.clone(),
+        source: file.source.clone(),
+        item: file.item.clone(),
+        backend: file.backend.clone(),
+    }
+}
 
 #[derive(Props, Clone, PartialEq)]
 pub struct DownloadsProps {
@@ -26,15 +59,7 @@ pub fn Downloads(mut props: DownloadsProps) -> Element {
     // Count specific states for the header summary
     let processing_count = active_downloads
         .iter()
-        .filter(|f| {
-            matches!(
-                f.state,
-                DownloadState::Queued
-                    | DownloadState::InProgress
-                    | DownloadState::Importing
-                    | DownloadState::Completed // Still needs to be imported
-            )
-        })
+        .filter(|f| is_active(&f.state))
         .count();
 
     let errored_count = active_downloads
@@ -42,27 +67,150 @@ pub fn Downloads(mut props: DownloadsProps) -> Element {
         .filter(|f| matches!(f.state, DownloadState::Failed(_) | DownloadState::Cancelled))
         .count();
 
+    // One summary per imported album, its tracks all sharing it
+    let mut import_summaries: Vec<(Option<String>, ImportSummary)> = Vec::new();
+    for file in &active_downloads {
+        if let Some(summary) = &file.import_summary {
+            if !import_summaries.iter().any(|(_, s)| s == summary) {
+                import_summaries.push((file.batch_label.clone(), summary.clone()));
+            }
+        }
+    }
+
+    let failed_imports: Vec<String> = active_downloads
+        .iter()
+        .filter(|f| f.is_failed_import())
+        .map(|f| f.item.clone())
+        .collect();
+
+    // Notes of the listed downloads, fetched again when the list changes
+    let downloads = props.downloads;
+    let download_ids = use_memo(move || {
+        let mut ids: Vec<String> = downloads
+            .read()
+            .values()
+            .map(|f| f.id.clone())
+            .filter(|id| !id.is_empty())
+            .collect();
+        ids.sort();
+        ids
+    });
+    let notes = use_resource(move || async move {
+        let ids = download_ids();
+        if ids.is_empty() {
+            return HashMap::new();
+        }
+        api::get_download_notes(ids).await.unwrap_or_default()
+    });
+
+    // Items picked for bulk actions, dropped once they leave the list
+    let mut selected = use_signal(HashSet::<String>::new);
+    let mut bulk_error = use_signal(String::new);
+    let selected_count = selected
+        .read()
+        .iter()
+        .filter(|item| props.downloads.read().contains_key(*item))
+        .count();
+    let all_selected = selected_count > 0 && selected_count == active_downloads.len();
+
+    let mut downloads_signal = props.downloads;
+
+    // Drop finished entries among `items` from the panel and the server's
+    // retry history
+    let mut clear_items = move |items: Vec<String>| {
+        let cleared: Vec<String> = {
+            let mut map = downloads_signal.write();
+            let cleared = items
+                .into_iter()
+                .filter(|item| map.get(item).is_some_and(|f| !is_active(&f.state)))
+                .collect::<Vec<_>>();
+            for item in &cleared {
+                map.remove(item);
+            }
+            cleared
+        };
+        if cleared.is_empty() {
+            return;
+        }
+        selected.write().retain(|item| !cleared.contains(item));
+        spawn(async move {
+            let _ = api::clear_downloads(cleared).await;
+        });
+    };
+
     let clear_finished = move |_| {
-        let mut map = props.downloads.write();
-        map.retain(|_, file| {
-            matches!(
-                file.state,
-                DownloadState::Queued
-                    | DownloadState::InProgress
-                    | DownloadState::Importing
-                    | DownloadState::Completed // Downloads that are completed but not yet imported
-            )
+        let finished: Vec<String> = downloads_signal.read().keys().cloned().collect();
+        clear_items(finished);
+    };
+
+    let mut retry_items = move |items: Vec<String>| {
+        if items.is_empty() {
+            return;
+        }
+        bulk_error.set(String::new());
+        spawn(async move {
+            match api::retry_downloads(items.clone()).await {
+                Ok(_) => selected.write().retain(|item| !items.contains(item)),
+                Err(e) => bulk_error.set(friendly_error(&e)),
+            }
         });
     };
 
-    let mut downloads_signal = props.downloads;
-    let cancel_download = move |file: DownloadProgress| {
-        let req = CancelDownloadRequest {
-            id: file.id.clone(),
-            source: file.source.clone(),
-            item: file.item.clone(),
-            backend: file.backend.clone(),
+    let toggle_all = move |_| {
+        if all_selected {
+            selected.write().clear();
+        } else {
+            let items: HashSet<String> = downloads_signal.read().keys().cloned().collect();
+            selected.set(items);
+        }
+    };
+
+    let cancel_selected = move |_| {
+        let reqs: Vec<CancelDownloadRequest> = {
+            let map = downloads_signal.read();
+            selected
+                .read()
+                .iter()
+                .filter_map(|item| map.get(item))
+                .filter(|f| matches!(f.state, DownloadState::Queued | DownloadState::InProgress))
+                .map(cancel_request)
+                .collect()
         };
+        if reqs.is_empty() {
+            return;
+        }
+        bulk_error.set(String::new());
+        spawn(async move {
+            if let Err(e) = api::cancel_downloads(reqs).await {
+                bulk_error.set(friendly_error(&e));
+            }
+        });
+    };
+
+    let retry_selected = move |_| {
+        let items: Vec<String> = {
+            let map = downloads_signal.read();
+            selected
+                .read()
+                .iter()
+                .filter(|item| {
+                    map.get(*item).is_some_and(|f| {
+                        matches!(f.state, DownloadState::Failed(_) | DownloadState::Cancelled)
+                    })
+                })
+                .cloned()
+                .collect()
+        };
+        retry_items(items);
+    };
+
+    let clear_selected = move |_| {
+        let items: Vec<String> = selected.read().iter().cloned().collect();
+        clear_items(items);
+    };
+
+    let cancel_download = move |file: DownloadProgress| {
+        let req = cancel_request(&file);
         let item_key = file.item.clone();
         spawn(async move {
             if api::cancel_download(req).await.is_ok() {
@@ -121,6 +269,40 @@ pub fn Downloads(mut props: DownloadsProps) -> Element {
             }
           }
 
+          if !active_downloads.is_empty() {
+            div { class: "px-6 py-2 border-b border-white/10 flex items-center gap-3 text-[10px] font-mono uppercase tracking-widest",
+              button {
+                class: "text-gray-400 hover:text-white transition-colors cursor-pointer",
+                onclick: toggle_all,
+                if all_selected { "[ None ]" } else { "[ All ]" }
+              }
+              if selected_count > 0 {
+                span { class: "text-gray-500", "{selected_count} selected" }
+                div { class: "flex-1" }
+                button {
+                  class: "text-gray-400 hover:text-red-400 transition-colors cursor-pointer",
+                  onclick: cancel_selected,
+                  "[ Cancel ]"
+                }
+                button {
+                  class: "text-gray-400 hover:text-beet-leaf transition-colors cursor-pointer",
+                  onclick: retry_selected,
+                  "[ Retry ]"
+                }
+                button {
+                  class: "text-gray-400 hover:text-white transition-colors cursor-pointer",
+                  onclick: clear_selected,
+                  "[ Clear ]"
+                }
+              }
+            }
+          }
+          if !bulk_error().is_empty() {
+            div { class: "mx-6 mt-4 p-3 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-xs",
+              "{bulk_error}"
+            }
+          }
+
           // Content
           div { class: "flex-1 overflow-y-auto p-6 no-scrollbar space-y-4",
             if active_downloads.is_empty() {
@@ -129,12 +311,40 @@ pub fn Downloads(mut props: DownloadsProps) -> Element {
               }
             }
 
+            for (label, summary) in import_summaries.iter() {
+              ImportSummaryCard { label: label.clone(), summary: summary.clone() }
+            }
+
             for file in active_downloads.iter() {
-              DownloadItem { file: file.clone(), on_cancel: cancel_download }
+              DownloadItem {
+                is_selected: selected.read().contains(&file.item),
+                note: notes.read().as_ref().and_then(|n| n.get(&file.id).cloned()),
+                file: file.clone(),
+                on_cancel: cancel_download,
+                on_select: move |item: String| {
+                    let mut selected = selected.write();
+                    if !selected.remove(&item) {
+                        selected.insert(item);
+                    }
+                },
+              }
             }
           }
           // Footer
-          div { class: "p-4 border-t border-white/10 bg-black/20",
+          div { class: "p-4 border-t border-white/10 bg-black/20 space-y-2",
+            if !failed_imports.is_empty() {
+              {
+                let count = failed_imports.len();
+                let failed_imports = failed_imports.clone();
+                rsx! {
+                  button {
+                    class: "w-full py-2 text-xs font-mono uppercase tracking-widest text-center border border-white/10 hover:bg-white/5 text-gray-400 hover:text-beet-leaf transition-colors cursor-pointer hover:border-beet-leaf/30",
+                    onclick: move |_| retry_items(failed_imports.clone()),
+                    "RETRY FAILED IMPORTS ({count})"
+                  }
+                }
+              }
+            }
             button {
               class: "w-full py-2 text-xs font-mono uppercase tracking-widest text-center border border-white/10 hover:bg-white/5 text-gray-400 hover:text-white transition-colors cursor-pointer hover:border-red-500/30",
               onclick: clear_finished,
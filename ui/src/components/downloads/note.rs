@@ -0,0 +1,110 @@
+use dioxus::prelude::*;
+use shared::download::DownloadNote;
+
+use crate::friendly_error;
+
+const INPUT_CLASS: &str = "w-full px-2 py-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-xs";
+
+/// A download's note and tags, with a way to edit them. `download_id` is
+/// what the note is kept under: the transfer's id.
+#[component]
+pub fn NoteEditor(
+    download_id: String,
+    note: Option<DownloadNote>,
+    on_saved: Option<EventHandler<DownloadNote>>,
+) -> Element {
+    let download_id = use_signal(|| download_id);
+    // What was saved here, which wins over the note given
+    let mut saved = use_signal(|| None::<DownloadNote>);
+    let mut editing = use_signal(|| false);
+    let mut text = use_signal(String::new);
+    let mut tags = use_signal(String::new);
+    let mut error = use_signal(String::new);
+
+    let current = saved().or(note).filter(|n| !n.is_empty());
+
+    let start_editing = {
+        let current = current.clone().unwrap_or_default();
+        move |_| {
+            text.set(current.note.clone());
+            tags.set(current.tags.join(", "));
+            error.set(String::new());
+            editing.set(true);
+        }
+    };
+
+    let save = move |_| async move {
+        error.set(String::new());
+        let note = DownloadNote {
+            note: text(),
+            tags: DownloadNote::parse_tags(&tags()),
+        };
+        match api::set_download_note(download_id(), note).await {
+            Ok(note) => {
+                saved.set(Some(note.clone()));
+                editing.set(false);
+                if let Some(on_saved) = on_saved {
+                    on_saved.call(note);
+                }
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    if editing() {
+        return rsx! {
+            div {
+                class: "space-y-1 mt-2",
+                onclick: move |e| e.stop_propagation(),
+                input {
+                    class: INPUT_CLASS,
+                    value: "{text}",
+                    placeholder: "Note",
+                    oninput: move |e| text.set(e.value()),
+                }
+                input {
+                    class: INPUT_CLASS,
+                    value: "{tags}",
+                    placeholder: "Tags, comma separated: for dad, vinyl rip",
+                    oninput: move |e| tags.set(e.value()),
+                }
+                div { class: "flex items-center gap-2 text-xs font-mono",
+                    button {
+                        class: "px-1.5 py-0.5 rounded border border-white/10 text-gray-300 hover:text-beet-leaf cursor-pointer",
+                        onclick: save,
+                        "Save"
+                    }
+                    button {
+                        class: "px-1.5 py-0.5 text-gray-500 hover:text-white cursor-pointer",
+                        onclick: move |_| editing.set(false),
+                        "Cancel"
+                    }
+                    if !error().is_empty() {
+                        span { class: "text-red-400 truncate", "{error}" }
+                    }
+                }
+            }
+        };
+    }
+
+    rsx! {
+        div { class: "flex flex-wrap items-center gap-1 mt-1 text-xs font-mono min-w-0",
+            if let Some(current) = &current {
+                for tag in current.tags.iter() {
+                    span { class: "px-1.5 rounded bg-beet-accent/10 border border-beet-accent/30 text-beet-accent",
+                        "{tag}"
+                    }
+                }
+                if !current.note.is_empty() {
+                    span { class: "text-gray-400 italic truncate", title: "{current.note}", "{current.note}" }
+                }
+            }
+            button {
+                class: "px-1 text-gray-600 hover:text-white cursor-pointer",
+                title: "Add a note or tags",
+                onclick: start_editing,
+                if current.is_some() { "edit" } else { "+ note" }
+            }
+        }
+    }
+}
@@ -1,18 +1,34 @@
 use dioxus::logger::tracing::warn;
-use dioxus::prelude::ServerFnError;
+use dioxus::prelude::*;
+use shared::error::{ApiError, ApiErrorKind};
+
+/// The typed error a server function failed with. Errors sent without a
+/// kind are classified from their status code.
+pub fn api_error(error: &ServerFnError) -> ApiError {
+    match error {
+        ServerFnError::ServerError {
+            message,
+            code,
+            details,
+        } => details
+            .as_ref()
+            .and_then(|d| serde_json::from_value::<ApiError>(d.clone()).ok())
+            .unwrap_or_else(|| ApiError::new(ApiErrorKind::from_status(*code), message.clone())),
+        ServerFnError::Request(_) => ApiError::new(ApiErrorKind::Network, error.to_string()),
+        _ => ApiError::new(ApiErrorKind::Internal, error.to_string()),
+    }
+}
 
 /// Maps a ServerFnError to a short, user-facing message.
 /// Logs the full technical error via tracing::warn for debugging.
 pub fn friendly_error(error: &ServerFnError) -> String {
     warn!("Server error: {error:?}");
 
-    match error {
-        ServerFnError::ServerError { code: 401, .. } => {
-            "Session expired. Please log in again.".to_string()
-        }
-        ServerFnError::ServerError {
-            code: 500, message, ..
-        } => {
+    let error = api_error(error);
+    match error.kind {
+        // Messages of errors raised without a kind still carry the hint
+        ApiErrorKind::Internal => {
+            let message = &error.message;
             if message.contains("not found") {
                 "The requested item was not found.".to_string()
             } else if message.contains("not authorized") || message.contains("Not authorized") {
@@ -20,15 +36,38 @@ pub fn friendly_error(error: &ServerFnError) -> String {
             } else if message.contains("already") {
                 message.clone()
             } else {
-                "Something went wrong. Try again.".to_string()
+                error.describe()
             }
         }
-        ServerFnError::ServerError { code, .. } => {
-            format!("Server error ({code}). Try again.")
-        }
-        ServerFnError::Request(_) => {
-            "Could not reach the server. Check your connection.".to_string()
+        _ => error.describe(),
+    }
+}
+
+/// Whether retrying the failed call may succeed.
+pub fn is_retryable(error: &ServerFnError) -> bool {
+    api_error(error).kind.is_retryable()
+}
+
+/// A failed call's message, with a retry button when the failure looks
+/// temporary (an upstream service or the connection).
+#[component]
+pub fn ErrorNotice(error: ApiError, on_retry: Option<EventHandler<()>>) -> Element {
+    let message = match error.kind {
+        ApiErrorKind::Internal if error.message.contains("already") => error.message.clone(),
+        _ => error.describe(),
+    };
+    let retry = on_retry.filter(|_| error.kind.is_retryable());
+
+    rsx! {
+      div { class: "w-full p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm flex items-center justify-between gap-4",
+        span { "{message}" }
+        if let Some(retry) = retry {
+          button {
+            class: "text-xs uppercase tracking-wider border border-red-500/50 rounded px-2 py-1 hover:bg-red-500/20 shrink-0",
+            onclick: move |_| retry.call(()),
+            "Retry"
+          }
         }
-        _ => "Something went wrong. Try again.".to_string(),
+      }
     }
 }
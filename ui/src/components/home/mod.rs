@@ -0,0 +1,321 @@
+use api::models::folder::Folder;
+use api::{folder_diagnostics, get_home_summary, get_user_folders, get_watchlist_hits};
+use dioxus::prelude::*;
+use shared::calendar::CalendarRelease;
+use shared::download::{remote_file_name, DownloadState};
+use shared::home::{HomeSummary, RecentImport};
+use shared::system::FolderDiagnostics;
+
+use crate::downloads::{is_active, ActiveDownloads};
+use crate::{use_auth, use_system_health, SystemStatus};
+
+/// Downloads, hits and imports listed per card
+const CARD_ROWS: usize = 5;
+
+const CARD_CLASS: &str = "bg-beet-panel border border-white/10 p-5 rounded-lg shadow-2xl";
+const CARD_TITLE_CLASS: &str =
+    "text-xs font-mono text-gray-400 uppercase tracking-wider mb-3 flex justify-between";
+
+/// Home page at a glance: a quick search over the running downloads,
+/// recent imports, new releases of watched artists, backend health and
+/// free space. `on_search` takes an artist (possibly empty) and a query.
+#[component]
+pub fn HomeDashboard(
+    on_search: EventHandler<(String, String)>,
+    on_classic: EventHandler<()>,
+) -> Element {
+    let auth = use_auth();
+    let health = use_system_health();
+    let downloads = try_use_context::<ActiveDownloads>();
+    let mut query = use_signal(String::new);
+
+    let summary = use_resource(move || async move { auth.call(get_home_summary).await.ok() });
+    let hits = use_resource(move || async move { auth.call(get_watchlist_hits).await.ok() });
+    let disks = use_resource(move || async move {
+        let folders = auth.call(get_user_folders).await.ok()?;
+        let diagnostics = auth.call(folder_diagnostics).await.ok()?;
+        Some((folders, diagnostics))
+    });
+
+    let submit = move || {
+        let q = query().trim().to_string();
+        if !q.is_empty() {
+            on_search.call((String::new(), q));
+        }
+    };
+
+    let (active, failed) = match downloads {
+        Some(d) => {
+            let map = d.0.read();
+            let mut active: Vec<_> = map
+                .values()
+                .filter(|f| is_active(&f.state))
+                .cloned()
+                .collect();
+            active.sort_by(|a, b| b.percent.total_cmp(&a.percent));
+            let failed = map
+                .values()
+                .filter(|f| matches!(f.state, DownloadState::Failed(_)))
+                .count();
+            (active, failed)
+        }
+        None => (Vec::new(), 0),
+    };
+
+    rsx! {
+        div { class: "space-y-6 w-full max-w-5xl mx-auto z-10",
+            form {
+                class: "flex gap-2",
+                onsubmit: move |e| {
+                    e.prevent_default();
+                    submit();
+                },
+                input {
+                    class: "flex-grow p-3 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                    placeholder: "Search for an album...",
+                    value: "{query}",
+                    oninput: move |e| query.set(e.value()),
+                }
+                button { class: "retro-btn rounded", r#type: "submit", "Search" }
+            }
+            div { class: "flex justify-between items-center",
+                SystemStatus { health: health.get(), navidrome_status: auth.navidrome_status() }
+                button {
+                    class: "text-xs font-mono text-gray-500 hover:text-gray-300 cursor-pointer",
+                    onclick: move |_| on_classic.call(()),
+                    "Search only"
+                }
+            }
+
+            if let Some(Some(summary)) = summary() {
+                if !summary.recent_imports.is_empty() {
+                    RecentlyAdded { imports: summary.recent_imports, on_search }
+                }
+            }
+
+            div { class: "grid grid-cols-1 md:grid-cols-2 gap-6",
+                div { class: CARD_CLASS,
+                    h3 { class: CARD_TITLE_CLASS,
+                        span { "Active downloads" }
+                        span { "{active.len()}" }
+                    }
+                    if active.is_empty() {
+                        p { class: "text-sm font-mono text-gray-500 italic", "Nothing downloading." }
+                    }
+                    ul { class: "space-y-2",
+                        for file in active.iter().take(CARD_ROWS) {
+                            li { key: "{file.id}", class: "text-sm font-mono",
+                                div { class: "flex justify-between gap-2",
+                                    span { class: "truncate text-gray-300", title: "{file.batch_label.clone().unwrap_or_default()}",
+                                        "{remote_file_name(&file.item)}"
+                                    }
+                                    span { class: "shrink-0 text-gray-500", "{file.percent:.0}%" }
+                                }
+                                div { class: "h-1 bg-white/5 rounded mt-1",
+                                    div {
+                                        class: "h-1 bg-beet-leaf rounded",
+                                        style: "width: {file.percent:.0}%",
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    if failed > 0 {
+                        p { class: "text-xs font-mono text-red-400 mt-3", "{failed} failed" }
+                    }
+                }
+
+                div { class: CARD_CLASS,
+                    h3 { class: CARD_TITLE_CLASS, span { "Recent imports" } }
+                    match summary() {
+                        None => rsx! { p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." } },
+                        Some(None) => rsx! { p { class: "text-sm font-mono text-gray-500", "Could not load the history." } },
+                        Some(Some(summary)) => rsx! { RecentImports { summary } },
+                    }
+                }
+
+                div { class: CARD_CLASS,
+                    h3 { class: CARD_TITLE_CLASS, span { "New from watched artists" } }
+                    match hits() {
+                        None => rsx! { p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." } },
+                        Some(None) => rsx! { p { class: "text-sm font-mono text-gray-500", "Could not load releases." } },
+                        Some(Some(hits)) => rsx! { WatchlistHits { hits, on_search } },
+                    }
+                }
+
+                div { class: CARD_CLASS,
+                    h3 { class: CARD_TITLE_CLASS, span { "Disk space" } }
+                    match disks() {
+                        None => rsx! { p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." } },
+                        Some(None) => rsx! { p { class: "text-sm font-mono text-gray-500", "Could not check the folders." } },
+                        Some(Some((folders, diagnostics))) => rsx! { DiskSpace { folders, diagnostics } },
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn RecentImports(summary: HomeSummary) -> Element {
+    rsx! {
+        p { class: "text-xs font-mono text-gray-500 mb-3",
+            "Last 24 hours: {summary.imported} imported"
+            if summary.failed > 0 {
+                span { class: "text-red-400", ", {summary.failed} failed" }
+            }
+        }
+        if summary.recent_imports.is_empty() {
+            p { class: "text-sm font-mono text-gray-500 italic", "Nothing imported yet." }
+        }
+        ul { class: "space-y-2",
+            for import in summary.recent_imports.iter().take(CARD_ROWS) {
+                li {
+                    class: "text-sm font-mono flex justify-between gap-2",
+                    title: import.imported_path.clone().unwrap_or_default(),
+                    span { class: "truncate text-gray-300",
+                        "{import.artist.as_deref().unwrap_or(\"Unknown\")} - {import.album.as_deref().unwrap_or(\"Unknown\")}"
+                    }
+                    span { class: "shrink-0 text-gray-500", "{import.tracks} tr." }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn WatchlistHits(hits: Vec<CalendarRelease>, on_search: EventHandler<(String, String)>) -> Element {
+    rsx! {
+        if hits.is_empty() {
+            p { class: "text-sm font-mono text-gray-500 italic", "No new releases this month." }
+        }
+        ul { class: "space-y-2",
+            for release in hits.into_iter().take(CARD_ROWS) {
+                li {
+                    key: "{release.release_group_id}",
+                    class: "text-sm font-mono flex justify-between items-center gap-2",
+                    div { class: "min-w-0",
+                        div { class: "truncate text-gray-300", "{release.artist} - {release.title}" }
+                        div { class: "text-xs text-gray-500", "{release.date}" }
+                    }
+                    button {
+                        class: "shrink-0 text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                        onclick: move |_| on_search.call((release.artist.clone(), release.title.clone())),
+                        "[ Search ]"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn DiskSpace(folders: Vec<Folder>, diagnostics: Vec<FolderDiagnostics>) -> Element {
+    rsx! {
+        if diagnostics.is_empty() {
+            p { class: "text-sm font-mono text-gray-500 italic", "No library folders yet." }
+        }
+        ul { class: "space-y-3",
+            for diag in diagnostics {
+                {
+                    let name = folders
+                        .iter()
+                        .find(|f| f.id == diag.folder_id)
+                        .map(|f| f.name.clone())
+                        .unwrap_or_default();
+                    let used = match (diag.free_bytes, diag.total_bytes) {
+                        (Some(free), Some(total)) if total > 0 => 100 - free * 100 / total,
+                        _ => 0,
+                    };
+                    let bar = if used >= 90 { "bg-red-500" } else { "bg-beet-accent" };
+                    let label = if diag.has_problem() {
+                        diag.error.clone().unwrap_or_else(|| "Unavailable".to_string())
+                    } else {
+                        diag.free_space_label().unwrap_or_default()
+                    };
+                    rsx! {
+                        li { key: "{diag.folder_id}", class: "text-sm font-mono",
+                            div { class: "flex justify-between gap-2",
+                                span { class: "truncate text-gray-300", "{name}" }
+                                span {
+                                    class: "shrink-0",
+                                    class: if diag.has_problem() { "text-red-400" } else { "text-gray-500" },
+                                    "{label}"
+                                }
+                            }
+                            div { class: "h-1 bg-white/5 rounded mt-1",
+                                div { class: "h-1 rounded {bar}", style: "width: {used}%" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Covers of the latest imported albums. Clicking one opens the album in
+/// the search, where its tracks are marked as in the library.
+#[component]
+fn RecentlyAdded(imports: Vec<RecentImport>, on_search: EventHandler<(String, String)>) -> Element {
+    rsx! {
+        div { class: CARD_CLASS,
+            h3 { class: CARD_TITLE_CLASS, span { "Recently added" } }
+            div { class: "grid grid-cols-3 sm:grid-cols-4 lg:grid-cols-6 gap-4",
+                for import in imports {
+                    {
+                        let artist = import.artist.clone().unwrap_or_default();
+                        let album = import.album.clone().unwrap_or_default();
+                        let query = (artist.clone(), album.clone());
+                        rsx! {
+                            button {
+                                key: "{artist}-{album}",
+                                class: "text-left group cursor-pointer min-w-0",
+                                title: "{artist} - {album}",
+                                onclick: move |_| on_search.call(query.clone()),
+                                CoverTile { url: import.cover_url.clone() }
+                                div { class: "mt-2 text-xs font-mono text-gray-300 truncate group-hover:text-white",
+                                    "{album}"
+                                }
+                                div { class: "text-xs font-mono text-gray-500 truncate", "{artist}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn CoverTile(url: Option<String>) -> Element {
+    let mut has_error = use_signal(|| false);
+
+    rsx! {
+        div { class: "aspect-square bg-beet-dark border border-white/5 rounded-md flex items-center justify-center overflow-hidden",
+            if let Some(url) = url.filter(|_| !has_error()) {
+                img {
+                    src: "{url}",
+                    alt: "",
+                    loading: "lazy",
+                    class: "w-full h-full object-cover group-hover:opacity-80 transition-opacity",
+                    onerror: move |_| has_error.set(true),
+                }
+            } else {
+                svg {
+                    class: "w-8 h-8 text-white/20",
+                    xmlns: "http://www.w3.org/2000/svg",
+                    fill: "none",
+                    "viewBox": "0 0 24 24",
+                    "stroke-width": "1.5",
+                    stroke: "currentColor",
+                    path {
+                        "stroke-linecap": "round",
+                        "stroke-linejoin": "round",
+                        d: "M9 9l10.5-3m0 6.553v3.75a2.25 2.25 0 01-1.632 2.163l-1.32.377a1.803 1.803 0 11-.99-3.467l2.31-.66a2.25 2.25 0 001.632-2.163zm0 0V2.25L9 5.25v10.303m0 0v3.75a2.25 2.25 0 01-1.632 2.163l-1.32.377a1.803 1.803 0 01-.99-3.467l2.31-.66A2.25 2.25 0 009 15.553z",
+                    }
+                }
+            }
+        }
+    }
+}
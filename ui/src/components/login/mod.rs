@@ -3,11 +3,29 @@ use std::pin::Pin;
 
 use dioxus::prelude::*;
 
-type LoginCallback = Callback<(String, String), Pin<Box<dyn Future<Output = Result<(), String>>>>>;
+mod register;
+mod reset;
+pub use register::Register;
+pub use reset::{ForgotPassword, ResetPassword};
+
+pub type LoginCallback =
+    Callback<(String, String), Pin<Box<dyn Future<Output = Result<(), String>>>>>;
+
+/// Text inputs of the login, signup and password forms
+const INPUT_CLASS: &str = "w-full bg-beet-dark border border-white/10 rounded p-3 text-white focus:outline-none focus:border-beet-accent focus:shadow-[0_0_10px_rgba(217,70,239,0.3)] transition-all font-mono";
+
+/// Form submission with a single value, such as a username or a password.
+pub type SubmitCallback = Callback<String, Pin<Box<dyn Future<Output = Result<(), String>>>>>;
 
 #[derive(Props, PartialEq, Clone)]
 pub struct Props {
     login: LoginCallback,
+    /// Shows a "forgot password" link when set
+    #[props(default)]
+    on_forgot: Option<EventHandler<()>>,
+    /// Why the user has to sign in, such as an expired session
+    #[props(default)]
+    notice: Option<String>,
 }
 
 #[component]
@@ -30,6 +48,82 @@ pub fn Login(props: Props) -> Element {
         });
     };
 
+    rsx! {
+      AuthCard {
+        if let Some(notice) = props.notice.clone() {
+          div { class: "mb-6 p-3 bg-yellow-500/10 border border-yellow-500/50 rounded text-yellow-400 text-sm font-mono",
+            "{notice}"
+          }
+        }
+        // Form
+        form {
+          class: "space-y-6",
+          onsubmit: move |e| {
+              e.prevent_default();
+              handle_login();
+          },
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Username"
+            }
+            input {
+              class: "w-full bg-beet-dark border border-white/10 rounded p-3 text-white focus:outline-none focus:border-beet-accent focus:shadow-[0_0_10px_rgba(217,70,239,0.3)] transition-all font-mono",
+              value: "{username}",
+              oninput: move |e| username.set(e.value()),
+              "type": "text",
+              placeholder: "Enter username",
+            }
+          }
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Password"
+            }
+            input {
+              class: "w-full bg-beet-dark border border-white/10 rounded p-3 text-white focus:outline-none focus:border-beet-accent focus:shadow-[0_0_10px_rgba(217,70,239,0.3)] transition-all font-mono",
+              value: "{password}",
+              oninput: move |e| password.set(e.value()),
+              "type": "password",
+              placeholder: "Enter password",
+            }
+          }
+
+          if !error().is_empty() {
+            AuthError { message: error() }
+          }
+
+          button {
+            class: "w-full retro-btn flex justify-center items-center gap-2 group",
+            "type": "submit",
+            span { "AUTHENTICATE" }
+            svg {
+              class: "w-4 h-4 group-hover:translate-x-1 transition-transform",
+              fill: "none",
+              view_box: "0 0 24 24",
+              stroke: "currentColor",
+              path {
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+                stroke_width: "2",
+                d: "M14 5l7 7m0 0l-7 7m7-7H3",
+              }
+            }
+          }
+        }
+        if let Some(on_forgot) = props.on_forgot {
+          button {
+            class: "w-full mt-4 text-xs font-mono text-gray-500 hover:text-beet-accent transition-colors cursor-pointer",
+            onclick: move |_| on_forgot.call(()),
+            "Forgot your password?"
+          }
+        }
+      }
+    }
+}
+
+/// Centered card with the Soulbeet logo, framing the login, signup and
+/// password forms.
+#[component]
+fn AuthCard(children: Element) -> Element {
     rsx! {
       div { class: "flex flex-col items-center justify-center min-h-screen text-white font-display",
         // bg decorations
@@ -61,75 +155,29 @@ pub fn Login(props: Props) -> Element {
             }
           }
 
-          // Form
-          form {
-            class: "space-y-6",
-            onsubmit: move |e| {
-                e.prevent_default();
-                handle_login();
-            },
-            div {
-              label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
-                "Username"
-              }
-              input {
-                class: "w-full bg-beet-dark border border-white/10 rounded p-3 text-white focus:outline-none focus:border-beet-accent focus:shadow-[0_0_10px_rgba(217,70,239,0.3)] transition-all font-mono",
-                value: "{username}",
-                oninput: move |e| username.set(e.value()),
-                "type": "text",
-                placeholder: "Enter username",
-              }
-            }
-            div {
-              label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
-                "Password"
-              }
-              input {
-                class: "w-full bg-beet-dark border border-white/10 rounded p-3 text-white focus:outline-none focus:border-beet-accent focus:shadow-[0_0_10px_rgba(217,70,239,0.3)] transition-all font-mono",
-                value: "{password}",
-                oninput: move |e| password.set(e.value()),
-                "type": "password",
-                placeholder: "Enter password",
-              }
-            }
-
-            if !error().is_empty() {
-              div { class: "p-3 bg-red-500/10 border border-red-500/50 rounded text-red-400 text-sm font-mono flex items-center gap-2",
-                svg {
-                  class: "w-4 h-4",
-                  fill: "none",
-                  view_box: "0 0 24 24",
-                  stroke: "currentColor",
-                  path {
-                    stroke_linecap: "round",
-                    stroke_linejoin: "round",
-                    stroke_width: "2",
-                    d: "M12 8v4m0 4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z",
-                  }
-                }
-                "{error}"
-              }
-            }
+          {children}
+        }
+      }
+    }
+}
 
-            button {
-              class: "w-full retro-btn flex justify-center items-center gap-2 group",
-              "type": "submit",
-              span { "AUTHENTICATE" }
-              svg {
-                class: "w-4 h-4 group-hover:translate-x-1 transition-transform",
-                fill: "none",
-                view_box: "0 0 24 24",
-                stroke: "currentColor",
-                path {
-                  stroke_linecap: "round",
-                  stroke_linejoin: "round",
-                  stroke_width: "2",
-                  d: "M14 5l7 7m0 0l-7 7m7-7H3",
-                }
-              }
-            }
+#[component]
+fn AuthError(message: String) -> Element {
+    rsx! {
+      div { class: "p-3 bg-red-500/10 border border-red-500/50 rounded text-red-400 text-sm font-mono flex items-center gap-2",
+        svg {
+          class: "w-4 h-4",
+          fill: "none",
+          view_box: "0 0 24 24",
+          stroke: "currentColor",
+          path {
+            stroke_linecap: "round",
+            stroke_linejoin: "round",
+            stroke_width: "2",
+            d: "M12 8v4m0 4h.01M21 12a9 9 0 11-18 0 9 9 0 0118 0z",
           }
         }
+        "{message}"
       }
     }
 }
@@ -0,0 +1,107 @@
+use dioxus::prelude::*;
+
+use super::{AuthCard, AuthError, LoginCallback, INPUT_CLASS};
+
+/// Signup form behind an invite link. `valid` is `None` while the invite is
+/// being checked; `register` receives the chosen username and password.
+#[component]
+pub fn Register(valid: Option<bool>, register: LoginCallback) -> Element {
+    let mut username = use_signal(|| "".to_string());
+    let mut password = use_signal(|| "".to_string());
+    let mut confirm = use_signal(|| "".to_string());
+    let mut error = use_signal(|| "".to_string());
+    let mut submitting = use_signal(|| false);
+
+    let handle_register = move || {
+        error.set("".to_string());
+        if username().trim().is_empty() || password().is_empty() {
+            error.set("Choose a username and a password".to_string());
+            return;
+        }
+        if password() != confirm() {
+            error.set("Passwords don't match".to_string());
+            return;
+        }
+        let user = username.read().trim().to_string();
+        let pass = password.read().to_string();
+        spawn(async move {
+            submitting.set(true);
+            if let Err(e) = register.call((user, pass)).await {
+                error.set(e);
+            }
+            submitting.set(false);
+        });
+    };
+
+    rsx! {
+      AuthCard {
+        match valid {
+          None => rsx! {
+            p { class: "text-center text-sm font-mono text-gray-400 animate-pulse", "Checking invite..." }
+          },
+          Some(false) => rsx! {
+            AuthError { message: "This invite link is invalid, has been used or has expired. Ask for a new one." }
+          },
+          Some(true) => rsx! {
+            form {
+              class: "space-y-6",
+              onsubmit: move |e| {
+                  e.prevent_default();
+                  handle_register();
+              },
+              p { class: "text-sm font-mono text-gray-400",
+                "You've been invited. Choose the username and password you'll sign in with."
+              }
+              div {
+                label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                  "Username"
+                }
+                input {
+                  class: INPUT_CLASS,
+                  value: "{username}",
+                  oninput: move |e| username.set(e.value()),
+                  "type": "text",
+                  placeholder: "Choose a username",
+                }
+              }
+              div {
+                label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                  "Password"
+                }
+                input {
+                  class: INPUT_CLASS,
+                  value: "{password}",
+                  oninput: move |e| password.set(e.value()),
+                  "type": "password",
+                  placeholder: "Choose a password",
+                }
+              }
+              div {
+                label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                  "Confirm Password"
+                }
+                input {
+                  class: INPUT_CLASS,
+                  value: "{confirm}",
+                  oninput: move |e| confirm.set(e.value()),
+                  "type": "password",
+                  placeholder: "Repeat the password",
+                }
+              }
+
+              if !error().is_empty() {
+                AuthError { message: error() }
+              }
+
+              button {
+                class: "w-full retro-btn flex justify-center items-center gap-2",
+                "type": "submit",
+                disabled: submitting(),
+                if submitting() { "CREATING ACCOUNT..." } else { "CREATE ACCOUNT" }
+              }
+            }
+          },
+        }
+      }
+    }
+}
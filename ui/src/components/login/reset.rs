@@ -0,0 +1,141 @@
+use dioxus::prelude::*;
+
+use super::{AuthCard, AuthError, SubmitCallback, INPUT_CLASS};
+
+const LABEL_CLASS: &str = "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider";
+
+/// Asks for a reset link by email. `request` receives the username.
+#[component]
+pub fn ForgotPassword(request: SubmitCallback, on_back: EventHandler<()>) -> Element {
+    let mut username = use_signal(|| "".to_string());
+    let mut error = use_signal(|| "".to_string());
+    let mut sent = use_signal(|| false);
+
+    let handle_request = move || {
+        error.set("".to_string());
+        let user = username.read().trim().to_string();
+        if user.is_empty() {
+            error.set("Enter your username".to_string());
+            return;
+        }
+        spawn(async move {
+            match request.call(user).await {
+                Ok(_) => sent.set(true),
+                Err(e) => error.set(e),
+            }
+        });
+    };
+
+    rsx! {
+      AuthCard {
+        if sent() {
+          p { class: "text-sm font-mono text-gray-300",
+            "If that account has an email address, a reset link is on its way. It works for 24 hours."
+          }
+        } else {
+          form {
+            class: "space-y-6",
+            onsubmit: move |e| {
+                e.prevent_default();
+                handle_request();
+            },
+            p { class: "text-sm font-mono text-gray-400",
+              "Enter your username and we'll email you a link to choose a new password."
+            }
+            div {
+              label { class: LABEL_CLASS, "Username" }
+              input {
+                class: INPUT_CLASS,
+                value: "{username}",
+                oninput: move |e| username.set(e.value()),
+                "type": "text",
+                placeholder: "Enter username",
+              }
+            }
+            if !error().is_empty() {
+              AuthError { message: error() }
+            }
+            button { class: "w-full retro-btn", "type": "submit", "SEND RESET LINK" }
+          }
+        }
+        button {
+          class: "w-full mt-4 text-xs font-mono text-gray-500 hover:text-beet-accent transition-colors cursor-pointer",
+          onclick: move |_| on_back.call(()),
+          "Back to sign in"
+        }
+      }
+    }
+}
+
+/// Form behind a reset link. `valid` is `None` while the link is being
+/// checked; `reset` receives the new password.
+#[component]
+pub fn ResetPassword(valid: Option<bool>, reset: SubmitCallback) -> Element {
+    let mut password = use_signal(|| "".to_string());
+    let mut confirm = use_signal(|| "".to_string());
+    let mut error = use_signal(|| "".to_string());
+
+    let handle_reset = move || {
+        error.set("".to_string());
+        if password().is_empty() {
+            error.set("Choose a new password".to_string());
+            return;
+        }
+        if password() != confirm() {
+            error.set("Passwords don't match".to_string());
+            return;
+        }
+        let pass = password.read().to_string();
+        spawn(async move {
+            if let Err(e) = reset.call(pass).await {
+                error.set(e);
+            }
+        });
+    };
+
+    rsx! {
+      AuthCard {
+        match valid {
+          None => rsx! {
+            p { class: "text-center text-sm font-mono text-gray-400 animate-pulse", "Checking link..." }
+          },
+          Some(false) => rsx! {
+            AuthError { message: "This reset link is invalid, has been used or has expired. Ask for a new one." }
+          },
+          Some(true) => rsx! {
+            form {
+              class: "space-y-6",
+              onsubmit: move |e| {
+                  e.prevent_default();
+                  handle_reset();
+              },
+              div {
+                label { class: LABEL_CLASS, "New Password" }
+                input {
+                  class: INPUT_CLASS,
+                  value: "{password}",
+                  oninput: move |e| password.set(e.value()),
+                  "type": "password",
+                  placeholder: "Choose a password",
+                }
+              }
+              div {
+                label { class: LABEL_CLASS, "Confirm Password" }
+                input {
+                  class: INPUT_CLASS,
+                  value: "{confirm}",
+                  oninput: move |e| confirm.set(e.value()),
+                  "type": "password",
+                  placeholder: "Repeat the password",
+                }
+              }
+              if !error().is_empty() {
+                AuthError { message: error() }
+              }
+              button { class: "w-full retro-btn", "type": "submit", "SET PASSWORD" }
+            }
+          },
+        }
+      }
+    }
+}
@@ -1,4 +1,5 @@
 pub mod album;
+pub mod calendar;
 pub mod confirm_modal;
 pub mod cover_art;
 pub mod dashboard;
@@ -6,22 +7,33 @@ pub mod discovery;
 pub mod downloads;
 pub mod error_display;
 pub mod footer;
+pub mod home;
 pub mod login;
 pub mod modal;
+pub mod party;
 pub mod search;
 pub mod settings;
 pub mod simple;
 pub mod status;
+pub mod upload;
+pub mod wishlist;
 
 pub use album::{Album, AlbumHeader};
+pub use calendar::{ReleaseCalendar, WatchedArtists};
 pub use confirm_modal::*;
 pub use cover_art::*;
 pub use downloads::*;
 pub use error_display::*;
 pub use footer::Footer;
-pub use login::Login;
+pub use home::HomeDashboard;
+pub use login::{ForgotPassword, Login, Register, ResetPassword};
 pub use modal::*;
+pub use party::{PartyGuest, PartyHost};
 pub use search::*;
 pub use settings::*;
 pub use simple::*;
 pub use status::*;
+pub use upload::{ImportReviewSignal, ImportReviews, LocalImport};
+pub use wishlist::{
+    ChartImport, CompleteAlbums, LabelCatalog, MissingMusicList, Wishlist, WishlistTransfer,
+};
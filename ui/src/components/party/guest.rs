@@ -0,0 +1,142 @@
+use api::{get_guest_party, request_party_track, PartyGuestView};
+use dioxus::prelude::*;
+
+use super::{status_badge, REFRESH_MS};
+use crate::friendly_error;
+
+const INPUT_CLASS: &str = "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm";
+
+/// The page a party link opens: the queue so far and a form to ask the
+/// host for a track. Guests need no account.
+#[component]
+pub fn PartyGuest(token: String) -> Element {
+    let token = use_signal(|| token);
+    let mut view = use_signal(|| None::<Result<PartyGuestView, String>>);
+    let mut guest_name = use_signal(String::new);
+    let mut artist = use_signal(String::new);
+    let mut title = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut notice = use_signal(String::new);
+
+    let refresh = move || async move {
+        view.set(Some(
+            get_guest_party(token())
+                .await
+                .map_err(|e| friendly_error(&e)),
+        ));
+    };
+
+    use_future(move || async move {
+        loop {
+            refresh().await;
+            gloo_timers::future::TimeoutFuture::new(REFRESH_MS).await;
+        }
+    });
+
+    let handle_request = move || async move {
+        error.set(String::new());
+        notice.set(String::new());
+        busy.set(true);
+        match request_party_track(token(), guest_name(), artist(), title()).await {
+            Ok(request) => {
+                notice.set(format!("Asked for {} - {}", request.artist, request.title));
+                artist.set(String::new());
+                title.set(String::new());
+                refresh().await;
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let body = match view() {
+        None => rsx! {
+            p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." }
+        },
+        Some(Err(e)) => rsx! {
+            p { class: "text-sm font-mono text-red-400", "{e}" }
+        },
+        Some(Ok(party)) => rsx! {
+            h1 { class: "text-2xl font-bold text-beet-accent font-display", "{party.name}" }
+            p { class: "text-xs font-mono text-gray-400 mb-6",
+                "Hosted by {party.host}. Ask for a track and the host picks what plays."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-3 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "mb-4 p-3 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{notice}"
+                }
+            }
+
+            form {
+                class: "space-y-3",
+                onsubmit: move |e| {
+                    e.prevent_default();
+                    spawn(handle_request());
+                },
+                input {
+                    class: INPUT_CLASS,
+                    value: "{guest_name}",
+                    placeholder: "Your name",
+                    oninput: move |e| guest_name.set(e.value()),
+                }
+                input {
+                    class: INPUT_CLASS,
+                    value: "{artist}",
+                    placeholder: "Artist",
+                    oninput: move |e| artist.set(e.value()),
+                }
+                input {
+                    class: INPUT_CLASS,
+                    value: "{title}",
+                    placeholder: "Track",
+                    oninput: move |e| title.set(e.value()),
+                }
+                button {
+                    class: "w-full retro-btn rounded",
+                    "type": "submit",
+                    disabled: busy(),
+                    "Request"
+                }
+            }
+
+            if !party.requests.is_empty() {
+                h2 { class: "text-xs font-mono text-gray-400 uppercase tracking-wider mt-8 mb-2", "Queue" }
+                ul { class: "space-y-1",
+                    for request in party.requests.iter().rev() {
+                        {
+                            let (label, class) = status_badge(request);
+                            rsx! {
+                                li {
+                                    key: "{request.id}",
+                                    class: "flex items-center gap-3 bg-white/5 border border-white/5 p-2 rounded text-sm",
+                                    div { class: "flex-1 min-w-0 truncate text-white",
+                                        "{request.artist} – {request.title}"
+                                    }
+                                    span { class: "text-xs font-mono text-gray-500 shrink-0", "{request.guest_name}" }
+                                    span { class: "text-xs font-mono shrink-0 {class}", "{label}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "flex flex-col items-center min-h-screen text-white py-12 px-4",
+            div { class: "fixed top-1/4 -left-10 w-64 h-64 bg-beet-accent/10 rounded-full blur-[150px] pointer-events-none" }
+            div { class: "fixed bottom-1/4 -right-10 w-64 h-64 bg-beet-leaf/10 rounded-full blur-[150px] pointer-events-none" }
+            div { class: "p-6 bg-beet-panel border border-white/10 rounded-lg shadow-2xl w-full max-w-md relative z-10",
+                {body}
+            }
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::collections::HashMap;
+
+use api::models::folder::Folder;
+use api::models::party::{Party, PartyRequest, PartyRequestStatus};
+use api::{
+    decide_party_request, end_party, get_app_url, get_parties, get_party_requests,
+    get_user_folders, renew_party_link, start_party,
+};
+use dioxus::prelude::*;
+
+use super::{status_badge, REFRESH_MS};
+use crate::{friendly_error, use_auth};
+
+const INPUT_CLASS: &str = "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm";
+
+/// One open party: its link, its queue and the host's decisions.
+#[component]
+fn PartyPanel(
+    party: Party,
+    token: Option<String>,
+    on_link: EventHandler<(String, String)>,
+    on_ended: EventHandler<()>,
+) -> Element {
+    let auth = use_auth();
+    let party_id = use_signal(|| party.id.clone());
+    let mut requests = use_signal(Vec::<PartyRequest>::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let app_url = use_resource(move || async move { auth.call(get_app_url).await.ok() });
+
+    use_future(move || async move {
+        loop {
+            match auth.call(|| get_party_requests(party_id())).await {
+                Ok(list) => requests.set(list),
+                Err(e) => error.set(friendly_error(&e)),
+            }
+            gloo_timers::future::TimeoutFuture::new(REFRESH_MS).await;
+        }
+    });
+
+    let decide = move |request_id: String, approve: bool| async move {
+        error.set(String::new());
+        busy.set(true);
+        match auth
+            .call(|| decide_party_request(party_id(), request_id.clone(), approve))
+            .await
+        {
+            Ok(decided) => {
+                if let Some(request) = requests.write().iter_mut().find(|r| r.id == decided.id) {
+                    *request = decided;
+                }
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let handle_renew = move |_| async move {
+        error.set(String::new());
+        match auth.call(|| renew_party_link(party_id())).await {
+            Ok(link) => on_link.call((link.party.id, link.token)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let handle_end = move |_| async move {
+        error.set(String::new());
+        match auth.call(|| end_party(party_id())).await {
+            Ok(()) => on_ended.call(()),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let ends = chrono::DateTime::from_timestamp(party.expires_at, 0)
+        .map(|d| {
+            d.with_timezone(&chrono::Local)
+                .format("%a %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let pending = requests
+        .read()
+        .iter()
+        .filter(|r| r.status() == PartyRequestStatus::Pending)
+        .count();
+
+    rsx! {
+        div { class: "bg-white/5 border border-white/10 p-4 rounded space-y-3",
+            div { class: "flex items-center gap-2",
+                div { class: "flex-1 min-w-0",
+                    p { class: "text-white font-semibold truncate", "{party.name}" }
+                    p { class: "text-xs font-mono text-gray-500", "Ends {ends} · {pending} waiting" }
+                }
+                button {
+                    class: "px-2 py-1 text-xs font-mono text-red-400 hover:text-red-300 cursor-pointer shrink-0",
+                    onclick: handle_end,
+                    "End party"
+                }
+            }
+
+            if let Some(token) = token {
+                input {
+                    class: "w-full {INPUT_CLASS} text-gray-300",
+                    readonly: true,
+                    value: app_url()
+                        .flatten()
+                        .map(|url| format!("{url}/party/{token}"))
+                        .unwrap_or_default(),
+                    onclick: move |e| e.stop_propagation(),
+                }
+            } else {
+                div { class: "flex items-center gap-2",
+                    p { class: "flex-1 text-xs font-mono text-gray-500",
+                        "The link is only shown once. Make a new one to share it again; the old one stops working."
+                    }
+                    button {
+                        class: "px-2 py-1 text-xs font-mono text-gray-400 hover:text-white cursor-pointer shrink-0",
+                        onclick: handle_renew,
+                        "New link"
+                    }
+                }
+            }
+
+            if !error().is_empty() {
+                p { class: "text-xs font-mono text-red-400", "{error}" }
+            }
+
+            if requests.read().is_empty() {
+                p { class: "text-xs font-mono text-gray-500", "No requests yet." }
+            }
+            div { class: "space-y-1 max-h-80 overflow-y-auto",
+                for request in requests.read().iter().rev().cloned() {
+                    {
+                        let (label, class) = status_badge(&request);
+                        let approve_id = request.id.clone();
+                        let reject_id = request.id.clone();
+                        rsx! {
+                            div {
+                                key: "{request.id}",
+                                class: "flex items-center gap-2 p-2 bg-beet-panel border border-white/10 rounded text-sm",
+                                div { class: "flex-1 min-w-0",
+                                    p { class: "text-white truncate", "{request.artist} – {request.title}" }
+                                    p { class: "text-xs font-mono text-gray-500 truncate", "from {request.guest_name}" }
+                                    if let Some(err) = &request.error {
+                                        p { class: "text-xs font-mono text-red-400 truncate", title: "{err}", "{err}" }
+                                    }
+                                }
+                                if request.status() == PartyRequestStatus::Pending {
+                                    button {
+                                        class: "px-2 py-1 text-xs font-mono text-beet-leaf hover:text-white cursor-pointer shrink-0",
+                                        disabled: busy(),
+                                        onclick: move |_| decide(approve_id.clone(), true),
+                                        "Approve"
+                                    }
+                                    button {
+                                        class: "px-2 py-1 text-xs font-mono text-gray-500 hover:text-white cursor-pointer shrink-0",
+                                        disabled: busy(),
+                                        onclick: move |_| decide(reject_id.clone(), false),
+                                        "Reject"
+                                    }
+                                } else {
+                                    span { class: "text-xs font-mono shrink-0 {class}", "{label}" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Host a party: share a link, let guests ask for tracks and approve the
+/// ones to download into a folder.
+#[component]
+pub fn PartyHost() -> Element {
+    let auth = use_auth();
+    let mut name = use_signal(String::new);
+    let mut hours = use_signal(|| "4".to_string());
+    let mut folders = use_signal(Vec::<Folder>::new);
+    let mut folder_id = use_signal(String::new);
+    // Link tokens known in this session, by party id
+    let mut tokens = use_signal(HashMap::<String, String>::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+
+    use_future(move || async move {
+        if let Ok(list) = auth.call(get_user_folders).await {
+            if let Some(first) = list.first() {
+                folder_id.set(first.id.clone());
+            }
+            folders.set(list);
+        }
+    });
+
+    let mut parties = use_resource(move || async move { auth.call(get_parties).await });
+
+    let handle_start = move |_| async move {
+        error.set(String::new());
+        let Ok(hours) = hours().trim().parse::<u32>() else {
+            error.set("Enter how many hours the party lasts".to_string());
+            return;
+        };
+        busy.set(true);
+        match auth.call(|| start_party(name(), folder_id(), hours)).await {
+            Ok(link) => {
+                tokens.write().insert(link.party.id, link.token);
+                name.set(String::new());
+                parties.restart();
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let list = match &*parties.read() {
+        None => rsx! {},
+        Some(Err(e)) => rsx! {
+            p { class: "text-xs font-mono text-red-400", {friendly_error(e)} }
+        },
+        Some(Ok(list)) => rsx! {
+            div { class: "space-y-4 mt-4",
+                for party in list.iter().cloned() {
+                    PartyPanel {
+                        key: "{party.id}",
+                        token: tokens.read().get(&party.id).cloned(),
+                        party,
+                        on_link: move |(id, token)| {
+                            tokens.write().insert(id, token);
+                        },
+                        on_ended: move |_| parties.restart(),
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Party" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Share a link for the evening. Guests ask for tracks without an account; the ones you approve are downloaded into the folder."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            div { class: "flex flex-wrap gap-2",
+                input {
+                    class: "flex-1 min-w-40 {INPUT_CLASS}",
+                    value: "{name}",
+                    placeholder: "Party name",
+                    oninput: move |e| name.set(e.value()),
+                }
+                select {
+                    class: INPUT_CLASS,
+                    value: "{folder_id}",
+                    onchange: move |e| folder_id.set(e.value()),
+                    for folder in folders.read().iter() {
+                        option { value: "{folder.id}", "{folder.name}" }
+                    }
+                }
+                input {
+                    class: "w-20 {INPUT_CLASS}",
+                    "type": "number",
+                    min: "1",
+                    value: "{hours}",
+                    title: "How many hours the link works",
+                    oninput: move |e| hours.set(e.value()),
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: busy() || name().trim().is_empty() || folder_id().is_empty(),
+                    onclick: handle_start,
+                    "Start"
+                }
+            }
+
+            {list}
+        }
+    }
+}
@@ -0,0 +1,19 @@
+use api::models::party::{PartyRequest, PartyRequestStatus};
+
+mod guest;
+mod host;
+pub use guest::PartyGuest;
+pub use host::PartyHost;
+
+/// How often the party queue is refreshed, in milliseconds.
+const REFRESH_MS: u32 = 5_000;
+
+/// Label and text colour of a request's status.
+fn status_badge(request: &PartyRequest) -> (&'static str, &'static str) {
+    match request.status() {
+        PartyRequestStatus::Pending => ("waiting", "text-gray-400"),
+        PartyRequestStatus::Approved => ("approved", "text-beet-leaf"),
+        PartyRequestStatus::Rejected => ("declined", "text-gray-500"),
+        PartyRequestStatus::Failed => ("failed", "text-red-400"),
+    }
+}
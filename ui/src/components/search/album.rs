@@ -3,7 +3,7 @@ use dioxus::prelude::*;
 use shared::metadata::{Album, Track};
 use std::collections::HashMap;
 
-use super::download_icon::{DownloadIcon, DownloadRowState};
+use super::download_icon::{DownloadIcon, DownloadRowState, READ_ONLY_HINT};
 use super::inline_track_panel::InlineTrackPanel;
 use crate::CoverArt;
 
@@ -32,6 +32,8 @@ pub struct Props {
 #[component]
 pub fn AlbumResult(props: Props) -> Element {
     let album = &props.album;
+    let details = album.details.summary();
+    let read_only = props.download_state == DownloadRowState::ReadOnly;
 
     rsx! {
         div { class: "space-y-0",
@@ -56,6 +58,9 @@ pub fn AlbumResult(props: Props) -> Element {
                     if let Some(release_date) = &album.release_date {
                         p { class: "text-xs text-gray-500 font-mono", "{release_date}" }
                     }
+                    if !details.is_empty() {
+                        p { class: "text-xs text-gray-600 font-mono truncate", title: "{details}", "{details}" }
+                    }
                 }
 
                 // Chevron indicator (D-02)
@@ -77,8 +82,9 @@ pub fn AlbumResult(props: Props) -> Element {
                 div { class: "flex items-center gap-1 shrink-0",
                     // Search sources button
                     button {
-                        class: "p-2 rounded-full hover:bg-white/10 transition-colors cursor-pointer group/src",
-                        title: "Search sources",
+                        class: "p-2 rounded-full hover:bg-white/10 transition-colors cursor-pointer group/src disabled:opacity-40 disabled:cursor-not-allowed",
+                        title: if read_only { READ_ONLY_HINT } else { "Search sources" },
+                        disabled: read_only,
                         onclick: move |evt: MouseEvent| {
                             evt.stop_propagation();
                             props.on_search_sources.call(());
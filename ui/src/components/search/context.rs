@@ -1,5 +1,6 @@
 use dioxus::prelude::*;
-use shared::download::AutoDownloadEvent;
+use shared::download::{AutoDownloadEvent, DownloadQuery, SharedSearchEvent};
+use shared::library::AlbumCompletion;
 
 #[derive(Clone, Copy)]
 pub struct SearchReset(pub Signal<u32>);
@@ -7,5 +8,16 @@ pub struct SearchReset(pub Signal<u32>);
 #[derive(Clone, Copy)]
 pub struct SearchPrefill(pub Signal<Option<(String, String)>>);
 
+/// Missing tracks of a library album to search for, filed into its folder
+#[derive(Clone, Copy)]
+pub struct CompleteAlbumSignal(pub Signal<Option<AlbumCompletion>>);
+
+/// A search from the search history to run again
+#[derive(Clone, Copy)]
+pub struct RerunSearchSignal(pub Signal<Option<DownloadQuery>>);
+
 #[derive(Clone, Copy)]
 pub struct AutoDownloadSignal(pub Signal<Option<AutoDownloadEvent>>);
+
+#[derive(Clone, Copy)]
+pub struct SharedSearchSignal(pub Signal<Option<SharedSearchEvent>>);
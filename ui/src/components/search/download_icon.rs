@@ -4,6 +4,9 @@ use dioxus::prelude::*;
 
 use super::download_options_menu::FolderDropdown;
 
+/// Tooltip of the download controls a viewer sees.
+pub const READ_ONLY_HINT: &str = "Viewers can browse but not download. Ask for download rights.";
+
 #[derive(Clone, PartialEq, Debug, Default)]
 pub enum DownloadRowState {
     #[default]
@@ -13,6 +16,8 @@ pub enum DownloadRowState {
     Done,
     Failed(String),
     Disabled,
+    /// The user is a viewer and can't download at all
+    ReadOnly,
 }
 
 #[derive(Props, PartialEq, Clone)]
@@ -118,6 +123,7 @@ pub fn DownloadIcon(props: DownloadIconProps) -> Element {
                 },
                 title: match state {
                     DownloadRowState::Disabled => "Configure a folder in Settings".to_string(),
+                    DownloadRowState::ReadOnly => READ_ONLY_HINT.to_string(),
                     DownloadRowState::Failed(msg) => msg.clone(),
                     _ => String::new(),
                 },
@@ -170,7 +176,7 @@ pub fn DownloadIcon(props: DownloadIconProps) -> Element {
                             }
                         }
                     },
-                    DownloadRowState::Disabled => rsx! {
+                    DownloadRowState::Disabled | DownloadRowState::ReadOnly => rsx! {
                         svg {
                             class: "w-5 h-5 text-gray-700 cursor-not-allowed",
                             fill: "none",
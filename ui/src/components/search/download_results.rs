@@ -1,8 +1,16 @@
 use dioxus::logger::tracing::info;
 use dioxus::prelude::*;
-use shared::download::{DownloadableGroup, DownloadableItem};
-use std::collections::HashSet;
-
+use shared::download::{
+    remote_file_name, DownloadableGroup, DownloadableItem, ImportOverrides, ImportTarget,
+    MappedTrack, NonCanonicalImport, NonCanonicalKind, IMPORT_FLAG_ALLOWLIST,
+};
+use shared::matching::rank_match;
+use shared::quality::SelectionSummary;
+use shared::slskd::MatchResult;
+use shared::system::{BackendInfo, FolderDiagnostics};
+use std::collections::{HashMap, HashSet};
+
+use super::download_summary::DownloadSummary;
 use crate::{use_auth, Checkbox};
 
 /// Format a sample rate in Hz as kHz, trimming a trailing .0 (44100 -> "44.1", 48000 -> "48").
@@ -63,15 +71,73 @@ fn quality_badge(item: &DownloadableItem) -> Element {
     }
 }
 
+fn match_badge(result: &MatchResult) -> Element {
+    let color = if result.total_score >= 0.8 {
+        "text-green-400"
+    } else if result.total_score >= 0.5 {
+        "text-amber-400"
+    } else {
+        "text-red-400"
+    };
+    let percent = (result.total_score * 100.0).round() as u32;
+    let tooltip = if result.matched_track.is_empty() {
+        "Match against the searched release".to_string()
+    } else {
+        format!("Matched as \"{}\"", result.matched_track)
+    };
+
+    rsx! {
+        span { class: "text-[10px] font-mono shrink-0 {color}", title: "{tooltip}", "{percent}%" }
+    }
+}
+
 #[derive(Props, PartialEq, Clone)]
 pub struct Props {
     pub results: Vec<DownloadableGroup>,
     pub is_searching: bool,
     pub is_downloading: Signal<bool>,
     #[props(into)]
-    pub on_download: EventHandler<(Vec<DownloadableItem>, String)>,
+    pub on_download: EventHandler<(Vec<DownloadableItem>, String, ImportOverrides)>,
     #[props(into)]
     pub on_back: EventHandler<()>,
+    /// Externally owned selection, used to keep picks in sync in shared sessions.
+    #[props(default)]
+    pub selected_tracks: Option<Signal<HashSet<String>>>,
+    #[props(default)]
+    pub on_selection_change: Option<EventHandler<Vec<String>>>,
+    /// Shows a "Share" button when set and no session is active yet.
+    #[props(default)]
+    pub on_share: Option<EventHandler<()>>,
+    #[props(default)]
+    pub share_link: Option<String>,
+    #[props(default)]
+    pub participants: Vec<String>,
+    /// Titles of the tracks searched for, in release order. Files can be
+    /// reassigned to one of these by hand.
+    #[props(default)]
+    pub expected_tracks: Vec<String>,
+    /// Artist and album searched for. Files are scored against them here,
+    /// the same way the server ranked them.
+    #[props(default)]
+    pub searched_artist: Option<String>,
+    #[props(default)]
+    pub searched_album: Option<String>,
+    /// How the genre routing rules apply to the release searched for. The
+    /// folder of a matching rule is preselected.
+    #[props(default)]
+    pub routing: Option<Signal<Option<api::RoutingPreview>>>,
+    /// Folder suggested from past downloads of the artist or the genre,
+    /// preselected unless a folder was already picked.
+    #[props(default)]
+    pub suggestion: Option<Signal<Option<api::FolderSuggestion>>>,
+}
+
+/// Expected track picked by hand for a file, overriding the automatic match.
+#[derive(Debug, Clone, PartialEq)]
+enum Assignment {
+    Track(MappedTrack),
+    /// Leave the file out of the download
+    Ignore,
 }
 
 #[derive(Props, Clone, PartialEq)]
@@ -80,6 +146,11 @@ struct AlbumResultItemProps {
     selected_tracks: Signal<HashSet<String>>,
     on_album_select_all: EventHandler<DownloadableGroup>,
     on_track_toggle: EventHandler<String>,
+    expected_tracks: Vec<String>,
+    searched_artist: Option<String>,
+    searched_album: Option<String>,
+    assignments: Signal<HashMap<String, Assignment>>,
+    on_assign: EventHandler<(String, Option<Assignment>)>,
     is_best: bool,
     starts_expanded: bool,
 }
@@ -89,6 +160,15 @@ struct TrackItemProps {
     track: DownloadableItem,
     is_selected: bool,
     on_toggle: EventHandler<String>,
+    /// Tracks the file can be reassigned to
+    track_options: Vec<String>,
+    /// Whether `track_options` are in release order, so their position is
+    /// the track number
+    numbered: bool,
+    assignment: Option<Assignment>,
+    on_assign: EventHandler<(String, Option<Assignment>)>,
+    /// How the file scores against the searched release
+    match_result: Option<MatchResult>,
 }
 
 fn get_track_id(track: &DownloadableItem) -> String {
@@ -98,6 +178,25 @@ fn get_track_id(track: &DownloadableItem) -> String {
 #[component]
 fn TrackItem(props: TrackItemProps) -> Element {
     let unique_id = get_track_id(&props.track);
+    let assign_id = unique_id.clone();
+    let file_name = remote_file_name(&props.track.id).to_string();
+    let ignored = props.assignment == Some(Assignment::Ignore);
+    let (title, remapped) = match &props.assignment {
+        Some(Assignment::Track(track)) => (track.title.clone(), true),
+        _ => (props.track.title.clone(), false),
+    };
+    let selected_value = match &props.assignment {
+        None => String::new(),
+        Some(Assignment::Ignore) => "ignore".to_string(),
+        Some(Assignment::Track(track)) => props
+            .track_options
+            .iter()
+            .position(|t| *t == track.title)
+            .map(|i| i.to_string())
+            .unwrap_or_default(),
+    };
+    let options = props.track_options.clone();
+    let numbered = props.numbered;
 
     rsx! {
         li {
@@ -105,12 +204,49 @@ fn TrackItem(props: TrackItemProps) -> Element {
             class: "flex items-center gap-2 p-1 rounded-md hover:bg-white/10 cursor-pointer",
             onclick: move |_| props.on_toggle.call(unique_id.clone()),
 
-            Checkbox { is_selected: props.is_selected }
+            Checkbox { is_selected: props.is_selected && !ignored }
+
+            label {
+                class: "cursor-pointer font-mono text-sm flex-1 min-w-0 truncate",
+                class: if ignored { "text-gray-600 line-through" } else if remapped { "text-beet-accent" } else { "text-gray-300" },
+                title: "{file_name}",
+                "{title}"
+            }
 
-            label { class: "cursor-pointer text-gray-300 font-mono text-sm flex-1 min-w-0 truncate",
-                "{props.track.title}"
+            if options.len() > 1 {
+                select {
+                    class: "max-w-32 p-0.5 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-gray-400 font-mono text-[10px] cursor-pointer",
+                    title: "Expected track this file is",
+                    value: "{selected_value}",
+                    onclick: move |e| e.stop_propagation(),
+                    onchange: move |e| {
+                        let assignment = match e.value().as_str() {
+                            "" => None,
+                            "ignore" => Some(Assignment::Ignore),
+                            index => index.parse::<usize>().ok().and_then(|i| {
+                                options.get(i).map(|title| {
+                                    Assignment::Track(MappedTrack {
+                                        title: title.clone(),
+                                        number: numbered.then_some(i as u32 + 1),
+                                    })
+                                })
+                            }),
+                        };
+                        props.on_assign.call((assign_id.clone(), assignment));
+                    },
+                    option { value: "", "As matched" }
+                    for (i, option_title) in props.track_options.iter().enumerate() {
+                        option { value: "{i}",
+                            if numbered { "{i + 1}. {option_title}" } else { "{option_title}" }
+                        }
+                    }
+                    option { value: "ignore", "Ignore" }
+                }
             }
 
+            if let Some(result) = &props.match_result {
+                {match_badge(result)}
+            }
             {quality_badge(&props.track)}
         }
     }
@@ -121,6 +257,37 @@ fn AlbumResultItem(props: AlbumResultItemProps) -> Element {
     let album = props.album.clone();
     let mut is_expanded = use_signal(|| props.starts_expanded);
 
+    // Without the searched tracks, files can be swapped among the titles
+    // matched in this folder
+    let numbered = !props.expected_tracks.is_empty();
+    let track_options = if numbered {
+        props.expected_tracks.clone()
+    } else {
+        let mut titles: Vec<String> = Vec::new();
+        for item in &props.album.items {
+            if !titles.contains(&item.title) {
+                titles.push(item.title.clone());
+            }
+        }
+        titles
+    };
+
+    let searched_artist = props.searched_artist.clone();
+    let searched_album = props.searched_album.clone();
+    let expected: Vec<String> = props.expected_tracks.clone();
+    let score_file = move |track: &DownloadableItem| {
+        if searched_artist.is_none() && searched_album.is_none() {
+            return None;
+        }
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        Some(rank_match(
+            &track.id,
+            searched_artist.as_deref(),
+            searched_album.as_deref(),
+            &expected,
+        ))
+    };
+
     rsx! {
         div {
             key: "{album.group_id}",
@@ -172,9 +339,14 @@ fn AlbumResultItem(props: AlbumResultItemProps) -> Element {
                 ul { class: "space-y-1",
                     for track in props.album.items {
                         TrackItem {
+                            match_result: score_file(&track),
                             is_selected: props.selected_tracks.read().contains(&get_track_id(&track)),
+                            assignment: props.assignments.read().get(&get_track_id(&track)).cloned(),
                             track,
                             on_toggle: props.on_track_toggle,
+                            track_options: track_options.clone(),
+                            numbered,
+                            on_assign: props.on_assign,
                         }
                     }
                 }
@@ -186,52 +358,138 @@ fn AlbumResultItem(props: AlbumResultItemProps) -> Element {
 /// Main component responsible for displaying all download options.
 #[component]
 pub fn DownloadResults(props: Props) -> Element {
-    let mut selected_tracks = use_signal(HashSet::<String>::new);
+    let local_selection = use_signal(HashSet::<String>::new);
+    let mut selected_tracks = props.selected_tracks.unwrap_or(local_selection);
+    let on_selection_change = props.on_selection_change;
+    let notify_selection = move || {
+        if let Some(handler) = on_selection_change {
+            handler.call(selected_tracks.read().iter().cloned().collect());
+        }
+    };
     let results = props.results.clone();
     let mut folders = use_signal(std::vec::Vec::new);
     let mut selected_folder = use_signal(|| "".to_string());
+    let mut diagnostics = use_signal(HashMap::<String, FolderDiagnostics>::new);
     let mut is_downloading = props.is_downloading;
     let auth = use_auth();
 
+    // Advanced import options, sent as ImportOverrides
+    let mut show_advanced = use_signal(|| false);
+    let mut importers = use_signal(Vec::<BackendInfo>::new);
+    let mut importer_choice = use_signal(String::new);
+    let mut extra_flags = use_signal(Vec::<String>::new);
+    let mut target_override = use_signal(String::new);
+    // Further libraries that each get their own copy
+    let mut extra_targets = use_signal(Vec::<ImportTarget>::new);
+    let mut review_tags = use_signal(|| false);
+    let mut release_kind = use_signal(|| None::<NonCanonicalKind>);
+    let mut assignments = use_signal(HashMap::<String, Assignment>::new);
+    // Selection waiting for confirmation in the summary
+    let mut pending = use_signal(|| None::<(Vec<DownloadableItem>, String, ImportOverrides)>);
+    let routing = props.routing;
+
+    use_effect(move || {
+        let routed = routing.and_then(|r| r.read().as_ref().and_then(|p| p.folder_id.clone()));
+        if let Some(folder_id) = routed {
+            selected_folder.set(folder_id);
+        }
+    });
+
+    let suggestion = props.suggestion;
+    use_effect(move || {
+        let suggested = suggestion.and_then(|s| s.read().as_ref().map(|s| s.folder_id.clone()));
+        if let Some(folder_id) = suggested {
+            if selected_folder.peek().is_empty() {
+                selected_folder.set(folder_id);
+            }
+        }
+    });
+
     use_future(move || async move {
-        if let Ok(user_folders) = auth.call(api::get_user_folders()).await {
+        if let Ok(user_folders) = auth.call(api::get_user_folders).await {
             info!("Fetched {} user folders", user_folders.len());
 
             // Only select if the user has exactly one folder
             // It could be error prone to auto-select if there are multiple folders
             // (I failed multiple times because of this)
             if user_folders.len() == 1 {
-                selected_folder.set(user_folders[0].path.clone());
+                selected_folder.set(user_folders[0].id.clone());
             }
             folders.set(user_folders);
         }
+        if let Ok(diags) = auth.call(api::folder_diagnostics).await {
+            diagnostics.set(
+                diags
+                    .into_iter()
+                    .map(|d| (d.folder_id.clone(), d))
+                    .collect(),
+            );
+        }
+        if let Ok(backends) = auth.call(api::get_backends).await {
+            importers.set(backends.importer);
+        }
     });
 
-    let handle_album_select_all = move |group: DownloadableGroup| {
-        let mut selected = selected_tracks.write();
-        let all_selected = group
-            .items
-            .iter()
-            .all(|t| selected.contains(&get_track_id(t)));
+    let folder_label = move |folder: &api::models::folder::Folder| -> String {
+        match diagnostics.read().get(&folder.id) {
+            Some(d) if !d.exists => format!("{} (missing)", folder.name),
+            Some(d) if !d.writable => format!("{} (read-only)", folder.name),
+            Some(d) => match d.free_space_label() {
+                Some(free) => format!("{} ({})", folder.name, free),
+                None => folder.name.clone(),
+            },
+            None => folder.name.clone(),
+        }
+    };
 
-        if all_selected {
-            for item in &group.items {
-                selected.remove(&get_track_id(item));
-            }
-        } else {
-            for item in &group.items {
-                selected.insert(get_track_id(item));
+    let handle_album_select_all = move |group: DownloadableGroup| {
+        {
+            let mut selected = selected_tracks.write();
+            let all_selected = group
+                .items
+                .iter()
+                .all(|t| selected.contains(&get_track_id(t)));
+
+            if all_selected {
+                for item in &group.items {
+                    selected.remove(&get_track_id(item));
+                }
+            } else {
+                for item in &group.items {
+                    selected.insert(get_track_id(item));
+                }
             }
         }
+        notify_selection();
     };
 
     let handle_track_toggle = move |filename: String| {
         info!("Toggle track selection: {}", filename);
-        let mut selected = selected_tracks.write();
-        if selected.contains(&filename) {
-            selected.remove(&filename);
+        {
+            let mut selected = selected_tracks.write();
+            if selected.contains(&filename) {
+                selected.remove(&filename);
+            } else {
+                selected.insert(filename);
+            }
+        }
+        notify_selection();
+    };
+
+    let handle_assign = move |(id, assignment): (String, Option<Assignment>)| {
+        let ignored = assignment == Some(Assignment::Ignore);
+        match assignment {
+            Some(assignment) => assignments.write().insert(id.clone(), assignment),
+            None => assignments.write().remove(&id),
+        };
+        // Reassigning a file implies wanting it
+        let changed = if ignored {
+            selected_tracks.write().remove(&id)
         } else {
-            selected.insert(filename);
+            selected_tracks.write().insert(id)
+        };
+        if changed {
+            notify_selection();
         }
     };
 
@@ -243,12 +501,14 @@ pub fn DownloadResults(props: Props) -> Element {
         }
 
         let selected_ids = selected_tracks.read();
+        let assigned = assignments.read();
 
         let items_to_download: Vec<DownloadableItem> = props
             .results
             .iter()
             .flat_map(|group| group.items.iter())
             .filter(|item| selected_ids.contains(&get_track_id(item)))
+            .filter(|item| assigned.get(&get_track_id(item)) != Some(&Assignment::Ignore))
             .cloned()
             .collect();
 
@@ -259,12 +519,95 @@ pub fn DownloadResults(props: Props) -> Element {
         // Set downloading state immediately to prevent double-clicks
         is_downloading.set(true);
 
-        props
-            .on_download
-            .call((items_to_download, selected_folder()));
+        let overrides = ImportOverrides {
+            importer: Some(importer_choice()).filter(|i| !i.is_empty()),
+            extra_flags: extra_flags(),
+            target_path: Some(target_override().trim().to_string()).filter(|t| !t.is_empty()),
+            review_tags: review_tags(),
+            non_canonical: release_kind().map(|kind| NonCanonicalImport {
+                kind,
+                artist: items_to_download.first().map(|i| i.artist.clone()),
+                album: items_to_download.first().map(|i| i.album.clone()),
+            }),
+            track_mapping: items_to_download
+                .iter()
+                .filter_map(|item| match assigned.get(&get_track_id(item)) {
+                    Some(Assignment::Track(track)) => {
+                        Some((remote_file_name(&item.id).to_string(), track.clone()))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            extra_targets: extra_targets()
+                .into_iter()
+                .filter(|t| t.folder_id != selected_folder())
+                .collect(),
+            ..Default::default()
+        };
+
+        pending.set(Some((items_to_download, selected_folder(), overrides)));
     };
 
+    let handle_confirm = move |_| {
+        if let Some(download) = pending.take() {
+            props.on_download.call(download);
+        }
+    };
+
+    let handle_cancel = move |_| {
+        pending.set(None);
+        is_downloading.set(false);
+    };
+
+    // Where the pending download goes and who imports it, for the summary
+    let pending_target = move |folder_id: &str, overrides: &ImportOverrides| -> String {
+        let folders = folders.read();
+        let main = match &overrides.target_path {
+            Some(path) => path.clone(),
+            None => folders
+                .iter()
+                .find(|f| f.id == folder_id)
+                .map(|f| format!("{} ({})", f.name, f.path))
+                .unwrap_or_default(),
+        };
+        let extra: Vec<_> = overrides
+            .extra_targets
+            .iter()
+            .filter_map(|t| folders.iter().find(|f| f.id == t.folder_id))
+            .map(|f| f.name.clone())
+            .collect();
+        if extra.is_empty() {
+            main
+        } else {
+            format!("{} + {}", main, extra.join(", "))
+        }
+    };
+    let pending_importer = move |overrides: &ImportOverrides| -> String {
+        overrides
+            .importer
+            .as_ref()
+            .and_then(|id| {
+                importers
+                    .read()
+                    .iter()
+                    .find(|i| i.id == *id)
+                    .map(|i| i.name.clone())
+            })
+            .unwrap_or_else(|| "Default".to_string())
+    };
+
+    let participants_label = props.participants.join(", ");
+
     rsx! {
+        if let Some((items, folder_id, overrides)) = pending() {
+            DownloadSummary {
+                summary: SelectionSummary::of(&items),
+                target: pending_target(&folder_id, &overrides),
+                importer: pending_importer(&overrides),
+                on_confirm: handle_confirm,
+                on_cancel: handle_cancel,
+            }
+        }
         div { class: "bg-beet-panel border border-white/10 text-white p-6 sm:p-8 rounded-lg shadow-2xl w-full max-w-2xl mx-auto my-10 font-display relative",
             div { class: "relative mb-6",
                 button {
@@ -285,6 +628,28 @@ pub fn DownloadResults(props: Props) -> Element {
                     }
                 }
                 h3 { class: "text-2xl font-bold text-center text-beet-accent", "Download Options" }
+                if props.share_link.is_none() {
+                    if let Some(on_share) = props.on_share {
+                        button {
+                            class: "absolute right-0 top-1/2 -translate-y-1/2 text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
+                            onclick: move |_| on_share.call(()),
+                            "Share"
+                        }
+                    }
+                }
+            }
+            if let Some(link) = props.share_link.clone() {
+                div { class: "mb-4 p-3 bg-beet-accent/5 border border-beet-accent/30 rounded space-y-1",
+                    p { class: "text-xs font-mono text-gray-400 uppercase tracking-wider", "Shared session" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 text-white font-mono text-xs",
+                        readonly: true,
+                        value: "{link}",
+                    }
+                    if !participants_label.is_empty() {
+                        p { class: "text-xs font-mono text-beet-leaf", "With: {participants_label}" }
+                    }
+                }
             }
             div { class: "mb-4",
                 label {
@@ -299,7 +664,166 @@ pub fn DownloadResults(props: Props) -> Element {
                     onchange: move |e| selected_folder.set(e.value()),
                     option { value: "", disabled: true, "Select a folder" }
                     for folder in folders.read().iter() {
-                        option { value: "{folder.path}", "{folder.name}" }
+                        option { value: "{folder.id}", {folder_label(folder)} }
+                    }
+                }
+                if let Some(hint) = suggestion.and_then(|s| s()).filter(|s| s.folder_id == selected_folder()) {
+                    p { class: "mt-1 text-xs font-mono text-gray-500", "Suggested: {hint.reason}" }
+                }
+                if let Some(preview) = routing.and_then(|r| r()) {
+                    div { class: "mt-2 p-3 bg-white/5 border border-white/10 rounded font-mono text-xs text-gray-400 space-y-1",
+                        p { class: "text-gray-300",
+                            if preview.trace.genres.is_empty() {
+                                "Genre routing"
+                            } else {
+                                "Genre routing: {preview.trace.genres.join(\", \")}"
+                            }
+                        }
+                        for step in preview.trace.steps.iter() {
+                            p { "{step}" }
+                        }
+                    }
+                }
+                button {
+                    class: "mt-2 text-xs font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
+                    onclick: move |_| show_advanced.set(!show_advanced()),
+                    if show_advanced() { "\u{25BE} Advanced import options" } else { "\u{25B8} Advanced import options" }
+                }
+                if show_advanced() {
+                    div { class: "mt-2 p-3 bg-white/5 border border-white/10 rounded space-y-3 font-mono text-sm",
+                        if importers.read().len() > 1 {
+                            div {
+                                label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Importer" }
+                                select {
+                                    class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                                    value: "{importer_choice}",
+                                    onchange: move |e| importer_choice.set(e.value()),
+                                    option { value: "", "Default" }
+                                    for importer in importers.read().iter() {
+                                        option { value: "{importer.id}", "{importer.name}" }
+                                    }
+                                }
+                            }
+                        }
+                        div {
+                            label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Import destination" }
+                            input {
+                                class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                                value: "{target_override}",
+                                oninput: move |e| target_override.set(e.value()),
+                                placeholder: "Selected folder (must stay inside one of your folders)",
+                                "type": "text",
+                            }
+                        }
+                        if folders.read().len() > 1 {
+                            div { class: "space-y-1",
+                                p { class: "text-xs text-gray-400 uppercase tracking-wider", "Also import into" }
+                                for folder in folders.read().iter().filter(|f| f.id != selected_folder()).cloned() {
+                                    {
+                                        let toggle_id = folder.id.clone();
+                                        let importer_id = folder.id.clone();
+                                        let picked = extra_targets
+                                            .read()
+                                            .iter()
+                                            .find(|t| t.folder_id == folder.id)
+                                            .cloned();
+                                        rsx! {
+                                            div { key: "{folder.id}", class: "flex items-center gap-2 text-gray-300",
+                                                div {
+                                                    class: "flex items-center gap-2 flex-1 min-w-0 cursor-pointer",
+                                                    onclick: move |_| {
+                                                        let mut targets = extra_targets.write();
+                                                        match targets.iter().position(|t| t.folder_id == toggle_id) {
+                                                            Some(pos) => {
+                                                                targets.remove(pos);
+                                                            }
+                                                            None => targets.push(ImportTarget {
+                                                                folder_id: toggle_id.clone(),
+                                                                importer: None,
+                                                            }),
+                                                        }
+                                                    },
+                                                    Checkbox { is_selected: picked.is_some() }
+                                                    span { class: "truncate", {folder_label(&folder)} }
+                                                }
+                                                if let Some(target) = picked.filter(|_| importers.read().len() > 1) {
+                                                    select {
+                                                        class: "p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white text-xs",
+                                                        value: target.importer.unwrap_or_default(),
+                                                        onchange: move |e| {
+                                                            let importer = Some(e.value()).filter(|i| !i.is_empty());
+                                                            if let Some(target) = extra_targets
+                                                                .write()
+                                                                .iter_mut()
+                                                                .find(|t| t.folder_id == importer_id)
+                                                            {
+                                                                target.importer = importer;
+                                                            }
+                                                        },
+                                                        option { value: "", "Default importer" }
+                                                        for importer in importers.read().iter() {
+                                                            option { value: "{importer.id}", "{importer.name}" }
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                                p { class: "text-xs text-gray-500",
+                                    "Each picked folder gets its own copy of the download, imported with that folder's import mode and remote."
+                                }
+                            }
+                        }
+                        div {
+                            label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Release type" }
+                            select {
+                                class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                                onchange: move |e| {
+                                    release_kind.set(match e.value().as_str() {
+                                        "live" => Some(NonCanonicalKind::Live),
+                                        "bootleg" => Some(NonCanonicalKind::Bootleg),
+                                        _ => None,
+                                    })
+                                },
+                                option { value: "", selected: release_kind().is_none(), "Regular (match on MusicBrainz)" }
+                                option { value: "live", selected: release_kind() == Some(NonCanonicalKind::Live), "Live recording" }
+                                option { value: "bootleg", selected: release_kind() == Some(NonCanonicalKind::Bootleg), "Bootleg" }
+                            }
+                            if release_kind().is_some() {
+                                p { class: "text-xs text-gray-500 mt-1",
+                                    "Tagged from the search result and folder name, filed under the live/bootleg path. Importer and flags are ignored."
+                                }
+                            }
+                        }
+                        div {
+                            class: "flex items-center gap-2 cursor-pointer text-gray-300",
+                            onclick: move |_| review_tags.set(!review_tags()),
+                            Checkbox { is_selected: review_tags() }
+                            span { "Review tags before import" }
+                            span { class: "text-xs text-gray-500 truncate", "Edit artist, album, cover and titles on the Import page" }
+                        }
+                        div { class: "space-y-1",
+                            p { class: "text-xs text-gray-400 uppercase tracking-wider", "Extra flags" }
+                            for (flag, description) in IMPORT_FLAG_ALLOWLIST.iter() {
+                                div {
+                                    class: "flex items-center gap-2 cursor-pointer text-gray-300",
+                                    title: "{description}",
+                                    onclick: move |_| {
+                                        let mut flags = extra_flags.write();
+                                        match flags.iter().position(|f| f == flag) {
+                                            Some(pos) => {
+                                                flags.remove(pos);
+                                            }
+                                            None => flags.push(flag.to_string()),
+                                        }
+                                    },
+                                    Checkbox { is_selected: extra_flags.read().iter().any(|f| f == flag) }
+                                    span { "{flag}" }
+                                    span { class: "text-xs text-gray-500 truncate", "{description}" }
+                                }
+                            }
+                        }
                     }
                 }
             }
@@ -321,6 +845,11 @@ pub fn DownloadResults(props: Props) -> Element {
                         selected_tracks,
                         on_album_select_all: handle_album_select_all,
                         on_track_toggle: handle_track_toggle,
+                        expected_tracks: props.expected_tracks.clone(),
+                        searched_artist: props.searched_artist.clone(),
+                        searched_album: props.searched_album.clone(),
+                        assignments,
+                        on_assign: handle_assign,
                         is_best: idx == 0,
                         starts_expanded: idx == 0,
                     }
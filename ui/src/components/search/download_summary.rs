@@ -0,0 +1,83 @@
+use dioxus::prelude::*;
+use shared::quality::SelectionSummary;
+
+use crate::downloads::format_size;
+use crate::Modal;
+
+/// Rough duration for the estimate, e.g. "about 4 min".
+fn format_eta(secs: u64) -> String {
+    match secs {
+        0..60 => "under a minute".to_string(),
+        60..3600 => format!("about {} min", secs.div_ceil(60)),
+        _ => format!("about {} h {} min", secs / 3600, (secs % 3600) / 60),
+    }
+}
+
+/// What is about to be queued, confirmed before anything is sent to the
+/// peers.
+#[component]
+pub fn DownloadSummary(
+    summary: SelectionSummary,
+    /// Folder (or path inside it) the files are imported into
+    target: String,
+    importer: String,
+    on_confirm: EventHandler,
+    on_cancel: EventHandler,
+) -> Element {
+    let warnings = summary.warnings();
+    let files = match summary.files {
+        1 => "1 track".to_string(),
+        n => format!("{} tracks", n),
+    };
+    let peers = match summary.peers {
+        1 => "1 peer".to_string(),
+        n => format!("{} peers", n),
+    };
+    let eta = summary
+        .estimated_secs
+        .map(format_eta)
+        .unwrap_or_else(|| "unknown".to_string());
+
+    rsx! {
+        Modal {
+            on_close: move |_| on_cancel.call(()),
+            header: rsx! {
+                h2 { class: "text-lg font-bold text-white font-display", "Start Download" }
+            },
+            div { class: "font-mono text-sm space-y-4",
+                p { class: "text-gray-300", "{files} from {peers}" }
+                div { class: "grid grid-cols-[auto_1fr] gap-x-4 gap-y-1 text-xs",
+                    span { class: "text-gray-500 uppercase tracking-wider", "Size" }
+                    span { class: "text-gray-300", {format_size(summary.total_size)} }
+                    span { class: "text-gray-500 uppercase tracking-wider", "Time" }
+                    span { class: "text-gray-300", "{eta}" }
+                    span { class: "text-gray-500 uppercase tracking-wider", "Folder" }
+                    span { class: "text-gray-300 break-all", "{target}" }
+                    span { class: "text-gray-500 uppercase tracking-wider", "Importer" }
+                    span { class: "text-gray-300", "{importer}" }
+                    span { class: "text-gray-500 uppercase tracking-wider", "Formats" }
+                    span { class: "text-gray-300", {summary.formats.join(", ")} }
+                }
+                if !warnings.is_empty() {
+                    div { class: "p-3 bg-yellow-900/20 border border-yellow-500/40 rounded text-yellow-400 text-xs space-y-1",
+                        for warning in warnings {
+                            p { "{warning}" }
+                        }
+                    }
+                }
+                div { class: "flex justify-end gap-2",
+                    button {
+                        class: "px-3 py-1.5 text-sm font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
+                        onclick: move |_| on_cancel.call(()),
+                        "Cancel"
+                    }
+                    button {
+                        class: "retro-btn rounded text-sm",
+                        onclick: move |_| on_confirm.call(()),
+                        "Download"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -5,6 +5,7 @@ use std::collections::HashMap;
 
 use super::download_icon::DownloadRowState;
 use super::inline_track_row::InlineTrackRow;
+use crate::use_auth;
 
 const SKELETON_WIDTHS: [u8; 4] = [60, 75, 50, 65];
 
@@ -24,6 +25,7 @@ pub struct Props {
 #[component]
 pub fn InlineTrackPanel(props: Props) -> Element {
     let has_folders = !props.folders.is_empty();
+    let read_only = use_auth().is_viewer();
 
     rsx! {
         div {
@@ -56,7 +58,9 @@ pub fn InlineTrackPanel(props: Props) -> Element {
                             class: "max-h-[400px] overflow-y-auto space-y-0.5",
                             for (idx, track) in tracks.iter().enumerate() {
                                 {
-                                    let dl_state = if !has_folders {
+                                    let dl_state = if read_only {
+                                        DownloadRowState::ReadOnly
+                                    } else if !has_folders {
                                         DownloadRowState::Disabled
                                     } else {
                                         props.download_states.read().get(&track.id).cloned().unwrap_or_default()
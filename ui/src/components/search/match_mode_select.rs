@@ -0,0 +1,22 @@
+use dioxus::prelude::*;
+use shared::download::MatchMode;
+
+/// Per-search override of the matching mode. `None` uses the user's setting.
+#[component]
+pub fn MatchModeSelect(match_mode: Signal<Option<MatchMode>>) -> Element {
+    rsx! {
+      select {
+        class: "bg-black/20 rounded p-1 mr-2 text-xs font-bold text-gray-400 border-none focus:outline-none focus:ring-0 cursor-pointer",
+        title: "How closely Soulseek files must match",
+        onchange: move |e| match_mode.set(MatchMode::from_setting(&e.value())),
+        option { value: "", selected: match_mode().is_none(), "MATCH" }
+        for preset in MatchMode::PRESETS {
+          option {
+            value: "{preset.to_setting()}",
+            selected: match_mode() == Some(preset),
+            "{preset.label().to_uppercase()}"
+          }
+        }
+      }
+    }
+}
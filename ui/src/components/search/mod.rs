@@ -2,7 +2,10 @@ pub mod album;
 pub mod context;
 pub mod track;
 
-pub use context::{AutoDownloadSignal, SearchPrefill, SearchReset};
+pub use context::{
+    AutoDownloadSignal, CompleteAlbumSignal, RerunSearchSignal, SearchPrefill, SearchReset,
+    SharedSearchSignal,
+};
 
 mod download_icon;
 pub use download_icon::{DownloadIcon, DownloadRowState};
@@ -15,46 +18,116 @@ mod inline_track_row;
 mod folder_chip;
 use folder_chip::FolderChip;
 
+mod shared_session;
+pub use shared_session::SharedSearch;
+
 use api::models::folder::Folder;
 use dioxus::logger::tracing::{info, warn};
 use dioxus::prelude::*;
 use shared::download::{
-    AutoDownloadEvent, DownloadQuery, DownloadableGroup, DownloadableItem,
-    SearchState as DownloadSearchState,
+    AlbumMerge, AutoDownloadEvent, DownloadQuery, DownloadableGroup, DownloadableItem,
+    ExpectedRelease, ImportOverrides, MatchMode, SearchState as DownloadSearchState,
+    SearchStrategy, SharedSearchEvent,
+};
+use shared::error::ApiError;
+use shared::features::flags;
+use shared::metadata::{
+    AlbumWithTracks, IdentifierKind, Provider, SearchResult, SearchResults, Track,
 };
-use shared::metadata::{AlbumWithTracks, Provider, SearchResult, SearchResults, Track};
+use shared::quality::{diversify, rerank};
 use std::collections::{HashMap, HashSet};
 
 use track::TrackResult;
 
 use crate::search::album::AlbumResult;
 use crate::settings_context::use_settings;
-use crate::{use_auth, use_system_health, Button, SystemStatus};
+use crate::{
+    api_error, use_auth, use_features, use_system_health, Button, ErrorNotice, SystemStatus,
+};
 
 mod download_results;
 use download_results::DownloadResults;
 
+mod download_summary;
+
 mod toast;
 use toast::{FallbackToast, FallbackToastData};
 
 mod search_type_toggle;
 use search_type_toggle::{SearchType, SearchTypeToggle};
 
+mod match_mode_select;
+use match_mode_select::MatchModeSelect;
+
+mod search_timeout_select;
+use search_timeout_select::SearchTimeoutSelect;
+
+mod search_strategy_select;
+use search_strategy_select::SearchStrategySelect;
+
+mod simulate_toggle;
+use simulate_toggle::SimulateToggle;
+
+mod ranking_sliders;
+use ranking_sliders::RankingSliders;
+
 #[component]
 pub fn Search() -> Element {
-    let auth = use_auth();
+    let mut auth = use_auth();
     let mut settings = use_settings();
     let mut search_results = use_signal::<Option<SearchResults>>(|| None);
     let mut search = use_signal(String::new);
     let mut artist = use_signal::<Option<String>>(|| None);
     let mut search_type = use_signal(|| settings.last_search_type());
+    // Matching mode for sources found from this page, None for the user's default
+    let match_mode = use_signal(|| None::<MatchMode>);
+    // Search timeout in seconds for this page, None for the user's default
+    let search_timeout = use_signal(|| None::<u32>);
+    // Search text shape for this page, unless a query sets its own
+    let search_strategy = use_signal(SearchStrategy::default);
+    let features = use_features();
+    let simulate = use_signal(|| false);
+    // A toggle left on stays ignored once dry runs are disabled
+    let dry_run = move || simulate() && features.is_enabled(flags::DRY_RUN);
     let mut loading = use_signal(|| false);
+    let mut search_error = use_signal(|| None::<ApiError>);
     let mut expanded_albums = use_signal::<HashSet<String>>(HashSet::new);
     let mut album_cache = use_signal::<HashMap<String, AlbumWithTracks>>(HashMap::new);
     let mut download_options = use_signal::<Option<Vec<DownloadableGroup>>>(|| None);
+    // Weights for re-ranking the sources on screen, starting from the profile's
+    let mut ranking = use_signal(|| settings.quality_profile().ranking);
+    let ranked_options = use_memo(move || {
+        let weights = ranking();
+        download_options().map(|groups| {
+            if weights.is_neutral() {
+                groups
+            } else {
+                rerank(groups, &settings.quality_profile().diversity, &weights)
+            }
+        })
+    });
+    // Titles searched for, offered when reassigning files by hand
+    let mut expected_tracks = use_signal(Vec::<String>::new);
+    // Artist and album searched for, to score files against on the client
+    let mut searched_release = use_signal(|| (None::<String>, None::<String>));
+    // MusicBrainz release searched for, for the importer to match against
+    let mut expected_release = use_signal(|| None::<ExpectedRelease>);
+    // How the genre routing rules apply to it, shown with the results
+    let mut routing = use_signal(|| None::<api::RoutingPreview>);
+    // Folder suggested for it from past downloads or its genre
+    let mut suggested_folder = use_signal(|| None::<api::FolderSuggestion>);
     let mut is_downloading = use_signal(|| false);
+    // Shared search session state for the current download search
+    let mut current_search_id = use_signal::<Option<String>>(|| None);
+    let mut shared_session = use_signal::<Option<String>>(|| None);
+    let mut shared_selection = use_signal(HashSet::<String>::new);
+    let mut session_participants = use_signal(Vec::<String>::new);
     let search_reset = try_use_context::<SearchReset>();
     let search_prefill = try_use_context::<SearchPrefill>();
+    let complete_album = try_use_context::<CompleteAlbumSignal>();
+    let rerun_search = try_use_context::<RerunSearchSignal>();
+    // Library album the current search fills in, set by the "complete" flow
+    let mut completing_album = use_signal(|| None::<AlbumMerge>);
 
     let system_health = use_system_health();
 
@@ -74,6 +147,7 @@ pub fn Search() -> Element {
     use_effect(move || {
         if settings.is_loaded() && !synced() {
             search_type.set(settings.last_search_type());
+            ranking.set(settings.quality_profile().ranking);
             synced.set(true);
         }
     });
@@ -101,7 +175,7 @@ pub fn Search() -> Element {
 
     // Fetch user folders
     use_future(move || async move {
-        if let Ok(user_folders) = auth.call(api::get_user_folders()).await {
+        if let Ok(user_folders) = auth.call(api::get_user_folders).await {
             if user_folders.len() == 1 {
                 selected_folder_id.set(Some(user_folders[0].id.clone()));
             }
@@ -122,6 +196,66 @@ pub fn Search() -> Element {
         }
     });
 
+    // Apply picks and participant changes coming from users sharing this search
+    let shared_events = try_use_context::<SharedSearchSignal>();
+    use_effect(move || {
+        let Some(mut ctx) = shared_events else { return };
+        let Some(event) = (ctx.0)() else { return };
+        if shared_session.peek().as_deref() != Some(event.session_id()) {
+            return;
+        }
+        (ctx.0).set(None);
+        match event {
+            SharedSearchEvent::Selection { selected, .. } => {
+                shared_selection.set(selected.into_iter().collect());
+            }
+            SharedSearchEvent::Participants { usernames, .. } => {
+                session_participants.set(usernames);
+            }
+            SharedSearchEvent::Closed { .. } => {
+                shared_session.set(None);
+                session_participants.set(Vec::new());
+            }
+            SharedSearchEvent::Results { .. } => {}
+        }
+    });
+
+    let share_search = move |_| {
+        let Some(search_id) = current_search_id() else { return };
+        let groups = download_options().unwrap_or_default();
+        spawn(async move {
+            match auth
+                .call(|| api::share_search_session(search_id.clone(), groups.clone()))
+                .await
+            {
+                Ok(session_id) => {
+                    session_participants.set(auth.username().into_iter().collect());
+                    shared_session.set(Some(session_id));
+                }
+                Err(e) => warn!("Failed to share search: {:?}", e),
+            }
+        });
+    };
+
+    let sync_selection = move |selected: Vec<String>| {
+        let Some(session_id) = shared_session() else { return };
+        spawn(async move {
+            let _ = auth
+                .call(|| api::update_session_selection(session_id.clone(), selected.clone()))
+                .await;
+        });
+    };
+
+    let me = auth.username();
+    let session_others: Vec<String> = session_participants()
+        .into_iter()
+        .filter(|p| Some(p) != me.as_ref())
+        .collect();
+    let app_url = use_resource(move || async move { auth.call(api::get_app_url).await.ok() });
+    let share_link = shared_session()
+        .zip(app_url().flatten())
+        .map(|(id, url)| format!("{url}/session/{id}"));
+
     // Buffer for events that arrive before batch_to_item is populated (race condition)
     let mut pending_events = use_signal::<Vec<AutoDownloadEvent>>(Vec::new);
 
@@ -305,6 +439,15 @@ pub fn Search() -> Element {
 
     // Start an auto_download for a specific folder
     let mut start_auto_download = move |item_id: String, query: DownloadQuery, folder: Folder| {
+        let query = DownloadQuery {
+            match_mode: query.match_mode.or(match_mode()),
+            search_timeout_secs: query.search_timeout_secs.or(search_timeout()),
+            search_strategy: match query.search_strategy {
+                SearchStrategy::Auto => search_strategy(),
+                strategy => strategy,
+            },
+            ..query
+        };
         download_states.write().insert(item_id.clone(), DownloadRowState::Searching);
 
         // Propagate to expanded track rows (D-09)
@@ -324,11 +467,15 @@ pub fn Search() -> Element {
 
         spawn(async move {
             let result = auth
-                .call(api::auto_download(api::AutoDownloadRequest {
-                    query,
-                    folder_id: folder.id.clone(),
-                    folder_path: folder.path.clone(),
-                }))
+                .call(|| {
+                    api::auto_download(api::AutoDownloadRequest {
+                        query: query.clone(),
+                        folder_id: folder.id.clone(),
+                        folder_path: folder.path.clone(),
+                        simulate: dry_run(),
+                        replacing_peer: None,
+                    })
+                })
                 .await;
 
             match result {
@@ -384,11 +531,70 @@ pub fn Search() -> Element {
     };
 
     let download = move |query: DownloadQuery| async move {
+        let query = DownloadQuery {
+            match_mode: query.match_mode.or(match_mode()),
+            search_timeout_secs: query.search_timeout_secs.or(search_timeout()),
+            search_strategy: match query.search_strategy {
+                SearchStrategy::Auto => search_strategy(),
+                strategy => strategy,
+            },
+            ..query
+        };
         loading.set(true);
         download_options.set(Some(vec![]));
+        expected_tracks.set(query.tracks.iter().map(|t| t.title.clone()).collect());
+        expected_release.set(query.album.as_ref().and_then(ExpectedRelease::from_album));
+        routing.set(None);
+        suggested_folder.set(None);
+        let suggest_for = query.clone();
+        spawn(async move {
+            suggested_folder.set(
+                auth.call(|| api::suggest_folder(suggest_for.clone()))
+                    .await
+                    .ok()
+                    .flatten(),
+            );
+        });
+        if !settings.routing_rules().is_empty() {
+            let routed = query.clone();
+            spawn(async move {
+                routing.set(
+                    auth.call(|| api::preview_routing(routed.clone()))
+                        .await
+                        .ok(),
+                );
+            });
+        }
+        searched_release.set((
+            query
+                .album
+                .as_ref()
+                .map(|a| a.artist.clone())
+                .or_else(|| query.tracks.first().map(|t| t.artist.clone())),
+            query
+                .album
+                .as_ref()
+                .map(|a| a.title.clone())
+                .or_else(|| query.tracks.first().and_then(|t| t.album_title.clone())),
+        ));
+        shared_selection.write().clear();
+        if let Some(session_id) = shared_session.take() {
+            session_participants.set(Vec::new());
+            spawn(async move {
+                let _ = auth
+                    .call(|| api::leave_search_session(session_id.clone()))
+                    .await;
+            });
+        }
 
-        let search_id = match auth.call(api::start_download_search(query)).await {
-            Ok(id) => id,
+        let search_id = match auth
+            .call(|| api::start_download_search(query.clone()))
+            .await
+        {
+            Ok(id) => {
+                current_search_id.set(Some(id.clone()));
+                id
+            }
             Err(e) => {
                 warn!("Failed to start download search: {:?}", e);
                 loading.set(false);
@@ -398,13 +604,16 @@ pub fn Search() -> Element {
 
         loop {
             match auth
-                .call(api::poll_download_search(api::PollQuery {
-                    search_id: search_id.clone(),
-                    backend: None,
-                }))
+                .call(|| {
+                    api::poll_download_search(api::PollQuery {
+                        search_id: search_id.clone(),
+                        backend: None,
+                    })
+                })
                 .await
             {
                 Ok(response) => {
+                    let diversity = settings.quality_profile().diversity;
                     download_options.with_mut(|current| {
                         if let Some(list) = current {
                             for new_group in response.groups {
@@ -418,12 +627,9 @@ pub fn Search() -> Element {
                                 }
                             }
 
-                            // Resort new results by score
-                            list.sort_by(|a, b| {
-                                b.score
-                                    .partial_cmp(&a.score)
-                                    .unwrap_or(std::cmp::Ordering::Equal)
-                            });
+                            // Resort new results by score, keeping every format
+                            // and completeness represented near the top
+                            *list = diversify(std::mem::take(list), &diversity);
                         }
                     });
 
@@ -440,48 +646,108 @@ pub fn Search() -> Element {
         loading.set(false);
     };
 
-    let download_tracks = move |(items, folder): (Vec<DownloadableItem>, String)| async move {
+    let download_tracks = move |(items, folder_id, import_overrides): (
+        Vec<DownloadableItem>,
+        String,
+        ImportOverrides,
+    )| async move {
+        let import_overrides = ImportOverrides {
+            complete_album: completing_album(),
+            release: expected_release(),
+            ..import_overrides
+        };
         match auth
-            .call(api::download(api::DownloadRequest {
-                items,
-                target_folder: folder,
-                backend: None,
-            }))
+            .call(|| {
+                api::download(api::DownloadRequest {
+                    items: items.clone(),
+                    folder_id: folder_id.clone(),
+                    backend: None,
+                    import_overrides: import_overrides.clone(),
+                    simulate: dry_run(),
+                })
+            })
             .await
         {
-            Ok(_res) => info!("Downloads started"),
+            Ok(res) => {
+                info!("Downloads started");
+                for (queued, refused) in res
+                    .iter()
+                    .filter_map(|q| q.substituted.as_ref().map(|s| (q, s)))
+                {
+                    info!(
+                        "{} refused {} ({}), queued from {} instead",
+                        refused.source, refused.item, refused.error, queued.source
+                    );
+                }
+            }
             Err(e) => warn!("Failed to start downloads: {:?}", e),
         }
         is_downloading.set(false);
     };
 
     let perform_search = move || async move {
+        auth.remember_search(artist(), search());
         loading.set(true);
         download_options.set(None);
+        completing_album.set(None);
+        search_error.set(None);
+
+        // A pasted release link opens that album directly
+        let input = search().trim().to_string();
+        if input.starts_with("http://") || input.starts_with("https://") {
+            match auth.call(|| api::resolve_release_url(input.clone())).await {
+                Ok(album) => {
+                    let album_id = album.album.id.clone();
+                    search_results.set(Some(SearchResults {
+                        provider: Provider::MusicBrainz,
+                        results: vec![SearchResult::Album(album.album.clone())],
+                    }));
+                    album_cache.write().insert(album_id.clone(), album);
+                    expanded_albums.write().insert(album_id);
+                }
+                Err(e) => search_error.set(Some(api_error(&e))),
+            }
+            loading.set(false);
+            return;
+        }
 
         let provider = Some(settings.default_provider());
+        let (identifier, query) = match IdentifierKind::detect(&input) {
+            Some((kind, value)) => (Some(kind), value),
+            None => (None, search()),
+        };
 
         let query_data = api::SearchQuery {
             artist: artist(),
-            query: search(),
+            query,
             provider,
+            identifier,
         };
 
         let result = match search_type() {
-            SearchType::Album => auth.call(api::search_album(query_data)).await,
-            SearchType::Track => auth.call(api::search_track(query_data)).await,
+            SearchType::Album => auth.call(|| api::search_album(query_data.clone())).await,
+            SearchType::Track => auth.call(|| api::search_track(query_data.clone())).await,
         };
 
-        if let Ok(data) = result {
-            search_results.set(Some(data));
+        match result {
+            Ok(data) => search_results.set(Some(data)),
+            Err(e) => search_error.set(Some(api_error(&e))),
         }
         loading.set(false);
     };
 
+    // Leaving the page forgets the search, unless the session running out
+    // is what took the user away
+    use_drop(move || {
+        if auth.is_logged_in() {
+            auth.remember_search(None, String::new());
+        }
+    });
+
     use_effect(move || {
         if let Some(mut prefill) = search_prefill {
             if let Some((prefill_artist, prefill_query)) = (prefill.0)() {
-                artist.set(Some(prefill_artist));
+                artist.set(Some(prefill_artist).filter(|a| !a.is_empty()));
                 search.set(prefill_query);
                 (prefill.0).set(None);
                 spawn(perform_search());
@@ -489,6 +755,29 @@ pub fn Search() -> Element {
         }
     });
 
+    use_effect(move || {
+        if let Some(mut signal) = complete_album {
+            if let Some(completion) = (signal.0)() {
+                (signal.0).set(None);
+                completing_album.set(Some(AlbumMerge {
+                    path: completion.path,
+                    tracklist: completion.tracklist,
+                }));
+                let query = DownloadQuery::new(completion.missing).album(completion.album);
+                spawn(download(query));
+            }
+        }
+    });
+
+    use_effect(move || {
+        if let Some(mut signal) = rerun_search {
+            if let Some(query) = (signal.0)() {
+                (signal.0).set(None);
+                spawn(download(query));
+            }
+        }
+    });
+
     let mut toggle_expand = move |album_id: String, provider: Provider| {
         let is_expanded = expanded_albums.read().contains(&album_id);
         if is_expanded {
@@ -500,10 +789,12 @@ pub fn Search() -> Element {
                 let aid = album_id.clone();
                 spawn(async move {
                     match auth
-                        .call(api::find_album(api::AlbumQuery {
-                            id: aid.clone(),
-                            provider: Some(provider),
-                        }))
+                        .call(|| {
+                            api::find_album(api::AlbumQuery {
+                                id: aid.clone(),
+                                provider: Some(provider),
+                            })
+                        })
                         .await
                     {
                         Ok(album_data) => {
@@ -584,7 +875,7 @@ pub fn Search() -> Element {
               "type": "text",
               value: "{search}",
               class: "w-2/3 bg-transparent border-none focus:ring-0 text-white text-sm placeholder-gray-600 font-mono h-10 focus:outline-none",
-              placeholder: "Search artist, album or track...",
+              placeholder: "Search, paste a release link, or barcode/ISRC...",
               oninput: move |event| search.set(event.value()),
               onkeydown: move |event| {
                   if event.key() == Key::Enter {
@@ -619,6 +910,12 @@ pub fn Search() -> Element {
                 }
               }
               SearchTypeToggle { search_type }
+              MatchModeSelect { match_mode }
+              SearchTimeoutSelect { search_timeout }
+              SearchStrategySelect { search_strategy }
+              if features.is_enabled(flags::DRY_RUN) {
+                SimulateToggle { simulate }
+              }
               Button {
                 class: "rounded ml-2 whitespace-nowrap",
                 disabled: loading() || search.read().is_empty(),
@@ -663,8 +960,23 @@ pub fn Search() -> Element {
 
         SystemStatus { health: system_health.get(), navidrome_status: auth.navidrome_status() }
 
+        if let Some(error) = search_error() {
+          ErrorNotice {
+            error,
+            on_retry: move |_| {
+                spawn(perform_search());
+            },
+          }
+        }
+
         // Results
-        if let Some(results) = download_options.read().clone() {
+        if let Some(results) = ranked_options() {
+          if let Some(merge) = completing_album() {
+            p { class: "mb-2 text-xs font-mono text-gray-400 text-center",
+              "Completing an album: the tracks you pick are added to {merge.path}"
+            }
+          }
+          RankingSliders { weights: ranking }
           DownloadResults {
             results,
             is_searching: loading(),
@@ -674,8 +986,25 @@ pub fn Search() -> Element {
             },
             on_back: move |_| {
                 download_options.set(None);
+                completing_album.set(None);
                 loading.set(false);
+                if let Some(session_id) = shared_session.take() {
+                    session_participants.set(Vec::new());
+                    spawn(async move {
+                        let _ = auth.call(|| api::leave_search_session(session_id.clone())).await;
+                    });
+                }
             },
+            selected_tracks: shared_selection,
+            on_selection_change: sync_selection,
+            on_share: share_search,
+            share_link: share_link.clone(),
+            participants: session_others.clone(),
+            expected_tracks: expected_tracks(),
+            searched_artist: searched_release().0,
+            searched_album: searched_release().1,
+            routing,
+            suggestion: suggested_folder,
           }
         } else if loading() {
           div { class: "flex flex-col justify-center items-center py-10",
@@ -701,7 +1030,13 @@ pub fn Search() -> Element {
                                 let track_id = track.id.clone();
                                 let dl_state = download_states.read().get(&track_id).cloned().unwrap_or_default();
                                 let has_folders = !folders.read().is_empty();
-                                let effective_state = if !has_folders { DownloadRowState::Disabled } else { dl_state };
+                                let effective_state = if auth.is_viewer() {
+                                    DownloadRowState::ReadOnly
+                                } else if !has_folders {
+                                    DownloadRowState::Disabled
+                                } else {
+                                    dl_state
+                                };
                                 let current_folders = folders.read().clone();
                                 let current_folder_id = selected_folder_id();
                                 rsx! {
@@ -744,7 +1079,13 @@ pub fn Search() -> Element {
                                 let album_id = album.id.clone();
                                 let dl_state = download_states.read().get(&album_id).cloned().unwrap_or_default();
                                 let has_folders = !folders.read().is_empty();
-                                let effective_state = if !has_folders { DownloadRowState::Disabled } else { dl_state };
+                                let effective_state = if auth.is_viewer() {
+                                    DownloadRowState::ReadOnly
+                                } else if !has_folders {
+                                    DownloadRowState::Disabled
+                                } else {
+                                    dl_state
+                                };
                                 let current_folders = folders.read().clone();
                                 let current_folder_id = selected_folder_id();
 
@@ -776,6 +1117,7 @@ pub fn Search() -> Element {
                                                   release_date: album_for_search.release_date.clone(),
                                                   mbid: album_for_search.mbid.clone(),
                                                   cover_url: album_for_search.cover_url.clone(),
+                                                  details: album_for_search.details.clone(),
                                               });
                                               spawn(download(query));
                                           }
@@ -793,6 +1135,7 @@ pub fn Search() -> Element {
                                               release_date: album_for_dl.release_date.clone(),
                                               mbid: album_for_dl.mbid.clone(),
                                               cover_url: album_for_dl.cover_url.clone(),
+                                              details: album_for_dl.details.clone(),
                                           });
                                           handle_auto_download(album_for_dl.id.clone(), query);
                                       },
@@ -804,6 +1147,7 @@ pub fn Search() -> Element {
                                               release_date: album_for_override.release_date.clone(),
                                               mbid: album_for_override.mbid.clone(),
                                               cover_url: album_for_override.cover_url.clone(),
+                                              details: album_for_override.details.clone(),
                                           });
                                           handle_override_download(album_for_override.id.clone(), query, folder);
                                       },
@@ -0,0 +1,88 @@
+use dioxus::prelude::*;
+use shared::quality::RankingWeights;
+
+use crate::friendly_error;
+use crate::settings_context::use_settings;
+
+/// Sliders weighting quality, completeness and peer speed. Results on
+/// screen are re-ranked as they move; saving stores the weights in the
+/// user's quality profile as the starting point for later searches.
+#[component]
+pub fn RankingSliders(weights: Signal<RankingWeights>) -> Element {
+    let mut settings = use_settings();
+    let mut status = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+
+    let saved = settings.quality_profile().ranking;
+    let is_saved = weights() == saved;
+
+    let save = move |_| async move {
+        saving.set(true);
+        let mut profile = settings.quality_profile();
+        profile.ranking = weights();
+        let update = api::UpdateUserSettings {
+            quality_profile: Some(profile.to_json()),
+            ..Default::default()
+        };
+        match settings.update(update).await {
+            Ok(_) => status.set(String::new()),
+            Err(e) => status.set(friendly_error(&e)),
+        }
+        saving.set(false);
+    };
+
+    let sliders: [(&str, f64, fn(&mut RankingWeights) -> &mut f64); 3] = [
+        ("Quality", weights().quality, |w| &mut w.quality),
+        ("Completeness", weights().completeness, |w| {
+            &mut w.completeness
+        }),
+        ("Speed", weights().speed, |w| &mut w.speed),
+    ];
+
+    rsx! {
+      div { class: "flex flex-wrap items-center gap-x-6 gap-y-2 mb-4 px-4 py-3 bg-beet-panel border border-white/10 rounded-lg font-mono text-xs text-gray-400",
+        span { class: "uppercase tracking-wider text-gray-500", "Rank by" }
+        for (name , value , field) in sliders {
+          label { key: "{name}", class: "flex items-center gap-2",
+            "{name}"
+            input {
+              r#type: "range",
+              class: "w-24 accent-beet-accent",
+              min: "0",
+              max: "100",
+              value: (value * 100.0).round().to_string(),
+              oninput: move |e| {
+                  if let Ok(percent) = e.value().parse::<f64>() {
+                      *field(&mut weights.write()) = percent / 100.0;
+                  }
+              },
+            }
+          }
+        }
+        if !weights().is_neutral() {
+          button {
+            class: "text-gray-500 hover:text-gray-300",
+            title: "Back to the search's own order",
+            onclick: move |_| weights.set(RankingWeights::default()),
+            "Reset"
+          }
+        }
+        if !is_saved {
+          button {
+            class: "text-beet-accent hover:text-white disabled:opacity-50",
+            disabled: saving(),
+            title: "Use these weights for future searches",
+            onclick: save,
+            if saving() {
+              "Saving..."
+            } else {
+              "Save to profile"
+            }
+          }
+        }
+        if !status().is_empty() {
+          span { class: "text-red-400", "{status}" }
+        }
+      }
+    }
+}
@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+use shared::download::SearchStrategy;
+
+/// Per-search choice of how the Soulseek search text is shaped.
+#[component]
+pub fn SearchStrategySelect(search_strategy: Signal<SearchStrategy>) -> Element {
+    rsx! {
+      select {
+        class: "bg-black/20 rounded p-1 mr-2 text-xs font-bold text-gray-400 border-none focus:outline-none focus:ring-0 cursor-pointer",
+        title: "How the search text is shaped",
+        onchange: move |e| {
+            search_strategy.set(SearchStrategy::from_setting(&e.value()).unwrap_or_default())
+        },
+        for strategy in SearchStrategy::ALL {
+          option {
+            value: "{strategy.to_setting()}",
+            selected: search_strategy() == strategy,
+            "{strategy.label().to_uppercase()}"
+          }
+        }
+      }
+    }
+}
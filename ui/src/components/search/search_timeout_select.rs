@@ -0,0 +1,23 @@
+use dioxus::prelude::*;
+use shared::download::SEARCH_TIMEOUT_CHOICES;
+
+/// Per-search override of how long Soulseek is searched. `None` uses the
+/// user's setting.
+#[component]
+pub fn SearchTimeoutSelect(search_timeout: Signal<Option<u32>>) -> Element {
+    rsx! {
+      select {
+        class: "bg-black/20 rounded p-1 mr-2 text-xs font-bold text-gray-400 border-none focus:outline-none focus:ring-0 cursor-pointer",
+        title: "How long to wait for Soulseek results",
+        onchange: move |e| search_timeout.set(e.value().parse().ok()),
+        option { value: "", selected: search_timeout().is_none(), "WAIT" }
+        for secs in SEARCH_TIMEOUT_CHOICES {
+          option {
+            value: "{secs}",
+            selected: search_timeout() == Some(secs),
+            "{secs}S"
+          }
+        }
+      }
+    }
+}
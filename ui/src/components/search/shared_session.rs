@@ -0,0 +1,148 @@
+use dioxus::logger::tracing::{info, warn};
+use dioxus::prelude::*;
+use shared::download::{
+    DownloadableGroup, DownloadableItem, ImportOverrides, SearchState as DownloadSearchState,
+    SharedSearchEvent,
+};
+use std::collections::HashSet;
+
+use super::download_results::DownloadResults;
+use super::SharedSearchSignal;
+use crate::{friendly_error, use_auth};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct SharedSearchProps {
+    pub session_id: String,
+    #[props(into)]
+    pub on_leave: EventHandler<()>,
+}
+
+/// Download results of a search someone else shared. Results stream in from
+/// the owner's search and picks stay in sync between all participants.
+#[component]
+pub fn SharedSearch(props: SharedSearchProps) -> Element {
+    let auth = use_auth();
+    let session_id = use_signal(|| props.session_id.clone());
+    let mut groups = use_signal(Vec::<DownloadableGroup>::new);
+    let mut state = use_signal(|| DownloadSearchState::InProgress);
+    let mut selected = use_signal(HashSet::<String>::new);
+    let mut participants = use_signal(Vec::<String>::new);
+    let mut owner = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut closed = use_signal(|| false);
+    let mut is_downloading = use_signal(|| false);
+
+    use_future(move || async move {
+        match auth.call(|| api::join_search_session(session_id())).await {
+            Ok(snapshot) => {
+                groups.set(snapshot.groups);
+                state.set(snapshot.state);
+                selected.set(snapshot.selected.into_iter().collect());
+                participants.set(snapshot.participants);
+                owner.set(snapshot.owner);
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let shared_events = try_use_context::<SharedSearchSignal>();
+    use_effect(move || {
+        let Some(mut ctx) = shared_events else { return };
+        let Some(event) = (ctx.0)() else { return };
+        if event.session_id() != session_id.peek().as_str() {
+            return;
+        }
+        (ctx.0).set(None);
+        match event {
+            SharedSearchEvent::Results {
+                groups: new_groups,
+                state: new_state,
+                ..
+            } => {
+                groups.set(new_groups);
+                state.set(new_state);
+            }
+            SharedSearchEvent::Selection { selected: picks, by, .. } => {
+                info!("{} updated the shared selection", by);
+                selected.set(picks.into_iter().collect());
+            }
+            SharedSearchEvent::Participants { usernames, .. } => participants.set(usernames),
+            SharedSearchEvent::Closed { .. } => closed.set(true),
+        }
+    });
+
+    let sync_selection = move |picks: Vec<String>| {
+        spawn(async move {
+            let _ = auth
+                .call(|| api::update_session_selection(session_id(), picks.clone()))
+                .await;
+        });
+    };
+
+    let download_tracks = move |(items, folder_id, import_overrides): (
+        Vec<DownloadableItem>,
+        String,
+        ImportOverrides,
+    )| async move {
+        match auth
+            .call(|| {
+                api::download(api::DownloadRequest {
+                    items: items.clone(),
+                    folder_id: folder_id.clone(),
+                    backend: None,
+                    import_overrides: import_overrides.clone(),
+                    simulate: false,
+                })
+            })
+            .await
+        {
+            Ok(_) => info!("Downloads started from shared search"),
+            Err(e) => warn!("Failed to start downloads: {:?}", e),
+        }
+        is_downloading.set(false);
+    };
+
+    let on_leave = props.on_leave;
+    let leave = move |_| {
+        spawn(async move {
+            let _ = auth.call(|| api::leave_search_session(session_id())).await;
+        });
+        on_leave.call(());
+    };
+
+    if !error().is_empty() || closed() {
+        let message = if closed() {
+            format!("{} ended this shared search.", owner())
+        } else {
+            error()
+        };
+        return rsx! {
+            div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl max-w-2xl mx-auto my-10 text-center space-y-4",
+                p { class: "text-gray-300 font-mono text-sm", "{message}" }
+                button { class: "retro-btn rounded text-sm", onclick: move |_| on_leave.call(()), "Back to search" }
+            }
+        };
+    }
+
+    let me = auth.username();
+    let mut with = participants();
+    with.retain(|p| Some(p) != me.as_ref());
+
+    rsx! {
+        p { class: "text-center text-xs font-mono text-gray-400 mt-6",
+            "Shared by {owner}"
+        }
+        DownloadResults {
+            results: groups(),
+            is_searching: state() == DownloadSearchState::InProgress && groups.read().is_empty(),
+            is_downloading,
+            on_download: move |data| {
+                spawn(download_tracks(data));
+            },
+            on_back: leave,
+            selected_tracks: selected,
+            on_selection_change: sync_selection,
+            participants: with,
+        }
+    }
+}
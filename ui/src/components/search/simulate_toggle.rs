@@ -0,0 +1,21 @@
+use dioxus::prelude::*;
+
+/// Switches downloads started from this page to dry runs, which report
+/// what would be downloaded and where without queueing anything.
+#[component]
+pub fn SimulateToggle(simulate: Signal<bool>) -> Element {
+    let class = if simulate() {
+        "text-purple-300 bg-purple-500/20 shadow-sm"
+    } else {
+        "text-gray-500 hover:text-gray-300 hover:bg-white/5"
+    };
+
+    rsx! {
+      button {
+        class: "px-3 py-1 mr-2 text-xs font-bold rounded transition-all duration-200 {class}",
+        title: "Dry run: search and pick sources, but never download or import",
+        onclick: move |_| simulate.toggle(),
+        "SIM"
+      }
+    }
+}
@@ -2,7 +2,8 @@ use api::models::folder::Folder;
 use dioxus::prelude::*;
 use shared::metadata::Track;
 
-use super::download_icon::{DownloadIcon, DownloadRowState};
+use super::download_icon::{DownloadIcon, DownloadRowState, READ_ONLY_HINT};
+use crate::release_art_url;
 
 #[derive(Props, PartialEq, Clone)]
 pub struct Props {
@@ -22,11 +23,25 @@ pub struct Props {
 #[component]
 pub fn TrackResult(props: Props) -> Element {
     let track = props.track.clone();
+    let read_only = props.download_state == DownloadRowState::ReadOnly;
     let mut cover_error = use_signal(|| false);
+    // Year and details of the album, to tell same-named releases apart
+    let release_line = [
+        track
+            .release_date
+            .as_deref()
+            .map(|d| d.chars().take(4).collect::<String>()),
+        Some(track.details.summary()).filter(|s| !s.is_empty()),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" · ");
 
-    let cover_url = track.release_mbid.as_ref().map(|mbid| {
-        format!("https://coverartarchive.org/release/{}/front-250", mbid)
-    });
+    let cover_url = track
+        .release_mbid
+        .as_deref()
+        .map(|mbid| release_art_url(mbid, 250));
 
     rsx! {
       div {
@@ -72,6 +87,11 @@ pub fn TrackResult(props: Props) -> Element {
               "{album_title}"
             }
           }
+          if !release_line.is_empty() {
+            p { class: "text-xs text-gray-600 font-mono truncate", title: "{release_line}",
+              "{release_line}"
+            }
+          }
         }
 
         // Duration
@@ -85,8 +105,9 @@ pub fn TrackResult(props: Props) -> Element {
         div { class: "flex items-center gap-1 shrink-0",
           // Search sources button
           button {
-            class: "p-2 rounded-full hover:bg-white/10 transition-colors cursor-pointer group/src",
-            title: "Search sources",
+            class: "p-2 rounded-full hover:bg-white/10 transition-colors cursor-pointer group/src disabled:opacity-40 disabled:cursor-not-allowed",
+            title: if read_only { READ_ONLY_HINT } else { "Search sources" },
+            disabled: read_only,
             onclick: move |evt: MouseEvent| {
               evt.stop_propagation();
               props.on_search_sources.call(());
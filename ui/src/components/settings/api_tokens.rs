@@ -0,0 +1,152 @@
+use api::models::api_token::ApiToken;
+use api::{create_api_token, delete_api_token, get_api_tokens};
+use dioxus::prelude::*;
+
+use crate::auth::use_auth;
+use crate::{friendly_error, ConfirmModal};
+
+fn format_date(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Create and revoke the current user's API tokens, used by integrations
+/// such as the `/api/events` activity stream.
+#[component]
+pub fn ApiTokenManager() -> Element {
+    let auth = use_auth();
+    let mut tokens = use_signal(Vec::<ApiToken>::new);
+    let mut new_name = use_signal(String::new);
+    // Plaintext of the token just created, shown once
+    let mut new_secret = use_signal(|| None::<String>);
+    let mut pending_delete = use_signal(|| None::<ApiToken>);
+    let mut error = use_signal(String::new);
+
+    let fetch_tokens = move || async move {
+        match auth.call(get_api_tokens).await {
+            Ok(list) => tokens.set(list),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    use_future(move || async move {
+        fetch_tokens().await;
+    });
+
+    let handle_create = move |_| async move {
+        error.set(String::new());
+        if new_name().trim().is_empty() {
+            error.set("Give the token a name".to_string());
+            return;
+        }
+        match auth.call(|| create_api_token(new_name())).await {
+            Ok(created) => {
+                new_secret.set(Some(created.secret));
+                new_name.set(String::new());
+                fetch_tokens().await;
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let handle_delete = move |id: String| async move {
+        match auth.call(|| delete_api_token(id.clone())).await {
+            Ok(()) => fetch_tokens().await,
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "API Tokens" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "For dashboards and automations. Send as "
+                code { class: "text-beet-leaf", "Authorization: Bearer <token>" }
+                "."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            if let Some(secret) = new_secret() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded font-mono text-sm space-y-2",
+                    p { class: "text-green-400", "Copy this token now, it won't be shown again:" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 text-white font-mono text-xs",
+                        readonly: true,
+                        value: "{secret}",
+                    }
+                    button {
+                        class: "text-xs uppercase tracking-wider font-bold text-gray-500 hover:text-white transition-colors cursor-pointer",
+                        onclick: move |_| new_secret.set(None),
+                        "[ Done ]"
+                    }
+                }
+            }
+
+            div { class: "flex gap-2 mb-6",
+                input {
+                    class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                    value: "{new_name}",
+                    oninput: move |e| new_name.set(e.value()),
+                    placeholder: "Token name, e.g. Home Assistant",
+                    "type": "text",
+                }
+                button { class: "retro-btn rounded", onclick: handle_create, "Create" }
+            }
+
+            if tokens.read().is_empty() {
+                p { class: "text-gray-500 font-mono italic text-sm", "No API tokens." }
+            } else {
+                ul { class: "space-y-2",
+                    for token in tokens.read().iter().cloned() {
+                        {
+                            let created = format_date(token.created_at);
+                            let used = token
+                                .last_used_at
+                                .map(|ts| format!("last used {}", format_date(ts)))
+                                .unwrap_or_else(|| "never used".to_string());
+                            let to_revoke = token.clone();
+                            rsx! {
+                                li {
+                                    key: "{token.id}",
+                                    class: "flex justify-between items-center bg-white/5 border border-white/5 p-3 rounded",
+                                    div {
+                                        div { class: "font-bold text-white font-display", "{token.name}" }
+                                        div { class: "text-xs font-mono text-gray-500", "Created {created} · {used}" }
+                                    }
+                                    button {
+                                        class: "text-xs uppercase tracking-wider font-bold text-red-400 hover:text-red-300 transition-colors cursor-pointer",
+                                        onclick: move |_| pending_delete.set(Some(to_revoke.clone())),
+                                        "[ Revoke ]"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+
+            if let Some(token) = pending_delete() {
+                ConfirmModal {
+                    message: format!(
+                        "Revoke '{}'? Anything using it will stop working.",
+                        token.name,
+                    ),
+                    confirm_label: "Revoke".to_string(),
+                    danger: true,
+                    on_confirm: move |_| {
+                        let id = token.id.clone();
+                        pending_delete.set(None);
+                        spawn(handle_delete(id));
+                    },
+                    on_cancel: move |_| pending_delete.set(None),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use api::{get_monitor_failures, get_panic_reports};
+use dioxus::prelude::*;
+use shared::system::{MonitorFailure, PanicReport};
+
+use crate::friendly_error;
+
+fn format_at(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|d| {
+            d.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+#[component]
+fn PanicRow(report: PanicReport) -> Element {
+    rsx! {
+        details { class: "bg-white/5 border border-white/5 rounded p-2 text-xs font-mono",
+            summary { class: "flex items-center gap-2 cursor-pointer min-w-0",
+                span { class: "text-gray-500 shrink-0", {format_at(report.at)} }
+                span { class: "text-red-400 truncate flex-1", title: "{report.message}", "{report.message}" }
+            }
+            div { class: "mt-2 space-y-1 text-gray-400",
+                p { class: "break-all", "At {report.location} on thread {report.thread}" }
+                if let Some(context) = &report.context {
+                    p { class: "break-all", "While: {context}" }
+                }
+                if let Some(backtrace) = &report.backtrace {
+                    pre { class: "p-2 bg-beet-dark rounded text-gray-300 whitespace-pre-wrap break-all max-h-64 overflow-y-auto",
+                        "{backtrace}"
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MonitorFailureRow(failure: MonitorFailure) -> Element {
+    rsx! {
+        div { class: "p-2 bg-red-900/20 border border-red-500/30 rounded text-xs font-mono text-red-300",
+            span { class: "text-gray-500 mr-2", {format_at(failure.at)} }
+            "Gave up on {failure.label} for {failure.username}: {failure.reason} again after {failure.restarts} restarts, {failure.failed} download(s) failed"
+        }
+    }
+}
+
+/// Panics the server caught since it started, and the download monitors
+/// that kept dying.
+#[component]
+pub fn CrashReportsPanel() -> Element {
+    let mut reports = use_resource(move || async move { get_panic_reports().await });
+    let mut failures = use_resource(move || async move { get_monitor_failures().await });
+
+    let list = match &*reports.read() {
+        None => rsx! {},
+        Some(Err(e)) => rsx! {
+            p { class: "text-xs font-mono text-red-400", {friendly_error(e)} }
+        },
+        Some(Ok(list)) if list.is_empty() => rsx! {
+            p { class: "text-sm font-mono text-gray-500 italic", "No crash since the server started." }
+        },
+        Some(Ok(list)) => rsx! {
+            div { class: "space-y-1 max-h-96 overflow-y-auto",
+                for (i, report) in list.iter().cloned().enumerate() {
+                    PanicRow { key: "{i}", report }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            div { class: "flex items-center justify-between mb-1",
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Crash Reports" }
+                button {
+                    class: "text-xs font-mono text-gray-400 hover:text-white cursor-pointer",
+                    onclick: move |_| {
+                        reports.restart();
+                        failures.restart();
+                    },
+                    "Refresh"
+                }
+            }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Errors the server recovered from. A download monitor caught in one is restarted; after three restarts its downloads are marked failed and can be retried. Set RUST_BACKTRACE=1 to capture backtraces."
+            }
+            if let Some(Ok(failed)) = &*failures.read() {
+                if !failed.is_empty() {
+                    div { class: "space-y-1 mb-4",
+                        for (i, failure) in failed.iter().cloned().enumerate() {
+                            MonitorFailureRow { key: "{i}", failure }
+                        }
+                    }
+                }
+            }
+            {list}
+        }
+    }
+}
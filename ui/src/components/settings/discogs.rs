@@ -0,0 +1,148 @@
+use dioxus::prelude::*;
+
+use crate::settings_context::use_settings;
+use crate::{friendly_error, Checkbox};
+
+/// Link to a Discogs account: imported albums go to its collection and its
+/// wantlist can be copied to the wishlist.
+#[component]
+pub fn DiscogsManager() -> Element {
+    let mut settings = use_settings();
+    let mut token = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    let current = settings.get().unwrap_or_default();
+    let username = current
+        .discogs_username
+        .clone()
+        .filter(|_| current.discogs_token.is_some());
+    let sync_collection = current.discogs_sync_collection;
+
+    let refresh = move || async move {
+        if let Ok(s) = api::get_user_settings().await {
+            settings.set(s);
+        }
+    };
+
+    let handle_connect = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        busy.set(true);
+        match api::connect_discogs(token()).await {
+            Ok(name) => {
+                token.set(String::new());
+                success_msg.set(format!("Connected as {}", name));
+                refresh().await;
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let handle_disconnect = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        match api::disconnect_discogs().await {
+            Ok(()) => refresh().await,
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let handle_wantlist = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        busy.set(true);
+        match api::import_discogs_wantlist().await {
+            Ok(0) => {
+                success_msg.set("The wishlist already has everything on the wantlist".to_string())
+            }
+            Ok(added) => success_msg.set(format!("Added {} album(s) to the wishlist", added)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let toggle_sync = move |_| async move {
+        let update = api::UpdateUserSettings {
+            discogs_sync_collection: Some(!sync_collection),
+            ..Default::default()
+        };
+        if let Err(e) = settings.update(update).await {
+            error.set(friendly_error(&e));
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Discogs" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Mark imported albums as owned in your Discogs collection and pull your wantlist into the wishlist."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            if let Some(name) = username {
+                div { class: "flex items-center justify-between mb-4",
+                    p { class: "text-sm font-mono text-gray-300",
+                        "Connected as "
+                        span { class: "text-beet-leaf", "{name}" }
+                    }
+                    button { class: "text-xs font-mono text-red-400 hover:underline", onclick: handle_disconnect,
+                        "Disconnect"
+                    }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer mb-4",
+                    onclick: toggle_sync,
+                    Checkbox { is_selected: sync_collection }
+                    span { class: "text-sm text-gray-300 font-mono", "Add imported albums to my collection" }
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: busy(),
+                    onclick: handle_wantlist,
+                    if busy() { "Importing..." } else { "Import Wantlist" }
+                }
+            } else {
+                div { class: "mb-4",
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Personal Access Token"
+                    }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                        value: "{token}",
+                        oninput: move |e| token.set(e.value()),
+                        placeholder: "Discogs token",
+                        "type": "password",
+                    }
+                    p { class: "text-xs text-gray-500 font-mono mt-1",
+                        "Generate one under "
+                        a {
+                            href: "https://www.discogs.com/settings/developers",
+                            target: "_blank",
+                            class: "text-beet-accent hover:underline",
+                            "Discogs developer settings"
+                        }
+                    }
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: busy() || token().trim().is_empty(),
+                    onclick: handle_connect,
+                    if busy() { "Connecting..." } else { "Connect" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use api::{get_download_pause, set_download_pause};
+use dioxus::prelude::*;
+use shared::system::DownloadPause;
+
+use crate::{friendly_error, Checkbox};
+
+/// Instance-wide switch holding back all downloading, for every user.
+#[component]
+pub fn DownloadPauseManager() -> Element {
+    let mut pause = use_signal(DownloadPause::default);
+    let mut cancel_active = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    use_future(move || async move {
+        match get_download_pause().await {
+            Ok(p) => pause.set(p),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let handle_toggle = move |_| async move {
+        error.set(String::new());
+        busy.set(true);
+        let paused = !pause().paused;
+        match set_download_pause(paused, paused && cancel_active()).await {
+            Ok(p) => {
+                pause.set(p);
+                cancel_active.set(false);
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let status = match (pause().paused, pause().deferred) {
+        (false, _) => "Downloading normally.".to_string(),
+        (true, 0) => "Paused. New downloads wait until resumed.".to_string(),
+        (true, n) => format!("Paused. {} download(s) wait until resumed.", n),
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Pause Downloading" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Hold back every user's downloads, e.g. during a backup or when the connection is needed for something else. Downloads asked for meanwhile are queued on resume; held ones are lost if the server restarts."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            p { class: "text-sm font-mono text-gray-300 mb-4", "{status}" }
+            if !pause().paused {
+                div {
+                    class: "flex items-center gap-2 cursor-pointer mb-4",
+                    onclick: move |_| cancel_active.set(!cancel_active()),
+                    Checkbox { is_selected: cancel_active() }
+                    span { class: "text-sm text-gray-300 font-mono",
+                        "Also cancel the transfers running now"
+                    }
+                }
+            }
+
+            button {
+                class: "retro-btn rounded",
+                disabled: busy(),
+                onclick: handle_toggle,
+                if pause().paused { "Resume Downloading" } else { "Pause Downloading" }
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use api::set_feature_flag;
+use dioxus::prelude::*;
+use shared::features::FEATURE_FLAGS;
+
+use crate::{friendly_error, use_features, Checkbox};
+
+/// Switches for experimental subsystems. Each change is saved right away
+/// and shared with the rest of the app through the features context.
+#[component]
+pub fn FeatureFlagsManager() -> Element {
+    let mut features = use_features();
+    let mut error = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    let mut toggle = move |key: &'static str| {
+        let enabled = !features.is_enabled(key);
+        spawn(async move {
+            error.set(String::new());
+            busy.set(true);
+            match set_feature_flag(key.to_string(), enabled).await {
+                Ok(flags) => features.set(flags),
+                Err(e) => error.set(friendly_error(&e)),
+            }
+            busy.set(false);
+        });
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Experimental" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Features still being worked on. They are off until enabled here, for every user."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            div { class: "space-y-4",
+                for flag in FEATURE_FLAGS.iter() {
+                    div {
+                        key: "{flag.key}",
+                        class: if busy() { "flex items-start gap-2 opacity-50" } else { "flex items-start gap-2 cursor-pointer" },
+                        onclick: move |_| {
+                            if !busy() {
+                                toggle(flag.key);
+                            }
+                        },
+                        Checkbox { is_selected: features.is_enabled(flag.key) }
+                        div {
+                            span { class: "block text-sm text-gray-300 font-mono", "{flag.name}" }
+                            span { class: "block text-xs text-gray-500 font-mono", "{flag.description}" }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
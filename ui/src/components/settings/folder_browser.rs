@@ -0,0 +1,106 @@
+use dioxus::prelude::*;
+
+use crate::auth::use_auth;
+use crate::{friendly_error, Modal};
+
+#[derive(Props, PartialEq, Clone)]
+pub struct FolderBrowserProps {
+    /// Directory to open first. Empty starts at the allowed roots.
+    #[props(default)]
+    pub initial_path: String,
+    pub on_select: EventHandler<String>,
+    pub on_close: EventHandler,
+}
+
+/// Modal for picking a directory on the server instead of typing its path.
+#[component]
+pub fn FolderBrowser(props: FolderBrowserProps) -> Element {
+    let auth = use_auth();
+    let initial = props.initial_path.clone();
+    let mut current = use_signal(move || (!initial.is_empty()).then_some(initial));
+
+    let mut listing = use_resource(move || async move {
+        let path = current();
+        match auth.call(|| api::browse_directories(path.clone())).await {
+            // A typed path that no longer exists should not trap the user
+            Err(_) if path.is_some() => auth.call(|| api::browse_directories(None)).await,
+            other => other,
+        }
+    });
+
+    let on_select = props.on_select;
+    let on_close = props.on_close;
+
+    rsx! {
+        Modal {
+            on_close: move |_| on_close.call(()),
+            header: rsx! {
+                h2 { class: "text-lg font-bold text-white font-display", "Browse Server Folders" }
+            },
+            match &*listing.read() {
+                None => rsx! {
+                    div { class: "animate-pulse text-gray-400 font-mono text-sm", "Loading..." }
+                },
+                Some(Err(e)) => {
+                    let msg = friendly_error(e);
+                    rsx! {
+                        div { class: "text-red-400 text-sm font-mono mb-3", "{msg}" }
+                        button {
+                            class: "text-xs font-mono text-gray-400 hover:text-white underline decoration-dotted cursor-pointer",
+                            onclick: move |_| listing.restart(),
+                            "Retry"
+                        }
+                    }
+                }
+                Some(Ok(dir)) => {
+                    let selected = dir.path.clone();
+                    let parent = dir.parent.clone();
+                    let at_top = dir.path.is_none();
+                    let location = dir.path.clone().unwrap_or_else(|| "Allowed roots".to_string());
+                    let entries = dir.entries.clone();
+                    rsx! {
+                        div { class: "flex items-center gap-2 mb-3",
+                            button {
+                                class: "text-xs font-mono text-gray-400 hover:text-white transition-colors cursor-pointer disabled:opacity-40 disabled:cursor-not-allowed",
+                                disabled: at_top,
+                                onclick: move |_| current.set(parent.clone()),
+                                "[ Up ]"
+                            }
+                            span { class: "text-xs font-mono text-beet-leaf truncate", "{location}" }
+                        }
+                        if entries.is_empty() {
+                            p { class: "text-gray-500 font-mono italic text-sm mb-3", "No sub-folders." }
+                        }
+                        ul { class: "space-y-1 mb-4 max-h-72 overflow-y-auto",
+                            for entry in entries.into_iter() {
+                                li {
+                                    key: "{entry.path}",
+                                    class: "px-2 py-1.5 rounded text-sm font-mono text-gray-300 hover:bg-white/10 hover:text-white cursor-pointer truncate",
+                                    onclick: {
+                                        let path = entry.path.clone();
+                                        move |_| current.set(Some(path.clone()))
+                                    },
+                                    "\u{1F4C1} {entry.name}"
+                                }
+                            }
+                        }
+                        div { class: "flex justify-end gap-2",
+                            button {
+                                class: "px-3 py-1.5 text-sm font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
+                                onclick: move |_| on_close.call(()),
+                                "Cancel"
+                            }
+                            if let Some(path) = selected {
+                                button {
+                                    class: "retro-btn rounded text-sm",
+                                    onclick: move |_| on_select.call(path.clone()),
+                                    "Use this folder"
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
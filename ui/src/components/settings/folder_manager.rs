@@ -1,21 +1,44 @@
-use api::{create_user_folder, delete_folder, get_user_folders, update_folder};
+use api::models::folder::FOLDER_UNDO_WINDOW_SECS;
+use api::{
+    create_user_folder, delete_folder, folder_diagnostics, get_user_folders, restore_folder,
+    update_folder,
+};
 use dioxus::prelude::*;
+use shared::download::ImportMode;
+use shared::system::FolderDiagnostics;
+use std::collections::HashMap;
 
+use super::FolderBrowser;
 use crate::auth::use_auth;
-use crate::friendly_error;
+use crate::{friendly_error, Checkbox};
+
+/// Which path input the folder browser fills in.
+#[derive(Clone, Copy, PartialEq)]
+enum BrowseTarget {
+    NewFolder,
+    EditFolder,
+}
 
 #[component]
 pub fn FolderManager() -> Element {
     let mut folder_name = use_signal(|| "".to_string());
     let mut folder_path = use_signal(|| "".to_string());
     let mut folders = use_signal(Vec::new);
+    let mut diagnostics = use_signal(HashMap::<String, FolderDiagnostics>::new);
 
     let mut editing_folder_id = use_signal(|| None::<String>);
     let mut edit_folder_name = use_signal(|| "".to_string());
     let mut edit_folder_path = use_signal(|| "".to_string());
+    let mut edit_import_mode = use_signal(ImportMode::default);
+    let mut edit_remote = use_signal(String::new);
+    let mut edit_shared = use_signal(|| false);
+    let mut browse_target = use_signal(|| None::<BrowseTarget>);
 
     let mut error = use_signal(|| "".to_string());
     let mut success_msg = use_signal(|| "".to_string());
+    // (id, name) of the most recently deleted folder while it can still be restored
+    let mut undo_folder = use_signal(|| None::<(String, String)>);
+    let mut undo_seconds_left = use_signal(|| 0i64);
     let auth = use_auth();
 
     // Library settings state
@@ -110,10 +133,18 @@ pub fn FolderManager() -> Element {
     });
 
     let fetch_folders = move || async move {
-        match auth.call(get_user_folders()).await {
+        match auth.call(get_user_folders).await {
             Ok(fetched_folders) => folders.set(fetched_folders),
             Err(e) => error.set(friendly_error(&e)),
         }
+        if let Ok(diags) = auth.call(folder_diagnostics).await {
+            diagnostics.set(
+                diags
+                    .into_iter()
+                    .map(|d| (d.folder_id.clone(), d))
+                    .collect(),
+            );
+        }
     };
 
     use_future(move || async move {
@@ -130,7 +161,7 @@ pub fn FolderManager() -> Element {
         }
 
         match auth
-            .call(create_user_folder(folder_name(), folder_path()))
+            .call(|| create_user_folder(folder_name(), folder_path()))
             .await
         {
             Ok(_) => {
@@ -143,10 +174,41 @@ pub fn FolderManager() -> Element {
         }
     };
 
-    let handle_delete_folder = move |id: String| async move {
-        match auth.call(delete_folder(id)).await {
+    let handle_delete_folder = move |id: String, name: String| async move {
+        error.set("".to_string());
+        success_msg.set("".to_string());
+        match auth.call(|| delete_folder(id.clone())).await {
+            Ok(_) => {
+                undo_folder.set(Some((id.clone(), name)));
+                undo_seconds_left.set(FOLDER_UNDO_WINDOW_SECS);
+                fetch_folders().await;
+
+                // Count down the undo window; a newer delete takes over the toast.
+                loop {
+                    gloo_timers::future::TimeoutFuture::new(1000).await;
+                    if undo_folder().map(|(pending, _)| pending) != Some(id.clone()) {
+                        break;
+                    }
+                    let left = undo_seconds_left() - 1;
+                    if left <= 0 {
+                        undo_folder.set(None);
+                        break;
+                    }
+                    undo_seconds_left.set(left);
+                }
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let handle_undo_delete = move |_| async move {
+        let Some((id, name)) = undo_folder() else {
+            return;
+        };
+        undo_folder.set(None);
+        match auth.call(|| restore_folder(id.clone())).await {
             Ok(_) => {
-                success_msg.set("Folder deleted successfully".to_string());
+                success_msg.set(format!("Folder '{name}' restored"));
                 fetch_folders().await;
             }
             Err(e) => error.set(friendly_error(&e)),
@@ -155,7 +217,16 @@ pub fn FolderManager() -> Element {
 
     let handle_update_folder = move |id: String| async move {
         match auth
-            .call(update_folder(id, edit_folder_name(), edit_folder_path()))
+            .call(|| {
+                update_folder(
+                    id.clone(),
+                    edit_folder_name(),
+                    edit_folder_path(),
+                    edit_import_mode(),
+                    Some(edit_remote()),
+                    edit_shared(),
+                )
+            })
             .await
         {
             Ok(_) => {
@@ -201,12 +272,19 @@ pub fn FolderManager() -> Element {
                     label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
                         "Folder Path"
                     }
-                    input {
-                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
-                        value: "{folder_path}",
-                        oninput: move |e| folder_path.set(e.value()),
-                        placeholder: "/home/user/Music",
-                        "type": "text",
+                    div { class: "flex gap-2",
+                        input {
+                            class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            value: "{folder_path}",
+                            oninput: move |e| folder_path.set(e.value()),
+                            placeholder: "/home/user/Music",
+                            "type": "text",
+                        }
+                        button {
+                            class: "px-3 text-xs font-mono uppercase tracking-wider border border-white/10 rounded text-gray-400 hover:text-white hover:border-beet-accent/50 transition-colors cursor-pointer",
+                            onclick: move |_| browse_target.set(Some(BrowseTarget::NewFolder)),
+                            "Browse"
+                        }
                     }
                 }
             }
@@ -229,7 +307,10 @@ pub fn FolderManager() -> Element {
                             .map(|folder| {
                                 let id_edit = folder.id.clone();
                                 let id_delete = folder.id.clone();
+                                let name_delete = folder.name.clone();
+                                let diag = diagnostics.read().get(&folder.id).cloned();
                                 let id_update = folder.id.clone();
+                                let owned = folder.is_owned_by(&auth.user_id().unwrap_or_default());
                                 rsx! {
                                     li { class: "bg-white/5 border border-white/5 p-3 rounded hover:border-beet-accent/30 transition-colors",
                                         if editing_folder_id() == Some(folder.id.clone()) {
@@ -240,11 +321,59 @@ pub fn FolderManager() -> Element {
                                                     oninput: move |e| edit_folder_name.set(e.value()),
                                                     placeholder: "Name",
                                                 }
+                                                div { class: "flex gap-2",
+                                                    input {
+                                                        class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent text-white font-mono text-sm",
+                                                        value: "{edit_folder_path}",
+                                                        oninput: move |e| edit_folder_path.set(e.value()),
+                                                        placeholder: "Path",
+                                                    }
+                                                    button {
+                                                        class: "px-3 text-xs font-mono uppercase tracking-wider border border-white/10 rounded text-gray-400 hover:text-white hover:border-beet-accent/50 transition-colors cursor-pointer",
+                                                        onclick: move |_| browse_target.set(Some(BrowseTarget::EditFolder)),
+                                                        "Browse"
+                                                    }
+                                                }
+                                                div { class: "flex items-center gap-2",
+                                                    label { class: "text-xs font-mono text-gray-400 uppercase tracking-wider", "Import" }
+                                                    select {
+                                                        class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent text-white font-mono text-sm",
+                                                        onchange: move |e| edit_import_mode.set(ImportMode::from(e.value().as_str())),
+                                                        for mode in ImportMode::ALL {
+                                                            option {
+                                                                value: mode.as_str(),
+                                                                selected: edit_import_mode() == mode,
+                                                                {mode.label()}
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                if edit_import_mode().keeps_download() {
+                                                    p { class: "text-xs font-mono text-gray-500",
+                                                        "Links files into this folder and keeps the download. The download directory must be on the same filesystem; reflinks also need btrfs or xfs. Falls back to a normal import otherwise."
+                                                    }
+                                                }
                                                 input {
                                                     class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent text-white font-mono text-sm",
-                                                    value: "{edit_folder_path}",
-                                                    oninput: move |e| edit_folder_path.set(e.value()),
-                                                    placeholder: "Path",
+                                                    value: "{edit_remote}",
+                                                    oninput: move |e| edit_remote.set(e.value()),
+                                                    placeholder: "Remote target (optional): sftp://user@host/music or rclone-remote:music",
+                                                }
+                                                if !edit_remote().trim().is_empty() {
+                                                    p { class: "text-xs font-mono text-gray-500",
+                                                        "Imports land in the path above, then move to the remote. Failed uploads are retried and stay local until they go through."
+                                                    }
+                                                }
+                                                div {
+                                                    class: "flex items-center gap-2 cursor-pointer",
+                                                    onclick: move |_| edit_shared.set(!edit_shared()),
+                                                    Checkbox { is_selected: edit_shared() }
+                                                    span { class: "text-sm text-gray-300 font-mono", "Share with other users" }
+                                                }
+                                                if edit_shared() {
+                                                    p { class: "text-xs font-mono text-gray-500",
+                                                        "Other users can download into this folder and see it in their library. Only you can edit or remove it."
+                                                    }
                                                 }
                                                 div { class: "flex gap-2 mt-2",
                                                     button {
@@ -262,23 +391,65 @@ pub fn FolderManager() -> Element {
                                         } else {
                                             div { class: "flex justify-between items-center",
                                                 div {
-                                                    span { class: "font-bold text-white block font-display", "{folder.name}" }
+                                                    div { class: "flex items-center gap-2",
+                                                        span { class: "font-bold text-white font-display", "{folder.name}" }
+                                                        if !owned {
+                                                            span { class: "text-[10px] font-mono uppercase tracking-wider text-beet-accent border border-beet-accent/30 rounded px-1",
+                                                                "Shared with you"
+                                                            }
+                                                        } else if folder.shared {
+                                                            span { class: "text-[10px] font-mono uppercase tracking-wider text-beet-leaf border border-beet-leaf/30 rounded px-1",
+                                                                "Shared"
+                                                            }
+                                                        } else {
+                                                            span { class: "text-[10px] font-mono uppercase tracking-wider text-gray-500 border border-white/10 rounded px-1",
+                                                                "Private"
+                                                            }
+                                                        }
+                                                    }
                                                     span { class: "text-gray-500 text-xs font-mono", "{folder.path}" }
-                                                }
-                                                div { class: "flex gap-3",
-                                                    button {
-                                                        class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted",
-                                                        onclick: move |_| {
-                                                            edit_folder_name.set(folder.name.clone());
-                                                            edit_folder_path.set(folder.path.clone());
-                                                            editing_folder_id.set(Some(id_edit.clone()));
-                                                        },
-                                                        "Edit"
+                                                    if let Some(remote) = &folder.remote {
+                                                        span { class: "text-beet-accent text-xs font-mono block", "→ {remote}" }
                                                     }
-                                                    button {
-                                                        class: "text-xs font-mono text-gray-400 hover:text-red-400 transition-colors underline decoration-dotted",
-                                                        onclick: move |_| handle_delete_folder(id_delete.clone()),
-                                                        "Delete"
+                                                    if let Some(diag) = diag {
+                                                        div { class: "flex gap-2 mt-1 text-[10px] font-mono uppercase tracking-wider",
+                                                            if diag.has_problem() {
+                                                                span {
+                                                                    class: "text-red-400",
+                                                                    title: diag.error.clone().unwrap_or_default(),
+                                                                    if diag.exists { "Read-only" } else { "Missing" }
+                                                                }
+                                                            } else {
+                                                                span { class: "text-beet-leaf", "Writable" }
+                                                            }
+                                                            if let Some(free) = diag.free_space_label() {
+                                                                span { class: "text-gray-500", "{free}" }
+                                                            }
+                                                            if folder.import_mode().keeps_download() {
+                                                                span { class: "text-beet-accent", {folder.import_mode().label()} }
+                                                            }
+                                                        }
+                                                    }
+                                                }
+                                                if owned {
+                                                    div { class: "flex gap-3",
+                                                        button {
+                                                            class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted",
+                                                            onclick: move |_| {
+                                                                edit_folder_name.set(folder.name.clone());
+                                                                edit_folder_path.set(folder.path.clone());
+                                                                edit_import_mode.set(ImportMode::from(folder.import_mode.as_str()));
+                                                                edit_remote.set(folder.remote.clone().unwrap_or_default());
+                                                                edit_shared.set(folder.shared);
+                                                                editing_folder_id.set(Some(id_edit.clone()));
+                                                            },
+                                                            "Edit"
+                                                        }
+                                                        button {
+                                                            class: "text-xs font-mono text-gray-400 hover:text-red-400 transition-colors underline decoration-dotted",
+                                                            onclick: move |_| handle_delete_folder(id_delete.clone(), name_delete.clone()),
+                                                            "Delete"
+                                                        }
                                                     }
                                                 }
                                             }
@@ -671,6 +842,37 @@ pub fn FolderManager() -> Element {
                 },
             }
         }
+
+        if let Some(target) = browse_target() {
+            FolderBrowser {
+                initial_path: match target {
+                    BrowseTarget::NewFolder => folder_path(),
+                    BrowseTarget::EditFolder => edit_folder_path(),
+                },
+                on_select: move |path: String| {
+                    match target {
+                        BrowseTarget::NewFolder => folder_path.set(path),
+                        BrowseTarget::EditFolder => edit_folder_path.set(path),
+                    }
+                    browse_target.set(None);
+                },
+                on_close: move |_| browse_target.set(None),
+            }
+        }
+
+        // Undo toast for a freshly deleted folder
+        if let Some((_, name)) = undo_folder() {
+            div { class: "fixed bottom-4 right-4 z-40 w-80 md:w-96 bg-beet-panel border border-white/10 rounded-lg shadow-2xl p-4 flex items-center justify-between gap-3",
+                p { class: "text-sm text-gray-300 font-mono",
+                    "Deleted '{name}' ({undo_seconds_left}s)"
+                }
+                button {
+                    class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                    onclick: handle_undo_delete,
+                    "[ Undo ]"
+                }
+            }
+        }
         }
     }
 }
@@ -0,0 +1,210 @@
+use api::models::import_hook_run::ImportHookRun;
+use api::{get_import_hook_runs, get_import_hooks, set_import_hooks};
+use dioxus::prelude::*;
+use shared::hooks::{HookContext, HookKind, ImportHook};
+
+use crate::{friendly_error, Checkbox};
+
+const FIELD_CLASS: &str = "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm";
+
+fn format_ran_at(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|d| {
+            d.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// A run of a hook in the import log, with its output folded away.
+#[component]
+fn HookRunRow(run: ImportHookRun) -> Element {
+    let status_class = if run.success {
+        "text-beet-leaf"
+    } else {
+        "text-red-400"
+    };
+    let took = format!("{:.1}s", run.duration_ms as f64 / 1000.0);
+
+    rsx! {
+        details { class: "bg-white/5 border border-white/5 rounded p-2 text-xs font-mono",
+            summary { class: "flex items-center gap-2 cursor-pointer min-w-0",
+                span { class: "text-gray-500 shrink-0", {format_ran_at(run.ran_at)} }
+                span { class: "text-white shrink-0", "{run.hook_name}" }
+                span { class: "text-gray-400 truncate flex-1", title: "{run.album_path}", "{run.album_path}" }
+                span { class: "shrink-0 {status_class}", "{run.status}" }
+                span { class: "text-gray-500 shrink-0", "{took}" }
+            }
+            if run.output.is_empty() {
+                p { class: "mt-2 text-gray-500 italic", "No output" }
+            } else {
+                pre { class: "mt-2 p-2 bg-beet-dark rounded text-gray-300 whitespace-pre-wrap break-all max-h-64 overflow-y-auto",
+                    "{run.output}"
+                }
+            }
+        }
+    }
+}
+
+/// Commands and webhooks run after each successful import, and the log of
+/// what they did.
+#[component]
+pub fn ImportHooksManager() -> Element {
+    let mut hooks = use_signal(Vec::<ImportHook>::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+
+    use_future(move || async move {
+        match get_import_hooks().await {
+            Ok(list) => hooks.set(list),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+    let mut runs = use_resource(move || async move { get_import_hook_runs().await });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        if let Some(problem) = hooks.read().iter().find_map(ImportHook::problem) {
+            error.set(problem);
+            return;
+        }
+        saving.set(true);
+        match set_import_hooks(hooks()).await {
+            Ok(saved) => {
+                hooks.set(saved);
+                success_msg.set("Import hooks saved".to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        saving.set(false);
+    };
+
+    let env_names = HookContext::ENV_NAMES.join(", ");
+    let log = match &*runs.read() {
+        None => rsx! {},
+        Some(Err(e)) => rsx! {
+            p { class: "text-xs font-mono text-red-400", {friendly_error(e)} }
+        },
+        Some(Ok(list)) if list.is_empty() => rsx! {
+            p { class: "text-sm font-mono text-gray-500 italic", "No hook has run yet." }
+        },
+        Some(Ok(list)) => rsx! {
+            div { class: "space-y-1 max-h-96 overflow-y-auto",
+                for run in list.iter().cloned() {
+                    HookRunRow { key: "{run.id}", run }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Post-import Hooks" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Run after every successful import, in order. Commands run with sh and only get PATH and {env_names}, and only when the server sets ALLOW_COMMAND_HOOKS; webhooks get the same as a JSON POST."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            if hooks.read().is_empty() {
+                p { class: "text-sm font-mono text-gray-500 italic mb-4", "No hooks." }
+            }
+            div { class: "space-y-3 mb-4",
+                for (i, hook) in hooks.read().iter().cloned().enumerate() {
+                    div { key: "{i}", class: "p-3 bg-white/5 border border-white/10 rounded space-y-2",
+                        div { class: "flex flex-wrap items-center gap-2",
+                            div {
+                                class: "cursor-pointer",
+                                title: "Enabled",
+                                onclick: move |_| hooks.write()[i].enabled ^= true,
+                                Checkbox { is_selected: hook.enabled }
+                            }
+                            input {
+                                class: "{FIELD_CLASS} flex-grow min-w-32",
+                                value: "{hook.name}",
+                                placeholder: "Name",
+                                oninput: move |e| hooks.write()[i].name = e.value(),
+                            }
+                            select {
+                                class: FIELD_CLASS,
+                                onchange: move |e| hooks.write()[i].kind = HookKind::from(e.value().as_str()),
+                                for kind in HookKind::ALL {
+                                    option {
+                                        value: kind.as_str(),
+                                        selected: kind == hook.kind,
+                                        {kind.label()}
+                                    }
+                                }
+                            }
+                            input {
+                                class: "{FIELD_CLASS} w-20",
+                                "type": "number",
+                                min: "1",
+                                max: ImportHook::MAX_TIMEOUT_SECS.to_string(),
+                                title: "Timeout in seconds",
+                                value: "{hook.timeout_secs}",
+                                oninput: move |e| {
+                                    if let Ok(secs) = e.value().trim().parse() {
+                                        hooks.write()[i].timeout_secs = secs;
+                                    }
+                                },
+                            }
+                            button {
+                                class: "text-xs font-mono text-red-400 hover:text-red-300 cursor-pointer",
+                                onclick: move |_| {
+                                    hooks.write().remove(i);
+                                },
+                                "Remove"
+                            }
+                        }
+                        input {
+                            class: "{FIELD_CLASS} w-full",
+                            value: "{hook.target}",
+                            placeholder: match hook.kind {
+                                HookKind::Command => "curl -fsS \"http://jellyfin:8096/Library/Refresh\" -X POST",
+                                HookKind::Webhook => "https://example.com/hooks/soulbeet",
+                            },
+                            oninput: move |e| hooks.write()[i].target = e.value(),
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-2 mb-6",
+                button {
+                    class: "retro-btn rounded",
+                    onclick: move |_| hooks.write().push(ImportHook::default()),
+                    "Add Hook"
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: saving(),
+                    onclick: handle_save,
+                    if saving() { "Saving..." } else { "Save Hooks" }
+                }
+            }
+
+            div { class: "flex items-center justify-between mb-2",
+                h3 { class: "text-xs font-mono text-gray-400 uppercase tracking-wider", "Import log" }
+                button {
+                    class: "text-xs font-mono text-gray-400 hover:text-white cursor-pointer",
+                    onclick: move |_| runs.restart(),
+                    "Refresh"
+                }
+            }
+            {log}
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use api::{get_logging_status, set_log_levels};
+use dioxus::prelude::*;
+use shared::logging::{LogLevelOverride, LoggingStatus, LEVELS};
+
+use crate::friendly_error;
+
+const FIELD_CLASS: &str = "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm";
+
+#[component]
+fn StatusRow(label: &'static str, value: String) -> Element {
+    rsx! {
+        div { class: "flex gap-2 min-w-0",
+            span { class: "text-gray-500 shrink-0 w-24", "{label}" }
+            span { class: "text-gray-300 break-all", "{value}" }
+        }
+    }
+}
+
+/// Where logs go, as set in the environment, and the modules logged at
+/// their own level.
+#[component]
+pub fn LoggingManager() -> Element {
+    let mut status = use_signal(LoggingStatus::default);
+    let mut overrides = use_signal(Vec::<LogLevelOverride>::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+
+    let mut apply = move |s: LoggingStatus| {
+        overrides.set(s.overrides.clone());
+        status.set(s);
+    };
+
+    use_future(move || async move {
+        match get_logging_status().await {
+            Ok(s) => apply(s),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        if let Some(problem) = overrides.read().iter().find_map(LogLevelOverride::problem) {
+            error.set(problem);
+            return;
+        }
+        saving.set(true);
+        match set_log_levels(overrides()).await {
+            Ok(s) => {
+                apply(s);
+                success_msg.set("Log levels applied".to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        saving.set(false);
+    };
+
+    let current = status();
+    let file = match &current.file_dir {
+        Some(dir) if current.max_files > 0 => format!(
+            "{}, rotated {}, last {} kept",
+            dir,
+            current.rotation.as_str(),
+            current.max_files
+        ),
+        Some(dir) => format!("{}, rotated {}", dir, current.rotation.as_str()),
+        None => "stdout only".to_string(),
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Logging" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Outputs and format come from LOG_DIR, LOG_FORMAT and LOG_LEVEL. Modules listed here are logged at their own level, right away and after restarts."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            div { class: "space-y-1 mb-6 p-3 bg-white/5 border border-white/5 rounded text-xs font-mono",
+                StatusRow { label: "Format", value: current.format.as_str().to_string() }
+                StatusRow { label: "Files", value: file }
+                StatusRow { label: "Base filter", value: current.base_filter.clone() }
+                StatusRow { label: "Active filter", value: current.active_filter.clone() }
+            }
+
+            h3 { class: "text-xs font-mono text-gray-400 uppercase tracking-wider mb-2", "Module levels" }
+            if overrides.read().is_empty() {
+                p { class: "text-sm font-mono text-gray-500 italic mb-4", "Every module logs at the base level." }
+            }
+            div { class: "space-y-2 mb-4",
+                for (i, o) in overrides.read().iter().cloned().enumerate() {
+                    div { key: "{i}", class: "flex items-center gap-2",
+                        input {
+                            class: "{FIELD_CLASS} flex-grow",
+                            value: "{o.target}",
+                            placeholder: "soulbeet::import",
+                            oninput: move |e| overrides.write()[i].target = e.value(),
+                        }
+                        select {
+                            class: FIELD_CLASS,
+                            onchange: move |e| overrides.write()[i].level = e.value(),
+                            for level in LEVELS {
+                                option { value: level, selected: level == o.level, {level} }
+                            }
+                        }
+                        button {
+                            class: "text-xs font-mono text-red-400 hover:text-red-300 cursor-pointer",
+                            onclick: move |_| {
+                                overrides.write().remove(i);
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-2",
+                button {
+                    class: "retro-btn rounded",
+                    onclick: move |_| {
+                        overrides
+                            .write()
+                            .push(LogLevelOverride {
+                                target: String::new(),
+                                level: "debug".to_string(),
+                            })
+                    },
+                    "Add Module"
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: saving(),
+                    onclick: handle_save,
+                    if saving() { "Applying..." } else { "Apply Levels" }
+                }
+            }
+        }
+    }
+}
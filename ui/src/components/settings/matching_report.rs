@@ -0,0 +1,80 @@
+use api::get_matching_report;
+use dioxus::prelude::*;
+use shared::analytics::{MatchingReport, RankOutcome};
+
+use crate::friendly_error;
+
+fn percent(rate: Option<f64>) -> String {
+    rate.map(|r| format!("{:.0}%", r * 100.0))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn rank_label(outcome: &RankOutcome) -> String {
+    match outcome.rank {
+        0 => "Top".to_string(),
+        n if n >= MatchingReport::LAST_RANK => format!("#{} or lower", n + 1),
+        n => format!("#{}", n + 1),
+    }
+}
+
+/// Which ranked candidate downloads came from and how they ended, recorded
+/// on this server only.
+#[component]
+pub fn MatchingReportPanel() -> Element {
+    let report = use_resource(|| async { get_matching_report().await });
+
+    let body = match &*report.read() {
+        None => rsx! {
+            p { class: "text-sm font-mono text-gray-500 animate-pulse", "Loading..." }
+        },
+        Some(Err(e)) => rsx! {
+            p { class: "text-sm font-mono text-red-400", {friendly_error(e)} }
+        },
+        Some(Ok(report)) if report.ranks.is_empty() => rsx! {
+            p { class: "text-sm font-mono text-gray-500 italic", "No downloads recorded yet." }
+        },
+        Some(Ok(report)) => rsx! {
+            p { class: "text-sm font-mono text-gray-300 mb-4",
+                "Top candidate picked in {percent(report.top_pick_rate())} of manual downloads."
+            }
+            table { class: "w-full text-xs font-mono",
+                thead {
+                    tr { class: "text-gray-500 uppercase tracking-wider text-left",
+                        th { class: "pb-2", "Picked" }
+                        th { class: "pb-2", "Rank" }
+                        th { class: "pb-2 text-right", "Picks" }
+                        th { class: "pb-2 text-right", "Imported" }
+                        th { class: "pb-2 text-right", "Failed" }
+                        th { class: "pb-2 text-right", "Score gap" }
+                    }
+                }
+                tbody {
+                    for outcome in report.ranks.iter() {
+                        tr { class: "text-gray-300 border-t border-white/5",
+                            td { class: "py-1",
+                                if outcome.automatic { "Auto" } else { "Manual" }
+                            }
+                            td { class: "py-1", {rank_label(outcome)} }
+                            td { class: "py-1 text-right", "{outcome.picks}" }
+                            td { class: "py-1 text-right", "{outcome.imported}" }
+                            td { class: "py-1 text-right",
+                                "{outcome.failed} ({percent(outcome.failure_rate())})"
+                            }
+                            td { class: "py-1 text-right", "{outcome.mean_score_gap:.2}" }
+                        }
+                    }
+                }
+            }
+        },
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Matching Accuracy" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Where the search results people download ranked, and whether they imported. Kept on this server, without user names."
+            }
+            {body}
+        }
+    }
+}
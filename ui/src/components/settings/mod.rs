@@ -1,9 +1,39 @@
+mod api_tokens;
 mod app_config;
+mod crash_reports;
+mod discogs;
+mod download_pause;
+mod feature_flags;
+mod folder_browser;
 mod folder_manager;
+mod import_hooks;
+mod logging;
+mod matching_report;
+mod musicbrainz;
+mod notifications;
 mod preferences;
+mod rename_rules;
+mod routing_rules;
+mod security;
+mod transfer_cleanup;
 mod user_manager;
 
+pub use api_tokens::ApiTokenManager;
 pub use app_config::AppConfigManager;
+pub use crash_reports::CrashReportsPanel;
+pub use discogs::DiscogsManager;
+pub use download_pause::DownloadPauseManager;
+pub use feature_flags::FeatureFlagsManager;
+pub use folder_browser::FolderBrowser;
 pub use folder_manager::FolderManager;
+pub use import_hooks::ImportHooksManager;
+pub use logging::LoggingManager;
+pub use matching_report::MatchingReportPanel;
+pub use musicbrainz::MusicBrainzManager;
+pub use notifications::NotificationSettings;
 pub use preferences::PreferencesManager;
+pub use rename_rules::RenameRulesManager;
+pub use routing_rules::RoutingRulesManager;
+pub use security::SecurityPolicyManager;
+pub use transfer_cleanup::TransferCleanupManager;
 pub use user_manager::UserManager;
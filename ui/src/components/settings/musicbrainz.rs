@@ -0,0 +1,117 @@
+use api::{get_musicbrainz_config, update_musicbrainz_config};
+use dioxus::prelude::*;
+use shared::system::MusicBrainzConfig;
+
+use crate::{friendly_error, use_system_health};
+
+/// MusicBrainz server used for lookups and the contact sent along, with
+/// what the server last reported about it.
+#[component]
+pub fn MusicBrainzManager() -> Element {
+    let health = use_system_health();
+    let mut mirror_url = use_signal(String::new);
+    let mut contact = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    let mut apply = move |c: MusicBrainzConfig| {
+        mirror_url.set(c.mirror_url.unwrap_or_default());
+        contact.set(c.contact.unwrap_or_default());
+    };
+
+    use_future(move || async move {
+        match get_musicbrainz_config().await {
+            Ok(c) => apply(c),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        busy.set(true);
+        let config = MusicBrainzConfig {
+            mirror_url: Some(mirror_url()),
+            contact: Some(contact()),
+        };
+        match update_musicbrainz_config(config).await {
+            Ok(c) => {
+                apply(c);
+                success_msg.set("MusicBrainz settings saved".to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let status = health.get().musicbrainz;
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "MusicBrainz" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "A self-hosted mirror is not rate limited, which speeds up searches, the release calendar and label catalogs. Imports keep the server set in the beets config."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            if !status.endpoint.is_empty() {
+                div { class: "flex items-center gap-2 mb-4 text-sm font-mono text-gray-300",
+                    span {
+                        class: format!(
+                            "w-2 h-2 rounded-full {}",
+                            if status.reachable { "bg-beet-leaf" } else { "bg-red-500" },
+                        ),
+                    }
+                    if status.reachable {
+                        "Reachable at {status.endpoint}"
+                    } else {
+                        "Unreachable at {status.endpoint}"
+                    }
+                    if status.mirror {
+                        span { class: "text-xs text-gray-500", "(mirror, no rate limit)" }
+                    }
+                }
+                p { class: "text-xs font-mono text-gray-500 mb-4 break-all", "User agent: {status.user_agent}" }
+            }
+
+            div { class: "space-y-4 mb-6",
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider", "Mirror URL" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                        value: "{mirror_url}",
+                        oninput: move |e| mirror_url.set(e.value()),
+                        placeholder: "Empty for musicbrainz.org, e.g. http://musicbrainz.lan:5000",
+                    }
+                }
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider", "Contact" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                        value: "{contact}",
+                        oninput: move |e| contact.set(e.value()),
+                        placeholder: "Email or URL MusicBrainz can reach you at",
+                    }
+                }
+            }
+
+            button {
+                class: "retro-btn rounded",
+                disabled: busy(),
+                onclick: handle_save,
+                "Save"
+            }
+        }
+    }
+}
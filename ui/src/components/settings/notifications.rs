@@ -0,0 +1,61 @@
+use dioxus::prelude::*;
+
+/// Read the browser's notification permission: "granted", "denied",
+/// "default", or "unsupported".
+async fn notification_permission(request: bool) -> String {
+    let js = if request {
+        r#"
+        if (!("Notification" in window)) return "unsupported";
+        return await Notification.requestPermission();
+        "#
+    } else {
+        r#"
+        if (!("Notification" in window)) return "unsupported";
+        return Notification.permission;
+        "#
+    };
+    document::eval(js)
+        .join::<String>()
+        .await
+        .unwrap_or_else(|_| "unsupported".to_string())
+}
+
+/// Opt in to browser notifications when downloads finish while SoulBeet
+/// is in a background tab. The permission is per browser.
+#[component]
+pub fn NotificationSettings() -> Element {
+    let mut permission = use_signal(String::new);
+
+    use_future(move || async move {
+        permission.set(notification_permission(false).await);
+    });
+
+    let enable = move |_| async move {
+        permission.set(notification_permission(true).await);
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Browser Notifications" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Get notified in this browser when downloads are imported or fail while SoulBeet is in the background."
+            }
+            match permission().as_str() {
+                "granted" => rsx! {
+                    p { class: "text-sm font-mono text-beet-leaf", "Enabled for this browser." }
+                },
+                "denied" => rsx! {
+                    p { class: "text-sm font-mono text-amber-400",
+                        "Blocked. Allow notifications for this site in your browser settings."
+                    }
+                },
+                "unsupported" => rsx! {
+                    p { class: "text-sm font-mono text-gray-500", "Not supported by this browser." }
+                },
+                _ => rsx! {
+                    button { class: "retro-btn rounded", onclick: enable, "Enable Notifications" }
+                },
+            }
+        }
+    }
+}
@@ -1,12 +1,20 @@
 use dioxus::prelude::*;
+use shared::download::{MatchMode, SEARCH_TIMEOUT_CHOICES};
+use shared::quality::SearchFilters;
 
-use crate::friendly_error;
 use crate::settings_context::use_settings;
+use crate::{friendly_error, Checkbox};
 
 #[component]
 pub fn PreferencesManager() -> Element {
     let mut settings = use_settings();
     let mut selected_provider = use_signal(|| settings.default_provider());
+    let mut match_mode = use_signal(|| settings.match_mode());
+    // Kept as text so the field can be edited freely
+    let mut custom_score = use_signal(String::new);
+    let mut quality_profile = use_signal(|| settings.quality_profile());
+    let mut search_timeout = use_signal(|| settings.search_timeout_secs());
+    let mut home_dashboard = use_signal(|| settings.home_dashboard());
     let mut error = use_signal(String::new);
     let mut success_msg = use_signal(String::new);
     let mut saving = use_signal(|| false);
@@ -15,6 +23,14 @@ pub fn PreferencesManager() -> Element {
     use_effect(move || {
         if settings.is_loaded() && !synced() {
             selected_provider.set(settings.default_provider());
+            quality_profile.set(settings.quality_profile());
+            search_timeout.set(settings.search_timeout_secs());
+            home_dashboard.set(settings.home_dashboard());
+            let mode = settings.match_mode();
+            match_mode.set(mode);
+            if let MatchMode::Custom(score) = mode {
+                custom_score.set(format!("{:.2}", score));
+            }
             synced.set(true);
         }
     });
@@ -46,10 +62,25 @@ pub fn PreferencesManager() -> Element {
             return;
         }
 
+        let mode = match match_mode() {
+            MatchMode::Custom(_) => match custom_score().trim().parse::<f64>() {
+                Ok(score) if (0.0..=1.0).contains(&score) => MatchMode::Custom(score),
+                _ => {
+                    error.set("Custom match score must be between 0 and 1.".to_string());
+                    return;
+                }
+            },
+            preset => preset,
+        };
+
         saving.set(true);
 
         let update = api::UpdateUserSettings {
             default_metadata_provider: Some(selected_provider()),
+            match_mode: Some(mode.to_setting()),
+            quality_profile: Some(quality_profile.read().to_json()),
+            search_timeout_secs: Some(search_timeout()),
+            home_dashboard: Some(home_dashboard()),
             ..Default::default()
         };
 
@@ -108,6 +139,161 @@ pub fn PreferencesManager() -> Element {
                         }
                     }
                 }
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Source Matching"
+                    }
+                    div { class: "flex gap-2",
+                        select {
+                            class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            onchange: move |e| {
+                                let mode = match e.value().as_str() {
+                                    "custom" => {
+                                        if custom_score().trim().is_empty() {
+                                            custom_score.set(format!("{:.2}", match_mode().min_score()));
+                                        }
+                                        MatchMode::Custom(match_mode().min_score())
+                                    }
+                                    other => MatchMode::from_setting(other).unwrap_or_default(),
+                                };
+                                match_mode.set(mode);
+                            },
+                            for preset in MatchMode::PRESETS {
+                                option {
+                                    value: "{preset.to_setting()}",
+                                    selected: match_mode() == preset,
+                                    "{preset.label()} (score ≥ {preset.min_score():.2})"
+                                }
+                            }
+                            option {
+                                value: "custom",
+                                selected: matches!(match_mode(), MatchMode::Custom(_)),
+                                "Custom"
+                            }
+                        }
+                        if matches!(match_mode(), MatchMode::Custom(_)) {
+                            input {
+                                class: "w-24 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                                "type": "number",
+                                min: "0",
+                                max: "1",
+                                step: "0.05",
+                                value: "{custom_score}",
+                                oninput: move |e| custom_score.set(e.value()),
+                            }
+                        }
+                    }
+                    p { class: "text-xs text-gray-500 mt-1 font-mono",
+                        "How closely Soulseek files must match the release. Loose finds rare releases under messy folder names; strict keeps results clean for popular ones. Can be changed per search."
+                    }
+                }
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Search Timeout"
+                    }
+                    select {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                        onchange: move |e| {
+                            if let Ok(secs) = e.value().parse() {
+                                search_timeout.set(secs);
+                            }
+                        },
+                        for secs in SEARCH_TIMEOUT_CHOICES {
+                            option {
+                                value: "{secs}",
+                                selected: search_timeout() == secs,
+                                "{secs} seconds"
+                            }
+                        }
+                    }
+                    p { class: "text-xs text-gray-500 mt-1 font-mono",
+                        "How long Soulseek searches collect results. Longer searches reach slow peers with rare releases. Can be changed per search."
+                    }
+                }
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Result Diversity"
+                    }
+                    div {
+                        class: "flex items-center gap-2 cursor-pointer mb-1",
+                        onclick: move |_| {
+                            let mut profile = quality_profile.write();
+                            profile.diversity.per_format = !profile.diversity.per_format;
+                        },
+                        Checkbox { is_selected: quality_profile.read().diversity.per_format }
+                        span { class: "text-sm text-gray-300 font-mono", "Show the best source of each format (lossless, 256k+, lower) first" }
+                    }
+                    div {
+                        class: "flex items-center gap-2 cursor-pointer",
+                        onclick: move |_| {
+                            let mut profile = quality_profile.write();
+                            profile.diversity.per_completeness = !profile.diversity.per_completeness;
+                        },
+                        Checkbox { is_selected: quality_profile.read().diversity.per_completeness }
+                        span { class: "text-sm text-gray-300 font-mono", "Show the best complete and partial sources first" }
+                    }
+                }
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Search Filters"
+                    }
+                    div { class: "grid grid-cols-1 md:grid-cols-2 gap-2 mb-2",
+                        select {
+                            class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            onchange: move |e| quality_profile.write().search_filters.min_bitrate = e.value().parse().ok(),
+                            option {
+                                value: "",
+                                selected: quality_profile.read().search_filters.min_bitrate.is_none(),
+                                "Any bitrate"
+                            }
+                            for kbps in SearchFilters::BITRATE_CHOICES {
+                                option {
+                                    value: "{kbps}",
+                                    selected: quality_profile.read().search_filters.min_bitrate == Some(kbps),
+                                    "Lossy files from {kbps} kbps"
+                                }
+                            }
+                        }
+                        input {
+                            class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            "type": "number",
+                            min: "1",
+                            placeholder: "Minimum files per peer",
+                            value: quality_profile.read().search_filters.min_response_files.map(|n| n.to_string()).unwrap_or_default(),
+                            oninput: move |e| {
+                                quality_profile.write().search_filters.min_response_files = e
+                                    .value()
+                                    .trim()
+                                    .parse()
+                                    .ok()
+                                    .filter(|n| *n > 0);
+                            },
+                        }
+                    }
+                    div {
+                        class: "flex items-center gap-2 cursor-pointer",
+                        onclick: move |_| {
+                            let mut profile = quality_profile.write();
+                            profile.search_filters.free_slot_only = !profile.search_filters.free_slot_only;
+                        },
+                        Checkbox { is_selected: quality_profile.read().search_filters.free_slot_only }
+                        span { class: "text-sm text-gray-300 font-mono", "Only peers with a free upload slot" }
+                    }
+                    p { class: "text-xs text-gray-500 mt-1 font-mono",
+                        "Drops responses before ranking. Lossless files always pass the bitrate filter. Strict filters can hide rare releases."
+                    }
+                }
+                div {
+                    div {
+                        class: "flex items-center gap-2 cursor-pointer",
+                        onclick: move |_| home_dashboard.set(!home_dashboard()),
+                        Checkbox { is_selected: home_dashboard() }
+                        span { class: "text-sm text-gray-300 font-mono", "Open on the dashboard" }
+                    }
+                    p { class: "text-xs text-gray-500 mt-1 font-mono",
+                        "Show downloads, recent imports, new releases and disk space above a quick search on the home page."
+                    }
+                }
             }
 
             button {
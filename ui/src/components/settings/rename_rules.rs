@@ -0,0 +1,167 @@
+use dioxus::prelude::*;
+
+use crate::settings_context::use_settings;
+use crate::{friendly_error, Checkbox};
+
+/// File names the preview starts with, typical of what peers share.
+const SAMPLE_NAMES: &str = "01 - Artist - Track_Name [FLAC].flac\n\
+                            A2. Side_A_Song (1979).mp3\n\
+                            07–Café del Mar.m4a";
+
+/// Rules for renaming downloaded files before they are imported, with a
+/// dry run over sample names.
+#[component]
+pub fn RenameRulesManager() -> Element {
+    let mut settings = use_settings();
+    let mut rules = use_signal(|| settings.rename_rules());
+    let mut samples = use_signal(|| SAMPLE_NAMES.to_string());
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+    let mut synced = use_signal(|| false);
+
+    use_effect(move || {
+        if settings.is_loaded() && !synced() {
+            rules.set(settings.rename_rules());
+            synced.set(true);
+        }
+    });
+
+    let preview = use_resource(move || async move {
+        let file_names: Vec<String> = samples()
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(String::from)
+            .collect();
+        api::preview_rename_rules(rules(), file_names).await
+    });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        saving.set(true);
+        let update = api::UpdateUserSettings {
+            rename_rules: Some(rules.read().to_json()),
+            ..Default::default()
+        };
+        match settings.update(update).await {
+            Ok(_) => success_msg.set("Rename rules saved".to_string()),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        saving.set(false);
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "File Renaming" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Rename downloaded files before import, for importer setups that read track numbers or titles from file names."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            div { class: "space-y-2 mb-4",
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| {
+                        let mut r = rules.write();
+                        r.enabled = !r.enabled;
+                    },
+                    Checkbox { is_selected: rules.read().enabled }
+                    span { class: "text-sm text-gray-300 font-mono", "Rename files before import" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| {
+                        let mut r = rules.write();
+                        r.strip_track_junk = !r.strip_track_junk;
+                    },
+                    Checkbox { is_selected: rules.read().strip_track_junk }
+                    span { class: "text-sm text-gray-300 font-mono", "Strip track numbers, [tags] and years" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| {
+                        let mut r = rules.write();
+                        r.normalize_separators = !r.normalize_separators;
+                    },
+                    Checkbox { is_selected: rules.read().normalize_separators }
+                    span { class: "text-sm text-gray-300 font-mono", "Normalize separators and spacing" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| {
+                        let mut r = rules.write();
+                        r.transliterate = !r.transliterate;
+                    },
+                    Checkbox { is_selected: rules.read().transliterate }
+                    span { class: "text-sm text-gray-300 font-mono", "Transliterate accented letters" }
+                }
+            }
+
+            div { class: "mb-4",
+                label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                    "Name Template"
+                }
+                input {
+                    class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                    value: "{rules.read().template}",
+                    oninput: move |e| rules.write().template = e.value(),
+                    placeholder: "{{number}} - {{title}}",
+                }
+                p { class: "text-xs font-mono text-gray-500 mt-1",
+                    "{{number}} is the track number found in the original name, {{title}} the cleaned rest."
+                }
+            }
+
+            div { class: "mb-6",
+                label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                    "Preview"
+                }
+                textarea {
+                    class: "w-full h-24 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-xs",
+                    value: "{samples}",
+                    oninput: move |e| samples.set(e.value()),
+                }
+                match &*preview.read() {
+                    Some(Ok(renames)) => rsx! {
+                        div { class: "mt-2 space-y-1",
+                            for rename in renames.iter() {
+                                div { class: "text-xs font-mono flex flex-wrap gap-2",
+                                    span { class: "text-gray-500", "{rename.from}" }
+                                    span { class: "text-gray-600", "→" }
+                                    if rename.from == rename.to {
+                                        span { class: "text-gray-400", "unchanged" }
+                                    } else {
+                                        span { class: "text-beet-leaf", "{rename.to}" }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    Some(Err(e)) => rsx! {
+                        div { class: "mt-2 text-xs font-mono text-red-400", {friendly_error(e)} }
+                    },
+                    None => rsx! {},
+                }
+            }
+
+            button {
+                class: "retro-btn rounded",
+                disabled: saving(),
+                onclick: handle_save,
+                if saving() { "Saving..." } else { "Save Rename Rules" }
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use dioxus::prelude::*;
+use shared::quality::SearchFilters;
+use shared::routing::RoutingRule;
+
+use crate::settings_context::use_settings;
+use crate::{friendly_error, use_auth};
+
+const FIELD_CLASS: &str = "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono";
+
+/// Quality choice of a rule as a select value: "any", "lossless" or a
+/// minimum bitrate.
+fn quality_value(rule: &RoutingRule) -> String {
+    match (rule.lossless_only, rule.min_bitrate) {
+        (true, _) => "lossless".to_string(),
+        (false, Some(kbps)) => kbps.to_string(),
+        (false, None) => "any".to_string(),
+    }
+}
+
+fn set_quality(rule: &mut RoutingRule, value: &str) {
+    rule.lossless_only = value == "lossless";
+    rule.min_bitrate = value.parse().ok();
+}
+
+/// Rules sending releases of a genre to their own folder and quality, tried
+/// in order when a download starts.
+#[component]
+pub fn RoutingRulesManager() -> Element {
+    let auth = use_auth();
+    let mut settings = use_settings();
+    let mut rules = use_signal(|| settings.routing_rules());
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut saving = use_signal(|| false);
+    let mut synced = use_signal(|| false);
+
+    let folders =
+        use_resource(
+            move || async move { auth.call(api::get_user_folders).await.unwrap_or_default() },
+        );
+
+    use_effect(move || {
+        if settings.is_loaded() && !synced() {
+            rules.set(settings.routing_rules());
+            synced.set(true);
+        }
+    });
+
+    let handle_add = move |_| {
+        let folder_id = folders
+            .read()
+            .as_ref()
+            .and_then(|f| f.first().map(|f| f.id.clone()))
+            .unwrap_or_default();
+        rules.write().rules.push(RoutingRule {
+            folder_id,
+            ..Default::default()
+        });
+    };
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        if rules.read().rules.iter().any(|r| r.genre.trim().is_empty()) {
+            error.set("Every rule needs a genre".to_string());
+            return;
+        }
+        saving.set(true);
+        let update = api::UpdateUserSettings {
+            routing_rules: Some(rules.read().to_json()),
+            ..Default::default()
+        };
+        match settings.update(update).await {
+            Ok(_) => success_msg.set("Routing rules saved".to_string()),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        saving.set(false);
+    };
+
+    let folder_list = folders.read().clone().unwrap_or_default();
+    let count = rules.read().rules.len();
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Genre Routing" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Send releases of a genre to their own folder and quality, e.g. Classical into a classical folder in lossless only. The first rule matching one of the release's genres applies; the folder chosen for the download is used otherwise."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            if count == 0 {
+                p { class: "text-sm font-mono text-gray-500 italic mb-4", "No routing rules." }
+            }
+            div { class: "space-y-2 mb-4",
+                for (i, rule) in rules.read().rules.iter().cloned().enumerate() {
+                    div { key: "{i}", class: "flex flex-wrap items-center gap-2",
+                        span { class: "text-xs font-mono text-gray-500 w-6", "{i + 1}." }
+                        input {
+                            class: "{FIELD_CLASS} flex-grow min-w-32",
+                            value: "{rule.genre}",
+                            placeholder: "Genre",
+                            oninput: move |e| rules.write().rules[i].genre = e.value(),
+                        }
+                        select {
+                            class: FIELD_CLASS,
+                            onchange: move |e| rules.write().rules[i].folder_id = e.value(),
+                            for folder in folder_list.iter() {
+                                option {
+                                    value: "{folder.id}",
+                                    selected: folder.id == rule.folder_id,
+                                    "{folder.name}"
+                                }
+                            }
+                        }
+                        select {
+                            class: FIELD_CLASS,
+                            onchange: move |e| set_quality(&mut rules.write().rules[i], &e.value()),
+                            option { value: "any", selected: quality_value(&rule) == "any", "Any quality" }
+                            option { value: "lossless", selected: rule.lossless_only, "Lossless only" }
+                            for kbps in SearchFilters::BITRATE_CHOICES {
+                                option {
+                                    value: "{kbps}",
+                                    selected: quality_value(&rule) == kbps.to_string(),
+                                    "Lossy from {kbps} kbps"
+                                }
+                            }
+                        }
+                        button {
+                            class: "text-xs font-mono text-gray-400 hover:text-white disabled:opacity-30 cursor-pointer",
+                            title: "Try earlier",
+                            disabled: i == 0,
+                            onclick: move |_| rules.write().rules.swap(i - 1, i),
+                            "▲"
+                        }
+                        button {
+                            class: "text-xs font-mono text-red-400 hover:text-red-300 cursor-pointer",
+                            onclick: move |_| {
+                                rules.write().rules.remove(i);
+                            },
+                            "Remove"
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex gap-2",
+                button {
+                    class: "retro-btn rounded",
+                    disabled: folder_list.is_empty(),
+                    onclick: handle_add,
+                    "Add Rule"
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: saving(),
+                    onclick: handle_save,
+                    if saving() { "Saving..." } else { "Save Routing Rules" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use api::{get_security_policy, set_security_policy};
+use dioxus::prelude::*;
+use shared::security::SecurityPolicy;
+
+use crate::{friendly_error, Checkbox};
+
+const NUMBER_INPUT_CLASS: &str = "w-24 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono";
+
+/// Rules for the passwords users choose and how many wrong passwords lock
+/// an account.
+#[component]
+pub fn SecurityPolicyManager() -> Element {
+    let mut policy = use_signal(SecurityPolicy::default);
+    let mut min_length = use_signal(String::new);
+    let mut max_attempts = use_signal(String::new);
+    let mut lockout_minutes = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    let mut apply = move |p: SecurityPolicy| {
+        min_length.set(p.password.min_length.to_string());
+        max_attempts.set(p.lockout.max_attempts.to_string());
+        lockout_minutes.set(p.lockout.lockout_minutes.to_string());
+        policy.set(p);
+    };
+
+    use_future(move || async move {
+        match get_security_policy().await {
+            Ok(p) => apply(p),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        let (Ok(length), Ok(attempts), Ok(minutes)) = (
+            min_length().trim().parse::<usize>(),
+            max_attempts().trim().parse::<u32>(),
+            lockout_minutes().trim().parse::<u32>(),
+        ) else {
+            error.set("Lengths, attempts and minutes must be whole numbers".to_string());
+            return;
+        };
+        let mut update = policy();
+        update.password.min_length = length;
+        update.lockout.max_attempts = attempts;
+        update.lockout.lockout_minutes = minutes;
+        busy.set(true);
+        match set_security_policy(update).await {
+            Ok(p) => {
+                apply(p);
+                success_msg.set("Security settings saved".to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let rule = policy().password;
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Passwords & Lockout" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Applies to passwords set from now on, at signup, reset or change. Passwords that sign in through Navidrome are Navidrome's."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            div { class: "space-y-4 mb-6",
+                div {
+                    label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                        "Minimum length"
+                    }
+                    input {
+                        class: NUMBER_INPUT_CLASS,
+                        "type": "number",
+                        min: "1",
+                        value: "{min_length}",
+                        oninput: move |e| min_length.set(e.value()),
+                    }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| policy.write().password.require_mixed_case ^= true,
+                    Checkbox { is_selected: rule.require_mixed_case }
+                    span { class: "text-sm text-gray-300 font-mono", "Require lowercase and uppercase letters" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| policy.write().password.require_digit ^= true,
+                    Checkbox { is_selected: rule.require_digit }
+                    span { class: "text-sm text-gray-300 font-mono", "Require a digit" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| policy.write().password.require_symbol ^= true,
+                    Checkbox { is_selected: rule.require_symbol }
+                    span { class: "text-sm text-gray-300 font-mono", "Require a symbol" }
+                }
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| policy.write().password.reject_common ^= true,
+                    Checkbox { is_selected: rule.reject_common }
+                    span { class: "text-sm text-gray-300 font-mono", "Refuse well-known breached passwords" }
+                }
+                div { class: "flex flex-wrap gap-6",
+                    div {
+                        label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                            "Lock after (failed sign-ins)"
+                        }
+                        input {
+                            class: NUMBER_INPUT_CLASS,
+                            "type": "number",
+                            min: "0",
+                            value: "{max_attempts}",
+                            oninput: move |e| max_attempts.set(e.value()),
+                        }
+                    }
+                    div {
+                        label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                            "Lock for (minutes)"
+                        }
+                        input {
+                            class: NUMBER_INPUT_CLASS,
+                            "type": "number",
+                            min: "1",
+                            value: "{lockout_minutes}",
+                            oninput: move |e| lockout_minutes.set(e.value()),
+                        }
+                    }
+                }
+                p { class: "text-xs font-mono text-gray-500",
+                    "0 failed sign-ins never locks. Locked users can be unlocked from the user list."
+                }
+            }
+
+            button {
+                class: "retro-btn rounded",
+                disabled: busy(),
+                onclick: handle_save,
+                "Save Security Settings"
+            }
+        }
+    }
+}
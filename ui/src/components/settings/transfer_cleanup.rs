@@ -0,0 +1,151 @@
+use api::{get_transfer_cleanup, run_transfer_cleanup_now, update_transfer_cleanup};
+use dioxus::prelude::*;
+use shared::system::TransferCleanupStatus;
+
+use crate::{friendly_error, Checkbox};
+
+fn format_date(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+/// Schedule for removing finished transfers from slskd, which otherwise
+/// keeps every download it ever made.
+#[component]
+pub fn TransferCleanupManager() -> Element {
+    let mut status = use_signal(TransferCleanupStatus::default);
+    let mut enabled = use_signal(|| false);
+    let mut retention = use_signal(String::new);
+    let mut interval = use_signal(String::new);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+
+    let mut apply = move |s: TransferCleanupStatus| {
+        enabled.set(s.enabled);
+        retention.set(s.retention_hours.to_string());
+        interval.set(s.interval_hours.to_string());
+        status.set(s);
+    };
+
+    use_future(move || async move {
+        match get_transfer_cleanup().await {
+            Ok(s) => apply(s),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    });
+
+    let handle_save = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        let (Ok(retention_hours), Ok(interval_hours)) =
+            (retention().trim().parse::<u32>(), interval().trim().parse::<u32>())
+        else {
+            error.set("Retention and interval must be whole numbers of hours".to_string());
+            return;
+        };
+        busy.set(true);
+        match update_transfer_cleanup(enabled(), retention_hours, interval_hours).await {
+            Ok(s) => {
+                apply(s);
+                success_msg.set("Cleanup schedule saved".to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let handle_run = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        busy.set(true);
+        match run_transfer_cleanup_now().await {
+            Ok(s) => apply(s),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let last_run = match (status().last_run, status().last_removed) {
+        (Some(at), Some(removed)) => {
+            format!("Last run {}: removed {} transfer(s)", format_date(at), removed)
+        }
+        (Some(at), None) => format!("Last run {}", format_date(at)),
+        _ => "Not run since the server started".to_string(),
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 mt-6",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Transfer Cleanup" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Remove finished and failed downloads from slskd's transfer list so it stays fast to poll."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            div { class: "space-y-4 mb-6",
+                div {
+                    class: "flex items-center gap-2 cursor-pointer",
+                    onclick: move |_| enabled.set(!enabled()),
+                    Checkbox { is_selected: enabled() }
+                    span { class: "text-sm text-gray-300 font-mono", "Clean up automatically" }
+                }
+                div { class: "flex flex-wrap gap-6",
+                    div {
+                        label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                            "Keep for (hours)"
+                        }
+                        input {
+                            class: "w-24 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            "type": "number",
+                            min: "0",
+                            value: "{retention}",
+                            oninput: move |e| retention.set(e.value()),
+                        }
+                    }
+                    div {
+                        label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                            "Run every (hours)"
+                        }
+                        input {
+                            class: "w-24 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            "type": "number",
+                            min: "1",
+                            value: "{interval}",
+                            oninput: move |e| interval.set(e.value()),
+                        }
+                    }
+                }
+                p { class: "text-xs font-mono text-gray-500", "{last_run}" }
+                if let Some(err) = status().last_error {
+                    p { class: "text-xs font-mono text-amber-400", "Last run failed: {err}" }
+                }
+            }
+
+            div { class: "flex gap-3",
+                button {
+                    class: "retro-btn rounded",
+                    disabled: busy(),
+                    onclick: handle_save,
+                    "Save Schedule"
+                }
+                button {
+                    class: "text-xs font-mono text-gray-400 hover:text-white underline decoration-dotted cursor-pointer disabled:opacity-50",
+                    disabled: busy(),
+                    onclick: handle_run,
+                    if busy() { "Running..." } else { "Run now" }
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,20 @@
-use api::{delete_user, get_users, register, update_user_password, update_username};
+use api::models::invite::Invite;
+use api::{
+    create_invites, create_password_reset, create_user, delete_invite, delete_user, get_invites,
+    get_users, set_user_role, unlock_user, update_user_email, update_user_password,
+    update_username,
+};
 use dioxus::prelude::*;
+use shared::security::UserRole;
 
 use crate::auth::use_auth;
+use crate::ConfirmModal;
 
 #[derive(Clone, PartialEq)]
 enum EditMode {
     Password,
     Username,
+    Email,
 }
 
 #[component]
@@ -19,22 +27,74 @@ pub fn UserManager() -> Element {
     let mut edit_mode = use_signal(|| EditMode::Password);
     let mut edit_user_password = use_signal(|| "".to_string());
     let mut edit_user_username = use_signal(|| "".to_string());
+    let mut edit_user_email = use_signal(|| "".to_string());
+    // (id, username) of the user awaiting delete confirmation
+    let mut pending_delete = use_signal(|| None::<(String, String)>);
+
+    let mut invites = use_signal(Vec::<Invite>::new);
+    let mut invite_count = use_signal(|| "1".to_string());
+    let mut invite_hours = use_signal(|| "72".to_string());
+    let mut invite_folder_name = use_signal(|| "".to_string());
+    let mut invite_folder_path = use_signal(|| "".to_string());
+    // Links of the invites just created; their tokens can't be fetched again
+    let mut new_links = use_signal(Vec::<String>::new);
 
     let mut error = use_signal(|| "".to_string());
     let mut success_msg = use_signal(|| "".to_string());
     let auth = use_auth();
 
     let fetch_users = move || async move {
-        match auth.call(get_users()).await {
+        match auth.call(get_users).await {
             Ok(fetched_users) => users.set(fetched_users),
             Err(e) => error.set(format!("Failed to fetch users: {e}")),
         }
     };
 
+    let fetch_invites = move || async move {
+        match auth.call(get_invites).await {
+            Ok(list) => invites.set(list),
+            Err(e) => error.set(format!("Failed to fetch invites: {e}")),
+        }
+    };
+
     use_future(move || async move {
         fetch_users().await;
+        fetch_invites().await;
     });
 
+    let handle_create_invites = move |_| async move {
+        error.set("".to_string());
+        success_msg.set("".to_string());
+
+        let (Ok(count), Ok(hours)) = (
+            invite_count().trim().parse::<u32>(),
+            invite_hours().trim().parse::<u32>(),
+        ) else {
+            error.set("Number of invites and validity must be numbers".to_string());
+            return;
+        };
+        let folder_path = Some(invite_folder_path()).filter(|p| !p.trim().is_empty());
+        let folder_name = Some(invite_folder_name()).filter(|n| !n.trim().is_empty());
+
+        match auth
+            .call(|| create_invites(count, hours, folder_name.clone(), folder_path.clone()))
+            .await
+        {
+            Ok(created) => {
+                new_links.set(created.into_iter().map(|c| c.link).collect());
+                fetch_invites().await;
+            }
+            Err(e) => error.set(format!("Failed to create invites: {e}")),
+        }
+    };
+
+    let handle_delete_invite = move |id: String| async move {
+        match auth.call(|| delete_invite(id.clone())).await {
+            Ok(_) => fetch_invites().await,
+            Err(e) => error.set(format!("Failed to revoke invite: {e}")),
+        }
+    };
+
     let handle_create_user = move |_| async move {
         error.set("".to_string());
         success_msg.set("".to_string());
@@ -44,7 +104,10 @@ pub fn UserManager() -> Element {
             return;
         }
 
-        match auth.call(register(new_username(), new_password())).await {
+        match auth
+            .call(|| create_user(new_username(), new_password()))
+            .await
+        {
             Ok(_) => {
                 success_msg.set(format!("User '{}' created successfully", new_username()));
                 new_username.set("".to_string());
@@ -56,7 +119,7 @@ pub fn UserManager() -> Element {
     };
 
     let handle_delete_user = move |id: String| async move {
-        match auth.call(delete_user(id)).await {
+        match auth.call(|| delete_user(id.clone())).await {
             Ok(_) => {
                 success_msg.set("User deleted successfully".to_string());
                 fetch_users().await;
@@ -71,7 +134,7 @@ pub fn UserManager() -> Element {
             return;
         }
         match auth
-            .call(update_user_password(id, edit_user_password()))
+            .call(|| update_user_password(id.clone(), edit_user_password()))
             .await
         {
             Ok(_) => {
@@ -89,7 +152,7 @@ pub fn UserManager() -> Element {
             error.set("Username cannot be empty".to_string());
             return;
         }
-        match auth.call(update_username(edit_user_username())).await {
+        match auth.call(|| update_username(edit_user_username())).await {
             Ok(_) => {
                 success_msg.set("Username updated".to_string());
                 editing_user_id.set(None);
@@ -100,6 +163,52 @@ pub fn UserManager() -> Element {
         }
     };
 
+    let handle_update_email = move |id: String| async move {
+        match auth
+            .call(|| update_user_email(id.clone(), edit_user_email()))
+            .await
+        {
+            Ok(_) => {
+                success_msg.set("Email updated".to_string());
+                editing_user_id.set(None);
+                edit_user_email.set("".to_string());
+                fetch_users().await;
+            }
+            Err(e) => error.set(format!("Failed to update email: {e}")),
+        }
+    };
+
+    let handle_reset_link = move |id: String| async move {
+        error.set("".to_string());
+        match auth.call(|| create_password_reset(id.clone())).await {
+            Ok(link) => {
+                success_msg.set(format!("Reset link, valid for 24 hours: {link}"));
+            }
+            Err(e) => error.set(format!("Failed to create reset link: {e}")),
+        }
+    };
+
+    let handle_set_role = move |id: String, role: UserRole| async move {
+        error.set("".to_string());
+        match auth.call(|| set_user_role(id.clone(), role)).await {
+            Ok(_) => {
+                success_msg.set(format!("Role changed to {}", role.label()));
+                fetch_users().await;
+            }
+            Err(e) => error.set(format!("Failed to change role: {e}")),
+        }
+    };
+
+    let handle_unlock = move |id: String| async move {
+        match auth.call(|| unlock_user(id.clone())).await {
+            Ok(_) => {
+                success_msg.set("User unlocked".to_string());
+                fetch_users().await;
+            }
+            Err(e) => error.set(format!("Failed to unlock user: {e}")),
+        }
+    };
+
     rsx! {
       div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
         h2 { class: "text-xl font-bold mb-4 text-beet-accent font-display",
@@ -113,7 +222,7 @@ pub fn UserManager() -> Element {
           }
         }
         if !success_msg().is_empty() {
-          div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+          div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm break-all",
             "{success_msg}"
           }
         }
@@ -151,6 +260,123 @@ pub fn UserManager() -> Element {
           "Create User"
         }
 
+        // Invite Links
+        h3 { class: "text-lg font-bold mb-2 text-white font-display border-b border-white/10 pb-2",
+          "Invite Links"
+        }
+        p { class: "text-xs font-mono text-gray-400 mb-4",
+          "Send a link instead of a password: whoever opens it picks their own username and password. Give a folder path to create a library folder for them on signup."
+        }
+        div { class: "grid grid-cols-2 md:grid-cols-4 gap-4 mb-4",
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Invites"
+            }
+            input {
+              class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+              value: "{invite_count}",
+              oninput: move |e| invite_count.set(e.value()),
+              "type": "number",
+              min: "1",
+              max: "20",
+            }
+          }
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Valid For"
+            }
+            select {
+              class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+              value: "{invite_hours}",
+              onchange: move |e| invite_hours.set(e.value()),
+              option { value: "24", "1 day" }
+              option { value: "72", "3 days" }
+              option { value: "168", "7 days" }
+              option { value: "720", "30 days" }
+            }
+          }
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Folder Name"
+            }
+            input {
+              class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+              value: "{invite_folder_name}",
+              oninput: move |e| invite_folder_name.set(e.value()),
+              placeholder: "Music",
+              "type": "text",
+            }
+          }
+          div {
+            label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+              "Folder Path"
+            }
+            input {
+              class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+              value: "{invite_folder_path}",
+              oninput: move |e| invite_folder_path.set(e.value()),
+              placeholder: "Optional, e.g. /music/alice",
+              "type": "text",
+            }
+          }
+        }
+        button {
+          class: "retro-btn mb-4 rounded",
+          onclick: handle_create_invites,
+          "Create Invite Links"
+        }
+        if !new_links.read().is_empty() {
+          div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded font-mono text-sm space-y-1",
+            p { class: "text-green-400", "Copy these links now, they won't be shown again:" }
+            for link in new_links() {
+              div { key: "{link}", class: "text-gray-300 break-all select-all", "{link}" }
+            }
+          }
+        }
+        {
+            let now = chrono::Utc::now().timestamp();
+            let pending: Vec<Invite> = invites.read().iter().filter(|i| i.is_pending(now)).cloned().collect();
+            let used: Vec<Invite> = invites.read().iter().filter(|i| i.used_at.is_some()).cloned().collect();
+            rsx! {
+              if !pending.is_empty() || !used.is_empty() {
+                ul { class: "space-y-2 mb-6",
+                  for invite in pending {
+                    {
+                        let id = invite.id.clone();
+                        let expires = chrono::DateTime::from_timestamp(invite.expires_at, 0)
+                            .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+                            .unwrap_or_default();
+                        rsx! {
+                          li {
+                            key: "{invite.id}",
+                            class: "flex justify-between items-center gap-2 bg-white/5 border border-white/5 p-3 rounded text-sm font-mono",
+                            span { class: "text-gray-300",
+                              "Pending, expires {expires}"
+                              if let Some(path) = &invite.folder_path {
+                                span { class: "text-gray-500", " · folder {path}" }
+                              }
+                            }
+                            button {
+                              class: "text-xs font-mono text-gray-400 hover:text-red-400 transition-colors underline decoration-dotted cursor-pointer",
+                              onclick: move |_| handle_delete_invite(id.clone()),
+                              "Revoke"
+                            }
+                          }
+                        }
+                    }
+                  }
+                  for invite in used {
+                    li {
+                      key: "{invite.id}",
+                      class: "bg-white/5 border border-white/5 p-3 rounded text-sm font-mono text-gray-500",
+                      "Used by {invite.used_by.clone().unwrap_or_default()}"
+                    }
+                  }
+                }
+              }
+            }
+        }
+
         // User List
         h3 { class: "text-lg font-bold mb-2 text-white font-display border-b border-white/10 pb-2",
           "Existing Users"
@@ -169,7 +395,16 @@ pub fn UserManager() -> Element {
                         let id_un = user.id.clone();
                         let id_edit_pw = user.id.clone();
                         let id_edit_un = user.id.clone();
+                        let id_email = user.id.clone();
+                        let id_edit_email = user.id.clone();
+                        let id_reset = user.id.clone();
+                        let id_unlock = user.id.clone();
+                        let locked = user.is_locked(chrono::Utc::now().timestamp());
+                        let id_role = user.id.clone();
+                        let other_role = if user.role().is_viewer() { UserRole::Member } else { UserRole::Viewer };
+                        let current_email = user.email.clone().unwrap_or_default();
                         let id_delete = user.id.clone();
+                        let name_delete = user.username.clone();
                         rsx! {
                           li { class: "bg-white/5 border border-white/5 p-3 rounded hover:border-beet-accent/30 transition-colors",
                             if editing_user_id() == Some(user.id.clone()) {
@@ -195,7 +430,7 @@ pub fn UserManager() -> Element {
                                       "[ Cancel ]"
                                     }
                                   }
-                                } else {
+                                } else if edit_mode() == EditMode::Username {
                                   input {
                                     class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent text-white font-mono text-sm",
                                     value: "{edit_user_username}",
@@ -215,12 +450,50 @@ pub fn UserManager() -> Element {
                                       "[ Cancel ]"
                                     }
                                   }
+                                } else {
+                                  input {
+                                    class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent text-white font-mono text-sm",
+                                    value: "{edit_user_email}",
+                                    oninput: move |e| edit_user_email.set(e.value()),
+                                    placeholder: "Email for reset links, empty to remove",
+                                    "type": "email",
+                                  }
+                                  div { class: "flex gap-2 mt-1",
+                                    button {
+                                      class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                                      onclick: move |_| handle_update_email(id_email.clone()),
+                                      "[ Save ]"
+                                    }
+                                    button {
+                                      class: "text-xs uppercase tracking-wider font-bold text-gray-500 hover:text-white transition-colors cursor-pointer",
+                                      onclick: move |_| editing_user_id.set(None),
+                                      "[ Cancel ]"
+                                    }
+                                  }
                                 }
                               }
                             } else {
                               div { class: "flex justify-between items-center flex-wrap gap-2",
-                                span { class: "font-bold text-white font-display", "{user.username}" }
+                                div {
+                                  span { class: "font-bold text-white font-display", "{user.username}" }
+                                  if let Some(email) = &user.email {
+                                    span { class: "ml-2 text-xs font-mono text-gray-500", "{email}" }
+                                  }
+                                  if user.role().is_viewer() {
+                                    span { class: "ml-2 text-xs font-mono text-beet-accent uppercase", "Viewer" }
+                                  }
+                                  if locked {
+                                    span { class: "ml-2 text-xs font-mono text-red-400 uppercase", "Locked" }
+                                  }
+                                }
                                 div { class: "flex gap-3",
+                                  if locked {
+                                    button {
+                                      class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
+                                      onclick: move |_| handle_unlock(id_unlock.clone()),
+                                      "Unlock"
+                                    }
+                                  }
                                   button {
                                     class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
                                     onclick: move |_| {
@@ -239,9 +512,31 @@ pub fn UserManager() -> Element {
                                     },
                                     "Password"
                                   }
+                                  button {
+                                    class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
+                                    onclick: move |_| {
+                                        editing_user_id.set(Some(id_edit_email.clone()));
+                                        edit_mode.set(EditMode::Email);
+                                        edit_user_email.set(current_email.clone());
+                                    },
+                                    "Email"
+                                  }
+                                  button {
+                                    class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
+                                    onclick: move |_| handle_reset_link(id_reset.clone()),
+                                    "Reset Link"
+                                  }
+                                  button {
+                                    class: "text-xs font-mono text-gray-400 hover:text-beet-accent transition-colors underline decoration-dotted cursor-pointer",
+                                    title: if other_role.is_viewer() { "Only browse: no downloads, imports or settings" } else { "Give full access" },
+                                    onclick: move |_| handle_set_role(id_role.clone(), other_role),
+                                    "Make {other_role.label()}"
+                                  }
                                   button {
                                     class: "text-xs font-mono text-gray-400 hover:text-red-400 transition-colors underline decoration-dotted cursor-pointer",
-                                    onclick: move |_| handle_delete_user(id_delete.clone()),
+                                    onclick: move |_| {
+                                        pending_delete.set(Some((id_delete.clone(), name_delete.clone())));
+                                    },
                                     "Delete"
                                   }
                                 }
@@ -253,6 +548,23 @@ pub fn UserManager() -> Element {
             }
           }
         }
+
+        if let Some((id, username)) = pending_delete() {
+          ConfirmModal {
+            message: format!(
+                "Delete user '{username}'? Their folders and settings are removed as well. This cannot be undone.",
+            ),
+            confirm_label: "Delete",
+            danger: true,
+            require_text: Some(username.clone()),
+            on_confirm: move |_| {
+                let id = id.clone();
+                pending_delete.set(None);
+                spawn(async move { handle_delete_user(id).await });
+            },
+            on_cancel: move |_| pending_delete.set(None),
+          }
+        }
       }
     }
 }
@@ -1,5 +1,5 @@
 mod button;
 mod checkbox;
 
-pub use button::Button;
+pub use button::{Button, ButtonVariant};
 pub use checkbox::Checkbox;
@@ -1,5 +1,5 @@
 use dioxus::prelude::*;
-use shared::system::{NavidromeStatus, SystemHealth};
+use shared::system::{BreakerState, NavidromeStatus, SystemHealth};
 
 #[component]
 pub fn SystemStatus(health: SystemHealth, navidrome_status: NavidromeStatus) -> Element {
@@ -10,6 +10,15 @@ pub fn SystemStatus(health: SystemHealth, navidrome_status: NavidromeStatus) ->
         NavidromeStatus::Offline => None,
     };
 
+    let breaker_hint = match health.downloader_breaker {
+        BreakerState::Closed => None,
+        BreakerState::Open { retry_in_secs } => Some(format!(
+            "Too many failed requests to slskd, retrying in {}s",
+            retry_in_secs
+        )),
+        BreakerState::HalfOpen => Some("Checking whether slskd is back".to_string()),
+    };
+
     let user_not_linked = matches!(
         navidrome_status,
         NavidromeStatus::InvalidCredentials | NavidromeStatus::Unknown
@@ -17,7 +26,9 @@ pub fn SystemStatus(health: SystemHealth, navidrome_status: NavidromeStatus) ->
 
     rsx! {
       div { class: "flex justify-center gap-6 text-xs font-mono text-gray-500 flex-wrap",
-        span { class: "flex items-center gap-2",
+        span {
+          class: "flex items-center gap-2",
+          title: breaker_hint.clone().unwrap_or_default(),
           span {
             class: format!(
                 "w-2 h-2 rounded-full {}",
@@ -26,6 +37,8 @@ pub fn SystemStatus(health: SystemHealth, navidrome_status: NavidromeStatus) ->
           }
           if health.downloader_online {
             "DOWNLOADER ONLINE"
+          } else if breaker_hint.is_some() {
+            "DOWNLOADER BACKING OFF"
           } else {
             "DOWNLOADER OFFLINE"
           }
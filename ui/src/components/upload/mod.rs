@@ -0,0 +1,235 @@
+use api::models::folder::Folder;
+use api::{ImportUploadRequest, UPLOAD_AUDIO_EXTENSIONS};
+use dioxus::html::FileData;
+use dioxus::prelude::*;
+
+use crate::{friendly_error, use_auth, Checkbox};
+
+mod review;
+pub use review::{ImportReviewSignal, ImportReviews};
+
+#[derive(Clone, PartialEq)]
+enum UploadStatus {
+    Pending,
+    Uploading,
+    Done,
+    Failed(String),
+}
+
+#[derive(Clone, PartialEq)]
+struct StagedFile {
+    name: String,
+    size: u64,
+    status: UploadStatus,
+}
+
+fn is_audio(name: &str) -> bool {
+    name.rsplit_once('.')
+        .map(|(_, ext)| UPLOAD_AUDIO_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn set_status(mut files: Signal<Vec<StagedFile>>, index: usize, status: UploadStatus) {
+    if let Some(file) = files.write().get_mut(index) {
+        file.status = status;
+    }
+}
+
+fn format_mb(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+/// Drop zone for uploading local audio files (e.g. Bandcamp purchases) and
+/// running them through the beets import pipeline.
+#[component]
+pub fn LocalImport() -> Element {
+    let auth = use_auth();
+    let mut files = use_signal(Vec::<StagedFile>::new);
+    let mut upload_id = use_signal(|| uuid::Uuid::new_v4().to_string());
+    let mut folders = use_signal(Vec::<Folder>::new);
+    let mut selected_folder = use_signal(String::new);
+    let mut as_album = use_signal(|| true);
+    let mut is_dragging = use_signal(|| false);
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut success_msg = use_signal(String::new);
+
+    use_future(move || async move {
+        if let Ok(user_folders) = auth.call(api::get_user_folders).await {
+            if user_folders.len() == 1 {
+                selected_folder.set(user_folders[0].id.clone());
+            }
+            folders.set(user_folders);
+        }
+    });
+
+    let upload_files = move |dropped: Vec<FileData>| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        is_busy.set(true);
+
+        for file in dropped {
+            let name = file.name();
+            if !is_audio(&name) {
+                continue;
+            }
+            let index = files.read().len();
+            files.write().push(StagedFile {
+                name: name.clone(),
+                size: file.size(),
+                status: UploadStatus::Pending,
+            });
+
+            set_status(files, index, UploadStatus::Uploading);
+            let status = match auth
+                .call(|| api::upload_file(upload_id(), name.clone(), file.clone().into()))
+                .await
+            {
+                Ok(_) => UploadStatus::Done,
+                Err(e) => UploadStatus::Failed(friendly_error(&e)),
+            };
+            set_status(files, index, status);
+        }
+
+        is_busy.set(false);
+    };
+
+    let handle_import = move |_| async move {
+        error.set(String::new());
+        success_msg.set(String::new());
+        if selected_folder().is_empty() {
+            error.set("Select a target folder first".to_string());
+            return;
+        }
+        is_busy.set(true);
+        let req = ImportUploadRequest {
+            upload_id: upload_id(),
+            folder_id: selected_folder(),
+            as_album: as_album(),
+        };
+        match auth.call(|| api::import_upload(req.clone())).await {
+            Ok(()) => {
+                success_msg.set(
+                    "Import started. Follow its progress in the downloads panel.".to_string(),
+                );
+                files.set(Vec::new());
+                upload_id.set(uuid::Uuid::new_v4().to_string());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        is_busy.set(false);
+    };
+
+    let handle_discard = move |_| async move {
+        let _ = auth.call(|| api::discard_upload(upload_id())).await;
+        files.set(Vec::new());
+        upload_id.set(uuid::Uuid::new_v4().to_string());
+    };
+
+    let uploaded = files
+        .read()
+        .iter()
+        .filter(|f| f.status == UploadStatus::Done)
+        .count();
+    let drop_class = if is_dragging() {
+        "border-beet-accent bg-beet-accent/5"
+    } else {
+        "border-white/10"
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 space-y-4",
+            h2 { class: "text-xl font-bold text-beet-accent font-display", "Import Local Files" }
+
+            if !error().is_empty() {
+                div { class: "p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !success_msg().is_empty() {
+                div { class: "p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{success_msg}"
+                }
+            }
+
+            label {
+                class: "block border-2 border-dashed rounded-lg p-10 text-center cursor-pointer transition-colors {drop_class}",
+                ondragover: move |evt| {
+                    evt.prevent_default();
+                    is_dragging.set(true);
+                },
+                ondragleave: move |_| is_dragging.set(false),
+                ondrop: move |evt| {
+                    evt.prevent_default();
+                    is_dragging.set(false);
+                    spawn(upload_files(evt.files()));
+                },
+                p { class: "text-gray-300 font-mono text-sm", "Drop audio files here, or click to pick them" }
+                p { class: "text-gray-500 font-mono text-xs mt-1", {UPLOAD_AUDIO_EXTENSIONS.join(", ")} }
+                input {
+                    class: "hidden",
+                    "type": "file",
+                    multiple: true,
+                    accept: "audio/*",
+                    onchange: move |evt| {
+                        spawn(upload_files(evt.files()));
+                    },
+                }
+            }
+
+            if !files.read().is_empty() {
+                ul { class: "space-y-1",
+                    for file in files.read().iter().cloned() {
+                        li { class: "flex justify-between gap-2 text-sm font-mono bg-white/5 rounded px-3 py-1.5",
+                            span { class: "text-gray-300 truncate", "{file.name}" }
+                            match file.status {
+                                UploadStatus::Pending => rsx! { span { class: "text-gray-500 shrink-0", "waiting" } },
+                                UploadStatus::Uploading => rsx! { span { class: "text-beet-accent shrink-0", "uploading" } },
+                                UploadStatus::Done => rsx! { span { class: "text-beet-leaf shrink-0", {format_mb(file.size)} } },
+                                UploadStatus::Failed(msg) => rsx! { span { class: "text-red-400 shrink-0", title: "{msg}", "failed" } },
+                            }
+                        }
+                    }
+                }
+
+                div { class: "grid grid-cols-1 md:grid-cols-2 gap-4 items-end",
+                    div {
+                        label { class: "block text-xs font-mono text-gray-400 mb-1 uppercase tracking-wider",
+                            "Target Folder"
+                        }
+                        select {
+                            class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono",
+                            value: "{selected_folder}",
+                            onchange: move |e| selected_folder.set(e.value()),
+                            option { value: "", disabled: true, "Select a folder" }
+                            for folder in folders.read().iter() {
+                                option { value: "{folder.id}", "{folder.name}" }
+                            }
+                        }
+                    }
+                    div {
+                        class: "flex items-center gap-2 cursor-pointer text-sm font-mono text-gray-300",
+                        onclick: move |_| as_album.set(!as_album()),
+                        Checkbox { is_selected: as_album() }
+                        "Import as album"
+                    }
+                }
+
+                div { class: "flex justify-end gap-2",
+                    button {
+                        class: "px-3 py-1.5 text-sm font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
+                        disabled: is_busy(),
+                        onclick: handle_discard,
+                        "Discard"
+                    }
+                    button {
+                        class: "retro-btn rounded text-sm",
+                        disabled: is_busy() || uploaded == 0,
+                        onclick: handle_import,
+                        "Import {uploaded} file(s)"
+                    }
+                }
+            }
+        }
+    }
+}
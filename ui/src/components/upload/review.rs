@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use api::{apply_import_review, get_pending_imports};
+use dioxus::prelude::*;
+use shared::download::{FileTags, ImportEdits, PendingImport};
+
+use crate::{friendly_error, use_auth};
+
+/// Latest pending import pushed over the downloads websocket.
+#[derive(Clone, Copy)]
+pub struct ImportReviewSignal(pub Signal<Option<PendingImport>>);
+
+/// First value of a tag shared by the files, used to prefill the album fields.
+fn common_tag(files: &[FileTags], get: impl Fn(&FileTags) -> Option<String>) -> String {
+    files.iter().find_map(get).unwrap_or_default()
+}
+
+fn file_name(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).to_string()
+}
+
+/// Downloads held back for tag review, each with an editor.
+#[component]
+pub fn ImportReviews() -> Element {
+    let auth = use_auth();
+    let mut pending = use_signal(Vec::<PendingImport>::new);
+    let review_signal = try_use_context::<ImportReviewSignal>();
+
+    let mut fetch_pending = move || {
+        spawn(async move {
+            if let Ok(list) = auth.call(get_pending_imports).await {
+                pending.set(list);
+            }
+        });
+    };
+
+    use_effect(move || {
+        // Refetch whenever a new review is announced
+        if let Some(signal) = review_signal {
+            let _ = signal.0.read();
+        }
+        fetch_pending();
+    });
+
+    if pending.read().is_empty() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div { class: "space-y-4",
+            for item in pending.read().iter().cloned() {
+                ReviewCard {
+                    key: "{item.id}",
+                    pending: item,
+                    on_done: move |_| fetch_pending(),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Props, Clone, PartialEq)]
+struct ReviewCardProps {
+    pending: PendingImport,
+    on_done: EventHandler<()>,
+}
+
+#[component]
+fn ReviewCard(props: ReviewCardProps) -> Element {
+    let auth = use_auth();
+    let files = props.pending.files.clone();
+
+    let initial_artist = common_tag(&files, |f| f.artist.clone());
+    let initial_album_artist = common_tag(&files, |f| f.album_artist.clone());
+    let initial_album = common_tag(&files, |f| f.album.clone());
+    let initial_year = files
+        .iter()
+        .find_map(|f| f.year)
+        .map(|y| y.to_string())
+        .unwrap_or_default();
+
+    let mut artist = use_signal(|| initial_artist.clone());
+    let mut album_artist = use_signal(|| initial_album_artist.clone());
+    let mut album = use_signal(|| initial_album.clone());
+    let mut year = use_signal(|| initial_year.clone());
+    let mut cover_url = use_signal(String::new);
+    let mut titles = use_signal(|| {
+        files
+            .iter()
+            .map(|f| (f.path.clone(), f.title.clone().unwrap_or_default()))
+            .collect::<HashMap<_, _>>()
+    });
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+
+    let review_id = props.pending.id.clone();
+    let original_files = files.clone();
+    let on_done = props.on_done;
+    let mut submit = move |edited: bool| {
+        let review_id = review_id.clone();
+        let original_files = original_files.clone();
+        let changed = |value: String, initial: &str| {
+            let value = value.trim().to_string();
+            (value != initial).then_some(value)
+        };
+
+        let edits = if edited {
+            let year_value = year().trim().to_string();
+            let parsed_year = if year_value.is_empty() || year_value == initial_year {
+                None
+            } else {
+                match year_value.parse::<u32>() {
+                    Ok(y) => Some(y),
+                    Err(_) => {
+                        error.set("Year must be a number".to_string());
+                        return;
+                    }
+                }
+            };
+            ImportEdits {
+                artist: changed(artist(), &initial_artist),
+                album_artist: changed(album_artist(), &initial_album_artist),
+                album: changed(album(), &initial_album),
+                year: parsed_year,
+                cover_url: Some(cover_url().trim().to_string()).filter(|u| !u.is_empty()),
+                titles: titles
+                    .read()
+                    .iter()
+                    .filter(|(path, title)| {
+                        let before = original_files
+                            .iter()
+                            .find(|f| &f.path == *path)
+                            .and_then(|f| f.title.clone())
+                            .unwrap_or_default();
+                        title.trim() != before
+                    })
+                    .map(|(path, title)| (path.clone(), title.trim().to_string()))
+                    .collect(),
+                ..Default::default()
+            }
+        } else {
+            ImportEdits::default()
+        };
+
+        spawn(async move {
+            error.set(String::new());
+            is_busy.set(true);
+            match auth
+                .call(|| apply_import_review(review_id.clone(), edits.clone()))
+                .await
+            {
+                Ok(()) => on_done.call(()),
+                Err(e) => error.set(friendly_error(&e)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let mut submit_as_is = submit.clone();
+    let file_count = files.len();
+    let with_cover = files.iter().filter(|f| f.has_cover).count();
+    let preview = cover_url().trim().to_string();
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10 space-y-4",
+            div {
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Review: {props.pending.label}" }
+                p { class: "text-xs font-mono text-gray-500",
+                    "{file_count} file(s), {with_cover} with cover art · into {props.pending.target_path}"
+                }
+            }
+
+            if !error().is_empty() {
+                div { class: "p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            div { class: "grid grid-cols-1 md:grid-cols-2 gap-4 font-mono text-sm",
+                div {
+                    label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Artist" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                        value: "{artist}",
+                        oninput: move |e| artist.set(e.value()),
+                        "type": "text",
+                    }
+                }
+                div {
+                    label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Album artist" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                        value: "{album_artist}",
+                        oninput: move |e| album_artist.set(e.value()),
+                        "type": "text",
+                    }
+                }
+                div {
+                    label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Album" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                        value: "{album}",
+                        oninput: move |e| album.set(e.value()),
+                        "type": "text",
+                    }
+                }
+                div {
+                    label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Year" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                        value: "{year}",
+                        oninput: move |e| year.set(e.value()),
+                        "type": "text",
+                        inputmode: "numeric",
+                    }
+                }
+            }
+
+            div { class: "flex gap-4 items-start font-mono text-sm",
+                div { class: "flex-1",
+                    label { class: "block text-xs text-gray-400 mb-1 uppercase tracking-wider", "Cover image URL" }
+                    input {
+                        class: "w-full p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                        value: "{cover_url}",
+                        oninput: move |e| cover_url.set(e.value()),
+                        placeholder: "Leave empty to keep the current cover",
+                        "type": "url",
+                    }
+                }
+                if !preview.is_empty() {
+                    img {
+                        class: "w-20 h-20 object-cover rounded border border-white/10 shrink-0",
+                        src: "{preview}",
+                        alt: "Cover preview",
+                    }
+                }
+            }
+
+            div { class: "space-y-1",
+                p { class: "text-xs font-mono text-gray-400 uppercase tracking-wider", "Track titles" }
+                for file in files.iter().cloned() {
+                    {
+                        let name = file_name(&file.path);
+                        let track = file.track.map(|t| format!("{:02}", t)).unwrap_or_default();
+                        let current = titles.read().get(&file.path).cloned().unwrap_or_default();
+                        let path = file.path.clone();
+                        rsx! {
+                            div {
+                                key: "{file.path}",
+                                class: "flex items-center gap-2 font-mono text-sm",
+                                span { class: "w-6 text-gray-500 shrink-0", "{track}" }
+                                input {
+                                    class: "flex-1 p-1.5 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                                    value: "{current}",
+                                    oninput: move |e| {
+                                        titles.write().insert(path.clone(), e.value());
+                                    },
+                                    "type": "text",
+                                }
+                                span { class: "text-xs text-gray-500 truncate max-w-[40%]", title: "{file.path}", "{name}" }
+                            }
+                        }
+                    }
+                }
+            }
+
+            div { class: "flex justify-end gap-2",
+                button {
+                    class: "px-3 py-1.5 text-sm font-mono text-gray-400 hover:text-white transition-colors cursor-pointer",
+                    disabled: is_busy(),
+                    onclick: move |_| submit_as_is(false),
+                    "Import as-is"
+                }
+                button {
+                    class: "retro-btn rounded text-sm",
+                    disabled: is_busy(),
+                    onclick: move |_| submit(true),
+                    "Save tags & import"
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use api::{add_to_wishlist, parse_chart, resolve_chart_entries, CHART_RESOLVE_BATCH};
+use dioxus::prelude::*;
+use shared::wishlist::{ChartMatch, NewWishlistItem};
+
+use crate::{friendly_error, use_auth, Checkbox};
+
+fn to_item(m: &ChartMatch) -> NewWishlistItem {
+    match &m.album {
+        Some(album) => NewWishlistItem {
+            artist: album.artist.clone(),
+            title: album.title.clone(),
+            release_mbid: album.mbid.clone().or_else(|| Some(album.id.clone())),
+        },
+        None => NewWishlistItem {
+            artist: m.entry.artist.clone(),
+            title: m.entry.title.clone(),
+            release_mbid: None,
+        },
+    }
+}
+
+/// Bulk-add a "best of" chart to the wishlist: paste a list or a chart
+/// link, check the MusicBrainz matches, then add the selected rows.
+#[component]
+pub fn ChartImport(on_added: EventHandler<()>) -> Element {
+    let auth = use_auth();
+    let mut source = use_signal(String::new);
+    let mut matches = use_signal(Vec::<ChartMatch>::new);
+    // Indices of the rows to add
+    let mut selected = use_signal(Vec::<usize>::new);
+    let mut progress = use_signal(|| None::<(usize, usize)>);
+    let mut error = use_signal(String::new);
+    let mut notice = use_signal(String::new);
+
+    let handle_parse = move |_| async move {
+        error.set(String::new());
+        notice.set(String::new());
+        if source().trim().is_empty() {
+            return;
+        }
+        let entries = match auth.call(|| parse_chart(source())).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                error.set(friendly_error(&e));
+                return;
+            }
+        };
+
+        matches.set(Vec::new());
+        selected.set(Vec::new());
+        let total = entries.len();
+        progress.set(Some((0, total)));
+        for batch in entries.chunks(CHART_RESOLVE_BATCH) {
+            match auth.call(|| resolve_chart_entries(batch.to_vec())).await {
+                Ok(resolved) => {
+                    let offset = matches.read().len();
+                    // Matched rows start selected; unmatched ones are opt-in
+                    selected.write().extend(
+                        resolved
+                            .iter()
+                            .enumerate()
+                            .filter(|(_, m)| m.album.is_some())
+                            .map(|(i, _)| offset + i),
+                    );
+                    matches.write().extend(resolved);
+                    progress.set(Some((matches.read().len(), total)));
+                }
+                Err(e) => {
+                    error.set(friendly_error(&e));
+                    break;
+                }
+            }
+        }
+        progress.set(None);
+    };
+
+    let handle_add = move |_| async move {
+        error.set(String::new());
+        let items: Vec<NewWishlistItem> = {
+            let matches = matches.read();
+            let selected = selected.read();
+            selected.iter().filter_map(|i| matches.get(*i)).map(to_item).collect()
+        };
+        if items.is_empty() {
+            return;
+        }
+        let label = source().trim().lines().next().unwrap_or_default().to_string();
+        let chart = label.starts_with("http").then_some(label);
+        match auth
+            .call(|| add_to_wishlist(items.clone(), chart.clone()))
+            .await
+        {
+            Ok(added) => {
+                notice.set(format!("Added {} album(s) to the wishlist", added));
+                matches.set(Vec::new());
+                selected.set(Vec::new());
+                source.set(String::new());
+                on_added.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    let is_resolving = progress().is_some();
+    let selected_count = selected.read().len();
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Import a Chart" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Paste an Album of the Year or RateYourMusic chart link, or a list with one "
+                code { class: "text-beet-leaf", "Artist - Album" }
+                " per line."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{notice}"
+                }
+            }
+
+            textarea {
+                class: "w-full h-32 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm",
+                value: "{source}",
+                disabled: is_resolving,
+                oninput: move |e| source.set(e.value()),
+                placeholder: "https://www.albumoftheyear.org/ratings/...\n\nor\n\n1. Slowdive - Souvlaki (1993)\n2. Ride - Nowhere (1990)",
+            }
+            div { class: "flex justify-end mt-2",
+                button {
+                    class: "retro-btn rounded",
+                    disabled: is_resolving,
+                    onclick: handle_parse,
+                    "Find Albums"
+                }
+            }
+
+            if let Some((done, total)) = progress() {
+                p { class: "mt-4 text-sm font-mono text-gray-400 animate-pulse",
+                    "Matching on MusicBrainz... {done}/{total}"
+                }
+            }
+
+            if !matches.read().is_empty() {
+                div { class: "mt-6 space-y-2",
+                    div { class: "flex justify-between items-center",
+                        span { class: "text-xs font-mono text-gray-400 uppercase tracking-wider",
+                            "{selected_count} of {matches.read().len()} selected"
+                        }
+                        button {
+                            class: "retro-btn rounded",
+                            disabled: is_resolving || selected_count == 0,
+                            onclick: handle_add,
+                            "Add to Wishlist"
+                        }
+                    }
+                    ul { class: "space-y-1 max-h-[28rem] overflow-y-auto",
+                        for (i, m) in matches.read().iter().cloned().enumerate() {
+                            {
+                                let is_selected = selected.read().contains(&i);
+                                let position = m.entry.position.map(|p| format!("{}.", p)).unwrap_or_default();
+                                rsx! {
+                                    li {
+                                        key: "{i}",
+                                        class: "flex items-center gap-3 bg-white/5 border border-white/5 p-2 rounded cursor-pointer hover:bg-white/10",
+                                        onclick: move |_| {
+                                            let mut selected = selected.write();
+                                            if let Some(pos) = selected.iter().position(|s| *s == i) {
+                                                selected.remove(pos);
+                                            } else {
+                                                selected.push(i);
+                                            }
+                                        },
+                                        Checkbox { is_selected }
+                                        span { class: "w-8 text-right text-xs font-mono text-gray-500", "{position}" }
+                                        div { class: "flex-1 min-w-0",
+                                            div { class: "text-sm text-white truncate",
+                                                "{m.entry.artist} – {m.entry.title}"
+                                            }
+                                            match &m.album {
+                                                Some(album) => rsx! {
+                                                    div { class: "text-xs font-mono text-beet-leaf truncate",
+                                                        "MusicBrainz: {album.artist} – {album.title}"
+                                                        if let Some(date) = &album.release_date {
+                                                            " ({date})"
+                                                        }
+                                                    }
+                                                },
+                                                None => rsx! {
+                                                    div { class: "text-xs font-mono text-yellow-500", "No MusicBrainz match, added by name" }
+                                                },
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
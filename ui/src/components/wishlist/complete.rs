@@ -0,0 +1,204 @@
+use api::models::folder::Folder;
+use api::{find_missing_tracks, get_library_albums, get_user_folders, remove_library_album};
+use dioxus::prelude::*;
+use shared::library::{AlbumCompletion, LibraryAlbum};
+
+use crate::{friendly_error, use_auth, Checkbox, ConfirmModal};
+
+/// Albums of a library folder, each checked on demand against its release
+/// for missing tracks, or removed. `on_complete` receives the missing
+/// tracks to search for and the directory they should end up in.
+#[component]
+pub fn CompleteAlbums(on_complete: EventHandler<AlbumCompletion>) -> Element {
+    let auth = use_auth();
+    let mut folders = use_signal(Vec::<Folder>::new);
+    let mut folder_id = use_signal(String::new);
+    let mut albums = use_signal(|| None::<Vec<LibraryAlbum>>);
+    let mut completions = use_signal(Vec::<AlbumCompletion>::new);
+    let mut checking = use_signal(|| None::<String>);
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut pending_remove = use_signal(|| None::<LibraryAlbum>);
+    let mut trash = use_signal(|| true);
+
+    use_future(move || async move {
+        if let Ok(list) = auth.call(get_user_folders).await {
+            if let Some(first) = list.first() {
+                folder_id.set(first.id.clone());
+            }
+            folders.set(list);
+        }
+    });
+
+    let handle_list = move |_| async move {
+        error.set(String::new());
+        loading.set(true);
+        completions.set(Vec::new());
+        match auth.call(|| get_library_albums(folder_id())).await {
+            Ok(list) => albums.set(Some(list)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        loading.set(false);
+    };
+
+    let handle_check = move |path: String| async move {
+        error.set(String::new());
+        checking.set(Some(path.clone()));
+        match auth
+            .call(|| find_missing_tracks(folder_id(), path.clone()))
+            .await
+        {
+            Ok(completion) => completions.write().push(completion),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        checking.set(None);
+    };
+
+    let handle_remove = move |path: String| async move {
+        error.set(String::new());
+        match auth
+            .call(|| remove_library_album(folder_id(), path.clone(), trash()))
+            .await
+        {
+            Ok(()) => {
+                if let Some(list) = albums.write().as_mut() {
+                    list.retain(|a| a.path != path);
+                }
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            div { class: "flex justify-between items-center gap-4 mb-2",
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Complete Your Albums" }
+                div { class: "flex gap-2",
+                    select {
+                        class: "p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-gray-300 font-mono text-sm",
+                        onchange: move |e| {
+                            folder_id.set(e.value());
+                            albums.set(None);
+                        },
+                        for folder in folders() {
+                            option {
+                                value: "{folder.id}",
+                                selected: folder.id == folder_id(),
+                                "{folder.name}"
+                            }
+                        }
+                    }
+                    button {
+                        class: "retro-btn rounded text-sm",
+                        disabled: loading() || folder_id().is_empty(),
+                        onclick: handle_list,
+                        if loading() { "Loading..." } else { "List Albums" }
+                    }
+                }
+            }
+            p { class: "text-xs font-mono text-gray-400 mb-2",
+                "Compare an album with its release to find the tracks you don't have. They are searched for and added to the album's folder."
+            }
+            div {
+                class: "flex items-center gap-2 cursor-pointer mb-4",
+                onclick: move |_| trash.set(!trash()),
+                Checkbox { is_selected: trash() }
+                span { class: "text-xs text-gray-400 font-mono",
+                    "Move removed albums to the folder's .trash instead of deleting them"
+                }
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            match albums() {
+                None => rsx! {},
+                Some(list) if list.is_empty() => rsx! {
+                    p { class: "text-gray-500 font-mono italic text-sm", "No albums in this folder yet." }
+                },
+                Some(list) => rsx! {
+                    ul { class: "space-y-2 max-h-[32rem] overflow-y-auto",
+                        for album in list {
+                            {
+                                let completion = completions
+                                    .read()
+                                    .iter()
+                                    .find(|c| c.path == album.path)
+                                    .cloned();
+                                let is_checking = checking().as_deref() == Some(album.path.as_str());
+                                let path = album.path.clone();
+                                let removed = album.clone();
+                                rsx! {
+                                    li {
+                                        key: "{album.path}",
+                                        class: "flex justify-between items-center gap-4 bg-white/5 border border-white/5 p-3 rounded",
+                                        div { class: "min-w-0",
+                                            div { class: "font-bold text-white font-display truncate", "{album.album}" }
+                                            div { class: "text-xs font-mono text-gray-500 truncate",
+                                                "{album.artist} · {album.track_count} tracks"
+                                            }
+                                        }
+                                        div { class: "shrink-0 flex items-center gap-3",
+                                            button {
+                                                class: "text-xs uppercase tracking-wider font-bold text-gray-500 hover:text-red-400 transition-colors cursor-pointer",
+                                                onclick: move |_| pending_remove.set(Some(removed.clone())),
+                                                "[ Remove ]"
+                                            }
+                                            match completion {
+                                                None => rsx! {
+                                                    button {
+                                                        class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer disabled:opacity-50",
+                                                        disabled: checking().is_some(),
+                                                        onclick: move |_| {
+                                                            spawn(handle_check(path.clone()));
+                                                        },
+                                                        if is_checking { "[ Checking... ]" } else { "[ Check ]" }
+                                                    }
+                                                },
+                                                Some(completion) if completion.missing.is_empty() => rsx! {
+                                                    span { class: "text-xs uppercase tracking-wider font-bold text-gray-500", "[ Complete ]" }
+                                                },
+                                                Some(completion) => {
+                                                    let count = completion.missing.len();
+                                                    rsx! {
+                                                        button {
+                                                            class: "text-xs uppercase tracking-wider font-bold text-beet-accent hover:text-white transition-colors cursor-pointer",
+                                                            title: completion.missing.iter().map(|t| t.title.as_str()).collect::<Vec<_>>().join("\n"),
+                                                            onclick: move |_| on_complete.call(completion.clone()),
+                                                            "[ Get {count} missing ]"
+                                                        }
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+
+            if let Some(album) = pending_remove() {
+                ConfirmModal {
+                    message: if trash() {
+                        format!("Remove '{}' by {} from the library? Its files are moved to the folder's .trash.", album.album, album.artist)
+                    } else {
+                        format!("Remove '{}' by {} from the library? Its files are deleted from disk.", album.album, album.artist)
+                    },
+                    confirm_label: "Remove",
+                    danger: true,
+                    on_confirm: move |_| {
+                        let path = album.path.clone();
+                        pending_remove.set(None);
+                        spawn(async move { handle_remove(path).await });
+                    },
+                    on_cancel: move |_| pending_remove.set(None),
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use api::models::folder::Folder;
+use api::{
+    add_to_wishlist, get_label_releases, get_user_folders, queue_label_releases, MAX_LABEL_QUEUE,
+};
+use dioxus::prelude::*;
+use shared::wishlist::{LabelFilter, LabelRelease, NewWishlistItem};
+
+use crate::{friendly_error, use_auth, Checkbox};
+
+/// Primary types offered in the filter, as MusicBrainz names them.
+const PRIMARY_TYPES: [&str; 4] = ["Album", "EP", "Single", "Broadcast"];
+
+fn parse_year(value: &str) -> Option<u32> {
+    value.trim().parse().ok()
+}
+
+/// Browse a record label's catalog, filter it by year and type, then add
+/// the selected releases to the wishlist or download them one by one.
+#[component]
+pub fn LabelCatalog(on_added: EventHandler<()>) -> Element {
+    let auth = use_auth();
+    let mut label = use_signal(String::new);
+    let mut releases = use_signal(Vec::<LabelRelease>::new);
+    let mut filter = use_signal(LabelFilter::default);
+    // Release MBIDs of the selected rows
+    let mut selected = use_signal(Vec::<String>::new);
+    let mut folders = use_signal(Vec::<Folder>::new);
+    let mut folder_id = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut notice = use_signal(String::new);
+
+    use_future(move || async move {
+        if let Ok(list) = auth.call(get_user_folders).await {
+            if let Some(first) = list.first() {
+                folder_id.set(first.id.clone());
+            }
+            folders.set(list);
+        }
+    });
+
+    let handle_lookup = move |_| async move {
+        error.set(String::new());
+        notice.set(String::new());
+        if label().trim().is_empty() {
+            return;
+        }
+        busy.set(true);
+        match auth.call(|| get_label_releases(label())).await {
+            Ok(list) => {
+                if list.is_empty() {
+                    notice.set("MusicBrainz lists no releases for this label".to_string());
+                }
+                selected.set(Vec::new());
+                releases.set(list);
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let chosen = move || -> Vec<LabelRelease> {
+        let selected = selected.read();
+        releases
+            .read()
+            .iter()
+            .filter(|r| filter.read().matches(r) && selected.contains(&r.release_mbid))
+            .cloned()
+            .collect()
+    };
+
+    let handle_wish = move |_| async move {
+        error.set(String::new());
+        let items: Vec<NewWishlistItem> = chosen()
+            .into_iter()
+            .map(|r| NewWishlistItem {
+                artist: r.artist,
+                title: r.title,
+                release_mbid: Some(r.release_mbid),
+            })
+            .collect();
+        if items.is_empty() {
+            return;
+        }
+        busy.set(true);
+        match auth
+            .call(|| add_to_wishlist(items.clone(), Some("label".to_string())))
+            .await
+        {
+            Ok(added) => {
+                notice.set(format!("Added {} album(s) to the wishlist", added));
+                selected.set(Vec::new());
+                on_added.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let handle_download = move |_| async move {
+        error.set(String::new());
+        let chosen = chosen();
+        if chosen.is_empty() {
+            return;
+        }
+        busy.set(true);
+        match auth
+            .call(|| queue_label_releases(chosen.clone(), folder_id()))
+            .await
+        {
+            Ok(waiting) => {
+                notice.set(format!(
+                    "{} release(s) waiting; one download starts every 30 seconds",
+                    waiting
+                ));
+                selected.set(Vec::new());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        busy.set(false);
+    };
+
+    let shown: Vec<LabelRelease> = releases
+        .read()
+        .iter()
+        .filter(|r| filter.read().matches(r))
+        .cloned()
+        .collect();
+    let selected_count = shown
+        .iter()
+        .filter(|r| selected.read().contains(&r.release_mbid))
+        .count();
+    let all_shown_selected = !shown.is_empty() && selected_count == shown.len();
+    let shown_ids: Vec<String> = shown.iter().map(|r| r.release_mbid.clone()).collect();
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            h2 { class: "text-xl font-bold mb-1 text-beet-accent font-display", "Label Catalog" }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Paste a MusicBrainz label link to list its releases, then wish for or download a selection."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{notice}"
+                }
+            }
+
+            div { class: "flex gap-2",
+                input {
+                    class: "flex-1 p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white font-mono text-sm",
+                    value: "{label}",
+                    placeholder: "https://musicbrainz.org/label/...",
+                    oninput: move |e| label.set(e.value()),
+                }
+                button {
+                    class: "retro-btn rounded",
+                    disabled: busy(),
+                    onclick: handle_lookup,
+                    "List Releases"
+                }
+            }
+
+            if !releases.read().is_empty() {
+                div { class: "mt-6 space-y-2",
+                    div { class: "flex flex-wrap items-center gap-2 text-sm font-mono",
+                        input {
+                            class: "w-20 p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                            placeholder: "From",
+                            oninput: move |e| filter.write().from_year = parse_year(&e.value()),
+                        }
+                        input {
+                            class: "w-20 p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white",
+                            placeholder: "To",
+                            oninput: move |e| filter.write().to_year = parse_year(&e.value()),
+                        }
+                        select {
+                            class: "p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-gray-300",
+                            onchange: move |e| {
+                                let value = e.value();
+                                filter.write().primary_type = (!value.is_empty()).then_some(value);
+                            },
+                            option { value: "", "Any type" }
+                            for kind in PRIMARY_TYPES {
+                                option { value: "{kind}", "{kind}" }
+                            }
+                        }
+                    }
+                    div { class: "flex flex-wrap justify-between items-center gap-2",
+                        div {
+                            class: "flex items-center gap-2 cursor-pointer",
+                            onclick: move |_| {
+                                let mut selected = selected.write();
+                                if all_shown_selected {
+                                    selected.retain(|id| !shown_ids.contains(id));
+                                } else {
+                                    for id in &shown_ids {
+                                        if !selected.contains(id) {
+                                            selected.push(id.clone());
+                                        }
+                                    }
+                                }
+                            },
+                            Checkbox { is_selected: all_shown_selected }
+                            span { class: "text-xs font-mono text-gray-400 uppercase tracking-wider",
+                                "{selected_count} of {shown.len()} selected"
+                            }
+                        }
+                        div { class: "flex items-center gap-2",
+                            select {
+                                class: "p-1 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-gray-300 font-mono text-sm",
+                                onchange: move |e| folder_id.set(e.value()),
+                                for folder in folders() {
+                                    option {
+                                        value: "{folder.id}",
+                                        selected: folder.id == folder_id(),
+                                        "{folder.name}"
+                                    }
+                                }
+                            }
+                            button {
+                                class: "retro-btn rounded text-sm",
+                                disabled: busy() || selected_count == 0,
+                                onclick: handle_wish,
+                                "Add to Wishlist"
+                            }
+                            button {
+                                class: "retro-btn rounded text-sm",
+                                disabled: busy() || selected_count == 0 || selected_count > MAX_LABEL_QUEUE
+                                    || folder_id().is_empty(),
+                                title: "At most {MAX_LABEL_QUEUE} at once",
+                                onclick: handle_download,
+                                "Download"
+                            }
+                        }
+                    }
+                    ul { class: "space-y-1 max-h-[28rem] overflow-y-auto",
+                        for release in shown {
+                            {
+                                let id = release.release_mbid.clone();
+                                let is_selected = selected.read().contains(&id);
+                                let kind = release.primary_type.clone().unwrap_or_default();
+                                rsx! {
+                                    li {
+                                        key: "{release.release_mbid}",
+                                        class: "flex items-center gap-3 bg-white/5 border border-white/5 p-2 rounded cursor-pointer hover:bg-white/10",
+                                        onclick: move |_| {
+                                            let mut selected = selected.write();
+                                            if let Some(pos) = selected.iter().position(|s| *s == id) {
+                                                selected.remove(pos);
+                                            } else {
+                                                selected.push(id.clone());
+                                            }
+                                        },
+                                        Checkbox { is_selected }
+                                        span { class: "w-24 text-xs font-mono text-gray-500", "{release.date}" }
+                                        div { class: "flex-1 min-w-0 text-sm text-white truncate",
+                                            "{release.artist} – {release.title}"
+                                        }
+                                        span { class: "text-xs font-mono text-gray-500", "{kind}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use api::models::wishlist::WishlistItem;
+use api::{get_wishlist, remove_from_wishlist};
+use dioxus::prelude::*;
+
+use crate::{friendly_error, use_auth};
+
+/// The user's wishlist. `on_search` receives (artist, title) to look the
+/// album up on the search page.
+#[component]
+pub fn Wishlist(refresh: Signal<u32>, on_search: EventHandler<(String, String)>) -> Element {
+    let auth = use_auth();
+    let mut items = use_signal(Vec::<WishlistItem>::new);
+    let mut error = use_signal(String::new);
+
+    let fetch_items = move || async move {
+        match auth.call(get_wishlist).await {
+            Ok(list) => items.set(list),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    use_effect(move || {
+        let _ = refresh();
+        spawn(fetch_items());
+    });
+
+    let handle_remove = move |id: String| async move {
+        match auth.call(|| remove_from_wishlist(id.clone())).await {
+            Ok(()) => fetch_items().await,
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            h2 { class: "text-xl font-bold mb-4 text-beet-accent font-display",
+                "Wishlist ({items.read().len()})"
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            if items.read().is_empty() {
+                p { class: "text-gray-500 font-mono italic text-sm", "Nothing on the wishlist yet." }
+            } else {
+                ul { class: "space-y-2",
+                    for item in items.read().iter().cloned() {
+                        {
+                            let query = (item.artist.clone(), item.title.clone());
+                            let id = item.id.clone();
+                            rsx! {
+                                li {
+                                    key: "{item.id}",
+                                    class: "flex justify-between items-center gap-4 bg-white/5 border border-white/5 p-3 rounded",
+                                    div { class: "min-w-0",
+                                        div { class: "font-bold text-white font-display truncate", "{item.title}" }
+                                        div { class: "text-xs font-mono text-gray-500 truncate",
+                                            "{item.artist}"
+                                            if item.release_mbid.is_none() {
+                                                " · not matched"
+                                            }
+                                        }
+                                    }
+                                    div { class: "flex gap-3 shrink-0",
+                                        button {
+                                            class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                                            onclick: move |_| on_search.call(query.clone()),
+                                            "[ Search ]"
+                                        }
+                                        button {
+                                            class: "text-xs uppercase tracking-wider font-bold text-red-400 hover:text-red-300 transition-colors cursor-pointer",
+                                            onclick: move |_| {
+                                                spawn(handle_remove(id.clone()));
+                                            },
+                                            "[ Remove ]"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
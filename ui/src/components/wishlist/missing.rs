@@ -0,0 +1,126 @@
+use api::{add_to_wishlist, get_missing_music};
+use dioxus::prelude::*;
+use shared::recommendation::MissingMusic;
+use shared::wishlist::NewWishlistItem;
+
+use crate::{friendly_error, use_auth};
+
+/// Albums and tracks from the user's ListenBrainz history and
+/// recommendations that aren't in their library. Checked on demand since
+/// it reads the whole library index. `on_search` receives (artist, title).
+#[component]
+pub fn MissingMusicList(
+    on_search: EventHandler<(String, String)>,
+    on_added: EventHandler<()>,
+) -> Element {
+    let auth = use_auth();
+    let mut items = use_signal(|| None::<Vec<MissingMusic>>);
+    let mut loading = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut wished = use_signal(Vec::<String>::new);
+
+    let handle_check = move |_| async move {
+        error.set(String::new());
+        loading.set(true);
+        match auth.call(get_missing_music).await {
+            Ok(list) => items.set(Some(list)),
+            Err(e) => error.set(friendly_error(&e)),
+        }
+        loading.set(false);
+    };
+
+    let handle_wish = move |item: MissingMusic| async move {
+        let new = NewWishlistItem {
+            artist: item.artist.clone(),
+            title: item.title.clone(),
+            release_mbid: item.mbid.clone(),
+        };
+        match auth
+            .call(|| add_to_wishlist(vec![new.clone()], Some("listenbrainz".to_string())))
+            .await
+        {
+            Ok(_) => {
+                wished.write().push(format!("{}\0{}", item.artist, item.title));
+                on_added.call(());
+            }
+            Err(e) => error.set(friendly_error(&e)),
+        }
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            div { class: "flex justify-between items-center mb-2",
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Missing From Your Library" }
+                button {
+                    class: "retro-btn rounded text-sm",
+                    disabled: loading(),
+                    onclick: handle_check,
+                    if loading() { "Checking..." } else if items.read().is_some() { "Check Again" } else { "Check ListenBrainz" }
+                }
+            }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "What you listen to most, and what ListenBrainz recommends, that isn't in any of your folders."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+
+            match items() {
+                None => rsx! {},
+                Some(list) if list.is_empty() => rsx! {
+                    p { class: "text-gray-500 font-mono italic text-sm", "You own everything you listen to." }
+                },
+                Some(list) => rsx! {
+                    ul { class: "space-y-2 max-h-[32rem] overflow-y-auto",
+                        for item in list {
+                            {
+                                let query = (item.artist.clone(), item.title.clone());
+                                let is_wished = wished.read().contains(&format!("{}\0{}", item.artist, item.title));
+                                let detail = if item.recommended {
+                                    "recommended".to_string()
+                                } else {
+                                    format!("{} listens", item.listen_count)
+                                };
+                                let kind = if item.is_album { "album" } else { "track" };
+                                let to_wish = item.clone();
+                                rsx! {
+                                    li { class: "flex justify-between items-center gap-4 bg-white/5 border border-white/5 p-3 rounded",
+                                        div { class: "min-w-0",
+                                            div { class: "font-bold text-white font-display truncate", "{item.title}" }
+                                            div { class: "text-xs font-mono text-gray-500 truncate",
+                                                "{item.artist} · {kind} · {detail}"
+                                            }
+                                        }
+                                        div { class: "flex gap-3 shrink-0",
+                                            button {
+                                                class: "text-xs uppercase tracking-wider font-bold text-beet-leaf hover:text-white transition-colors cursor-pointer",
+                                                onclick: move |_| on_search.call(query.clone()),
+                                                "[ Search ]"
+                                            }
+                                            if item.is_album {
+                                                if is_wished {
+                                                    span { class: "text-xs uppercase tracking-wider font-bold text-gray-500", "[ Wished ]" }
+                                                } else {
+                                                    button {
+                                                        class: "text-xs uppercase tracking-wider font-bold text-beet-accent hover:text-white transition-colors cursor-pointer",
+                                                        onclick: move |_| {
+                                                            spawn(handle_wish(to_wish.clone()));
+                                                        },
+                                                        "[ Wishlist ]"
+                                                    }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
@@ -0,0 +1,13 @@
+mod chart_import;
+mod complete;
+mod label_catalog;
+mod list;
+mod missing;
+mod transfer;
+
+pub use chart_import::ChartImport;
+pub use complete::CompleteAlbums;
+pub use label_catalog::LabelCatalog;
+pub use list::Wishlist;
+pub use missing::MissingMusicList;
+pub use transfer::WishlistTransfer;
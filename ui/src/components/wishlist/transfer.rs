@@ -0,0 +1,110 @@
+use api::models::folder::Folder;
+use api::{get_user_folders, import_wishlist};
+use dioxus::html::FileData;
+use dioxus::prelude::*;
+use shared::export::WishlistExport;
+
+use crate::dashboard::{ExportButtons, ExportKind};
+use crate::{friendly_error, use_auth};
+
+/// Export the wishlist and download queue to a file, or merge one from
+/// another instance. Releases already wanted are skipped.
+#[component]
+pub fn WishlistTransfer(on_added: EventHandler<()>) -> Element {
+    let auth = use_auth();
+    let mut folders = use_signal(Vec::<Folder>::new);
+    // Folder to queue the file's queue into; empty to wish for it instead
+    let mut folder_id = use_signal(String::new);
+    let mut busy = use_signal(|| false);
+    let mut error = use_signal(String::new);
+    let mut notice = use_signal(String::new);
+
+    use_future(move || async move {
+        if let Ok(list) = auth.call(get_user_folders).await {
+            folders.set(list);
+        }
+    });
+
+    let handle_import = move |files: Vec<FileData>| async move {
+        error.set(String::new());
+        notice.set(String::new());
+        let Some(file) = files.into_iter().next() else {
+            return;
+        };
+        busy.set(true);
+        let parsed = match file.read_bytes().await {
+            Ok(bytes) => serde_json::from_slice::<WishlistExport>(&bytes)
+                .map_err(|_| "Not a soulbeet wishlist file".to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match parsed {
+            Ok(export) => {
+                let folder = Some(folder_id()).filter(|id| !id.is_empty());
+                match auth
+                    .call(|| import_wishlist(export.clone(), folder.clone()))
+                    .await
+                {
+                    Ok(result) => {
+                        notice.set(format!(
+                            "{} added to the wishlist, {} queued, {} already wanted",
+                            result.added, result.queued, result.skipped
+                        ));
+                        on_added.call(());
+                    }
+                    Err(e) => error.set(friendly_error(&e)),
+                }
+            }
+            Err(e) => error.set(e),
+        }
+        busy.set(false);
+    };
+
+    rsx! {
+        div { class: "bg-beet-panel border border-white/10 p-6 rounded-lg shadow-2xl relative z-10",
+            div { class: "flex items-center justify-between gap-4 mb-1",
+                h2 { class: "text-xl font-bold text-beet-accent font-display", "Move Wishlist" }
+                ExportButtons { kind: ExportKind::Wishlist }
+            }
+            p { class: "text-xs font-mono text-gray-400 mb-4",
+                "Export the wishlist and download queue with their MusicBrainz ids, or import an export from another instance."
+            }
+
+            if !error().is_empty() {
+                div { class: "mb-4 p-4 bg-red-900/20 border border-red-500/50 rounded text-red-400 font-mono text-sm",
+                    "{error}"
+                }
+            }
+            if !notice().is_empty() {
+                div { class: "mb-4 p-4 bg-green-900/20 border border-green-500/50 rounded text-green-400 font-mono text-sm",
+                    "{notice}"
+                }
+            }
+
+            div { class: "flex flex-wrap items-center gap-2 text-sm font-mono",
+                label { class: "text-gray-400", "Queued releases go" }
+                select {
+                    class: "p-2 rounded bg-beet-dark border border-white/10 focus:border-beet-accent focus:outline-none text-white text-sm",
+                    value: "{folder_id}",
+                    onchange: move |e| folder_id.set(e.value()),
+                    option { value: "", "to the wishlist" }
+                    for folder in folders.read().iter() {
+                        option { value: "{folder.id}", "into {folder.name}" }
+                    }
+                }
+                label {
+                    class: if busy() { "retro-btn rounded text-sm opacity-50" } else { "retro-btn rounded text-sm cursor-pointer" },
+                    if busy() { "Importing..." } else { "Import JSON" }
+                    input {
+                        class: "hidden",
+                        "type": "file",
+                        accept: ".json,application/json",
+                        disabled: busy(),
+                        onchange: move |evt| {
+                            spawn(handle_import(evt.files()));
+                        },
+                    }
+                }
+            }
+        }
+    }
+}
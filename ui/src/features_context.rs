@@ -0,0 +1,53 @@
+use crate::use_auth;
+use dioxus::prelude::*;
+use shared::features::FeatureFlags;
+
+/// Feature flags context, so experimental panels can check whether they
+/// are enabled without fetching the flags themselves.
+#[derive(Clone, Copy, Debug)]
+pub struct Features {
+    state: Signal<FeatureFlags>,
+}
+
+impl Features {
+    pub fn is_enabled(&self, key: &str) -> bool {
+        self.state.read().is_enabled(key)
+    }
+
+    pub fn get(&self) -> FeatureFlags {
+        self.state.read().clone()
+    }
+
+    /// Replace the flags after they were changed in settings.
+    pub fn set(&mut self, flags: FeatureFlags) {
+        self.state.set(flags);
+    }
+}
+
+/// Hook to access the feature flags context.
+pub fn use_features() -> Features {
+    use_context::<Features>()
+}
+
+/// Provider component that loads the feature flags once a user is logged in.
+#[component]
+pub fn FeaturesProvider(children: Element) -> Element {
+    let auth = use_auth();
+    let mut state = use_signal(FeatureFlags::default);
+
+    use_effect(move || {
+        if auth.is_logged_in() {
+            spawn(async move {
+                if let Ok(flags) = auth.call(api::get_feature_flags).await {
+                    state.set(flags);
+                }
+            });
+        } else {
+            state.set(FeatureFlags::default());
+        }
+    });
+
+    use_context_provider(|| Features { state });
+
+    rsx! { {children} }
+}
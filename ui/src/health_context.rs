@@ -34,7 +34,7 @@ pub fn HealthProvider(children: Element) -> Element {
     let mut poll = use_future(move || async move {
         loop {
             if auth.is_logged_in() {
-                if let Ok(health) = auth.call(api::get_system_health()).await {
+                if let Ok(health) = auth.call(api::get_system_health).await {
                     state.set(health);
                 }
             }
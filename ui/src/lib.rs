@@ -9,9 +9,15 @@ pub use layout::Layout;
 mod auth;
 pub use auth::*;
 
+mod base_path;
+pub use base_path::*;
+
 mod health_context;
 pub use health_context::*;
 
+mod features_context;
+pub use features_context::*;
+
 mod settings_context;
 pub use settings_context::*;
 
@@ -1,6 +1,9 @@
 use crate::use_auth;
 use api::{ProviderInfo, UpdateUserSettings, UserSettings};
 use dioxus::prelude::*;
+use shared::download::{MatchMode, RenameRules, DEFAULT_SEARCH_TIMEOUT_SECS};
+use shared::quality::QualityProfile;
+use shared::routing::RoutingRules;
 
 #[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub enum SearchType {
@@ -82,6 +85,57 @@ impl Settings {
             .unwrap_or_default()
     }
 
+    /// Get the default source matching mode.
+    pub fn match_mode(&self) -> MatchMode {
+        self.state
+            .read()
+            .as_ref()
+            .and_then(|s| s.match_mode.as_deref())
+            .and_then(MatchMode::from_setting)
+            .unwrap_or_default()
+    }
+
+    /// Whether the home page is the dashboard rather than the bare search.
+    pub fn home_dashboard(&self) -> bool {
+        self.state.read().as_ref().is_some_and(|s| s.home_dashboard)
+    }
+
+    /// Get the default Soulseek search timeout, in seconds.
+    pub fn search_timeout_secs(&self) -> u32 {
+        self.state
+            .read()
+            .as_ref()
+            .and_then(|s| s.search_timeout_secs)
+            .unwrap_or(DEFAULT_SEARCH_TIMEOUT_SECS)
+    }
+
+    /// Get the user's quality profile.
+    pub fn quality_profile(&self) -> QualityProfile {
+        self.state
+            .read()
+            .as_ref()
+            .map(|s| s.quality_profile())
+            .unwrap_or_default()
+    }
+
+    /// Get the user's pre-import rename rules.
+    pub fn rename_rules(&self) -> RenameRules {
+        self.state
+            .read()
+            .as_ref()
+            .map(|s| s.rename_rules())
+            .unwrap_or_default()
+    }
+
+    /// Get the user's genre routing rules.
+    pub fn routing_rules(&self) -> RoutingRules {
+        self.state
+            .read()
+            .as_ref()
+            .map(|s| s.routing_rules())
+            .unwrap_or_default()
+    }
+
     /// Get the list of available metadata providers.
     pub fn providers(&self) -> Vec<ProviderInfo> {
         self.providers.read().clone()
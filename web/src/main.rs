@@ -1,25 +1,36 @@
 use auth::{use_auth, AuthProvider};
+#[cfg(feature = "web")]
+use dioxus::logger::tracing::warn;
 use dioxus::prelude::*;
-use shared::download::DownloadProgress;
 #[cfg(feature = "web")]
 use shared::download::DownloadEvent;
+use shared::download::DownloadProgress;
 use shared::system::NavidromeStatus;
+#[cfg(feature = "web")]
+use shared::updates::{DeltaDecoder, UpdateFrame};
 use std::collections::HashMap;
 
 #[cfg(feature = "web")]
 use dioxus::fullstack::WebSocketOptions;
 #[cfg(feature = "web")]
 use websocket::use_resilient_websocket;
+use websocket::SocketStatus;
 
 use ui::{
-    AutoDownloadSignal, Downloads, HealthProvider, Layout, Navbar, SearchPrefill, SearchReset,
-    SettingsProvider,
+    ActiveDownloads, AutoDownloadSignal, CompleteAlbumSignal, Downloads, FeaturesProvider,
+    HealthProvider, ImportReviewSignal, Layout, Navbar, RerunSearchSignal, SearchPrefill,
+    SearchReset, SettingsProvider, SharedSearchSignal,
+};
+use views::{
+    DashboardPage, ForgotPasswordPage, ImportPage, LoginPage, PartyPage, RegisterPage,
+    ReleasesPage, ResetPasswordPage, SearchPage, SessionPage, SettingsPage, WishlistPage,
 };
-use views::{DashboardPage, LoginPage, SearchPage, SettingsPage};
 
 mod auth;
+#[cfg(feature = "server")]
+mod serve;
+mod tab_status;
 mod views;
-#[cfg(feature = "web")]
 mod websocket;
 
 #[derive(Debug, Clone, Routable, PartialEq)]
@@ -28,12 +39,28 @@ pub enum Route {
     #[layout(AuthGuard)]
         #[route("/login")]
         LoginPage {},
+        #[route("/register?:token")]
+        RegisterPage { token: String },
+        #[route("/forgot-password")]
+        ForgotPasswordPage {},
+        #[route("/reset-password?:token")]
+        ResetPasswordPage { token: String },
+        #[route("/party/:token")]
+        PartyPage { token: String },
 
         #[layout(WebNavbar)]
             #[route("/")]
             SearchPage {},
             #[route("/dashboard")]
             DashboardPage {},
+            #[route("/import")]
+            ImportPage {},
+            #[route("/wishlist")]
+            WishlistPage {},
+            #[route("/releases")]
+            ReleasesPage {},
+            #[route("/session/:id")]
+            SessionPage { id: String },
             #[route("/settings")]
             SettingsPage {},
 }
@@ -41,21 +68,80 @@ pub enum Route {
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const MAIN_CSS: Asset = asset!("/assets/tailwind.css");
 
+#[cfg(feature = "server")]
+fn router() -> axum::Router {
+    use tower_cookies::CookieManagerLayer;
+    use tower_http::compression::CompressionLayer;
+
+    // Start background cleanup task for user channels
+    api::globals::start_channel_cleanup_task();
+
+    let router = dioxus::server::router(App);
+    #[cfg(feature = "demo")]
+    let router = router.layer(axum::middleware::from_fn(api::demo::read_only_settings));
+
+    let router = router
+        .route(
+            "/api/events",
+            axum::routing::get(api::activity::activity_stream),
+        )
+        .route(
+            "/api/calendar.ics",
+            axum::routing::get(api::calendar::calendar_feed),
+        )
+        .route(
+            "/api/covers/{key}",
+            axum::routing::get(api::covers::album_cover),
+        )
+        .route(
+            "/api/art/{release_id}",
+            axum::routing::get(api::covers::release_art),
+        )
+        .layer(axum::middleware::from_fn(api::panics::catch_request_panics))
+        .layer(axum::middleware::from_fn(api::csrf::verify_origin))
+        .layer(axum::middleware::from_fn(api::cache_headers::cache_headers))
+        .layer(axum::middleware::from_fn(
+            api::security_headers::security_headers,
+        ))
+        // Outside the security headers, which rewrite pages as sent
+        .layer(CompressionLayer::new())
+        .layer(axum::middleware::from_fn(
+            api::network_acl::restrict_networks,
+        ))
+        .layer(CookieManagerLayer::new());
+
+    // Under a sub-path, answer both with the prefix and without it: some
+    // proxies pass it on, others strip it
+    match api::config::CONFIG.base_path() {
+        "" => router,
+        base => axum::Router::new()
+            .nest_service(base, router.clone())
+            .fallback_service(router),
+    }
+}
+
 fn main() {
     #[cfg(feature = "server")]
     {
-        use tower_cookies::CookieManagerLayer;
-
-        dioxus::serve(|| async move {
-            // Start background cleanup task for user channels
-            api::globals::start_channel_cleanup_task();
-
-            Ok(dioxus::server::router(App).layer(CookieManagerLayer::new()))
-        });
+        api::logging::init();
+        api::panics::install();
+        // Under `dx serve`, for hot reloading. Clients reach the server
+        // through its proxy there, so their address isn't known anyway.
+        if dioxus::cli_config::is_cli_enabled() && api::config::CONFIG.tls().is_none() {
+            dioxus::serve(|| async move { Ok(router()) });
+            return;
+        }
+        serve::serve(api::config::CONFIG.tls());
     }
 
     #[cfg(not(feature = "server"))]
-    dioxus::launch(App);
+    {
+        // Server functions and the downloads socket live under the sub-path too
+        if !ui::base_path().is_empty() {
+            dioxus::fullstack::set_server_url(ui::base_path());
+        }
+        dioxus::launch(App);
+    }
 }
 
 #[component]
@@ -69,7 +155,9 @@ fn App() -> Element {
         AuthProvider {
             SettingsProvider {
                 HealthProvider {
-                    Router::<Route> {}
+                    FeaturesProvider {
+                        Router::<Route> {}
+                    }
                 }
             }
         }
@@ -85,8 +173,16 @@ fn AuthGuard() -> Element {
     use_effect(move || {
         let is_logged_in = auth.is_logged_in();
 
-        // If not logged in AND we're not already on /login -> go to login
-        if !is_logged_in && !matches!(current, Route::LoginPage {}) {
+        // If not logged in AND not on /login, signing up, resetting a password or at a party -> go to login
+        let signing_in = matches!(
+            current,
+            Route::LoginPage {}
+                | Route::RegisterPage { .. }
+                | Route::ForgotPasswordPage {}
+                | Route::ResetPasswordPage { .. }
+                | Route::PartyPage { .. }
+        );
+        if !is_logged_in && !signing_in {
             nav.replace(Route::LoginPage {});
         }
 
@@ -106,34 +202,107 @@ fn WebNavbar() -> Element {
     let mut auth = use_auth();
     let mut downloads_open = use_signal(|| false);
     let mut search_reset = use_signal(|| 0);
-    #[allow(unused_mut)] // mutated in websocket callback (web feature only)
+    #[allow(unused_mut)] // mutated by the websocket and the reload fetch (web feature only)
     let mut downloads = use_signal::<HashMap<String, DownloadProgress>>(HashMap::new);
+    use_context_provider(|| ActiveDownloads(downloads));
 
-    let search_prefill = use_signal(|| None::<(String, String)>);
+    // A search cut short by the session running out picks up where it was
+    let search_prefill = use_signal(move || auth.take_resumed_search());
     use_context_provider(|| SearchReset(search_reset));
     use_context_provider(|| SearchPrefill(search_prefill));
+    let complete_album = use_signal(|| None::<shared::library::AlbumCompletion>);
+    use_context_provider(|| CompleteAlbumSignal(complete_album));
+    let rerun_search = use_signal(|| None::<shared::download::DownloadQuery>);
+    use_context_provider(|| RerunSearchSignal(rerun_search));
 
     #[allow(unused_mut)]
     let mut auto_download_signal = use_signal(|| None::<shared::download::AutoDownloadEvent>);
     use_context_provider(|| AutoDownloadSignal(auto_download_signal));
 
+    #[allow(unused_mut)]
+    let mut shared_search_signal = use_signal(|| None::<shared::download::SharedSearchEvent>);
+    use_context_provider(|| SharedSearchSignal(shared_search_signal));
+
+    #[allow(unused_mut)]
+    let mut import_review_signal = use_signal(|| None::<shared::download::PendingImport>);
+    use_context_provider(|| ImportReviewSignal(import_review_signal));
+
     #[cfg(feature = "web")]
-    use_resilient_websocket(
-        || api::download_updates_ws(WebSocketOptions::new()),
-        move |event: DownloadEvent| {
-            match event {
-                DownloadEvent::Progress(data) => {
+    let socket_status = use_resilient_websocket(
+        || api::download_updates_binary_ws(WebSocketOptions::new()),
+        {
+            let mut decoder = DeltaDecoder::default();
+            move |frame: UpdateFrame| {
+                let event = match decoder.decode(frame) {
+                    Ok(Some(event)) => event,
+                    Ok(None) => return,
+                    Err(e) => {
+                        // The server was upgraded under this page
+                        warn!("{}, reloading", e);
+                        if let Some(window) = web_sys::window() {
+                            let _ = window.location().reload();
+                        }
+                        return;
+                    }
+                };
+                match event {
+                    DownloadEvent::Progress(data) => {
+                        let mut map = downloads.write();
+                        for file in data {
+                            map.insert(file.item.clone(), file);
+                        }
+                    }
+                    DownloadEvent::AutoDownload(auto_event) => {
+                        auto_download_signal.set(Some(auto_event));
+                    }
+                    DownloadEvent::SharedSearch(shared_event) => {
+                        shared_search_signal.set(Some(shared_event));
+                    }
+                    DownloadEvent::ImportReview(pending) => {
+                        import_review_signal.set(Some(pending));
+                    }
+                    DownloadEvent::FolderHealth(change) => {
+                        tab_status::notify_folder_health(&change);
+                    }
+                    DownloadEvent::Cleared(items) => {
+                        let mut map = downloads.write();
+                        for item in &items {
+                            map.remove(item);
+                        }
+                    }
+                }
+            }
+        },
+        // The socket replays the download snapshot on connect. Ask for the
+        // rest again: transfer progress from the backend, and reviews
+        // announced while it was down.
+        move || {
+            import_review_signal.set(None);
+            spawn(async move {
+                if let Ok(active) = auth.call(api::get_active_downloads).await {
                     let mut map = downloads.write();
-                    for file in data {
+                    for file in active {
                         map.insert(file.item.clone(), file);
                     }
                 }
-                DownloadEvent::AutoDownload(auto_event) => {
-                    auto_download_signal.set(Some(auto_event));
-                }
-            }
+            });
         },
     );
+    #[cfg(not(feature = "web"))]
+    let socket_status = use_signal(SocketStatus::default);
+
+    // Fill the panel right away after a reload instead of waiting for the
+    // next update
+    #[cfg(feature = "web")]
+    use_future(move || async move {
+        if let Ok(active) = auth.call(api::get_active_downloads).await {
+            let mut map = downloads.write();
+            for file in active {
+                // Updates that already arrived over the socket are newer
+                map.entry(file.item.clone()).or_insert(file);
+            }
+        }
+    });
 
     let logout = move |_| {
         spawn(async move {
@@ -141,7 +310,10 @@ fn WebNavbar() -> Element {
         });
     };
 
+    let title = tab_status::use_tab_status(downloads);
+
     rsx! {
+        document::Title { "{title}" }
         Layout {
             Navbar {
                 Link {
@@ -181,6 +353,60 @@ fn WebNavbar() -> Element {
                         }
                     }
                 }
+                Link {
+                    class: "nav-link text-white font-medium border-b-2 border-transparent hover:border-beet-accent pb-0.5",
+                    active_class: "border-beet-accent",
+                    to: Route::ImportPage {},
+                    span { class: "hidden md:block", "Import" }
+                    svg {
+                        class: "md:hidden w-6 h-6",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        stroke_width: "1.5",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M3 16.5v2.25A2.25 2.25 0 005.25 21h13.5A2.25 2.25 0 0021 18.75V16.5m-13.5-9L12 3m0 0l4.5 4.5M12 3v13.5",
+                        }
+                    }
+                }
+                Link {
+                    class: "nav-link text-white font-medium border-b-2 border-transparent hover:border-beet-accent pb-0.5",
+                    active_class: "border-beet-accent",
+                    to: Route::WishlistPage {},
+                    span { class: "hidden md:block", "Wishlist" }
+                    svg {
+                        class: "md:hidden w-6 h-6",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        stroke_width: "1.5",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M17.593 3.322c1.1.128 1.907 1.077 1.907 2.185V21L12 17.25 4.5 21V5.507c0-1.108.806-2.057 1.907-2.185a48.507 48.507 0 0111.186 0z",
+                        }
+                    }
+                }
+                Link {
+                    class: "nav-link text-white font-medium border-b-2 border-transparent hover:border-beet-accent pb-0.5",
+                    active_class: "border-beet-accent",
+                    to: Route::ReleasesPage {},
+                    span { class: "hidden md:block", "Releases" }
+                    svg {
+                        class: "md:hidden w-6 h-6",
+                        fill: "none",
+                        stroke: "currentColor",
+                        view_box: "0 0 24 24",
+                        stroke_width: "1.5",
+                        path {
+                            stroke_linecap: "round",
+                            stroke_linejoin: "round",
+                            d: "M6.75 3v2.25M17.25 3v2.25M3 18.75V7.5a2.25 2.25 0 012.25-2.25h13.5A2.25 2.25 0 0121 7.5v11.25m-18 0A2.25 2.25 0 005.25 21h13.5A2.25 2.25 0 0021 18.75m-18 0v-7.5A2.25 2.25 0 015.25 9h13.5A2.25 2.25 0 0121 11.25v7.5",
+                        }
+                    }
+                }
                 Link {
                     class: "nav-link text-white font-medium border-b-2 border-transparent hover:border-beet-accent pb-0.5",
                     active_class: "border-beet-accent",
@@ -243,6 +469,9 @@ fn WebNavbar() -> Element {
             }
 
             NavidromeBanner {}
+            ConnectionBanner { status: socket_status }
+            FolderAlertBanner {}
+            DownloadPauseBanner {}
 
             main { class: "px-4 sm:px-6 lg:px-8 flex-grow flex flex-col relative overflow-y-auto w-full py-8 no-scrollbar",
                 Outlet::<Route> {}
@@ -252,6 +481,77 @@ fn WebNavbar() -> Element {
     }
 }
 
+/// Shown while the live download updates are cut off and being retried.
+#[component]
+fn ConnectionBanner(status: Signal<SocketStatus>) -> Element {
+    let SocketStatus::Reconnecting {
+        attempt,
+        retry_in_secs,
+    } = status()
+    else {
+        return rsx! {};
+    };
+
+    rsx! {
+        div { class: "mx-4 sm:mx-6 lg:mx-8 mt-2 px-3 py-2 bg-beet-panel border border-yellow-500/30 rounded flex items-center gap-3 text-xs font-mono",
+            span { class: "w-1.5 h-1.5 rounded-full bg-yellow-500 shrink-0 animate-pulse" }
+            span { class: "text-gray-500 uppercase tracking-widest shrink-0 hidden sm:inline", "LIVE" }
+            span { class: "text-gray-400 flex-1 min-w-0 truncate",
+                "Connection lost, reconnecting… Next try in {retry_in_secs}s (attempt {attempt})."
+            }
+        }
+    }
+}
+
+/// Folders whose mount is down. Clears itself once the health poll sees
+/// them back.
+#[component]
+fn FolderAlertBanner() -> Element {
+    let health = ui::use_system_health();
+    let alerts = health.get().folder_alerts;
+
+    rsx! {
+        for alert in alerts {
+            div {
+                key: "{alert.folder_id}",
+                class: "mx-4 sm:mx-6 lg:mx-8 mt-2 px-3 py-2 bg-beet-panel border border-yellow-500/30 rounded flex items-center gap-3 text-xs font-mono",
+                span { class: "w-1.5 h-1.5 rounded-full bg-yellow-500 shrink-0" }
+                span { class: "text-gray-500 uppercase tracking-widest shrink-0 hidden sm:inline", "FOLDER" }
+                span {
+                    class: "text-gray-400 flex-1 min-w-0 truncate",
+                    title: "{alert.path}",
+                    "{alert.folder_name}: {alert.problem.describe()}. Imports into it are paused until it's back."
+                }
+            }
+        }
+    }
+}
+
+/// Shown to everyone while an admin has paused downloading.
+#[component]
+fn DownloadPauseBanner() -> Element {
+    let health = ui::use_system_health();
+    let pause = health.get().download_pause;
+    if !pause.paused {
+        return rsx! {};
+    }
+    let message = match pause.deferred {
+        0 => "Paused by an admin. New downloads wait until it's resumed.".to_string(),
+        n => format!(
+            "Paused by an admin. {} download(s) wait until it's resumed.",
+            n
+        ),
+    };
+
+    rsx! {
+        div { class: "mx-4 sm:mx-6 lg:mx-8 mt-2 px-3 py-2 bg-beet-panel border border-yellow-500/30 rounded flex items-center gap-3 text-xs font-mono",
+            span { class: "w-1.5 h-1.5 rounded-full bg-yellow-500 shrink-0" }
+            span { class: "text-gray-500 uppercase tracking-widest shrink-0 hidden sm:inline", "DOWNLOADS" }
+            span { class: "text-gray-400 flex-1 min-w-0 truncate", "{message}" }
+        }
+    }
+}
+
 #[component]
 fn NavidromeBanner() -> Element {
     let auth = use_auth();
@@ -0,0 +1,99 @@
+//! Serving the app ourselves rather than through `dioxus::serve`, which
+//! doesn't pass on the client address that the network rules and rate
+//! limits need. Also serves HTTPS directly, for instances reached without a
+//! reverse proxy, where the login cookie would otherwise cross the network
+//! in cleartext.
+
+use std::net::SocketAddr;
+
+use api::config::{TlsConfig, CONFIG};
+use axum::extract::connect_info::IntoMakeServiceWithConnectInfo;
+use dioxus::logger::tracing::{error, info};
+
+/// Serve the app, over HTTPS when `tls` is set, until the process ends.
+pub fn serve(tls: Option<&TlsConfig>) {
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            error!("Could not start the async runtime: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = runtime.block_on(run(tls)) {
+        error!("Server stopped: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(tls: Option<&TlsConfig>) -> std::io::Result<()> {
+    let addr: SocketAddr = format!("{}:{}", CONFIG.ip, CONFIG.port)
+        .parse()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+    let app = super::router().into_make_service_with_connect_info::<SocketAddr>();
+    info!("Listening on {}://{}", CONFIG.scheme(), addr);
+
+    let Some(tls) = tls else {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        return axum::serve(listener, app).await;
+    };
+    match tls {
+        TlsConfig::Files { cert, key } => {
+            let config = axum_server::tls_rustls::RustlsConfig::from_pem_file(cert, key).await?;
+            axum_server::bind_rustls(addr, config).serve(app).await
+        }
+        TlsConfig::Acme {
+            domains,
+            contact,
+            cache_dir,
+            staging,
+        } => serve_acme(addr, app, domains, contact.as_deref(), cache_dir, *staging).await,
+    }
+}
+
+#[cfg(feature = "acme")]
+async fn serve_acme(
+    addr: SocketAddr,
+    app: IntoMakeServiceWithConnectInfo<axum::Router, SocketAddr>,
+    domains: &[String],
+    contact: Option<&str>,
+    cache_dir: &std::path::Path,
+    staging: bool,
+) -> std::io::Result<()> {
+    use dioxus::logger::tracing::warn;
+    use futures::StreamExt;
+    use rustls_acme::{caches::DirCache, AcmeConfig};
+
+    let mut state = AcmeConfig::new(domains)
+        .contact(contact.map(|email| format!("mailto:{}", email)))
+        .cache(DirCache::new(cache_dir.to_path_buf()))
+        .directory_lets_encrypt(!staging)
+        .state();
+    let acceptor = state.axum_acceptor(state.default_rustls_config());
+
+    // Drives issuance and renewal
+    tokio::spawn(async move {
+        while let Some(event) = state.next().await {
+            match event {
+                Ok(ok) => info!("ACME: {:?}", ok),
+                Err(e) => warn!("ACME: {}", e),
+            }
+        }
+    });
+
+    axum_server::bind(addr).acceptor(acceptor).serve(app).await
+}
+
+#[cfg(not(feature = "acme"))]
+async fn serve_acme(
+    _addr: SocketAddr,
+    _app: IntoMakeServiceWithConnectInfo<axum::Router, SocketAddr>,
+    _domains: &[String],
+    _contact: Option<&str>,
+    _cache_dir: &std::path::Path,
+    _staging: bool,
+) -> std::io::Result<()> {
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "ACME_DOMAINS is set but this build has no ACME support (the `acme` feature)",
+    ))
+}
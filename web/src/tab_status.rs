@@ -0,0 +1,171 @@
+//! Download status outside the page: tab title, favicon badge and browser
+//! notifications when transfers finish while the tab is in the background.
+
+use dioxus::prelude::*;
+#[cfg(feature = "web")]
+use shared::download::FolderHealthChange;
+use shared::download::{DownloadProgress, DownloadState};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const APP_NAME: &str = "SoulBeet";
+
+/// Badge colors, matching the theme accent and error red.
+const ACTIVE_BADGE: &str = "#ff00ff";
+const FAILED_BADGE: &str = "#ef4444";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Badge {
+    None,
+    Active,
+    Failed,
+}
+
+fn is_active(state: &DownloadState) -> bool {
+    matches!(
+        state,
+        DownloadState::Queued
+            | DownloadState::InProgress
+            | DownloadState::Completed
+            | DownloadState::ImportWaiting
+            | DownloadState::ImportPaused
+            | DownloadState::Importing
+            | DownloadState::Uploading(_)
+    )
+}
+
+fn counts(downloads: &HashMap<String, DownloadProgress>) -> (usize, usize) {
+    let active = downloads.values().filter(|d| is_active(&d.state)).count();
+    let failed = downloads
+        .values()
+        .filter(|d| matches!(d.state, DownloadState::Failed(_)))
+        .count();
+    (active, failed)
+}
+
+/// Redraw the favicon with a colored dot, or restore the original one.
+fn set_favicon_badge(badge: Badge) {
+    let color = match badge {
+        Badge::None => "null".to_string(),
+        Badge::Active => format!("\"{ACTIVE_BADGE}\""),
+        Badge::Failed => format!("\"{FAILED_BADGE}\""),
+    };
+    document::eval(&format!(
+        r#"
+        const color = {color};
+        let link = document.querySelector("link[rel~='icon']");
+        if (!link) {{
+            link = document.createElement("link");
+            link.rel = "icon";
+            document.head.appendChild(link);
+        }}
+        if (!window.__soulbeetFavicon) window.__soulbeetFavicon = link.href || "/favicon.ico";
+        if (!color) {{
+            link.href = window.__soulbeetFavicon;
+            return;
+        }}
+        const img = new Image();
+        img.onload = () => {{
+            const canvas = document.createElement("canvas");
+            canvas.width = canvas.height = 32;
+            const ctx = canvas.getContext("2d");
+            ctx.drawImage(img, 0, 0, 32, 32);
+            ctx.beginPath();
+            ctx.arc(24, 8, 8, 0, 2 * Math.PI);
+            ctx.fillStyle = color;
+            ctx.fill();
+            link.href = canvas.toDataURL("image/png");
+        }};
+        img.src = window.__soulbeetFavicon;
+        "#
+    ));
+}
+
+/// Show a browser notification if the user allowed them and isn't looking
+/// at the tab. Tagged with its text, so the same news from several open
+/// tabs shows once.
+fn notify(body: &str) {
+    let body = serde_json::to_string(body).unwrap_or_default();
+    document::eval(&format!(
+        r#"
+        if ("Notification" in window && Notification.permission === "granted" && document.hidden) {{
+            new Notification("{APP_NAME}", {{ body: {body}, tag: {body}, icon: "/favicon.ico" }});
+        }}
+        "#
+    ));
+}
+
+/// Notify that a folder's mount failed or came back.
+#[cfg(feature = "web")]
+pub fn notify_folder_health(change: &FolderHealthChange) {
+    let message = match &change.problem {
+        Some(problem) => format!("{}: {}, imports paused", change.folder_name, problem),
+        None => format!("{} is available again", change.folder_name),
+    };
+    notify(&message);
+}
+
+/// Keep the tab title and favicon in sync with `downloads`, and notify
+/// when downloads finish. Returns the title to render.
+pub fn use_tab_status(downloads: Signal<HashMap<String, DownloadProgress>>) -> String {
+    // Last seen state per item, to notice transitions without re-running
+    // the effect on its own writes
+    let last_states = use_hook(|| Rc::new(RefCell::new(HashMap::<String, DownloadState>::new())));
+    let mut badge = use_signal(|| Badge::None);
+
+    use_effect(move || {
+        let map = downloads.read();
+        let (active, failed) = counts(&map);
+        let next = if failed > 0 {
+            Badge::Failed
+        } else if active > 0 {
+            Badge::Active
+        } else {
+            Badge::None
+        };
+        if *badge.peek() != next {
+            badge.set(next);
+            set_favicon_badge(next);
+        }
+
+        let mut imported = 0;
+        let mut newly_failed = 0;
+        let mut seen = last_states.borrow_mut();
+        for (item, progress) in map.iter() {
+            let previous = seen.insert(item.clone(), progress.state.clone());
+            if previous.as_ref() == Some(&progress.state) {
+                continue;
+            }
+            // Only transitions seen live count, not entries replayed on load
+            if previous.is_none() && !is_active(&progress.state) {
+                continue;
+            }
+            match progress.state {
+                DownloadState::Imported => imported += 1,
+                DownloadState::Failed(_) => newly_failed += 1,
+                _ => {}
+            }
+        }
+        seen.retain(|item, _| map.contains_key(item));
+
+        let message = match (imported, newly_failed) {
+            (0, 0) => None,
+            (n, 0) => Some(format!("{n} download(s) imported")),
+            (0, f) => Some(format!("{f} download(s) failed")),
+            (n, f) => Some(format!("{n} imported, {f} failed")),
+        };
+        if let Some(message) = message {
+            notify(&message);
+        }
+    });
+
+    let (active, failed) = counts(&downloads.read());
+    if active > 0 {
+        format!("({active}) downloading… — {APP_NAME}")
+    } else if failed > 0 {
+        format!("({failed}) failed — {APP_NAME}")
+    } else {
+        APP_NAME.to_string()
+    }
+}
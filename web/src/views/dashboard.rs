@@ -1,10 +1,18 @@
 use dioxus::prelude::*;
-use ui::dashboard::{DashboardTab, DashboardTabs, DeletionHistoryTab, StatsOverview};
+use ui::dashboard::{
+    BandwidthOverview, DashboardTab, DashboardTabs, DeletionHistoryTab, DownloadHistoryTab,
+    ExportButtons, ExportKind, LibraryHealthTab, SearchHistoryTab, StatsOverview,
+};
 use ui::discovery::DiscoveryOverview;
+use ui::RerunSearchSignal;
+
+use crate::Route;
 
 #[component]
 pub fn DashboardPage() -> Element {
     let mut active_tab = use_signal(DashboardTab::default);
+    let nav = use_navigator();
+    let rerun_search = use_context::<RerunSearchSignal>();
 
     rsx! {
         div { class: "fixed top-1/4 -left-10 w-64 h-64 bg-blue-500/10 rounded-full blur-[100px] pointer-events-none" }
@@ -27,7 +35,20 @@ pub fn DashboardPage() -> Element {
             div { class: "pt-6",
                 match active_tab() {
                     DashboardTab::Overview => rsx! { OverviewTab {} },
-                    DashboardTab::History => rsx! { DeletionHistoryTab {} },
+                    DashboardTab::History => rsx! {
+                        div { class: "space-y-8",
+                            DownloadHistoryTab {}
+                            SearchHistoryTab {
+                                on_rerun: move |query| {
+                                    let mut rerun = rerun_search.0;
+                                    rerun.set(Some(query));
+                                    nav.push(Route::SearchPage {});
+                                },
+                            }
+                            DeletionHistoryTab {}
+                        }
+                    },
+                    DashboardTab::Library => rsx! { LibraryHealthTab {} },
                     DashboardTab::Discovery => rsx! { DiscoveryOverview {} },
                 }
             }
@@ -40,7 +61,7 @@ fn OverviewTab() -> Element {
     let stats_resource = use_resource(|| async { api::get_library_stats().await });
     let binding = stats_resource.read();
 
-    match &*binding {
+    let stats = match &*binding {
         Some(Ok(s)) => {
             let s = s.clone();
             rsx! { StatsOverview { stats: s } }
@@ -55,5 +76,14 @@ fn OverviewTab() -> Element {
                 "Loading stats..."
             }
         },
+    };
+
+    rsx! {
+        div { class: "flex items-center justify-end gap-3 mb-4",
+            span { class: "text-xs font-mono text-gray-500", "Library index" }
+            ExportButtons { kind: ExportKind::Library }
+        }
+        {stats}
+        div { class: "mt-8", BandwidthOverview {} }
     }
 }
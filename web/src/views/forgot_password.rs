@@ -0,0 +1,32 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use api::request_password_reset;
+use dioxus::prelude::*;
+use ui::ForgotPassword;
+
+use crate::Route;
+
+#[component]
+pub fn ForgotPasswordPage() -> Element {
+    let navigator = use_navigator();
+
+    let request = use_callback(
+        move |username: String| -> Pin<Box<dyn Future<Output = Result<(), String>>>> {
+            Box::pin(async move {
+                request_password_reset(username)
+                    .await
+                    .map_err(|e| ui::friendly_error(&e))
+            })
+        },
+    );
+
+    rsx! {
+        ForgotPassword {
+            request,
+            on_back: move |_| {
+                navigator.push(Route::LoginPage {});
+            },
+        }
+    }
+}
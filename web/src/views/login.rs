@@ -1,8 +1,9 @@
 use std::future::Future;
 use std::pin::Pin;
 
-use api::login;
+use api::{login, password_reset_by_email};
 use dioxus::prelude::*;
+use shared::error::ApiErrorKind;
 use ui::Login;
 
 use crate::auth::use_auth;
@@ -12,6 +13,7 @@ use crate::Route;
 pub fn LoginPage() -> Element {
     let navigator = use_navigator();
     let mut auth = use_auth();
+    let by_email = use_resource(|| async { password_reset_by_email().await.unwrap_or(false) });
 
     let login = use_callback(move |(username, password): (String, String)|
         -> Pin<Box<dyn Future<Output = Result<(), String>>>>
@@ -23,12 +25,27 @@ pub fn LoginPage() -> Element {
                     navigator.push(Route::SearchPage {});
                     Ok(())
                 }
-                _ => Err("Invalid username or password".to_string()),
+                // A locked account says so; anything else stays vague
+                Err(e) => match ui::api_error(&e) {
+                    err if err.kind == ApiErrorKind::Validation => Err(err.message),
+                    _ => Err("Invalid username or password".to_string()),
+                },
             }
         })
     });
 
+    // Only offered when the server can actually email a link
+    let on_forgot = (by_email() == Some(true)).then(|| {
+        EventHandler::new(move |_| {
+            navigator.push(Route::ForgotPasswordPage {});
+        })
+    });
+
+    let notice = auth
+        .session_expired()
+        .then(|| "Your session expired. Sign in again to pick up where you left off.".to_string());
+
     rsx! {
-        Login { login }
+        Login { login, on_forgot, notice }
     }
 }
@@ -1,9 +1,25 @@
 mod dashboard;
+mod forgot_password;
+mod import;
 mod login;
+mod party;
+mod register;
+mod releases;
+mod reset_password;
 mod search;
+mod session;
 mod settings;
+mod wishlist;
 
 pub use dashboard::DashboardPage;
+pub use forgot_password::ForgotPasswordPage;
+pub use import::ImportPage;
 pub use login::LoginPage;
+pub use party::PartyPage;
+pub use register::RegisterPage;
+pub use releases::ReleasesPage;
+pub use reset_password::ResetPasswordPage;
 pub use search::SearchPage;
+pub use session::SessionPage;
 pub use settings::SettingsPage;
+pub use wishlist::WishlistPage;
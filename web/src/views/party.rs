@@ -0,0 +1,10 @@
+use dioxus::prelude::*;
+use ui::PartyGuest;
+
+/// A guest's page behind a party link. Works without an account.
+#[component]
+pub fn PartyPage(token: String) -> Element {
+    rsx! {
+        PartyGuest { token }
+    }
+}
@@ -0,0 +1,47 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use api::{check_invite, login, redeem_invite};
+use dioxus::prelude::*;
+use ui::Register;
+
+use crate::auth::use_auth;
+use crate::Route;
+
+/// Signup through an invite link, signing the new user in afterwards.
+#[component]
+pub fn RegisterPage(token: String) -> Element {
+    let navigator = use_navigator();
+    let mut auth = use_auth();
+
+    let check_token = token.clone();
+    let valid = use_resource(move || {
+        let token = check_token.clone();
+        async move { check_invite(token).await.unwrap_or(false) }
+    });
+
+    let register = use_callback(move |(username, password): (String, String)|
+        -> Pin<Box<dyn Future<Output = Result<(), String>>>>
+    {
+        let token = token.clone();
+        Box::pin(async move {
+            redeem_invite(token, username.clone(), password.clone())
+                .await
+                .map_err(|e| ui::friendly_error(&e))?;
+            match login(username, password).await {
+                Ok(response) => {
+                    auth.login(response);
+                    navigator.push(Route::SearchPage {});
+                }
+                Err(_) => {
+                    navigator.push(Route::LoginPage {});
+                }
+            }
+            Ok(())
+        })
+    });
+
+    rsx! {
+        Register { valid: valid(), register }
+    }
+}
@@ -0,0 +1,37 @@
+use std::future::Future;
+use std::pin::Pin;
+
+use api::{check_password_reset, reset_password};
+use dioxus::prelude::*;
+use ui::ResetPassword;
+
+use crate::Route;
+
+/// Choosing a new password through a reset link, then signing in with it.
+#[component]
+pub fn ResetPasswordPage(token: String) -> Element {
+    let navigator = use_navigator();
+
+    let check_token = token.clone();
+    let valid = use_resource(move || {
+        let token = check_token.clone();
+        async move { check_password_reset(token).await.unwrap_or(false) }
+    });
+
+    let reset = use_callback(
+        move |password: String| -> Pin<Box<dyn Future<Output = Result<(), String>>>> {
+            let token = token.clone();
+            Box::pin(async move {
+                reset_password(token, password)
+                    .await
+                    .map_err(|e| ui::friendly_error(&e))?;
+                navigator.push(Route::LoginPage {});
+                Ok(())
+            })
+        },
+    );
+
+    rsx! {
+        ResetPassword { valid: valid(), reset }
+    }
+}
@@ -1,9 +1,49 @@
 use dioxus::prelude::*;
-use ui::Search;
+use ui::{
+    use_settings, CompleteAlbumSignal, HomeDashboard, RerunSearchSignal, Search, SearchPrefill,
+    SearchReset,
+};
 
 #[component]
 pub fn SearchPage() -> Element {
-    rsx! {
-        Search {}
+    let settings = use_settings();
+    let prefill = try_use_context::<SearchPrefill>();
+    let complete_album = try_use_context::<CompleteAlbumSignal>();
+    let rerun_search = try_use_context::<RerunSearchSignal>();
+    let reset = try_use_context::<SearchReset>();
+
+    // Pages sending a search here skip the dashboard
+    let mut searching = use_signal(|| {
+        prefill.is_some_and(|p| p.0.peek().is_some())
+            || complete_album.is_some_and(|c| c.0.peek().is_some())
+            || rerun_search.is_some_and(|r| r.0.peek().is_some())
+    });
+    let mut last_reset = use_signal(|| reset.map(|r| *r.0.peek()));
+
+    // The Search link in the navbar goes back to the dashboard
+    use_effect(move || {
+        let current = reset.map(|r| (r.0)());
+        if current != *last_reset.peek() {
+            last_reset.set(current);
+            searching.set(false);
+        }
+    });
+
+    if settings.home_dashboard() && !searching() {
+        rsx! {
+            HomeDashboard {
+                on_search: move |query: (String, String)| {
+                    if let Some(mut prefill) = prefill {
+                        (prefill.0).set(Some(query));
+                    }
+                    searching.set(true);
+                },
+                on_classic: move |_| searching.set(true),
+            }
+        }
+    } else {
+        rsx! {
+            Search {}
+        }
     }
 }
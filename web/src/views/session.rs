@@ -0,0 +1,21 @@
+use dioxus::prelude::*;
+use ui::SharedSearch;
+
+use crate::Route;
+
+#[component]
+pub fn SessionPage(id: String) -> Element {
+    let nav = use_navigator();
+
+    rsx! {
+        div { class: "w-full max-w-5xl z-10 mx-auto",
+            SharedSearch {
+                key: "{id}",
+                session_id: id.clone(),
+                on_leave: move |_| {
+                    nav.push(Route::SearchPage {});
+                },
+            }
+        }
+    }
+}
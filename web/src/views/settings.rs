@@ -1,5 +1,12 @@
 use dioxus::prelude::*;
-use ui::settings::{AppConfigManager, FolderManager, PreferencesManager, UserManager};
+use ui::settings::{
+    ApiTokenManager, AppConfigManager, CrashReportsPanel, DiscogsManager, DownloadPauseManager,
+    FeatureFlagsManager, FolderManager, ImportHooksManager, LoggingManager, MatchingReportPanel,
+    MusicBrainzManager, NotificationSettings, PreferencesManager, RenameRulesManager,
+    RoutingRulesManager, SecurityPolicyManager, TransferCleanupManager, UserManager,
+};
+
+use crate::auth::use_auth;
 
 #[derive(PartialEq, Clone, Copy, Default)]
 enum SettingsTab {
@@ -13,6 +20,7 @@ enum SettingsTab {
 #[component]
 pub fn SettingsPage() -> Element {
     let mut active_tab = use_signal(SettingsTab::default);
+    let auth = use_auth();
 
     rsx! {
         div { class: "fixed top-1/4 -left-10 w-64 h-64 bg-beet-accent/10 rounded-full blur-[100px] pointer-events-none" }
@@ -25,6 +33,12 @@ pub fn SettingsPage() -> Element {
                 }
             }
 
+            if auth.is_viewer() {
+                p { class: "text-center text-sm font-mono text-gray-400",
+                    "You're signed in as a viewer: settings are read-only."
+                }
+            }
+
             // Tab navigation - pill style matching navbar
             nav { class: "flex items-center justify-center gap-1 bg-beet-panel/50 p-1.5 rounded-full border border-white/5 backdrop-blur-sm w-fit mx-auto",
                 TabButton {
@@ -57,9 +71,29 @@ pub fn SettingsPage() -> Element {
             div { class: "pt-8",
                 match active_tab() {
                     SettingsTab::Search => rsx! { PreferencesManager {} },
-                    SettingsTab::Library => rsx! { FolderManager {} },
-                    SettingsTab::Users => rsx! { UserManager {} },
-                    SettingsTab::Config => rsx! { AppConfigManager {} },
+                    SettingsTab::Library => rsx! {
+                        FolderManager {}
+                        RenameRulesManager {}
+                        RoutingRulesManager {}
+                        ImportHooksManager {}
+                        DiscogsManager {}
+                    },
+                    SettingsTab::Users => rsx! {
+                        UserManager {}
+                        SecurityPolicyManager {}
+                        ApiTokenManager {}
+                        NotificationSettings {}
+                    },
+                    SettingsTab::Config => rsx! {
+                        AppConfigManager {}
+                        MusicBrainzManager {}
+                        DownloadPauseManager {}
+                        TransferCleanupManager {}
+                        FeatureFlagsManager {}
+                        MatchingReportPanel {}
+                        LoggingManager {}
+                        CrashReportsPanel {}
+                    },
                 }
             }
         }
@@ -0,0 +1,55 @@
+use dioxus::prelude::*;
+use ui::{
+    ChartImport, CompleteAlbumSignal, CompleteAlbums, LabelCatalog, MissingMusicList, PartyHost,
+    SearchPrefill, Wishlist, WishlistTransfer,
+};
+
+use crate::Route;
+
+#[component]
+pub fn WishlistPage() -> Element {
+    let nav = use_navigator();
+    let search_prefill = use_context::<SearchPrefill>();
+    let complete_album = use_context::<CompleteAlbumSignal>();
+    let mut refresh = use_signal(|| 0u32);
+
+    rsx! {
+        div { class: "fixed top-1/4 -left-10 w-64 h-64 bg-beet-accent/10 rounded-full blur-[100px] pointer-events-none" }
+        div { class: "fixed bottom-1/4 -right-10 w-64 h-64 bg-beet-leaf/10 rounded-full blur-[100px] pointer-events-none" }
+
+        div { class: "space-y-6 text-white w-full max-w-3xl z-10 mx-auto",
+            div { class: "text-center mb-6",
+                h1 { class: "text-4xl font-bold text-beet-accent mb-2 font-display",
+                    "Wishlist"
+                }
+            }
+            ChartImport { on_added: move |_| refresh += 1 }
+            LabelCatalog { on_added: move |_| refresh += 1 }
+            WishlistTransfer { on_added: move |_| refresh += 1 }
+            PartyHost {}
+            Wishlist {
+                refresh,
+                on_search: move |query| {
+                    let mut prefill = search_prefill.0;
+                    prefill.set(Some(query));
+                    nav.push(Route::SearchPage {});
+                },
+            }
+            MissingMusicList {
+                on_search: move |query| {
+                    let mut prefill = search_prefill.0;
+                    prefill.set(Some(query));
+                    nav.push(Route::SearchPage {});
+                },
+                on_added: move |_| refresh += 1,
+            }
+            CompleteAlbums {
+                on_complete: move |completion| {
+                    let mut signal = complete_album.0;
+                    signal.set(Some(completion));
+                    nav.push(Route::SearchPage {});
+                },
+            }
+        }
+    }
+}
@@ -1,109 +1,194 @@
-//! Resilient WebSocket client with automatic reconnection.
+//! Resilient WebSocket client with automatic reconnection, for sockets
+//! sending CBOR.
 //!
-//! This module is only compiled when the `web` feature is enabled (browser environment).
+//! The hook is only compiled when the `web` feature is enabled (browser
+//! environment). Lost connections are retried with exponential backoff and
+//! jitter, so clients don't all hammer a restarting server at once, and
+//! retrying stops once the session is gone.
 
-use dioxus::logger::tracing::{info, warn};
+#[cfg(feature = "web")]
+use dioxus::fullstack::{CborEncoding, Websocket};
+#[cfg(feature = "web")]
+use dioxus::logger::tracing::{debug, info, warn};
+#[cfg(feature = "web")]
 use dioxus::prelude::*;
+#[cfg(feature = "web")]
 use gloo_timers::future::TimeoutFuture;
+#[cfg(feature = "web")]
 use serde::de::DeserializeOwned;
+#[cfg(feature = "web")]
 use std::cell::RefCell;
+#[cfg(feature = "web")]
 use std::future::Future;
+#[cfg(feature = "web")]
 use std::rc::Rc;
 
+#[cfg(feature = "web")]
+use crate::auth::use_auth;
+
+/// Where the connection stands, for the UI.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+#[cfg_attr(not(feature = "web"), allow(dead_code))]
+pub enum SocketStatus {
+    /// Not connected yet, or signed out
+    #[default]
+    Idle,
+    Connected,
+    /// Lost, trying again in `retry_in_secs`
+    Reconnecting {
+        attempt: u32,
+        retry_in_secs: u32,
+    },
+}
+
 /// Configuration for WebSocket reconnection behavior.
+#[cfg(feature = "web")]
 #[derive(Clone)]
 pub struct ReconnectConfig {
     /// Initial delay before first reconnection attempt (in milliseconds).
     pub base_delay_ms: u32,
     /// Maximum delay between reconnection attempts (in milliseconds).
     pub max_delay_ms: u32,
+    /// Share of each delay that is randomly taken off, from 0.0 to 1.0.
+    pub jitter: f64,
 }
 
+#[cfg(feature = "web")]
 impl Default for ReconnectConfig {
     fn default() -> Self {
         Self {
             base_delay_ms: 1_000,
-            max_delay_ms: 30_000,
+            max_delay_ms: 60_000,
+            jitter: 0.3,
         }
     }
 }
 
+#[cfg(feature = "web")]
 impl ReconnectConfig {
-    fn calculate_delay(&self, retry_count: u32) -> u32 {
-        let base_delay = self
+    /// Delay before attempt `failures + 1`: doubling from the base delay up
+    /// to the maximum, minus a random share.
+    fn calculate_delay(&self, failures: u32) -> u32 {
+        let exponent = failures.saturating_sub(1).min(16);
+        let delay = self
             .base_delay_ms
-            .saturating_mul(2u32.saturating_pow(retry_count.min(5)));
-        let delay = base_delay.min(self.max_delay_ms);
-        let jitter = (delay / 4).saturating_mul(retry_count % 4) / 4;
-        delay.saturating_add(jitter)
+            .saturating_mul(1 << exponent)
+            .min(self.max_delay_ms);
+        let jitter = delay as f64 * self.jitter.clamp(0.0, 1.0) * js_sys::Math::random();
+        delay.saturating_sub(jitter as u32)
     }
 }
 
-pub fn use_resilient_websocket<T, F, Fut, C>(connect: C, on_message: F)
+/// Keep a WebSocket open while the user is signed in. `on_reconnect` runs
+/// each time the connection comes back after being lost, to catch up on
+/// what was missed.
+#[cfg(feature = "web")]
+pub fn use_resilient_websocket<T, F, Fut, C, R>(
+    connect: C,
+    on_message: F,
+    on_reconnect: R,
+) -> Signal<SocketStatus>
 where
     T: DeserializeOwned + 'static,
     F: FnMut(T) + 'static,
     C: Fn() -> Fut + 'static,
-    Fut: Future<Output = Result<dioxus::fullstack::Websocket<(), T>, ServerFnError>> + 'static,
+    Fut: Future<Output = Result<Websocket<(), T, CborEncoding>, ServerFnError>> + 'static,
+    R: FnMut() + 'static,
 {
-    use_resilient_websocket_with_config(connect, on_message, ReconnectConfig::default())
+    use_resilient_websocket_with_config(
+        connect,
+        on_message,
+        on_reconnect,
+        ReconnectConfig::default(),
+    )
 }
 
-pub fn use_resilient_websocket_with_config<T, F, Fut, C>(
+#[cfg(feature = "web")]
+pub fn use_resilient_websocket_with_config<T, F, Fut, C, R>(
     connect: C,
     on_message: F,
+    on_reconnect: R,
     config: ReconnectConfig,
-) where
+) -> Signal<SocketStatus>
+where
     T: DeserializeOwned + 'static,
     F: FnMut(T) + 'static,
     C: Fn() -> Fut + 'static,
-    Fut: Future<Output = Result<dioxus::fullstack::Websocket<(), T>, ServerFnError>> + 'static,
+    Fut: Future<Output = Result<Websocket<(), T, CborEncoding>, ServerFnError>> + 'static,
+    R: FnMut() + 'static,
 {
+    let auth = use_auth();
+    let mut status = use_signal(SocketStatus::default);
+
     // Store callbacks in Rc<RefCell> to allow sharing across async boundaries
     let connect = use_hook(|| Rc::new(connect));
     let on_message = use_hook(|| Rc::new(RefCell::new(on_message)));
+    let on_reconnect = use_hook(|| Rc::new(RefCell::new(on_reconnect)));
 
     use_future(move || {
         let connect = Rc::clone(&connect);
         let on_message = Rc::clone(&on_message);
+        let on_reconnect = Rc::clone(&on_reconnect);
         let config = config.clone();
 
         async move {
-            let mut retry_count: u32 = 0;
+            let mut failures: u32 = 0;
+            let mut connected_before = false;
 
             loop {
-                info!(
-                    "Connecting to WebSocket (attempt {})",
-                    retry_count.saturating_add(1)
-                );
+                if !auth.is_logged_in() {
+                    info!("Signed out, closing the downloads WebSocket");
+                    status.set(SocketStatus::Idle);
+                    return;
+                }
 
                 match connect().await {
                     Ok(socket) => {
-                        retry_count = 0;
+                        if connected_before {
+                            info!("WebSocket reconnected after {} attempt(s)", failures);
+                            on_reconnect.borrow_mut()();
+                        } else {
+                            info!("WebSocket connected");
+                        }
+                        connected_before = true;
+                        failures = 0;
+                        status.set(SocketStatus::Connected);
                         receive_messages(socket, &on_message).await;
+                        warn!("WebSocket disconnected");
                     }
                     Err(e) => {
-                        warn!("Failed to establish WebSocket connection: {:?}", e);
+                        debug!("WebSocket connection attempt failed: {:?}", e);
+                        // The upgrade doesn't tell an expired session from
+                        // a server that is down; asking does. A 401 signs
+                        // the user out, which ends the loop.
+                        let _ = auth.call(api::get_current_user).await;
+                        if !auth.is_logged_in() {
+                            continue;
+                        }
                     }
                 }
 
-                retry_count = retry_count.saturating_add(1);
-                let delay = config.calculate_delay(retry_count);
-
-                warn!(
-                    "WebSocket disconnected, reconnecting in {}ms (attempt {})",
-                    delay, retry_count
+                failures = failures.saturating_add(1);
+                let delay = config.calculate_delay(failures);
+                debug!(
+                    "Reconnecting the WebSocket in {}ms (attempt {})",
+                    delay, failures
                 );
+                status.set(SocketStatus::Reconnecting {
+                    attempt: failures,
+                    retry_in_secs: delay.div_ceil(1000),
+                });
                 TimeoutFuture::new(delay).await;
             }
         }
     });
+
+    status
 }
 
-async fn receive_messages<T, F>(
-    socket: dioxus::fullstack::Websocket<(), T>,
-    on_message: &Rc<RefCell<F>>,
-) where
+#[cfg(feature = "web")]
+async fn receive_messages<T, F>(socket: Websocket<(), T, CborEncoding>, on_message: &Rc<RefCell<F>>)
+where
     T: DeserializeOwned,
     F: FnMut(T),
 {